@@ -1 +1,87 @@
- 
+use std::net::SocketAddr;
+use std::path::PathBuf;
+use std::time::Duration;
+
+mod models;
+mod routes;
+mod state;
+mod utils;
+
+#[tokio::main]
+async fn main() {
+    env_logger::init();
+
+    let data_dir = std::env::var("SLYNQIX_DATA_DIR").unwrap_or_else(|_| "data".to_string());
+    let state = state::AppState::load(PathBuf::from(data_dir));
+
+    let scan_minutes: u64 = std::env::var("SLYNQIX_DQ_SCAN_MINUTES")
+        .ok()
+        .and_then(|v| v.parse().ok())
+        .unwrap_or(60);
+    let scan_state = state.clone();
+    tokio::spawn(async move {
+        let mut interval = tokio::time::interval(Duration::from_secs(scan_minutes * 60));
+        loop {
+            interval.tick().await;
+            // The scan walks every symbol's history; keep it off the
+            // runtime's worker threads.
+            let state = scan_state.clone();
+            match tokio::task::spawn_blocking(move || routes::data_quality::run_scan(&state)).await
+            {
+                Ok(Ok(summary)) => log::info!(
+                    "Data quality scan: {} symbols, {} open issues ({} new)",
+                    summary.symbols,
+                    summary.open_issues,
+                    summary.new_issues
+                ),
+                Ok(Err(e)) => log::error!("Failed to save the data quality scan: {}", e),
+                Err(e) => log::error!("Data quality scan failed: {}", e),
+            }
+        }
+    });
+
+    // Daily screens run once the day's bars are in, after the close
+    let screen_time = std::env::var("SLYNQIX_SCREEN_TIME")
+        .ok()
+        .and_then(|v| chrono::NaiveTime::parse_from_str(&v, "%H:%M").ok())
+        .unwrap_or_else(|| chrono::NaiveTime::from_hms_opt(16, 0, 0).unwrap());
+    let screen_state = state.clone();
+    tokio::spawn(async move {
+        let mut interval = tokio::time::interval(Duration::from_secs(5 * 60));
+        let mut backoff = models::screener::RetryBackoff::default();
+        loop {
+            interval.tick().await;
+            let now = models::calendar::now_ist();
+            // Screens read every symbol's history from the stores; run
+            // them off the runtime's worker threads.
+            let state = screen_state.clone();
+            let run = tokio::task::spawn_blocking(move || {
+                let ran = routes::screener::run_due(&state, now, screen_time, &mut backoff);
+                (ran, backoff)
+            });
+            match run.await {
+                Ok((ran, kept)) => {
+                    backoff = kept;
+                    if ran > 0 {
+                        log::info!("Ran {} scheduled screens", ran);
+                    }
+                }
+                Err(e) => {
+                    log::error!("Scheduled screens failed: {}", e);
+                    backoff = Default::default();
+                }
+            }
+        }
+    });
+
+    let app = routes::router(state);
+
+    let addr: SocketAddr = std::env::var("SLYNQIX_ADDR")
+        .unwrap_or_else(|_| "127.0.0.1:8081".to_string())
+        .parse()
+        .expect("SLYNQIX_ADDR must be a socket address");
+
+    log::info!("Slynqix backend listening on {}", addr);
+    let listener = tokio::net::TcpListener::bind(addr).await.unwrap();
+    axum::serve(listener, app).await.unwrap();
+}
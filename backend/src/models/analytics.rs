@@ -0,0 +1,482 @@
+use std::collections::{BTreeMap, HashMap, HashSet};
+
+use chrono::{Datelike, Duration, Months, NaiveDate, NaiveDateTime, Timelike};
+use serde::Serialize;
+
+use crate::models::calendar::TradingCalendar;
+use crate::models::journal::{Direction, Tag, TagKind, Trade, TradeStatus, TradeSummary};
+
+/// A closed trade reduced to what the statistics need.
+struct Outcome<'a> {
+    trade: &'a Trade,
+    direction: Option<Direction>,
+    entry: NaiveDateTime,
+    exit: NaiveDateTime,
+    pnl: f64,
+}
+
+#[derive(Clone, Debug, Default, Serialize)]
+pub struct PerformanceStats {
+    pub trades: usize,
+    pub wins: usize,
+    pub losses: usize,
+    pub win_rate: f64,
+    pub net_pnl: f64,
+    pub gross_profit: f64,
+    pub gross_loss: f64,
+    pub avg_win: f64,
+    pub avg_loss: f64,
+    /// Average PnL per trade.
+    pub expectancy: f64,
+    /// Gross profit over gross loss; `None` when there are no losses.
+    pub profit_factor: Option<f64>,
+    pub max_drawdown: f64,
+    pub longest_win_streak: usize,
+    pub longest_loss_streak: usize,
+    /// Positive for a run of wins, negative for a run of losses.
+    pub current_streak: i64,
+}
+
+#[derive(Clone, Debug, Serialize)]
+pub struct EquityPoint {
+    pub time: NaiveDateTime,
+    pub equity: f64,
+    pub drawdown: f64,
+}
+
+#[derive(Clone, Debug, Serialize)]
+pub struct Breakdown {
+    pub key: String,
+    pub trades: usize,
+    pub win_rate: f64,
+    pub net_pnl: f64,
+    pub avg_pnl: f64,
+    /// Share of the overall net PnL, in percent. `None` when the overall
+    /// net PnL is zero.
+    pub contribution: Option<f64>,
+}
+
+#[derive(Clone, Debug, Serialize)]
+pub struct JournalAnalytics {
+    pub overall: PerformanceStats,
+    pub equity_curve: Vec<EquityPoint>,
+    pub by_symbol: Vec<Breakdown>,
+    pub by_weekday: Vec<Breakdown>,
+    pub by_hour: Vec<Breakdown>,
+    pub by_holding_time: Vec<Breakdown>,
+    pub by_setup: Vec<Breakdown>,
+    pub by_mistake: Vec<Breakdown>,
+    pub by_emotion: Vec<Breakdown>,
+    pub by_direction: Vec<Breakdown>,
+    /// Trades still open, left out of every statistic.
+    pub open_trades: usize,
+}
+
+/// Holding time buckets, upper bound in minutes.
+const HOLDING_BUCKETS: [(i64, &str); 6] = [
+    (15, "Under 15m"),
+    (60, "15m - 1h"),
+    (60 * 24, "1h - 1 day"),
+    (60 * 24 * 5, "1 - 5 days"),
+    (60 * 24 * 30, "5 - 30 days"),
+    (i64::MAX, "Over 30 days"),
+];
+
+fn holding_bucket(minutes: i64) -> (usize, &'static str) {
+    let index = HOLDING_BUCKETS
+        .iter()
+        .position(|(limit, _)| minutes < *limit)
+        .unwrap_or(HOLDING_BUCKETS.len() - 1);
+    (index, HOLDING_BUCKETS[index].1)
+}
+
+/// Performance over closed trades. Trades are ordered by exit time, so the
+/// equity curve and streaks follow the order PnL was realized. Tags missing
+/// from `tags` are treated as setups.
+pub fn analyze(trades: &[(Trade, TradeSummary)], tags: &[Tag]) -> JournalAnalytics {
+    let mut outcomes: Vec<Outcome> = trades
+        .iter()
+        .filter(|(_, s)| s.status == TradeStatus::Closed)
+        .filter_map(|(trade, summary)| {
+            Some(Outcome {
+                trade,
+                direction: summary.direction,
+                entry: summary.entry_time?,
+                exit: summary.exit_time?,
+                pnl: summary.realized_pnl,
+            })
+        })
+        .collect();
+    outcomes.sort_by_key(|o| o.exit);
+
+    let mut equity = 0.0;
+    let mut peak = 0.0;
+    let equity_curve = outcomes
+        .iter()
+        .map(|o| {
+            equity += o.pnl;
+            peak = f64::max(peak, equity);
+            EquityPoint {
+                time: o.exit,
+                equity,
+                drawdown: peak - equity,
+            }
+        })
+        .collect();
+
+    let all: Vec<&Outcome> = outcomes.iter().collect();
+    let overall = stats(&all);
+    let kinds: HashMap<&str, TagKind> = tags.iter().map(|t| (t.name.as_str(), t.kind)).collect();
+    let tagged = |kind: TagKind| {
+        let kinds = &kinds;
+        move |o: &Outcome| {
+            o.trade
+                .tags
+                .iter()
+                .filter(|t| kinds.get(t.as_str()).copied().unwrap_or(TagKind::Setup) == kind)
+                .map(|t| (0, t.clone()))
+                .collect()
+        }
+    };
+    let group = |keys: &dyn Fn(&Outcome) -> Vec<(usize, String)>, by_pnl: bool| {
+        breakdown(&outcomes, keys, by_pnl, overall.net_pnl)
+    };
+
+    JournalAnalytics {
+        equity_curve,
+        by_symbol: group(&|o| vec![(0, o.trade.symbol.clone())], true),
+        by_weekday: group(
+            &|o| {
+                let day = o.entry.weekday();
+                vec![(day.num_days_from_monday() as usize, day.to_string())]
+            },
+            false,
+        ),
+        by_hour: group(
+            &|o| {
+                let hour = o.entry.hour() as usize;
+                vec![(hour, format!("{:02}:00", hour))]
+            },
+            false,
+        ),
+        by_holding_time: group(
+            &|o| {
+                let (order, label) = holding_bucket((o.exit - o.entry).num_minutes());
+                vec![(order, label.to_string())]
+            },
+            false,
+        ),
+        by_setup: group(&tagged(TagKind::Setup), true),
+        by_mistake: group(&tagged(TagKind::Mistake), true),
+        by_emotion: group(&tagged(TagKind::Emotion), true),
+        by_direction: group(
+            &|o| match o.direction {
+                Some(Direction::Long) => vec![(0, "Long".to_string())],
+                Some(Direction::Short) => vec![(1, "Short".to_string())],
+                None => Vec::new(),
+            },
+            false,
+        ),
+        open_trades: trades.len() - outcomes.len(),
+        overall,
+    }
+}
+
+fn stats(outcomes: &[&Outcome]) -> PerformanceStats {
+    let mut stats = PerformanceStats {
+        trades: outcomes.len(),
+        ..Default::default()
+    };
+    if outcomes.is_empty() {
+        return stats;
+    }
+
+    let (mut equity, mut peak) = (0.0, 0.0);
+    let (mut wins_run, mut losses_run) = (0, 0);
+    for outcome in outcomes {
+        // Scratch trades count as neither and break both streaks
+        if outcome.pnl > 0.0 {
+            stats.wins += 1;
+            stats.gross_profit += outcome.pnl;
+            wins_run += 1;
+            losses_run = 0;
+        } else if outcome.pnl < 0.0 {
+            stats.losses += 1;
+            stats.gross_loss += -outcome.pnl;
+            losses_run += 1;
+            wins_run = 0;
+        } else {
+            wins_run = 0;
+            losses_run = 0;
+        }
+        stats.longest_win_streak = stats.longest_win_streak.max(wins_run);
+        stats.longest_loss_streak = stats.longest_loss_streak.max(losses_run);
+
+        equity += outcome.pnl;
+        peak = f64::max(peak, equity);
+        stats.max_drawdown = stats.max_drawdown.max(peak - equity);
+    }
+
+    let count = outcomes.len() as f64;
+    stats.net_pnl = stats.gross_profit - stats.gross_loss;
+    stats.win_rate = stats.wins as f64 / count * 100.0;
+    stats.avg_win = if stats.wins > 0 {
+        stats.gross_profit / stats.wins as f64
+    } else {
+        0.0
+    };
+    stats.avg_loss = if stats.losses > 0 {
+        -stats.gross_loss / stats.losses as f64
+    } else {
+        0.0
+    };
+    stats.expectancy = stats.net_pnl / count;
+    stats.profit_factor = (stats.gross_loss > 0.0).then(|| stats.gross_profit / stats.gross_loss);
+    stats.current_streak = if wins_run > 0 {
+        wins_run as i64
+    } else {
+        -(losses_run as i64)
+    };
+    stats
+}
+
+/// Group outcomes by one or more keys each. Keys carry a sort order;
+/// `by_pnl` sorts groups by net PnL instead, best first.
+fn breakdown(
+    outcomes: &[Outcome],
+    keys: &dyn Fn(&Outcome) -> Vec<(usize, String)>,
+    by_pnl: bool,
+    total_pnl: f64,
+) -> Vec<Breakdown> {
+    let mut groups: BTreeMap<(usize, String), Vec<&Outcome>> = BTreeMap::new();
+    for outcome in outcomes {
+        for key in keys(outcome) {
+            groups.entry(key).or_default().push(outcome);
+        }
+    }
+
+    let mut rows: Vec<Breakdown> = groups
+        .into_iter()
+        .map(|((_, key), group)| {
+            let stats = stats(&group);
+            Breakdown {
+                key,
+                trades: stats.trades,
+                win_rate: stats.win_rate,
+                net_pnl: stats.net_pnl,
+                avg_pnl: stats.expectancy,
+                contribution: (total_pnl != 0.0).then(|| stats.net_pnl / total_pnl.abs() * 100.0),
+            }
+        })
+        .collect();
+    if by_pnl {
+        rows.sort_by(|a, b| b.net_pnl.total_cmp(&a.net_pnl));
+    }
+    rows
+}
+
+#[derive(Clone, Debug, Serialize)]
+pub struct PnlDay {
+    pub date: NaiveDate,
+    pub net_pnl: f64,
+    /// Trades with a fill on this day.
+    pub trades: usize,
+    pub is_trading_day: bool,
+    pub holiday: Option<String>,
+}
+
+#[derive(Clone, Debug, Serialize)]
+pub struct PnlWeek {
+    /// Monday of the week; the first week may start in the previous month.
+    pub start: NaiveDate,
+    pub net_pnl: f64,
+    pub trades: usize,
+}
+
+#[derive(Clone, Debug, Serialize)]
+pub struct PnlCalendar {
+    pub month: NaiveDate,
+    pub days: Vec<PnlDay>,
+    pub weeks: Vec<PnlWeek>,
+    pub net_pnl: f64,
+    pub trades: usize,
+    pub winning_days: usize,
+    pub losing_days: usize,
+}
+
+/// Daily realized PnL for the month starting at `month`. Weekly totals
+/// only count days inside the month.
+pub fn pnl_calendar(trades: &[Trade], month: NaiveDate, calendar: &TradingCalendar) -> PnlCalendar {
+    let end = month + Months::new(1);
+    let mut pnl: BTreeMap<NaiveDate, f64> = BTreeMap::new();
+    let mut active: BTreeMap<NaiveDate, HashSet<&str>> = BTreeMap::new();
+    for trade in trades {
+        for (day, value) in trade.daily_pnl() {
+            if day >= month && day < end {
+                *pnl.entry(day).or_default() += value;
+                active.entry(day).or_default().insert(&trade.id);
+            }
+        }
+    }
+
+    let days: Vec<PnlDay> = month
+        .iter_days()
+        .take_while(|day| *day < end)
+        .map(|date| {
+            let info = calendar.trading_day(date);
+            PnlDay {
+                date,
+                net_pnl: pnl.get(&date).copied().unwrap_or(0.0),
+                trades: active.get(&date).map_or(0, HashSet::len),
+                is_trading_day: info.is_trading_day,
+                holiday: info.holiday,
+            }
+        })
+        .collect();
+
+    let mut weeks: Vec<PnlWeek> = Vec::new();
+    let mut week_trades: Vec<HashSet<&str>> = Vec::new();
+    for day in &days {
+        let start = day.date - Duration::days(day.date.weekday().num_days_from_monday() as i64);
+        if weeks.last().is_none_or(|w| w.start != start) {
+            weeks.push(PnlWeek {
+                start,
+                net_pnl: 0.0,
+                trades: 0,
+            });
+            week_trades.push(HashSet::new());
+        }
+        if let (Some(week), Some(ids)) = (weeks.last_mut(), week_trades.last_mut()) {
+            week.net_pnl += day.net_pnl;
+            ids.extend(active.get(&day.date).into_iter().flatten());
+            week.trades = ids.len();
+        }
+    }
+
+    let month_trades: HashSet<&str> = active.values().flatten().copied().collect();
+    PnlCalendar {
+        month,
+        net_pnl: days.iter().map(|d| d.net_pnl).sum(),
+        trades: month_trades.len(),
+        winning_days: days.iter().filter(|d| d.net_pnl > 0.0).count(),
+        losing_days: days.iter().filter(|d| d.net_pnl < 0.0).count(),
+        days,
+        weeks,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::models::journal::{Execution, Side};
+
+    /// A round trip on `day` of March 2025, bought at 100 and sold at `exit`.
+    fn round_trip(symbol: &str, day: u32, exit: f64) -> (Trade, TradeSummary) {
+        let fill = |side, price, hour| Execution {
+            id: String::new(),
+            side,
+            quantity: 10,
+            price,
+            time: NaiveDate::from_ymd_opt(2025, 3, day)
+                .unwrap()
+                .and_hms_opt(hour, 0, 0)
+                .unwrap(),
+            fees: 0.0,
+            trade_id: None,
+        };
+        let trade = Trade {
+            id: String::new(),
+            symbol: symbol.to_string(),
+            executions: vec![fill(Side::Buy, 100.0, 10), fill(Side::Sell, exit, 14)],
+            notes: String::new(),
+            tags: vec!["breakout".to_string()],
+        };
+        let summary = trade.summarize(None);
+        (trade, summary)
+    }
+
+    #[test]
+    fn stats_follow_the_order_pnl_was_realized() {
+        // Listed out of order: +100 on the 3rd, -50 on the 4th, +20 on the 5th
+        let trades = vec![
+            round_trip("TCS", 5, 102.0),
+            round_trip("INFY", 3, 110.0),
+            round_trip("INFY", 4, 95.0),
+        ];
+        let analytics = analyze(&trades, &[]);
+        let overall = &analytics.overall;
+
+        assert_eq!((overall.trades, overall.wins, overall.losses), (3, 2, 1));
+        assert!((overall.net_pnl - 70.0).abs() < 1e-9);
+        assert_eq!(overall.profit_factor, Some(120.0 / 50.0));
+        assert!((overall.max_drawdown - 50.0).abs() < 1e-9);
+        assert_eq!(overall.current_streak, 1);
+        let equity: Vec<f64> = analytics.equity_curve.iter().map(|p| p.equity).collect();
+        assert_eq!(equity, vec![100.0, 50.0, 70.0]);
+
+        assert_eq!(analytics.by_symbol[0].key, "INFY");
+        assert_eq!(analytics.by_symbol[0].trades, 2);
+        assert_eq!(analytics.by_setup[0].trades, 3);
+        assert_eq!(analytics.by_holding_time[0].key, "1h - 1 day");
+    }
+
+    #[test]
+    fn open_trades_are_counted_but_not_scored() {
+        let (mut trade, _) = round_trip("INFY", 3, 110.0);
+        trade.executions.pop();
+        let summary = trade.summarize(Some(105.0));
+        let analytics = analyze(&[(trade, summary)], &[]);
+
+        assert_eq!(analytics.open_trades, 1);
+        assert_eq!(analytics.overall.trades, 0);
+        assert!(analytics.equity_curve.is_empty());
+    }
+
+    #[test]
+    fn tags_are_broken_down_by_kind() {
+        let (mut trade, summary) = round_trip("INFY", 3, 95.0);
+        trade.tags.push("FOMO".to_string());
+        let tags = [Tag {
+            name: "FOMO".to_string(),
+            kind: TagKind::Mistake,
+            description: String::new(),
+        }];
+        let analytics = analyze(&[(trade, summary)], &tags);
+
+        assert_eq!(analytics.by_setup[0].key, "breakout");
+        assert_eq!(analytics.by_mistake[0].key, "FOMO");
+        assert!((analytics.by_mistake[0].net_pnl + 50.0).abs() < 1e-9);
+        assert!(analytics.by_emotion.is_empty());
+    }
+
+    #[test]
+    fn calendar_totals_days_and_weeks_in_the_month() {
+        let trades: Vec<Trade> = [(3, 110.0), (4, 95.0), (5, 102.0)]
+            .into_iter()
+            .map(|(day, exit)| round_trip("INFY", day, exit).0)
+            .enumerate()
+            .map(|(i, mut trade)| {
+                trade.id = format!("t{}", i);
+                trade
+            })
+            .collect();
+        let month = NaiveDate::from_ymd_opt(2025, 3, 1).unwrap();
+        let calendar = pnl_calendar(&trades, month, &TradingCalendar::with_defaults());
+
+        assert_eq!(calendar.days.len(), 31);
+        assert_eq!(
+            (calendar.trades, calendar.winning_days, calendar.losing_days),
+            (3, 2, 1)
+        );
+        assert!((calendar.net_pnl - 70.0).abs() < 1e-9);
+        // March 2025 starts on a Saturday, so its first week began in February
+        assert_eq!(
+            calendar.weeks[0].start,
+            NaiveDate::from_ymd_opt(2025, 2, 24).unwrap()
+        );
+        assert_eq!(calendar.weeks[1].trades, 3);
+        assert!((calendar.weeks[1].net_pnl - 70.0).abs() < 1e-9);
+        let holi = &calendar.days[13];
+        assert!(!holi.is_trading_day && holi.holiday.is_some());
+    }
+}
@@ -0,0 +1,302 @@
+use std::path::{Path, PathBuf};
+
+use chrono::NaiveDateTime;
+use serde::{Deserialize, Serialize};
+
+use crate::utils::persist;
+
+/// Largest file accepted for upload.
+pub const MAX_FILE_BYTES: usize = 10 * 1024 * 1024;
+/// Largest thumbnail accepted; thumbnails are small previews, not copies.
+pub const MAX_THUMBNAIL_BYTES: usize = 256 * 1024;
+pub const MAX_PER_TRADE: usize = 20;
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum AttachmentKind {
+    /// Uploaded screenshot or photo.
+    Image,
+    /// Price chart rendered by the backend around the trade.
+    Chart,
+    /// Anything else that can't be shown inline, e.g. a contract note PDF.
+    File,
+}
+
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct Attachment {
+    pub id: String,
+    pub trade_id: String,
+    pub name: String,
+    pub content_type: String,
+    pub size: usize,
+    pub kind: AttachmentKind,
+    #[serde(default)]
+    pub caption: String,
+    pub created_at: NaiveDateTime,
+    #[serde(default)]
+    pub has_thumbnail: bool,
+}
+
+/// Work out what an upload is from its leading bytes rather than trusting
+/// the client. SVG uploads are refused since they can carry scripts.
+pub fn sniff(bytes: &[u8], name: &str) -> Result<(&'static str, AttachmentKind), String> {
+    let image = |content_type| Ok((content_type, AttachmentKind::Image));
+    if bytes.starts_with(b"\x89PNG\r\n\x1a\n") {
+        return image("image/png");
+    }
+    if bytes.starts_with(&[0xff, 0xd8, 0xff]) {
+        return image("image/jpeg");
+    }
+    if bytes.starts_with(b"GIF87a") || bytes.starts_with(b"GIF89a") {
+        return image("image/gif");
+    }
+    if bytes.len() >= 12 && &bytes[..4] == b"RIFF" && &bytes[8..12] == b"WEBP" {
+        return image("image/webp");
+    }
+    if bytes.starts_with(b"%PDF-") {
+        return Ok(("application/pdf", AttachmentKind::File));
+    }
+
+    let extension = name.rsplit_once('.').map(|(_, ext)| ext.to_lowercase());
+    if std::str::from_utf8(bytes).is_ok() {
+        match extension.as_deref() {
+            Some("csv") => return Ok(("text/csv", AttachmentKind::File)),
+            Some("txt") | Some("md") => return Ok(("text/plain", AttachmentKind::File)),
+            _ => {}
+        }
+    }
+    Err(format!(
+        "unsupported file type for '{}'; use PNG, JPEG, GIF, WebP, PDF, CSV or text",
+        name
+    ))
+}
+
+/// File name safe to echo back in headers: no paths, quotes or control
+/// characters, and a sane length.
+pub fn clean_name(name: &str) -> String {
+    let base = name.rsplit(['/', '\\']).next().unwrap_or(name);
+    let cleaned: String = base
+        .chars()
+        .filter(|c| !c.is_control() && *c != '"')
+        .take(100)
+        .collect();
+    let cleaned = cleaned.trim();
+    if cleaned.is_empty() {
+        "attachment".to_string()
+    } else {
+        cleaned.to_string()
+    }
+}
+
+#[derive(Default, Serialize, Deserialize)]
+struct AttachmentIndex {
+    next_id: u64,
+    attachments: Vec<Attachment>,
+}
+
+/// Attachment files kept in one directory, named by id, with `index.json`
+/// holding the metadata. Thumbnails sit next to their file as `<id>.thumb`.
+pub struct AttachmentStore {
+    dir: PathBuf,
+    data: AttachmentIndex,
+}
+
+impl AttachmentStore {
+    pub fn load(dir: &Path) -> Self {
+        let data = persist::load_json(&dir.join("index.json"));
+
+        Self {
+            dir: dir.to_path_buf(),
+            data,
+        }
+    }
+
+    fn save(&self) -> Result<(), String> {
+        persist::save_json(&self.dir.join("index.json"), &self.data)
+    }
+
+    fn file_path(&self, id: &str) -> PathBuf {
+        self.dir.join(id)
+    }
+
+    fn thumbnail_path(&self, id: &str) -> PathBuf {
+        self.dir.join(format!("{}.thumb", id))
+    }
+
+    fn write(path: &Path, bytes: &[u8]) -> Result<(), String> {
+        std::fs::write(path, bytes)
+            .map_err(|e| format!("failed to write {}: {}", path.display(), e))
+    }
+
+    /// Attachments of a trade, oldest first.
+    pub fn for_trade(&self, trade_id: &str) -> Vec<Attachment> {
+        self.data
+            .attachments
+            .iter()
+            .filter(|a| a.trade_id == trade_id)
+            .cloned()
+            .collect()
+    }
+
+    pub fn get(&self, id: &str) -> Option<&Attachment> {
+        self.data.attachments.iter().find(|a| a.id == id)
+    }
+
+    pub fn read(&self, id: &str) -> Result<Vec<u8>, String> {
+        std::fs::read(self.file_path(id)).map_err(|e| e.to_string())
+    }
+
+    pub fn read_thumbnail(&self, id: &str) -> Result<Vec<u8>, String> {
+        std::fs::read(self.thumbnail_path(id)).map_err(|e| e.to_string())
+    }
+
+    /// Store a new attachment with its content and optional thumbnail. The
+    /// id is assigned here.
+    pub fn add(
+        &mut self,
+        mut attachment: Attachment,
+        bytes: &[u8],
+        thumbnail: Option<&[u8]>,
+    ) -> Result<Attachment, String> {
+        std::fs::create_dir_all(&self.dir).map_err(|e| e.to_string())?;
+        self.data.next_id += 1;
+        attachment.id = format!("a{}", self.data.next_id);
+        attachment.size = bytes.len();
+        attachment.has_thumbnail = thumbnail.is_some();
+
+        Self::write(&self.file_path(&attachment.id), bytes)?;
+        if let Some(thumbnail) = thumbnail {
+            Self::write(&self.thumbnail_path(&attachment.id), thumbnail)?;
+        }
+        self.data.attachments.push(attachment.clone());
+        self.save()?;
+        Ok(attachment)
+    }
+
+    pub fn set_thumbnail(&mut self, id: &str, bytes: &[u8]) -> Result<Option<Attachment>, String> {
+        let path = self.thumbnail_path(id);
+        let Some(attachment) = self.data.attachments.iter_mut().find(|a| a.id == id) else {
+            return Ok(None);
+        };
+        Self::write(&path, bytes)?;
+        attachment.has_thumbnail = true;
+        let attachment = attachment.clone();
+        self.save()?;
+        Ok(Some(attachment))
+    }
+
+    pub fn set_caption(&mut self, id: &str, caption: String) -> Result<Option<Attachment>, String> {
+        let Some(attachment) = self.data.attachments.iter_mut().find(|a| a.id == id) else {
+            return Ok(None);
+        };
+        attachment.caption = caption;
+        let attachment = attachment.clone();
+        self.save()?;
+        Ok(Some(attachment))
+    }
+
+    pub fn remove(&mut self, id: &str) -> Result<bool, String> {
+        let before = self.data.attachments.len();
+        self.data.attachments.retain(|a| a.id != id);
+        if self.data.attachments.len() == before {
+            return Ok(false);
+        }
+        self.delete_files(id);
+        self.save()?;
+        Ok(true)
+    }
+
+    /// Drop every attachment of a deleted trade.
+    pub fn remove_trade(&mut self, trade_id: &str) -> Result<usize, String> {
+        let ids: Vec<String> = self
+            .data
+            .attachments
+            .iter()
+            .filter(|a| a.trade_id == trade_id)
+            .map(|a| a.id.clone())
+            .collect();
+        if ids.is_empty() {
+            return Ok(0);
+        }
+        self.data.attachments.retain(|a| a.trade_id != trade_id);
+        for id in &ids {
+            self.delete_files(id);
+        }
+        self.save()?;
+        Ok(ids.len())
+    }
+
+    /// Missing files are fine here; the index is what counts.
+    fn delete_files(&self, id: &str) {
+        for path in [self.file_path(id), self.thumbnail_path(id)] {
+            if let Err(e) = std::fs::remove_file(&path) {
+                if e.kind() != std::io::ErrorKind::NotFound {
+                    log::warn!("Failed to delete {}: {}", path.display(), e);
+                }
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn uploads_are_typed_by_content_not_name() {
+        let png = b"\x89PNG\r\n\x1a\n....";
+        assert_eq!(
+            sniff(png, "chart.pdf").unwrap(),
+            ("image/png", AttachmentKind::Image)
+        );
+        assert_eq!(
+            sniff(b"%PDF-1.7", "note").unwrap(),
+            ("application/pdf", AttachmentKind::File)
+        );
+        assert_eq!(sniff(b"a,b\n1,2\n", "fills.CSV").unwrap().0, "text/csv");
+        assert!(sniff(b"<svg onload=alert(1)>", "logo.svg").is_err());
+        assert!(sniff(&[0, 159, 146, 150], "data.txt").is_err());
+    }
+
+    #[test]
+    fn names_lose_paths_quotes_and_control_characters() {
+        assert_eq!(clean_name("C:\\shots\\entry \"1\".png"), "entry 1.png");
+        assert_eq!(clean_name("../../etc/passwd"), "passwd");
+        assert_eq!(clean_name("\n\t"), "attachment");
+    }
+
+    #[test]
+    fn attachments_survive_a_reload_and_go_with_their_trade() {
+        let dir = std::env::temp_dir().join(format!("slynqix-attachments-{}", std::process::id()));
+        let _ = std::fs::remove_dir_all(&dir);
+        let mut store = AttachmentStore::load(&dir);
+        let attachment = |trade_id: &str| Attachment {
+            id: String::new(),
+            trade_id: trade_id.to_string(),
+            name: "entry.png".to_string(),
+            content_type: "image/png".to_string(),
+            size: 0,
+            kind: AttachmentKind::Image,
+            caption: String::new(),
+            created_at: chrono::NaiveDate::from_ymd_opt(2025, 3, 3)
+                .unwrap()
+                .and_hms_opt(10, 0, 0)
+                .unwrap(),
+            has_thumbnail: false,
+        };
+        let first = store.add(attachment("t1"), b"one", Some(b"thumb")).unwrap();
+        store.add(attachment("t1"), b"two", None).unwrap();
+        let other = store.add(attachment("t2"), b"three", None).unwrap();
+
+        let reloaded = AttachmentStore::load(&dir);
+        assert_eq!(reloaded.for_trade("t1").len(), 2);
+        assert_eq!(reloaded.read(&first.id).unwrap(), b"one");
+        assert_eq!(reloaded.read_thumbnail(&first.id).unwrap(), b"thumb");
+        assert_eq!(reloaded.get(&first.id).unwrap().size, 3);
+
+        assert_eq!(store.remove_trade("t1").unwrap(), 2);
+        assert!(store.read(&first.id).is_err());
+        assert!(store.get(&other.id).is_some());
+        std::fs::remove_dir_all(&dir).unwrap();
+    }
+}
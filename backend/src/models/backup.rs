@@ -0,0 +1,461 @@
+use std::collections::HashSet;
+
+use chrono::NaiveDateTime;
+use serde::{Deserialize, Serialize};
+
+use crate::models::journal::{Execution, JournalStore, Side, Tag, TagKind, Trade};
+use crate::models::tradebook;
+use crate::utils::csv;
+use crate::utils::spreadsheet::{self, Sheet};
+
+/// Largest backup file accepted for a restore.
+pub const MAX_BACKUP_BYTES: usize = 50 * 1024 * 1024;
+
+const VERSION: u32 = 1;
+
+/// One row per execution. Trade fields repeat on every row of the trade
+/// so the sheet can be filtered and sorted without losing them.
+const COLUMNS: [&str; 10] = [
+    "trade",
+    "symbol",
+    "side",
+    "quantity",
+    "price",
+    "time",
+    "fees",
+    "broker_trade_id",
+    "tags",
+    "notes",
+];
+
+const TIME_FORMAT: &str = "%Y-%m-%d %H:%M:%S";
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum BackupFormat {
+    Json,
+    Csv,
+    /// Excel 2003 XML spreadsheet, with an executions and a tags sheet.
+    Xml,
+}
+
+impl BackupFormat {
+    pub fn extension(self) -> &'static str {
+        match self {
+            BackupFormat::Json => "json",
+            BackupFormat::Csv => "csv",
+            BackupFormat::Xml => "xml",
+        }
+    }
+
+    pub fn content_type(self) -> &'static str {
+        match self {
+            BackupFormat::Json => "application/json",
+            BackupFormat::Csv => "text/csv",
+            BackupFormat::Xml => "application/vnd.ms-excel",
+        }
+    }
+}
+
+/// Everything a backup carries: trades with their executions, notes and
+/// tags, plus the tag definitions.
+#[derive(Serialize, Deserialize)]
+pub struct Backup {
+    pub version: u32,
+    pub exported_at: NaiveDateTime,
+    #[serde(default)]
+    pub tags: Vec<Tag>,
+    pub trades: Vec<Trade>,
+}
+
+pub fn backup(journal: &JournalStore, exported_at: NaiveDateTime) -> Backup {
+    Backup {
+        version: VERSION,
+        exported_at,
+        tags: journal.tags().to_vec(),
+        trades: journal.trades().to_vec(),
+    }
+}
+
+fn execution_rows(trades: &[Trade]) -> Vec<Vec<String>> {
+    let mut rows = vec![COLUMNS.iter().map(|c| c.to_string()).collect()];
+    for trade in trades {
+        for execution in trade.sorted_executions() {
+            rows.push(vec![
+                trade.id.clone(),
+                trade.symbol.clone(),
+                format!("{:?}", execution.side),
+                execution.quantity.to_string(),
+                execution.price.to_string(),
+                execution.time.format(TIME_FORMAT).to_string(),
+                execution.fees.to_string(),
+                execution.trade_id.clone().unwrap_or_default(),
+                trade.tags.join("; "),
+                trade.notes.clone(),
+            ]);
+        }
+    }
+    rows
+}
+
+fn kind_name(kind: TagKind) -> &'static str {
+    match kind {
+        TagKind::Setup => "setup",
+        TagKind::Mistake => "mistake",
+        TagKind::Emotion => "emotion",
+    }
+}
+
+pub fn export(backup: &Backup, format: BackupFormat) -> Result<String, String> {
+    match format {
+        BackupFormat::Json => serde_json::to_string_pretty(backup).map_err(|e| e.to_string()),
+        BackupFormat::Csv => Ok(execution_rows(&backup.trades)
+            .iter()
+            .map(|row| csv::row(row) + "\n")
+            .collect()),
+        BackupFormat::Xml => {
+            let mut tags = vec![vec![
+                "name".to_string(),
+                "kind".to_string(),
+                "description".to_string(),
+            ]];
+            tags.extend(backup.tags.iter().map(|tag| {
+                vec![
+                    tag.name.clone(),
+                    kind_name(tag.kind).to_string(),
+                    tag.description.clone(),
+                ]
+            }));
+            Ok(spreadsheet::write(&[
+                Sheet {
+                    name: "Executions".to_string(),
+                    rows: execution_rows(&backup.trades),
+                },
+                Sheet {
+                    name: "Tags".to_string(),
+                    rows: tags,
+                },
+            ]))
+        }
+    }
+}
+
+fn table(mut rows: Vec<Vec<String>>) -> csv::Table {
+    let headers = if rows.is_empty() {
+        Vec::new()
+    } else {
+        rows.remove(0)
+            .into_iter()
+            .map(|h| h.trim().to_lowercase())
+            .collect()
+    };
+    // Blank rows, as spreadsheets keep them, carry no executions
+    rows.retain(|row| row.iter().any(|cell| !cell.trim().is_empty()));
+    csv::Table { headers, rows }
+}
+
+/// Trades from execution rows, grouped by the trade column in the order
+/// they first appear. A trade with any unreadable row is left out whole
+/// rather than restored with missing fills.
+fn read_executions(table: &csv::Table, errors: &mut Vec<String>) -> Result<Vec<Trade>, String> {
+    let column = |name: &str| table.column(&[name]);
+    let trade_col = column("trade").ok_or("backup has no trade column")?;
+    let symbol_col = column("symbol").ok_or("backup has no symbol column")?;
+    let side_col = column("side").ok_or("backup has no side column")?;
+    let quantity_col = column("quantity").ok_or("backup has no quantity column")?;
+    let price_col = column("price").ok_or("backup has no price column")?;
+    let time_col = column("time").ok_or("backup has no time column")?;
+    let (fees_col, id_col) = (column("fees"), column("broker_trade_id"));
+    let (tags_col, notes_col) = (column("tags"), column("notes"));
+
+    let mut trades: Vec<Trade> = Vec::new();
+    let mut broken: Vec<String> = Vec::new();
+    for (index, row) in table.rows.iter().enumerate() {
+        // Header is line 1
+        let line = index + 2;
+        let Some(key) = table.get(row, Some(trade_col)) else {
+            errors.push(format!("line {}: missing trade", line));
+            continue;
+        };
+        let Some(symbol) = table.get(row, Some(symbol_col)) else {
+            errors.push(format!("line {}: missing symbol", line));
+            skip(&mut broken, key);
+            continue;
+        };
+
+        let side = match table.get(row, Some(side_col)).map(str::to_lowercase) {
+            Some(side) if side.starts_with('b') => Some(Side::Buy),
+            Some(side) if side.starts_with('s') => Some(Side::Sell),
+            _ => None,
+        };
+        let quantity = table
+            .get(row, Some(quantity_col))
+            .and_then(|v| v.parse::<u32>().ok());
+        let price = table.get(row, Some(price_col)).and_then(parse_number);
+        let fees = match table.get(row, fees_col) {
+            Some(v) => parse_number(v),
+            None => Some(0.0),
+        };
+        let time = tradebook::parse_time(table.get(row, Some(time_col)), None);
+        let (Some(side), Some(quantity), Some(price), Some(fees), Some(time)) =
+            (side, quantity, price, fees, time)
+        else {
+            errors.push(format!(
+                "line {}: unreadable side, quantity, price, fees or time",
+                line
+            ));
+            skip(&mut broken, key);
+            continue;
+        };
+
+        let execution = Execution {
+            id: String::new(),
+            side,
+            quantity,
+            price,
+            time,
+            fees,
+            trade_id: table.get(row, id_col).map(str::to_string),
+        };
+        match trades.iter_mut().find(|t| t.id == key) {
+            Some(trade) if trade.symbol != symbol => {
+                errors.push(format!(
+                    "line {}: trade {} mixes symbols {} and {}",
+                    line, key, trade.symbol, symbol
+                ));
+                skip(&mut broken, key);
+            }
+            Some(trade) => trade.executions.push(execution),
+            None => trades.push(Trade {
+                id: key.to_string(),
+                symbol: symbol.to_string(),
+                executions: vec![execution],
+                notes: table.get(row, notes_col).unwrap_or_default().to_string(),
+                tags: table
+                    .get(row, tags_col)
+                    .map(|tags| tags.split(';').map(|t| t.trim().to_string()).collect())
+                    .unwrap_or_default(),
+            }),
+        }
+    }
+
+    for key in &broken {
+        errors.push(format!("trade {}: skipped because of unreadable rows", key));
+    }
+    trades.retain(|t| !broken.contains(&t.id));
+    Ok(trades)
+}
+
+/// A finite number; `inf` and `NaN` parse as floats but can't be saved
+/// back to JSON.
+fn parse_number(value: &str) -> Option<f64> {
+    value.parse::<f64>().ok().filter(|v| v.is_finite())
+}
+
+fn skip(broken: &mut Vec<String>, key: &str) {
+    if !broken.iter().any(|k| k == key) {
+        broken.push(key.to_string());
+    }
+}
+
+fn read_tags(table: &csv::Table, errors: &mut Vec<String>) -> Vec<Tag> {
+    let (name_col, kind_col) = (table.column(&["name"]), table.column(&["kind"]));
+    let description_col = table.column(&["description"]);
+    let mut tags = Vec::new();
+    for (index, row) in table.rows.iter().enumerate() {
+        let Some(name) = table.get(row, name_col) else {
+            continue;
+        };
+        let kind = match table.get(row, kind_col).map(str::to_lowercase).as_deref() {
+            Some("setup") | None => TagKind::Setup,
+            Some("mistake") => TagKind::Mistake,
+            Some("emotion") => TagKind::Emotion,
+            Some(other) => {
+                errors.push(format!("tags line {}: unknown kind '{}'", index + 2, other));
+                continue;
+            }
+        };
+        tags.push(Tag {
+            name: name.to_string(),
+            kind,
+            description: table
+                .get(row, description_col)
+                .unwrap_or_default()
+                .to_string(),
+        });
+    }
+    tags
+}
+
+/// Trades and tag definitions read from a backup. Rows that can't be
+/// read are reported in `errors`; a file that can't be read at all fails.
+fn read(
+    text: &str,
+    format: BackupFormat,
+    errors: &mut Vec<String>,
+) -> Result<(Vec<Trade>, Vec<Tag>), String> {
+    Ok(match format {
+        BackupFormat::Json => {
+            let backup: Backup =
+                serde_json::from_str(text).map_err(|e| format!("invalid backup JSON: {}", e))?;
+            if backup.version > VERSION {
+                return Err(format!(
+                    "backup version {} is newer than this app supports",
+                    backup.version
+                ));
+            }
+            (backup.trades, backup.tags)
+        }
+        BackupFormat::Csv => {
+            let trades = read_executions(&csv::Table::parse(text), errors)?;
+            (trades, Vec::new())
+        }
+        BackupFormat::Xml => {
+            let mut sheets = spreadsheet::read(text)?;
+            let tags = match sheets
+                .iter()
+                .position(|s| s.name.eq_ignore_ascii_case("tags"))
+            {
+                Some(index) => read_tags(&table(sheets.remove(index).rows), errors),
+                None => Vec::new(),
+            };
+            let executions = sheets
+                .iter()
+                .position(|s| s.name.eq_ignore_ascii_case("executions"))
+                .unwrap_or(0);
+            let Some(sheet) = sheets.into_iter().nth(executions) else {
+                return Err("spreadsheet has no executions sheet".to_string());
+            };
+            let trades = read_executions(&table(sheet.rows), errors)?;
+            (trades, tags)
+        }
+    })
+}
+
+/// Executions in time order, which identify a trade whatever its id or
+/// the case of its symbol.
+fn fingerprint(trade: &Trade) -> String {
+    let mut key = trade.symbol.to_uppercase();
+    for e in trade.sorted_executions() {
+        key.push_str(&format!(
+            "|{:?} {} {} {}",
+            e.side, e.quantity, e.price, e.time
+        ));
+    }
+    key
+}
+
+/// What restoring a backup would add to the journal.
+#[derive(Default)]
+pub struct RestorePlan {
+    /// Trades to add, with ids cleared so the journal assigns fresh ones.
+    pub trades: Vec<Trade>,
+    /// Tag definitions the journal doesn't have yet.
+    pub tags: Vec<Tag>,
+    /// Trades already in the journal, or repeated in the file.
+    pub duplicates: usize,
+    pub errors: Vec<String>,
+}
+
+/// Read and validate a backup against the journal. Invalid trades are
+/// reported and left out; trades whose executions match a journal trade,
+/// or that carry a broker trade id already imported, count as duplicates.
+pub fn plan(
+    journal: &JournalStore,
+    text: &str,
+    format: BackupFormat,
+) -> Result<RestorePlan, String> {
+    let mut plan = RestorePlan::default();
+    let (trades, tags) = read(text, format, &mut plan.errors)?;
+
+    let known_ids = journal.trade_ids();
+    let mut seen: HashSet<String> = journal.trades().iter().map(fingerprint).collect();
+    for (index, mut trade) in trades.into_iter().enumerate() {
+        let label = if trade.id.is_empty() {
+            format!("trade {}", index + 1)
+        } else {
+            format!("trade {}", trade.id)
+        };
+        trade.symbol = trade.symbol.trim().to_string();
+        if let Err(e) = trade.validate() {
+            plan.errors.push(format!("{}: {}", label, e));
+            continue;
+        }
+        let imported = trade
+            .executions
+            .iter()
+            .filter_map(|e| e.trade_id.as_deref())
+            .any(|id| known_ids.contains(id));
+        if imported || !seen.insert(fingerprint(&trade)) {
+            plan.duplicates += 1;
+            continue;
+        }
+
+        trade.id = String::new();
+        for execution in &mut trade.executions {
+            execution.id = String::new();
+        }
+        plan.trades.push(trade);
+    }
+
+    let mut names: HashSet<String> = journal.tags().iter().map(|t| t.name.clone()).collect();
+    for mut tag in tags {
+        tag.name = tag.name.trim().to_string();
+        if !tag.name.is_empty() && names.insert(tag.name.clone()) {
+            plan.tags.push(tag);
+        }
+    }
+
+    Ok(plan)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn journal_path(name: &str) -> std::path::PathBuf {
+        let path = std::env::temp_dir().join(format!(
+            "slynqix-backup-{}-{}.json",
+            name,
+            std::process::id()
+        ));
+        let _ = std::fs::remove_file(&path);
+        path
+    }
+
+    const HEADER: &str = "trade,symbol,side,quantity,price,time,fees\n";
+
+    #[test]
+    fn restore_skips_non_finite_numbers() {
+        let journal = JournalStore::load(&journal_path("non-finite"));
+        let text = format!(
+            "{}t1,INFY,Buy,10,inf,2025-03-03 10:00:00,0\nt2,TCS,Buy,10,3500,2025-03-03 10:00:00,NaN\nt3,ITC,Buy,10,410,2025-03-03 10:00:00,0\n",
+            HEADER
+        );
+        let plan = plan(&journal, &text, BackupFormat::Csv).unwrap();
+
+        assert_eq!(plan.trades.len(), 1);
+        assert_eq!(plan.trades[0].symbol, "ITC");
+        assert_eq!(plan.errors.len(), 4);
+    }
+
+    #[test]
+    fn restoring_an_export_into_its_journal_adds_nothing() {
+        let path = journal_path("round-trip");
+        let mut journal = JournalStore::load(&path);
+        let text = format!("{}t1,Infy,Buy,10,1500,2025-03-03 10:00:00,0\n", HEADER);
+        let first = plan(&journal, &text, BackupFormat::Csv).unwrap();
+        assert_eq!(first.trades[0].symbol, "Infy");
+        journal.insert_all(first.tags, first.trades).unwrap();
+
+        let now = chrono::NaiveDate::from_ymd_opt(2025, 3, 4)
+            .unwrap()
+            .and_hms_opt(0, 0, 0)
+            .unwrap();
+        let exported = export(&backup(&journal, now), BackupFormat::Csv).unwrap();
+        let again = plan(&journal, &exported, BackupFormat::Csv).unwrap();
+        assert!(again.trades.is_empty());
+        assert_eq!(again.duplicates, 1);
+        std::fs::remove_file(&path).unwrap();
+    }
+}
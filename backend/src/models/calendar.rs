@@ -0,0 +1,431 @@
+use std::collections::BTreeMap;
+use std::path::Path;
+
+use chrono::{Datelike, Duration, FixedOffset, NaiveDate, NaiveDateTime, NaiveTime, Utc, Weekday};
+use serde::{Deserialize, Serialize};
+
+use crate::utils::csv;
+
+/// IST is a fixed UTC+05:30 offset with no daylight saving.
+pub fn ist() -> FixedOffset {
+    FixedOffset::east_opt(5 * 3600 + 30 * 60).unwrap()
+}
+
+pub fn now_ist() -> NaiveDateTime {
+    Utc::now().with_timezone(&ist()).naive_local()
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum SessionPhase {
+    PreOpen,
+    Normal,
+    Closing,
+    Closed,
+}
+
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct Session {
+    pub phase: SessionPhase,
+    pub start: NaiveTime,
+    pub end: NaiveTime,
+}
+
+/// A one-off session on a day that is otherwise a holiday or weekend,
+/// e.g. Muhurat trading on Diwali or a Saturday budget session.
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct SpecialSession {
+    pub name: String,
+    pub start: NaiveTime,
+    pub end: NaiveTime,
+}
+
+#[derive(Clone, Debug, Serialize)]
+pub struct TradingDay {
+    pub date: NaiveDate,
+    pub is_trading_day: bool,
+    pub holiday: Option<String>,
+    pub special_session: Option<String>,
+    pub sessions: Vec<Session>,
+    /// The day itself when it trades, otherwise the trading day before it.
+    pub nearest_trading_day: NaiveDate,
+    /// False when there is no holiday list for the year, so only weekends
+    /// are known to be closed and `is_trading_day` is a guess.
+    pub holidays_known: bool,
+    /// Says which years the holiday list covers when it doesn't cover this
+    /// day's.
+    pub note: Option<String>,
+}
+
+#[derive(Clone, Debug, Serialize)]
+pub struct MarketStatus {
+    pub now: NaiveDateTime,
+    pub phase: SessionPhase,
+    pub label: String,
+    pub is_trading_day: bool,
+    pub next_change: Option<NaiveDateTime>,
+    /// Set when today's year has no holiday list, see [`TradingDay::note`].
+    pub note: Option<String>,
+}
+
+#[derive(Clone, Debug, Serialize)]
+pub struct Holiday {
+    pub date: NaiveDate,
+    pub name: String,
+}
+
+fn time(h: u32, m: u32) -> NaiveTime {
+    NaiveTime::from_hms_opt(h, m, 0).unwrap()
+}
+
+fn date(y: i32, m: u32, d: u32) -> NaiveDate {
+    NaiveDate::from_ymd_opt(y, m, d).unwrap()
+}
+
+/// Equity trading calendar shared by NSE and BSE, which publish the same
+/// holiday list. All times are IST.
+pub struct TradingCalendar {
+    holidays: BTreeMap<NaiveDate, String>,
+    special_sessions: BTreeMap<NaiveDate, SpecialSession>,
+}
+
+impl TradingCalendar {
+    pub fn with_defaults() -> Self {
+        let holidays = [
+            (date(2025, 2, 26), "Mahashivratri"),
+            (date(2025, 3, 14), "Holi"),
+            (date(2025, 3, 31), "Id-Ul-Fitr (Ramadan Eid)"),
+            (date(2025, 4, 10), "Shri Mahavir Jayanti"),
+            (date(2025, 4, 14), "Dr. Baba Saheb Ambedkar Jayanti"),
+            (date(2025, 4, 18), "Good Friday"),
+            (date(2025, 5, 1), "Maharashtra Day"),
+            (date(2025, 8, 15), "Independence Day"),
+            (date(2025, 8, 27), "Ganesh Chaturthi"),
+            (date(2025, 10, 2), "Mahatma Gandhi Jayanti / Dussehra"),
+            (date(2025, 10, 21), "Diwali Laxmi Pujan"),
+            (date(2025, 10, 22), "Diwali Balipratipada"),
+            (date(2025, 11, 5), "Prakash Gurpurb Sri Guru Nanak Dev"),
+            (date(2025, 12, 25), "Christmas"),
+            (date(2026, 1, 26), "Republic Day"),
+            (date(2026, 3, 3), "Holi"),
+            (date(2026, 3, 26), "Shri Ram Navami"),
+            (date(2026, 3, 31), "Shri Mahavir Jayanti"),
+            (date(2026, 4, 3), "Good Friday"),
+            (date(2026, 4, 14), "Dr. Baba Saheb Ambedkar Jayanti"),
+            (date(2026, 5, 1), "Maharashtra Day"),
+            (date(2026, 5, 28), "Bakri Id"),
+            (date(2026, 6, 26), "Muharram"),
+            (date(2026, 9, 14), "Ganesh Chaturthi"),
+            (date(2026, 10, 2), "Mahatma Gandhi Jayanti"),
+            (date(2026, 10, 20), "Dussehra"),
+            (date(2026, 11, 10), "Diwali Balipratipada"),
+            (date(2026, 11, 24), "Prakash Gurpurb Sri Guru Nanak Dev"),
+            (date(2026, 12, 25), "Christmas"),
+        ]
+        .into_iter()
+        .map(|(day, name)| (day, name.to_string()))
+        .collect();
+
+        let special_sessions = [
+            (date(2025, 2, 1), "Union Budget", time(9, 15), time(15, 30)),
+            (
+                date(2025, 10, 21),
+                "Muhurat Trading",
+                time(13, 45),
+                time(14, 45),
+            ),
+            (
+                date(2026, 11, 8),
+                "Muhurat Trading",
+                time(18, 0),
+                time(19, 0),
+            ),
+        ]
+        .into_iter()
+        .map(|(day, name, start, end)| {
+            (
+                day,
+                SpecialSession {
+                    name: name.to_string(),
+                    start,
+                    end,
+                },
+            )
+        })
+        .collect();
+
+        Self {
+            holidays,
+            special_sessions,
+        }
+    }
+
+    /// Load exchange circulars on top of the built-in calendar. Each row is
+    /// `date,name[,start,end]`; rows with a start/end are special sessions,
+    /// the rest are holidays.
+    pub fn load(path: &Path) -> Result<Self, String> {
+        let text = std::fs::read_to_string(path)
+            .map_err(|e| format!("failed to read {}: {}", path.display(), e))?;
+        let mut calendar = Self::with_defaults();
+        let table = csv::Table::parse(&text);
+        let date_col = table.column(&["date"]);
+        let name_col = table.column(&["name", "description"]);
+        let start_col = table.column(&["start"]);
+        let end_col = table.column(&["end"]);
+
+        for row in &table.rows {
+            let Some(day) = table
+                .get(row, date_col)
+                .and_then(|v| NaiveDate::parse_from_str(v, "%Y-%m-%d").ok())
+            else {
+                continue;
+            };
+            let name = table
+                .get(row, name_col)
+                .unwrap_or("Exchange holiday")
+                .to_string();
+            let parse_time = |col| {
+                table
+                    .get(row, col)
+                    .and_then(|v| NaiveTime::parse_from_str(v, "%H:%M").ok())
+            };
+
+            match (parse_time(start_col), parse_time(end_col)) {
+                (Some(start), Some(end)) => {
+                    calendar
+                        .special_sessions
+                        .insert(day, SpecialSession { name, start, end });
+                }
+                _ => {
+                    calendar.holidays.insert(day, name);
+                }
+            }
+        }
+
+        Ok(calendar)
+    }
+
+    /// Every date in `year`, or `None` when chrono can't represent it.
+    fn year_range(year: i32) -> Option<std::ops::RangeInclusive<NaiveDate>> {
+        Some(NaiveDate::from_ymd_opt(year, 1, 1)?..=NaiveDate::from_ymd_opt(year, 12, 31)?)
+    }
+
+    /// Whether the holiday list covers `year`, either built in or loaded
+    /// from a circular.
+    pub fn knows_year(&self, year: i32) -> bool {
+        Self::year_range(year).is_some_and(|range| self.holidays.range(range).next().is_some())
+    }
+
+    /// Why days in `year` are only guessed to trade, when the holiday list
+    /// doesn't cover it.
+    pub fn coverage_note(&self, year: i32) -> Option<String> {
+        if self.knows_year(year) {
+            return None;
+        }
+        let years: Vec<String> = self.known_years().iter().map(i32::to_string).collect();
+        Some(format!(
+            "The holiday list covers {} only; in {} just weekends are known to be closed",
+            years.join(", "),
+            year
+        ))
+    }
+
+    /// Weekday holidays in `year`, or `None` when the year has no holiday
+    /// list.
+    pub fn holidays_in_year(&self, year: i32) -> Option<Vec<Holiday>> {
+        if !self.knows_year(year) {
+            return None;
+        }
+        let holidays = self
+            .holidays
+            .range(Self::year_range(year)?)
+            .filter(|(day, _)| !is_weekend(**day))
+            .map(|(day, name)| Holiday {
+                date: *day,
+                name: name.clone(),
+            })
+            .collect();
+        Some(holidays)
+    }
+
+    /// Years the holiday list covers, oldest first.
+    pub fn known_years(&self) -> Vec<i32> {
+        let mut years: Vec<i32> = self.holidays.keys().map(|day| day.year()).collect();
+        years.dedup();
+        years
+    }
+
+    pub fn is_trading_day(&self, day: NaiveDate) -> bool {
+        self.special_sessions.contains_key(&day)
+            || (!is_weekend(day) && !self.holidays.contains_key(&day))
+    }
+
+    pub fn previous_trading_day(&self, day: NaiveDate) -> NaiveDate {
+        let mut candidate = day - Duration::days(1);
+        while !self.is_trading_day(candidate) {
+            candidate -= Duration::days(1);
+        }
+        candidate
+    }
+
+    pub fn next_trading_day(&self, day: NaiveDate) -> NaiveDate {
+        let mut candidate = day + Duration::days(1);
+        while !self.is_trading_day(candidate) {
+            candidate += Duration::days(1);
+        }
+        candidate
+    }
+
+    /// `day` itself when it is a trading day, otherwise the one before it.
+    pub fn last_trading_day_on_or_before(&self, day: NaiveDate) -> NaiveDate {
+        if self.is_trading_day(day) {
+            day
+        } else {
+            self.previous_trading_day(day)
+        }
+    }
+
+    pub fn trading_days_between(&self, from: NaiveDate, to: NaiveDate) -> Vec<NaiveDate> {
+        from.iter_days()
+            .take_while(|day| *day <= to)
+            .filter(|day| self.is_trading_day(*day))
+            .collect()
+    }
+
+    /// Sessions for the day. Regular days have pre-open (09:00-09:15),
+    /// normal (09:15-15:30) and the closing session (15:40-16:00); special
+    /// sessions get a 15 minute pre-open ahead of their normal window.
+    pub fn sessions(&self, day: NaiveDate) -> Vec<Session> {
+        if let Some(special) = self.special_sessions.get(&day) {
+            if is_weekend(day) || self.holidays.contains_key(&day) {
+                return vec![
+                    Session {
+                        phase: SessionPhase::PreOpen,
+                        start: special.start - Duration::minutes(15),
+                        end: special.start,
+                    },
+                    Session {
+                        phase: SessionPhase::Normal,
+                        start: special.start,
+                        end: special.end,
+                    },
+                ];
+            }
+        }
+        if !self.is_trading_day(day) {
+            return Vec::new();
+        }
+
+        vec![
+            Session {
+                phase: SessionPhase::PreOpen,
+                start: time(9, 0),
+                end: time(9, 15),
+            },
+            Session {
+                phase: SessionPhase::Normal,
+                start: time(9, 15),
+                end: time(15, 30),
+            },
+            Session {
+                phase: SessionPhase::Closing,
+                start: time(15, 40),
+                end: time(16, 0),
+            },
+        ]
+    }
+
+    /// The normal-market window for the day, used to bound intraday bars.
+    pub fn normal_session(&self, day: NaiveDate) -> Option<Session> {
+        self.sessions(day)
+            .into_iter()
+            .find(|s| s.phase == SessionPhase::Normal)
+    }
+
+    pub fn trading_day(&self, day: NaiveDate) -> TradingDay {
+        TradingDay {
+            date: day,
+            is_trading_day: self.is_trading_day(day),
+            holiday: self.holidays.get(&day).cloned(),
+            special_session: self.special_sessions.get(&day).map(|s| s.name.clone()),
+            sessions: self.sessions(day),
+            nearest_trading_day: self.last_trading_day_on_or_before(day),
+            holidays_known: self.knows_year(day.year()),
+            note: self.coverage_note(day.year()),
+        }
+    }
+
+    pub fn status_at(&self, now: NaiveDateTime) -> MarketStatus {
+        let today = now.date();
+        let clock = now.time();
+        let sessions = self.sessions(today);
+
+        let current = sessions.iter().find(|s| s.start <= clock && clock < s.end);
+        let phase = current.map_or(SessionPhase::Closed, |s| s.phase);
+        let next_change = match current {
+            Some(session) => Some(today.and_time(session.end)),
+            None => sessions
+                .iter()
+                .find(|s| s.start > clock)
+                .map(|s| today.and_time(s.start))
+                .or_else(|| {
+                    let next = self.next_trading_day(today);
+                    self.sessions(next).first().map(|s| next.and_time(s.start))
+                }),
+        };
+
+        let label = match (phase, self.special_sessions.get(&today)) {
+            (SessionPhase::Normal, Some(special)) => special.name.clone(),
+            (SessionPhase::PreOpen, _) => "Pre-open".to_string(),
+            (SessionPhase::Normal, None) => "Market open".to_string(),
+            (SessionPhase::Closing, _) => "Closing session".to_string(),
+            (SessionPhase::Closed, _) => match self.holidays.get(&today) {
+                Some(name) if !is_weekend(today) => format!("Closed: {}", name),
+                _ => "Market closed".to_string(),
+            },
+        };
+
+        MarketStatus {
+            now,
+            phase,
+            label,
+            is_trading_day: self.is_trading_day(today),
+            next_change,
+            note: self.coverage_note(today.year()),
+        }
+    }
+}
+
+fn is_weekend(day: NaiveDate) -> bool {
+    matches!(day.weekday(), Weekday::Sat | Weekday::Sun)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn holidays_only_for_listed_years() {
+        let calendar = TradingCalendar::with_defaults();
+        let holidays = calendar.holidays_in_year(2025).unwrap();
+        assert!(holidays.iter().any(|h| h.name == "Holi"));
+        assert!(calendar.holidays_in_year(2031).is_none());
+        // Past what chrono can represent
+        assert!(calendar.holidays_in_year(300_000).is_none());
+        assert!(calendar.holidays_in_year(i32::MIN).is_none());
+    }
+
+    #[test]
+    fn trading_days_outside_the_list_are_flagged() {
+        let calendar = TradingCalendar::with_defaults();
+        let holi = calendar.trading_day(date(2025, 3, 14));
+        assert!(!holi.is_trading_day && holi.holidays_known);
+        assert_eq!(holi.nearest_trading_day, date(2025, 3, 13));
+
+        let guess = calendar.trading_day(date(2031, 3, 14));
+        assert!(!guess.holidays_known);
+        assert!(holi.note.is_none());
+        assert!(guess.note.unwrap().contains("2025, 2026 only"));
+        assert!(!calendar.is_trading_day(date(2031, 3, 15)));
+    }
+}
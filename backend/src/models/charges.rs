@@ -0,0 +1,302 @@
+use std::collections::HashMap;
+use std::path::Path;
+
+use serde::{Deserialize, Serialize};
+
+use crate::models::instrument::{ExchangeSegment, InstrumentKind};
+use crate::models::journal::Side;
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ChargeSegment {
+    EquityDelivery,
+    EquityIntraday,
+    Futures,
+    Options,
+    CurrencyFutures,
+    CurrencyOptions,
+    CommodityFutures,
+    CommodityOptions,
+}
+
+impl ChargeSegment {
+    /// Pick the charge schedule for an instrument. Equity is charged as
+    /// intraday only when the position is squared off the same day.
+    pub fn for_instrument(kind: InstrumentKind, segment: ExchangeSegment, intraday: bool) -> Self {
+        let option = matches!(kind, InstrumentKind::CallOption | InstrumentKind::PutOption);
+        match (segment, kind) {
+            (ExchangeSegment::NseCd, _) if option => ChargeSegment::CurrencyOptions,
+            (ExchangeSegment::NseCd, _) => ChargeSegment::CurrencyFutures,
+            (ExchangeSegment::McxFo, _) if option => ChargeSegment::CommodityOptions,
+            (ExchangeSegment::McxFo, _) => ChargeSegment::CommodityFutures,
+            _ if option => ChargeSegment::Options,
+            (_, InstrumentKind::Future) => ChargeSegment::Futures,
+            _ if intraday => ChargeSegment::EquityIntraday,
+            _ => ChargeSegment::EquityDelivery,
+        }
+    }
+}
+
+/// Rates for one segment. Percentages are of turnover, which is the
+/// premium for options.
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct SegmentRates {
+    /// Brokerage per order as a percent of turnover, capped at
+    /// `brokerage_flat`. `None` means a flat `brokerage_flat` per order.
+    pub brokerage_percent: Option<f64>,
+    pub brokerage_flat: f64,
+    /// STT, or CTT for commodities.
+    pub stt_buy_percent: f64,
+    pub stt_sell_percent: f64,
+    pub exchange_percent: f64,
+    pub stamp_buy_percent: f64,
+}
+
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct RateTable {
+    pub gst_percent: f64,
+    pub sebi_per_crore: f64,
+    pub segments: HashMap<ChargeSegment, SegmentRates>,
+}
+
+#[derive(Clone, Copy, Debug, Default, Serialize)]
+pub struct ChargeBreakdown {
+    pub turnover: f64,
+    pub brokerage: f64,
+    pub stt: f64,
+    pub exchange: f64,
+    pub sebi: f64,
+    pub stamp_duty: f64,
+    pub gst: f64,
+    pub total: f64,
+}
+
+impl ChargeBreakdown {
+    fn add(&mut self, other: &ChargeBreakdown) {
+        self.turnover += other.turnover;
+        self.brokerage += other.brokerage;
+        self.stt += other.stt;
+        self.exchange += other.exchange;
+        self.sebi += other.sebi;
+        self.stamp_duty += other.stamp_duty;
+        self.gst += other.gst;
+        self.total += other.total;
+    }
+}
+
+#[derive(Clone, Debug, Deserialize)]
+pub struct Order {
+    pub side: Side,
+    pub quantity: u32,
+    pub price: f64,
+}
+
+fn rates(
+    brokerage_percent: Option<f64>,
+    brokerage_flat: f64,
+    stt: (f64, f64),
+    exchange_percent: f64,
+    stamp_buy_percent: f64,
+) -> SegmentRates {
+    SegmentRates {
+        brokerage_percent,
+        brokerage_flat,
+        stt_buy_percent: stt.0,
+        stt_sell_percent: stt.1,
+        exchange_percent,
+        stamp_buy_percent,
+    }
+}
+
+/// Round to the paisa, as contract notes do.
+fn paisa(value: f64) -> f64 {
+    (value * 100.0).round() / 100.0
+}
+
+impl RateTable {
+    /// Discount broker schedule with NSE transaction charges, effective
+    /// October 2024.
+    pub fn with_defaults() -> Self {
+        let segments = HashMap::from([
+            (
+                ChargeSegment::EquityDelivery,
+                rates(None, 0.0, (0.1, 0.1), 0.00297, 0.015),
+            ),
+            (
+                ChargeSegment::EquityIntraday,
+                rates(Some(0.03), 20.0, (0.0, 0.025), 0.00297, 0.003),
+            ),
+            (
+                ChargeSegment::Futures,
+                rates(Some(0.03), 20.0, (0.0, 0.02), 0.00173, 0.002),
+            ),
+            (
+                ChargeSegment::Options,
+                rates(None, 20.0, (0.0, 0.1), 0.03503, 0.003),
+            ),
+            (
+                ChargeSegment::CurrencyFutures,
+                rates(Some(0.03), 20.0, (0.0, 0.0), 0.00035, 0.0001),
+            ),
+            (
+                ChargeSegment::CurrencyOptions,
+                rates(None, 20.0, (0.0, 0.0), 0.0311, 0.0001),
+            ),
+            (
+                ChargeSegment::CommodityFutures,
+                rates(Some(0.03), 20.0, (0.0, 0.01), 0.0021, 0.002),
+            ),
+            (
+                ChargeSegment::CommodityOptions,
+                rates(None, 20.0, (0.0, 0.05), 0.0418, 0.003),
+            ),
+        ]);
+
+        Self {
+            gst_percent: 18.0,
+            sebi_per_crore: 10.0,
+            segments,
+        }
+    }
+
+    /// Load a JSON rate table. Segments missing from the file keep their
+    /// default rates.
+    pub fn load(path: &Path) -> Result<Self, String> {
+        let text = std::fs::read_to_string(path)
+            .map_err(|e| format!("failed to read {}: {}", path.display(), e))?;
+        let loaded: RateTable = serde_json::from_str(&text)
+            .map_err(|e| format!("failed to parse {}: {}", path.display(), e))?;
+
+        let mut table = Self::with_defaults();
+        table.gst_percent = loaded.gst_percent;
+        table.sebi_per_crore = loaded.sebi_per_crore;
+        table.segments.extend(loaded.segments);
+        Ok(table)
+    }
+
+    /// Charges on a single order.
+    pub fn order(&self, segment: ChargeSegment, order: &Order) -> ChargeBreakdown {
+        let Some(rates) = self.segments.get(&segment) else {
+            return ChargeBreakdown::default();
+        };
+        let turnover = order.quantity as f64 * order.price;
+        let percent = |rate: f64| turnover * rate / 100.0;
+
+        let brokerage = match rates.brokerage_percent {
+            Some(rate) => percent(rate).min(rates.brokerage_flat),
+            None => rates.brokerage_flat,
+        };
+        let (stt, stamp_duty) = match order.side {
+            Side::Buy => (
+                percent(rates.stt_buy_percent),
+                percent(rates.stamp_buy_percent),
+            ),
+            Side::Sell => (percent(rates.stt_sell_percent), 0.0),
+        };
+        let exchange = percent(rates.exchange_percent);
+        let sebi = turnover * self.sebi_per_crore / 1e7;
+        let gst = (brokerage + exchange + sebi) * self.gst_percent / 100.0;
+
+        let mut breakdown = ChargeBreakdown {
+            turnover,
+            brokerage: paisa(brokerage),
+            stt: stt.round(),
+            exchange: paisa(exchange),
+            sebi: paisa(sebi),
+            stamp_duty: stamp_duty.round(),
+            gst: paisa(gst),
+            total: 0.0,
+        };
+        breakdown.total = paisa(
+            breakdown.brokerage
+                + breakdown.stt
+                + breakdown.exchange
+                + breakdown.sebi
+                + breakdown.stamp_duty
+                + breakdown.gst,
+        );
+        breakdown
+    }
+
+    /// Charges on each order plus their sum.
+    pub fn orders(
+        &self,
+        segment: ChargeSegment,
+        orders: &[Order],
+    ) -> (Vec<ChargeBreakdown>, ChargeBreakdown) {
+        let each: Vec<ChargeBreakdown> = orders.iter().map(|o| self.order(segment, o)).collect();
+        let mut total = ChargeBreakdown::default();
+        for breakdown in &each {
+            total.add(breakdown);
+        }
+        (each, total)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn order(side: Side) -> Order {
+        Order {
+            side,
+            quantity: 100,
+            price: 1000.0,
+        }
+    }
+
+    #[test]
+    fn intraday_round_trip_matches_the_contract_note() {
+        let table = RateTable::with_defaults();
+        let (each, total) = table.orders(
+            ChargeSegment::EquityIntraday,
+            &[order(Side::Buy), order(Side::Sell)],
+        );
+
+        // Brokerage hits the ₹20 cap; STT is on the sell side only and
+        // stamp duty on the buy side only
+        assert_eq!(each[0].brokerage, 20.0);
+        assert_eq!((each[0].stt, each[0].stamp_duty), (0.0, 3.0));
+        assert_eq!((each[1].stt, each[1].stamp_duty), (25.0, 0.0));
+        assert_eq!(each[0].gst, 4.15);
+        assert_eq!(each[0].total, 30.22);
+        assert_eq!(each[1].total, 52.22);
+        assert_eq!(total.turnover, 200_000.0);
+        assert!((total.total - 82.44).abs() < 1e-9);
+    }
+
+    #[test]
+    fn delivery_has_no_brokerage_and_stt_both_ways() {
+        let charges =
+            RateTable::with_defaults().order(ChargeSegment::EquityDelivery, &order(Side::Buy));
+
+        assert_eq!(charges.brokerage, 0.0);
+        assert_eq!((charges.stt, charges.stamp_duty), (100.0, 15.0));
+        assert_eq!(charges.total, 118.62);
+    }
+
+    #[test]
+    fn segments_follow_the_instrument() {
+        let pick = ChargeSegment::for_instrument;
+        assert_eq!(
+            pick(InstrumentKind::Equity, ExchangeSegment::NseEq, false),
+            ChargeSegment::EquityDelivery
+        );
+        assert_eq!(
+            pick(InstrumentKind::Equity, ExchangeSegment::BseEq, true),
+            ChargeSegment::EquityIntraday
+        );
+        assert_eq!(
+            pick(InstrumentKind::PutOption, ExchangeSegment::NseFo, true),
+            ChargeSegment::Options
+        );
+        assert_eq!(
+            pick(InstrumentKind::Future, ExchangeSegment::McxFo, false),
+            ChargeSegment::CommodityFutures
+        );
+        assert_eq!(
+            pick(InstrumentKind::CallOption, ExchangeSegment::NseCd, false),
+            ChargeSegment::CurrencyOptions
+        );
+    }
+}
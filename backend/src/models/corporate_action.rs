@@ -0,0 +1,396 @@
+use std::collections::HashMap;
+use std::path::{Path, PathBuf};
+
+use chrono::NaiveDate;
+use serde::{Deserialize, Serialize};
+
+use crate::models::market::Bar;
+use crate::utils::csv;
+use crate::utils::persist;
+
+#[derive(Clone, Debug, Serialize, Deserialize)]
+#[serde(tag = "type", rename_all = "snake_case")]
+pub enum ActionKind {
+    /// Face value change, e.g. 10 -> 2 is a 1:5 split.
+    Split {
+        old_face_value: f64,
+        new_face_value: f64,
+    },
+    /// `new_shares` bonus shares for every `held_shares` held.
+    Bonus {
+        new_shares: u32,
+        held_shares: u32,
+    },
+    Dividend {
+        amount: f64,
+    },
+    /// `new_shares` rights for every `held_shares` held, issued at `price`.
+    Rights {
+        new_shares: u32,
+        held_shares: u32,
+        price: f64,
+    },
+}
+
+impl ActionKind {
+    pub fn describe(&self) -> String {
+        match self {
+            ActionKind::Split {
+                old_face_value,
+                new_face_value,
+            } => format!("Split FV {} → {}", old_face_value, new_face_value),
+            ActionKind::Bonus {
+                new_shares,
+                held_shares,
+            } => format!("Bonus {}:{}", new_shares, held_shares),
+            ActionKind::Dividend { amount } => format!("Dividend ₹{:.2}", amount),
+            ActionKind::Rights {
+                new_shares,
+                held_shares,
+                price,
+            } => format!("Rights {}:{} @ ₹{:.2}", new_shares, held_shares, price),
+        }
+    }
+
+    /// Reject actions whose adjustment factor would be zero, infinite or
+    /// NaN.
+    pub fn validate(&self) -> Result<(), String> {
+        let positive = |value: f64| value.is_finite() && value > 0.0;
+        match *self {
+            ActionKind::Split {
+                old_face_value,
+                new_face_value,
+            } if !positive(old_face_value) || !positive(new_face_value) => {
+                Err("face values must be positive".to_string())
+            }
+            ActionKind::Bonus {
+                new_shares,
+                held_shares,
+            }
+            | ActionKind::Rights {
+                new_shares,
+                held_shares,
+                ..
+            } if !valid_ratio(new_shares, held_shares) => {
+                Err("both sides of the ratio must be positive".to_string())
+            }
+            ActionKind::Rights { price, .. } if !positive(price) => {
+                Err("rights price must be positive".to_string())
+            }
+            ActionKind::Dividend { amount } if !positive(amount) => {
+                Err("dividend amount must be positive".to_string())
+            }
+            _ => Ok(()),
+        }
+    }
+}
+
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct CorporateAction {
+    pub symbol: String,
+    pub ex_date: NaiveDate,
+    #[serde(flatten)]
+    pub kind: ActionKind,
+}
+
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum Adjustment {
+    /// Prices as traded.
+    #[default]
+    Raw,
+    /// Back-adjusted for splits, bonuses and rights.
+    Capital,
+    /// Capital adjustments plus dividends, for total-return series.
+    Total,
+}
+
+#[derive(Clone, Debug, Serialize)]
+pub struct AdjustmentFactor {
+    pub ex_date: NaiveDate,
+    pub action: String,
+    /// Multiplier applied to prices before the ex-date for this action alone.
+    pub price_factor: f64,
+    /// Divisor applied to volumes before the ex-date; 1 for dividends,
+    /// which don't change the share count.
+    pub volume_factor: f64,
+    /// Product of this and every later factor, i.e. what the earliest
+    /// bars before this ex-date are multiplied by.
+    pub cumulative: f64,
+}
+
+#[derive(Clone, Debug, Serialize)]
+pub struct AdjustedSeries {
+    pub adjustment: String,
+    pub bars: Vec<Bar>,
+    pub factors: Vec<AdjustmentFactor>,
+}
+
+/// Corporate actions from the exchange file, plus the ones added through
+/// the API, which are persisted as JSON.
+#[derive(Default)]
+pub struct CorporateActionStore {
+    by_symbol: HashMap<String, Vec<CorporateAction>>,
+    path: Option<PathBuf>,
+    added: Vec<CorporateAction>,
+}
+
+impl CorporateActionStore {
+    /// Load `symbol,ex_date,action,old_face_value,new_face_value,ratio,amount,price`
+    /// rows, where `ratio` is `new:held` for bonuses and rights.
+    pub fn load(path: &Path) -> Result<Self, String> {
+        let text = std::fs::read_to_string(path)
+            .map_err(|e| format!("failed to read {}: {}", path.display(), e))?;
+        let mut store = Self::default();
+        let table = csv::Table::parse(&text);
+        let symbol_col = table.column(&["symbol"]);
+        let date_col = table.column(&["ex_date", "date"]);
+        let action_col = table.column(&["action", "type"]);
+        let old_fv_col = table.column(&["old_face_value"]);
+        let new_fv_col = table.column(&["new_face_value"]);
+        let ratio_col = table.column(&["ratio"]);
+        let amount_col = table.column(&["amount"]);
+        let price_col = table.column(&["price"]);
+
+        for (line, row) in table.rows.iter().enumerate() {
+            let number = |col| table.get(row, col).and_then(|v| v.parse::<f64>().ok());
+            let ratio = table.get(row, ratio_col).and_then(parse_ratio);
+            let kind = match table.get(row, action_col).map(str::to_lowercase).as_deref() {
+                Some("split") => number(old_fv_col).zip(number(new_fv_col)).map(
+                    |(old_face_value, new_face_value)| ActionKind::Split {
+                        old_face_value,
+                        new_face_value,
+                    },
+                ),
+                Some("bonus") => ratio.map(|(new_shares, held_shares)| ActionKind::Bonus {
+                    new_shares,
+                    held_shares,
+                }),
+                Some("dividend") => {
+                    number(amount_col).map(|amount| ActionKind::Dividend { amount })
+                }
+                Some("rights") => {
+                    ratio
+                        .zip(number(price_col))
+                        .map(|((new_shares, held_shares), price)| ActionKind::Rights {
+                            new_shares,
+                            held_shares,
+                            price,
+                        })
+                }
+                _ => None,
+            };
+            let symbol = table.get(row, symbol_col);
+            let ex_date = table
+                .get(row, date_col)
+                .and_then(|v| NaiveDate::parse_from_str(v, "%Y-%m-%d").ok());
+
+            match (symbol, ex_date, kind.filter(|k| k.validate().is_ok())) {
+                (Some(symbol), Some(ex_date), Some(kind)) => store.add(CorporateAction {
+                    symbol: symbol.to_string(),
+                    ex_date,
+                    kind,
+                }),
+                _ => log::warn!("Skipping corporate action on line {}", line + 2),
+            }
+        }
+
+        Ok(store)
+    }
+
+    /// Load the actions added through the API from `path`, which they are
+    /// saved to from then on.
+    pub fn load_added(&mut self, path: &Path) {
+        let added: Vec<CorporateAction> = persist::load_json(path);
+        for action in &added {
+            self.add(action.clone());
+        }
+        self.path = Some(path.to_path_buf());
+        self.added = added;
+    }
+
+    /// Add and persist an action entered by hand.
+    pub fn record(&mut self, action: CorporateAction) -> Result<(), String> {
+        self.added.push(action.clone());
+        if let Some(path) = &self.path {
+            if let Err(e) = persist::save_json(path, &self.added) {
+                self.added.pop();
+                return Err(e);
+            }
+        }
+        self.add(action);
+        Ok(())
+    }
+
+    fn add(&mut self, action: CorporateAction) {
+        let actions = self.by_symbol.entry(action.symbol.clone()).or_default();
+        actions.push(action);
+        actions.sort_by_key(|a| a.ex_date);
+    }
+
+    pub fn for_symbol(&self, symbol: &str) -> &[CorporateAction] {
+        self.by_symbol.get(symbol).map_or(&[], Vec::as_slice)
+    }
+}
+
+fn valid_ratio(new: u32, held: u32) -> bool {
+    new > 0 && held > 0
+}
+
+fn parse_ratio(value: &str) -> Option<(u32, u32)> {
+    let (new, held) = value.split_once(':')?;
+    let new = new.trim().parse().ok()?;
+    let held = held.trim().parse().ok()?;
+    valid_ratio(new, held).then_some((new, held))
+}
+
+/// Back-adjust `bars` (oldest first) so prices before each ex-date are
+/// comparable with prices after it. Volumes are scaled inversely by the
+/// share-count changes so traded value is preserved.
+pub fn adjust(bars: &[Bar], actions: &[CorporateAction], adjustment: Adjustment) -> AdjustedSeries {
+    let mut factors: Vec<AdjustmentFactor> = Vec::new();
+
+    if adjustment != Adjustment::Raw {
+        for action in actions {
+            // Dividend and rights factors depend on the last close before the ex-date
+            let prev_close = bars
+                .iter()
+                .rev()
+                .find(|bar| bar.date() < action.ex_date)
+                .map(|bar| bar.close);
+
+            let price_factor = match (&action.kind, prev_close) {
+                (
+                    ActionKind::Split {
+                        old_face_value,
+                        new_face_value,
+                    },
+                    _,
+                ) if *old_face_value > 0.0 => new_face_value / old_face_value,
+                (
+                    ActionKind::Bonus {
+                        new_shares,
+                        held_shares,
+                    },
+                    _,
+                ) => *held_shares as f64 / (*held_shares + *new_shares) as f64,
+                (
+                    ActionKind::Rights {
+                        new_shares,
+                        held_shares,
+                        price,
+                    },
+                    Some(close),
+                ) if close > 0.0 => {
+                    let (new, held) = (*new_shares as f64, *held_shares as f64);
+                    let theoretical = (held * close + new * price) / (held + new);
+                    theoretical / close
+                }
+                (ActionKind::Dividend { amount }, Some(close))
+                    if adjustment == Adjustment::Total && close > *amount =>
+                {
+                    (close - amount) / close
+                }
+                _ => continue,
+            };
+
+            let volume_factor = match action.kind {
+                ActionKind::Dividend { .. } => 1.0,
+                _ => price_factor,
+            };
+
+            factors.push(AdjustmentFactor {
+                ex_date: action.ex_date,
+                action: action.kind.describe(),
+                price_factor,
+                volume_factor,
+                cumulative: price_factor,
+            });
+        }
+
+        // Accumulate from the most recent action backwards
+        for idx in (0..factors.len().saturating_sub(1)).rev() {
+            factors[idx].cumulative = factors[idx].price_factor * factors[idx + 1].cumulative;
+        }
+    }
+
+    let adjusted = bars
+        .iter()
+        .map(|bar| {
+            let (price, share) = factors
+                .iter()
+                .filter(|factor| bar.date() < factor.ex_date)
+                .fold((1.0, 1.0), |(price, share), factor| {
+                    (price * factor.price_factor, share * factor.volume_factor)
+                });
+
+            Bar {
+                time: bar.time,
+                open: bar.open * price,
+                high: bar.high * price,
+                low: bar.low * price,
+                close: bar.close * price,
+                volume: (bar.volume as f64 / share).round() as u64,
+            }
+        })
+        .collect();
+
+    AdjustedSeries {
+        adjustment: format!("{:?}", adjustment).to_lowercase(),
+        bars: adjusted,
+        factors,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn rejects_degenerate_actions() {
+        let split = |old_face_value, new_face_value| ActionKind::Split {
+            old_face_value,
+            new_face_value,
+        };
+        assert!(split(10.0, 2.0).validate().is_ok());
+        assert!(split(10.0, 0.0).validate().is_err());
+        assert!(split(f64::NAN, 2.0).validate().is_err());
+        assert!(ActionKind::Bonus {
+            new_shares: 0,
+            held_shares: 1
+        }
+        .validate()
+        .is_err());
+        assert!(ActionKind::Rights {
+            new_shares: 1,
+            held_shares: 5,
+            price: f64::INFINITY
+        }
+        .validate()
+        .is_err());
+        assert!(ActionKind::Dividend { amount: -1.0 }.validate().is_err());
+        assert_eq!(parse_ratio("1:0"), None);
+    }
+
+    #[test]
+    fn recorded_actions_survive_a_reload() {
+        let dir =
+            std::env::temp_dir().join(format!("slynqix-corporate-actions-{}", std::process::id()));
+        let _ = std::fs::remove_dir_all(&dir);
+        let path = dir.join("corporate_actions.json");
+        let mut store = CorporateActionStore::default();
+        store.load_added(&path);
+        store
+            .record(CorporateAction {
+                symbol: "INFY".to_string(),
+                ex_date: NaiveDate::from_ymd_opt(2024, 6, 3).unwrap(),
+                kind: ActionKind::Dividend { amount: 8.0 },
+            })
+            .unwrap();
+
+        let mut reloaded = CorporateActionStore::default();
+        reloaded.load_added(&path);
+        assert_eq!(reloaded.for_symbol("INFY").len(), 1);
+        std::fs::remove_dir_all(&dir).unwrap();
+    }
+}
@@ -0,0 +1,348 @@
+use std::collections::{BTreeSet, HashMap};
+use std::path::{Path, PathBuf};
+
+use chrono::{NaiveDate, NaiveDateTime};
+use serde::{Deserialize, Serialize};
+
+use crate::models::calendar::TradingCalendar;
+use crate::models::corporate_action::CorporateActionStore;
+use crate::models::market::{Bar, MarketStore};
+use crate::utils::{indicators, persist};
+
+/// Close-to-close moves below this are never flagged, so quiet symbols
+/// don't raise spikes on ordinary news days.
+const SPIKE_FLOOR: f64 = 0.20;
+/// Moves above this many standard deviations of daily returns are flagged.
+const SPIKE_SIGMAS: f64 = 6.0;
+/// Symbols whose last bar is more than this many trading days old are stale.
+const STALE_AFTER_DAYS: usize = 3;
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum IssueKind {
+    MissingBar,
+    NonPositivePrice,
+    OhlcInconsistent,
+    OutlierSpike,
+    StaleSymbol,
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum IssueStatus {
+    Open,
+    Resolved,
+    Ignored,
+}
+
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct Issue {
+    pub id: u64,
+    pub symbol: String,
+    pub date: NaiveDate,
+    pub kind: IssueKind,
+    pub detail: String,
+    pub status: IssueStatus,
+    pub detected_at: NaiveDateTime,
+}
+
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct ScanSummary {
+    pub ran_at: NaiveDateTime,
+    pub symbols: usize,
+    pub open_issues: usize,
+    pub new_issues: usize,
+}
+
+#[derive(Clone, Default, Serialize, Deserialize)]
+struct DataQualityFile {
+    next_id: u64,
+    issues: Vec<Issue>,
+    last_scan: Option<ScanSummary>,
+}
+
+/// Issues found by the scanner, persisted as JSON after every scan and
+/// status change. Re-scans keep the status of issues that were already
+/// recorded, so ignored issues stay ignored and issues that no longer
+/// reproduce are marked resolved.
+pub struct DataQualityStore {
+    path: PathBuf,
+    data: DataQualityFile,
+}
+
+impl DataQualityStore {
+    pub fn load(path: &Path) -> Self {
+        let data = persist::load_json(path);
+
+        Self {
+            path: path.to_path_buf(),
+            data,
+        }
+    }
+
+    /// Save, or put the data back as it was `before` when that fails.
+    fn commit(&mut self, before: DataQualityFile) -> Result<(), String> {
+        persist::save_json(&self.path, &self.data).inspect_err(|_| self.data = before)
+    }
+
+    pub fn issues(&self, status: Option<IssueStatus>) -> Vec<Issue> {
+        self.data
+            .issues
+            .iter()
+            .filter(|issue| status.is_none_or(|s| issue.status == s))
+            .cloned()
+            .collect()
+    }
+
+    pub fn get(&self, id: u64) -> Option<&Issue> {
+        self.data.issues.iter().find(|issue| issue.id == id)
+    }
+
+    pub fn set_status(&mut self, id: u64, status: IssueStatus) -> Result<Option<Issue>, String> {
+        let before = self.data.clone();
+        let Some(issue) = self.data.issues.iter_mut().find(|issue| issue.id == id) else {
+            return Ok(None);
+        };
+        issue.status = status;
+        let issue = issue.clone();
+        self.commit(before)?;
+        Ok(Some(issue))
+    }
+
+    pub fn last_scan(&self) -> Option<&ScanSummary> {
+        self.data.last_scan.as_ref()
+    }
+
+    pub fn record_scan(
+        &mut self,
+        found: Vec<(String, NaiveDate, IssueKind, String)>,
+        symbols: usize,
+        now: NaiveDateTime,
+    ) -> Result<ScanSummary, String> {
+        let before = self.data.clone();
+        let mut found: HashMap<(String, NaiveDate, IssueKind), String> = found
+            .into_iter()
+            .map(|(symbol, date, kind, detail)| ((symbol, date, kind), detail))
+            .collect();
+
+        for issue in &mut self.data.issues {
+            let key = (issue.symbol.clone(), issue.date, issue.kind);
+            match found.remove(&key) {
+                Some(detail) => {
+                    issue.detail = detail;
+                    if issue.status == IssueStatus::Resolved {
+                        issue.status = IssueStatus::Open;
+                    }
+                }
+                None if issue.status == IssueStatus::Open => issue.status = IssueStatus::Resolved,
+                None => {}
+            }
+        }
+
+        let new_issues = found.len();
+        let mut fresh: Vec<_> = found.into_iter().collect();
+        fresh.sort_by(|a, b| (&a.0 .0, a.0 .1).cmp(&(&b.0 .0, b.0 .1)));
+        for ((symbol, date, kind), detail) in fresh {
+            self.data.next_id += 1;
+            self.data.issues.push(Issue {
+                id: self.data.next_id,
+                symbol,
+                date,
+                kind,
+                detail,
+                status: IssueStatus::Open,
+                detected_at: now,
+            });
+        }
+
+        let summary = ScanSummary {
+            ran_at: now,
+            symbols,
+            open_issues: self
+                .data
+                .issues
+                .iter()
+                .filter(|issue| issue.status == IssueStatus::Open)
+                .count(),
+            new_issues,
+        };
+        self.data.last_scan = Some(summary.clone());
+        self.commit(before)?;
+        Ok(summary)
+    }
+}
+
+/// Check every symbol's daily history against the trading calendar and
+/// basic price sanity rules.
+pub fn scan(
+    market: &MarketStore,
+    calendar: &TradingCalendar,
+    actions: &CorporateActionStore,
+    today: NaiveDate,
+) -> Vec<(String, NaiveDate, IssueKind, String)> {
+    let mut found = Vec::new();
+
+    for symbol in market.daily_symbols() {
+        let bars = market.daily_bars(&symbol);
+        let (Some(first), Some(last)) = (bars.first(), bars.last()) else {
+            continue;
+        };
+        let mut report = |date: NaiveDate, kind: IssueKind, detail: String| {
+            found.push((symbol.clone(), date, kind, detail));
+        };
+
+        let have: BTreeSet<NaiveDate> = bars.iter().map(Bar::date).collect();
+        for day in calendar.trading_days_between(first.date(), last.date()) {
+            if !have.contains(&day) {
+                report(
+                    day,
+                    IssueKind::MissingBar,
+                    "No bar for a trading day".to_string(),
+                );
+            }
+        }
+
+        for bar in &bars {
+            if [bar.open, bar.high, bar.low, bar.close]
+                .iter()
+                .any(|p| *p <= 0.0)
+            {
+                report(
+                    bar.date(),
+                    IssueKind::NonPositivePrice,
+                    format!(
+                        "O {:.2} H {:.2} L {:.2} C {:.2}",
+                        bar.open, bar.high, bar.low, bar.close
+                    ),
+                );
+            } else if bar.high < bar.low
+                || bar.high < bar.open.max(bar.close)
+                || bar.low > bar.open.min(bar.close)
+            {
+                report(
+                    bar.date(),
+                    IssueKind::OhlcInconsistent,
+                    format!(
+                        "High {:.2} / Low {:.2} don't contain Open {:.2} / Close {:.2}",
+                        bar.high, bar.low, bar.open, bar.close
+                    ),
+                );
+            }
+        }
+
+        // Corporate actions legitimately gap the raw price on the ex-date
+        let ex_dates: BTreeSet<NaiveDate> = actions
+            .for_symbol(&symbol)
+            .iter()
+            .map(|a| a.ex_date)
+            .collect();
+        let returns: Vec<f64> = bars
+            .windows(2)
+            .filter(|w| w[0].close > 0.0 && w[1].close > 0.0)
+            .map(|w| w[1].close / w[0].close - 1.0)
+            .collect();
+        let threshold = indicators::std_dev(&returns)
+            .map_or(SPIKE_FLOOR, |sd| (sd * SPIKE_SIGMAS).max(SPIKE_FLOOR));
+        for pair in bars.windows(2) {
+            let (prev, bar) = (&pair[0], &pair[1]);
+            if prev.close <= 0.0 || bar.close <= 0.0 || ex_dates.contains(&bar.date()) {
+                continue;
+            }
+            let change = bar.close / prev.close - 1.0;
+            if change.abs() > threshold {
+                report(
+                    bar.date(),
+                    IssueKind::OutlierSpike,
+                    format!(
+                        "Close moved {:+.1}% from {:.2} to {:.2}",
+                        change * 100.0,
+                        prev.close,
+                        bar.close
+                    ),
+                );
+            }
+        }
+
+        let expected = calendar.last_trading_day_on_or_before(today);
+        let missed = calendar
+            .trading_days_between(last.date(), expected)
+            .len()
+            .saturating_sub(1);
+        if missed > STALE_AFTER_DAYS {
+            report(
+                last.date(),
+                IssueKind::StaleSymbol,
+                format!("Last bar is {} trading days old", missed),
+            );
+        }
+    }
+
+    found
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn temp_dir(name: &str) -> PathBuf {
+        let dir = std::env::temp_dir().join(format!("slynqix-{}-{}", name, std::process::id()));
+        let _ = std::fs::remove_dir_all(&dir);
+        std::fs::create_dir_all(&dir).unwrap();
+        dir
+    }
+
+    fn found(day: u32) -> Vec<(String, NaiveDate, IssueKind, String)> {
+        vec![(
+            "INFY".to_string(),
+            NaiveDate::from_ymd_opt(2025, 1, day).unwrap(),
+            IssueKind::MissingBar,
+            "no bar".to_string(),
+        )]
+    }
+
+    fn now() -> NaiveDateTime {
+        NaiveDate::from_ymd_opt(2025, 1, 10)
+            .unwrap()
+            .and_hms_opt(18, 0, 0)
+            .unwrap()
+    }
+
+    #[test]
+    fn issues_and_their_status_survive_a_reload() {
+        let dir = temp_dir("dq-reload");
+        let path = dir.join("data_quality.json");
+        let mut store = DataQualityStore::load(&path);
+        store.record_scan(found(2), 1, now()).unwrap();
+        let id = store.issues(None)[0].id;
+        store.set_status(id, IssueStatus::Ignored).unwrap().unwrap();
+
+        let mut store = DataQualityStore::load(&path);
+        assert_eq!(store.get(id).unwrap().status, IssueStatus::Ignored);
+        assert_eq!(store.last_scan().unwrap().new_issues, 1);
+        let summary = store.record_scan(found(2), 1, now()).unwrap();
+        assert_eq!((summary.open_issues, summary.new_issues), (0, 0));
+        assert_eq!(store.record_scan(found(3), 1, now()).unwrap().new_issues, 1);
+        assert_eq!(store.issues(None).last().unwrap().id, id + 1);
+        std::fs::remove_dir_all(&dir).unwrap();
+    }
+
+    #[test]
+    fn failed_saves_leave_the_store_unchanged() {
+        let dir = temp_dir("dq-failed-save");
+        let mut store = DataQualityStore::load(&dir.join("data_quality.json"));
+        store.record_scan(found(2), 1, now()).unwrap();
+        let id = store.issues(None)[0].id;
+        // A path under a file can't be written.
+        store.path = dir.join("data_quality.json").join("data_quality.json");
+
+        assert!(store.set_status(id, IssueStatus::Ignored).is_err());
+        assert_eq!(store.get(id).unwrap().status, IssueStatus::Open);
+        assert!(store.record_scan(found(3), 1, now()).is_err());
+        assert_eq!(store.issues(None).len(), 1);
+        assert!(store
+            .set_status(id + 9, IssueStatus::Ignored)
+            .unwrap()
+            .is_none());
+        std::fs::remove_dir_all(&dir).unwrap();
+    }
+}
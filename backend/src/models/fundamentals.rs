@@ -0,0 +1,658 @@
+use std::collections::BTreeMap;
+use std::path::{Path, PathBuf};
+
+use chrono::{Datelike, Months, NaiveDate};
+use serde::{Deserialize, Serialize};
+
+use crate::models::scoring::FactorWeights;
+use crate::utils::csv;
+use crate::utils::persist;
+
+/// Figures for one reporting period. Amounts are in ₹ crore, per-share
+/// values in ₹. Anything the source file didn't have is left empty.
+#[derive(Clone, Debug, Default, PartialEq, Serialize, Deserialize)]
+pub struct FinancialPeriod {
+    pub period_end: NaiveDate,
+    // Profit and loss
+    pub revenue: Option<f64>,
+    pub operating_expenses: Option<f64>,
+    pub operating_profit: Option<f64>,
+    pub other_income: Option<f64>,
+    pub depreciation: Option<f64>,
+    pub interest: Option<f64>,
+    pub profit_before_tax: Option<f64>,
+    pub tax: Option<f64>,
+    pub net_profit: Option<f64>,
+    pub eps: Option<f64>,
+    pub dividend_per_share: Option<f64>,
+    // Balance sheet
+    pub share_capital: Option<f64>,
+    pub reserves: Option<f64>,
+    pub borrowings: Option<f64>,
+    pub other_liabilities: Option<f64>,
+    pub total_assets: Option<f64>,
+    pub fixed_assets: Option<f64>,
+    pub investments: Option<f64>,
+    pub cash: Option<f64>,
+    // Cash flow
+    pub operating_cash_flow: Option<f64>,
+    pub investing_cash_flow: Option<f64>,
+    pub financing_cash_flow: Option<f64>,
+    pub capex: Option<f64>,
+}
+
+impl FinancialPeriod {
+    fn new(period_end: NaiveDate) -> Self {
+        Self {
+            period_end,
+            ..Default::default()
+        }
+    }
+
+    /// The field for a file column. Names are matched after lowercasing
+    /// and turning spaces into underscores; common aliases are accepted.
+    fn field_mut(&mut self, column: &str) -> Option<&mut Option<f64>> {
+        Some(match column {
+            "revenue" | "sales" | "revenue_from_operations" | "total_revenue" => &mut self.revenue,
+            "operating_expenses" | "expenses" | "total_expenses" => &mut self.operating_expenses,
+            "operating_profit" | "ebitda" => &mut self.operating_profit,
+            "other_income" => &mut self.other_income,
+            "depreciation" => &mut self.depreciation,
+            "interest" | "finance_costs" => &mut self.interest,
+            "profit_before_tax" | "pbt" => &mut self.profit_before_tax,
+            "tax" => &mut self.tax,
+            "net_profit" | "pat" | "profit_after_tax" => &mut self.net_profit,
+            "eps" => &mut self.eps,
+            "dividend_per_share" | "dps" => &mut self.dividend_per_share,
+            "share_capital" | "equity_capital" => &mut self.share_capital,
+            "reserves" => &mut self.reserves,
+            "borrowings" | "debt" | "total_debt" => &mut self.borrowings,
+            "other_liabilities" => &mut self.other_liabilities,
+            "total_assets" => &mut self.total_assets,
+            "fixed_assets" | "net_block" => &mut self.fixed_assets,
+            "investments" => &mut self.investments,
+            "cash" | "cash_and_equivalents" => &mut self.cash,
+            "operating_cash_flow" | "cash_from_operations" => &mut self.operating_cash_flow,
+            "investing_cash_flow" | "cash_from_investing" => &mut self.investing_cash_flow,
+            "financing_cash_flow" | "cash_from_financing" => &mut self.financing_cash_flow,
+            "capex" => &mut self.capex,
+            _ => return None,
+        })
+    }
+
+    /// Shareholders' funds: share capital plus reserves.
+    pub fn equity(&self) -> Option<f64> {
+        Some(self.share_capital? + self.reserves?)
+    }
+}
+
+/// Holding pattern at a quarter end, in percent of shares.
+#[derive(Clone, Debug, Default, PartialEq, Serialize, Deserialize)]
+pub struct Shareholding {
+    pub period_end: NaiveDate,
+    pub promoters: Option<f64>,
+    pub fii: Option<f64>,
+    pub dii: Option<f64>,
+    pub government: Option<f64>,
+    pub public: Option<f64>,
+    /// Promoter shares pledged, in percent of the promoter holding.
+    pub pledged: Option<f64>,
+}
+
+impl Shareholding {
+    fn field_mut(&mut self, column: &str) -> Option<&mut Option<f64>> {
+        Some(match column {
+            "promoters" | "promoter" => &mut self.promoters,
+            "fii" | "fiis" | "fpi" => &mut self.fii,
+            "dii" | "diis" => &mut self.dii,
+            "government" => &mut self.government,
+            "public" => &mut self.public,
+            "pledged" | "pledge" => &mut self.pledged,
+            _ => return None,
+        })
+    }
+}
+
+#[derive(Clone, Debug, Default, Serialize, Deserialize)]
+pub struct CompanyFundamentals {
+    pub symbol: String,
+    #[serde(default)]
+    pub name: String,
+    #[serde(default)]
+    pub sector: String,
+    #[serde(default)]
+    pub industry: String,
+    /// Shares outstanding, in crore.
+    #[serde(default)]
+    pub shares_outstanding: Option<f64>,
+    #[serde(default)]
+    pub face_value: Option<f64>,
+    /// Oldest first.
+    #[serde(default)]
+    pub quarterly: Vec<FinancialPeriod>,
+    /// Oldest first.
+    #[serde(default)]
+    pub annual: Vec<FinancialPeriod>,
+    /// Oldest first.
+    #[serde(default)]
+    pub shareholding: Vec<Shareholding>,
+}
+
+impl CompanyFundamentals {
+    fn new(symbol: &str) -> Self {
+        Self {
+            symbol: symbol.to_string(),
+            ..Default::default()
+        }
+    }
+
+    fn sort(&mut self) {
+        self.quarterly.sort_by_key(|p| p.period_end);
+        self.annual.sort_by_key(|p| p.period_end);
+        self.shareholding.sort_by_key(|s| s.period_end);
+    }
+}
+
+/// Which file is being imported.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ImportKind {
+    /// symbol, name, sector, industry, shares_outstanding, face_value
+    Profile,
+    /// symbol, period_end and any statement columns
+    Quarterly,
+    Annual,
+    /// symbol, period_end, promoters, fii, dii, government, public, pledged
+    Shareholding,
+}
+
+#[derive(Clone, Debug, Default, Serialize)]
+pub struct ImportSummary {
+    pub symbols: usize,
+    pub rows: usize,
+    /// Columns in the file that match no field, so were not imported.
+    pub ignored_columns: Vec<String>,
+    pub errors: Vec<String>,
+}
+
+/// Symbols are stored trimmed and uppercase.
+pub fn normalize(symbol: &str) -> String {
+    symbol.trim().to_uppercase()
+}
+
+fn column_key(header: &str) -> String {
+    header
+        .trim()
+        .to_lowercase()
+        .replace(['/', '-'], " ")
+        .split_whitespace()
+        .collect::<Vec<_>>()
+        .join("_")
+}
+
+/// Numbers as exported by data vendors: thousands separators, percent
+/// signs and brackets for negatives are all accepted.
+fn parse_number(value: &str) -> Option<f64> {
+    let cleaned: String = value
+        .chars()
+        .filter(|c| !matches!(c, ',' | '%' | '₹' | ' '))
+        .collect();
+    match cleaned.strip_prefix('(').and_then(|v| v.strip_suffix(')')) {
+        Some(inner) => inner.parse::<f64>().ok().map(|v| -v),
+        None => cleaned.parse().ok(),
+    }
+    .filter(|v: &f64| v.is_finite())
+}
+
+const DATE_FORMATS: [&str; 4] = ["%Y-%m-%d", "%d-%m-%Y", "%d/%m/%Y", "%d-%b-%Y"];
+
+/// Period ends are full dates, or a month such as "Mar 2024" or "Mar-24"
+/// meaning its last day.
+fn parse_period_end(value: &str) -> Option<NaiveDate> {
+    if let Some(date) = DATE_FORMATS
+        .iter()
+        .find_map(|f| NaiveDate::parse_from_str(value, f).ok())
+    {
+        return Some(date);
+    }
+    let normalized = value.replace(['-', '\''], " ");
+    let (month, year) = normalized.split_once(' ')?;
+    let year: i32 = match year.trim().parse::<i32>().ok()? {
+        y if y < 100 => 2000 + y,
+        y => y,
+    };
+    let month: String = month.chars().take(3).collect();
+    let first = NaiveDate::parse_from_str(&format!("1 {} {}", month, year), "%d %b %Y").ok()?;
+    (first + Months::new(1)).pred_opt()
+}
+
+fn upsert_period(
+    periods: &mut Vec<FinancialPeriod>,
+    period_end: NaiveDate,
+) -> &mut FinancialPeriod {
+    match periods.iter().position(|p| p.period_end == period_end) {
+        Some(index) => &mut periods[index],
+        None => {
+            periods.push(FinancialPeriod::new(period_end));
+            periods.last_mut().unwrap()
+        }
+    }
+}
+
+fn upsert_holding(holdings: &mut Vec<Shareholding>, period_end: NaiveDate) -> &mut Shareholding {
+    match holdings.iter().position(|s| s.period_end == period_end) {
+        Some(index) => &mut holdings[index],
+        None => {
+            holdings.push(Shareholding {
+                period_end,
+                ..Default::default()
+            });
+            holdings.last_mut().unwrap()
+        }
+    }
+}
+
+#[derive(Serialize, Deserialize, Default)]
+struct FundamentalsFile {
+    companies: BTreeMap<String, CompanyFundamentals>,
+    #[serde(default)]
+    score_weights: FactorWeights,
+}
+
+/// Company fundamentals by symbol, persisted as JSON after every change.
+pub struct FundamentalsStore {
+    path: PathBuf,
+    data: FundamentalsFile,
+}
+
+impl FundamentalsStore {
+    pub fn load(path: &Path) -> Self {
+        let data = persist::load_json(path);
+
+        Self {
+            path: path.to_path_buf(),
+            data,
+        }
+    }
+
+    fn save(&self) -> Result<(), String> {
+        persist::save_json_compact(&self.path, &self.data)
+    }
+
+    pub fn companies(&self) -> impl Iterator<Item = &CompanyFundamentals> {
+        self.data.companies.values()
+    }
+
+    pub fn get(&self, symbol: &str) -> Option<&CompanyFundamentals> {
+        self.data.companies.get(&normalize(symbol))
+    }
+
+    pub fn put(&mut self, mut company: CompanyFundamentals) -> Result<CompanyFundamentals, String> {
+        company.symbol = normalize(&company.symbol);
+        company.sort();
+        self.data
+            .companies
+            .insert(company.symbol.clone(), company.clone());
+        self.save()?;
+        Ok(company)
+    }
+
+    pub fn remove(&mut self, symbol: &str) -> Result<bool, String> {
+        if self.data.companies.remove(&normalize(symbol)).is_none() {
+            return Ok(false);
+        }
+        self.save()?;
+        Ok(true)
+    }
+
+    pub fn score_weights(&self) -> &FactorWeights {
+        &self.data.score_weights
+    }
+
+    pub fn set_score_weights(&mut self, weights: FactorWeights) -> Result<FactorWeights, String> {
+        self.data.score_weights = weights;
+        self.save()?;
+        Ok(self.data.score_weights.clone())
+    }
+
+    /// Merge a CSV file into the store. Rows update the period with the
+    /// same end date, overwriting only the columns the file has, so P&L,
+    /// balance sheet and cash flow can come from separate files. Rows that
+    /// can't be read are reported by line number.
+    pub fn import_csv(&mut self, text: &str, kind: ImportKind) -> Result<ImportSummary, String> {
+        let table = csv::Table::parse(text);
+        let keys: Vec<String> = table.headers.iter().map(|h| column_key(h)).collect();
+        let symbol_col = keys
+            .iter()
+            .position(|k| matches!(k.as_str(), "symbol" | "tradingsymbol" | "nse_code"))
+            .ok_or("file has no symbol column")?;
+        let date_col = match kind {
+            ImportKind::Profile => None,
+            _ => keys.iter().position(|k| {
+                matches!(
+                    k.as_str(),
+                    "period_end" | "period" | "date" | "quarter" | "year"
+                )
+            }),
+        };
+        if kind != ImportKind::Profile && date_col.is_none() {
+            return Err("file has no period_end column".to_string());
+        }
+
+        let mut summary = ImportSummary::default();
+        let mut probe_period = FinancialPeriod::default();
+        let mut probe_holding = Shareholding::default();
+        for (index, key) in keys.iter().enumerate() {
+            if index == symbol_col || Some(index) == date_col {
+                continue;
+            }
+            let known = match kind {
+                ImportKind::Profile => matches!(
+                    key.as_str(),
+                    "name" | "sector" | "industry" | "shares_outstanding" | "face_value"
+                ),
+                ImportKind::Quarterly | ImportKind::Annual => probe_period.field_mut(key).is_some(),
+                ImportKind::Shareholding => probe_holding.field_mut(key).is_some(),
+            };
+            if !known {
+                summary.ignored_columns.push(table.headers[index].clone());
+            }
+        }
+
+        let mut touched: Vec<String> = Vec::new();
+        for (index, row) in table.rows.iter().enumerate() {
+            // Header is line 1
+            let line = index + 2;
+            let Some(symbol) = table.get(row, Some(symbol_col)) else {
+                summary
+                    .errors
+                    .push(format!("line {}: missing symbol", line));
+                continue;
+            };
+            let symbol = normalize(symbol);
+            let period_end = match date_col {
+                Some(col) => match table.get(row, Some(col)).and_then(parse_period_end) {
+                    Some(date) => Some(date),
+                    None => {
+                        summary
+                            .errors
+                            .push(format!("line {}: unreadable period end", line));
+                        continue;
+                    }
+                },
+                None => None,
+            };
+
+            let company = self
+                .data
+                .companies
+                .entry(symbol.clone())
+                .or_insert_with(|| CompanyFundamentals::new(&symbol));
+            let mut bad: Vec<&str> = Vec::new();
+            for (col, key) in keys.iter().enumerate() {
+                if col == symbol_col || Some(col) == date_col {
+                    continue;
+                }
+                let Some(value) = table.get(row, Some(col)) else {
+                    continue;
+                };
+                match kind {
+                    ImportKind::Profile => match key.as_str() {
+                        "name" => company.name = value.to_string(),
+                        "sector" => company.sector = value.to_string(),
+                        "industry" => company.industry = value.to_string(),
+                        "shares_outstanding" | "face_value" => match parse_number(value) {
+                            Some(number) if key == "face_value" => {
+                                company.face_value = Some(number)
+                            }
+                            Some(number) => company.shares_outstanding = Some(number),
+                            None => bad.push(&table.headers[col]),
+                        },
+                        _ => {}
+                    },
+                    ImportKind::Quarterly | ImportKind::Annual => {
+                        let periods = if kind == ImportKind::Quarterly {
+                            &mut company.quarterly
+                        } else {
+                            &mut company.annual
+                        };
+                        let period = upsert_period(periods, period_end.unwrap());
+                        if let Some(field) = period.field_mut(key) {
+                            match parse_number(value) {
+                                Some(number) => *field = Some(number),
+                                None => bad.push(&table.headers[col]),
+                            }
+                        }
+                    }
+                    ImportKind::Shareholding => {
+                        let holding =
+                            upsert_holding(&mut company.shareholding, period_end.unwrap());
+                        if let Some(field) = holding.field_mut(key) {
+                            match parse_number(value) {
+                                Some(number) => *field = Some(number),
+                                None => bad.push(&table.headers[col]),
+                            }
+                        }
+                    }
+                }
+            }
+            company.sort();
+            if !bad.is_empty() {
+                summary
+                    .errors
+                    .push(format!("line {}: unreadable {}", line, bad.join(", ")));
+            }
+            summary.rows += 1;
+            if !touched.contains(&symbol) {
+                touched.push(symbol);
+            }
+        }
+
+        summary.symbols = touched.len();
+        if summary.rows > 0 {
+            self.save()?;
+        }
+        Ok(summary)
+    }
+}
+
+/// Key ratios worked out from the statements and the last close. Each is
+/// empty when the inputs it needs are missing.
+#[derive(Clone, Debug, Default, Serialize)]
+pub struct Ratios {
+    /// ₹ crore.
+    pub market_cap: Option<f64>,
+    /// Trailing twelve months: the last four quarters, else the last year.
+    pub ttm_revenue: Option<f64>,
+    pub ttm_net_profit: Option<f64>,
+    pub ttm_eps: Option<f64>,
+    pub pe: Option<f64>,
+    pub book_value_per_share: Option<f64>,
+    pub pb: Option<f64>,
+    /// Percent, on average equity over the last year.
+    pub roe: Option<f64>,
+    /// Percent: EBIT over average equity plus borrowings.
+    pub roce: Option<f64>,
+    /// Left out when the balance sheet doesn't report borrowings.
+    pub debt_to_equity: Option<f64>,
+    /// Percent of TTM revenue.
+    pub operating_margin: Option<f64>,
+    pub net_margin: Option<f64>,
+    pub dividend_yield: Option<f64>,
+    /// Compound annual growth in percent over 3 and 5 years.
+    pub revenue_cagr_3y: Option<f64>,
+    pub revenue_cagr_5y: Option<f64>,
+    pub profit_cagr_3y: Option<f64>,
+    pub profit_cagr_5y: Option<f64>,
+}
+
+fn ratio(numerator: Option<f64>, denominator: Option<f64>) -> Option<f64> {
+    let (n, d) = (numerator?, denominator?);
+    (d != 0.0).then_some(n / d)
+}
+
+fn percent(numerator: Option<f64>, denominator: Option<f64>) -> Option<f64> {
+    ratio(numerator, denominator).map(|r| r * 100.0)
+}
+
+/// Growth from `years` annual periods back to the latest. Undefined when
+/// either end is not positive.
+fn cagr(
+    annual: &[FinancialPeriod],
+    years: usize,
+    value: fn(&FinancialPeriod) -> Option<f64>,
+) -> Option<f64> {
+    let last = annual.last()?;
+    let first = annual.iter().rev().nth(years)?;
+    // Annual periods must really be `years` apart, allowing a month's slack
+    let span = (last.period_end - first.period_end).num_days() as f64 / 365.25;
+    if (span - years as f64).abs() > 0.1 {
+        return None;
+    }
+    let (start, end) = (value(first)?, value(last)?);
+    (start > 0.0 && end > 0.0).then(|| ((end / start).powf(1.0 / years as f64) - 1.0) * 100.0)
+}
+
+/// Sum over the last four quarters when they span a year.
+fn trailing(
+    quarterly: &[FinancialPeriod],
+    value: fn(&FinancialPeriod) -> Option<f64>,
+) -> Option<f64> {
+    let recent: Vec<&FinancialPeriod> = quarterly.iter().rev().take(4).collect();
+    if recent.len() < 4 {
+        return None;
+    }
+    let latest = recent[0].period_end;
+    let earliest = recent[3].period_end;
+    if earliest.year() * 12 + earliest.month() as i32 + 9
+        != latest.year() * 12 + latest.month() as i32
+    {
+        return None;
+    }
+    recent.iter().map(|p| value(p)).sum()
+}
+
+/// Latest balance sheet: the last annual period with equity figures, or
+/// the last quarter with them when companies publish half-yearly ones.
+fn latest_balance_sheet(company: &CompanyFundamentals) -> Option<&FinancialPeriod> {
+    let annual = company.annual.iter().rev().find(|p| p.equity().is_some());
+    let quarterly = company
+        .quarterly
+        .iter()
+        .rev()
+        .find(|p| p.equity().is_some());
+    match (annual, quarterly) {
+        (Some(a), Some(q)) if q.period_end > a.period_end => Some(q),
+        (Some(a), _) => Some(a),
+        (None, q) => q,
+    }
+}
+
+pub fn ratios(company: &CompanyFundamentals, price: Option<f64>) -> Ratios {
+    let last_year = company.annual.last();
+    let ttm_revenue =
+        trailing(&company.quarterly, |p| p.revenue).or(last_year.and_then(|p| p.revenue));
+    let ttm_net_profit =
+        trailing(&company.quarterly, |p| p.net_profit).or(last_year.and_then(|p| p.net_profit));
+    let ttm_operating_profit = trailing(&company.quarterly, |p| p.operating_profit)
+        .or(last_year.and_then(|p| p.operating_profit));
+
+    let shares = company.shares_outstanding.filter(|s| *s > 0.0);
+    let market_cap = price.zip(shares).map(|(p, s)| p * s);
+    let ttm_eps = ratio(ttm_net_profit, shares);
+    let balance = latest_balance_sheet(company);
+    let equity = balance.and_then(|p| p.equity());
+    let book_value_per_share = ratio(equity, shares);
+
+    // Returns use the average of this and the previous year's capital
+    let (roe, roce) = match company.annual.iter().rev().find(|p| p.equity().is_some()) {
+        Some(year) => {
+            let previous = company
+                .annual
+                .iter()
+                .rev()
+                .find(|p| p.period_end < year.period_end && p.equity().is_some());
+            let average =
+                |value: fn(&FinancialPeriod) -> Option<f64>| match previous.and_then(value) {
+                    Some(before) => value(year).map(|now| (now + before) / 2.0),
+                    None => value(year),
+                };
+            let average_equity = average(|p| p.equity());
+            let capital = average(|p| Some(p.equity()? + p.borrowings.unwrap_or(0.0)));
+            let ebit = year
+                .profit_before_tax
+                .map(|pbt| pbt + year.interest.unwrap_or(0.0));
+            (
+                percent(year.net_profit, average_equity),
+                percent(ebit, capital),
+            )
+        }
+        None => (None, None),
+    };
+
+    Ratios {
+        market_cap,
+        ttm_revenue,
+        ttm_net_profit,
+        ttm_eps,
+        pe: ratio(price, ttm_eps).filter(|pe| *pe > 0.0),
+        book_value_per_share,
+        pb: ratio(price, book_value_per_share).filter(|pb| *pb > 0.0),
+        roe,
+        roce,
+        debt_to_equity: ratio(balance.and_then(|p| p.borrowings), equity),
+        operating_margin: percent(ttm_operating_profit, ttm_revenue),
+        net_margin: percent(ttm_net_profit, ttm_revenue),
+        dividend_yield: percent(last_year.and_then(|p| p.dividend_per_share), price),
+        revenue_cagr_3y: cagr(&company.annual, 3, |p| p.revenue),
+        revenue_cagr_5y: cagr(&company.annual, 5, |p| p.revenue),
+        profit_cagr_3y: cagr(&company.annual, 3, |p| p.net_profit),
+        profit_cagr_5y: cagr(&company.annual, 5, |p| p.net_profit),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn balance_sheet(borrowings: Option<f64>) -> CompanyFundamentals {
+        CompanyFundamentals {
+            symbol: "INFY".to_string(),
+            annual: vec![FinancialPeriod {
+                period_end: NaiveDate::from_ymd_opt(2024, 3, 31).unwrap(),
+                share_capital: Some(100.0),
+                reserves: Some(900.0),
+                borrowings,
+                ..Default::default()
+            }],
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn debt_to_equity_needs_reported_borrowings() {
+        assert_eq!(ratios(&balance_sheet(None), None).debt_to_equity, None);
+        assert_eq!(
+            ratios(&balance_sheet(Some(250.0)), None).debt_to_equity,
+            Some(0.25)
+        );
+    }
+
+    #[test]
+    fn symbols_are_case_insensitive() {
+        let dir = std::env::temp_dir().join(format!("slynqix-fundamentals-{}", std::process::id()));
+        let _ = std::fs::remove_dir_all(&dir);
+        let mut store = FundamentalsStore::load(&dir.join("fundamentals.json"));
+        store
+            .put(CompanyFundamentals {
+                symbol: " infy".to_string(),
+                ..Default::default()
+            })
+            .unwrap();
+
+        assert_eq!(store.get("Infy").unwrap().symbol, "INFY");
+        assert!(store.remove("infy ").unwrap());
+        assert!(store.get("INFY").is_none());
+        std::fs::remove_dir_all(&dir).unwrap();
+    }
+}
@@ -0,0 +1,347 @@
+use std::collections::HashMap;
+use std::path::Path;
+
+use chrono::NaiveDate;
+use serde::{Deserialize, Serialize};
+
+use crate::utils::csv;
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum InstrumentKind {
+    Equity,
+    Index,
+    Future,
+    CallOption,
+    PutOption,
+}
+
+impl InstrumentKind {
+    pub fn is_derivative(self) -> bool {
+        matches!(
+            self,
+            InstrumentKind::Future | InstrumentKind::CallOption | InstrumentKind::PutOption
+        )
+    }
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "SCREAMING_SNAKE_CASE")]
+pub enum ExchangeSegment {
+    NseEq,
+    BseEq,
+    NseFo,
+    BseFo,
+    NseCd,
+    McxFo,
+    Indices,
+}
+
+impl ExchangeSegment {
+    pub fn parse(value: &str) -> Option<Self> {
+        match value.trim().to_uppercase().replace('-', "_").as_str() {
+            "NSE" | "NSE_EQ" => Some(ExchangeSegment::NseEq),
+            "BSE" | "BSE_EQ" => Some(ExchangeSegment::BseEq),
+            "NFO" | "NFO_FUT" | "NFO_OPT" | "NSE_FO" => Some(ExchangeSegment::NseFo),
+            "BFO" | "BFO_FUT" | "BFO_OPT" | "BSE_FO" => Some(ExchangeSegment::BseFo),
+            "CDS" | "CDS_FUT" | "CDS_OPT" | "NSE_CD" => Some(ExchangeSegment::NseCd),
+            "MCX" | "MCX_FUT" | "MCX_OPT" | "MCX_FO" => Some(ExchangeSegment::McxFo),
+            "INDICES" | "NSE_INDEX" | "BSE_INDEX" => Some(ExchangeSegment::Indices),
+            _ => None,
+        }
+    }
+}
+
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct Instrument {
+    pub token: String,
+    pub symbol: String,
+    pub name: String,
+    pub underlying: Option<String>,
+    pub kind: InstrumentKind,
+    pub segment: ExchangeSegment,
+    pub expiry: Option<NaiveDate>,
+    pub strike: Option<f64>,
+    pub lot_size: u32,
+    pub tick_size: f64,
+}
+
+#[derive(Clone, Debug, Serialize)]
+pub struct SearchHit {
+    #[serde(flatten)]
+    pub instrument: Instrument,
+    pub score: u32,
+}
+
+/// Segments a bare symbol is looked up in, first match wins: NSE lists
+/// most symbols that BSE also does and is where most volume trades.
+const LOOKUP_ORDER: [ExchangeSegment; 7] = [
+    ExchangeSegment::NseEq,
+    ExchangeSegment::NseFo,
+    ExchangeSegment::NseCd,
+    ExchangeSegment::Indices,
+    ExchangeSegment::BseEq,
+    ExchangeSegment::BseFo,
+    ExchangeSegment::McxFo,
+];
+
+/// In-memory instrument master keyed by segment and trading symbol, so
+/// the same symbol on NSE and BSE are separate instruments.
+#[derive(Default)]
+pub struct InstrumentRegistry {
+    instruments: Vec<Instrument>,
+    by_symbol: HashMap<(ExchangeSegment, String), usize>,
+}
+
+impl InstrumentRegistry {
+    /// Registry seeded with the headline indices so the app is usable
+    /// before an instrument dump has been loaded.
+    pub fn with_defaults() -> Self {
+        let mut registry = Self::default();
+        for (symbol, name, segment) in [
+            ("Nifty 50", "NIFTY 50", ExchangeSegment::Indices),
+            ("BankNifty", "NIFTY BANK", ExchangeSegment::Indices),
+            ("FinNifty", "NIFTY FIN SERVICE", ExchangeSegment::Indices),
+            ("Sensex", "S&P BSE SENSEX", ExchangeSegment::Indices),
+            ("Midcap", "NIFTY MIDCAP 100", ExchangeSegment::Indices),
+        ] {
+            registry.insert(Instrument {
+                token: symbol.to_uppercase().replace(' ', ""),
+                symbol: symbol.to_string(),
+                name: name.to_string(),
+                underlying: None,
+                kind: InstrumentKind::Index,
+                segment,
+                expiry: None,
+                strike: None,
+                lot_size: 1,
+                tick_size: 0.05,
+            });
+        }
+        registry
+    }
+
+    /// Load a Kite-style instrument dump (`instrument_token, tradingsymbol,
+    /// name, expiry, strike, tick_size, lot_size, instrument_type, segment,
+    /// exchange`). Rows that cannot be classified are skipped.
+    pub fn load_dump(path: &Path) -> Result<Self, String> {
+        let text = std::fs::read_to_string(path)
+            .map_err(|e| format!("failed to read {}: {}", path.display(), e))?;
+        let mut registry = Self::with_defaults();
+        let loaded = registry.import_csv(&text)?;
+        log::info!("Loaded {} instruments from {}", loaded, path.display());
+        Ok(registry)
+    }
+
+    pub fn import_csv(&mut self, text: &str) -> Result<usize, String> {
+        let table = csv::Table::parse(text);
+        let token_col = table.column(&["instrument_token", "token", "exchange_token"]);
+        let symbol_col = table
+            .column(&["tradingsymbol", "trading_symbol", "symbol"])
+            .ok_or("instrument dump has no tradingsymbol column")?;
+        let name_col = table.column(&["name"]);
+        let expiry_col = table.column(&["expiry"]);
+        let strike_col = table.column(&["strike"]);
+        let tick_col = table.column(&["tick_size"]);
+        let lot_col = table.column(&["lot_size"]);
+        let type_col = table.column(&["instrument_type"]);
+        let segment_col = table.column(&["segment"]);
+        let exchange_col = table.column(&["exchange"]);
+
+        let mut loaded = 0;
+        for row in &table.rows {
+            let Some(symbol) = table.get(row, Some(symbol_col)) else {
+                continue;
+            };
+            let segment_raw = table
+                .get(row, segment_col)
+                .or_else(|| table.get(row, exchange_col))
+                .unwrap_or("NSE");
+            let Some(segment) = ExchangeSegment::parse(segment_raw) else {
+                continue;
+            };
+            let kind = match table.get(row, type_col).unwrap_or("EQ") {
+                "EQ" => {
+                    if segment == ExchangeSegment::Indices {
+                        InstrumentKind::Index
+                    } else {
+                        InstrumentKind::Equity
+                    }
+                }
+                "FUT" => InstrumentKind::Future,
+                "CE" => InstrumentKind::CallOption,
+                "PE" => InstrumentKind::PutOption,
+                "INDEX" => InstrumentKind::Index,
+                _ => continue,
+            };
+            let name = table.get(row, name_col).unwrap_or(symbol).to_string();
+
+            self.insert(Instrument {
+                token: table.get(row, token_col).unwrap_or(symbol).to_string(),
+                symbol: symbol.to_string(),
+                underlying: kind.is_derivative().then(|| name.clone()),
+                name,
+                kind,
+                segment,
+                expiry: table
+                    .get(row, expiry_col)
+                    .and_then(|v| NaiveDate::parse_from_str(v, "%Y-%m-%d").ok()),
+                strike: table
+                    .get(row, strike_col)
+                    .and_then(|v| v.parse().ok())
+                    .filter(|strike: &f64| *strike > 0.0),
+                lot_size: table
+                    .get(row, lot_col)
+                    .and_then(|v| v.parse().ok())
+                    .unwrap_or(1),
+                tick_size: table
+                    .get(row, tick_col)
+                    .and_then(|v| v.parse().ok())
+                    .unwrap_or(0.05),
+            });
+            loaded += 1;
+        }
+
+        Ok(loaded)
+    }
+
+    pub fn insert(&mut self, instrument: Instrument) {
+        let key = (instrument.segment, normalize(&instrument.symbol));
+        match self.by_symbol.get(&key) {
+            Some(&idx) => self.instruments[idx] = instrument,
+            None => {
+                self.by_symbol.insert(key, self.instruments.len());
+                self.instruments.push(instrument);
+            }
+        }
+    }
+
+    /// The instrument trading as `symbol`, on NSE when it is listed on
+    /// both exchanges.
+    pub fn get(&self, symbol: &str) -> Option<&Instrument> {
+        let symbol = normalize(symbol);
+        LOOKUP_ORDER
+            .iter()
+            .find_map(|segment| self.by_symbol.get(&(*segment, symbol.clone())))
+            .map(|&idx| &self.instruments[idx])
+    }
+
+    pub fn get_in(&self, segment: ExchangeSegment, symbol: &str) -> Option<&Instrument> {
+        self.by_symbol
+            .get(&(segment, normalize(symbol)))
+            .map(|&idx| &self.instruments[idx])
+    }
+
+    pub fn len(&self) -> usize {
+        self.instruments.len()
+    }
+
+    /// Fuzzy search over trading symbol and name. Exact and prefix matches
+    /// rank above substring matches, which rank above subsequence matches
+    /// (so "bnknfty" still finds BankNifty).
+    pub fn search(
+        &self,
+        query: &str,
+        kind: Option<InstrumentKind>,
+        segment: Option<ExchangeSegment>,
+        limit: usize,
+    ) -> Vec<SearchHit> {
+        let needle = normalize(query);
+        let mut hits: Vec<SearchHit> = self
+            .instruments
+            .iter()
+            .filter(|i| kind.is_none_or(|k| i.kind == k))
+            .filter(|i| segment.is_none_or(|s| i.segment == s))
+            .filter_map(|i| {
+                let score = match_score(&needle, &normalize(&i.symbol))
+                    .max(match_score(&needle, &normalize(&i.name)).saturating_sub(5));
+                (score > 0).then(|| SearchHit {
+                    instrument: i.clone(),
+                    score,
+                })
+            })
+            .collect();
+
+        hits.sort_by(|a, b| {
+            b.score
+                .cmp(&a.score)
+                .then(a.instrument.symbol.len().cmp(&b.instrument.symbol.len()))
+                .then(a.instrument.expiry.cmp(&b.instrument.expiry))
+        });
+        hits.truncate(limit);
+        hits
+    }
+}
+
+fn normalize(value: &str) -> String {
+    value
+        .chars()
+        .filter(|c| c.is_ascii_alphanumeric())
+        .map(|c| c.to_ascii_lowercase())
+        .collect()
+}
+
+fn match_score(needle: &str, haystack: &str) -> u32 {
+    if needle.is_empty() {
+        return 1;
+    }
+    if haystack == needle {
+        return 100;
+    }
+    if haystack.starts_with(needle) {
+        return 80;
+    }
+    if haystack.contains(needle) {
+        return 60;
+    }
+
+    // Subsequence match, rewarding characters that land next to each other.
+    let mut chars = haystack.chars();
+    let mut adjacent = 0;
+    for (pos, wanted) in needle.chars().enumerate() {
+        let mut offset = 0;
+        loop {
+            match chars.next() {
+                Some(c) if c == wanted => break,
+                Some(_) => offset += 1,
+                None => return 0,
+            }
+        }
+        if offset == 0 && pos > 0 {
+            adjacent += 1;
+        }
+    }
+    20 + (adjacent * 20 / needle.len() as u32)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn keeps_both_listings_and_prefers_nse() {
+        let mut registry = InstrumentRegistry::default();
+        registry
+            .import_csv(
+                "instrument_token,tradingsymbol,name,instrument_type,segment,exchange\n\
+                 500325,RELIANCE,RELIANCE INDUSTRIES,EQ,BSE,BSE\n\
+                 738561,RELIANCE,RELIANCE INDUSTRIES,EQ,NSE,NSE\n\
+                 500010,HDFC,HDFC,EQ,BSE,BSE\n",
+            )
+            .unwrap();
+
+        assert_eq!(registry.len(), 3);
+        assert_eq!(registry.get("reliance").unwrap().token, "738561");
+        assert_eq!(
+            registry
+                .get_in(ExchangeSegment::BseEq, "RELIANCE")
+                .unwrap()
+                .token,
+            "500325"
+        );
+        assert_eq!(
+            registry.get("HDFC").unwrap().segment,
+            ExchangeSegment::BseEq
+        );
+    }
+}
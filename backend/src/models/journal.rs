@@ -1 +1,749 @@
- 
+use std::collections::{BTreeMap, HashSet, VecDeque};
+use std::path::{Path, PathBuf};
+
+use chrono::{NaiveDate, NaiveDateTime};
+use serde::{Deserialize, Serialize};
+
+use crate::utils::persist;
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub enum Side {
+    Buy,
+    Sell,
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub enum Direction {
+    Long,
+    Short,
+}
+
+impl Direction {
+    fn of(side: Side) -> Self {
+        match side {
+            Side::Buy => Direction::Long,
+            Side::Sell => Direction::Short,
+        }
+    }
+
+    fn sign(self) -> f64 {
+        match self {
+            Direction::Long => 1.0,
+            Direction::Short => -1.0,
+        }
+    }
+}
+
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct Execution {
+    #[serde(default)]
+    pub id: String,
+    pub side: Side,
+    pub quantity: u32,
+    pub price: f64,
+    pub time: NaiveDateTime,
+    #[serde(default)]
+    pub fees: f64,
+    /// Broker trade id for imported fills, used to skip re-imports.
+    #[serde(default)]
+    pub trade_id: Option<String>,
+}
+
+/// A position from first entry until it is flat again, made up of every
+/// fill that opened, added to, reduced or closed it.
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct Trade {
+    #[serde(default)]
+    pub id: String,
+    pub symbol: String,
+    pub executions: Vec<Execution>,
+    #[serde(default)]
+    pub notes: String,
+    /// Setup names and other labels the trade is filed under.
+    #[serde(default)]
+    pub tags: Vec<String>,
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum TradeStatus {
+    Open,
+    Closed,
+}
+
+#[derive(Clone, Debug, Serialize)]
+pub struct TradeSummary {
+    pub direction: Option<Direction>,
+    pub status: TradeStatus,
+    pub entry_quantity: u32,
+    pub exit_quantity: u32,
+    pub open_quantity: u32,
+    pub avg_entry_price: f64,
+    pub avg_exit_price: f64,
+    /// Average cost of the lots still open.
+    pub open_avg_price: f64,
+    pub gross_pnl: f64,
+    pub fees: f64,
+    /// Realized PnL net of fees.
+    pub realized_pnl: f64,
+    pub unrealized_pnl: Option<f64>,
+    pub entry_time: Option<NaiveDateTime>,
+    pub exit_time: Option<NaiveDateTime>,
+}
+
+/// Part of a position closed by a later fill, with its share of the fees
+/// of both fills.
+#[derive(Clone, Debug, Serialize)]
+pub struct ClosedLot {
+    pub direction: Direction,
+    pub quantity: u32,
+    pub entry_time: NaiveDateTime,
+    pub entry_price: f64,
+    pub exit_time: NaiveDateTime,
+    pub exit_price: f64,
+    pub fees: f64,
+}
+
+struct Lot {
+    quantity: u32,
+    price: f64,
+    time: NaiveDateTime,
+    fee_per_unit: f64,
+}
+
+struct Matching {
+    summary: TradeSummary,
+    daily: BTreeMap<NaiveDate, f64>,
+    closed: Vec<ClosedLot>,
+}
+
+impl Trade {
+    pub fn sorted_executions(&self) -> Vec<&Execution> {
+        let mut executions: Vec<&Execution> = self.executions.iter().collect();
+        executions.sort_by_key(|e| e.time);
+        executions
+    }
+
+    /// Match exits against entries first-in-first-out. The first fill sets
+    /// the direction; a fill that more than closes the position opens the
+    /// remainder in the opposite direction. `mark` prices the open lots
+    /// for unrealized PnL.
+    pub fn summarize(&self, mark: Option<f64>) -> TradeSummary {
+        self.match_lots(mark).summary
+    }
+
+    /// Realized PnL by calendar day: each exit's matched PnL on the day it
+    /// filled, less the fees of every fill on its own day.
+    pub fn daily_pnl(&self) -> BTreeMap<NaiveDate, f64> {
+        self.match_lots(None).daily
+    }
+
+    /// Every entry lot matched against an exit, in the order they closed.
+    /// Fees are split by quantity across the lots each fill touched.
+    pub fn closed_lots(&self) -> Vec<ClosedLot> {
+        self.match_lots(None).closed
+    }
+
+    fn match_lots(&self, mark: Option<f64>) -> Matching {
+        let mut daily: BTreeMap<NaiveDate, f64> = BTreeMap::new();
+        let mut closed: Vec<ClosedLot> = Vec::new();
+        let mut lots: VecDeque<Lot> = VecDeque::new();
+        let mut position: Option<Direction> = None;
+        let mut first_direction = None;
+        let (mut entry_qty, mut entry_value) = (0u32, 0.0);
+        let (mut exit_qty, mut exit_value) = (0u32, 0.0);
+        let mut gross_pnl = 0.0;
+        let mut fees = 0.0;
+        let mut exit_time = None;
+        let executions = self.sorted_executions();
+
+        for execution in &executions {
+            fees += execution.fees;
+            let day = daily.entry(execution.time.date()).or_default();
+            *day -= execution.fees;
+            let side = Direction::of(execution.side);
+            let mut remaining = execution.quantity;
+            let fee_per_unit = execution.fees / execution.quantity.max(1) as f64;
+
+            if let Some(direction) = position.filter(|d| *d != side) {
+                while remaining > 0 {
+                    let Some(lot) = lots.front_mut() else {
+                        break;
+                    };
+                    let matched = remaining.min(lot.quantity);
+                    let pnl = (execution.price - lot.price) * matched as f64 * direction.sign();
+                    gross_pnl += pnl;
+                    *day += pnl;
+                    exit_qty += matched;
+                    exit_value += execution.price * matched as f64;
+                    closed.push(ClosedLot {
+                        direction,
+                        quantity: matched,
+                        entry_time: lot.time,
+                        entry_price: lot.price,
+                        exit_time: execution.time,
+                        exit_price: execution.price,
+                        fees: (lot.fee_per_unit + fee_per_unit) * matched as f64,
+                    });
+                    lot.quantity -= matched;
+                    remaining -= matched;
+                    if lot.quantity == 0 {
+                        lots.pop_front();
+                    }
+                }
+                exit_time = Some(execution.time);
+                if lots.is_empty() {
+                    position = None;
+                }
+            }
+
+            if remaining > 0 {
+                position = Some(side);
+                first_direction.get_or_insert(side);
+                entry_qty += remaining;
+                entry_value += execution.price * remaining as f64;
+                lots.push_back(Lot {
+                    quantity: remaining,
+                    price: execution.price,
+                    time: execution.time,
+                    fee_per_unit,
+                });
+            }
+        }
+
+        let open_quantity: u32 = lots.iter().map(|lot| lot.quantity).sum();
+        let open_value: f64 = lots.iter().map(|lot| lot.price * lot.quantity as f64).sum();
+        let open_avg_price = if open_quantity > 0 {
+            open_value / open_quantity as f64
+        } else {
+            0.0
+        };
+        let unrealized_pnl = match (position, mark) {
+            (Some(direction), Some(mark)) => {
+                Some((mark - open_avg_price) * open_quantity as f64 * direction.sign())
+            }
+            (None, _) => Some(0.0),
+            _ => None,
+        };
+
+        let summary = TradeSummary {
+            direction: first_direction,
+            status: if open_quantity > 0 {
+                TradeStatus::Open
+            } else {
+                TradeStatus::Closed
+            },
+            entry_quantity: entry_qty,
+            exit_quantity: exit_qty,
+            open_quantity,
+            avg_entry_price: average(entry_value, entry_qty),
+            avg_exit_price: average(exit_value, exit_qty),
+            open_avg_price,
+            gross_pnl,
+            fees,
+            realized_pnl: gross_pnl - fees,
+            unrealized_pnl,
+            entry_time: executions.first().map(|e| e.time),
+            exit_time: if open_quantity == 0 { exit_time } else { None },
+        };
+        Matching {
+            summary,
+            daily,
+            closed,
+        }
+    }
+
+    /// Bought minus sold quantity.
+    pub fn net_quantity(&self) -> i64 {
+        self.executions
+            .iter()
+            .map(|e| match e.side {
+                Side::Buy => e.quantity as i64,
+                Side::Sell => -(e.quantity as i64),
+            })
+            .sum()
+    }
+
+    pub fn validate(&self) -> Result<(), String> {
+        if self.symbol.trim().is_empty() {
+            return Err("trade needs a symbol".to_string());
+        }
+        if self.executions.is_empty() {
+            return Err("trade needs at least one execution".to_string());
+        }
+        for execution in &self.executions {
+            if execution.quantity == 0 {
+                return Err("execution quantity must be positive".to_string());
+            }
+            if !(execution.price.is_finite() && execution.price > 0.0) {
+                return Err("execution price must be positive".to_string());
+            }
+            if !(execution.fees.is_finite() && execution.fees >= 0.0) {
+                return Err("execution fees cannot be negative".to_string());
+            }
+        }
+        Ok(())
+    }
+}
+
+fn average(value: f64, quantity: u32) -> f64 {
+    if quantity > 0 {
+        value / quantity as f64
+    } else {
+        0.0
+    }
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum TagKind {
+    Setup,
+    Mistake,
+    Emotion,
+}
+
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct Tag {
+    pub name: String,
+    pub kind: TagKind,
+    #[serde(default)]
+    pub description: String,
+}
+
+fn default_tags() -> Vec<Tag> {
+    [
+        ("Breakout", TagKind::Setup),
+        ("Pullback", TagKind::Setup),
+        ("ORB", TagKind::Setup),
+        ("FOMO", TagKind::Mistake),
+        ("Moved stop", TagKind::Mistake),
+        ("Oversized", TagKind::Mistake),
+        ("Confident", TagKind::Emotion),
+        ("Fearful", TagKind::Emotion),
+        ("Greedy", TagKind::Emotion),
+    ]
+    .into_iter()
+    .map(|(name, kind)| Tag {
+        name: name.to_string(),
+        kind,
+        description: String::new(),
+    })
+    .collect()
+}
+
+#[derive(Clone, Serialize, Deserialize)]
+struct JournalFile {
+    next_id: u64,
+    trades: Vec<Trade>,
+    #[serde(default = "default_tags")]
+    tags: Vec<Tag>,
+}
+
+impl Default for JournalFile {
+    fn default() -> Self {
+        Self {
+            next_id: 0,
+            trades: Vec::new(),
+            tags: default_tags(),
+        }
+    }
+}
+
+/// Journal trades, persisted as JSON after every change.
+pub struct JournalStore {
+    path: PathBuf,
+    data: JournalFile,
+}
+
+impl JournalStore {
+    pub fn load(path: &Path) -> Self {
+        let data = persist::load_json(path);
+
+        Self {
+            path: path.to_path_buf(),
+            data,
+        }
+    }
+
+    fn save(&self) -> Result<(), String> {
+        persist::save_json(&self.path, &self.data)
+    }
+
+    /// Save, or put the data back as it was `before` when that fails, so
+    /// memory never holds changes the file doesn't.
+    fn commit(&mut self, before: JournalFile) -> Result<(), String> {
+        self.save().inspect_err(|_| self.data = before)
+    }
+
+    fn next_id(&mut self, prefix: &str) -> String {
+        self.data.next_id += 1;
+        format!("{}{}", prefix, self.data.next_id)
+    }
+
+    fn assign_ids(&mut self, trade: &mut Trade) {
+        for execution in &mut trade.executions {
+            if execution.id.is_empty() {
+                execution.id = self.next_id("e");
+            }
+        }
+    }
+
+    /// Tags used on a trade but not defined yet are added as setups.
+    fn register_tags(&mut self, trade: &mut Trade) {
+        let mut seen = HashSet::new();
+        for tag in &mut trade.tags {
+            *tag = tag.trim().to_string();
+        }
+        trade
+            .tags
+            .retain(|t| !t.is_empty() && seen.insert(t.clone()));
+        for name in &trade.tags {
+            if self.tag(name).is_none() {
+                self.data.tags.push(Tag {
+                    name: name.clone(),
+                    kind: TagKind::Setup,
+                    description: String::new(),
+                });
+            }
+        }
+    }
+
+    pub fn tags(&self) -> &[Tag] {
+        &self.data.tags
+    }
+
+    pub fn tag(&self, name: &str) -> Option<&Tag> {
+        self.data.tags.iter().find(|t| t.name == name)
+    }
+
+    /// Create a tag, or update the one currently called `name`. Renaming
+    /// relabels every trade carrying the tag.
+    pub fn save_tag(&mut self, name: Option<&str>, tag: Tag) -> Result<Tag, String> {
+        let existing = name.and_then(|n| self.data.tags.iter().position(|t| t.name == n));
+        if let Some(clash) = self.data.tags.iter().position(|t| t.name == tag.name) {
+            if Some(clash) != existing {
+                return Err(format!("tag '{}' already exists", tag.name));
+            }
+        }
+
+        let before = self.data.clone();
+        match existing {
+            Some(index) => {
+                let old = std::mem::replace(&mut self.data.tags[index], tag.clone());
+                if old.name != tag.name {
+                    for trade in &mut self.data.trades {
+                        for label in &mut trade.tags {
+                            if *label == old.name {
+                                *label = tag.name.clone();
+                            }
+                        }
+                    }
+                }
+            }
+            None => self.data.tags.push(tag.clone()),
+        }
+        self.commit(before)?;
+        Ok(tag)
+    }
+
+    /// Delete a tag and take it off every trade.
+    pub fn remove_tag(&mut self, name: &str) -> Result<bool, String> {
+        if self.tag(name).is_none() {
+            return Ok(false);
+        }
+        let before = self.data.clone();
+        self.data.tags.retain(|t| t.name != name);
+        for trade in &mut self.data.trades {
+            trade.tags.retain(|t| t != name);
+        }
+        self.commit(before)?;
+        Ok(true)
+    }
+
+    pub fn trades(&self) -> &[Trade] {
+        &self.data.trades
+    }
+
+    /// Broker trade ids of every imported fill.
+    pub fn trade_ids(&self) -> HashSet<&str> {
+        self.data
+            .trades
+            .iter()
+            .flat_map(|t| &t.executions)
+            .filter_map(|e| e.trade_id.as_deref())
+            .collect()
+    }
+
+    /// The most recent trade on `symbol`, in any case, that still has open
+    /// quantity.
+    pub fn open_trade(&self, symbol: &str) -> Option<&Trade> {
+        self.data
+            .trades
+            .iter()
+            .rev()
+            .find(|t| t.symbol.eq_ignore_ascii_case(symbol) && t.net_quantity() != 0)
+    }
+
+    pub fn get(&self, id: &str) -> Option<&Trade> {
+        self.data.trades.iter().find(|t| t.id == id)
+    }
+
+    pub fn insert(&mut self, mut trade: Trade) -> Result<Trade, String> {
+        let before = self.data.clone();
+        trade.id = self.next_id("t");
+        self.assign_ids(&mut trade);
+        self.register_tags(&mut trade);
+        self.data.trades.push(trade.clone());
+        self.commit(before)?;
+        Ok(trade)
+    }
+
+    /// Add new tags and trades together, saving once: either all of them
+    /// are stored or, when one is invalid or the save fails, none are.
+    /// Tags go first so trades keep their kinds instead of defaulting to
+    /// setups.
+    pub fn insert_all(&mut self, tags: Vec<Tag>, trades: Vec<Trade>) -> Result<Vec<Trade>, String> {
+        let mut names: HashSet<&str> = self.data.tags.iter().map(|t| t.name.as_str()).collect();
+        for tag in &tags {
+            if !names.insert(&tag.name) {
+                return Err(format!("tag '{}' already exists", tag.name));
+            }
+        }
+        self.store_all(tags, trades, Vec::new())
+    }
+
+    /// Store a tradebook import: new trades, plus the open trades its
+    /// fills continued, which replace the stored ones with the same id.
+    /// Saved once, like [`JournalStore::insert_all`].
+    pub fn import(&mut self, created: Vec<Trade>, updated: Vec<Trade>) -> Result<(), String> {
+        if let Some(trade) = updated.iter().find(|t| self.get(&t.id).is_none()) {
+            return Err(format!("trade {} no longer exists", trade.id));
+        }
+        self.store_all(Vec::new(), created, updated).map(|_| ())
+    }
+
+    fn store_all(
+        &mut self,
+        tags: Vec<Tag>,
+        created: Vec<Trade>,
+        updated: Vec<Trade>,
+    ) -> Result<Vec<Trade>, String> {
+        for trade in created.iter().chain(&updated) {
+            trade
+                .validate()
+                .map_err(|e| format!("{}: {}", trade.symbol, e))?;
+        }
+
+        let before = self.data.clone();
+        self.data.tags.extend(tags);
+        let mut inserted = Vec::with_capacity(created.len());
+        for mut trade in created {
+            trade.id = self.next_id("t");
+            self.assign_ids(&mut trade);
+            self.register_tags(&mut trade);
+            self.data.trades.push(trade.clone());
+            inserted.push(trade);
+        }
+        for mut trade in updated {
+            self.assign_ids(&mut trade);
+            self.register_tags(&mut trade);
+            if let Some(stored) = self.data.trades.iter_mut().find(|t| t.id == trade.id) {
+                *stored = trade;
+            }
+        }
+        self.commit(before)?;
+        Ok(inserted)
+    }
+
+    pub fn update(&mut self, id: &str, mut trade: Trade) -> Result<Option<Trade>, String> {
+        let Some(idx) = self.data.trades.iter().position(|t| t.id == id) else {
+            return Ok(None);
+        };
+        let before = self.data.clone();
+        trade.id = id.to_string();
+        self.assign_ids(&mut trade);
+        self.register_tags(&mut trade);
+        self.data.trades[idx] = trade.clone();
+        self.commit(before)?;
+        Ok(Some(trade))
+    }
+
+    pub fn remove(&mut self, id: &str) -> Result<bool, String> {
+        let Some(index) = self.data.trades.iter().position(|t| t.id == id) else {
+            return Ok(false);
+        };
+        let before = self.data.clone();
+        self.data.trades.remove(index);
+        self.commit(before)?;
+        Ok(true)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn execution(side: Side, quantity: u32, price: f64, minute: u32) -> Execution {
+        Execution {
+            id: String::new(),
+            side,
+            quantity,
+            price,
+            time: NaiveDate::from_ymd_opt(2025, 3, 3)
+                .unwrap()
+                .and_hms_opt(10, minute, 0)
+                .unwrap(),
+            fees: 1.0,
+            trade_id: None,
+        }
+    }
+
+    fn fills(executions: Vec<Execution>) -> Trade {
+        Trade {
+            id: String::new(),
+            symbol: "INFY".to_string(),
+            executions,
+            notes: String::new(),
+            tags: Vec::new(),
+        }
+    }
+
+    fn trade(symbol: &str, price: f64) -> Trade {
+        Trade {
+            id: String::new(),
+            symbol: symbol.to_string(),
+            executions: vec![Execution {
+                id: String::new(),
+                side: Side::Buy,
+                quantity: 10,
+                price,
+                time: NaiveDate::from_ymd_opt(2025, 3, 3)
+                    .unwrap()
+                    .and_hms_opt(10, 0, 0)
+                    .unwrap(),
+                fees: 0.0,
+                trade_id: None,
+            }],
+            notes: String::new(),
+            tags: vec!["Breakout".to_string()],
+        }
+    }
+
+    fn store(name: &str) -> JournalStore {
+        let path = std::env::temp_dir().join(format!(
+            "slynqix-journal-{}-{}.json",
+            name,
+            std::process::id()
+        ));
+        let _ = std::fs::remove_file(&path);
+        JournalStore::load(&path)
+    }
+
+    #[test]
+    fn exits_match_the_oldest_entries_first() {
+        // Listed out of order; matching follows fill time
+        let trade = fills(vec![
+            execution(Side::Sell, 15, 120.0, 2),
+            execution(Side::Buy, 10, 100.0, 0),
+            execution(Side::Buy, 10, 110.0, 1),
+        ]);
+        let summary = trade.summarize(Some(100.0));
+
+        assert_eq!(summary.direction, Some(Direction::Long));
+        assert_eq!(summary.status, TradeStatus::Open);
+        assert_eq!(summary.open_quantity, 5);
+        assert_eq!(summary.open_avg_price, 110.0);
+        assert_eq!(summary.avg_entry_price, 105.0);
+        assert_eq!(summary.gross_pnl, 10.0 * 20.0 + 5.0 * 10.0);
+        assert_eq!(summary.realized_pnl, 250.0 - 3.0);
+        assert_eq!(summary.unrealized_pnl, Some(-50.0));
+        assert!(summary.exit_time.is_none());
+    }
+
+    #[test]
+    fn a_fill_past_flat_opens_the_other_way() {
+        let trade = fills(vec![
+            execution(Side::Buy, 10, 100.0, 0),
+            execution(Side::Sell, 15, 90.0, 1),
+        ]);
+        let summary = trade.summarize(Some(80.0));
+
+        assert_eq!(summary.direction, Some(Direction::Long));
+        assert_eq!((summary.exit_quantity, summary.open_quantity), (10, 5));
+        assert_eq!(summary.gross_pnl, -100.0);
+        // The 5 left over are short from 90
+        assert_eq!(summary.unrealized_pnl, Some(50.0));
+        assert_eq!(trade.summarize(None).unrealized_pnl, None);
+    }
+
+    #[test]
+    fn closed_trades_have_no_unrealized_pnl() {
+        let trade = fills(vec![
+            execution(Side::Sell, 10, 100.0, 0),
+            execution(Side::Buy, 10, 95.0, 5),
+        ]);
+        let summary = trade.summarize(None);
+
+        assert_eq!(summary.direction, Some(Direction::Short));
+        assert_eq!(summary.status, TradeStatus::Closed);
+        assert_eq!(summary.realized_pnl, 48.0);
+        assert_eq!(summary.unrealized_pnl, Some(0.0));
+        assert_eq!(summary.exit_time, Some(trade.executions[1].time));
+    }
+
+    #[test]
+    fn insert_all_saves_every_trade_once() {
+        let mut journal = store("insert-all");
+        let inserted = journal
+            .insert_all(
+                Vec::new(),
+                vec![trade("INFY", 1500.0), trade("TCS", 3500.0)],
+            )
+            .unwrap();
+
+        assert_eq!(inserted.len(), 2);
+        assert_ne!(inserted[0].id, inserted[1].id);
+        let reloaded = JournalStore::load(&journal.path);
+        assert_eq!(reloaded.trades().len(), 2);
+        assert!(reloaded.tag("Breakout").is_some());
+        std::fs::remove_file(&journal.path).unwrap();
+    }
+
+    #[test]
+    fn insert_all_stores_nothing_when_one_trade_is_invalid() {
+        let mut journal = store("insert-invalid");
+        let result = journal.insert_all(Vec::new(), vec![trade("INFY", 1500.0), trade("TCS", 0.0)]);
+
+        assert!(result.is_err());
+        assert!(journal.trades().is_empty());
+        assert!(!journal.path.exists());
+    }
+
+    #[test]
+    fn validate_rejects_non_finite_numbers() {
+        let mut infinite = trade("INFY", f64::INFINITY);
+        assert!(infinite.validate().is_err());
+        infinite.executions[0].price = 1500.0;
+        infinite.executions[0].fees = f64::NAN;
+        assert!(infinite.validate().is_err());
+    }
+
+    #[test]
+    fn failed_saves_leave_the_journal_unchanged() {
+        let mut journal = store("rollback");
+        journal.insert(trade("INFY", 1500.0)).unwrap();
+        let saved = journal.path.clone();
+        // A path under a file can't be written
+        journal.path = saved.join("journal.json");
+
+        assert!(journal.insert(trade("TCS", 3500.0)).is_err());
+        let id = journal.trades()[0].id.clone();
+        assert!(journal.update(&id, trade("INFY", 1600.0)).is_err());
+        assert!(journal.remove(&id).is_err());
+        assert!(journal.remove_tag("Breakout").is_err());
+        assert_eq!(journal.trades().len(), 1);
+        assert_eq!(journal.trades()[0].executions[0].price, 1500.0);
+        assert!(journal.tag("Breakout").is_some());
+        std::fs::remove_file(&saved).unwrap();
+    }
+}
@@ -1 +1,592 @@
- 
+use std::collections::{BTreeMap, HashMap};
+use std::io::Write;
+use std::path::{Path, PathBuf};
+
+use chrono::{Datelike, Duration, NaiveDate, NaiveDateTime, NaiveTime, Timelike};
+use serde::{Deserialize, Serialize};
+
+use crate::models::calendar::TradingCalendar;
+use crate::utils::csv;
+
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct Bar {
+    pub time: NaiveDateTime,
+    pub open: f64,
+    pub high: f64,
+    pub low: f64,
+    pub close: f64,
+    pub volume: u64,
+}
+
+impl Bar {
+    pub fn date(&self) -> NaiveDate {
+        self.time.date()
+    }
+
+    fn merge(&mut self, next: &Bar) {
+        self.high = self.high.max(next.high);
+        self.low = self.low.min(next.low);
+        self.close = next.close;
+        self.volume += next.volume;
+    }
+}
+
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct Tick {
+    pub symbol: String,
+    /// Exchange time in IST.
+    pub time: NaiveDateTime,
+    pub price: f64,
+    #[serde(default)]
+    pub volume: u64,
+}
+
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub enum Timeframe {
+    #[serde(rename = "1m")]
+    M1,
+    #[serde(rename = "3m")]
+    M3,
+    #[serde(rename = "5m")]
+    M5,
+    #[serde(rename = "15m")]
+    M15,
+    #[serde(rename = "1h")]
+    H1,
+    #[default]
+    #[serde(rename = "1D")]
+    D1,
+    #[serde(rename = "1W")]
+    W1,
+    #[serde(rename = "1M")]
+    Mn1,
+}
+
+impl Timeframe {
+    /// Bucket width for intraday timeframes, `None` for daily and above.
+    pub fn minutes(self) -> Option<i64> {
+        match self {
+            Timeframe::M1 => Some(1),
+            Timeframe::M3 => Some(3),
+            Timeframe::M5 => Some(5),
+            Timeframe::M15 => Some(15),
+            Timeframe::H1 => Some(60),
+            Timeframe::D1 | Timeframe::W1 | Timeframe::Mn1 => None,
+        }
+    }
+}
+
+/// OHLCV history per symbol: daily bars as imported, plus 1 minute bars
+/// built from ticks or intraday imports. Other timeframes are resampled
+/// on demand.
+#[derive(Default)]
+pub struct MarketStore {
+    daily: HashMap<String, BTreeMap<NaiveDateTime, Bar>>,
+    minute: HashMap<String, BTreeMap<NaiveDateTime, Bar>>,
+}
+
+impl MarketStore {
+    /// Load every `*.csv` in `dir` as daily bars and every `*.csv` in
+    /// `dir/1m` as minute bars, each `symbol,date,open,high,low,close,volume`.
+    /// Files are read oldest first, so where two have a bar for the same
+    /// time the one written last wins.
+    pub fn load_dir(dir: &Path) -> Result<Self, String> {
+        let mut store = Self::default();
+        store.load_files(dir, Timeframe::D1)?;
+        if dir.join("1m").is_dir() {
+            store.load_files(&dir.join("1m"), Timeframe::M1)?;
+        }
+        Ok(store)
+    }
+
+    fn load_files(&mut self, dir: &Path, timeframe: Timeframe) -> Result<(), String> {
+        let entries = std::fs::read_dir(dir)
+            .map_err(|e| format!("failed to read {}: {}", dir.display(), e))?;
+        let mut files: Vec<_> = entries
+            .flatten()
+            .map(|entry| entry.path())
+            .filter(|path| path.extension().and_then(|e| e.to_str()) == Some("csv"))
+            .map(|path| {
+                let modified = std::fs::metadata(&path).and_then(|m| m.modified()).ok();
+                (modified, path)
+            })
+            .collect();
+        files.sort();
+
+        for (_, path) in files {
+            let text = std::fs::read_to_string(&path)
+                .map_err(|e| format!("failed to read {}: {}", path.display(), e))?;
+            let loaded = self.import_csv(&text, None, timeframe)?;
+            log::info!("Loaded {} bars from {}", loaded, path.display());
+        }
+
+        Ok(())
+    }
+
+    /// Import daily (`D1`) or minute (`M1`) bars. `default_symbol` is used
+    /// for files without a symbol column, e.g. single-symbol exports.
+    pub fn import_csv(
+        &mut self,
+        text: &str,
+        default_symbol: Option<&str>,
+        timeframe: Timeframe,
+    ) -> Result<usize, String> {
+        let target = match timeframe {
+            Timeframe::D1 => &mut self.daily,
+            Timeframe::M1 => &mut self.minute,
+            _ => return Err("only 1m and 1D bars can be imported".to_string()),
+        };
+        let table = csv::Table::parse(text);
+        let symbol_col = table.column(&["symbol", "tradingsymbol"]);
+        let date_col = table
+            .column(&["date", "timestamp", "time"])
+            .ok_or("bar file has no date column")?;
+        let columns = ["open", "high", "low", "close"].map(|name| table.column(&[name]));
+        let volume_col = table.column(&["volume"]);
+
+        if symbol_col.is_none() && default_symbol.is_none() {
+            return Err("bar file has no symbol column".to_string());
+        }
+
+        let mut loaded = 0;
+        for row in &table.rows {
+            let Some(symbol) = table.get(row, symbol_col).or(default_symbol) else {
+                continue;
+            };
+            let Some(time) = table.get(row, Some(date_col)).and_then(parse_time) else {
+                continue;
+            };
+            let prices: Option<Vec<f64>> = columns
+                .iter()
+                .map(|col| table.get(row, *col).and_then(|v| v.parse().ok()))
+                .collect();
+            let Some(prices) = prices else {
+                continue;
+            };
+
+            target.entry(symbol.to_string()).or_default().insert(
+                time,
+                Bar {
+                    time,
+                    open: prices[0],
+                    high: prices[1],
+                    low: prices[2],
+                    close: prices[3],
+                    volume: table
+                        .get(row, volume_col)
+                        .and_then(|v| v.parse::<f64>().ok())
+                        .map_or(0, |v| v as u64),
+                },
+            );
+            loaded += 1;
+        }
+
+        Ok(loaded)
+    }
+
+    /// Replace one symbol's history with its series in `fresh`, usually
+    /// the store just read back from disk after a bad import has been
+    /// corrected at the source. Only the timeframes `fresh` has bars for
+    /// are replaced; bars that only live in memory are kept. Returns the
+    /// number of bars replaced, 0 when `fresh` has none for the symbol.
+    pub fn replace_symbol(&mut self, fresh: MarketStore, symbol: &str) -> usize {
+        let mut reloaded = 0;
+        for (current, mut source) in [
+            (&mut self.daily, fresh.daily),
+            (&mut self.minute, fresh.minute),
+        ] {
+            if let Some(series) = source.remove(symbol) {
+                reloaded += series.len();
+                current.insert(symbol.to_string(), series);
+            }
+        }
+        reloaded
+    }
+
+    /// Merge bars parsed into `other`, appending them to the files under
+    /// `dir` first so they are loaded again after a restart.
+    pub fn absorb(&mut self, other: MarketStore, dir: &Path) -> Result<(), String> {
+        for (current, source, dir) in [
+            (&mut self.daily, other.daily, dir.to_path_buf()),
+            (&mut self.minute, other.minute, dir.join("1m")),
+        ] {
+            for (symbol, series) in source {
+                let bars: Vec<Bar> = series.values().cloned().collect();
+                append_bars(&dir, &symbol, &bars)?;
+                current.entry(symbol).or_default().extend(series);
+            }
+        }
+        Ok(())
+    }
+
+    /// Insert or overwrite a single daily bar.
+    pub fn upsert_daily(&mut self, symbol: &str, bar: Bar) {
+        self.daily
+            .entry(symbol.to_string())
+            .or_default()
+            .insert(bar.time, bar);
+    }
+
+    pub fn daily_symbols(&self) -> Vec<String> {
+        let mut symbols: Vec<String> = self.daily.keys().cloned().collect();
+        symbols.sort();
+        symbols
+    }
+
+    /// Daily bars exactly as stored, without minute-bar fallback.
+    pub fn daily_bars(&self, symbol: &str) -> Vec<Bar> {
+        slice(self.daily.get(symbol), None, None)
+    }
+
+    /// The latest daily bar, as [`MarketStore::bars`] would end with at
+    /// `Timeframe::D1`, without resampling the whole history.
+    pub fn last_daily_bar(&self, symbol: &str) -> Option<Bar> {
+        let daily = self
+            .daily
+            .get(symbol)
+            .and_then(|series| series.last_key_value())
+            .map(|(_, bar)| bar);
+        let minute_day = self
+            .minute
+            .get(symbol)
+            .and_then(|series| series.last_key_value())
+            .map(|(time, _)| time.date());
+        match (daily, minute_day) {
+            (daily, Some(date)) if daily.is_none_or(|bar| bar.date() < date) => {
+                let minutes = slice(self.minute.get(symbol), Some(date), Some(date));
+                resample_by(&minutes, |bar| bar.date().and_time(NaiveTime::MIN)).pop()
+            }
+            (daily, _) => daily.cloned(),
+        }
+    }
+
+    /// Fold a trade into its 1 minute bar. Ticks outside the normal
+    /// session are dropped so pre-open and closing-session prints don't
+    /// distort intraday bars. Returns the minute bar the tick went into.
+    pub fn ingest_tick(&mut self, tick: &Tick, calendar: &TradingCalendar) -> Option<Bar> {
+        let session = calendar.normal_session(tick.time.date())?;
+        let clock = tick.time.time();
+        if clock < session.start || clock >= session.end {
+            return None;
+        }
+
+        let minute = tick
+            .time
+            .with_second(0)
+            .and_then(|t| t.with_nanosecond(0))
+            .unwrap_or(tick.time);
+        let update = Bar {
+            time: minute,
+            open: tick.price,
+            high: tick.price,
+            low: tick.price,
+            close: tick.price,
+            volume: tick.volume,
+        };
+        let bar = self
+            .minute
+            .entry(tick.symbol.clone())
+            .or_default()
+            .entry(minute)
+            .and_modify(|bar| bar.merge(&update))
+            .or_insert(update);
+        Some(bar.clone())
+    }
+
+    /// Bars for any timeframe between `from` and `to` inclusive, oldest
+    /// first. Intraday buckets are anchored at the session open so a 1h
+    /// bar covers 09:15-10:15 and the last one is cut at the close. Daily
+    /// bars come from the daily store, falling back to minute bars for days
+    /// that only have intraday data; weeks and months roll up daily bars.
+    /// The first day of the trailing minute history holding at least
+    /// `count` minute bars of `symbol`, or `None` when it has fewer.
+    pub fn minute_start(&self, symbol: &str, count: usize) -> Option<NaiveDate> {
+        let minutes = self.minute.get(symbol)?;
+        let time = minutes.keys().rev().nth(count.checked_sub(1)?)?;
+        Some(time.date())
+    }
+
+    pub fn bars(
+        &self,
+        symbol: &str,
+        timeframe: Timeframe,
+        from: Option<NaiveDate>,
+        to: Option<NaiveDate>,
+        calendar: &TradingCalendar,
+    ) -> Vec<Bar> {
+        match timeframe.minutes() {
+            Some(width) => {
+                let minutes = slice(self.minute.get(symbol), from, to);
+                resample_intraday(&minutes, width, calendar)
+            }
+            None => {
+                let mut daily: BTreeMap<NaiveDate, Bar> = BTreeMap::new();
+                for bar in resample_by(&slice(self.minute.get(symbol), from, to), |bar| {
+                    bar.date().and_time(NaiveTime::MIN)
+                }) {
+                    daily.insert(bar.date(), bar);
+                }
+                for bar in slice(self.daily.get(symbol), from, to) {
+                    daily.insert(bar.date(), bar);
+                }
+                let daily: Vec<Bar> = daily.into_values().collect();
+
+                match timeframe {
+                    Timeframe::W1 => resample_by(&daily, |bar| {
+                        let offset = bar.date().weekday().num_days_from_monday();
+                        (bar.date() - Duration::days(offset as i64)).and_time(NaiveTime::MIN)
+                    }),
+                    Timeframe::Mn1 => resample_by(&daily, |bar| {
+                        bar.date().with_day(1).unwrap().and_time(NaiveTime::MIN)
+                    }),
+                    _ => daily,
+                }
+            }
+        }
+    }
+}
+
+/// The file under `dir` that bars of `symbol` entered through the app are
+/// appended to. Later rows for the same time replace earlier ones when
+/// the file is loaded.
+fn symbol_file(dir: &Path, symbol: &str) -> PathBuf {
+    let name: String = symbol
+        .chars()
+        .map(|c| match c {
+            'A'..='Z' | 'a'..='z' | '0'..='9' | '-' | '&' => c,
+            _ => '_',
+        })
+        .collect();
+    dir.join(format!("{}.csv", name))
+}
+
+/// Append `bars` of `symbol` to its file under `dir`, in the
+/// `symbol,date,open,high,low,close,volume` layout the loader reads.
+pub fn append_bars(dir: &Path, symbol: &str, bars: &[Bar]) -> Result<(), String> {
+    if bars.is_empty() {
+        return Ok(());
+    }
+    let path = symbol_file(dir, symbol);
+    let mut text = String::new();
+    if !path.exists() {
+        text.push_str("symbol,date,open,high,low,close,volume\n");
+    }
+    for bar in bars {
+        text.push_str(&csv::row(&[
+            symbol.to_string(),
+            bar.time.format("%Y-%m-%d %H:%M:%S").to_string(),
+            bar.open.to_string(),
+            bar.high.to_string(),
+            bar.low.to_string(),
+            bar.close.to_string(),
+            bar.volume.to_string(),
+        ]));
+        text.push('\n');
+    }
+    let write = || -> std::io::Result<()> {
+        std::fs::create_dir_all(dir)?;
+        let mut file = std::fs::OpenOptions::new()
+            .create(true)
+            .append(true)
+            .open(&path)?;
+        file.write_all(text.as_bytes())?;
+        file.sync_all()
+    };
+    write().map_err(|e| format!("failed to write {}: {}", path.display(), e))
+}
+
+fn slice(
+    series: Option<&BTreeMap<NaiveDateTime, Bar>>,
+    from: Option<NaiveDate>,
+    to: Option<NaiveDate>,
+) -> Vec<Bar> {
+    let Some(series) = series else {
+        return Vec::new();
+    };
+    let start = from.map_or(NaiveDateTime::MIN, |d| d.and_time(NaiveTime::MIN));
+    let end = to.map_or(NaiveDateTime::MAX, |d| d.and_hms_opt(23, 59, 59).unwrap());
+
+    series
+        .range(start..=end)
+        .map(|(_, bar)| bar.clone())
+        .collect()
+}
+
+fn resample_intraday(minutes: &[Bar], width: i64, calendar: &TradingCalendar) -> Vec<Bar> {
+    resample_by(minutes, |bar| {
+        let open = calendar
+            .normal_session(bar.date())
+            .map_or(NaiveTime::MIN, |session| session.start);
+        let elapsed = (bar.time.time() - open).num_minutes().max(0);
+        bar.date().and_time(open) + Duration::minutes(elapsed / width * width)
+    })
+}
+
+/// Merge consecutive bars sharing a bucket key. Each output bar is stamped
+/// with its bucket start.
+fn resample_by(bars: &[Bar], bucket: impl Fn(&Bar) -> NaiveDateTime) -> Vec<Bar> {
+    let mut out: Vec<Bar> = Vec::new();
+    for bar in bars {
+        let key = bucket(bar);
+        match out.last_mut() {
+            Some(last) if last.time == key => last.merge(bar),
+            _ => out.push(Bar {
+                time: key,
+                ..bar.clone()
+            }),
+        }
+    }
+    out
+}
+
+fn parse_time(value: &str) -> Option<NaiveDateTime> {
+    NaiveDateTime::parse_from_str(value, "%Y-%m-%d %H:%M:%S")
+        .or_else(|_| NaiveDateTime::parse_from_str(value, "%Y-%m-%dT%H:%M:%S"))
+        .ok()
+        .or_else(|| {
+            NaiveDate::parse_from_str(value, "%Y-%m-%d")
+                .ok()
+                .map(|d| d.and_time(NaiveTime::MIN))
+        })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn temp_dir(name: &str) -> std::path::PathBuf {
+        let dir = std::env::temp_dir().join(format!("slynqix-{}-{}", name, std::process::id()));
+        let _ = std::fs::remove_dir_all(&dir);
+        std::fs::create_dir_all(&dir).unwrap();
+        dir
+    }
+
+    fn day(date: &str) -> NaiveDateTime {
+        parse_time(date).unwrap()
+    }
+
+    #[test]
+    fn reload_replaces_series_from_disk() {
+        let dir = temp_dir("reload-disk");
+        std::fs::write(
+            dir.join("bars.csv"),
+            "symbol,date,open,high,low,close,volume\nINFY,2025-01-02,100,110,95,105,1000\n",
+        )
+        .unwrap();
+        let mut store = MarketStore::load_dir(&dir).unwrap();
+        store.upsert_daily(
+            "INFY",
+            Bar {
+                time: day("2025-01-02"),
+                open: 0.0,
+                high: 0.0,
+                low: 0.0,
+                close: 0.0,
+                volume: 0,
+            },
+        );
+
+        let fresh = MarketStore::load_dir(&dir).unwrap();
+        assert_eq!(store.replace_symbol(fresh, "INFY"), 1);
+        assert_eq!(store.daily_bars("INFY")[0].close, 105.0);
+        std::fs::remove_dir_all(&dir).unwrap();
+    }
+
+    #[test]
+    fn reload_keeps_series_missing_on_disk() {
+        let dir = temp_dir("reload-memory");
+        let mut store = MarketStore::default();
+        store
+            .import_csv(
+                "date,open,high,low,close\n2025-01-02,100,110,95,105\n",
+                Some("TCS"),
+                Timeframe::D1,
+            )
+            .unwrap();
+
+        let fresh = MarketStore::load_dir(&dir).unwrap();
+        assert_eq!(store.replace_symbol(fresh, "TCS"), 0);
+        assert_eq!(store.daily_bars("TCS").len(), 1);
+        std::fs::remove_dir_all(&dir).unwrap();
+    }
+
+    #[test]
+    fn last_daily_bar_prefers_a_later_minute_day() {
+        let mut store = MarketStore::default();
+        store
+            .import_csv(
+                "date,open,high,low,close\n2025-01-01,90,95,85,92\n2025-01-02,100,110,95,105\n",
+                Some("INFY"),
+                Timeframe::D1,
+            )
+            .unwrap();
+        assert_eq!(store.last_daily_bar("INFY").unwrap().close, 105.0);
+
+        store
+            .import_csv(
+                "date,open,high,low,close\n2025-01-03 09:15:00,106,108,104,107\n2025-01-03 09:16:00,107,111,106,110\n",
+                Some("INFY"),
+                Timeframe::M1,
+            )
+            .unwrap();
+        let last = store.last_daily_bar("INFY").unwrap();
+        assert_eq!(last.date(), NaiveDate::from_ymd_opt(2025, 1, 3).unwrap());
+        assert_eq!((last.open, last.high, last.close), (106.0, 111.0, 110.0));
+        assert!(store.last_daily_bar("TCS").is_none());
+    }
+
+    #[test]
+    fn absorbed_bars_are_loaded_again_and_the_last_write_wins() {
+        let dir = temp_dir("absorb");
+        let mut store = MarketStore::default();
+        for close in [105, 107] {
+            let mut parsed = MarketStore::default();
+            parsed
+                .import_csv(
+                    &format!(
+                        "date,open,high,low,close\n2025-01-02,100,110,95,{}\n",
+                        close
+                    ),
+                    Some("M&M"),
+                    Timeframe::D1,
+                )
+                .unwrap();
+            parsed
+                .import_csv(
+                    "date,open,high,low,close\n2025-01-02 09:15:00,100,101,99,100\n",
+                    Some("M&M"),
+                    Timeframe::M1,
+                )
+                .unwrap();
+            store.absorb(parsed, &dir).unwrap();
+        }
+        assert_eq!(store.daily_bars("M&M")[0].close, 107.0);
+
+        let loaded = MarketStore::load_dir(&dir).unwrap();
+        assert_eq!(loaded.daily_bars("M&M").len(), 1);
+        assert_eq!(loaded.daily_bars("M&M")[0].close, 107.0);
+        assert_eq!(
+            loaded.minute_start("M&M", 1),
+            Some(day("2025-01-02").date())
+        );
+        std::fs::remove_dir_all(&dir).unwrap();
+    }
+
+    #[test]
+    fn minute_start_covers_the_requested_bars() {
+        let mut store = MarketStore::default();
+        store
+            .import_csv(
+                "date,open,high,low,close\n2025-01-02 15:29:00,1,1,1,1\n2025-01-03 09:15:00,1,1,1,1\n2025-01-03 09:16:00,1,1,1,1\n",
+                Some("INFY"),
+                Timeframe::M1,
+            )
+            .unwrap();
+
+        let jan = |d| NaiveDate::from_ymd_opt(2025, 1, d).unwrap();
+        assert_eq!(store.minute_start("INFY", 2), Some(jan(3)));
+        assert_eq!(store.minute_start("INFY", 3), Some(jan(2)));
+        assert_eq!(store.minute_start("INFY", 4), None);
+        assert_eq!(store.minute_start("INFY", 0), None);
+    }
+}
@@ -0,0 +1,23 @@
+pub mod analytics;
+pub mod attachment;
+pub mod backup;
+pub mod calendar;
+pub mod charges;
+pub mod corporate_action;
+pub mod data_quality;
+pub mod fundamentals;
+pub mod instrument;
+pub mod journal;
+pub mod market;
+pub mod option_chain;
+pub mod option_pricing;
+pub mod portfolio;
+pub mod portfolio_risk;
+pub mod rebalance;
+pub mod risk_profile;
+pub mod rules;
+pub mod scoring;
+pub mod screener;
+pub mod tax;
+pub mod tradebook;
+pub mod valuation;
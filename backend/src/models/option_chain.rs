@@ -0,0 +1,1117 @@
+//! Option chains by underlying and expiry, one snapshot per trading day,
+//! and the positioning read from them: put-call ratios, max pain, open
+//! interest build-up and the strikes where open interest piles up.
+
+use std::collections::BTreeMap;
+use std::path::{Path, PathBuf};
+
+use chrono::NaiveDate;
+use serde::{Deserialize, Serialize};
+
+use crate::models::option_pricing::{self, Contract, Greeks, Model};
+use crate::models::tradebook;
+use crate::utils::csv;
+use crate::utils::persist;
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum OptionType {
+    Call,
+    Put,
+}
+
+impl OptionType {
+    pub fn parse(value: &str) -> Option<Self> {
+        match value.trim().to_uppercase().as_str() {
+            "CE" | "C" | "CALL" | "CALLS" => Some(OptionType::Call),
+            "PE" | "P" | "PUT" | "PUTS" => Some(OptionType::Put),
+            _ => None,
+        }
+    }
+}
+
+/// One side of a strike.
+#[derive(Clone, Debug, Default, Serialize, Deserialize)]
+pub struct OptionQuote {
+    pub ltp: Option<f64>,
+    /// Change in LTP from the previous close.
+    pub price_change: Option<f64>,
+    pub oi: f64,
+    pub oi_change: Option<f64>,
+    pub volume: f64,
+    /// Implied volatility, in percent.
+    pub iv: Option<f64>,
+}
+
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct StrikeRow {
+    pub strike: f64,
+    pub call: Option<OptionQuote>,
+    pub put: Option<OptionQuote>,
+}
+
+impl StrikeRow {
+    pub fn side(&self, option_type: OptionType) -> Option<&OptionQuote> {
+        match option_type {
+            OptionType::Call => self.call.as_ref(),
+            OptionType::Put => self.put.as_ref(),
+        }
+    }
+
+    fn side_mut(&mut self, option_type: OptionType) -> &mut Option<OptionQuote> {
+        match option_type {
+            OptionType::Call => &mut self.call,
+            OptionType::Put => &mut self.put,
+        }
+    }
+}
+
+/// The chain of one expiry as of one day's close (or the time of import).
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct ChainSnapshot {
+    pub underlying: String,
+    pub expiry: NaiveDate,
+    pub date: NaiveDate,
+    /// Underlying price when the chain was taken, if the source had it.
+    pub spot: Option<f64>,
+    /// Ascending by strike.
+    pub strikes: Vec<StrikeRow>,
+}
+
+impl ChainSnapshot {
+    /// Strike closest to `spot`.
+    pub fn atm_strike(&self, spot: f64) -> Option<f64> {
+        self.strikes
+            .iter()
+            .map(|row| row.strike)
+            .min_by(|a, b| (a - spot).abs().total_cmp(&(b - spot).abs()))
+    }
+
+    /// Mean of the call and put IV at the strike nearest `spot`, in percent.
+    pub fn atm_iv(&self, spot: f64, rate: f64) -> Option<f64> {
+        let years = years_to_expiry(self.date, self.expiry);
+        let atm = self.atm_strike(spot)?;
+        let row = self.strikes.iter().find(|row| row.strike == atm)?;
+        let ivs: Vec<f64> = [OptionType::Call, OptionType::Put]
+            .into_iter()
+            .filter_map(|option_type| {
+                quote_iv(row.side(option_type)?, option_type, atm, spot, years, rate)
+            })
+            .collect();
+        (!ivs.is_empty()).then(|| ivs.iter().sum::<f64>() / ivs.len() as f64)
+    }
+
+    /// Fill in OI and price changes the source left out, from the
+    /// previous snapshot of the same expiry. Returns how many were filled.
+    pub fn fill_changes(&mut self, previous: &ChainSnapshot) -> usize {
+        let mut filled = 0;
+        for row in &mut self.strikes {
+            let Some(before) = previous.strikes.iter().find(|r| r.strike == row.strike) else {
+                continue;
+            };
+            for option_type in [OptionType::Call, OptionType::Put] {
+                let (Some(quote), Some(old)) =
+                    (row.side_mut(option_type), before.side(option_type))
+                else {
+                    continue;
+                };
+                if quote.oi_change.is_none() {
+                    quote.oi_change = Some(quote.oi - old.oi);
+                    filled += 1;
+                }
+                if quote.price_change.is_none() {
+                    quote.price_change = quote.ltp.zip(old.ltp).map(|(now, then)| now - then);
+                }
+            }
+        }
+        filled
+    }
+}
+
+/// How price and open interest moved together at a strike.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum OiActivity {
+    /// Price and OI up: new buyers.
+    LongBuildUp,
+    /// Price down, OI up: new writers.
+    ShortBuildUp,
+    /// Price up, OI down: writers buying back.
+    ShortCovering,
+    /// Price and OI down: buyers exiting.
+    LongUnwinding,
+}
+
+impl OiActivity {
+    pub fn classify(quote: &OptionQuote) -> Option<Self> {
+        let price = quote.price_change.filter(|c| *c != 0.0)?;
+        let oi = quote.oi_change.filter(|c| *c != 0.0)?;
+        Some(match (price > 0.0, oi > 0.0) {
+            (true, true) => OiActivity::LongBuildUp,
+            (false, true) => OiActivity::ShortBuildUp,
+            (true, false) => OiActivity::ShortCovering,
+            (false, false) => OiActivity::LongUnwinding,
+        })
+    }
+
+    pub fn label(self) -> &'static str {
+        match self {
+            OiActivity::LongBuildUp => "Long build-up",
+            OiActivity::ShortBuildUp => "Short build-up",
+            OiActivity::ShortCovering => "Short covering",
+            OiActivity::LongUnwinding => "Long unwinding",
+        }
+    }
+}
+
+#[derive(Clone, Debug, Serialize)]
+pub struct ChainRow {
+    pub strike: f64,
+    pub call: Option<OptionQuote>,
+    pub put: Option<OptionQuote>,
+    pub call_build_up: Option<OiActivity>,
+    pub put_build_up: Option<OiActivity>,
+    pub call_greeks: Option<Greeks>,
+    pub put_greeks: Option<Greeks>,
+}
+
+/// Strikes of one side with the same build-up, and their OI change.
+#[derive(Clone, Debug, Serialize)]
+pub struct BuildUpTotal {
+    pub option_type: OptionType,
+    pub build_up: OiActivity,
+    pub label: &'static str,
+    pub strikes: usize,
+    pub oi_change: f64,
+}
+
+/// A strike where open interest is concentrated.
+#[derive(Clone, Debug, Serialize)]
+pub struct OiLevel {
+    pub strike: f64,
+    pub oi: f64,
+    pub oi_change: Option<f64>,
+}
+
+/// What option writers would pay out if the underlying expired at a
+/// strike.
+#[derive(Clone, Debug, Serialize)]
+pub struct PainPoint {
+    pub strike: f64,
+    pub payout: f64,
+}
+
+#[derive(Clone, Debug, Serialize)]
+pub struct ChainAnalytics {
+    pub spot: Option<f64>,
+    pub atm_strike: Option<f64>,
+    pub call_oi: f64,
+    pub put_oi: f64,
+    pub call_oi_change: f64,
+    pub put_oi_change: f64,
+    pub call_volume: f64,
+    pub put_volume: f64,
+    /// Put open interest over call open interest.
+    pub pcr: Option<f64>,
+    pub pcr_volume: Option<f64>,
+    /// Put over call OI added today, when both sides added.
+    pub pcr_change: Option<f64>,
+    /// Expiry price at which option buyers as a whole collect least.
+    pub max_pain: Option<f64>,
+    pub pain: Vec<PainPoint>,
+    /// Highest put OI strikes at or below spot, largest first.
+    pub supports: Vec<OiLevel>,
+    /// Highest call OI strikes at or above spot, largest first.
+    pub resistances: Vec<OiLevel>,
+    pub build_up: Vec<BuildUpTotal>,
+    /// Risk-free rate the Greeks were worked out at, in percent.
+    pub rate: f64,
+    pub days_to_expiry: i64,
+    /// Mean of the call and put IV at the ATM strike, in percent.
+    pub atm_iv: Option<f64>,
+    pub iv_rank: Option<f64>,
+    pub iv_percentile: Option<f64>,
+    /// Days of ATM IV history the rank and percentile compare against.
+    pub iv_days: usize,
+    pub notes: Vec<String>,
+}
+
+#[derive(Clone, Debug, Serialize)]
+pub struct ChainView {
+    pub underlying: String,
+    pub expiry: NaiveDate,
+    pub date: NaiveDate,
+    pub rows: Vec<ChainRow>,
+    pub analytics: ChainAnalytics,
+    pub iv_history: Vec<IvPoint>,
+}
+
+/// ATM implied volatility of the nearest expiry on one day.
+#[derive(Clone, Debug, Serialize)]
+pub struct IvPoint {
+    pub date: NaiveDate,
+    pub expiry: NaiveDate,
+    pub iv: f64,
+}
+
+/// Chains are taken at the close and contracts expire at the close, so a
+/// chain is a whole number of calendar days from expiry.
+pub fn years_to_expiry(date: NaiveDate, expiry: NaiveDate) -> f64 {
+    (expiry - date).num_days() as f64 / 365.0
+}
+
+/// A quote's IV in percent: the source's own, or solved from its LTP.
+fn quote_iv(
+    quote: &OptionQuote,
+    option_type: OptionType,
+    strike: f64,
+    spot: f64,
+    years: f64,
+    rate: f64,
+) -> Option<f64> {
+    quote.iv.or_else(|| {
+        let contract = contract(option_type, strike, spot, years, rate);
+        option_pricing::implied_volatility(&contract, quote.ltp?).map(|iv| iv * 100.0)
+    })
+}
+
+fn contract(option_type: OptionType, strike: f64, spot: f64, years: f64, rate: f64) -> Contract {
+    Contract {
+        option_type,
+        model: Model::BlackScholes,
+        underlying: spot,
+        strike,
+        years,
+        rate,
+        dividend_yield: 0.0,
+    }
+}
+
+/// One ATM IV per day from the expiry nearest after it, oldest first.
+/// Expiry-day chains are passed over: with hours left their IV says
+/// little about the next expiry.
+pub fn iv_history<'a>(
+    snapshots: impl Iterator<Item = &'a ChainSnapshot>,
+    spot: &dyn Fn(&ChainSnapshot) -> Option<f64>,
+    rate: f64,
+) -> Vec<IvPoint> {
+    let mut nearest: BTreeMap<NaiveDate, &ChainSnapshot> = BTreeMap::new();
+    for snapshot in snapshots.filter(|s| s.expiry > s.date) {
+        let entry = nearest.entry(snapshot.date).or_insert(snapshot);
+        if snapshot.expiry < entry.expiry {
+            *entry = snapshot;
+        }
+    }
+    nearest
+        .into_values()
+        .filter_map(|snapshot| {
+            Some(IvPoint {
+                date: snapshot.date,
+                expiry: snapshot.expiry,
+                iv: snapshot.atm_iv(spot(snapshot)?, rate)?,
+            })
+        })
+        .collect()
+}
+
+/// Strikes reported as support and resistance on each side.
+const LEVELS: usize = 3;
+
+/// Days of ATM IV before today's is ranked against them.
+const MIN_IV_HISTORY: usize = 5;
+
+fn ratio(puts: f64, calls: f64) -> Option<f64> {
+    (calls > 0.0).then(|| puts / calls)
+}
+
+fn top_levels(
+    snapshot: &ChainSnapshot,
+    option_type: OptionType,
+    keep: impl Fn(f64) -> bool,
+) -> Vec<OiLevel> {
+    let mut levels: Vec<OiLevel> = snapshot
+        .strikes
+        .iter()
+        .filter(|row| keep(row.strike))
+        .filter_map(|row| {
+            let quote = row.side(option_type)?;
+            (quote.oi > 0.0).then_some(OiLevel {
+                strike: row.strike,
+                oi: quote.oi,
+                oi_change: quote.oi_change,
+            })
+        })
+        .collect();
+    levels.sort_by(|a, b| b.oi.total_cmp(&a.oi));
+    levels.truncate(LEVELS);
+    levels
+}
+
+/// Total intrinsic value of every open contract if the underlying settles
+/// at each strike; max pain is the cheapest of these for writers.
+fn pain(snapshot: &ChainSnapshot) -> Vec<PainPoint> {
+    snapshot
+        .strikes
+        .iter()
+        .map(|settle| PainPoint {
+            strike: settle.strike,
+            payout: snapshot
+                .strikes
+                .iter()
+                .map(|row| {
+                    let call = row.call.as_ref().map_or(0.0, |q| q.oi)
+                        * (settle.strike - row.strike).max(0.0);
+                    let put = row.put.as_ref().map_or(0.0, |q| q.oi)
+                        * (row.strike - settle.strike).max(0.0);
+                    call + put
+                })
+                .sum(),
+        })
+        .collect()
+}
+
+/// Chain rows with their build-up and Greeks, and the chain's
+/// positioning. `spot` is used when the snapshot has none of its own;
+/// `rate` is a decimal, and `iv_history` the ATM IV of earlier days to
+/// rank today's against.
+pub fn analyze(
+    snapshot: &ChainSnapshot,
+    spot: Option<f64>,
+    rate: f64,
+    iv_history: Vec<IvPoint>,
+) -> ChainView {
+    let spot = snapshot.spot.or(spot);
+    let mut notes = Vec::new();
+    let years = years_to_expiry(snapshot.date, snapshot.expiry);
+    let mut solved = 0;
+    let mut priced = |quote: &Option<OptionQuote>, option_type: OptionType, strike: f64| {
+        let mut quote = quote.clone()?;
+        let spot = spot.filter(|_| years > 0.0);
+        let greeks = spot.and_then(|spot| {
+            let iv = quote_iv(&quote, option_type, strike, spot, years, rate)?;
+            if quote.iv.is_none() {
+                quote.iv = Some(iv);
+                solved += 1;
+            }
+            let contract = contract(option_type, strike, spot, years, rate);
+            Some(option_pricing::greeks(&contract, iv / 100.0))
+        });
+        Some((quote, greeks))
+    };
+    let rows: Vec<ChainRow> = snapshot
+        .strikes
+        .iter()
+        .map(|row| {
+            let (call, call_greeks) = priced(&row.call, OptionType::Call, row.strike).unzip();
+            let (put, put_greeks) = priced(&row.put, OptionType::Put, row.strike).unzip();
+            ChainRow {
+                strike: row.strike,
+                call_build_up: row.call.as_ref().and_then(OiActivity::classify),
+                put_build_up: row.put.as_ref().and_then(OiActivity::classify),
+                call,
+                put,
+                call_greeks: call_greeks.flatten(),
+                put_greeks: put_greeks.flatten(),
+            }
+        })
+        .collect();
+    if solved > 0 {
+        notes.push(format!(
+            "IV solved from LTP for {} contracts the source gave no IV for",
+            solved
+        ));
+    }
+    if years <= 0.0 {
+        notes.push("Expiry-day chain, so no Greeks".to_string());
+    }
+
+    let sum = |option_type: OptionType, field: fn(&OptionQuote) -> f64| {
+        snapshot
+            .strikes
+            .iter()
+            .filter_map(|row| row.side(option_type))
+            .map(field)
+            .sum::<f64>()
+    };
+    let (call_oi, put_oi) = (
+        sum(OptionType::Call, |q| q.oi),
+        sum(OptionType::Put, |q| q.oi),
+    );
+    let (call_volume, put_volume) = (
+        sum(OptionType::Call, |q| q.volume),
+        sum(OptionType::Put, |q| q.volume),
+    );
+    let (call_oi_change, put_oi_change) = (
+        sum(OptionType::Call, |q| q.oi_change.unwrap_or(0.0)),
+        sum(OptionType::Put, |q| q.oi_change.unwrap_or(0.0)),
+    );
+
+    let pain = pain(snapshot);
+    let max_pain = (call_oi + put_oi > 0.0)
+        .then(|| {
+            pain.iter()
+                .min_by(|a, b| a.payout.total_cmp(&b.payout))
+                .map(|p| p.strike)
+        })
+        .flatten();
+
+    let supports = top_levels(snapshot, OptionType::Put, |strike| {
+        spot.is_none_or(|s| strike <= s)
+    });
+    let resistances = top_levels(snapshot, OptionType::Call, |strike| {
+        spot.is_none_or(|s| strike >= s)
+    });
+    if spot.is_none() {
+        notes.push(
+            "No underlying price, so support and resistance are the highest OI strikes anywhere in the chain"
+                .to_string(),
+        );
+    }
+
+    let mut build_up: Vec<BuildUpTotal> = Vec::new();
+    for row in &rows {
+        for (option_type, quote, kind) in [
+            (OptionType::Call, &row.call, row.call_build_up),
+            (OptionType::Put, &row.put, row.put_build_up),
+        ] {
+            let (Some(quote), Some(kind)) = (quote, kind) else {
+                continue;
+            };
+            let change = quote.oi_change.unwrap_or(0.0);
+            match build_up
+                .iter_mut()
+                .find(|t| t.option_type == option_type && t.build_up == kind)
+            {
+                Some(total) => {
+                    total.strikes += 1;
+                    total.oi_change += change;
+                }
+                None => build_up.push(BuildUpTotal {
+                    option_type,
+                    build_up: kind,
+                    label: kind.label(),
+                    strikes: 1,
+                    oi_change: change,
+                }),
+            }
+        }
+    }
+    build_up.sort_by_key(|t| (t.option_type == OptionType::Put, t.build_up as u8));
+    if rows
+        .iter()
+        .all(|row| row.call_build_up.is_none() && row.put_build_up.is_none())
+    {
+        notes.push(
+            "No price and OI changes to classify build-up; import the previous day's chain or a file with change columns"
+                .to_string(),
+        );
+    }
+
+    let atm_iv = spot
+        .filter(|_| years > 0.0)
+        .and_then(|spot| snapshot.atm_iv(spot, rate));
+    let earlier: Vec<f64> = iv_history
+        .iter()
+        .filter(|p| p.date < snapshot.date)
+        .map(|p| p.iv)
+        .collect();
+    if atm_iv.is_some() && earlier.len() < MIN_IV_HISTORY {
+        notes.push(format!(
+            "IV rank and percentile need {} days of chains; {} so far",
+            MIN_IV_HISTORY,
+            earlier.len()
+        ));
+    }
+    let ranked = atm_iv.filter(|_| earlier.len() >= MIN_IV_HISTORY);
+
+    ChainView {
+        underlying: snapshot.underlying.clone(),
+        expiry: snapshot.expiry,
+        date: snapshot.date,
+        rows,
+        analytics: ChainAnalytics {
+            spot,
+            atm_strike: spot.and_then(|s| snapshot.atm_strike(s)),
+            call_oi,
+            put_oi,
+            call_oi_change,
+            put_oi_change,
+            call_volume,
+            put_volume,
+            pcr: ratio(put_oi, call_oi),
+            pcr_volume: ratio(put_volume, call_volume),
+            pcr_change: (put_oi_change > 0.0)
+                .then(|| ratio(put_oi_change, call_oi_change))
+                .flatten(),
+            max_pain,
+            pain,
+            supports,
+            resistances,
+            build_up,
+            rate: rate * 100.0,
+            days_to_expiry: (snapshot.expiry - snapshot.date).num_days(),
+            atm_iv,
+            iv_rank: ranked.and_then(|iv| option_pricing::iv_rank(&earlier, iv)),
+            iv_percentile: ranked.and_then(|iv| option_pricing::iv_percentile(&earlier, iv)),
+            iv_days: earlier.len(),
+            notes,
+        },
+        iv_history,
+    }
+}
+
+/// Numbers as exchanges export them: thousands separators, and "-" for
+/// strikes that didn't trade.
+fn parse_number(value: &str) -> Option<f64> {
+    value
+        .replace(',', "")
+        .trim()
+        .parse::<f64>()
+        .ok()
+        .filter(|v| v.is_finite())
+}
+
+/// What a row's trading symbol says about the contract, for files that
+/// list contracts by symbol instead of strike and type columns.
+pub struct ContractInfo {
+    pub underlying: String,
+    pub expiry: NaiveDate,
+    pub strike: f64,
+    pub option_type: OptionType,
+}
+
+#[derive(Clone, Debug, Default, Serialize)]
+pub struct ImportSummary {
+    pub snapshots: usize,
+    pub strikes: usize,
+    pub errors: Vec<String>,
+    pub notes: Vec<String>,
+}
+
+/// Defaults for what a chain file doesn't say itself.
+pub struct ImportDefaults {
+    pub underlying: Option<String>,
+    pub expiry: Option<NaiveDate>,
+    pub date: NaiveDate,
+    pub spot: Option<f64>,
+}
+
+/// Underlying, expiry and day of a chain being read.
+type ChainKey = (String, NaiveDate, NaiveDate);
+
+/// Spot and strikes of a chain being read.
+type PendingChain = (Option<f64>, BTreeMap<i64, StrikeRow>);
+
+/// Read a chain file in either of two layouts:
+///
+/// - the exchange's option chain download, calls on the left of a STRIKE
+///   column and puts mirrored on the right (a leading "CALLS,,PUTS" line
+///   is skipped), for one underlying and expiry given in `defaults`;
+/// - one contract per row, with strike and option type columns or a
+///   trading symbol `contract` can resolve, and optionally underlying,
+///   expiry, date and spot columns; a file spanning several days gives
+///   a snapshot for each.
+pub fn parse_csv(
+    text: &str,
+    defaults: &ImportDefaults,
+    contract: &dyn Fn(&str) -> Option<ContractInfo>,
+) -> Result<(Vec<ChainSnapshot>, ImportSummary), String> {
+    let mut rows = csv::parse(text);
+    let Some(header_row) = rows
+        .iter()
+        .take(3)
+        .position(|row| {
+            row.iter()
+                .any(|cell| cell.trim().eq_ignore_ascii_case("strike"))
+        })
+        .or_else(|| {
+            rows.iter().take(3).position(|row| {
+                row.iter().any(|cell| {
+                    matches!(
+                        cell.trim().to_lowercase().as_str(),
+                        "tradingsymbol" | "symbol" | "trading_symbol"
+                    )
+                })
+            })
+        })
+    else {
+        return Err("file has no strike or trading symbol column".to_string());
+    };
+    let lines_skipped = header_row + 1;
+    let headers: Vec<String> = rows
+        .drain(..=header_row)
+        .next_back()
+        .unwrap_or_default()
+        .into_iter()
+        .map(|h| h.trim().to_lowercase())
+        .collect();
+    let find = |names: &[&str], range: std::ops::Range<usize>| {
+        names.iter().find_map(|name| {
+            headers
+                .iter()
+                .enumerate()
+                .skip(range.start)
+                .take(range.end - range.start)
+                .find(|(_, h)| h == name)
+                .map(|(i, _)| i)
+        })
+    };
+    let find_last = |names: &[&str], range: std::ops::Range<usize>| {
+        names.iter().find_map(|name| {
+            headers
+                .iter()
+                .enumerate()
+                .skip(range.start)
+                .take(range.end - range.start)
+                .filter(|(_, h)| h == name)
+                .map(|(i, _)| i)
+                .next_back()
+        })
+    };
+
+    const LTP: [&str; 4] = ["ltp", "last_price", "last price", "close"];
+    const OI: [&str; 3] = ["oi", "open_interest", "open interest"];
+    const OI_CHANGE: [&str; 5] = [
+        "chng in oi",
+        "change in oi",
+        "oi_change",
+        "change_in_oi",
+        "chg in oi",
+    ];
+    const VOLUME: [&str; 2] = ["volume", "vol"];
+    const IV: [&str; 3] = ["iv", "implied_volatility", "implied volatility"];
+    const PRICE_CHANGE: [&str; 4] = ["chng", "change", "net_change", "price_change"];
+
+    let all = 0..headers.len();
+    let strike_col = find(&["strike", "strike price", "strike_price"], all.clone());
+    let type_col = find(
+        &[
+            "option_type",
+            "option type",
+            "type",
+            "instrument_type",
+            "right",
+        ],
+        all.clone(),
+    );
+    let wide = strike_col.is_some()
+        && type_col.is_none()
+        && find(&OI, 0..strike_col.unwrap_or(0)).is_some()
+        && find(&OI, strike_col.unwrap_or(0) + 1..headers.len()).is_some();
+
+    let get = |row: &[String], col: Option<usize>| -> Option<String> {
+        col.and_then(|i| row.get(i))
+            .map(|v| v.trim().to_string())
+            .filter(|v| !v.is_empty() && v != "-")
+    };
+    let number =
+        |row: &[String], col: Option<usize>| get(row, col).as_deref().and_then(parse_number);
+    // A side with no price, OI or volume wasn't listed at that strike
+    let quote = |row: &[String], ltp, oi, oi_change, volume, iv, change| {
+        let quote = OptionQuote {
+            ltp: number(row, ltp),
+            price_change: number(row, change),
+            oi: number(row, oi).unwrap_or(0.0),
+            oi_change: number(row, oi_change),
+            volume: number(row, volume).unwrap_or(0.0),
+            iv: number(row, iv).filter(|v| *v > 0.0),
+        };
+        (quote.ltp.is_some() || quote.oi > 0.0 || quote.volume > 0.0).then_some(quote)
+    };
+
+    let mut summary = ImportSummary::default();
+    let mut chains: BTreeMap<ChainKey, PendingChain> = BTreeMap::new();
+    // Strikes keyed in paise so they compare exactly
+    let key = |strike: f64| (strike * 100.0).round() as i64;
+
+    if wide {
+        let (Some(underlying), Some(expiry)) = (defaults.underlying.clone(), defaults.expiry)
+        else {
+            return Err(
+                "the exchange chain layout needs the underlying and expiry to be given".to_string(),
+            );
+        };
+        let strike = strike_col.unwrap_or(0);
+        let left = 0..strike;
+        let right = strike + 1..headers.len();
+        let calls = (
+            find(&LTP, left.clone()),
+            find(&OI, left.clone()),
+            find(&OI_CHANGE, left.clone()),
+            find(&VOLUME, left.clone()),
+            find(&IV, left.clone()),
+            find(&PRICE_CHANGE, left),
+        );
+        // Puts mirror the calls, so take the matching column nearest the
+        // right-hand end
+        let puts = (
+            find_last(&LTP, right.clone()),
+            find_last(&OI, right.clone()),
+            find_last(&OI_CHANGE, right.clone()),
+            find_last(&VOLUME, right.clone()),
+            find_last(&IV, right.clone()),
+            find_last(&PRICE_CHANGE, right),
+        );
+        let entry = chains
+            .entry((underlying.to_uppercase(), expiry, defaults.date))
+            .or_insert((defaults.spot, BTreeMap::new()));
+        for (index, row) in rows.iter().enumerate() {
+            let line = index + lines_skipped + 1;
+            let Some(strike) = number(row, Some(strike)) else {
+                if row.iter().any(|c| !c.trim().is_empty()) {
+                    summary.errors.push(format!("line {}: no strike", line));
+                }
+                continue;
+            };
+            let call = quote(row, calls.0, calls.1, calls.2, calls.3, calls.4, calls.5);
+            let put = quote(row, puts.0, puts.1, puts.2, puts.3, puts.4, puts.5);
+            entry.1.insert(key(strike), StrikeRow { strike, call, put });
+        }
+    } else {
+        let symbol_col = find(&["tradingsymbol", "trading_symbol", "symbol"], all.clone());
+        let underlying_col = find(&["underlying", "name"], all.clone());
+        let expiry_col = find(&["expiry", "expiry_date", "expiry date"], all.clone());
+        let date_col = find(&["date", "timestamp", "trade_date"], all.clone());
+        let spot_col = find(
+            &[
+                "spot",
+                "underlying_price",
+                "underlying value",
+                "underlying_value",
+            ],
+            all.clone(),
+        );
+        let columns = (
+            find(&LTP, all.clone()),
+            find(&OI, all.clone()),
+            find(&OI_CHANGE, all.clone()),
+            find(&VOLUME, all.clone()),
+            find(&IV, all.clone()),
+            find(&PRICE_CHANGE, all),
+        );
+        for (index, row) in rows.iter().enumerate() {
+            let line = index + lines_skipped + 1;
+            if row.iter().all(|c| c.trim().is_empty()) {
+                continue;
+            }
+            let resolved = get(row, symbol_col).and_then(|s| contract(&s));
+            let underlying = get(row, underlying_col)
+                .or_else(|| resolved.as_ref().map(|c| c.underlying.clone()))
+                .or_else(|| defaults.underlying.clone());
+            let expiry = match get(row, expiry_col) {
+                Some(value) => match tradebook::parse_time(None, Some(&value)) {
+                    Some(time) => Some(time.date()),
+                    None => {
+                        summary
+                            .errors
+                            .push(format!("line {}: unreadable expiry '{}'", line, value));
+                        continue;
+                    }
+                },
+                None => resolved.as_ref().map(|c| c.expiry).or(defaults.expiry),
+            };
+            let strike = number(row, strike_col).or_else(|| resolved.as_ref().map(|c| c.strike));
+            let option_type = get(row, type_col)
+                .and_then(|t| OptionType::parse(&t))
+                .or_else(|| resolved.as_ref().map(|c| c.option_type));
+            let (Some(underlying), Some(expiry), Some(strike), Some(option_type)) =
+                (underlying, expiry, strike, option_type)
+            else {
+                summary.errors.push(format!(
+                    "line {}: needs an underlying, expiry, strike and CE/PE type",
+                    line
+                ));
+                continue;
+            };
+            let date = match get(row, date_col) {
+                Some(value) => match tradebook::parse_time(None, Some(&value)) {
+                    Some(time) => time.date(),
+                    None => {
+                        summary
+                            .errors
+                            .push(format!("line {}: unreadable date '{}'", line, value));
+                        continue;
+                    }
+                },
+                None => defaults.date,
+            };
+            let entry = chains
+                .entry((underlying.to_uppercase(), expiry, date))
+                .or_insert((defaults.spot, BTreeMap::new()));
+            if let Some(spot) = number(row, spot_col) {
+                entry.0 = Some(spot);
+            }
+            let row_entry = entry.1.entry(key(strike)).or_insert(StrikeRow {
+                strike,
+                call: None,
+                put: None,
+            });
+            *row_entry.side_mut(option_type) = quote(
+                row, columns.0, columns.1, columns.2, columns.3, columns.4, columns.5,
+            );
+        }
+    }
+
+    let snapshots: Vec<ChainSnapshot> = chains
+        .into_iter()
+        .filter(|(_, (_, strikes))| !strikes.is_empty())
+        .map(
+            |((underlying, expiry, date), (spot, strikes))| ChainSnapshot {
+                underlying,
+                expiry,
+                date,
+                spot,
+                strikes: strikes.into_values().collect(),
+            },
+        )
+        .collect();
+    summary.snapshots = snapshots.len();
+    summary.strikes = snapshots.iter().map(|s| s.strikes.len()).sum();
+    Ok((snapshots, summary))
+}
+
+/// An underlying with stored chains, its expiries and last snapshot date.
+#[derive(Clone, Debug, Serialize)]
+pub struct UnderlyingSummary {
+    pub underlying: String,
+    pub expiries: Vec<NaiveDate>,
+    pub latest: NaiveDate,
+}
+
+#[derive(Serialize, Deserialize, Default)]
+struct OptionChainFile {
+    #[serde(default)]
+    snapshots: Vec<ChainSnapshot>,
+}
+
+/// Chain snapshots, persisted as JSON after every import.
+pub struct OptionChainStore {
+    path: PathBuf,
+    data: OptionChainFile,
+}
+
+impl OptionChainStore {
+    pub fn load(path: &Path) -> Self {
+        let data = persist::load_json(path);
+
+        Self {
+            path: path.to_path_buf(),
+            data,
+        }
+    }
+
+    fn save(&self) -> Result<(), String> {
+        persist::save_json_compact(&self.path, &self.data)
+    }
+
+    /// Every underlying with its expiries, soonest first.
+    pub fn underlyings(&self) -> Vec<UnderlyingSummary> {
+        let mut by_underlying: BTreeMap<&str, UnderlyingSummary> = BTreeMap::new();
+        for snapshot in &self.data.snapshots {
+            let entry = by_underlying
+                .entry(&snapshot.underlying)
+                .or_insert_with(|| UnderlyingSummary {
+                    underlying: snapshot.underlying.clone(),
+                    expiries: Vec::new(),
+                    latest: snapshot.date,
+                });
+            if !entry.expiries.contains(&snapshot.expiry) {
+                entry.expiries.push(snapshot.expiry);
+            }
+            entry.latest = entry.latest.max(snapshot.date);
+        }
+        by_underlying
+            .into_values()
+            .map(|mut summary| {
+                summary.expiries.sort();
+                summary
+            })
+            .collect()
+    }
+
+    /// Snapshots of one underlying, oldest first.
+    pub fn history(&self, underlying: &str) -> impl Iterator<Item = &ChainSnapshot> {
+        let underlying = underlying.to_uppercase();
+        self.data
+            .snapshots
+            .iter()
+            .filter(move |s| s.underlying == underlying)
+    }
+
+    /// Latest snapshot of an expiry on or before `date`.
+    pub fn snapshot(
+        &self,
+        underlying: &str,
+        expiry: NaiveDate,
+        date: Option<NaiveDate>,
+    ) -> Option<&ChainSnapshot> {
+        self.history(underlying)
+            .filter(|s| s.expiry == expiry && date.is_none_or(|d| s.date <= d))
+            .max_by_key(|s| s.date)
+    }
+
+    /// Store imported snapshots, replacing any of the same underlying,
+    /// expiry and day. Missing OI and price changes are worked out from
+    /// the previous day's snapshot where there is one.
+    pub fn import(
+        &mut self,
+        snapshots: Vec<ChainSnapshot>,
+        summary: &mut ImportSummary,
+    ) -> Result<(), String> {
+        for mut snapshot in snapshots {
+            let previous = self
+                .history(&snapshot.underlying)
+                .filter(|s| s.expiry == snapshot.expiry && s.date < snapshot.date)
+                .max_by_key(|s| s.date);
+            if let Some(previous) = previous {
+                let filled = snapshot.fill_changes(previous);
+                if filled > 0 {
+                    summary.notes.push(format!(
+                        "{} {}: OI change worked out from the {} chain for {} contracts",
+                        snapshot.underlying, snapshot.expiry, previous.date, filled
+                    ));
+                }
+            }
+            let replaced = self.data.snapshots.len();
+            self.data.snapshots.retain(|s| {
+                !(s.underlying == snapshot.underlying
+                    && s.expiry == snapshot.expiry
+                    && s.date == snapshot.date)
+            });
+            if self.data.snapshots.len() < replaced {
+                summary.notes.push(format!(
+                    "{} {}: replaced the chain already stored for {}",
+                    snapshot.underlying, snapshot.expiry, snapshot.date
+                ));
+            }
+            self.data.snapshots.push(snapshot);
+        }
+        self.data.snapshots.sort_by(|a, b| {
+            (&a.underlying, a.expiry, a.date).cmp(&(&b.underlying, b.expiry, b.date))
+        });
+        self.save()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn day(d: u32) -> NaiveDate {
+        NaiveDate::from_ymd_opt(2025, 3, d).unwrap()
+    }
+
+    fn quote(oi: f64, price_change: Option<f64>, oi_change: Option<f64>) -> Option<OptionQuote> {
+        Some(OptionQuote {
+            ltp: Some(10.0),
+            price_change,
+            oi,
+            oi_change,
+            volume: oi,
+            iv: None,
+        })
+    }
+
+    fn chain() -> ChainSnapshot {
+        ChainSnapshot {
+            underlying: "NIFTY".to_string(),
+            expiry: day(27),
+            date: day(3),
+            spot: None,
+            strikes: vec![
+                StrikeRow {
+                    strike: 100.0,
+                    call: quote(10.0, None, None),
+                    put: quote(60.0, Some(-2.0), Some(5.0)),
+                },
+                StrikeRow {
+                    strike: 110.0,
+                    call: quote(20.0, None, None),
+                    put: quote(30.0, None, None),
+                },
+                StrikeRow {
+                    strike: 120.0,
+                    call: quote(50.0, Some(1.0), Some(10.0)),
+                    put: quote(5.0, None, None),
+                },
+            ],
+        }
+    }
+
+    #[test]
+    fn positioning_from_open_interest() {
+        let view = analyze(&chain(), Some(112.0), 0.065, Vec::new());
+        let analytics = &view.analytics;
+
+        assert_eq!(analytics.pcr, Some(95.0 / 80.0));
+        assert_eq!(analytics.atm_strike, Some(110.0));
+        // Writers pay 400 at 100 and 120 but only 150 at 110
+        assert_eq!(analytics.max_pain, Some(110.0));
+        let supports: Vec<f64> = analytics.supports.iter().map(|l| l.strike).collect();
+        assert_eq!(supports, [100.0, 110.0]);
+        assert_eq!(analytics.resistances[0].strike, 120.0);
+        assert_eq!(analytics.resistances.len(), 1);
+
+        assert_eq!(view.rows[2].call_build_up, Some(OiActivity::LongBuildUp));
+        assert_eq!(view.rows[0].put_build_up, Some(OiActivity::ShortBuildUp));
+        assert_eq!(analytics.build_up.len(), 2);
+        assert!(!analytics.notes.iter().any(|n| n.contains("build-up")));
+    }
+
+    #[test]
+    fn exchange_layout_reads_calls_and_mirrored_puts() {
+        let text = "CALLS,,,,PUTS\n\
+                    OI,CHNG IN OI,LTP,STRIKE,LTP,CHNG IN OI,OI\n\
+                    \"1,200\",100,55.5,\"22,000.00\",40,-50,900\n\
+                    -,-,-,\"22,100.00\",80,20,300\n";
+        let defaults = ImportDefaults {
+            underlying: Some("nifty".to_string()),
+            expiry: Some(day(27)),
+            date: day(3),
+            spot: Some(22_050.0),
+        };
+        let (snapshots, summary) = parse_csv(text, &defaults, &|_| None).unwrap();
+
+        assert_eq!((summary.snapshots, summary.strikes), (1, 2));
+        let chain = &snapshots[0];
+        assert_eq!(chain.underlying, "NIFTY");
+        let first = &chain.strikes[0];
+        assert_eq!(first.strike, 22_000.0);
+        assert_eq!(first.call.as_ref().unwrap().oi, 1200.0);
+        assert_eq!(first.put.as_ref().unwrap().oi_change, Some(-50.0));
+        assert!(chain.strikes[1].call.is_none());
+    }
+
+    #[test]
+    fn imports_fill_changes_from_the_day_before_and_replace_the_same_day() {
+        let path =
+            std::env::temp_dir().join(format!("slynqix-option-chains-{}.json", std::process::id()));
+        let _ = std::fs::remove_file(&path);
+        let text = "date,underlying,expiry,strike,type,ltp,oi\n\
+                    2025-03-03,NIFTY,2025-03-27,100,CE,10,100\n\
+                    2025-03-04,NIFTY,2025-03-27,100,CE,12,150\n";
+        let defaults = ImportDefaults {
+            underlying: None,
+            expiry: None,
+            date: day(4),
+            spot: None,
+        };
+        let (snapshots, mut summary) = parse_csv(text, &defaults, &|_| None).unwrap();
+        assert_eq!(summary.snapshots, 2);
+        let mut store = OptionChainStore::load(&path);
+        store.import(snapshots.clone(), &mut summary).unwrap();
+
+        let latest = store.snapshot("nifty", day(27), None).unwrap();
+        let call = latest.strikes[0].call.as_ref().unwrap();
+        assert_eq!((call.oi_change, call.price_change), (Some(50.0), Some(2.0)));
+        assert!(summary.notes[0].contains("worked out from the 2025-03-03 chain"));
+
+        let mut again = ImportSummary::default();
+        store.import(snapshots[1..].to_vec(), &mut again).unwrap();
+        assert!(again.notes.iter().any(|n| n.contains("replaced")));
+        let reloaded = OptionChainStore::load(&path);
+        assert_eq!(reloaded.history("NIFTY").count(), 2);
+        assert_eq!(reloaded.underlyings()[0].latest, day(4));
+        std::fs::remove_file(&path).unwrap();
+    }
+}
@@ -0,0 +1,331 @@
+//! European option prices and Greeks under Black-Scholes (on the spot,
+//! with a dividend yield) and Black-76 (on a future or forward), implied
+//! volatility from a premium, and where today's IV sits in its history.
+
+use serde::{Deserialize, Serialize};
+
+use crate::models::option_chain::OptionType;
+
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum Model {
+    /// Priced off the spot, which earns the dividend yield.
+    #[default]
+    BlackScholes,
+    /// Priced off a futures price, which costs nothing to carry.
+    Black76,
+}
+
+/// Everything about a contract except its volatility. Rates and yields
+/// are continuously compounded decimals (0.065 for 6.5%).
+#[derive(Clone, Copy, Debug)]
+pub struct Contract {
+    pub option_type: OptionType,
+    pub model: Model,
+    /// Spot for Black-Scholes, futures price for Black-76.
+    pub underlying: f64,
+    pub strike: f64,
+    /// Time to expiry in years.
+    pub years: f64,
+    pub rate: f64,
+    /// Ignored by Black-76.
+    pub dividend_yield: f64,
+}
+
+/// Price and sensitivities for one contract. Theta is per calendar day,
+/// vega per volatility point and rho per percentage point of rate, the
+/// units traders quote them in.
+#[derive(Clone, Copy, Debug, Default, Serialize)]
+pub struct Greeks {
+    pub price: f64,
+    pub delta: f64,
+    pub gamma: f64,
+    pub theta: f64,
+    pub vega: f64,
+    pub rho: f64,
+}
+
+/// Volatilities the implied volatility solver searches between.
+const MIN_VOLATILITY: f64 = 1e-4;
+const MAX_VOLATILITY: f64 = 5.0;
+/// Premium error, in rupees, the solver stops at.
+const PRICE_TOLERANCE: f64 = 1e-6;
+const MAX_ITERATIONS: usize = 100;
+
+/// Rational approximation to the normal tail, highest power first.
+const NUMERATOR: [f64; 7] = [
+    0.035_262_496_599_891_1,
+    0.700_383_064_443_688,
+    6.373_962_203_531_65,
+    33.912_866_078_383,
+    112.079_291_497_871,
+    221.213_596_169_931,
+    220.206_867_912_376,
+];
+const DENOMINATOR: [f64; 8] = [
+    0.088_388_347_648_318_4,
+    1.755_667_163_182_64,
+    16.064_177_579_207,
+    86.780_732_202_946_1,
+    296.564_248_779_674,
+    637.333_633_378_831,
+    793.826_512_519_948,
+    440.413_735_824_752,
+];
+
+pub fn norm_pdf(x: f64) -> f64 {
+    (-0.5 * x * x).exp() / (2.0 * std::f64::consts::PI).sqrt()
+}
+
+/// Standard normal CDF, by West's double precision version of Hart's
+/// algorithm (about 1e-14 absolute error).
+pub fn norm_cdf(x: f64) -> f64 {
+    let z = x.abs();
+    let tail = if z > 37.0 {
+        0.0
+    } else if z < 7.071_067_811_865_47 {
+        let numerator = NUMERATOR.iter().fold(0.0, |acc, c| acc * z + c);
+        let denominator = DENOMINATOR.iter().fold(0.0, |acc, c| acc * z + c);
+        (-z * z / 2.0).exp() * numerator / denominator
+    } else {
+        let fraction = z + 1.0 / (z + 2.0 / (z + 3.0 / (z + 4.0 / (z + 0.65))));
+        (-z * z / 2.0).exp() / fraction / 2.506_628_274_631
+    };
+    if x > 0.0 {
+        1.0 - tail
+    } else {
+        tail
+    }
+}
+
+impl Contract {
+    /// Cost of carry: what holding the underlying earns over the rate.
+    fn carry(&self) -> f64 {
+        match self.model {
+            Model::BlackScholes => self.rate - self.dividend_yield,
+            Model::Black76 => 0.0,
+        }
+    }
+
+    fn is_call(&self) -> bool {
+        self.option_type == OptionType::Call
+    }
+
+    fn intrinsic(&self) -> f64 {
+        if self.is_call() {
+            (self.underlying - self.strike).max(0.0)
+        } else {
+            (self.strike - self.underlying).max(0.0)
+        }
+    }
+
+    /// Lowest and highest premiums any volatility can give.
+    fn bounds(&self) -> (f64, f64) {
+        let underlying = self.underlying * ((self.carry() - self.rate) * self.years).exp();
+        let strike = self.strike * (-self.rate * self.years).exp();
+        if self.is_call() {
+            ((underlying - strike).max(0.0), underlying)
+        } else {
+            ((strike - underlying).max(0.0), strike)
+        }
+    }
+
+    fn is_valid(&self) -> bool {
+        self.underlying > 0.0
+            && self.strike > 0.0
+            && self.years.is_finite()
+            && self.rate.is_finite()
+            && self.dividend_yield.is_finite()
+    }
+}
+
+/// Premium of `contract` at annualised volatility `volatility` (0.15 for
+/// 15%).
+pub fn price(contract: &Contract, volatility: f64) -> f64 {
+    greeks(contract, volatility).price
+}
+
+/// Price and Greeks by the generalised Black-Scholes formula, which covers
+/// both models through the cost of carry. At expiry, or with no
+/// volatility, the option is worth its (discounted) intrinsic value.
+pub fn greeks(contract: &Contract, volatility: f64) -> Greeks {
+    let c = contract;
+    if !c.is_valid() {
+        return Greeks::default();
+    }
+    let carry = c.carry();
+    let rate = c.rate;
+    let t = c.years.max(0.0);
+    let sign = if c.is_call() { 1.0 } else { -1.0 };
+    let carry_discount = ((carry - rate) * t).exp();
+    let discount = (-rate * t).exp();
+
+    if t == 0.0 || volatility <= 0.0 {
+        let forward = c.underlying * (carry * t).exp();
+        let in_the_money = sign * (forward - c.strike) > 0.0;
+        let price = if t == 0.0 {
+            c.intrinsic()
+        } else {
+            (sign * (c.underlying * carry_discount - c.strike * discount)).max(0.0)
+        };
+        return Greeks {
+            price,
+            delta: if in_the_money {
+                sign * carry_discount
+            } else {
+                0.0
+            },
+            ..Greeks::default()
+        };
+    }
+
+    let root_t = t.sqrt();
+    let d1 = ((c.underlying / c.strike).ln() + (carry + volatility * volatility / 2.0) * t)
+        / (volatility * root_t);
+    let d2 = d1 - volatility * root_t;
+    let (n1, n2) = (norm_cdf(sign * d1), norm_cdf(sign * d2));
+    let price = sign * (c.underlying * carry_discount * n1 - c.strike * discount * n2);
+    let density = c.underlying * carry_discount * norm_pdf(d1);
+
+    let theta = -density * volatility / (2.0 * root_t)
+        - sign * (carry - rate) * c.underlying * carry_discount * n1
+        - sign * rate * c.strike * discount * n2;
+    // A futures price doesn't move with the rate, so Black-76 rho only
+    // reflects discounting the premium
+    let rho = match c.model {
+        Model::BlackScholes => sign * c.strike * t * discount * n2,
+        Model::Black76 => -t * price,
+    };
+
+    Greeks {
+        price,
+        delta: sign * carry_discount * n1,
+        gamma: carry_discount * norm_pdf(d1) / (c.underlying * volatility * root_t),
+        theta: theta / 365.0,
+        vega: density * root_t / 100.0,
+        rho: rho / 100.0,
+    }
+}
+
+/// Volatility at which `contract` is worth `premium`, or `None` when the
+/// premium is outside what any volatility gives (below intrinsic, say).
+/// Newton's method from the Brenner-Subrahmanyam estimate, falling back
+/// to bisection when a step leaves the bracket.
+pub fn implied_volatility(contract: &Contract, premium: f64) -> Option<f64> {
+    if !contract.is_valid() || contract.years <= 0.0 || !premium.is_finite() {
+        return None;
+    }
+    let (lowest, highest) = contract.bounds();
+    if premium <= lowest || premium >= highest {
+        return None;
+    }
+
+    let (mut low, mut high) = (MIN_VOLATILITY, MAX_VOLATILITY);
+    if price(contract, high) < premium {
+        return None;
+    }
+    let estimate =
+        (2.0 * std::f64::consts::PI / contract.years).sqrt() * premium / contract.underlying;
+    let mut volatility = estimate.clamp(0.05, 2.0);
+    for _ in 0..MAX_ITERATIONS {
+        let greeks = greeks(contract, volatility);
+        let error = greeks.price - premium;
+        if error.abs() < PRICE_TOLERANCE {
+            return Some(volatility);
+        }
+        if error > 0.0 {
+            high = volatility;
+        } else {
+            low = volatility;
+        }
+        let vega = greeks.vega * 100.0;
+        let step = volatility - error / vega;
+        volatility = if vega > 1e-10 && step > low && step < high {
+            step
+        } else {
+            (low + high) / 2.0
+        };
+        if high - low < 1e-10 {
+            return Some(volatility);
+        }
+    }
+    Some(volatility)
+}
+
+/// How far `current` sits between the lowest and highest IV of `history`,
+/// 0 to 100.
+pub fn iv_rank(history: &[f64], current: f64) -> Option<f64> {
+    let low = history
+        .iter()
+        .copied()
+        .fold(f64::INFINITY, f64::min)
+        .min(current);
+    let high = history
+        .iter()
+        .copied()
+        .fold(f64::NEG_INFINITY, f64::max)
+        .max(current);
+    (!history.is_empty() && high > low).then(|| (current - low) / (high - low) * 100.0)
+}
+
+/// Share of `history` with a lower IV than `current`, 0 to 100.
+pub fn iv_percentile(history: &[f64], current: f64) -> Option<f64> {
+    (!history.is_empty()).then(|| {
+        history.iter().filter(|v| **v < current).count() as f64 / history.len() as f64 * 100.0
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn contract(option_type: OptionType, model: Model) -> Contract {
+        Contract {
+            option_type,
+            model,
+            underlying: 100.0,
+            strike: 100.0,
+            years: 1.0,
+            rate: 0.05,
+            dividend_yield: 0.0,
+        }
+    }
+
+    #[test]
+    fn prices_black_scholes() {
+        let call = price(&contract(OptionType::Call, Model::BlackScholes), 0.2);
+        let put = price(&contract(OptionType::Put, Model::BlackScholes), 0.2);
+        assert!((call - 10.4506).abs() < 1e-4);
+        assert!((put - 5.5735).abs() < 1e-4);
+    }
+
+    #[test]
+    fn prices_black_76() {
+        // Hull's futures option example
+        let put = Contract {
+            underlying: 20.0,
+            strike: 20.0,
+            years: 4.0 / 12.0,
+            rate: 0.09,
+            ..contract(OptionType::Put, Model::Black76)
+        };
+        assert!((price(&put, 0.25) - 1.1166).abs() < 1e-4);
+    }
+
+    #[test]
+    fn implied_volatility_recovers_the_pricing_volatility() {
+        for option_type in [OptionType::Call, OptionType::Put] {
+            for model in [Model::BlackScholes, Model::Black76] {
+                let contract = Contract {
+                    strike: 110.0,
+                    ..contract(option_type, model)
+                };
+                let premium = price(&contract, 0.35);
+                let solved = implied_volatility(&contract, premium).unwrap();
+                assert!((solved - 0.35).abs() < 1e-6);
+            }
+        }
+        let call = contract(OptionType::Call, Model::BlackScholes);
+        assert_eq!(implied_volatility(&call, 1.0), None);
+    }
+}
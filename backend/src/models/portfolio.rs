@@ -0,0 +1,971 @@
+//! Long-term holdings built from buy and sell transactions. Lots are
+//! matched first-in-first-out, splits and bonuses from the corporate
+//! action store adjust the share count on their ex-date, and dividends
+//! are credited to whoever held the stock the day before. Returns are
+//! money-weighted (XIRR) so they can be compared with what the same cash
+//! flows would have earned in an index.
+
+use std::collections::{BTreeMap, HashMap, HashSet, VecDeque};
+use std::path::{Path, PathBuf};
+
+use chrono::NaiveDate;
+use serde::{Deserialize, Serialize};
+
+use crate::models::corporate_action::{ActionKind, CorporateAction};
+use crate::models::tradebook;
+use crate::utils::csv;
+use crate::utils::persist;
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum TransactionKind {
+    Buy,
+    Sell,
+}
+
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum TransactionSource {
+    #[default]
+    Manual,
+    Csv,
+    Journal,
+}
+
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct Transaction {
+    #[serde(default)]
+    pub id: String,
+    pub symbol: String,
+    pub kind: TransactionKind,
+    pub date: NaiveDate,
+    pub quantity: f64,
+    pub price: f64,
+    #[serde(default)]
+    pub fees: f64,
+    #[serde(default)]
+    pub source: TransactionSource,
+    /// Journal trade and execution a transaction was imported from, used
+    /// to skip re-imports.
+    #[serde(default)]
+    pub reference: Option<String>,
+}
+
+impl Transaction {
+    pub fn validate(&self) -> Result<(), String> {
+        if self.symbol.trim().is_empty() {
+            return Err("transaction needs a symbol".to_string());
+        }
+        if !self.quantity.is_finite() || self.quantity <= 0.0 {
+            return Err("quantity must be positive".to_string());
+        }
+        if !self.price.is_finite() || self.price <= 0.0 {
+            return Err("price must be positive".to_string());
+        }
+        if !self.fees.is_finite() || self.fees < 0.0 {
+            return Err("fees cannot be negative".to_string());
+        }
+        Ok(())
+    }
+
+    /// Cash in (positive) or out (negative) of the investor's pocket.
+    fn cash_flow(&self) -> f64 {
+        let amount = self.quantity * self.price;
+        match self.kind {
+            TransactionKind::Buy => -(amount + self.fees),
+            TransactionKind::Sell => amount - self.fees,
+        }
+    }
+}
+
+/// Shares held per share before a split or bonus; none for actions that
+/// don't change the count.
+fn share_multiplier(kind: &ActionKind) -> Option<f64> {
+    match kind {
+        ActionKind::Split {
+            old_face_value,
+            new_face_value,
+        } if *new_face_value > 0.0 => Some(old_face_value / new_face_value),
+        ActionKind::Bonus {
+            new_shares,
+            held_shares,
+        } if *held_shares > 0 => Some((held_shares + new_shares) as f64 / *held_shares as f64),
+        _ => None,
+    }
+}
+
+/// Quantities below this are rounding left over from fractional
+/// adjustments and count as flat.
+const DUST: f64 = 1e-6;
+
+struct Lot {
+    quantity: f64,
+    /// Per share, buy fees included.
+    cost: f64,
+}
+
+enum Event<'a> {
+    Action(&'a CorporateAction),
+    Trade(&'a Transaction),
+}
+
+/// Running state of every symbol's lots while transactions and corporate
+/// actions are replayed in date order.
+#[derive(Default)]
+struct Ledger {
+    lots: HashMap<String, VecDeque<Lot>>,
+    realized: HashMap<String, f64>,
+    dividends: HashMap<String, f64>,
+    flows: HashMap<String, Vec<(NaiveDate, f64)>>,
+    first_bought: HashMap<String, NaiveDate>,
+    notes: Vec<String>,
+}
+
+impl Ledger {
+    fn quantity(&self, symbol: &str) -> f64 {
+        self.lots.get(symbol).map_or(0.0, |lots| {
+            lots.iter().fold(0.0, |sum, lot| sum + lot.quantity)
+        })
+    }
+
+    fn apply(&mut self, date: NaiveDate, event: &Event) {
+        match event {
+            Event::Action(action) => {
+                let held = self.quantity(&action.symbol);
+                if held <= DUST {
+                    return;
+                }
+                if let ActionKind::Dividend { amount } = action.kind {
+                    let income = amount * held;
+                    *self.dividends.entry(action.symbol.clone()).or_default() += income;
+                    self.flows
+                        .entry(action.symbol.clone())
+                        .or_default()
+                        .push((date, income));
+                } else if let Some(multiplier) = share_multiplier(&action.kind) {
+                    for lot in self.lots.entry(action.symbol.clone()).or_default() {
+                        lot.quantity *= multiplier;
+                        lot.cost /= multiplier;
+                    }
+                }
+            }
+            Event::Trade(transaction) => {
+                let symbol = transaction.symbol.clone();
+                let flow = match transaction.kind {
+                    TransactionKind::Buy => {
+                        self.first_bought.entry(symbol.clone()).or_insert(date);
+                        self.lots.entry(symbol.clone()).or_default().push_back(Lot {
+                            quantity: transaction.quantity,
+                            cost: (transaction.quantity * transaction.price + transaction.fees)
+                                / transaction.quantity,
+                        });
+                        transaction.cash_flow()
+                    }
+                    TransactionKind::Sell => self.sell(date, transaction),
+                };
+                self.flows.entry(symbol).or_default().push((date, flow));
+            }
+        }
+    }
+
+    /// Match a sale against the oldest lots and return the cash it
+    /// brought in. Only the part covered by lots counts.
+    fn sell(&mut self, date: NaiveDate, transaction: &Transaction) -> f64 {
+        let lots = self.lots.entry(transaction.symbol.clone()).or_default();
+        let proceeds = transaction.price - transaction.fees / transaction.quantity;
+        let mut remaining = transaction.quantity;
+        let mut gain = 0.0;
+        while remaining > DUST {
+            let Some(lot) = lots.front_mut() else {
+                break;
+            };
+            let matched = remaining.min(lot.quantity);
+            gain += (proceeds - lot.cost) * matched;
+            lot.quantity -= matched;
+            remaining -= matched;
+            if lot.quantity <= DUST {
+                lots.pop_front();
+            }
+        }
+        if remaining > DUST {
+            self.notes.push(format!(
+                "{}: sale of {} on {} is more than was held; the excess is ignored",
+                transaction.symbol,
+                format_quantity(transaction.quantity),
+                date
+            ));
+        }
+        *self.realized.entry(transaction.symbol.clone()).or_default() += gain;
+        proceeds * (transaction.quantity - remaining.max(0.0))
+    }
+
+    /// Every symbol's cash flows in date order.
+    fn all_flows(&self) -> Vec<(NaiveDate, f64)> {
+        let mut flows: Vec<(NaiveDate, f64)> = self.flows.values().flatten().copied().collect();
+        flows.sort_by_key(|(date, _)| *date);
+        flows
+    }
+}
+
+fn format_quantity(quantity: f64) -> String {
+    if (quantity - quantity.round()).abs() < DUST {
+        format!("{:.0}", quantity)
+    } else {
+        format!("{:.4}", quantity)
+    }
+}
+
+/// Transactions and the corporate actions of the symbols traded, in the
+/// order they take effect. Actions go first on their ex-date, as a buy on
+/// the ex-date doesn't get the dividend or bonus.
+fn events<'a>(
+    transactions: &'a [Transaction],
+    actions: &'a [CorporateAction],
+) -> Vec<(NaiveDate, Event<'a>)> {
+    let symbols: HashSet<&str> = transactions.iter().map(|t| t.symbol.as_str()).collect();
+    let mut list: Vec<(NaiveDate, u8, Event)> = actions
+        .iter()
+        .filter(|a| symbols.contains(a.symbol.as_str()))
+        .map(|a| (a.ex_date, 0, Event::Action(a)))
+        .chain(transactions.iter().map(|t| (t.date, 1, Event::Trade(t))))
+        .collect();
+    list.sort_by_key(|(date, order, _)| (*date, *order));
+    list.into_iter()
+        .map(|(date, _, event)| (date, event))
+        .collect()
+}
+
+/// A close and the one before it, for value and day change.
+#[derive(Clone, Copy, Debug)]
+pub struct Quote {
+    pub close: f64,
+    pub previous: Option<f64>,
+    pub date: NaiveDate,
+}
+
+#[derive(Clone, Debug, Serialize)]
+pub struct Holding {
+    pub symbol: String,
+    pub name: String,
+    pub sector: String,
+    pub quantity: f64,
+    /// Per share, fees included, adjusted for splits and bonuses.
+    pub avg_cost: f64,
+    pub invested: f64,
+    pub price: Option<f64>,
+    pub price_date: Option<NaiveDate>,
+    /// At the last close; at cost when there is no price.
+    pub value: f64,
+    pub day_change: Option<f64>,
+    pub unrealized: Option<f64>,
+    pub unrealized_pct: Option<f64>,
+    pub realized: f64,
+    pub dividends: f64,
+    /// Percent of the portfolio's value.
+    pub weight: f64,
+    pub xirr: Option<f64>,
+    pub first_bought: Option<NaiveDate>,
+}
+
+/// A symbol sold out completely, kept for its realized gain.
+#[derive(Clone, Debug, Serialize)]
+pub struct ClosedHolding {
+    pub symbol: String,
+    pub realized: f64,
+    pub dividends: f64,
+    pub xirr: Option<f64>,
+}
+
+#[derive(Clone, Debug, Serialize)]
+pub struct SectorWeight {
+    pub sector: String,
+    pub value: f64,
+    pub weight: f64,
+    pub holdings: usize,
+}
+
+#[derive(Clone, Debug, Default, Serialize)]
+pub struct PortfolioSummary {
+    pub holdings: usize,
+    pub invested: f64,
+    pub value: f64,
+    pub day_change: f64,
+    pub day_change_pct: Option<f64>,
+    pub unrealized: f64,
+    pub unrealized_pct: Option<f64>,
+    pub realized: f64,
+    pub dividends: f64,
+    /// Unrealized plus realized plus dividends.
+    pub total_gain: f64,
+    /// Annualized money-weighted return, in percent.
+    pub xirr: Option<f64>,
+    /// Holdings without a close, valued at cost.
+    pub unpriced: Vec<String>,
+}
+
+/// What the portfolio's cash flows would have earned in an index.
+#[derive(Clone, Debug, Serialize)]
+pub struct BenchmarkComparison {
+    pub symbol: String,
+    pub value: f64,
+    pub gain: f64,
+    pub xirr: Option<f64>,
+    /// Portfolio XIRR less the benchmark's, in percentage points.
+    pub excess_xirr: Option<f64>,
+}
+
+#[derive(Clone, Debug, Serialize)]
+pub struct PortfolioReport {
+    pub as_of: NaiveDate,
+    pub summary: PortfolioSummary,
+    pub holdings: Vec<Holding>,
+    pub closed: Vec<ClosedHolding>,
+    pub sectors: Vec<SectorWeight>,
+    pub benchmarks: Vec<BenchmarkComparison>,
+    pub notes: Vec<String>,
+}
+
+/// Name and sector shown for a symbol.
+pub struct Listing {
+    pub name: String,
+    pub sector: String,
+}
+
+/// Annualized internal rate of return of dated cash flows, in percent.
+/// Needs money going both in and out; Newton's method is tried first and
+/// bisection over -99%..1000% is the fallback.
+pub fn xirr(flows: &[(NaiveDate, f64)]) -> Option<f64> {
+    let start = flows.iter().map(|(date, _)| *date).min()?;
+    if !flows.iter().any(|(_, v)| *v > 0.0) || !flows.iter().any(|(_, v)| *v < 0.0) {
+        return None;
+    }
+    let years: Vec<(f64, f64)> = flows
+        .iter()
+        .map(|(date, value)| ((*date - start).num_days() as f64 / 365.0, *value))
+        .collect();
+    let npv = |rate: f64| {
+        years
+            .iter()
+            .fold(0.0, |sum, (t, v)| sum + v / (1.0 + rate).powf(*t))
+    };
+    let slope = |rate: f64| {
+        years
+            .iter()
+            .fold(0.0, |sum, (t, v)| sum - t * v / (1.0 + rate).powf(t + 1.0))
+    };
+
+    let mut rate = 0.1;
+    for _ in 0..50 {
+        let (value, derivative) = (npv(rate), slope(rate));
+        if derivative == 0.0 || !derivative.is_finite() {
+            break;
+        }
+        let next = rate - value / derivative;
+        if !next.is_finite() || next <= -0.99 {
+            break;
+        }
+        if (next - rate).abs() < 1e-9 {
+            return Some(next * 100.0);
+        }
+        rate = next;
+    }
+
+    let (mut low, mut high) = (-0.99, 10.0);
+    if npv(low).signum() == npv(high).signum() {
+        return None;
+    }
+    for _ in 0..200 {
+        let mid = (low + high) / 2.0;
+        if npv(mid).signum() == npv(low).signum() {
+            low = mid;
+        } else {
+            high = mid;
+        }
+    }
+    Some((low + high) / 2.0 * 100.0)
+}
+
+/// Index closes by date, oldest first.
+pub type CloseSeries = BTreeMap<NaiveDate, f64>;
+
+/// Last close on or before `date`.
+fn close_on(series: &CloseSeries, date: NaiveDate) -> Option<f64> {
+    series.range(..=date).next_back().map(|(_, close)| *close)
+}
+
+/// Invest every purchase in the index and take every sale and dividend
+/// out of it, at the close on or before each date. Returns the units held
+/// after each flow, or none if the index has no close before a flow.
+fn benchmark_units(
+    flows: &[(NaiveDate, f64)],
+    series: &CloseSeries,
+) -> Option<Vec<(NaiveDate, f64)>> {
+    let mut units = 0.0;
+    let mut path = Vec::with_capacity(flows.len());
+    for (date, amount) in flows {
+        units -= amount / close_on(series, *date)?;
+        path.push((*date, units));
+    }
+    Some(path)
+}
+
+fn benchmark(
+    symbol: &str,
+    flows: &[(NaiveDate, f64)],
+    series: &CloseSeries,
+    as_of: NaiveDate,
+    portfolio_xirr: Option<f64>,
+) -> Option<BenchmarkComparison> {
+    let units = benchmark_units(flows, series)?.last()?.1;
+    let value = units * close_on(series, as_of)?;
+    let mut terminal = flows.to_vec();
+    terminal.push((as_of, value));
+    let gain = terminal.iter().fold(0.0, |sum, (_, v)| sum + v);
+    let xirr = xirr(&terminal);
+    Some(BenchmarkComparison {
+        symbol: symbol.to_string(),
+        value,
+        gain,
+        excess_xirr: portfolio_xirr.zip(xirr).map(|(p, b)| p - b),
+        xirr,
+    })
+}
+
+/// Value the holdings on `as_of` and compare them with each benchmark.
+pub fn report(
+    transactions: &[Transaction],
+    actions: &[CorporateAction],
+    as_of: NaiveDate,
+    quote: &dyn Fn(&str) -> Option<Quote>,
+    listing: &dyn Fn(&str) -> Listing,
+    benchmarks: &[(String, CloseSeries)],
+) -> PortfolioReport {
+    let mut ledger = Ledger::default();
+    for (date, event) in events(transactions, actions) {
+        if date <= as_of {
+            ledger.apply(date, &event);
+        }
+    }
+
+    let mut symbols: Vec<&String> = ledger.flows.keys().collect();
+    symbols.sort();
+    let mut summary = PortfolioSummary::default();
+    let mut holdings = Vec::new();
+    let mut closed = Vec::new();
+    let all_flows = ledger.all_flows();
+    let mut previous_value = 0.0;
+
+    for symbol in symbols {
+        let flows = &ledger.flows[symbol];
+        let realized = ledger.realized.get(symbol).copied().unwrap_or(0.0);
+        let dividends = ledger.dividends.get(symbol).copied().unwrap_or(0.0);
+        summary.realized += realized;
+        summary.dividends += dividends;
+
+        let quantity = ledger.quantity(symbol);
+        if quantity <= DUST {
+            closed.push(ClosedHolding {
+                symbol: symbol.clone(),
+                realized,
+                dividends,
+                xirr: xirr(flows),
+            });
+            continue;
+        }
+
+        let invested = ledger.lots[symbol]
+            .iter()
+            .fold(0.0, |sum, lot| sum + lot.quantity * lot.cost);
+        let quote = quote(symbol);
+        let price = quote.map(|q| q.close);
+        let value = price.map_or(invested, |p| p * quantity);
+        if price.is_none() {
+            summary.unpriced.push(symbol.clone());
+        }
+        // Yesterday's close times today's quantity; a trade made today
+        // shows its whole move since that close
+        let day_change = quote.and_then(|q| q.previous.map(|prev| (q.close - prev) * quantity));
+        previous_value += value - day_change.unwrap_or(0.0);
+        let unrealized = price.map(|_| value - invested);
+
+        let mut holding_flows = flows.clone();
+        holding_flows.push((as_of, value));
+        let Listing { name, sector } = listing(symbol);
+        summary.invested += invested;
+        summary.value += value;
+        summary.day_change += day_change.unwrap_or(0.0);
+        summary.unrealized += unrealized.unwrap_or(0.0);
+        holdings.push(Holding {
+            symbol: symbol.clone(),
+            name,
+            sector,
+            quantity,
+            avg_cost: invested / quantity,
+            invested,
+            price,
+            price_date: quote.map(|q| q.date),
+            value,
+            day_change,
+            unrealized,
+            unrealized_pct: unrealized
+                .filter(|_| invested > 0.0)
+                .map(|u| u / invested * 100.0),
+            realized,
+            dividends,
+            weight: 0.0,
+            xirr: xirr(&holding_flows),
+            first_bought: ledger.first_bought.get(symbol).copied(),
+        });
+    }
+
+    let mut by_sector: BTreeMap<String, (f64, usize)> = BTreeMap::new();
+    for holding in &mut holdings {
+        if summary.value > 0.0 {
+            holding.weight = holding.value / summary.value * 100.0;
+        }
+        let entry = by_sector.entry(holding.sector.clone()).or_default();
+        entry.0 += holding.value;
+        entry.1 += 1;
+    }
+    let mut sectors: Vec<SectorWeight> = by_sector
+        .into_iter()
+        .map(|(sector, (value, count))| SectorWeight {
+            weight: if summary.value > 0.0 {
+                value / summary.value * 100.0
+            } else {
+                0.0
+            },
+            sector,
+            value,
+            holdings: count,
+        })
+        .collect();
+    sectors.sort_by(|a, b| b.value.total_cmp(&a.value));
+    holdings.sort_by(|a, b| b.value.total_cmp(&a.value));
+
+    let mut terminal = all_flows.clone();
+    terminal.push((as_of, summary.value));
+    summary.holdings = holdings.len();
+    summary.xirr = xirr(&terminal);
+    summary.total_gain = summary.unrealized + summary.realized + summary.dividends;
+    summary.unrealized_pct =
+        (summary.invested > 0.0).then(|| summary.unrealized / summary.invested * 100.0);
+    summary.day_change_pct =
+        (previous_value > 0.0).then(|| summary.day_change / previous_value * 100.0);
+
+    let mut notes = ledger.notes;
+    if !summary.unpriced.is_empty() {
+        notes.push(format!(
+            "No market data for {}; valued at cost",
+            summary.unpriced.join(", ")
+        ));
+    }
+    let benchmarks = benchmarks
+        .iter()
+        .filter_map(|(symbol, series)| {
+            let comparison = benchmark(symbol, &all_flows, series, as_of, summary.xirr);
+            if comparison.is_none() && !all_flows.is_empty() {
+                notes.push(format!(
+                    "{} has no history back to the first purchase, so it is left out",
+                    symbol
+                ));
+            }
+            comparison
+        })
+        .collect();
+
+    PortfolioReport {
+        as_of,
+        summary,
+        holdings,
+        closed,
+        sectors,
+        benchmarks,
+        notes,
+    }
+}
+
+/// Portfolio and benchmark value at one close.
+#[derive(Clone, Debug, Serialize)]
+pub struct ValuePoint {
+    pub date: NaiveDate,
+    /// Cost of the lots held.
+    pub invested: f64,
+    pub value: f64,
+    pub benchmark: Option<f64>,
+}
+
+/// Daily value of the holdings at each of `dates`, using the last close on
+/// or before the day, next to the value of the same cash flows invested
+/// in `benchmark`.
+pub fn history(
+    transactions: &[Transaction],
+    actions: &[CorporateAction],
+    dates: &[NaiveDate],
+    closes: &HashMap<String, CloseSeries>,
+    benchmark: Option<&CloseSeries>,
+) -> Vec<ValuePoint> {
+    let events = events(transactions, actions);
+    // The benchmark follows every flow up to the last day, dividends too
+    let mut full = Ledger::default();
+    let last = dates.last().copied().unwrap_or(NaiveDate::MIN);
+    for (date, event) in events.iter().filter(|(date, _)| *date <= last) {
+        full.apply(*date, event);
+    }
+    let units = benchmark.and_then(|series| benchmark_units(&full.all_flows(), series));
+
+    let mut ledger = Ledger::default();
+    let mut next = 0;
+    let mut points = Vec::with_capacity(dates.len());
+    for date in dates {
+        while next < events.len() && events[next].0 <= *date {
+            ledger.apply(events[next].0, &events[next].1);
+            next += 1;
+        }
+        let (invested, value) =
+            ledger
+                .lots
+                .iter()
+                .fold((0.0, 0.0), |(cost, value), (symbol, lots)| {
+                    let quantity = lots.iter().fold(0.0, |sum, lot| sum + lot.quantity);
+                    let lot_cost = lots
+                        .iter()
+                        .fold(0.0, |sum, lot| sum + lot.quantity * lot.cost);
+                    let market = closes
+                        .get(symbol)
+                        .and_then(|series| close_on(series, *date))
+                        .map_or(lot_cost, |close| close * quantity);
+                    (cost + lot_cost, value + market)
+                });
+        let benchmark_value = match (&units, benchmark) {
+            (Some(path), Some(series)) => {
+                let held = path
+                    .iter()
+                    .take_while(|(day, _)| day <= date)
+                    .last()
+                    .map_or(0.0, |(_, units)| *units);
+                close_on(series, *date).map(|close| held * close)
+            }
+            _ => None,
+        };
+        points.push(ValuePoint {
+            date: *date,
+            invested,
+            value,
+            benchmark: benchmark_value,
+        });
+    }
+    points
+}
+
+#[derive(Clone, Debug, Default, Serialize)]
+pub struct ImportSummary {
+    pub added: usize,
+    pub skipped: usize,
+    pub errors: Vec<String>,
+    pub notes: Vec<String>,
+}
+
+/// Numbers as brokers export them, with thousands separators.
+fn parse_number(value: &str) -> Option<f64> {
+    value
+        .replace([',', '₹'], "")
+        .trim()
+        .parse::<f64>()
+        .ok()
+        .filter(|v| v.is_finite())
+}
+
+/// Transactions in a holdings or tradebook CSV. A holdings export has a
+/// symbol, quantity and average cost per row and becomes one purchase
+/// each, dated `default_date` unless the file has a date column.
+pub fn parse_csv(
+    text: &str,
+    default_date: NaiveDate,
+) -> Result<(Vec<Transaction>, ImportSummary), String> {
+    let table = csv::Table::parse(text);
+    let symbol_col = table
+        .column(&["symbol", "instrument", "tradingsymbol", "stock", "scrip"])
+        .ok_or("file has no symbol column")?;
+    let quantity_col = table
+        .column(&["quantity", "qty", "qty.", "shares", "units"])
+        .ok_or("file has no quantity column")?;
+    let price_col = table
+        .column(&[
+            "price",
+            "avg_cost",
+            "avg. cost",
+            "average_price",
+            "avg price",
+            "buy_price",
+            "buy price",
+        ])
+        .ok_or("file has no price or average cost column")?;
+    let date_col = table.column(&["date", "buy_date", "trade_date", "purchase_date"]);
+    let kind_col = table.column(&["kind", "side", "type", "trade_type"]);
+    let fees_col = table.column(&["fees", "charges", "brokerage"]);
+
+    let mut summary = ImportSummary::default();
+    let mut transactions = Vec::new();
+    let mut undated = 0;
+    for (index, row) in table.rows.iter().enumerate() {
+        // Header is line 1
+        let line = index + 2;
+        let Some(symbol) = table.get(row, Some(symbol_col)) else {
+            continue;
+        };
+        let kind = match table.get(row, kind_col).map(|k| k.to_lowercase()) {
+            None => TransactionKind::Buy,
+            Some(k) if k == "buy" || k == "b" => TransactionKind::Buy,
+            Some(k) if k == "sell" || k == "s" => TransactionKind::Sell,
+            Some(k) => {
+                summary
+                    .errors
+                    .push(format!("line {}: unknown side '{}'", line, k));
+                continue;
+            }
+        };
+        let date = match table.get(row, date_col) {
+            Some(value) => match tradebook::parse_time(None, Some(value)) {
+                Some(time) => time.date(),
+                None => {
+                    summary
+                        .errors
+                        .push(format!("line {}: unreadable date '{}'", line, value));
+                    continue;
+                }
+            },
+            None => {
+                undated += 1;
+                default_date
+            }
+        };
+        let transaction = Transaction {
+            id: String::new(),
+            symbol: symbol.to_uppercase(),
+            kind,
+            date,
+            quantity: table
+                .get(row, Some(quantity_col))
+                .and_then(parse_number)
+                .unwrap_or(0.0),
+            price: table
+                .get(row, Some(price_col))
+                .and_then(parse_number)
+                .unwrap_or(0.0),
+            fees: table
+                .get(row, fees_col)
+                .and_then(parse_number)
+                .unwrap_or(0.0),
+            source: TransactionSource::Csv,
+            reference: None,
+        };
+        match transaction.validate() {
+            Ok(()) => transactions.push(transaction),
+            Err(e) => summary.errors.push(format!("line {}: {}", line, e)),
+        }
+    }
+    if undated > 0 {
+        summary.notes.push(format!(
+            "{} row{} had no date and {} recorded as bought on {}; returns for them start that day",
+            undated,
+            if undated == 1 { "" } else { "s" },
+            if undated == 1 { "is" } else { "are" },
+            default_date
+        ));
+    }
+    Ok((transactions, summary))
+}
+
+#[derive(Serialize, Deserialize, Default)]
+struct PortfolioFile {
+    #[serde(default)]
+    next_id: u64,
+    #[serde(default)]
+    transactions: Vec<Transaction>,
+}
+
+/// Portfolio transactions, persisted as JSON after every change.
+pub struct PortfolioStore {
+    path: PathBuf,
+    data: PortfolioFile,
+}
+
+impl PortfolioStore {
+    pub fn load(path: &Path) -> Self {
+        let data = persist::load_json(path);
+
+        Self {
+            path: path.to_path_buf(),
+            data,
+        }
+    }
+
+    fn save(&self) -> Result<(), String> {
+        persist::save_json(&self.path, &self.data)
+    }
+
+    fn next_id(&mut self, prefix: &str) -> String {
+        self.data.next_id += 1;
+        format!("{}{}", prefix, self.data.next_id)
+    }
+
+    /// Oldest first.
+    pub fn transactions(&self) -> &[Transaction] {
+        &self.data.transactions
+    }
+
+    fn sort(&mut self) {
+        self.data.transactions.sort_by_key(|t| t.date);
+    }
+
+    pub fn insert(&mut self, mut transaction: Transaction) -> Result<Transaction, String> {
+        transaction.id = self.next_id("p");
+        self.data.transactions.push(transaction.clone());
+        self.sort();
+        self.save()?;
+        Ok(transaction)
+    }
+
+    pub fn update(
+        &mut self,
+        id: &str,
+        mut transaction: Transaction,
+    ) -> Result<Option<Transaction>, String> {
+        let Some(existing) = self.data.transactions.iter_mut().find(|t| t.id == id) else {
+            return Ok(None);
+        };
+        transaction.id = id.to_string();
+        transaction.source = existing.source;
+        transaction.reference = existing.reference.clone();
+        *existing = transaction.clone();
+        self.sort();
+        self.save()?;
+        Ok(Some(transaction))
+    }
+
+    pub fn remove(&mut self, id: &str) -> Result<bool, String> {
+        let before = self.data.transactions.len();
+        self.data.transactions.retain(|t| t.id != id);
+        if self.data.transactions.len() == before {
+            return Ok(false);
+        }
+        self.save()?;
+        Ok(true)
+    }
+
+    /// Add imported transactions, skipping fills whose broker or journal
+    /// reference is already stored, and rows identical to a transaction
+    /// stored before this import. Each stored row absorbs at most one
+    /// identical row, so repeated partial fills in one file are all kept.
+    pub fn import(
+        &mut self,
+        transactions: Vec<Transaction>,
+        summary: &mut ImportSummary,
+    ) -> Result<(), String> {
+        let stored = self.data.transactions.len();
+        let mut matched = vec![false; stored];
+        for mut transaction in transactions {
+            let duplicate = match &transaction.reference {
+                Some(reference) => self
+                    .data
+                    .transactions
+                    .iter()
+                    .any(|t| t.reference.as_ref() == Some(reference)),
+                None => {
+                    let same = self.data.transactions[..stored]
+                        .iter()
+                        .zip(&matched)
+                        .position(|(t, matched)| {
+                            !matched
+                                && t.symbol == transaction.symbol
+                                && t.kind == transaction.kind
+                                && t.date == transaction.date
+                                && (t.quantity - transaction.quantity).abs() < DUST
+                                && (t.price - transaction.price).abs() < DUST
+                        });
+                    if let Some(index) = same {
+                        matched[index] = true;
+                    }
+                    same.is_some()
+                }
+            };
+            if duplicate {
+                summary.skipped += 1;
+                continue;
+            }
+            transaction.id = self.next_id("p");
+            self.data.transactions.push(transaction);
+            summary.added += 1;
+        }
+        if summary.added > 0 {
+            self.sort();
+            self.save()?;
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn buy(quantity: f64, price: f64) -> Transaction {
+        Transaction {
+            id: String::new(),
+            symbol: "ITC".to_string(),
+            kind: TransactionKind::Buy,
+            date: NaiveDate::from_ymd_opt(2025, 6, 2).unwrap(),
+            quantity,
+            price,
+            fees: 0.0,
+            source: TransactionSource::Csv,
+            reference: None,
+        }
+    }
+
+    #[test]
+    fn import_keeps_identical_fills_from_one_file() {
+        let path = std::env::temp_dir().join(format!(
+            "slynqix-portfolio-import-{}.json",
+            std::process::id()
+        ));
+        let _ = std::fs::remove_file(&path);
+        let mut store = PortfolioStore::load(&path);
+        let file = vec![buy(50.0, 410.0), buy(50.0, 410.0), buy(20.0, 411.0)];
+
+        let mut first = ImportSummary::default();
+        store.import(file.clone(), &mut first).unwrap();
+        assert_eq!((first.added, first.skipped), (3, 0));
+
+        // Re-importing the same file adds nothing; a longer one adds the
+        // extra fill only
+        let mut again = ImportSummary::default();
+        let mut longer = file;
+        longer.push(buy(50.0, 410.0));
+        store.import(longer, &mut again).unwrap();
+        assert_eq!((again.added, again.skipped), (1, 3));
+        assert_eq!(store.data.transactions.len(), 4);
+        std::fs::remove_file(&path).unwrap();
+    }
+
+    #[test]
+    fn xirr_annualizes_dated_flows() {
+        let day = |y, m, d| NaiveDate::from_ymd_opt(y, m, d).unwrap();
+        let rate = xirr(&[(day(2023, 1, 1), -1000.0), (day(2024, 1, 1), 1100.0)]).unwrap();
+        assert!((rate - 10.0).abs() < 1e-6);
+
+        // Half the money in for half the year earns the same 10% a year
+        let rate = xirr(&[
+            (day(2023, 1, 1), -1000.0),
+            (day(2023, 7, 2), -1000.0),
+            (
+                day(2024, 1, 1),
+                1100.0 + 1000.0 * 1.1_f64.powf(183.0 / 365.0),
+            ),
+        ])
+        .unwrap();
+        assert!((rate - 10.0).abs() < 1e-6);
+        assert_eq!(xirr(&[(day(2023, 1, 1), -1000.0)]), None);
+    }
+}
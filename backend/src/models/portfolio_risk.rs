@@ -0,0 +1,439 @@
+//! Risk of the current holdings, measured on what they would have returned
+//! at today's weights over recent history. Daily returns come from
+//! total-return adjusted closes, so splits and dividends don't show up as
+//! losses. VaR and expected shortfall are one-day losses, as positive
+//! percentages of the portfolio value.
+
+use std::collections::BTreeSet;
+
+use chrono::NaiveDate;
+use serde::Serialize;
+
+use crate::models::portfolio::CloseSeries;
+use crate::utils::indicators::{covariance, mean, std_dev};
+
+const YEAR: f64 = 252.0;
+
+/// Confidence levels VaR is reported at, with their one-tailed normal
+/// quantiles for the parametric estimate.
+const CONFIDENCE: [(f64, f64); 2] = [(95.0, 1.645), (99.0, 2.326)];
+
+/// Fewer daily returns than this make the tail estimates meaningless.
+const MIN_OBSERVATIONS: usize = 30;
+
+/// Days in the rolling volatility window.
+pub const ROLLING_WINDOW: usize = 20;
+
+/// Single-stock limit when there is no risk profile.
+pub const DEFAULT_MAX_POSITION: f64 = 10.0;
+const MAX_SECTOR_WEIGHT: f64 = 30.0;
+/// Correlation above which two holdings count as one bet.
+const HIGH_CORRELATION: f64 = 0.8;
+/// Share of the portfolio variance that one holding shouldn't exceed.
+const MAX_RISK_CONTRIBUTION: f64 = 30.0;
+
+/// A priced holding and its adjusted daily closes.
+pub struct Position {
+    pub symbol: String,
+    pub sector: String,
+    /// Percent of the portfolio value.
+    pub weight: f64,
+    pub closes: CloseSeries,
+}
+
+#[derive(Clone, Debug, Serialize)]
+pub struct ValueAtRisk {
+    pub confidence: f64,
+    /// Loss exceeded on the worst days in the window.
+    pub historical: f64,
+    /// Loss from a normal distribution with the window's mean and spread.
+    pub parametric: f64,
+    /// Average loss on the days beyond the historical VaR.
+    pub expected_shortfall: f64,
+    pub historical_amount: f64,
+    pub parametric_amount: f64,
+    pub expected_shortfall_amount: f64,
+}
+
+#[derive(Clone, Debug, Serialize)]
+pub struct VolatilityPoint {
+    pub date: NaiveDate,
+    /// Annualized, in percent.
+    pub portfolio: f64,
+    pub benchmark: Option<f64>,
+}
+
+#[derive(Clone, Debug, Serialize)]
+pub struct HoldingRisk {
+    pub symbol: String,
+    pub weight: f64,
+    pub volatility: Option<f64>,
+    pub beta: Option<f64>,
+    /// Percent of the portfolio variance that comes from this holding.
+    pub risk_contribution: Option<f64>,
+}
+
+/// Pairwise correlation of daily returns; `values[i][j]` pairs
+/// `symbols[i]` with `symbols[j]`.
+#[derive(Clone, Debug, Default, Serialize)]
+pub struct CorrelationMatrix {
+    pub symbols: Vec<String>,
+    pub values: Vec<Vec<f64>>,
+}
+
+#[derive(Clone, Debug, Default, Serialize)]
+pub struct RiskReport {
+    pub from: Option<NaiveDate>,
+    pub to: Option<NaiveDate>,
+    /// Daily returns used.
+    pub observations: usize,
+    pub benchmark: Option<String>,
+    pub volatility: Option<f64>,
+    pub beta: Option<f64>,
+    pub benchmark_correlation: Option<f64>,
+    pub value_at_risk: Vec<ValueAtRisk>,
+    pub rolling_volatility: Vec<VolatilityPoint>,
+    pub holdings: Vec<HoldingRisk>,
+    pub correlation: CorrelationMatrix,
+    /// 1 / sum of squared weights: the number of equal holdings with the
+    /// same concentration.
+    pub effective_holdings: Option<f64>,
+    pub warnings: Vec<String>,
+    pub notes: Vec<String>,
+}
+
+fn correlation(a: &[f64], b: &[f64]) -> Option<f64> {
+    let spread = std_dev(a)? * std_dev(b)?;
+    (spread > 0.0).then(|| covariance(a, b).map(|c| c / spread))?
+}
+
+fn beta(returns: &[f64], market: &[f64]) -> Option<f64> {
+    let variance = covariance(market, market)?;
+    (variance > 0.0).then(|| covariance(returns, market).map(|c| c / variance))?
+}
+
+fn annualized(returns: &[f64]) -> Option<f64> {
+    std_dev(returns).map(|sd| sd * YEAR.sqrt() * 100.0)
+}
+
+/// The last `days + 1` trading days seen in any of `series`, starting
+/// where every one of them has a price.
+pub fn shared_dates(series: &[&CloseSeries], days: usize) -> Vec<NaiveDate> {
+    let start = series.iter().filter_map(|s| s.keys().next().copied()).max();
+    let dates: BTreeSet<NaiveDate> = series
+        .iter()
+        .flat_map(|s| s.keys().copied())
+        .filter(|date| start.is_some_and(|start| *date >= start))
+        .collect();
+    let skip = dates.len().saturating_sub(days + 1);
+    dates.into_iter().skip(skip).collect()
+}
+
+/// Returns between consecutive `dates`, using the last close on or before
+/// each. None when the series doesn't reach back to the first date.
+pub fn returns_on(series: &CloseSeries, dates: &[NaiveDate]) -> Option<Vec<f64>> {
+    let closes: Vec<f64> = dates
+        .iter()
+        .map(|date| {
+            series
+                .range(..=*date)
+                .next_back()
+                .map(|(_, close)| *close)
+                .filter(|close| *close > 0.0)
+        })
+        .collect::<Option<_>>()?;
+    Some(closes.windows(2).map(|w| w[1] / w[0] - 1.0).collect())
+}
+
+fn value_at_risk(returns: &[f64], value: f64) -> Vec<ValueAtRisk> {
+    let mut sorted = returns.to_vec();
+    sorted.sort_by(f64::total_cmp);
+    let (Some(avg), Some(sd)) = (mean(returns), std_dev(returns)) else {
+        return Vec::new();
+    };
+    CONFIDENCE
+        .iter()
+        .map(|(confidence, z)| {
+            let tail = ((1.0 - confidence / 100.0) * sorted.len() as f64).floor() as usize;
+            let historical = -sorted[tail.min(sorted.len() - 1)] * 100.0;
+            let worst = &sorted[..=tail.min(sorted.len() - 1)];
+            let expected_shortfall = -mean(worst).unwrap_or(0.0) * 100.0;
+            let parametric = (z * sd - avg) * 100.0;
+            ValueAtRisk {
+                confidence: *confidence,
+                historical,
+                parametric,
+                expected_shortfall,
+                historical_amount: historical / 100.0 * value,
+                parametric_amount: parametric / 100.0 * value,
+                expected_shortfall_amount: expected_shortfall / 100.0 * value,
+            }
+        })
+        .collect()
+}
+
+fn rolling_volatility(
+    dates: &[NaiveDate],
+    portfolio: &[f64],
+    benchmark: Option<&[f64]>,
+) -> Vec<VolatilityPoint> {
+    if portfolio.len() < ROLLING_WINDOW {
+        return Vec::new();
+    }
+    (ROLLING_WINDOW..=portfolio.len())
+        .filter_map(|end| {
+            let range = end - ROLLING_WINDOW..end;
+            Some(VolatilityPoint {
+                // Return i ends on dates[i + 1]
+                date: dates[end],
+                portfolio: annualized(&portfolio[range.clone()])?,
+                benchmark: benchmark.and_then(|b| annualized(&b[range])),
+            })
+        })
+        .collect()
+}
+
+/// Warnings about weight piling up in one stock, one sector or a few
+/// stocks that move together.
+fn concentration(
+    positions: &[&Position],
+    holdings: &[HoldingRisk],
+    correlation: &CorrelationMatrix,
+    max_position: f64,
+    limit_source: &str,
+) -> Vec<String> {
+    let mut warnings = Vec::new();
+    for position in positions {
+        if position.weight > max_position {
+            warnings.push(format!(
+                "{} is {:.1}% of the portfolio, above the {:.0}% single-stock limit of {}",
+                position.symbol, position.weight, max_position, limit_source
+            ));
+        }
+    }
+
+    let mut sectors: Vec<(&str, f64)> = Vec::new();
+    for position in positions {
+        match sectors.iter_mut().find(|(s, _)| *s == position.sector) {
+            Some(entry) => entry.1 += position.weight,
+            None => sectors.push((&position.sector, position.weight)),
+        }
+    }
+    for (sector, weight) in sectors {
+        if weight > MAX_SECTOR_WEIGHT && sector != "Unclassified" {
+            warnings.push(format!(
+                "{} is {:.1}% of the portfolio; more than {:.0}% in one sector ties the portfolio to it",
+                sector, weight, MAX_SECTOR_WEIGHT
+            ));
+        }
+    }
+
+    for holding in holdings {
+        if let Some(share) = holding
+            .risk_contribution
+            .filter(|c| *c > MAX_RISK_CONTRIBUTION)
+        {
+            warnings.push(format!(
+                "{} makes up {:.0}% of the portfolio's risk from {:.1}% of its value",
+                holding.symbol, share, holding.weight
+            ));
+        }
+    }
+
+    for (i, row) in correlation.values.iter().enumerate() {
+        for (j, value) in row.iter().enumerate().skip(i + 1) {
+            if *value > HIGH_CORRELATION {
+                warnings.push(format!(
+                    "{} and {} move together (correlation {:.2}), so they diversify little",
+                    correlation.symbols[i], correlation.symbols[j], value
+                ));
+            }
+        }
+    }
+    warnings
+}
+
+/// Risk of `positions` over the last `days` trading days, with beta and
+/// volatility against `benchmark` when it has history. `value` converts
+/// VaR to rupees.
+pub fn analyze(
+    positions: &[Position],
+    benchmark: Option<(&str, &CloseSeries)>,
+    days: usize,
+    value: f64,
+    max_position: f64,
+    limit_source: &str,
+) -> RiskReport {
+    let mut report = RiskReport::default();
+    let mut notes = Vec::new();
+
+    // A holding with a zero or negative close in the window has no returns
+    // there. Leaving it out can widen the window, so repeat until every
+    // remaining holding has a price on every shared date.
+    let mut positions: Vec<&Position> = positions.iter().collect();
+    let (dates, returns) = loop {
+        let closes: Vec<&CloseSeries> = positions.iter().map(|p| &p.closes).collect();
+        let dates = shared_dates(&closes, days);
+        let returns: Vec<Option<Vec<f64>>> = positions
+            .iter()
+            .map(|p| returns_on(&p.closes, &dates))
+            .collect();
+        if returns.iter().all(Option::is_some) {
+            break (dates, returns.into_iter().flatten().collect::<Vec<_>>());
+        }
+        let mut kept = Vec::new();
+        for (position, returns) in positions.into_iter().zip(&returns) {
+            match returns {
+                Some(_) => kept.push(position),
+                None => notes.push(format!(
+                    "{} has a zero or negative close in the window and is left out",
+                    position.symbol
+                )),
+            }
+        }
+        positions = kept;
+    };
+    let positions = positions.as_slice();
+    let dates = dates.as_slice();
+    if dates.len() < days + 1 && !dates.is_empty() {
+        notes.push(format!(
+            "Only {} days of history shared by every holding; the estimates use those",
+            dates.len().saturating_sub(1)
+        ));
+    }
+
+    let observations = dates.len().saturating_sub(1);
+    let total_weight: f64 = positions.iter().map(|p| p.weight).sum();
+    report.observations = observations;
+    report.from = dates.first().copied();
+    report.to = dates.last().copied();
+
+    let squares: f64 = positions.iter().map(|p| (p.weight / 100.0).powi(2)).sum();
+    report.effective_holdings = (squares > 0.0).then(|| 1.0 / squares);
+
+    // Today's weights held through the whole window, rescaled to the
+    // holdings that have prices
+    let portfolio: Vec<f64> = (0..observations)
+        .map(|t| {
+            positions
+                .iter()
+                .zip(&returns)
+                .map(|(p, r)| p.weight / total_weight * r[t])
+                .sum()
+        })
+        .collect();
+    let market = benchmark.and_then(|(symbol, series)| {
+        let returns = returns_on(series, dates);
+        if returns.is_none() && observations > 0 {
+            notes.push(format!(
+                "{} has no history over the window, so beta is left out",
+                symbol
+            ));
+        }
+        returns.map(|r| (symbol, r))
+    });
+    report.benchmark = market.as_ref().map(|(symbol, _)| symbol.to_string());
+
+    if observations < MIN_OBSERVATIONS {
+        notes.push(format!(
+            "{} daily returns are too few for risk estimates; at least {} are needed",
+            observations, MIN_OBSERVATIONS
+        ));
+        report.holdings = positions
+            .iter()
+            .map(|p| HoldingRisk {
+                symbol: p.symbol.clone(),
+                weight: p.weight,
+                volatility: None,
+                beta: None,
+                risk_contribution: None,
+            })
+            .collect();
+        report.warnings = concentration(
+            positions,
+            &report.holdings,
+            &report.correlation,
+            max_position,
+            limit_source,
+        );
+        report.notes = notes;
+        return report;
+    }
+
+    let market_returns = market.as_ref().map(|(_, r)| r.as_slice());
+    report.volatility = annualized(&portfolio);
+    report.beta = market_returns.and_then(|m| beta(&portfolio, m));
+    report.benchmark_correlation = market_returns.and_then(|m| correlation(&portfolio, m));
+    report.value_at_risk = value_at_risk(&portfolio, value);
+    report.rolling_volatility = rolling_volatility(dates, &portfolio, market_returns);
+
+    let portfolio_variance = covariance(&portfolio, &portfolio).filter(|v| *v > 0.0);
+    report.holdings = positions
+        .iter()
+        .zip(&returns)
+        .map(|(p, r)| HoldingRisk {
+            symbol: p.symbol.clone(),
+            weight: p.weight,
+            volatility: annualized(r),
+            beta: market_returns.and_then(|m| beta(r, m)),
+            // Weight times covariance with the portfolio; these add up to
+            // the portfolio variance
+            risk_contribution: portfolio_variance.and_then(|variance| {
+                covariance(r, &portfolio).map(|c| p.weight / total_weight * c / variance * 100.0)
+            }),
+        })
+        .collect();
+
+    report.correlation = CorrelationMatrix {
+        symbols: positions.iter().map(|p| p.symbol.clone()).collect(),
+        values: returns
+            .iter()
+            .map(|a| {
+                returns
+                    .iter()
+                    .map(|b| correlation(a, b).unwrap_or(0.0))
+                    .collect()
+            })
+            .collect(),
+    };
+    report.warnings = concentration(
+        positions,
+        &report.holdings,
+        &report.correlation,
+        max_position,
+        limit_source,
+    );
+    report.notes = notes;
+    report
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn position(symbol: &str, weight: f64, closes: impl Fn(usize) -> f64) -> Position {
+        let start = NaiveDate::from_ymd_opt(2025, 1, 1).unwrap();
+        Position {
+            symbol: symbol.to_string(),
+            sector: "Banks".to_string(),
+            weight,
+            closes: (0..60)
+                .map(|i| (start + chrono::Duration::days(i as i64), closes(i)))
+                .collect(),
+        }
+    }
+
+    #[test]
+    fn leaves_out_holdings_with_non_positive_closes() {
+        let positions = [
+            position("HDFCBANK", 60.0, |i| 100.0 + (i % 7) as f64),
+            position("YESBANK", 40.0, |i| if i == 45 { 0.0 } else { 20.0 }),
+        ];
+        let report = analyze(&positions, None, 50, 100_000.0, 100.0, "test");
+
+        assert_eq!(report.holdings.len(), 1);
+        assert_eq!(report.holdings[0].symbol, "HDFCBANK");
+        assert!(report.notes.iter().any(|n| n.contains("YESBANK")));
+        assert_eq!(report.observations, 50);
+    }
+}
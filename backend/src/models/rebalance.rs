@@ -0,0 +1,522 @@
+//! Target weights for a set of stocks and the trades that move the
+//! current holdings to them. Weights are long-only, add up to one and are
+//! capped per stock; every optimiser works on the annualized covariance of
+//! daily returns over a shared window, and mean-variance also uses their
+//! average return.
+
+use serde::{Deserialize, Serialize};
+
+use crate::models::charges::{ChargeSegment, Order, RateTable};
+use crate::models::journal::Side;
+use crate::utils::indicators::{covariance, mean};
+
+const YEAR: f64 = 252.0;
+const ITERATIONS: usize = 5000;
+const TOLERANCE: f64 = 1e-10;
+
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum Method {
+    #[default]
+    EqualWeight,
+    MinimumVariance,
+    RiskParity,
+    MeanVariance,
+}
+
+impl Method {
+    pub fn label(self) -> &'static str {
+        match self {
+            Method::EqualWeight => "Equal weight",
+            Method::MinimumVariance => "Minimum variance",
+            Method::RiskParity => "Risk parity",
+            Method::MeanVariance => "Mean-variance",
+        }
+    }
+}
+
+/// A stock that can be held, with what is held of it now.
+pub struct Candidate {
+    pub symbol: String,
+    pub price: f64,
+    pub quantity: f64,
+    /// Daily returns over the shared window, oldest first.
+    pub returns: Vec<f64>,
+}
+
+#[derive(Clone, Debug)]
+pub struct Constraints {
+    /// Largest weight of one stock, in percent.
+    pub max_weight: f64,
+    /// How much return mean-variance gives up per unit of variance.
+    pub risk_aversion: f64,
+    /// Cash added to (or, if negative, taken from) the portfolio.
+    pub cash: f64,
+    /// Trades smaller than this are left out.
+    pub min_trade: f64,
+}
+
+#[derive(Clone, Debug, Serialize)]
+pub struct TargetWeight {
+    pub symbol: String,
+    pub price: f64,
+    pub current_weight: f64,
+    pub target_weight: f64,
+    /// Annualized average return and volatility over the window, in percent.
+    pub expected_return: Option<f64>,
+    pub volatility: Option<f64>,
+    /// Percent of the target portfolio's variance from this stock.
+    pub risk_contribution: Option<f64>,
+}
+
+#[derive(Clone, Debug, Serialize)]
+pub struct RebalanceTrade {
+    pub symbol: String,
+    pub side: Side,
+    pub quantity: u32,
+    pub price: f64,
+    pub value: f64,
+    pub charges: f64,
+}
+
+/// Annualized return and volatility of a set of weights, in percent.
+#[derive(Clone, Copy, Debug, Default, Serialize)]
+pub struct PortfolioStats {
+    pub expected_return: Option<f64>,
+    pub volatility: Option<f64>,
+}
+
+#[derive(Clone, Debug, Serialize)]
+pub struct RebalancePlan {
+    pub method: Method,
+    pub label: &'static str,
+    pub max_weight: f64,
+    pub observations: usize,
+    pub value: f64,
+    pub cash: f64,
+    pub targets: Vec<TargetWeight>,
+    pub current: PortfolioStats,
+    pub target: PortfolioStats,
+    /// Sells first, so their proceeds fund the buys.
+    pub trades: Vec<RebalanceTrade>,
+    pub buy_value: f64,
+    pub sell_value: f64,
+    pub charges: f64,
+    /// Cash left after the trades and charges; negative when more is
+    /// needed.
+    pub cash_left: f64,
+    pub notes: Vec<String>,
+}
+
+type Matrix = Vec<Vec<f64>>;
+
+fn mat_vec(matrix: &Matrix, vector: &[f64]) -> Vec<f64> {
+    matrix
+        .iter()
+        .map(|row| row.iter().zip(vector).map(|(a, b)| a * b).sum())
+        .collect()
+}
+
+fn dot(a: &[f64], b: &[f64]) -> f64 {
+    a.iter().zip(b).map(|(x, y)| x * y).sum()
+}
+
+/// Closest point to `v` with weights between zero and `cap` that add up
+/// to one, found by bisecting on the shift applied to every weight.
+fn project(v: &[f64], cap: f64) -> Vec<f64> {
+    let clamp =
+        |shift: f64| -> Vec<f64> { v.iter().map(|x| (x - shift).clamp(0.0, cap)).collect() };
+    let total = |shift: f64| clamp(shift).iter().sum::<f64>();
+    let (mut low, mut high) = (
+        v.iter().copied().fold(f64::MAX, f64::min) - cap - 1.0,
+        v.iter().copied().fold(f64::MIN, f64::max) + 1.0,
+    );
+    for _ in 0..100 {
+        let mid = (low + high) / 2.0;
+        if total(mid) > 1.0 {
+            low = mid;
+        } else {
+            high = mid;
+        }
+    }
+    clamp((low + high) / 2.0)
+}
+
+/// Projected gradient ascent along `gradient`, starting from equal
+/// weights. `step` should be below 1 / the gradient's Lipschitz constant.
+fn climb(n: usize, cap: f64, step: f64, gradient: impl Fn(&[f64]) -> Vec<f64>) -> Vec<f64> {
+    let mut weights = project(&vec![1.0 / n as f64; n], cap);
+    for _ in 0..ITERATIONS {
+        let g = gradient(&weights);
+        let moved: Vec<f64> = weights.iter().zip(&g).map(|(w, g)| w + step * g).collect();
+        let next = project(&moved, cap);
+        let change: f64 = next
+            .iter()
+            .zip(&weights)
+            .map(|(a, b)| (a - b).powi(2))
+            .sum();
+        weights = next;
+        if change < TOLERANCE * TOLERANCE {
+            break;
+        }
+    }
+    weights
+}
+
+/// Equal risk contributions by the usual fixed-point update, projected
+/// back inside the cap after each step. With binding caps the
+/// contributions are as close to equal as the cap allows.
+fn risk_parity(covariance: &Matrix, cap: f64) -> Vec<f64> {
+    let n = covariance.len();
+    let mut weights = project(&vec![1.0 / n as f64; n], cap);
+    for _ in 0..ITERATIONS {
+        let marginal = mat_vec(covariance, &weights);
+        let variance = dot(&weights, &marginal);
+        if variance <= 0.0 {
+            break;
+        }
+        let target = variance / n as f64;
+        let scaled: Vec<f64> = weights
+            .iter()
+            .zip(&marginal)
+            .map(|(w, m)| {
+                let contribution = w * m;
+                if contribution > 0.0 {
+                    w * (target / contribution).sqrt()
+                } else {
+                    *w
+                }
+            })
+            .collect();
+        let sum: f64 = scaled.iter().sum();
+        let next = project(&scaled.iter().map(|w| w / sum).collect::<Vec<_>>(), cap);
+        let change: f64 = next
+            .iter()
+            .zip(&weights)
+            .map(|(a, b)| (a - b).powi(2))
+            .sum();
+        weights = next;
+        if change < TOLERANCE * TOLERANCE {
+            break;
+        }
+    }
+    weights
+}
+
+fn stats(weights: &[f64], means: &[f64], covariance: &Matrix) -> PortfolioStats {
+    let variance = dot(weights, &mat_vec(covariance, weights));
+    PortfolioStats {
+        expected_return: Some(dot(weights, means) * 100.0),
+        volatility: (variance >= 0.0).then(|| variance.sqrt() * 100.0),
+    }
+}
+
+/// Weights for `candidates` by `method` and the whole-share trades that
+/// reach them from the current quantities, with delivery charges from
+/// `rates`.
+pub fn plan(
+    candidates: &[Candidate],
+    method: Method,
+    constraints: &Constraints,
+    rates: &RateTable,
+) -> RebalancePlan {
+    let n = candidates.len();
+    let mut notes = Vec::new();
+    let observations = candidates
+        .iter()
+        .map(|c| c.returns.len())
+        .min()
+        .unwrap_or(0);
+
+    let mut cap = constraints.max_weight / 100.0;
+    if n > 0 && cap * (n as f64) < 1.0 {
+        cap = 1.0 / n as f64;
+        notes.push(format!(
+            "{} stocks can't stay under {:.1}% each; the cap is raised to {:.1}%",
+            n,
+            constraints.max_weight,
+            cap * 100.0
+        ));
+    }
+
+    let means: Vec<f64> = candidates
+        .iter()
+        .map(|c| mean(&c.returns).unwrap_or(0.0) * YEAR)
+        .collect();
+    let covariance: Matrix = candidates
+        .iter()
+        .map(|a| {
+            candidates
+                .iter()
+                .map(|b| covariance(&a.returns, &b.returns).unwrap_or(0.0) * YEAR)
+                .collect()
+        })
+        .collect();
+    let trace: f64 = (0..n).map(|i| covariance[i][i]).sum();
+    let needs_history = method != Method::EqualWeight;
+    let has_history = observations >= 2 && trace > 0.0;
+
+    let weights = if n == 0 {
+        Vec::new()
+    } else if needs_history && !has_history {
+        notes.push(format!(
+            "Not enough shared price history for {}; falling back to equal weights",
+            method.label()
+        ));
+        project(&vec![1.0 / n as f64; n], cap)
+    } else {
+        match method {
+            Method::EqualWeight => project(&vec![1.0 / n as f64; n], cap),
+            // The trace bounds the largest eigenvalue, which keeps the step
+            // small enough to converge
+            Method::MinimumVariance => climb(n, cap, 0.5 / trace, |w| {
+                mat_vec(&covariance, w).iter().map(|g| -g).collect()
+            }),
+            Method::MeanVariance => {
+                let aversion = constraints.risk_aversion.max(0.01);
+                climb(n, cap, 0.5 / (aversion * trace), |w| {
+                    mat_vec(&covariance, w)
+                        .iter()
+                        .zip(&means)
+                        .map(|(risk, mu)| mu - aversion * risk)
+                        .collect()
+                })
+            }
+            Method::RiskParity => risk_parity(&covariance, cap),
+        }
+    };
+    if method == Method::MeanVariance && has_history {
+        notes.push(
+            "Mean-variance leans on past average returns, which forecast future returns poorly; \
+             treat its tilts with caution"
+                .to_string(),
+        );
+    }
+
+    let held: Vec<f64> = candidates.iter().map(|c| c.quantity * c.price).collect();
+    let value: f64 = held.iter().sum();
+    let budget = value + constraints.cash;
+    if budget <= 0.0 && n > 0 {
+        notes.push("Nothing to invest: the holdings and added cash come to zero".to_string());
+    }
+    let current_weights: Vec<f64> = held
+        .iter()
+        .map(|h| if value > 0.0 { h / value } else { 0.0 })
+        .collect();
+    let marginal = mat_vec(&covariance, &weights);
+    let target_variance = dot(&weights, &marginal);
+
+    let targets = candidates
+        .iter()
+        .enumerate()
+        .map(|(i, c)| TargetWeight {
+            symbol: c.symbol.clone(),
+            price: c.price,
+            current_weight: current_weights[i] * 100.0,
+            target_weight: weights[i] * 100.0,
+            expected_return: has_history.then(|| means[i] * 100.0),
+            volatility: has_history.then(|| covariance[i][i].sqrt() * 100.0),
+            risk_contribution: (has_history && target_variance > 0.0)
+                .then(|| weights[i] * marginal[i] / target_variance * 100.0),
+        })
+        .collect();
+
+    let mut trades = Vec::new();
+    let mut skipped = 0;
+    for (i, c) in candidates.iter().enumerate() {
+        if c.price <= 0.0 {
+            continue;
+        }
+        let wanted = (weights[i] * budget.max(0.0) / c.price).floor();
+        let difference = wanted - c.quantity;
+        let quantity = difference.abs().round();
+        if quantity < 1.0 {
+            continue;
+        }
+        // Selling out goes through however small, so no stub is left
+        let exit = wanted == 0.0;
+        if quantity * c.price < constraints.min_trade && !exit {
+            skipped += 1;
+            continue;
+        }
+        let side = if difference > 0.0 {
+            Side::Buy
+        } else {
+            Side::Sell
+        };
+        let order = Order {
+            side,
+            quantity: quantity as u32,
+            price: c.price,
+        };
+        trades.push(RebalanceTrade {
+            symbol: c.symbol.clone(),
+            side,
+            quantity: order.quantity,
+            price: c.price,
+            value: quantity * c.price,
+            charges: rates.order(ChargeSegment::EquityDelivery, &order).total,
+        });
+    }
+    trades.sort_by_key(|t| (t.side == Side::Buy, t.symbol.clone()));
+    if skipped > 0 {
+        notes.push(format!(
+            "{} trade{} under ₹{:.0} left out",
+            skipped,
+            if skipped == 1 { "" } else { "s" },
+            constraints.min_trade
+        ));
+    }
+
+    let sum = |side: Side| {
+        trades
+            .iter()
+            .filter(|t| t.side == side)
+            .map(|t| t.value)
+            .sum::<f64>()
+    };
+    let (buy_value, sell_value) = (sum(Side::Buy), sum(Side::Sell));
+    let charges: f64 = trades.iter().map(|t| t.charges).sum();
+    let cash_left = constraints.cash + sell_value - buy_value - charges;
+    if cash_left < 0.0 {
+        notes.push(format!(
+            "The buys need ₹{:.0} more than the sales and added cash provide",
+            -cash_left
+        ));
+    }
+
+    RebalancePlan {
+        method,
+        label: method.label(),
+        max_weight: cap * 100.0,
+        observations,
+        value,
+        cash: constraints.cash,
+        current: if has_history {
+            stats(&current_weights, &means, &covariance)
+        } else {
+            PortfolioStats::default()
+        },
+        target: if has_history {
+            stats(&weights, &means, &covariance)
+        } else {
+            PortfolioStats::default()
+        },
+        targets,
+        trades,
+        buy_value,
+        sell_value,
+        charges,
+        cash_left,
+        notes,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Two stocks whose daily returns are uncorrelated, the second twice
+    /// as volatile as the first.
+    fn candidates(quantity: f64) -> Vec<Candidate> {
+        let calm = (0..40)
+            .map(|i| if i % 2 == 0 { 0.01 } else { -0.01 })
+            .collect();
+        let wild = (0..40)
+            .map(|i| if i % 4 < 2 { 0.02 } else { -0.02 })
+            .collect();
+        vec![
+            Candidate {
+                symbol: "CALM".to_string(),
+                price: 100.0,
+                quantity,
+                returns: calm,
+            },
+            Candidate {
+                symbol: "WILD".to_string(),
+                price: 100.0,
+                quantity: 0.0,
+                returns: wild,
+            },
+        ]
+    }
+
+    fn constraints(max_weight: f64) -> Constraints {
+        Constraints {
+            max_weight,
+            risk_aversion: 1.0,
+            cash: 0.0,
+            min_trade: 0.0,
+        }
+    }
+
+    fn targets(plan: &RebalancePlan) -> Vec<f64> {
+        plan.targets.iter().map(|t| t.target_weight).collect()
+    }
+
+    #[test]
+    fn projection_keeps_weights_under_the_cap_and_summing_to_one() {
+        let weights = project(&[0.7, 0.2, 0.1], 0.5);
+        for (weight, expected) in weights.iter().zip([0.5, 0.3, 0.2]) {
+            assert!((weight - expected).abs() < 1e-9);
+        }
+    }
+
+    #[test]
+    fn optimisers_weight_the_calmer_stock_more() {
+        let rates = RateTable::with_defaults();
+        let minimum = plan(
+            &candidates(0.0),
+            Method::MinimumVariance,
+            &constraints(100.0),
+            &rates,
+        );
+        // Inverse variance: 4 to 1
+        assert!((targets(&minimum)[0] - 80.0).abs() < 0.01);
+
+        let parity = plan(
+            &candidates(0.0),
+            Method::RiskParity,
+            &constraints(100.0),
+            &rates,
+        );
+        // Inverse volatility: 2 to 1, with equal risk contributions
+        assert!((targets(&parity)[0] - 200.0 / 3.0).abs() < 0.01);
+        let contributions: Vec<f64> = parity
+            .targets
+            .iter()
+            .map(|t| t.risk_contribution.unwrap())
+            .collect();
+        assert!((contributions[0] - contributions[1]).abs() < 0.01);
+
+        let capped = plan(
+            &candidates(0.0),
+            Method::MinimumVariance,
+            &constraints(60.0),
+            &rates,
+        );
+        assert!((targets(&capped)[0] - 60.0).abs() < 0.01);
+    }
+
+    #[test]
+    fn trades_sell_first_and_report_the_shortfall() {
+        let plan = plan(
+            &candidates(10.0),
+            Method::EqualWeight,
+            &constraints(20.0),
+            &RateTable::with_defaults(),
+        );
+
+        assert_eq!(plan.max_weight, 50.0);
+        assert!(plan.notes[0].contains("cap is raised to 50.0%"));
+        let trades: Vec<(&str, Side, u32)> = plan
+            .trades
+            .iter()
+            .map(|t| (t.symbol.as_str(), t.side, t.quantity))
+            .collect();
+        assert_eq!(trades, [("CALM", Side::Sell, 5), ("WILD", Side::Buy, 5)]);
+        assert!(plan.charges > 0.0);
+        assert!((plan.cash_left + plan.charges).abs() < 1e-9);
+        assert!(plan.notes.last().unwrap().contains("more than the sales"));
+    }
+}
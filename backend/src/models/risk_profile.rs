@@ -0,0 +1,578 @@
+//! Risk profile questionnaire and the guidance it drives. Questions score
+//! either the capacity to take risk (age, horizon, income, reserves) or
+//! the tolerance for it (reaction to losses, experience, goals); the
+//! bucket follows the lower of the two, so guidance never asks for more
+//! risk than the investor can afford or stomach.
+
+use std::collections::BTreeMap;
+use std::path::{Path, PathBuf};
+
+use chrono::NaiveDateTime;
+use serde::{Deserialize, Serialize};
+
+use crate::utils::format::rupees;
+use crate::utils::persist;
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum Dimension {
+    Capacity,
+    Tolerance,
+}
+
+/// A multiple choice question. Options run from the least to the most
+/// risk-taking answer and score their index.
+#[derive(Clone, Debug, Serialize)]
+pub struct Question {
+    pub id: &'static str,
+    pub dimension: Dimension,
+    pub text: &'static str,
+    pub options: &'static [&'static str],
+}
+
+/// Id of the horizon question; money needed within a year caps the bucket.
+const HORIZON: &str = "horizon";
+
+pub const QUESTIONS: [Question; 9] = [
+    Question {
+        id: "age",
+        dimension: Dimension::Capacity,
+        text: "How old are you?",
+        options: &[
+            "60 or older",
+            "50 to 59",
+            "40 to 49",
+            "30 to 39",
+            "Under 30",
+        ],
+    },
+    Question {
+        id: HORIZON,
+        dimension: Dimension::Capacity,
+        text: "When will you need most of this money?",
+        options: &[
+            "Within a year",
+            "In 1 to 3 years",
+            "In 3 to 5 years",
+            "In 5 to 10 years",
+            "Not for more than 10 years",
+        ],
+    },
+    Question {
+        id: "income",
+        dimension: Dimension::Capacity,
+        text: "How stable is your income?",
+        options: &[
+            "No regular income",
+            "Irregular or uncertain",
+            "Stable",
+            "Stable and growing",
+        ],
+    },
+    Question {
+        id: "reserves",
+        dimension: Dimension::Capacity,
+        text: "How many months of expenses do you keep aside outside these investments?",
+        options: &[
+            "None",
+            "Less than 3 months",
+            "3 to 6 months",
+            "More than 6 months",
+        ],
+    },
+    Question {
+        id: "dependents",
+        dimension: Dimension::Capacity,
+        text: "How many people depend on your income?",
+        options: &["Three or more", "One or two", "None"],
+    },
+    Question {
+        id: "drawdown",
+        dimension: Dimension::Tolerance,
+        text: "Your portfolio falls 20% in a month. What do you do?",
+        options: &[
+            "Sell everything",
+            "Sell some to limit the loss",
+            "Hold and wait",
+            "Buy more",
+        ],
+    },
+    Question {
+        id: "max_loss",
+        dimension: Dimension::Tolerance,
+        text: "What is the largest loss in a year you could accept without changing plans?",
+        options: &[
+            "None",
+            "Up to 5%",
+            "Up to 15%",
+            "Up to 25%",
+            "More than 25%",
+        ],
+    },
+    Question {
+        id: "experience",
+        dimension: Dimension::Tolerance,
+        text: "How long have you been investing in stocks or derivatives?",
+        options: &[
+            "Never",
+            "Less than 2 years",
+            "2 to 5 years",
+            "More than 5 years",
+        ],
+    },
+    Question {
+        id: "goal",
+        dimension: Dimension::Tolerance,
+        text: "What matters most for this money?",
+        options: &[
+            "Protecting what I have",
+            "Steady income",
+            "Balanced growth",
+            "Maximum long-term growth",
+        ],
+    },
+];
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, PartialOrd, Ord, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum RiskBucket {
+    Conservative,
+    ModeratelyConservative,
+    Moderate,
+    ModeratelyAggressive,
+    Aggressive,
+}
+
+/// Share of the portfolio suggested for one asset class, in percent.
+#[derive(Clone, Debug, Serialize)]
+pub struct Allocation {
+    pub asset_class: &'static str,
+    pub percent: f64,
+}
+
+/// What a bucket means in practice. Percentages are of the investable
+/// capital; limits left out don't apply to the bucket.
+#[derive(Clone, Debug, Serialize)]
+pub struct Guidance {
+    pub summary: &'static str,
+    pub allocation: Vec<Allocation>,
+    /// Largest holding in a single stock.
+    pub max_position: f64,
+    /// Capital lost if a position's stop is hit.
+    pub risk_per_trade: f64,
+    /// Annualized volatility above which a stock doesn't suit the profile.
+    pub max_volatility: Option<f64>,
+    pub max_debt_to_equity: Option<f64>,
+    /// Lowest composite score worth holding.
+    pub min_score: Option<f64>,
+}
+
+fn allocation(equity: f64, debt: f64, gold: f64, cash: f64) -> Vec<Allocation> {
+    [
+        ("Equity", equity),
+        ("Debt", debt),
+        ("Gold", gold),
+        ("Cash", cash),
+    ]
+    .into_iter()
+    .map(|(asset_class, percent)| Allocation {
+        asset_class,
+        percent,
+    })
+    .collect()
+}
+
+impl RiskBucket {
+    /// Buckets split the 0-100 score into fifths.
+    pub fn from_score(score: f64) -> Self {
+        match score {
+            s if s < 20.0 => RiskBucket::Conservative,
+            s if s < 40.0 => RiskBucket::ModeratelyConservative,
+            s if s < 60.0 => RiskBucket::Moderate,
+            s if s < 80.0 => RiskBucket::ModeratelyAggressive,
+            _ => RiskBucket::Aggressive,
+        }
+    }
+
+    pub fn label(self) -> &'static str {
+        match self {
+            RiskBucket::Conservative => "Conservative",
+            RiskBucket::ModeratelyConservative => "Moderately Conservative",
+            RiskBucket::Moderate => "Moderate",
+            RiskBucket::ModeratelyAggressive => "Moderately Aggressive",
+            RiskBucket::Aggressive => "Aggressive",
+        }
+    }
+
+    pub fn guidance(self) -> Guidance {
+        match self {
+            RiskBucket::Conservative => Guidance {
+                summary: "Capital preservation first, with a small equity sleeve in large, steady companies.",
+                allocation: allocation(20.0, 60.0, 10.0, 10.0),
+                max_position: 3.0,
+                risk_per_trade: 0.25,
+                max_volatility: Some(25.0),
+                max_debt_to_equity: Some(0.5),
+                min_score: Some(60.0),
+            },
+            RiskBucket::ModeratelyConservative => Guidance {
+                summary: "Mostly fixed income, with equity for some growth in well-financed companies.",
+                allocation: allocation(35.0, 50.0, 10.0, 5.0),
+                max_position: 4.0,
+                risk_per_trade: 0.5,
+                max_volatility: Some(30.0),
+                max_debt_to_equity: Some(1.0),
+                min_score: Some(50.0),
+            },
+            RiskBucket::Moderate => Guidance {
+                summary: "A balance of growth and stability, diversified across stocks and sectors.",
+                allocation: allocation(50.0, 35.0, 10.0, 5.0),
+                max_position: 5.0,
+                risk_per_trade: 0.75,
+                max_volatility: Some(35.0),
+                max_debt_to_equity: Some(1.5),
+                min_score: Some(40.0),
+            },
+            RiskBucket::ModeratelyAggressive => Guidance {
+                summary: "Growth-oriented, accepting sizeable swings for higher long-term returns.",
+                allocation: allocation(65.0, 25.0, 5.0, 5.0),
+                max_position: 7.0,
+                risk_per_trade: 1.0,
+                max_volatility: Some(45.0),
+                max_debt_to_equity: Some(2.0),
+                min_score: None,
+            },
+            RiskBucket::Aggressive => Guidance {
+                summary: "Maximum growth, able to sit through deep drawdowns and concentrated bets.",
+                allocation: allocation(80.0, 10.0, 5.0, 5.0),
+                max_position: 10.0,
+                risk_per_trade: 1.5,
+                max_volatility: None,
+                max_debt_to_equity: None,
+                min_score: None,
+            },
+        }
+    }
+}
+
+/// Questionnaire answers, keyed by question id with the chosen option's
+/// index, and the capital the guidance sizes positions from.
+#[derive(Clone, Debug, Default, Serialize, Deserialize)]
+pub struct RiskProfile {
+    pub answers: BTreeMap<String, usize>,
+    #[serde(default)]
+    pub capital: Option<f64>,
+    #[serde(default)]
+    pub assessed_at: Option<NaiveDateTime>,
+}
+
+impl RiskProfile {
+    pub fn validate(&self) -> Result<(), String> {
+        if let Some(id) = self
+            .answers
+            .keys()
+            .find(|id| !QUESTIONS.iter().any(|q| q.id == id.as_str()))
+        {
+            return Err(format!("unknown question '{}'", id));
+        }
+        for question in &QUESTIONS {
+            match self.answers.get(question.id) {
+                None => return Err(format!("question '{}' is not answered", question.id)),
+                Some(&option) if option >= question.options.len() => {
+                    return Err(format!(
+                        "question '{}' has {} options",
+                        question.id,
+                        question.options.len()
+                    ))
+                }
+                _ => {}
+            }
+        }
+        if self.capital.is_some_and(|c| !c.is_finite() || c <= 0.0) {
+            return Err("capital must be positive".to_string());
+        }
+        Ok(())
+    }
+}
+
+/// How a profile scores and what it is advised.
+#[derive(Clone, Debug, Serialize)]
+pub struct Assessment {
+    /// 0 to 100 per dimension.
+    pub capacity: f64,
+    pub tolerance: f64,
+    pub bucket: RiskBucket,
+    pub label: &'static str,
+    pub guidance: Guidance,
+    pub notes: Vec<String>,
+}
+
+fn dimension_score(profile: &RiskProfile, dimension: Dimension) -> f64 {
+    let (points, max) =
+        QUESTIONS
+            .iter()
+            .filter(|q| q.dimension == dimension)
+            .fold((0, 0), |(points, max), q| {
+                let answer = profile.answers.get(q.id).copied().unwrap_or(0);
+                (points + answer, max + q.options.len() - 1)
+            });
+    if max == 0 {
+        0.0
+    } else {
+        points as f64 / max as f64 * 100.0
+    }
+}
+
+/// Score a validated profile.
+pub fn assess(profile: &RiskProfile) -> Assessment {
+    let capacity = dimension_score(profile, Dimension::Capacity);
+    let tolerance = dimension_score(profile, Dimension::Tolerance);
+    let by_capacity = RiskBucket::from_score(capacity);
+    let by_tolerance = RiskBucket::from_score(tolerance);
+    let mut bucket = by_capacity.min(by_tolerance);
+
+    let mut notes = Vec::new();
+    if by_tolerance > by_capacity {
+        notes.push(format!(
+            "Your appetite for risk ({}) is higher than your situation can absorb ({}); the guidance follows your capacity.",
+            by_tolerance.label(),
+            by_capacity.label()
+        ));
+    } else if by_capacity > by_tolerance {
+        notes.push(format!(
+            "Your situation could carry more risk ({}) than you are comfortable with ({}); the guidance follows your comfort.",
+            by_capacity.label(),
+            by_tolerance.label()
+        ));
+    }
+    if profile.answers.get(HORIZON) == Some(&0) && bucket > RiskBucket::Conservative {
+        bucket = RiskBucket::Conservative;
+        notes.push(
+            "Money needed within a year belongs in debt and cash, so the profile is held at Conservative."
+                .to_string(),
+        );
+    }
+    if profile.capital.is_none() {
+        notes.push("Add your investable capital to get position sizes in rupees.".to_string());
+    }
+
+    Assessment {
+        capacity,
+        tolerance,
+        bucket,
+        label: bucket.label(),
+        guidance: bucket.guidance(),
+        notes,
+    }
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, PartialOrd, Ord, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum Fit {
+    Suitable,
+    Caution,
+    Unsuitable,
+}
+
+#[derive(Clone, Debug, Serialize)]
+pub struct Suitability {
+    pub fit: Fit,
+    pub reasons: Vec<String>,
+}
+
+/// How well a stock fits a bucket's limits. Volatility well beyond the
+/// limit rules a stock out; other breaches call for caution.
+pub fn suitability(
+    bucket: RiskBucket,
+    volatility: Option<f64>,
+    debt_to_equity: Option<f64>,
+    composite: Option<f64>,
+) -> Suitability {
+    let guidance = bucket.guidance();
+    let mut fit = Fit::Suitable;
+    let mut reasons = Vec::new();
+
+    match (volatility, guidance.max_volatility) {
+        (Some(vol), Some(limit)) if vol > limit => {
+            fit = fit.max(if vol > limit * 1.25 {
+                Fit::Unsuitable
+            } else {
+                Fit::Caution
+            });
+            reasons.push(format!(
+                "Volatility of {:.1}% a year is above the {:.0}% limit for the {} profile",
+                vol,
+                limit,
+                bucket.label()
+            ));
+        }
+        (None, Some(_)) => {
+            fit = fit.max(Fit::Caution);
+            reasons.push("Not enough price history to judge volatility".to_string());
+        }
+        _ => {}
+    }
+    if let (Some(ratio), Some(limit)) = (debt_to_equity, guidance.max_debt_to_equity) {
+        if ratio > limit {
+            fit = fit.max(Fit::Caution);
+            reasons.push(format!(
+                "Debt to equity of {:.2} is above the {:.1} limit",
+                ratio, limit
+            ));
+        }
+    }
+    if let (Some(score), Some(limit)) = (composite, guidance.min_score) {
+        if score < limit {
+            fit = fit.max(Fit::Caution);
+            reasons.push(format!(
+                "Composite score of {:.0} is below the {:.0} this profile looks for",
+                score, limit
+            ));
+        }
+    }
+    if reasons.is_empty() {
+        reasons.push(format!(
+            "Within the limits for the {} profile",
+            bucket.label()
+        ));
+    }
+    Suitability { fit, reasons }
+}
+
+/// Suggested size of a new position, the smaller of the bucket's position
+/// cap and what keeps the loss at the stop within its risk per trade.
+#[derive(Clone, Debug, Serialize)]
+pub struct PositionSize {
+    pub capital: f64,
+    pub price: f64,
+    /// Two daily standard deviations below the price, when volatility is
+    /// known.
+    pub stop: Option<f64>,
+    pub shares: u64,
+    pub value: f64,
+    pub percent_of_capital: f64,
+    /// Loss if the stop is hit.
+    pub risk_amount: Option<f64>,
+    pub note: String,
+}
+
+/// Trading days in a year, to turn annualized volatility into daily.
+const YEAR: f64 = 252.0;
+
+pub fn position_size(
+    bucket: RiskBucket,
+    capital: f64,
+    price: f64,
+    volatility: Option<f64>,
+) -> Option<PositionSize> {
+    if capital <= 0.0 || price <= 0.0 {
+        return None;
+    }
+    let guidance = bucket.guidance();
+    let cap_value = capital * guidance.max_position / 100.0;
+    let by_cap = (cap_value / price).floor() as u64;
+
+    let stop_distance = volatility
+        .map(|vol| price * vol / 100.0 / YEAR.sqrt() * 2.0)
+        .filter(|d| *d > 0.0 && *d < price);
+    let by_risk =
+        stop_distance.map(|d| (capital * guidance.risk_per_trade / 100.0 / d).floor() as u64);
+
+    let shares = by_risk.map_or(by_cap, |r| r.min(by_cap));
+    let value = shares as f64 * price;
+    let by_risk_limit = by_risk.is_some_and(|r| r < by_cap);
+    let note = match (shares, by_risk_limit) {
+        (0, true) => format!(
+            "One share risks more than the {}% risk per trade of {} at the stop",
+            guidance.risk_per_trade,
+            rupees(capital * guidance.risk_per_trade / 100.0)
+        ),
+        (0, false) => format!(
+            "One share costs more than the {:.0}% position cap of {}",
+            guidance.max_position,
+            rupees(cap_value)
+        ),
+        (_, true) => format!(
+            "Limited by the {}% risk per trade at the stop",
+            guidance.risk_per_trade
+        ),
+        (_, false) => format!("Limited by the {:.0}% position cap", guidance.max_position),
+    };
+
+    Some(PositionSize {
+        capital,
+        price,
+        stop: stop_distance.map(|d| price - d),
+        shares,
+        value,
+        percent_of_capital: value / capital * 100.0,
+        risk_amount: stop_distance.map(|d| d * shares as f64),
+        note,
+    })
+}
+
+#[derive(Serialize, Deserialize, Default)]
+struct ProfileFile {
+    #[serde(default)]
+    profile: Option<RiskProfile>,
+}
+
+/// The investor's risk profile, persisted as JSON after every change.
+pub struct RiskProfileStore {
+    path: PathBuf,
+    data: ProfileFile,
+}
+
+impl RiskProfileStore {
+    pub fn load(path: &Path) -> Self {
+        let data = persist::load_json(path);
+
+        Self {
+            path: path.to_path_buf(),
+            data,
+        }
+    }
+
+    fn save(&self) -> Result<(), String> {
+        persist::save_json(&self.path, &self.data)
+    }
+
+    pub fn profile(&self) -> Option<&RiskProfile> {
+        self.data.profile.as_ref()
+    }
+
+    pub fn set(&mut self, profile: RiskProfile) -> Result<(), String> {
+        self.data.profile = Some(profile);
+        self.save()
+    }
+
+    pub fn clear(&mut self) -> Result<bool, String> {
+        if self.data.profile.take().is_none() {
+            return Ok(false);
+        }
+        self.save()?;
+        Ok(true)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn position_size_names_the_limit_that_binds() {
+        let bucket = RiskBucket::Conservative;
+
+        let capped = position_size(bucket, 100_000.0, 1_000.0, Some(20.0)).unwrap();
+        assert_eq!(capped.shares, 3);
+        assert!(capped.note.contains("position cap"));
+
+        let risky = position_size(bucket, 100_000.0, 1_000.0, Some(400.0)).unwrap();
+        assert_eq!(risky.shares, 0);
+        assert!(risky.note.contains("risk per trade"), "{}", risky.note);
+
+        let dear = position_size(bucket, 100_000.0, 5_000.0, None).unwrap();
+        assert_eq!(dear.shares, 0);
+        assert!(dear.note.contains("position cap"), "{}", dear.note);
+    }
+}
@@ -0,0 +1,510 @@
+use std::collections::BTreeMap;
+use std::path::{Path, PathBuf};
+
+use chrono::{Duration, NaiveDate, NaiveDateTime, NaiveTime};
+use serde::{Deserialize, Serialize};
+
+use crate::models::journal::{Direction, Execution, Side, Trade};
+use crate::utils::format::rupees;
+use crate::utils::persist;
+
+/// A personal trading rule the journal is checked against.
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+#[serde(tag = "kind", rename_all = "snake_case")]
+pub enum RuleKind {
+    /// Trades opened after the first `limit` of the day break the rule.
+    MaxTradesPerDay { limit: u32 },
+    /// No fills in the first `minutes` of the normal session.
+    NoTradingAfterOpen { minutes: u32 },
+    /// Stop once the day's realized loss, net of fees, reaches `amount`.
+    MaxLossPerDay { amount: f64 },
+}
+
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct Rule {
+    #[serde(default)]
+    pub id: String,
+    #[serde(flatten)]
+    pub kind: RuleKind,
+    #[serde(default = "enabled")]
+    pub enabled: bool,
+}
+
+fn enabled() -> bool {
+    true
+}
+
+impl Rule {
+    pub fn validate(&self) -> Result<(), String> {
+        match self.kind {
+            RuleKind::MaxTradesPerDay { limit: 0 } => {
+                Err("trade limit must be at least 1".to_string())
+            }
+            RuleKind::NoTradingAfterOpen { minutes } if minutes == 0 || minutes > 375 => {
+                Err("minutes must be between 1 and 375".to_string())
+            }
+            RuleKind::MaxLossPerDay { amount } if !amount.is_finite() || amount <= 0.0 => {
+                Err("loss limit must be positive".to_string())
+            }
+            _ => Ok(()),
+        }
+    }
+
+    pub fn describe(&self) -> String {
+        match self.kind {
+            RuleKind::MaxTradesPerDay { limit } => format!(
+                "At most {} trade{} a day",
+                limit,
+                if limit == 1 { "" } else { "s" }
+            ),
+            RuleKind::NoTradingAfterOpen { minutes } => {
+                format!("No trading in the first {} minutes", minutes)
+            }
+            RuleKind::MaxLossPerDay { amount } => {
+                format!("Stop after a {} loss in a day", rupees(amount))
+            }
+        }
+    }
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ChecklistPhase {
+    PreMarket,
+    PostMarket,
+}
+
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct ChecklistItem {
+    #[serde(default)]
+    pub id: String,
+    pub phase: ChecklistPhase,
+    pub text: String,
+}
+
+/// Answers to the checklist for one day, kept with that day's trades.
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct DailyReview {
+    pub date: NaiveDate,
+    /// Ids of the checklist items ticked off.
+    #[serde(default)]
+    pub checked: Vec<String>,
+    /// Plan written before the open.
+    #[serde(default)]
+    pub pre_market_notes: String,
+    /// What went well or badly, written after the close.
+    #[serde(default)]
+    pub post_market_notes: String,
+}
+
+impl DailyReview {
+    pub fn blank(date: NaiveDate) -> Self {
+        Self {
+            date,
+            checked: Vec::new(),
+            pre_market_notes: String::new(),
+            post_market_notes: String::new(),
+        }
+    }
+
+    fn is_empty(&self) -> bool {
+        self.checked.is_empty()
+            && self.pre_market_notes.trim().is_empty()
+            && self.post_market_notes.trim().is_empty()
+    }
+}
+
+/// A journal trade that broke a rule.
+#[derive(Clone, Debug, Serialize)]
+pub struct Violation {
+    pub rule_id: String,
+    pub rule: String,
+    pub trade_id: String,
+    pub date: NaiveDate,
+    pub message: String,
+}
+
+/// Fills of `trade` that open or add to its position, or flip it to the
+/// other side, oldest first.
+fn entries(trade: &Trade) -> Vec<&Execution> {
+    let mut position = 0i64;
+    trade
+        .sorted_executions()
+        .into_iter()
+        .filter(|e| {
+            let before = position;
+            position += match e.side {
+                Side::Buy => e.quantity as i64,
+                Side::Sell => -(e.quantity as i64),
+            };
+            position.abs() > before.abs()
+                || (position != 0 && position.signum() == -before.signum())
+        })
+        .collect()
+}
+
+/// Check every trade against the enabled rules. `market_open` gives the
+/// start of the normal session on a day, if the market traded.
+pub fn check(
+    rules: &[Rule],
+    trades: &[Trade],
+    market_open: &dyn Fn(NaiveDate) -> Option<NaiveTime>,
+) -> Vec<Violation> {
+    let mut violations = Vec::new();
+    for rule in rules.iter().filter(|r| r.enabled) {
+        let mut flag = |trade: &Trade, date: NaiveDate, message: String| {
+            violations.push(Violation {
+                rule_id: rule.id.clone(),
+                rule: rule.describe(),
+                trade_id: trade.id.clone(),
+                date,
+                message,
+            })
+        };
+
+        match rule.kind {
+            RuleKind::MaxTradesPerDay { limit } => {
+                let mut by_day: BTreeMap<NaiveDate, Vec<(NaiveDateTime, &Trade)>> = BTreeMap::new();
+                for trade in trades {
+                    if let Some(first) = trade.sorted_executions().first() {
+                        by_day
+                            .entry(first.time.date())
+                            .or_default()
+                            .push((first.time, trade));
+                    }
+                }
+                for (day, mut opened) in by_day {
+                    opened.sort_by_key(|(time, _)| *time);
+                    for (index, (_, trade)) in opened.iter().enumerate().skip(limit as usize) {
+                        flag(
+                            trade,
+                            day,
+                            format!(
+                                "Trade {} of the day, over the limit of {}",
+                                index + 1,
+                                limit
+                            ),
+                        );
+                    }
+                }
+            }
+            RuleKind::NoTradingAfterOpen { minutes } => {
+                for trade in trades {
+                    let early = trade.sorted_executions().into_iter().find(|e| {
+                        market_open(e.time.date()).is_some_and(|open| {
+                            let open = e.time.date().and_time(open);
+                            e.time >= open && e.time < open + Duration::minutes(minutes as i64)
+                        })
+                    });
+                    if let Some(execution) = early {
+                        flag(
+                            trade,
+                            execution.time.date(),
+                            format!(
+                                "Filled at {} in the first {} minutes",
+                                execution.time.format("%H:%M"),
+                                minutes
+                            ),
+                        );
+                    }
+                }
+            }
+            RuleKind::MaxLossPerDay { amount } => {
+                // Realized PnL of every closed lot, in the order they closed
+                let mut exits: BTreeMap<NaiveDate, Vec<(NaiveDateTime, f64, &Trade)>> =
+                    BTreeMap::new();
+                for trade in trades {
+                    for lot in trade.closed_lots() {
+                        let sign = match lot.direction {
+                            Direction::Long => 1.0,
+                            Direction::Short => -1.0,
+                        };
+                        let pnl = sign * (lot.exit_price - lot.entry_price) * lot.quantity as f64
+                            - lot.fees;
+                        exits.entry(lot.exit_time.date()).or_default().push((
+                            lot.exit_time,
+                            pnl,
+                            trade,
+                        ));
+                    }
+                }
+
+                for (day, mut closed) in exits {
+                    closed.sort_by_key(|(time, _, _)| *time);
+                    let mut total = 0.0;
+                    let Some((hit, culprit)) = closed.iter().find_map(|(time, pnl, trade)| {
+                        total += pnl;
+                        (total <= -amount).then_some((*time, *trade))
+                    }) else {
+                        continue;
+                    };
+                    flag(
+                        culprit,
+                        day,
+                        format!("Took the day's loss to {}", rupees(total)),
+                    );
+                    // Exits after the hit cut risk, so only new exposure
+                    // counts against the limit
+                    for trade in trades {
+                        let later = entries(trade)
+                            .into_iter()
+                            .filter(|e| e.time.date() == day && e.time > hit)
+                            .map(|e| e.time)
+                            .min();
+                        if let Some(time) = later {
+                            flag(
+                                trade,
+                                day,
+                                format!(
+                                    "Added exposure at {} after the loss limit was hit at {}",
+                                    time.format("%H:%M"),
+                                    hit.format("%H:%M")
+                                ),
+                            );
+                        }
+                    }
+                }
+            }
+        }
+    }
+
+    violations.sort_by(|a, b| (a.date, &a.trade_id).cmp(&(b.date, &b.trade_id)));
+    violations
+}
+
+fn default_checklist() -> Vec<ChecklistItem> {
+    [
+        (
+            ChecklistPhase::PreMarket,
+            "Checked overnight news and global cues",
+        ),
+        (
+            ChecklistPhase::PreMarket,
+            "Marked key levels on the watchlist",
+        ),
+        (ChecklistPhase::PreMarket, "Know my max loss for the day"),
+        (ChecklistPhase::PreMarket, "Rested and focused"),
+        (ChecklistPhase::PostMarket, "Journaled every trade"),
+        (ChecklistPhase::PostMarket, "Followed my rules"),
+        (ChecklistPhase::PostMarket, "Reviewed mistakes and charts"),
+    ]
+    .into_iter()
+    .enumerate()
+    .map(|(index, (phase, text))| ChecklistItem {
+        id: format!("c{}", index + 1),
+        phase,
+        text: text.to_string(),
+    })
+    .collect()
+}
+
+#[derive(Serialize, Deserialize)]
+struct RulesFile {
+    next_id: u64,
+    rules: Vec<Rule>,
+    #[serde(default = "default_checklist")]
+    checklist: Vec<ChecklistItem>,
+    #[serde(default)]
+    reviews: BTreeMap<NaiveDate, DailyReview>,
+}
+
+impl Default for RulesFile {
+    fn default() -> Self {
+        Self {
+            // Past the ids of the default checklist
+            next_id: 100,
+            rules: Vec::new(),
+            checklist: default_checklist(),
+            reviews: BTreeMap::new(),
+        }
+    }
+}
+
+/// Trading rules, the daily checklist and the answers to it, persisted as
+/// JSON after every change.
+pub struct RulesStore {
+    path: PathBuf,
+    data: RulesFile,
+}
+
+impl RulesStore {
+    pub fn load(path: &Path) -> Self {
+        let data = persist::load_json(path);
+
+        Self {
+            path: path.to_path_buf(),
+            data,
+        }
+    }
+
+    fn save(&self) -> Result<(), String> {
+        persist::save_json(&self.path, &self.data)
+    }
+
+    fn next_id(&mut self, prefix: &str) -> String {
+        self.data.next_id += 1;
+        format!("{}{}", prefix, self.data.next_id)
+    }
+
+    pub fn rules(&self) -> &[Rule] {
+        &self.data.rules
+    }
+
+    pub fn insert(&mut self, mut rule: Rule) -> Result<Rule, String> {
+        rule.id = self.next_id("r");
+        self.data.rules.push(rule.clone());
+        self.save()?;
+        Ok(rule)
+    }
+
+    pub fn update(&mut self, id: &str, mut rule: Rule) -> Result<Option<Rule>, String> {
+        let Some(existing) = self.data.rules.iter_mut().find(|r| r.id == id) else {
+            return Ok(None);
+        };
+        rule.id = id.to_string();
+        *existing = rule.clone();
+        self.save()?;
+        Ok(Some(rule))
+    }
+
+    pub fn remove(&mut self, id: &str) -> Result<bool, String> {
+        let before = self.data.rules.len();
+        self.data.rules.retain(|r| r.id != id);
+        if self.data.rules.len() == before {
+            return Ok(false);
+        }
+        self.save()?;
+        Ok(true)
+    }
+
+    pub fn checklist(&self) -> &[ChecklistItem] {
+        &self.data.checklist
+    }
+
+    /// Replace the checklist. Items keep their ids so past answers still
+    /// line up; new items get one.
+    pub fn set_checklist(
+        &mut self,
+        mut items: Vec<ChecklistItem>,
+    ) -> Result<Vec<ChecklistItem>, String> {
+        for item in &mut items {
+            item.text = item.text.trim().to_string();
+            if item.id.is_empty() {
+                item.id = self.next_id("c");
+            }
+        }
+        items.retain(|i| !i.text.is_empty());
+        self.data.checklist = items.clone();
+        self.save()?;
+        Ok(items)
+    }
+
+    pub fn review(&self, date: NaiveDate) -> DailyReview {
+        self.data
+            .reviews
+            .get(&date)
+            .cloned()
+            .unwrap_or_else(|| DailyReview::blank(date))
+    }
+
+    /// Reviews from `from` to `to` inclusive; none when `from` is later.
+    pub fn reviews(&self, from: NaiveDate, to: NaiveDate) -> Vec<DailyReview> {
+        if from > to {
+            return Vec::new();
+        }
+        self.data
+            .reviews
+            .range(from..=to)
+            .map(|(_, r)| r.clone())
+            .collect()
+    }
+
+    /// Save a day's answers; an empty review removes the day.
+    pub fn save_review(&mut self, review: DailyReview) -> Result<DailyReview, String> {
+        if review.is_empty() {
+            self.data.reviews.remove(&review.date);
+        } else {
+            self.data.reviews.insert(review.date, review.clone());
+        }
+        self.save()?;
+        Ok(review)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn fill(side: Side, quantity: u32, price: f64, time: &str) -> Execution {
+        Execution {
+            id: String::new(),
+            side,
+            quantity,
+            price,
+            time: NaiveDateTime::parse_from_str(&format!("2025-03-03 {}", time), "%Y-%m-%d %H:%M")
+                .unwrap(),
+            fees: 0.0,
+            trade_id: None,
+        }
+    }
+
+    fn trade(id: &str, executions: Vec<Execution>) -> Trade {
+        Trade {
+            id: id.to_string(),
+            symbol: "SBIN".to_string(),
+            executions,
+            notes: String::new(),
+            tags: Vec::new(),
+        }
+    }
+
+    #[test]
+    fn max_loss_flags_only_new_exposure_after_the_hit() {
+        let rules = [Rule {
+            id: "r1".to_string(),
+            kind: RuleKind::MaxLossPerDay { amount: 1000.0 },
+            enabled: true,
+        }];
+        let trades = [
+            // Loses 1500 by 10:00
+            trade(
+                "t1",
+                vec![
+                    fill(Side::Buy, 100, 500.0, "09:30"),
+                    fill(Side::Sell, 100, 485.0, "10:00"),
+                ],
+            ),
+            // Opened before the hit and only closed after it
+            trade(
+                "t2",
+                vec![
+                    fill(Side::Buy, 10, 700.0, "09:45"),
+                    fill(Side::Sell, 10, 705.0, "10:30"),
+                ],
+            ),
+            // A new position after the hit
+            trade(
+                "t3",
+                vec![
+                    fill(Side::Sell, 20, 300.0, "11:00"),
+                    fill(Side::Buy, 20, 299.0, "11:30"),
+                ],
+            ),
+        ];
+        let flagged: Vec<String> = check(&rules, &trades, &|_| None)
+            .into_iter()
+            .map(|v| v.trade_id)
+            .collect();
+        assert_eq!(flagged, ["t1", "t3"]);
+    }
+
+    #[test]
+    fn reviews_with_reversed_range_are_empty() {
+        let store = RulesStore {
+            path: PathBuf::new(),
+            data: RulesFile::default(),
+        };
+        let day = |d| NaiveDate::from_ymd_opt(2025, 3, d).unwrap();
+        assert!(store.reviews(day(10), day(1)).is_empty());
+    }
+}
@@ -0,0 +1,665 @@
+//! Composite stock score from fundamental and technical factors. Every
+//! metric is ranked against peers, metric ranks are averaged into factor
+//! scores and factor scores are weighted into the composite, so each step
+//! can be shown and explained.
+
+use serde::{Deserialize, Serialize};
+
+use crate::models::fundamentals::Ratios;
+use crate::utils::indicators;
+
+/// Sectors with fewer companies than this are ranked against the whole
+/// universe instead, as a percentile among two or three peers says little.
+pub const MIN_SECTOR_PEERS: usize = 4;
+
+/// Trading days in a year, for annualizing and the 12 month return.
+const YEAR: usize = 252;
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum Factor {
+    Valuation,
+    Quality,
+    Growth,
+    Momentum,
+    Trend,
+    Volatility,
+}
+
+pub const FACTORS: [Factor; 6] = [
+    Factor::Valuation,
+    Factor::Quality,
+    Factor::Growth,
+    Factor::Momentum,
+    Factor::Trend,
+    Factor::Volatility,
+];
+
+impl Factor {
+    pub fn label(self) -> &'static str {
+        match self {
+            Factor::Valuation => "Valuation",
+            Factor::Quality => "Quality",
+            Factor::Growth => "Growth",
+            Factor::Momentum => "Momentum",
+            Factor::Trend => "Trend",
+            Factor::Volatility => "Volatility",
+        }
+    }
+}
+
+/// Relative importance of each factor. Weights need not add up to 100;
+/// they are scaled over the factors a stock has data for.
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct FactorWeights {
+    pub valuation: f64,
+    pub quality: f64,
+    pub growth: f64,
+    pub momentum: f64,
+    pub trend: f64,
+    pub volatility: f64,
+}
+
+impl Default for FactorWeights {
+    fn default() -> Self {
+        Self {
+            valuation: 20.0,
+            quality: 25.0,
+            growth: 20.0,
+            momentum: 15.0,
+            trend: 10.0,
+            volatility: 10.0,
+        }
+    }
+}
+
+impl FactorWeights {
+    pub fn weight(&self, factor: Factor) -> f64 {
+        match factor {
+            Factor::Valuation => self.valuation,
+            Factor::Quality => self.quality,
+            Factor::Growth => self.growth,
+            Factor::Momentum => self.momentum,
+            Factor::Trend => self.trend,
+            Factor::Volatility => self.volatility,
+        }
+    }
+
+    pub fn validate(&self) -> Result<(), String> {
+        if let Some(factor) = FACTORS.iter().find(|f| {
+            let weight = self.weight(**f);
+            !weight.is_finite() || weight < 0.0
+        }) {
+            return Err(format!("{} weight must be zero or more", factor.label()));
+        }
+        if FACTORS.iter().all(|f| self.weight(*f) == 0.0) {
+            return Err("at least one factor needs a weight".to_string());
+        }
+        Ok(())
+    }
+}
+
+/// Price-based inputs, from split-adjusted daily closes.
+#[derive(Clone, Debug, Default, Serialize)]
+pub struct Technicals {
+    /// Percent return over 3 and 12 months.
+    pub return_3m: Option<f64>,
+    pub return_12m: Option<f64>,
+    /// Percent distance of the last close from its 50 and 200 day averages.
+    pub vs_sma50: Option<f64>,
+    pub vs_sma200: Option<f64>,
+    /// Annualized standard deviation of daily returns over the last year,
+    /// in percent.
+    pub volatility: Option<f64>,
+}
+
+pub fn technicals(closes: &[f64]) -> Technicals {
+    let last = closes.last().copied();
+    let change = |days: usize| {
+        let start = closes.iter().rev().nth(days).filter(|s| **s > 0.0)?;
+        Some((last? / start - 1.0) * 100.0)
+    };
+    let distance = |average: Option<f64>| {
+        let average = average.filter(|a| *a > 0.0)?;
+        Some((last? / average - 1.0) * 100.0)
+    };
+    let recent = &closes[closes.len().saturating_sub(YEAR + 1)..];
+    let returns: Vec<f64> = recent
+        .windows(2)
+        .filter(|w| w[0] > 0.0)
+        .map(|w| w[1] / w[0] - 1.0)
+        .collect();
+    Technicals {
+        return_3m: change(63),
+        return_12m: change(YEAR),
+        vs_sma50: distance(indicators::sma(closes, 50)),
+        vs_sma200: distance(indicators::sma(closes, 200)),
+        // Needs a quarter of data to mean anything
+        volatility: (returns.len() >= 60)
+            .then(|| indicators::std_dev(&returns))
+            .flatten()
+            .map(|sd| sd * (YEAR as f64).sqrt() * 100.0),
+    }
+}
+
+/// Everything the score needs about one stock.
+#[derive(Clone, Debug)]
+pub struct StockInputs {
+    pub symbol: String,
+    pub name: String,
+    pub sector: String,
+    pub price: Option<f64>,
+    pub ratios: Ratios,
+    pub technicals: Technicals,
+}
+
+struct Metric {
+    factor: Factor,
+    key: &'static str,
+    label: &'static str,
+    unit: &'static str,
+    higher_is_better: bool,
+    value: fn(&StockInputs) -> Option<f64>,
+}
+
+const METRICS: [Metric; 14] = [
+    // Yields rather than P/E and P/B so loss makers rank as expensive
+    Metric {
+        factor: Factor::Valuation,
+        key: "earnings_yield",
+        label: "Earnings yield",
+        unit: "%",
+        higher_is_better: true,
+        value: |s| Some(s.ratios.ttm_eps? / s.price.filter(|p| *p > 0.0)? * 100.0),
+    },
+    Metric {
+        factor: Factor::Valuation,
+        key: "book_yield",
+        label: "Book to price",
+        unit: "%",
+        higher_is_better: true,
+        value: |s| Some(s.ratios.book_value_per_share? / s.price.filter(|p| *p > 0.0)? * 100.0),
+    },
+    Metric {
+        factor: Factor::Valuation,
+        key: "dividend_yield",
+        label: "Dividend yield",
+        unit: "%",
+        higher_is_better: true,
+        value: |s| s.ratios.dividend_yield,
+    },
+    Metric {
+        factor: Factor::Quality,
+        key: "roe",
+        label: "ROE",
+        unit: "%",
+        higher_is_better: true,
+        value: |s| s.ratios.roe,
+    },
+    Metric {
+        factor: Factor::Quality,
+        key: "roce",
+        label: "ROCE",
+        unit: "%",
+        higher_is_better: true,
+        value: |s| s.ratios.roce,
+    },
+    Metric {
+        factor: Factor::Quality,
+        key: "debt_to_equity",
+        label: "Debt to equity",
+        unit: "",
+        higher_is_better: false,
+        value: |s| s.ratios.debt_to_equity,
+    },
+    Metric {
+        factor: Factor::Quality,
+        key: "net_margin",
+        label: "Net margin",
+        unit: "%",
+        higher_is_better: true,
+        value: |s| s.ratios.net_margin,
+    },
+    Metric {
+        factor: Factor::Growth,
+        key: "revenue_cagr_3y",
+        label: "3 year revenue growth",
+        unit: "%",
+        higher_is_better: true,
+        value: |s| s.ratios.revenue_cagr_3y,
+    },
+    Metric {
+        factor: Factor::Growth,
+        key: "profit_cagr_3y",
+        label: "3 year profit growth",
+        unit: "%",
+        higher_is_better: true,
+        value: |s| s.ratios.profit_cagr_3y,
+    },
+    Metric {
+        factor: Factor::Momentum,
+        key: "return_3m",
+        label: "3 month return",
+        unit: "%",
+        higher_is_better: true,
+        value: |s| s.technicals.return_3m,
+    },
+    Metric {
+        factor: Factor::Momentum,
+        key: "return_12m",
+        label: "12 month return",
+        unit: "%",
+        higher_is_better: true,
+        value: |s| s.technicals.return_12m,
+    },
+    Metric {
+        factor: Factor::Trend,
+        key: "vs_sma50",
+        label: "Distance from 50 day average",
+        unit: "%",
+        higher_is_better: true,
+        value: |s| s.technicals.vs_sma50,
+    },
+    Metric {
+        factor: Factor::Trend,
+        key: "vs_sma200",
+        label: "Distance from 200 day average",
+        unit: "%",
+        higher_is_better: true,
+        value: |s| s.technicals.vs_sma200,
+    },
+    Metric {
+        factor: Factor::Volatility,
+        key: "volatility",
+        label: "Annualized volatility",
+        unit: "%",
+        higher_is_better: false,
+        value: |s| s.technicals.volatility,
+    },
+];
+
+/// Share of `others` that `value` beats, counting ties as half, from 0 to
+/// 100. `others` excludes the value itself; with nothing to compare
+/// against the value sits in the middle.
+fn percentile(value: f64, others: &[f64], higher_is_better: bool) -> f64 {
+    if others.is_empty() {
+        return 50.0;
+    }
+    let beaten = others
+        .iter()
+        .map(|other| match value.partial_cmp(other) {
+            Some(std::cmp::Ordering::Equal) | None => 0.5,
+            Some(std::cmp::Ordering::Greater) if higher_is_better => 1.0,
+            Some(std::cmp::Ordering::Less) if !higher_is_better => 1.0,
+            _ => 0.0,
+        })
+        .sum::<f64>();
+    beaten / others.len() as f64 * 100.0
+}
+
+#[derive(Clone, Debug, Serialize)]
+pub struct MetricScore {
+    pub key: &'static str,
+    pub label: &'static str,
+    pub value: Option<f64>,
+    pub unit: &'static str,
+    pub higher_is_better: bool,
+    /// Rank against the peer group, 100 being best.
+    pub percentile: Option<f64>,
+}
+
+#[derive(Clone, Debug, Serialize)]
+pub struct FactorScore {
+    pub factor: Factor,
+    pub label: &'static str,
+    /// Configured weight, and the share of the composite it ended up with
+    /// after factors without data were left out, in percent.
+    pub weight: f64,
+    pub effective_weight: f64,
+    /// Average metric percentile, 0 to 100.
+    pub score: Option<f64>,
+    /// Points this factor adds to the composite.
+    pub contribution: f64,
+    /// Rank of the factor score among sector peers.
+    pub sector_percentile: Option<f64>,
+    pub metrics: Vec<MetricScore>,
+}
+
+#[derive(Clone, Debug, Serialize)]
+pub struct StockScore {
+    pub symbol: String,
+    pub name: String,
+    pub sector: String,
+    /// "sector" or "market": what the metrics were ranked against.
+    pub peer_group: &'static str,
+    /// Companies in the sector, this one included.
+    pub sector_size: usize,
+    /// 0 to 100; empty when no factor had data.
+    pub composite: Option<f64>,
+    /// Percent of the configured weight backed by data.
+    pub coverage: f64,
+    pub sector_percentile: Option<f64>,
+    pub factors: Vec<FactorScore>,
+    pub why: Vec<String>,
+}
+
+fn same_sector(a: &StockInputs, b: &StockInputs) -> bool {
+    !a.sector.is_empty() && a.sector.eq_ignore_ascii_case(&b.sector)
+}
+
+/// Score every stock in `universe` against its peers, best first.
+pub fn score(universe: &[StockInputs], weights: &FactorWeights) -> Vec<StockScore> {
+    let mut scores: Vec<StockScore> = universe
+        .iter()
+        .enumerate()
+        .map(|(index, stock)| {
+            let sector: Vec<usize> = (0..universe.len())
+                .filter(|i| *i == index || same_sector(stock, &universe[*i]))
+                .collect();
+            let (peer_group, peers) = if sector.len() >= MIN_SECTOR_PEERS {
+                ("sector", sector.clone())
+            } else {
+                ("market", (0..universe.len()).collect())
+            };
+            let factors = FACTORS
+                .iter()
+                .map(|factor| {
+                    let metrics: Vec<MetricScore> = METRICS
+                        .iter()
+                        .filter(|m| m.factor == *factor)
+                        .map(|m| {
+                            let value = (m.value)(stock).filter(|v| v.is_finite());
+                            let others: Vec<f64> = peers
+                                .iter()
+                                .filter(|i| **i != index)
+                                .filter_map(|i| (m.value)(&universe[*i]))
+                                .filter(|v| v.is_finite())
+                                .collect();
+                            MetricScore {
+                                key: m.key,
+                                label: m.label,
+                                value,
+                                unit: m.unit,
+                                higher_is_better: m.higher_is_better,
+                                percentile: value
+                                    .map(|v| percentile(v, &others, m.higher_is_better)),
+                            }
+                        })
+                        .collect();
+                    let ranked: Vec<f64> = metrics.iter().filter_map(|m| m.percentile).collect();
+                    FactorScore {
+                        factor: *factor,
+                        label: factor.label(),
+                        weight: weights.weight(*factor),
+                        effective_weight: 0.0,
+                        score: indicators::mean(&ranked),
+                        contribution: 0.0,
+                        sector_percentile: None,
+                        metrics,
+                    }
+                })
+                .collect();
+            let mut result = StockScore {
+                symbol: stock.symbol.clone(),
+                name: stock.name.clone(),
+                sector: stock.sector.clone(),
+                peer_group,
+                sector_size: sector.len(),
+                composite: None,
+                coverage: 0.0,
+                sector_percentile: None,
+                factors,
+                why: Vec::new(),
+            };
+            combine(&mut result);
+            result
+        })
+        .collect();
+
+    // Sector ranks need every stock's scores first
+    let ranks: Vec<(Option<f64>, Vec<Option<f64>>)> = (0..scores.len())
+        .map(|index| {
+            let peers: Vec<&StockScore> = (0..scores.len())
+                .filter(|i| *i != index && same_sector(&universe[index], &universe[*i]))
+                .map(|i| &scores[i])
+                .collect();
+            let rank = |value: Option<f64>, of: &dyn Fn(&StockScore) -> Option<f64>| {
+                let others: Vec<f64> = peers.iter().filter_map(|p| of(p)).collect();
+                value
+                    .filter(|_| !others.is_empty())
+                    .map(|v| percentile(v, &others, true))
+            };
+            let own = &scores[index];
+            let factors = (0..FACTORS.len())
+                .map(|f| rank(own.factors[f].score, &|s| s.factors[f].score))
+                .collect();
+            (rank(own.composite, &|s| s.composite), factors)
+        })
+        .collect();
+    for (score, (composite, factors)) in scores.iter_mut().zip(ranks) {
+        score.sector_percentile = composite;
+        for (factor, rank) in score.factors.iter_mut().zip(factors) {
+            factor.sector_percentile = rank;
+        }
+        score.why = explain(score);
+    }
+
+    scores.sort_by(|a, b| {
+        b.composite
+            .unwrap_or(-1.0)
+            .total_cmp(&a.composite.unwrap_or(-1.0))
+            .then_with(|| a.symbol.cmp(&b.symbol))
+    });
+    scores
+}
+
+/// Weight the factor scores into the composite, spreading the weight of
+/// factors without data over the rest.
+fn combine(score: &mut StockScore) {
+    let total = score
+        .factors
+        .iter()
+        .filter(|f| f.score.is_some())
+        .fold(0.0, |sum, f| sum + f.weight);
+    let configured = score.factors.iter().fold(0.0, |sum, f| sum + f.weight);
+    if total <= 0.0 {
+        return;
+    }
+    score.coverage = total / configured * 100.0;
+    let mut composite = 0.0;
+    for factor in score.factors.iter_mut() {
+        if let Some(value) = factor.score {
+            factor.effective_weight = factor.weight / total * 100.0;
+            factor.contribution = value * factor.weight / total;
+            composite += factor.contribution;
+        }
+    }
+    score.composite = Some(composite);
+}
+
+fn describe_metric(metric: &MetricScore, peers: &str) -> Option<String> {
+    let (value, percentile) = (metric.value?, metric.percentile?);
+    Some(format!(
+        "{} of {:.1}{} is better than {:.0}% of {}",
+        metric.label, value, metric.unit, percentile, peers
+    ))
+}
+
+/// Plain-language reasons behind the composite: its standing, then each
+/// factor that pulls it up or down, the biggest pull first.
+fn explain(score: &StockScore) -> Vec<String> {
+    let Some(composite) = score.composite else {
+        return vec!["No fundamentals or price history to score".to_string()];
+    };
+    let peers = if score.peer_group == "sector" {
+        format!("{} peers", score.sector)
+    } else {
+        "all scored stocks".to_string()
+    };
+    let mut why = vec![match score.sector_percentile {
+        Some(rank) => format!(
+            "Scores {:.0}/100, ahead of {:.0}% of {} other {} stocks",
+            composite,
+            rank,
+            score.sector_size - 1,
+            score.sector
+        ),
+        None => format!("Scores {:.0}/100", composite),
+    }];
+    if score.peer_group == "market" {
+        why.push(format!(
+            "Fewer than {} companies in {}, so metrics are ranked against all scored stocks",
+            MIN_SECTOR_PEERS,
+            if score.sector.is_empty() {
+                "its sector"
+            } else {
+                &score.sector
+            }
+        ));
+    }
+
+    // Impact relative to an average (50) score, in composite points
+    let mut factors: Vec<(&FactorScore, f64)> = score
+        .factors
+        .iter()
+        .filter_map(|f| Some((f, (f.score? - 50.0) * f.effective_weight / 100.0)))
+        .collect();
+    factors.sort_by(|a, b| b.1.abs().total_cmp(&a.1.abs()));
+    for (factor, impact) in factors {
+        let value = factor.score.unwrap_or_default();
+        let ranked = factor.metrics.iter().filter(|m| m.percentile.is_some());
+        // The metric that best supports the verdict
+        let evidence = if impact >= 0.0 {
+            ranked.max_by(|a, b| {
+                a.percentile
+                    .unwrap_or(0.0)
+                    .total_cmp(&b.percentile.unwrap_or(0.0))
+            })
+        } else {
+            ranked.min_by(|a, b| {
+                a.percentile
+                    .unwrap_or(0.0)
+                    .total_cmp(&b.percentile.unwrap_or(0.0))
+            })
+        };
+        let verdict = match value {
+            v if v >= 65.0 => "a strength",
+            v if v <= 35.0 => "a weakness",
+            _ => "about average",
+        };
+        let mut line = format!(
+            "{} is {} ({:.0}/100, {:+.1} points)",
+            factor.label, verdict, value, impact
+        );
+        if let Some(detail) = evidence.and_then(|m| describe_metric(m, &peers)) {
+            line.push_str(": ");
+            line.push_str(&detail);
+        }
+        why.push(line);
+    }
+
+    let missing: Vec<&str> = score
+        .factors
+        .iter()
+        .filter(|f| f.score.is_none() && f.weight > 0.0)
+        .map(|f| f.label)
+        .collect();
+    if !missing.is_empty() {
+        why.push(format!(
+            "No data for {}, so {} weight was spread over the other factors",
+            missing.join(", "),
+            if missing.len() == 1 { "its" } else { "their" }
+        ));
+    }
+    why
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn stock(symbol: &str, sector: &str, roe: f64) -> StockInputs {
+        StockInputs {
+            symbol: symbol.to_string(),
+            name: symbol.to_string(),
+            sector: sector.to_string(),
+            price: Some(100.0),
+            ratios: Ratios {
+                roe: Some(roe),
+                ..Default::default()
+            },
+            technicals: Technicals::default(),
+        }
+    }
+
+    #[test]
+    fn percentiles_count_ties_as_half_and_respect_direction() {
+        assert_eq!(percentile(5.0, &[1.0, 5.0, 9.0, 3.0], true), 62.5);
+        assert_eq!(percentile(5.0, &[1.0, 5.0, 9.0, 3.0], false), 37.5);
+        assert_eq!(percentile(5.0, &[], true), 50.0);
+    }
+
+    #[test]
+    fn stocks_rank_against_their_sector_or_else_the_market() {
+        let universe = [
+            stock("BANKA", "Banks", 10.0),
+            stock("BANKB", "Banks", 25.0),
+            stock("BANKC", "Banks", 15.0),
+            stock("BANKD", "Banks", 20.0),
+            stock("SOFT", "IT", 12.0),
+        ];
+        let scores = score(&universe, &FactorWeights::default());
+        let order: Vec<&str> = scores.iter().map(|s| s.symbol.as_str()).collect();
+        assert_eq!(order, ["BANKB", "BANKD", "BANKC", "SOFT", "BANKA"]);
+
+        let best = &scores[0];
+        assert_eq!(best.peer_group, "sector");
+        assert_eq!(best.composite, Some(100.0));
+        // Only quality has data, so it carries the whole composite
+        assert_eq!(best.coverage, 25.0);
+        assert_eq!(best.factors[1].effective_weight, 100.0);
+        assert_eq!(best.sector_percentile, Some(100.0));
+        assert!(best
+            .why
+            .last()
+            .unwrap()
+            .starts_with("No data for Valuation"));
+
+        let soft = &scores[3];
+        assert_eq!(soft.peer_group, "market");
+        assert_eq!(soft.composite, Some(25.0));
+        assert!(soft.sector_percentile.is_none());
+    }
+
+    #[test]
+    fn technicals_need_enough_history() {
+        let closes: Vec<f64> = (0..300).map(|i| 100.0 + i as f64).collect();
+        let long = technicals(&closes);
+        assert!((long.return_12m.unwrap() - (399.0 / 147.0 - 1.0) * 100.0).abs() < 1e-9);
+        assert!(long.vs_sma200.unwrap() > long.vs_sma50.unwrap());
+        assert!(long.volatility.is_some());
+
+        let short = technicals(&closes[..40]);
+        assert!(short.return_3m.is_none() && short.vs_sma50.is_none());
+        assert!(short.volatility.is_none());
+    }
+
+    #[test]
+    fn weights_need_one_positive_factor() {
+        assert!(FactorWeights::default().validate().is_ok());
+        let zero = FactorWeights {
+            valuation: 0.0,
+            quality: 0.0,
+            growth: 0.0,
+            momentum: 0.0,
+            trend: 0.0,
+            volatility: 0.0,
+        };
+        assert!(zero.validate().is_err());
+        let negative = FactorWeights {
+            trend: -1.0,
+            ..Default::default()
+        };
+        assert!(negative.validate().is_err());
+    }
+}
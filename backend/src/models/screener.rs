@@ -0,0 +1,1138 @@
+//! Stock screens: filter expressions over price history and fundamentals,
+//! e.g. `roe > 15 and close > sma(200) and volume > 2 * avg_volume(20)`.
+//!
+//! Expressions combine fields (`close`, `roe`, `sector`), functions of a
+//! period in daily bars (`sma(200)`), numbers, quoted text, arithmetic,
+//! comparisons and `and` / `or` / `not`. A stock without the data a
+//! comparison needs never matches it.
+
+use std::collections::HashMap;
+use std::path::{Path, PathBuf};
+
+use chrono::{Duration, NaiveDate, NaiveDateTime};
+use serde::{Deserialize, Serialize};
+
+use crate::models::fundamentals::Ratios;
+use crate::models::market::Bar;
+use crate::utils::indicators;
+use crate::utils::persist;
+
+/// Longest period a function may look back over, in daily bars.
+pub const MAX_PERIOD: u32 = 1000;
+
+/// Longest expression accepted, in characters.
+pub const MAX_EXPRESSION_LENGTH: usize = 2000;
+
+/// Deepest nesting of brackets, `not` and unary minus. Parsing,
+/// type-checking and evaluation all recurse over the tree, so this and
+/// `MAX_OPERATORS` keep a hostile expression from exhausting the stack.
+const MAX_DEPTH: usize = 32;
+
+/// Most operators one expression may join.
+const MAX_OPERATORS: usize = 100;
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+enum Kind {
+    Number,
+    Text,
+    Bool,
+}
+
+pub struct FieldInfo {
+    pub name: &'static str,
+    pub description: &'static str,
+    kind: Kind,
+    value: fn(&StockData) -> Value,
+}
+
+fn number(value: Option<f64>) -> Value {
+    value
+        .filter(|v| v.is_finite())
+        .map_or(Value::Missing, Value::Number)
+}
+
+fn ratio(data: &StockData, get: fn(&Ratios) -> Option<f64>) -> Value {
+    number(data.ratios.as_ref().and_then(get))
+}
+
+fn last_bar(data: &StockData, get: fn(&Bar) -> f64) -> Value {
+    number(data.bars.last().map(get))
+}
+
+pub const FIELDS: [FieldInfo; 24] = [
+    FieldInfo {
+        name: "close",
+        description: "Last daily close",
+        kind: Kind::Number,
+        value: |d| last_bar(d, |b| b.close),
+    },
+    FieldInfo {
+        name: "open",
+        description: "Last daily open",
+        kind: Kind::Number,
+        value: |d| last_bar(d, |b| b.open),
+    },
+    FieldInfo {
+        name: "high",
+        description: "Last daily high",
+        kind: Kind::Number,
+        value: |d| last_bar(d, |b| b.high),
+    },
+    FieldInfo {
+        name: "low",
+        description: "Last daily low",
+        kind: Kind::Number,
+        value: |d| last_bar(d, |b| b.low),
+    },
+    FieldInfo {
+        name: "volume",
+        description: "Last daily volume",
+        kind: Kind::Number,
+        value: |d| last_bar(d, |b| b.volume as f64),
+    },
+    FieldInfo {
+        name: "change",
+        description: "Percent change from the previous close",
+        kind: Kind::Number,
+        value: |d| number(period_return(&d.bars, 1)),
+    },
+    FieldInfo {
+        name: "market_cap",
+        description: "Market capitalisation, ₹ crore",
+        kind: Kind::Number,
+        value: |d| ratio(d, |r| r.market_cap),
+    },
+    FieldInfo {
+        name: "pe",
+        description: "Price to TTM earnings",
+        kind: Kind::Number,
+        value: |d| ratio(d, |r| r.pe),
+    },
+    FieldInfo {
+        name: "pb",
+        description: "Price to book",
+        kind: Kind::Number,
+        value: |d| ratio(d, |r| r.pb),
+    },
+    FieldInfo {
+        name: "eps",
+        description: "TTM earnings per share",
+        kind: Kind::Number,
+        value: |d| ratio(d, |r| r.ttm_eps),
+    },
+    FieldInfo {
+        name: "book_value",
+        description: "Book value per share",
+        kind: Kind::Number,
+        value: |d| ratio(d, |r| r.book_value_per_share),
+    },
+    FieldInfo {
+        name: "roe",
+        description: "Return on equity, %",
+        kind: Kind::Number,
+        value: |d| ratio(d, |r| r.roe),
+    },
+    FieldInfo {
+        name: "roce",
+        description: "Return on capital employed, %",
+        kind: Kind::Number,
+        value: |d| ratio(d, |r| r.roce),
+    },
+    FieldInfo {
+        name: "debt_to_equity",
+        description: "Borrowings over equity",
+        kind: Kind::Number,
+        value: |d| ratio(d, |r| r.debt_to_equity),
+    },
+    FieldInfo {
+        name: "operating_margin",
+        description: "Operating profit over revenue, %",
+        kind: Kind::Number,
+        value: |d| ratio(d, |r| r.operating_margin),
+    },
+    FieldInfo {
+        name: "net_margin",
+        description: "Net profit over revenue, %",
+        kind: Kind::Number,
+        value: |d| ratio(d, |r| r.net_margin),
+    },
+    FieldInfo {
+        name: "dividend_yield",
+        description: "Dividend over price, %",
+        kind: Kind::Number,
+        value: |d| ratio(d, |r| r.dividend_yield),
+    },
+    FieldInfo {
+        name: "revenue_cagr_3y",
+        description: "3 year revenue growth, %",
+        kind: Kind::Number,
+        value: |d| ratio(d, |r| r.revenue_cagr_3y),
+    },
+    FieldInfo {
+        name: "revenue_cagr_5y",
+        description: "5 year revenue growth, %",
+        kind: Kind::Number,
+        value: |d| ratio(d, |r| r.revenue_cagr_5y),
+    },
+    FieldInfo {
+        name: "profit_cagr_3y",
+        description: "3 year profit growth, %",
+        kind: Kind::Number,
+        value: |d| ratio(d, |r| r.profit_cagr_3y),
+    },
+    FieldInfo {
+        name: "profit_cagr_5y",
+        description: "5 year profit growth, %",
+        kind: Kind::Number,
+        value: |d| ratio(d, |r| r.profit_cagr_5y),
+    },
+    FieldInfo {
+        name: "score",
+        description: "Mindsage composite score, 0 to 100",
+        kind: Kind::Number,
+        value: |d| number(d.score),
+    },
+    FieldInfo {
+        name: "sector",
+        description: "Sector name, compared as text",
+        kind: Kind::Text,
+        value: |d| Value::Text(d.sector.clone()),
+    },
+    FieldInfo {
+        name: "industry",
+        description: "Industry name, compared as text",
+        kind: Kind::Text,
+        value: |d| Value::Text(d.industry.clone()),
+    },
+];
+
+pub struct FunctionInfo {
+    pub name: &'static str,
+    pub description: &'static str,
+    /// Bars needed before the result is meaningful, from the period.
+    lookback: fn(u32) -> u32,
+    value: fn(&[Bar], usize) -> Option<f64>,
+}
+
+fn closes(bars: &[Bar]) -> Vec<f64> {
+    bars.iter().map(|b| b.close).collect()
+}
+
+fn tail(bars: &[Bar], period: usize) -> Option<&[Bar]> {
+    (period > 0 && bars.len() >= period).then(|| &bars[bars.len() - period..])
+}
+
+fn period_return(bars: &[Bar], period: usize) -> Option<f64> {
+    let start = bars.iter().rev().nth(period)?.close;
+    let last = bars.last()?.close;
+    (start > 0.0).then(|| (last / start - 1.0) * 100.0)
+}
+
+pub const FUNCTIONS: [FunctionInfo; 8] = [
+    FunctionInfo {
+        name: "sma",
+        description: "Simple moving average of the close",
+        lookback: |n| n,
+        value: |bars, n| indicators::sma(&closes(bars), n),
+    },
+    FunctionInfo {
+        name: "ema",
+        description: "Exponential moving average of the close",
+        lookback: |n| n * 3,
+        value: |bars, n| indicators::ema_series(&closes(bars), n).last().copied(),
+    },
+    FunctionInfo {
+        name: "rsi",
+        description: "Wilder's RSI",
+        lookback: |n| n * 5,
+        value: |bars, n| indicators::rsi(&closes(bars), n),
+    },
+    FunctionInfo {
+        name: "avg_volume",
+        description: "Average daily volume",
+        lookback: |n| n,
+        value: |bars, n| {
+            let volumes: Vec<f64> = tail(bars, n)?.iter().map(|b| b.volume as f64).collect();
+            indicators::mean(&volumes)
+        },
+    },
+    FunctionInfo {
+        name: "highest",
+        description: "Highest high over the period",
+        lookback: |n| n,
+        value: |bars, n| {
+            Some(
+                tail(bars, n)?
+                    .iter()
+                    .map(|b| b.high)
+                    .fold(f64::MIN, f64::max),
+            )
+        },
+    },
+    FunctionInfo {
+        name: "lowest",
+        description: "Lowest low over the period",
+        lookback: |n| n,
+        value: |bars, n| {
+            Some(
+                tail(bars, n)?
+                    .iter()
+                    .map(|b| b.low)
+                    .fold(f64::MAX, f64::min),
+            )
+        },
+    },
+    FunctionInfo {
+        name: "return",
+        description: "Percent change in the close over the period",
+        lookback: |n| n + 1,
+        value: period_return,
+    },
+    FunctionInfo {
+        name: "volatility",
+        description: "Annualized volatility of daily returns, %",
+        lookback: |n| n + 1,
+        value: |bars, n| {
+            let window = tail(bars, n + 1)?;
+            let returns: Vec<f64> = window
+                .windows(2)
+                .filter(|w| w[0].close > 0.0)
+                .map(|w| w[1].close / w[0].close - 1.0)
+                .collect();
+            indicators::std_dev(&returns).map(|sd| sd * 252f64.sqrt() * 100.0)
+        },
+    },
+];
+
+#[derive(Clone, Debug, PartialEq)]
+enum Value {
+    Number(f64),
+    Text(String),
+    Bool(bool),
+    Missing,
+}
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+enum Op {
+    Add,
+    Sub,
+    Mul,
+    Div,
+    Gt,
+    Ge,
+    Lt,
+    Le,
+    Eq,
+    Ne,
+    And,
+    Or,
+}
+
+#[derive(Clone, Debug)]
+enum Expr {
+    Number(f64),
+    Text(String),
+    Field(usize),
+    Call(usize, u32),
+    Neg(Box<Expr>),
+    Not(Box<Expr>),
+    Binary(Op, Box<Expr>, Box<Expr>),
+}
+
+#[derive(Clone, Debug, PartialEq)]
+enum Token {
+    Number(f64),
+    Ident(String),
+    Text(String),
+    Op(&'static str),
+    Open,
+    Close,
+}
+
+fn tokenize(text: &str) -> Result<Vec<(usize, Token)>, String> {
+    let chars: Vec<char> = text.chars().collect();
+    let mut tokens = Vec::new();
+    let mut i = 0;
+    while i < chars.len() {
+        let c = chars[i];
+        let start = i;
+        if c.is_whitespace() {
+            i += 1;
+            continue;
+        }
+        if c.is_ascii_digit() || (c == '.' && chars.get(i + 1).is_some_and(|n| n.is_ascii_digit()))
+        {
+            while i < chars.len() && (chars[i].is_ascii_digit() || chars[i] == '.') {
+                i += 1;
+            }
+            let literal: String = chars[start..i].iter().collect();
+            let value = literal
+                .parse()
+                .map_err(|_| format!("bad number '{}' at position {}", literal, start + 1))?;
+            tokens.push((start, Token::Number(value)));
+            continue;
+        }
+        if c.is_alphabetic() || c == '_' {
+            while i < chars.len() && (chars[i].is_alphanumeric() || chars[i] == '_') {
+                i += 1;
+            }
+            let word: String = chars[start..i].iter().collect();
+            tokens.push((start, Token::Ident(word.to_lowercase())));
+            continue;
+        }
+        if c == '"' || c == '\'' {
+            let end = chars[i + 1..]
+                .iter()
+                .position(|n| *n == c)
+                .ok_or_else(|| format!("unclosed quote at position {}", start + 1))?;
+            tokens.push((
+                start,
+                Token::Text(chars[i + 1..i + 1 + end].iter().collect()),
+            ));
+            i += end + 2;
+            continue;
+        }
+        let two: String = chars[i..(i + 2).min(chars.len())].iter().collect();
+        let (token, width) = match (two.as_str(), c) {
+            (">=", _) => (Token::Op(">="), 2),
+            ("<=", _) => (Token::Op("<="), 2),
+            ("==", _) => (Token::Op("=="), 2),
+            ("!=", _) | ("<>", _) => (Token::Op("!="), 2),
+            ("&&", _) => (Token::Op("and"), 2),
+            ("||", _) => (Token::Op("or"), 2),
+            (_, '>') => (Token::Op(">"), 1),
+            (_, '<') => (Token::Op("<"), 1),
+            (_, '=') => (Token::Op("=="), 1),
+            (_, '+') => (Token::Op("+"), 1),
+            (_, '-') => (Token::Op("-"), 1),
+            (_, '*') => (Token::Op("*"), 1),
+            (_, '/') => (Token::Op("/"), 1),
+            (_, '(') => (Token::Open, 1),
+            (_, ')') => (Token::Close, 1),
+            _ => return Err(format!("unexpected '{}' at position {}", c, start + 1)),
+        };
+        tokens.push((start, token));
+        i += width;
+    }
+    Ok(tokens)
+}
+
+struct Parser {
+    tokens: Vec<(usize, Token)>,
+    next: usize,
+    /// Length of the source, for errors at the end.
+    end: usize,
+    /// Brackets, `not`s and unary minuses currently open.
+    depth: usize,
+    operators: usize,
+}
+
+impl Parser {
+    fn peek(&self) -> Option<&Token> {
+        self.tokens.get(self.next).map(|(_, t)| t)
+    }
+
+    fn position(&self) -> usize {
+        self.tokens.get(self.next).map_or(self.end, |(p, _)| *p) + 1
+    }
+
+    fn error(&self, message: &str) -> String {
+        match self.tokens.get(self.next) {
+            Some(_) => format!("{} at position {}", message, self.position()),
+            None => format!("{} at the end", message),
+        }
+    }
+
+    /// Consume an operator or keyword if it is next.
+    fn accept(&mut self, op: &str) -> bool {
+        let matched = match self.peek() {
+            Some(Token::Op(o)) => *o == op,
+            Some(Token::Ident(word)) => word == op,
+            _ => false,
+        };
+        if matched {
+            self.next += 1;
+        }
+        matched
+    }
+
+    /// Count an operator against `MAX_OPERATORS`.
+    fn operator(&mut self) -> Result<(), String> {
+        self.operators += 1;
+        if self.operators > MAX_OPERATORS {
+            return Err(format!(
+                "the expression can join at most {} operators",
+                MAX_OPERATORS
+            ));
+        }
+        Ok(())
+    }
+
+    /// Parse `inner` one level deeper, up to `MAX_DEPTH`.
+    fn nested(&mut self, inner: fn(&mut Self) -> Result<Expr, String>) -> Result<Expr, String> {
+        if self.depth >= MAX_DEPTH {
+            return Err(self.error(&format!("nesting deeper than {} levels", MAX_DEPTH)));
+        }
+        self.depth += 1;
+        let expr = inner(self);
+        self.depth -= 1;
+        expr
+    }
+
+    fn or(&mut self) -> Result<Expr, String> {
+        let mut left = self.and()?;
+        while self.accept("or") {
+            self.operator()?;
+            left = Expr::Binary(Op::Or, Box::new(left), Box::new(self.and()?));
+        }
+        Ok(left)
+    }
+
+    fn and(&mut self) -> Result<Expr, String> {
+        let mut left = self.not()?;
+        while self.accept("and") {
+            self.operator()?;
+            left = Expr::Binary(Op::And, Box::new(left), Box::new(self.not()?));
+        }
+        Ok(left)
+    }
+
+    fn not(&mut self) -> Result<Expr, String> {
+        if self.accept("not") {
+            self.operator()?;
+            return Ok(Expr::Not(Box::new(self.nested(Self::not)?)));
+        }
+        self.comparison()
+    }
+
+    fn comparison(&mut self) -> Result<Expr, String> {
+        let left = self.sum()?;
+        let ops = [
+            (">=", Op::Ge),
+            ("<=", Op::Le),
+            ("!=", Op::Ne),
+            ("==", Op::Eq),
+            (">", Op::Gt),
+            ("<", Op::Lt),
+        ];
+        for (text, op) in ops {
+            if self.accept(text) {
+                self.operator()?;
+                return Ok(Expr::Binary(op, Box::new(left), Box::new(self.sum()?)));
+            }
+        }
+        Ok(left)
+    }
+
+    fn sum(&mut self) -> Result<Expr, String> {
+        let mut left = self.product()?;
+        loop {
+            let op = if self.accept("+") {
+                Op::Add
+            } else if self.accept("-") {
+                Op::Sub
+            } else {
+                return Ok(left);
+            };
+            self.operator()?;
+            left = Expr::Binary(op, Box::new(left), Box::new(self.product()?));
+        }
+    }
+
+    fn product(&mut self) -> Result<Expr, String> {
+        let mut left = self.unary()?;
+        loop {
+            let op = if self.accept("*") {
+                Op::Mul
+            } else if self.accept("/") {
+                Op::Div
+            } else {
+                return Ok(left);
+            };
+            self.operator()?;
+            left = Expr::Binary(op, Box::new(left), Box::new(self.unary()?));
+        }
+    }
+
+    fn unary(&mut self) -> Result<Expr, String> {
+        if self.accept("-") {
+            self.operator()?;
+            return Ok(Expr::Neg(Box::new(self.nested(Self::unary)?)));
+        }
+        self.primary()
+    }
+
+    fn primary(&mut self) -> Result<Expr, String> {
+        let position = self.position();
+        let Some(token) = self.peek().cloned() else {
+            return Err(self.error("expected a value"));
+        };
+        self.next += 1;
+        match token {
+            Token::Number(value) => Ok(Expr::Number(value)),
+            Token::Text(text) => Ok(Expr::Text(text)),
+            Token::Open => {
+                let inner = self.nested(Self::or)?;
+                if self.peek() != Some(&Token::Close) {
+                    return Err(self.error("expected ')'"));
+                }
+                self.next += 1;
+                Ok(inner)
+            }
+            Token::Ident(name) if self.peek() == Some(&Token::Open) => {
+                let function = FUNCTIONS
+                    .iter()
+                    .position(|f| f.name == name)
+                    .ok_or_else(|| {
+                        format!("unknown function '{}' at position {}", name, position)
+                    })?;
+                self.next += 1;
+                let period = match self.peek() {
+                    Some(Token::Number(n)) if n.fract() == 0.0 && *n >= 1.0 => *n,
+                    _ => return Err(self.error("expected a whole number of days")),
+                };
+                if period > MAX_PERIOD as f64 {
+                    return Err(self.error(&format!("period can be at most {}", MAX_PERIOD)));
+                }
+                self.next += 1;
+                if self.peek() != Some(&Token::Close) {
+                    return Err(self.error("expected ')'"));
+                }
+                self.next += 1;
+                Ok(Expr::Call(function, period as u32))
+            }
+            Token::Ident(name) => FIELDS
+                .iter()
+                .position(|f| f.name == name)
+                .map(Expr::Field)
+                .ok_or_else(|| format!("unknown field '{}' at position {}", name, position)),
+            Token::Op(op) => Err(format!("unexpected '{}' at position {}", op, position)),
+            Token::Close => Err(format!("unexpected ')' at position {}", position)),
+        }
+    }
+}
+
+impl Expr {
+    fn kind(&self) -> Result<Kind, String> {
+        Ok(match self {
+            Expr::Number(_) | Expr::Call(..) => Kind::Number,
+            Expr::Text(_) => Kind::Text,
+            Expr::Field(index) => FIELDS[*index].kind,
+            Expr::Neg(inner) => {
+                if inner.kind()? != Kind::Number {
+                    return Err("'-' needs a number".to_string());
+                }
+                Kind::Number
+            }
+            Expr::Not(inner) => {
+                if inner.kind()? != Kind::Bool {
+                    return Err("'not' needs a condition".to_string());
+                }
+                Kind::Bool
+            }
+            Expr::Binary(op, left, right) => {
+                let (left, right) = (left.kind()?, right.kind()?);
+                match op {
+                    Op::And | Op::Or if left == Kind::Bool && right == Kind::Bool => Kind::Bool,
+                    Op::And | Op::Or => return Err("'and' and 'or' join conditions".to_string()),
+                    Op::Eq | Op::Ne if left == right && left != Kind::Bool => Kind::Bool,
+                    _ if left != Kind::Number || right != Kind::Number => {
+                        return Err("arithmetic and ordering need numbers on both sides".to_string())
+                    }
+                    Op::Add | Op::Sub | Op::Mul | Op::Div => Kind::Number,
+                    _ => Kind::Bool,
+                }
+            }
+        })
+    }
+
+    /// Daily bars the expression needs, so only those are loaded.
+    fn lookback(&self) -> u32 {
+        match self {
+            Expr::Field(_) => 2,
+            Expr::Call(function, period) => (FUNCTIONS[*function].lookback)(*period),
+            Expr::Neg(inner) | Expr::Not(inner) => inner.lookback(),
+            Expr::Binary(_, left, right) => left.lookback().max(right.lookback()),
+            Expr::Number(_) | Expr::Text(_) => 0,
+        }
+    }
+
+    /// Numeric fields and function calls, shown as result columns.
+    fn terms(&self, out: &mut Vec<(String, Expr)>) {
+        let name = match self {
+            Expr::Field(index) if FIELDS[*index].kind == Kind::Number => {
+                FIELDS[*index].name.to_string()
+            }
+            Expr::Call(function, period) => format!("{}({})", FUNCTIONS[*function].name, period),
+            Expr::Neg(inner) | Expr::Not(inner) => return inner.terms(out),
+            Expr::Binary(_, left, right) => {
+                left.terms(out);
+                return right.terms(out);
+            }
+            _ => return,
+        };
+        if out.iter().all(|(existing, _)| *existing != name) {
+            out.push((name, self.clone()));
+        }
+    }
+
+    fn eval(&self, data: &StockData) -> Value {
+        match self {
+            Expr::Number(value) => Value::Number(*value),
+            Expr::Text(text) => Value::Text(text.clone()),
+            Expr::Field(index) => (FIELDS[*index].value)(data),
+            Expr::Call(function, period) => {
+                number((FUNCTIONS[*function].value)(&data.bars, *period as usize))
+            }
+            Expr::Neg(inner) => match inner.eval(data) {
+                Value::Number(value) => Value::Number(-value),
+                _ => Value::Missing,
+            },
+            Expr::Not(inner) => match inner.eval(data) {
+                Value::Bool(value) => Value::Bool(!value),
+                _ => Value::Missing,
+            },
+            Expr::Binary(Op::And, left, right) => match (left.eval(data), right.eval(data)) {
+                (Value::Bool(false), _) | (_, Value::Bool(false)) => Value::Bool(false),
+                (Value::Bool(true), Value::Bool(true)) => Value::Bool(true),
+                _ => Value::Missing,
+            },
+            Expr::Binary(Op::Or, left, right) => match (left.eval(data), right.eval(data)) {
+                (Value::Bool(true), _) | (_, Value::Bool(true)) => Value::Bool(true),
+                (Value::Bool(false), Value::Bool(false)) => Value::Bool(false),
+                _ => Value::Missing,
+            },
+            Expr::Binary(op, left, right) => match (left.eval(data), right.eval(data)) {
+                (Value::Text(a), Value::Text(b)) => {
+                    let equal = a.eq_ignore_ascii_case(&b);
+                    Value::Bool(if *op == Op::Ne { !equal } else { equal })
+                }
+                (Value::Number(a), Value::Number(b)) => match op {
+                    Op::Add => Value::Number(a + b),
+                    Op::Sub => Value::Number(a - b),
+                    Op::Mul => Value::Number(a * b),
+                    Op::Div if b == 0.0 => Value::Missing,
+                    Op::Div => Value::Number(a / b),
+                    Op::Gt => Value::Bool(a > b),
+                    Op::Ge => Value::Bool(a >= b),
+                    Op::Lt => Value::Bool(a < b),
+                    Op::Le => Value::Bool(a <= b),
+                    Op::Eq => Value::Bool(a == b),
+                    Op::Ne => Value::Bool(a != b),
+                    Op::And | Op::Or => unreachable!("handled above"),
+                },
+                _ => Value::Missing,
+            },
+        }
+    }
+}
+
+/// A parsed, type-checked screen expression.
+#[derive(Clone, Debug)]
+pub struct Filter {
+    expr: Expr,
+    terms: Vec<Expr>,
+    pub columns: Vec<String>,
+}
+
+impl Filter {
+    pub fn parse(text: &str) -> Result<Self, String> {
+        if text.chars().count() > MAX_EXPRESSION_LENGTH {
+            return Err(format!(
+                "the expression can be at most {} characters",
+                MAX_EXPRESSION_LENGTH
+            ));
+        }
+        let tokens = tokenize(text)?;
+        if tokens.is_empty() {
+            return Err("the expression is empty".to_string());
+        }
+        let mut parser = Parser {
+            tokens,
+            next: 0,
+            end: text.chars().count(),
+            depth: 0,
+            operators: 0,
+        };
+        let expr = parser.or()?;
+        if parser.next < parser.tokens.len() {
+            return Err(parser.error("unexpected text"));
+        }
+        if expr.kind()? != Kind::Bool {
+            return Err("the expression must be a condition, e.g. 'roe > 15'".to_string());
+        }
+        let mut found = Vec::new();
+        expr.terms(&mut found);
+        let (columns, terms) = found.into_iter().unzip();
+        Ok(Self {
+            expr,
+            terms,
+            columns,
+        })
+    }
+
+    pub fn lookback(&self) -> usize {
+        self.expr.lookback() as usize
+    }
+
+    pub fn matches(&self, data: &StockData) -> bool {
+        self.expr.eval(data) == Value::Bool(true)
+    }
+
+    /// Values of `columns` for a stock.
+    pub fn values(&self, data: &StockData) -> Vec<Option<f64>> {
+        self.terms
+            .iter()
+            .map(|term| match term.eval(data) {
+                Value::Number(value) => Some(value),
+                _ => None,
+            })
+            .collect()
+    }
+}
+
+/// What a screen sees of one stock.
+pub struct StockData {
+    pub symbol: String,
+    pub name: String,
+    pub sector: String,
+    pub industry: String,
+    /// Split-adjusted daily bars, oldest first.
+    pub bars: Vec<Bar>,
+    pub ratios: Option<Ratios>,
+    pub score: Option<f64>,
+}
+
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct ScreenRow {
+    pub symbol: String,
+    pub name: String,
+    pub sector: String,
+    pub close: Option<f64>,
+    pub change: Option<f64>,
+    /// One per column of the run.
+    pub values: Vec<Option<f64>>,
+}
+
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct ScreenRun {
+    pub expression: String,
+    pub ran_at: NaiveDateTime,
+    /// Date of the latest bar seen.
+    pub as_of: Option<NaiveDate>,
+    pub scanned: usize,
+    pub columns: Vec<String>,
+    pub rows: Vec<ScreenRow>,
+    /// Symbols that joined or left the results since the screen's
+    /// previous run.
+    #[serde(default)]
+    pub added: Vec<String>,
+    #[serde(default)]
+    pub removed: Vec<String>,
+}
+
+pub fn run(
+    filter: &Filter,
+    expression: &str,
+    stocks: &[StockData],
+    ran_at: NaiveDateTime,
+) -> ScreenRun {
+    let rows = stocks
+        .iter()
+        .filter(|stock| filter.matches(stock))
+        .map(|stock| ScreenRow {
+            symbol: stock.symbol.clone(),
+            name: stock.name.clone(),
+            sector: stock.sector.clone(),
+            close: stock.bars.last().map(|b| b.close),
+            change: period_return(&stock.bars, 1),
+            values: filter.values(stock),
+        })
+        .collect();
+    ScreenRun {
+        expression: expression.to_string(),
+        ran_at,
+        as_of: stocks
+            .iter()
+            .filter_map(|s| s.bars.last())
+            .map(|b| b.date())
+            .max(),
+        scanned: stocks.len(),
+        columns: filter.columns.clone(),
+        rows,
+        added: Vec::new(),
+        removed: Vec::new(),
+    }
+}
+
+/// A saved screen with the results of its last run.
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct Screen {
+    #[serde(default)]
+    pub id: String,
+    pub name: String,
+    pub expression: String,
+    /// Run after the close on every trading day.
+    #[serde(default)]
+    pub daily: bool,
+    #[serde(default)]
+    pub last_run: Option<ScreenRun>,
+}
+
+#[derive(Serialize, Deserialize, Default)]
+struct ScreenerFile {
+    #[serde(default)]
+    next_id: u64,
+    #[serde(default)]
+    screens: Vec<Screen>,
+}
+
+/// Saved screens, persisted as JSON after every change.
+pub struct ScreenerStore {
+    path: PathBuf,
+    data: ScreenerFile,
+}
+
+impl ScreenerStore {
+    pub fn load(path: &Path) -> Self {
+        let data = persist::load_json(path);
+
+        Self {
+            path: path.to_path_buf(),
+            data,
+        }
+    }
+
+    fn save(&self) -> Result<(), String> {
+        persist::save_json(&self.path, &self.data)
+    }
+
+    fn next_id(&mut self, prefix: &str) -> String {
+        self.data.next_id += 1;
+        format!("{}{}", prefix, self.data.next_id)
+    }
+
+    pub fn screens(&self) -> &[Screen] {
+        &self.data.screens
+    }
+
+    pub fn get(&self, id: &str) -> Option<&Screen> {
+        self.data.screens.iter().find(|s| s.id == id)
+    }
+
+    pub fn insert(&mut self, mut screen: Screen) -> Result<Screen, String> {
+        screen.id = self.next_id("s");
+        screen.last_run = None;
+        self.data.screens.push(screen.clone());
+        self.save()?;
+        Ok(screen)
+    }
+
+    /// Update name, expression and schedule. Results of the last run are
+    /// kept until the next one.
+    pub fn update(&mut self, id: &str, screen: Screen) -> Result<Option<Screen>, String> {
+        let Some(existing) = self.data.screens.iter_mut().find(|s| s.id == id) else {
+            return Ok(None);
+        };
+        existing.name = screen.name;
+        existing.expression = screen.expression;
+        existing.daily = screen.daily;
+        let updated = existing.clone();
+        self.save()?;
+        Ok(Some(updated))
+    }
+
+    pub fn remove(&mut self, id: &str) -> Result<bool, String> {
+        let before = self.data.screens.len();
+        self.data.screens.retain(|s| s.id != id);
+        if self.data.screens.len() == before {
+            return Ok(false);
+        }
+        self.save()?;
+        Ok(true)
+    }
+
+    /// Keep a run as the screen's latest, noting which symbols changed
+    /// since the one before.
+    pub fn record_run(
+        &mut self,
+        id: &str,
+        mut run: ScreenRun,
+    ) -> Result<Option<ScreenRun>, String> {
+        let Some(screen) = self.data.screens.iter_mut().find(|s| s.id == id) else {
+            return Ok(None);
+        };
+        if let Some(previous) = &screen.last_run {
+            let before: Vec<&String> = previous.rows.iter().map(|r| &r.symbol).collect();
+            let after: Vec<&String> = run.rows.iter().map(|r| &r.symbol).collect();
+            run.added = after
+                .iter()
+                .filter(|s| !before.contains(s))
+                .map(|s| s.to_string())
+                .collect();
+            run.removed = before
+                .iter()
+                .filter(|s| !after.contains(s))
+                .map(|s| s.to_string())
+                .collect();
+        }
+        screen.last_run = Some(run.clone());
+        self.save()?;
+        Ok(Some(run))
+    }
+}
+
+/// First wait before a failed scheduled run is tried again; it doubles
+/// with every further failure up to [`MAX_RETRY_DELAY_MINUTES`].
+const RETRY_DELAY_MINUTES: i64 = 10;
+const MAX_RETRY_DELAY_MINUTES: i64 = 6 * 60;
+
+/// Scheduled screens whose last run failed, e.g. because the file
+/// couldn't be saved, and when each may be tried again.
+#[derive(Default)]
+pub struct RetryBackoff {
+    failed: HashMap<String, (u32, NaiveDateTime)>,
+}
+
+impl RetryBackoff {
+    pub fn ready(&self, id: &str, now: NaiveDateTime) -> bool {
+        self.failed
+            .get(id)
+            .is_none_or(|(_, retry_at)| now >= *retry_at)
+    }
+
+    /// Note a failure at `now` and return when to try again.
+    pub fn failed(&mut self, id: &str, now: NaiveDateTime) -> NaiveDateTime {
+        let failures = self.failed.get(id).map_or(1, |(count, _)| count + 1);
+        let delay = RETRY_DELAY_MINUTES
+            .saturating_mul(1 << (failures - 1).min(16))
+            .min(MAX_RETRY_DELAY_MINUTES);
+        let retry_at = now + Duration::minutes(delay);
+        self.failed.insert(id.to_string(), (failures, retry_at));
+        retry_at
+    }
+
+    pub fn succeeded(&mut self, id: &str) {
+        self.failed.remove(id);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn stock(closes: &[f64]) -> StockData {
+        let start = NaiveDate::from_ymd_opt(2024, 1, 1).unwrap();
+        StockData {
+            symbol: "TEST".to_string(),
+            name: "Test Ltd".to_string(),
+            sector: "Banks".to_string(),
+            industry: "Private Banks".to_string(),
+            bars: closes
+                .iter()
+                .enumerate()
+                .map(|(i, close)| Bar {
+                    time: (start + chrono::Duration::days(i as i64))
+                        .and_hms_opt(15, 30, 0)
+                        .unwrap(),
+                    open: *close,
+                    high: *close,
+                    low: *close,
+                    close: *close,
+                    volume: 1000,
+                })
+                .collect(),
+            ratios: None,
+            score: None,
+        }
+    }
+
+    #[test]
+    fn evaluates_precedence_and_functions() {
+        let data = stock(&[10.0, 20.0, 30.0]);
+        let filter = Filter::parse("close > sma(3) and 1 + 2 * 3 == 7").unwrap();
+        assert!(filter.matches(&data));
+        assert_eq!(filter.columns, vec!["close", "sma(3)"]);
+        assert_eq!(filter.values(&data), vec![Some(30.0), Some(20.0)]);
+        assert_eq!(filter.lookback(), 3);
+        assert!(Filter::parse("sector = 'banks' and not close < 5")
+            .unwrap()
+            .matches(&data));
+    }
+
+    #[test]
+    fn missing_data_never_matches() {
+        let data = stock(&[]);
+        assert!(!Filter::parse("roe > 15").unwrap().matches(&data));
+        assert!(!Filter::parse("not roe > 15").unwrap().matches(&data));
+        assert!(!Filter::parse("close / 0 > 1")
+            .unwrap()
+            .matches(&stock(&[5.0])));
+    }
+
+    #[test]
+    fn rejects_bad_expressions() {
+        for (text, error) in [
+            ("", "empty"),
+            ("roe +", "expected a value at the end"),
+            ("roe > 15 15", "unexpected text"),
+            ("foo > 1", "unknown field 'foo'"),
+            ("bar(3) > 1", "unknown function 'bar'"),
+            ("sma(0) > 1", "whole number"),
+            ("sma(5000) > 1", "at most 1000"),
+            ("roe", "must be a condition"),
+            ("sector > 1", "need numbers"),
+            ("roe and pe", "join conditions"),
+            ("'open", "unclosed quote"),
+            ("roe # 1", "unexpected '#'"),
+        ] {
+            let message = Filter::parse(text).unwrap_err();
+            assert!(message.contains(error), "{}: {}", text, message);
+        }
+    }
+
+    #[test]
+    fn rejects_deep_nesting_without_overflowing() {
+        let text = format!("{}roe > 1{}", "(".repeat(20_000), ")".repeat(20_000));
+        assert!(Filter::parse(&text).unwrap_err().contains("at most"));
+        let text = format!("{}roe > 1{}", "(".repeat(40), ")".repeat(40));
+        assert!(Filter::parse(&text).unwrap_err().contains("nesting"));
+        let text = format!("{}roe > 1", "not ".repeat(40));
+        assert!(Filter::parse(&text).unwrap_err().contains("nesting"));
+        let text = format!("{}roe > 1{}", "(".repeat(10), ")".repeat(10));
+        assert!(Filter::parse(&text).is_ok());
+    }
+
+    #[test]
+    fn rejects_long_operator_chains() {
+        let chain = vec!["roe > 1"; 150].join(" and ");
+        assert!(Filter::parse(&chain).unwrap_err().contains("operators"));
+        let chain = vec!["roe > 1"; 20].join(" and ");
+        assert!(Filter::parse(&chain).is_ok());
+    }
+
+    #[test]
+    fn failed_runs_back_off() {
+        let now = NaiveDate::from_ymd_opt(2025, 1, 2)
+            .unwrap()
+            .and_hms_opt(16, 0, 0)
+            .unwrap();
+        let mut backoff = RetryBackoff::default();
+        assert!(backoff.ready("s1", now));
+
+        let first = backoff.failed("s1", now);
+        assert_eq!(first, now + Duration::minutes(10));
+        assert!(!backoff.ready("s1", now + Duration::minutes(5)));
+        assert!(backoff.ready("s1", first));
+        assert!(backoff.ready("s2", now));
+
+        assert_eq!(backoff.failed("s1", first), first + Duration::minutes(20));
+        for _ in 0..20 {
+            backoff.failed("s1", now);
+        }
+        assert_eq!(backoff.failed("s1", now), now + Duration::hours(6));
+
+        backoff.succeeded("s1");
+        assert!(backoff.ready("s1", now));
+    }
+}
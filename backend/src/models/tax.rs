@@ -0,0 +1,661 @@
+use chrono::{Datelike, Months, NaiveDate, NaiveDateTime, NaiveTime};
+use serde::Serialize;
+
+use crate::models::journal::{Direction, Execution, Side, Trade};
+
+/// How a closed lot is taxed under the Income Tax Act.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, PartialOrd, Ord, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum TaxHead {
+    /// Equity bought and sold the same day, s.43(5).
+    Speculative,
+    /// Futures and options, taxed as business income.
+    NonSpeculative,
+    /// Listed equity held 12 months or less, s.111A.
+    ShortTermCapitalGain,
+    /// Listed equity held more than 12 months, s.112A.
+    LongTermCapitalGain,
+}
+
+impl TaxHead {
+    pub fn label(self) -> &'static str {
+        match self {
+            TaxHead::Speculative => "Intraday (speculative business)",
+            TaxHead::NonSpeculative => "F&O (non-speculative business)",
+            TaxHead::ShortTermCapitalGain => "Short-term capital gains",
+            TaxHead::LongTermCapitalGain => "Long-term capital gains",
+        }
+    }
+
+    fn is_business(self) -> bool {
+        matches!(self, TaxHead::Speculative | TaxHead::NonSpeculative)
+    }
+}
+
+#[derive(Clone, Debug, Serialize)]
+pub struct TaxLot {
+    pub trade_id: String,
+    pub symbol: String,
+    pub head: TaxHead,
+    pub direction: Direction,
+    pub quantity: u32,
+    pub buy_date: NaiveDate,
+    pub sell_date: NaiveDate,
+    /// When the position was closed, which decides the financial year
+    /// and the rate. The sell date for long lots; the buy date for short
+    /// lots, whose sale comes first.
+    pub closed_date: NaiveDate,
+    pub holding_days: i64,
+    pub buy_value: f64,
+    pub sell_value: f64,
+    /// Cost used for the gain: the buy value, or the 31 Jan 2018 fair
+    /// market value when grandfathering raises it.
+    pub cost: f64,
+    pub expenses: f64,
+    pub gain: f64,
+    pub grandfathered: bool,
+    /// A contract still open after its expiry, closed here at the expiry
+    /// close: options at zero, futures at their settlement price.
+    pub expired: bool,
+}
+
+/// What the instrument master says about a symbol.
+#[derive(Clone, Copy, Debug, Default)]
+pub struct SymbolInfo {
+    /// Futures and options, by kind or by F&O segment.
+    pub derivative: bool,
+    /// Expiry of an option contract.
+    pub option_expiry: Option<NaiveDate>,
+    /// Expiry of a futures contract.
+    pub future_expiry: Option<NaiveDate>,
+    /// Final settlement price of a future whose expiry has passed, the
+    /// underlying's close on expiry, when the market data has it.
+    pub settlement_price: Option<f64>,
+}
+
+#[derive(Clone, Debug, Serialize)]
+pub struct HeadSummary {
+    pub head: TaxHead,
+    pub label: &'static str,
+    pub lots: usize,
+    /// Tax audit turnover for business heads: the sum of absolute
+    /// per-lot differences, per the ICAI guidance note. Sale value for
+    /// capital gains.
+    pub turnover: f64,
+    pub sell_value: f64,
+    pub cost: f64,
+    pub expenses: f64,
+    pub profit: f64,
+    pub loss: f64,
+    pub net: f64,
+    /// Capital gains only; business income is taxed at slab rates.
+    pub estimated_tax: Option<f64>,
+}
+
+#[derive(Clone, Debug, Serialize)]
+pub struct TaxReport {
+    /// e.g. "FY 2024-25".
+    pub financial_year: String,
+    pub from: NaiveDate,
+    pub to: NaiveDate,
+    pub heads: Vec<HeadSummary>,
+    pub lots: Vec<TaxLot>,
+    pub ltcg_exemption: f64,
+    pub notes: Vec<String>,
+}
+
+/// Financial year (April to March) containing `date`, by its start year.
+pub fn financial_year(date: NaiveDate) -> i32 {
+    if date.month() >= 4 {
+        date.year()
+    } else {
+        date.year() - 1
+    }
+}
+
+pub fn financial_year_label(start: i32) -> String {
+    format!("FY {}-{:02}", start, (start + 1) % 100)
+}
+
+fn grandfathering_date() -> NaiveDate {
+    NaiveDate::from_ymd_opt(2018, 1, 31).unwrap()
+}
+
+/// Rates changed with the July 2024 budget for transfers on or after
+/// 23 July 2024.
+fn budget_2024() -> NaiveDate {
+    NaiveDate::from_ymd_opt(2024, 7, 23).unwrap()
+}
+
+fn stcg_rate(sold: NaiveDate) -> f64 {
+    if sold >= budget_2024() {
+        0.20
+    } else {
+        0.15
+    }
+}
+
+fn ltcg_rate(sold: NaiveDate) -> f64 {
+    if sold >= budget_2024() {
+        0.125
+    } else {
+        0.10
+    }
+}
+
+/// Futures and options trading symbols end in FUT, or in a strike
+/// followed by CE or PE, e.g. `NIFTY25MARFUT` or `NIFTY2532022000CE`.
+fn looks_like_derivative(symbol: &str) -> bool {
+    let symbol = symbol.trim().to_uppercase();
+    if symbol.ends_with("FUT") {
+        return true;
+    }
+    let Some(rest) = symbol
+        .strip_suffix("CE")
+        .or_else(|| symbol.strip_suffix("PE"))
+    else {
+        return false;
+    };
+    rest.ends_with(|c: char| c.is_ascii_digit())
+        && rest.starts_with(|c: char| c.is_ascii_alphabetic())
+}
+
+/// When a contract left open is settled on its expiry day.
+fn expiry_close(expiry: NaiveDate) -> NaiveDateTime {
+    expiry.and_time(NaiveTime::from_hms_opt(15, 30, 0).unwrap())
+}
+
+/// `trade` with any position left open after `expiry` closed at `price`
+/// at the expiry close: zero for an option that expires out of the money,
+/// the final settlement price for a future.
+fn close_at_expiry(trade: &Trade, expiry: NaiveDate, price: f64) -> Option<Trade> {
+    let open = trade.net_quantity();
+    if open == 0 {
+        return None;
+    }
+    let mut closed = trade.clone();
+    closed.executions.push(Execution {
+        id: String::new(),
+        side: if open > 0 { Side::Sell } else { Side::Buy },
+        quantity: open.unsigned_abs() as u32,
+        price,
+        time: expiry_close(expiry),
+        fees: 0.0,
+        trade_id: None,
+    });
+    Some(closed)
+}
+
+fn ltcg_exemption(fy_start: i32) -> f64 {
+    if fy_start >= 2024 {
+        125_000.0
+    } else {
+        100_000.0
+    }
+}
+
+/// Classify every lot closed during the financial year starting in April
+/// of `fy_start`. `lookup` gives what the instrument master knows about a
+/// symbol; symbols it doesn't know are told apart by their name. Options
+/// still open after an expiry before `today` are closed at zero, futures
+/// at their settlement price.
+/// `fmv_2018` gives the highest price on 31 Jan 2018 for grandfathering
+/// long-term gains on equity bought before 1 Feb 2018.
+pub fn report(
+    trades: &[Trade],
+    fy_start: i32,
+    today: NaiveDate,
+    lookup: &dyn Fn(&str) -> Option<SymbolInfo>,
+    fmv_2018: &dyn Fn(&str) -> Option<f64>,
+) -> TaxReport {
+    let from = NaiveDate::from_ymd_opt(fy_start, 4, 1).unwrap();
+    let to = NaiveDate::from_ymd_opt(fy_start + 1, 3, 31).unwrap();
+    let mut notes = Vec::new();
+    let mut missing_fmv: Vec<String> = Vec::new();
+    let mut unknown: Vec<(String, bool)> = Vec::new();
+    let mut expired: Vec<String> = Vec::new();
+    let mut settled_futures: Vec<String> = Vec::new();
+    let mut unsettled: Vec<String> = Vec::new();
+
+    let mut lots: Vec<TaxLot> = Vec::new();
+    for trade in trades {
+        let info = lookup(&trade.symbol);
+        let derivative = match info {
+            Some(info) => info.derivative,
+            None => {
+                let guess = looks_like_derivative(&trade.symbol);
+                if !unknown.iter().any(|(s, _)| *s == trade.symbol) {
+                    unknown.push((trade.symbol.clone(), guess));
+                }
+                guess
+            }
+        };
+        let past = |expiry: &NaiveDate| *expiry < today;
+        let option_expiry = info.and_then(|i| i.option_expiry).filter(past);
+        let future_expiry = info.and_then(|i| i.future_expiry).filter(past);
+        let settlement = match (option_expiry, future_expiry) {
+            (Some(expiry), _) => Some((expiry, 0.0)),
+            (None, Some(expiry)) => match info.and_then(|i| i.settlement_price) {
+                Some(price) => Some((expiry, price)),
+                None => {
+                    if trade.net_quantity() != 0 && !unsettled.contains(&trade.symbol) {
+                        unsettled.push(trade.symbol.clone());
+                    }
+                    None
+                }
+            },
+            (None, None) => None,
+        };
+        let settled = settlement
+            .and_then(|(expiry, price)| close_at_expiry(trade, expiry, price).map(|t| (t, expiry)));
+        for lot in settled.as_ref().map_or(trade, |(t, _)| t).closed_lots() {
+            let (entry, exit) = (lot.entry_time.date(), lot.exit_time.date());
+            if exit < from || exit > to {
+                continue;
+            }
+            let is_expired = settled
+                .as_ref()
+                .is_some_and(|(_, expiry)| lot.exit_time == expiry_close(*expiry));
+            let settled_list = if option_expiry.is_some() {
+                &mut expired
+            } else {
+                &mut settled_futures
+            };
+            if is_expired && !settled_list.contains(&trade.symbol) {
+                settled_list.push(trade.symbol.clone());
+            }
+            let entry_value = lot.entry_price * lot.quantity as f64;
+            let exit_value = lot.exit_price * lot.quantity as f64;
+            let (buy_date, sell_date, buy_value, sell_value) = match lot.direction {
+                Direction::Long => (entry, exit, entry_value, exit_value),
+                Direction::Short => (exit, entry, exit_value, entry_value),
+            };
+
+            // Held more than 12 months means sold after the anniversary
+            let head = if derivative {
+                TaxHead::NonSpeculative
+            } else if entry == exit {
+                TaxHead::Speculative
+            } else if lot.direction == Direction::Long && exit > entry + Months::new(12) {
+                TaxHead::LongTermCapitalGain
+            } else {
+                TaxHead::ShortTermCapitalGain
+            };
+
+            let mut cost = buy_value;
+            let mut grandfathered = false;
+            if head == TaxHead::LongTermCapitalGain && buy_date <= grandfathering_date() {
+                match fmv_2018(&trade.symbol) {
+                    Some(fmv) => {
+                        // Cost is the higher of actual cost and the lower
+                        // of FMV and sale value
+                        let fair = (fmv * lot.quantity as f64).min(sell_value);
+                        if fair > cost {
+                            cost = fair;
+                            grandfathered = true;
+                        }
+                    }
+                    None => {
+                        if !missing_fmv.contains(&trade.symbol) {
+                            missing_fmv.push(trade.symbol.clone());
+                        }
+                    }
+                }
+            }
+
+            lots.push(TaxLot {
+                trade_id: trade.id.clone(),
+                symbol: trade.symbol.clone(),
+                head,
+                direction: lot.direction,
+                quantity: lot.quantity,
+                buy_date,
+                sell_date,
+                closed_date: exit,
+                holding_days: (exit - entry).num_days(),
+                buy_value,
+                sell_value,
+                cost,
+                expenses: lot.fees,
+                gain: sell_value - cost - lot.fees,
+                grandfathered,
+                expired: is_expired,
+            });
+        }
+    }
+    lots.sort_by(|a, b| {
+        (a.head, a.closed_date, &a.symbol).cmp(&(b.head, b.closed_date, &b.symbol))
+    });
+
+    let exemption = ltcg_exemption(fy_start);
+    let heads = [
+        TaxHead::Speculative,
+        TaxHead::NonSpeculative,
+        TaxHead::ShortTermCapitalGain,
+        TaxHead::LongTermCapitalGain,
+    ]
+    .into_iter()
+    .map(|head| summarize(head, &lots, exemption))
+    .collect();
+
+    if !unknown.is_empty() {
+        let list = |derivative: bool| {
+            unknown
+                .iter()
+                .filter(|(_, d)| *d == derivative)
+                .map(|(s, _)| s.as_str())
+                .collect::<Vec<_>>()
+                .join(", ")
+        };
+        let (fno, equity) = (list(true), list(false));
+        let mut note = "Not in the instrument master, so classified by symbol:".to_string();
+        if !fno.is_empty() {
+            note.push_str(&format!(" {} as F&O;", fno));
+        }
+        if !equity.is_empty() {
+            note.push_str(&format!(" {} as equity;", equity));
+        }
+        note.pop();
+        notes.push(format!(
+            "{}. Import the instrument master to confirm.",
+            note
+        ));
+    }
+    if !expired.is_empty() {
+        notes.push(format!(
+            "{} expired while still open and are taken as worthless; record the settlement \
+             as an exit if they expired in the money.",
+            expired.join(", ")
+        ));
+    }
+    if !settled_futures.is_empty() {
+        notes.push(format!(
+            "{} expired while still open and are settled at the underlying's close on expiry.",
+            settled_futures.join(", ")
+        ));
+    }
+    if !unsettled.is_empty() {
+        notes.push(format!(
+            "{} expired while still open but there is no close on expiry to settle them at; \
+             record the settlement as an exit.",
+            unsettled.join(", ")
+        ));
+    }
+    if !missing_fmv.is_empty() {
+        notes.push(format!(
+            "No 31 Jan 2018 price for {}; actual cost used instead of grandfathered cost.",
+            missing_fmv.join(", ")
+        ));
+    }
+    notes.push(
+        "Expenses are the fees recorded in the journal. STT is not deductible against capital \
+         gains, so remove it from STCG/LTCG expenses before filing."
+            .to_string(),
+    );
+    notes.push(
+        "Speculative losses can only be set off against speculative income; business income \
+         is taxed at slab rates and is not estimated here."
+            .to_string(),
+    );
+
+    TaxReport {
+        financial_year: financial_year_label(fy_start),
+        from,
+        to,
+        heads,
+        lots,
+        ltcg_exemption: exemption,
+        notes,
+    }
+}
+
+fn summarize(head: TaxHead, lots: &[TaxLot], exemption: f64) -> HeadSummary {
+    let lots: Vec<&TaxLot> = lots.iter().filter(|l| l.head == head).collect();
+    let sum = |f: &dyn Fn(&TaxLot) -> f64| lots.iter().fold(0.0, |total, l| total + f(l));
+    let profit = sum(&|l| l.gain.max(0.0));
+    let loss = sum(&|l| (-l.gain).max(0.0));
+    let net = profit - loss;
+    let sell_value = sum(&|l| l.sell_value);
+
+    // Rates differ either side of the 2024 budget, so tax is worked out
+    // per lot; the LTCG exemption comes off at the blended rate
+    let estimated_tax = match head {
+        TaxHead::ShortTermCapitalGain => {
+            let tax = sum(&|l| l.gain * stcg_rate(l.closed_date));
+            Some(tax.max(0.0))
+        }
+        TaxHead::LongTermCapitalGain => {
+            let taxable = (net - exemption).max(0.0);
+            let weighted = sum(&|l| l.gain * ltcg_rate(l.closed_date));
+            let rate = if net > 0.0 { weighted / net } else { 0.0 };
+            Some(taxable * rate)
+        }
+        _ => None,
+    };
+
+    HeadSummary {
+        head,
+        label: head.label(),
+        lots: lots.len(),
+        turnover: if head.is_business() {
+            sum(&|l| (l.sell_value - l.buy_value).abs())
+        } else {
+            sell_value
+        },
+        sell_value,
+        cost: sum(&|l| l.cost),
+        expenses: sum(&|l| l.expenses),
+        profit,
+        loss,
+        net,
+        estimated_tax,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn fill(side: Side, quantity: u32, price: f64, time: &str) -> Execution {
+        Execution {
+            id: String::new(),
+            side,
+            quantity,
+            price,
+            time: chrono::NaiveDateTime::parse_from_str(time, "%Y-%m-%d %H:%M").unwrap(),
+            fees: 0.0,
+            trade_id: None,
+        }
+    }
+
+    fn trade(symbol: &str, executions: Vec<Execution>) -> Trade {
+        Trade {
+            id: symbol.to_lowercase(),
+            symbol: symbol.to_string(),
+            executions,
+            notes: String::new(),
+            tags: Vec::new(),
+        }
+    }
+
+    fn equity(_: &str) -> Option<SymbolInfo> {
+        Some(SymbolInfo::default())
+    }
+
+    fn no_fmv(_: &str) -> Option<f64> {
+        None
+    }
+
+    fn today() -> NaiveDate {
+        NaiveDate::from_ymd_opt(2026, 6, 1).unwrap()
+    }
+
+    #[test]
+    fn classifies_equity_by_holding_period() {
+        let trades = [
+            trade(
+                "INFY",
+                vec![
+                    fill(Side::Buy, 10, 1500.0, "2025-05-05 09:30"),
+                    fill(Side::Sell, 10, 1520.0, "2025-05-05 14:00"),
+                ],
+            ),
+            trade(
+                "TCS",
+                vec![
+                    fill(Side::Buy, 10, 3000.0, "2025-01-10 10:00"),
+                    fill(Side::Sell, 10, 3300.0, "2025-06-10 10:00"),
+                ],
+            ),
+            trade(
+                "ITC",
+                vec![
+                    fill(Side::Buy, 100, 300.0, "2024-04-01 10:00"),
+                    fill(Side::Sell, 100, 400.0, "2025-04-02 10:00"),
+                ],
+            ),
+        ];
+        let report = report(&trades, 2025, today(), &equity, &no_fmv);
+        let head = |symbol: &str| {
+            report
+                .lots
+                .iter()
+                .find(|l| l.symbol == symbol)
+                .unwrap()
+                .head
+        };
+
+        assert_eq!(head("INFY"), TaxHead::Speculative);
+        assert_eq!(head("TCS"), TaxHead::ShortTermCapitalGain);
+        assert_eq!(head("ITC"), TaxHead::LongTermCapitalGain);
+        let stcg = &report.heads[2];
+        assert_eq!(stcg.net, 3000.0);
+        assert_eq!(stcg.estimated_tax, Some(600.0));
+    }
+
+    #[test]
+    fn unknown_symbols_are_classified_by_name() {
+        let trades = [
+            trade(
+                "NIFTY25JUNFUT",
+                vec![
+                    fill(Side::Buy, 75, 24000.0, "2025-06-02 10:00"),
+                    fill(Side::Sell, 75, 24100.0, "2025-06-03 10:00"),
+                ],
+            ),
+            trade(
+                "NEWLIST",
+                vec![
+                    fill(Side::Buy, 10, 100.0, "2025-06-02 10:00"),
+                    fill(Side::Sell, 10, 110.0, "2025-06-03 10:00"),
+                ],
+            ),
+        ];
+        let report = report(&trades, 2025, today(), &|_| None, &no_fmv);
+
+        assert_eq!(report.lots[0].head, TaxHead::NonSpeculative);
+        assert_eq!(report.lots[1].head, TaxHead::ShortTermCapitalGain);
+        assert!(report.notes[0].contains("NIFTY25JUNFUT as F&O"));
+        assert!(report.notes[0].contains("NEWLIST as equity"));
+        assert!(looks_like_derivative("BANKNIFTY2561254000PE"));
+        assert!(!looks_like_derivative("ACE"));
+    }
+
+    #[test]
+    fn short_lots_count_in_the_year_they_close() {
+        let trades = [trade(
+            "NIFTY25APRFUT",
+            vec![
+                fill(Side::Sell, 75, 23500.0, "2025-03-27 10:00"),
+                fill(Side::Buy, 75, 23000.0, "2025-04-03 10:00"),
+            ],
+        )];
+        let fno = |_: &str| {
+            Some(SymbolInfo {
+                derivative: true,
+                option_expiry: None,
+                ..Default::default()
+            })
+        };
+
+        assert!(report(&trades, 2024, today(), &fno, &no_fmv)
+            .lots
+            .is_empty());
+        let lots = report(&trades, 2025, today(), &fno, &no_fmv).lots;
+        assert_eq!(lots.len(), 1);
+        assert_eq!(
+            lots[0].sell_date,
+            NaiveDate::from_ymd_opt(2025, 3, 27).unwrap()
+        );
+        assert_eq!(
+            lots[0].closed_date,
+            NaiveDate::from_ymd_opt(2025, 4, 3).unwrap()
+        );
+        assert_eq!(lots[0].gain, 37_500.0);
+    }
+
+    #[test]
+    fn options_open_past_expiry_expire_worthless() {
+        let expiry = NaiveDate::from_ymd_opt(2025, 6, 26).unwrap();
+        let trades = [trade(
+            "NIFTY25JUN25000CE",
+            vec![fill(Side::Buy, 75, 40.0, "2025-06-20 10:00")],
+        )];
+        let option = |_: &str| {
+            Some(SymbolInfo {
+                derivative: true,
+                option_expiry: Some(expiry),
+                ..Default::default()
+            })
+        };
+
+        let report = report(&trades, 2025, today(), &option, &no_fmv);
+        assert_eq!(report.lots.len(), 1);
+        assert!(report.lots[0].expired);
+        assert_eq!(report.lots[0].closed_date, expiry);
+        assert_eq!(report.lots[0].gain, -3000.0);
+        assert!(report.notes.iter().any(|n| n.contains("worthless")));
+
+        // Not yet expired: still open, nothing to report
+        let before = super::report(&trades, 2025, expiry, &option, &no_fmv);
+        assert!(before.lots.is_empty());
+    }
+
+    #[test]
+    fn futures_open_past_expiry_settle_at_the_expiry_close() {
+        let expiry = NaiveDate::from_ymd_opt(2025, 6, 26).unwrap();
+        let trades = [trade(
+            "NIFTY25JUNFUT",
+            vec![
+                fill(Side::Buy, 150, 24000.0, "2025-06-20 10:00"),
+                fill(Side::Sell, 75, 24100.0, "2025-06-23 10:00"),
+            ],
+        )];
+        let future = |price: Option<f64>| {
+            move |_: &str| {
+                Some(SymbolInfo {
+                    derivative: true,
+                    future_expiry: Some(expiry),
+                    settlement_price: price,
+                    ..Default::default()
+                })
+            }
+        };
+
+        let report = report(&trades, 2025, today(), &future(Some(24200.0)), &no_fmv);
+        assert_eq!(report.lots.len(), 2);
+        assert!(!report.lots[0].expired);
+        assert!(report.lots[1].expired);
+        assert_eq!(report.lots[1].closed_date, expiry);
+        assert_eq!(report.lots[1].gain, 15_000.0);
+        assert!(report.notes.iter().any(|n| n.contains("settled")));
+
+        // Without a close on expiry the open part stays open and is noted
+        let unsettled = super::report(&trades, 2025, today(), &future(None), &no_fmv);
+        assert_eq!(unsettled.lots.len(), 1);
+        assert!(unsettled
+            .notes
+            .iter()
+            .any(|n| n.contains("no close on expiry")));
+    }
+}
@@ -0,0 +1,383 @@
+use std::collections::{BTreeMap, HashSet};
+
+use chrono::{NaiveDate, NaiveDateTime, NaiveTime};
+
+use crate::models::journal::{Execution, JournalStore, Side, Trade};
+use crate::utils::csv;
+
+/// One row of a broker tradebook.
+pub struct Fill {
+    pub symbol: String,
+    pub execution: Execution,
+}
+
+/// Parse a tradebook or contract-note CSV export. Column names from the
+/// common Indian brokers are recognised; rows that can't be read are
+/// reported by line number instead of failing the whole file.
+pub fn parse(text: &str) -> Result<(Vec<Fill>, Vec<String>), String> {
+    let table = csv::Table::parse(text);
+    let symbol_col = table
+        .column(&[
+            "symbol",
+            "tradingsymbol",
+            "trading symbol",
+            "scrip name",
+            "scrip",
+            "instrument",
+        ])
+        .ok_or("tradebook has no symbol column")?;
+    let side_col = table
+        .column(&[
+            "trade_type",
+            "trade type",
+            "side",
+            "buy/sell",
+            "transaction type",
+            "type",
+        ])
+        .ok_or("tradebook has no buy/sell column")?;
+    let quantity_col = table
+        .column(&["quantity", "qty", "trade qty", "traded qty"])
+        .ok_or("tradebook has no quantity column")?;
+    let price_col = table
+        .column(&["price", "trade price", "traded price", "rate"])
+        .ok_or("tradebook has no price column")?;
+    let id_col = table.column(&[
+        "trade_id",
+        "trade id",
+        "trade no",
+        "trade no.",
+        "trade num",
+        "trade number",
+    ]);
+    let time_col = table.column(&[
+        "order_execution_time",
+        "execution time",
+        "trade time",
+        "time",
+        "timestamp",
+    ]);
+    let date_col = table.column(&["trade_date", "trade date", "date"]);
+    if time_col.is_none() && date_col.is_none() {
+        return Err("tradebook has no date or time column".to_string());
+    }
+
+    let mut fills = Vec::new();
+    let mut errors = Vec::new();
+    for (index, row) in table.rows.iter().enumerate() {
+        // Header is line 1
+        let line = index + 2;
+        let Some(symbol) = table.get(row, Some(symbol_col)) else {
+            errors.push(format!("line {}: missing symbol", line));
+            continue;
+        };
+        let side = match table.get(row, Some(side_col)).map(str::to_lowercase) {
+            Some(side) if side.starts_with('b') => Side::Buy,
+            Some(side) if side.starts_with('s') => Side::Sell,
+            _ => {
+                errors.push(format!("line {}: unknown buy/sell value", line));
+                continue;
+            }
+        };
+        let quantity = table
+            .get(row, Some(quantity_col))
+            .and_then(parse_number)
+            .filter(|q| q.abs() <= f64::from(u32::MAX))
+            .map(|v| v.abs().round() as u32)
+            .filter(|q| *q > 0);
+        let price = table
+            .get(row, Some(price_col))
+            .and_then(parse_number)
+            .filter(|p| *p > 0.0);
+        let (Some(quantity), Some(price)) = (quantity, price) else {
+            errors.push(format!("line {}: invalid quantity or price", line));
+            continue;
+        };
+        let Some(time) = parse_time(table.get(row, time_col), table.get(row, date_col)) else {
+            errors.push(format!("line {}: unreadable trade time", line));
+            continue;
+        };
+
+        fills.push(Fill {
+            symbol: symbol.to_uppercase(),
+            execution: Execution {
+                id: String::new(),
+                side,
+                quantity,
+                price,
+                time,
+                fees: 0.0,
+                trade_id: table.get(row, id_col).map(str::to_string),
+            },
+        });
+    }
+
+    Ok((fills, errors))
+}
+
+/// A finite number, with thousands separators allowed. `inf` and `NaN`
+/// parse as floats but can't be saved back to JSON.
+fn parse_number(value: &str) -> Option<f64> {
+    value
+        .replace(',', "")
+        .parse::<f64>()
+        .ok()
+        .filter(|v| v.is_finite())
+}
+
+const DATE_FORMATS: [&str; 5] = ["%Y-%m-%d", "%d-%m-%Y", "%d/%m/%Y", "%d-%b-%Y", "%d %b %Y"];
+const TIME_FORMATS: [&str; 2] = ["%H:%M:%S", "%H:%M"];
+
+fn parse_date(value: &str) -> Option<NaiveDate> {
+    DATE_FORMATS
+        .iter()
+        .find_map(|f| NaiveDate::parse_from_str(value, f).ok())
+}
+
+/// Execution time column may hold a full timestamp or only the clock time,
+/// in which case it is combined with the trade date.
+pub fn parse_time(time: Option<&str>, date: Option<&str>) -> Option<NaiveDateTime> {
+    if let Some(time) = time {
+        let normalized = time.replace('T', " ");
+        if let Some((day, clock)) = normalized.split_once(' ') {
+            if let (Some(day), Some(clock)) = (parse_date(day), parse_clock(clock)) {
+                return Some(day.and_time(clock));
+            }
+        }
+    }
+    let day = parse_date(date?)?;
+    let clock = time.and_then(parse_clock).unwrap_or(NaiveTime::MIN);
+    Some(day.and_time(clock))
+}
+
+fn parse_clock(value: &str) -> Option<NaiveTime> {
+    let value = value.split('.').next().unwrap_or(value);
+    TIME_FORMATS
+        .iter()
+        .find_map(|f| NaiveTime::parse_from_str(value, f).ok())
+}
+
+/// What an import would do to the journal.
+#[derive(Default)]
+pub struct ImportPlan {
+    pub created: Vec<Trade>,
+    /// Open journal trades that imported fills add to, before and after.
+    pub updated: Vec<(Trade, Trade)>,
+    pub duplicates: usize,
+    /// Fills that reversed a position and were split between two trades.
+    pub splits: Vec<SplitFill>,
+}
+
+/// Where a planned trade is in an [`ImportPlan`].
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum PlannedTrade {
+    Created(usize),
+    Updated(usize),
+}
+
+impl ImportPlan {
+    pub fn trade_mut(&mut self, at: PlannedTrade) -> &mut Trade {
+        match at {
+            PlannedTrade::Created(index) => &mut self.created[index],
+            PlannedTrade::Updated(index) => &mut self.updated[index].1,
+        }
+    }
+}
+
+/// One broker fill split in two: the part that flattens the position is
+/// the last execution of `closing`, the rest the first execution of the
+/// created trade at `opening`. The fill was one order, so it is charged
+/// once between them.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct SplitFill {
+    pub closing: PlannedTrade,
+    pub opening: usize,
+}
+
+/// Group fills into round trips: a trade starts when a symbol's position
+/// leaves zero and ends when it is flat again, with a fill that reverses
+/// the position split between the two. Fills for a symbol that
+/// already has an open journal trade continue that trade. Fills whose
+/// broker trade id is already in the journal, or repeated in the file,
+/// are dropped.
+pub fn plan(journal: &JournalStore, fills: Vec<Fill>) -> ImportPlan {
+    let mut plan = ImportPlan::default();
+    let known = journal.trade_ids();
+    let mut seen: HashSet<String> = HashSet::new();
+
+    let mut by_symbol: BTreeMap<String, Vec<Execution>> = BTreeMap::new();
+    for fill in fills {
+        if let Some(id) = &fill.execution.trade_id {
+            if known.contains(id.as_str()) || !seen.insert(id.clone()) {
+                plan.duplicates += 1;
+                continue;
+            }
+        }
+        by_symbol
+            .entry(fill.symbol)
+            .or_default()
+            .push(fill.execution);
+    }
+
+    for (symbol, mut executions) in by_symbol {
+        executions.sort_by_key(|e| e.time);
+        let existing = journal.open_trade(&symbol).cloned();
+        let mut current = existing.clone();
+        // The trade the last split fill closed, until the trade it opened
+        // is planned too
+        let mut split_from = None;
+
+        for mut execution in executions {
+            // A fill that takes the position through zero closes the trade
+            // with the part that flattens it and opens the next with the rest
+            let open = current.as_ref().map_or(0, Trade::net_quantity);
+            let reduces = match execution.side {
+                Side::Buy => open < 0,
+                Side::Sell => open > 0,
+            };
+            if reduces && i64::from(execution.quantity) > open.abs() {
+                let mut closing = execution.clone();
+                closing.quantity = open.unsigned_abs() as u32;
+                execution.quantity -= closing.quantity;
+                if let Some(trade) = current.as_mut() {
+                    trade.executions.push(closing);
+                }
+                split_from = finish(&mut plan, current.take(), &existing);
+            }
+
+            let trade = current.get_or_insert_with(|| Trade {
+                id: String::new(),
+                symbol: symbol.clone(),
+                executions: Vec::new(),
+                notes: String::new(),
+                tags: Vec::new(),
+            });
+            trade.executions.push(execution);
+            if trade.net_quantity() == 0 {
+                let planned = finish(&mut plan, current.take(), &existing);
+                record_split(&mut plan, split_from.take(), planned);
+            }
+        }
+        let planned = finish(&mut plan, current, &existing);
+        record_split(&mut plan, split_from, planned);
+    }
+
+    plan
+}
+
+fn finish(
+    plan: &mut ImportPlan,
+    trade: Option<Trade>,
+    existing: &Option<Trade>,
+) -> Option<PlannedTrade> {
+    let trade = trade?;
+    Some(match existing {
+        Some(before) if before.id == trade.id => {
+            plan.updated.push((before.clone(), trade));
+            PlannedTrade::Updated(plan.updated.len() - 1)
+        }
+        _ => {
+            plan.created.push(trade);
+            PlannedTrade::Created(plan.created.len() - 1)
+        }
+    })
+}
+
+fn record_split(
+    plan: &mut ImportPlan,
+    closing: Option<PlannedTrade>,
+    opened: Option<PlannedTrade>,
+) {
+    if let (Some(closing), Some(PlannedTrade::Created(opening))) = (closing, opened) {
+        plan.splits.push(SplitFill { closing, opening });
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn fill(side: Side, quantity: u32, minute: u32) -> Fill {
+        Fill {
+            symbol: "INFY".to_string(),
+            execution: Execution {
+                id: String::new(),
+                side,
+                quantity,
+                price: 1500.0,
+                time: NaiveDate::from_ymd_opt(2025, 3, 3)
+                    .unwrap()
+                    .and_hms_opt(10, minute, 0)
+                    .unwrap(),
+                fees: 0.0,
+                trade_id: Some(format!("T{}", minute)),
+            },
+        }
+    }
+
+    #[test]
+    fn a_reversing_fill_closes_one_trade_and_opens_the_next() {
+        let path =
+            std::env::temp_dir().join(format!("slynqix-tradebook-{}.json", std::process::id()));
+        let journal = JournalStore::load(&path);
+        let plan = plan(
+            &journal,
+            vec![fill(Side::Buy, 10, 0), fill(Side::Sell, 15, 1)],
+        );
+
+        assert_eq!(plan.created.len(), 2);
+        assert_eq!(plan.created[0].net_quantity(), 0);
+        assert_eq!(plan.created[0].executions[1].quantity, 10);
+        assert_eq!(plan.created[1].net_quantity(), -5);
+        assert_eq!(
+            plan.created[1].executions[0].trade_id.as_deref(),
+            Some("T1")
+        );
+        assert_eq!(
+            plan.splits,
+            [SplitFill {
+                closing: PlannedTrade::Created(0),
+                opening: 1
+            }]
+        );
+    }
+
+    #[test]
+    fn parse_rejects_non_finite_prices() {
+        let (fills, errors) = parse(
+            "symbol,side,quantity,price,date\nINFY,buy,10,inf,2025-03-03\nTCS,buy,10,NaN,2025-03-03\nITC,buy,1e300,410,2025-03-03\nSBIN,buy,10,800,2025-03-03\n",
+        )
+        .unwrap();
+
+        assert_eq!(fills.len(), 1);
+        assert_eq!(fills[0].symbol, "SBIN");
+        assert_eq!(errors.len(), 3);
+    }
+
+    #[test]
+    fn fills_continue_an_open_trade_in_any_case() {
+        let path = std::env::temp_dir().join(format!(
+            "slynqix-tradebook-case-{}.json",
+            std::process::id()
+        ));
+        let _ = std::fs::remove_file(&path);
+        let mut journal = JournalStore::load(&path);
+        let mut open = fill(Side::Buy, 10, 0);
+        open.execution.trade_id = None;
+        journal
+            .insert(Trade {
+                id: String::new(),
+                symbol: "Infy".to_string(),
+                executions: vec![open.execution],
+                notes: String::new(),
+                tags: Vec::new(),
+            })
+            .unwrap();
+
+        let plan = plan(&journal, vec![fill(Side::Sell, 10, 5)]);
+        assert!(plan.created.is_empty());
+        assert_eq!(plan.updated.len(), 1);
+        assert_eq!(plan.updated[0].1.net_quantity(), 0);
+        std::fs::remove_file(&path).unwrap();
+    }
+}
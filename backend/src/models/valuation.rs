@@ -0,0 +1,566 @@
+//! Intrinsic value models: multi-stage DCF, reverse DCF, Graham number and
+//! dividend discount. Amounts are in ₹ crore and per-share values in ₹,
+//! matching the fundamentals store; rates are percentages.
+
+use std::path::{Path, PathBuf};
+
+use chrono::NaiveDateTime;
+use serde::{Deserialize, Serialize};
+
+use crate::models::fundamentals::{CompanyFundamentals, Ratios};
+use crate::utils::persist;
+
+/// Growth rates a reverse DCF searches between.
+const IMPLIED_GROWTH_RANGE: (f64, f64) = (-50.0, 100.0);
+
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct DcfInputs {
+    /// Free cash flow of the latest year.
+    pub base_cash_flow: f64,
+    /// Growth in the first stage.
+    pub growth: f64,
+    pub growth_years: u32,
+    /// Years over which growth fades in a straight line to the terminal
+    /// rate.
+    pub fade_years: u32,
+    pub terminal_growth: f64,
+    pub discount_rate: f64,
+    /// Borrowings less cash, subtracted from enterprise value.
+    pub net_debt: f64,
+    /// Crore shares.
+    pub shares: f64,
+}
+
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct DdmInputs {
+    /// Dividend per share of the latest year.
+    pub dividend: f64,
+    pub growth: f64,
+    /// Years of `growth` before the terminal rate; zero for a single-stage
+    /// Gordon model.
+    pub growth_years: u32,
+    pub terminal_growth: f64,
+    pub required_return: f64,
+}
+
+/// Every model's assumptions for one stock, as edited on the page and
+/// saved in scenarios.
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct Assumptions {
+    pub dcf: DcfInputs,
+    pub ddm: DdmInputs,
+}
+
+fn check_rate(name: &str, value: f64) -> Result<(), String> {
+    if !value.is_finite() || !(-100.0..=100.0).contains(&value) {
+        return Err(format!("{} must be between -100% and 100%", name));
+    }
+    Ok(())
+}
+
+fn check_years(name: &str, value: u32) -> Result<(), String> {
+    if value > 50 {
+        return Err(format!("{} can be at most 50", name));
+    }
+    Ok(())
+}
+
+impl DcfInputs {
+    pub fn validate(&self) -> Result<(), String> {
+        if !self.base_cash_flow.is_finite() || self.base_cash_flow <= 0.0 {
+            return Err("DCF needs a positive base cash flow".to_string());
+        }
+        if !self.shares.is_finite() || self.shares <= 0.0 {
+            return Err("DCF needs the number of shares".to_string());
+        }
+        if !self.net_debt.is_finite() {
+            return Err("net debt must be a number".to_string());
+        }
+        check_rate("growth", self.growth)?;
+        check_rate("terminal growth", self.terminal_growth)?;
+        check_rate("discount rate", self.discount_rate)?;
+        check_years("growth years", self.growth_years)?;
+        check_years("fade years", self.fade_years)?;
+        if self.discount_rate <= self.terminal_growth {
+            return Err("discount rate must be above terminal growth".to_string());
+        }
+        Ok(())
+    }
+}
+
+impl DdmInputs {
+    pub fn validate(&self) -> Result<(), String> {
+        if !self.dividend.is_finite() || self.dividend <= 0.0 {
+            return Err("dividend discount needs a positive dividend".to_string());
+        }
+        check_rate("dividend growth", self.growth)?;
+        check_rate("terminal dividend growth", self.terminal_growth)?;
+        check_rate("required return", self.required_return)?;
+        check_years("dividend growth years", self.growth_years)?;
+        if self.required_return <= self.terminal_growth {
+            return Err("required return must be above terminal dividend growth".to_string());
+        }
+        Ok(())
+    }
+}
+
+#[derive(Clone, Debug, Serialize)]
+pub struct ProjectedYear {
+    pub year: u32,
+    pub growth: f64,
+    pub cash_flow: f64,
+    pub present_value: f64,
+}
+
+#[derive(Clone, Debug, Serialize)]
+pub struct DcfResult {
+    pub projections: Vec<ProjectedYear>,
+    pub terminal_value: f64,
+    pub terminal_present_value: f64,
+    pub enterprise_value: f64,
+    pub equity_value: f64,
+    pub per_share: f64,
+    /// Share of enterprise value that comes from the terminal value, in
+    /// percent.
+    pub terminal_share: f64,
+}
+
+/// Growth in `year` (1-based): the first-stage rate, then a straight-line
+/// fade that reaches the terminal rate the year after the fade ends.
+fn stage_growth(inputs: &DcfInputs, year: u32) -> f64 {
+    if year <= inputs.growth_years {
+        return inputs.growth;
+    }
+    let step = (year - inputs.growth_years) as f64 / (inputs.fade_years + 1) as f64;
+    inputs.growth - (inputs.growth - inputs.terminal_growth) * step
+}
+
+/// Value assuming inputs were validated.
+fn dcf_value(inputs: &DcfInputs) -> DcfResult {
+    let rate = inputs.discount_rate / 100.0;
+    let mut cash_flow = inputs.base_cash_flow;
+    let projections: Vec<ProjectedYear> = (1..=inputs.growth_years + inputs.fade_years)
+        .map(|year| {
+            let growth = stage_growth(inputs, year);
+            cash_flow *= 1.0 + growth / 100.0;
+            ProjectedYear {
+                year,
+                growth,
+                cash_flow,
+                present_value: cash_flow / (1.0 + rate).powi(year as i32),
+            }
+        })
+        .collect();
+    let years = projections.len() as i32;
+    let terminal_growth = inputs.terminal_growth / 100.0;
+    let terminal_value = cash_flow * (1.0 + terminal_growth) / (rate - terminal_growth);
+    let terminal_present_value = terminal_value / (1.0 + rate).powi(years);
+    let enterprise_value = projections
+        .iter()
+        .fold(terminal_present_value, |sum, p| sum + p.present_value);
+    let equity_value = enterprise_value - inputs.net_debt;
+    DcfResult {
+        terminal_value,
+        terminal_present_value,
+        enterprise_value,
+        equity_value,
+        per_share: equity_value / inputs.shares,
+        terminal_share: terminal_present_value / enterprise_value * 100.0,
+        projections,
+    }
+}
+
+pub fn dcf(inputs: &DcfInputs) -> Result<DcfResult, String> {
+    inputs.validate()?;
+    Ok(dcf_value(inputs))
+}
+
+#[derive(Clone, Debug, Serialize)]
+pub struct ReverseDcf {
+    /// First-stage growth the price implies, with the other DCF
+    /// assumptions unchanged.
+    pub implied_growth: f64,
+    pub price: f64,
+}
+
+/// The first-stage growth at which the DCF value equals `price`. Value
+/// rises with growth, so a bisection finds it; prices outside what the
+/// search range can produce have no answer.
+pub fn reverse_dcf(inputs: &DcfInputs, price: f64) -> Result<ReverseDcf, String> {
+    inputs.validate()?;
+    if inputs.growth_years + inputs.fade_years == 0 {
+        return Err("reverse DCF needs at least one growth or fade year".to_string());
+    }
+    let value_at = |growth: f64| {
+        dcf_value(&DcfInputs {
+            growth,
+            ..inputs.clone()
+        })
+        .per_share
+    };
+    let (mut low, mut high) = IMPLIED_GROWTH_RANGE;
+    if price < value_at(low) || price > value_at(high) {
+        return Err(format!(
+            "the price implies growth outside {}% to {}%",
+            low, high
+        ));
+    }
+    for _ in 0..100 {
+        let middle = (low + high) / 2.0;
+        if value_at(middle) < price {
+            low = middle;
+        } else {
+            high = middle;
+        }
+    }
+    Ok(ReverseDcf {
+        implied_growth: (low + high) / 2.0,
+        price,
+    })
+}
+
+#[derive(Clone, Debug, Serialize)]
+pub struct DdmResult {
+    pub dividends_present_value: f64,
+    pub terminal_present_value: f64,
+    pub per_share: f64,
+}
+
+fn ddm_value(inputs: &DdmInputs) -> DdmResult {
+    let rate = inputs.required_return / 100.0;
+    let growth = inputs.growth / 100.0;
+    let mut dividend = inputs.dividend;
+    let mut dividends_present_value = 0.0;
+    for year in 1..=inputs.growth_years {
+        dividend *= 1.0 + growth;
+        dividends_present_value += dividend / (1.0 + rate).powi(year as i32);
+    }
+    let terminal_growth = inputs.terminal_growth / 100.0;
+    let terminal = dividend * (1.0 + terminal_growth) / (rate - terminal_growth);
+    let terminal_present_value = terminal / (1.0 + rate).powi(inputs.growth_years as i32);
+    DdmResult {
+        dividends_present_value,
+        terminal_present_value,
+        per_share: dividends_present_value + terminal_present_value,
+    }
+}
+
+pub fn ddm(inputs: &DdmInputs) -> Result<DdmResult, String> {
+    inputs.validate()?;
+    Ok(ddm_value(inputs))
+}
+
+/// Graham number, √(22.5 × EPS × book value per share): the most a
+/// defensive investor should pay. Undefined for loss makers or negative
+/// book value.
+pub fn graham_number(eps: f64, book_value_per_share: f64) -> Option<f64> {
+    (eps > 0.0 && book_value_per_share > 0.0).then(|| (22.5 * eps * book_value_per_share).sqrt())
+}
+
+/// Per-share value over a grid of two assumptions. Cells whose inputs are
+/// invalid, e.g. a discount rate below terminal growth, are empty.
+#[derive(Clone, Debug, Serialize)]
+pub struct Sensitivity {
+    pub title: String,
+    pub row_label: String,
+    pub column_label: String,
+    pub rows: Vec<f64>,
+    pub columns: Vec<f64>,
+    pub values: Vec<Vec<Option<f64>>>,
+}
+
+/// Five values centred on `base`.
+fn around(base: f64, step: f64) -> Vec<f64> {
+    (-2..=2).map(|i| base + step * i as f64).collect()
+}
+
+fn grid<T>(
+    title: &str,
+    (row_label, rows): (&str, Vec<f64>),
+    (column_label, columns): (&str, Vec<f64>),
+    inputs: &T,
+    apply: impl Fn(&mut T, f64, f64),
+    value: impl Fn(&T) -> Option<f64>,
+) -> Sensitivity
+where
+    T: Clone,
+{
+    let values = rows
+        .iter()
+        .map(|row| {
+            columns
+                .iter()
+                .map(|column| {
+                    let mut varied = inputs.clone();
+                    apply(&mut varied, *row, *column);
+                    value(&varied)
+                })
+                .collect()
+        })
+        .collect();
+    Sensitivity {
+        title: title.to_string(),
+        row_label: row_label.to_string(),
+        column_label: column_label.to_string(),
+        rows,
+        columns,
+        values,
+    }
+}
+
+pub fn dcf_sensitivity(inputs: &DcfInputs) -> Vec<Sensitivity> {
+    let per_share = |i: &DcfInputs| dcf(i).ok().map(|r| r.per_share);
+    vec![
+        grid(
+            "DCF value: discount rate against terminal growth",
+            ("Discount rate", around(inputs.discount_rate, 1.0)),
+            ("Terminal growth", around(inputs.terminal_growth, 0.5)),
+            inputs,
+            |i, rate, terminal| {
+                i.discount_rate = rate;
+                i.terminal_growth = terminal;
+            },
+            per_share,
+        ),
+        grid(
+            "DCF value: growth against discount rate",
+            ("Growth", around(inputs.growth, 2.5)),
+            ("Discount rate", around(inputs.discount_rate, 1.0)),
+            inputs,
+            |i, growth, rate| {
+                i.growth = growth;
+                i.discount_rate = rate;
+            },
+            per_share,
+        ),
+    ]
+}
+
+pub fn ddm_sensitivity(inputs: &DdmInputs) -> Sensitivity {
+    grid(
+        "Dividend discount value: required return against growth",
+        ("Required return", around(inputs.required_return, 1.0)),
+        ("Dividend growth", around(inputs.growth, 1.0)),
+        inputs,
+        |i, rate, growth| {
+            i.required_return = rate;
+            i.growth = growth;
+        },
+        |i| ddm(i).ok().map(|r| r.per_share),
+    )
+}
+
+/// Growth assumption from history: 3 year profit CAGR, else revenue CAGR,
+/// kept within a range a decade of compounding can plausibly sustain.
+fn historical_growth(ratios: &Ratios) -> f64 {
+    ratios
+        .profit_cagr_3y
+        .or(ratios.revenue_cagr_3y)
+        .map_or(8.0, |g| (g * 10.0).round() / 10.0)
+        .clamp(0.0, 25.0)
+}
+
+/// Starting assumptions from the latest annual statements. Free cash flow
+/// is operating cash flow less capex, whichever sign the file used for
+/// capex; without cash flow data net profit stands in.
+pub fn default_assumptions(company: &CompanyFundamentals, ratios: &Ratios) -> Assumptions {
+    let last_year = company.annual.last();
+    let cash_flow = last_year.and_then(|p| {
+        let operating = p.operating_cash_flow?;
+        Some(operating - p.capex.unwrap_or(0.0).abs())
+    });
+    let base_cash_flow = cash_flow
+        .filter(|c| *c > 0.0)
+        .or(ratios.ttm_net_profit)
+        .unwrap_or(0.0);
+    let net_debt = last_year.map_or(0.0, |p| p.borrowings.unwrap_or(0.0) - p.cash.unwrap_or(0.0));
+    let growth = historical_growth(ratios);
+    Assumptions {
+        dcf: DcfInputs {
+            base_cash_flow,
+            growth,
+            growth_years: 5,
+            fade_years: 5,
+            terminal_growth: 5.0,
+            discount_rate: 12.0,
+            net_debt,
+            shares: company.shares_outstanding.unwrap_or(0.0),
+        },
+        ddm: DdmInputs {
+            dividend: last_year.and_then(|p| p.dividend_per_share).unwrap_or(0.0),
+            growth,
+            growth_years: 5,
+            terminal_growth: 5.0,
+            required_return: 12.0,
+        },
+    }
+}
+
+/// A named set of assumptions kept for a stock.
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct Scenario {
+    #[serde(default)]
+    pub id: String,
+    #[serde(default)]
+    pub symbol: String,
+    pub name: String,
+    pub assumptions: Assumptions,
+    #[serde(default)]
+    pub saved_at: Option<NaiveDateTime>,
+}
+
+#[derive(Serialize, Deserialize, Default)]
+struct ValuationFile {
+    #[serde(default)]
+    next_id: u64,
+    #[serde(default)]
+    scenarios: Vec<Scenario>,
+}
+
+/// Saved valuation scenarios, persisted as JSON after every change.
+pub struct ValuationStore {
+    path: PathBuf,
+    data: ValuationFile,
+}
+
+impl ValuationStore {
+    pub fn load(path: &Path) -> Self {
+        let data = persist::load_json(path);
+
+        Self {
+            path: path.to_path_buf(),
+            data,
+        }
+    }
+
+    fn save(&self) -> Result<(), String> {
+        persist::save_json(&self.path, &self.data)
+    }
+
+    fn next_id(&mut self, prefix: &str) -> String {
+        self.data.next_id += 1;
+        format!("{}{}", prefix, self.data.next_id)
+    }
+
+    pub fn scenarios(&self, symbol: &str) -> Vec<Scenario> {
+        self.data
+            .scenarios
+            .iter()
+            .filter(|s| s.symbol == symbol)
+            .cloned()
+            .collect()
+    }
+
+    /// Save a new scenario, or replace the stock's scenario with the same
+    /// name.
+    pub fn save_scenario(&mut self, mut scenario: Scenario) -> Result<Scenario, String> {
+        match self
+            .data
+            .scenarios
+            .iter_mut()
+            .find(|s| s.symbol == scenario.symbol && s.name.eq_ignore_ascii_case(&scenario.name))
+        {
+            Some(existing) => {
+                scenario.id = existing.id.clone();
+                *existing = scenario.clone();
+            }
+            None => {
+                scenario.id = self.next_id("v");
+                self.data.scenarios.push(scenario.clone());
+            }
+        }
+        self.save()?;
+        Ok(scenario)
+    }
+
+    pub fn remove(&mut self, id: &str) -> Result<bool, String> {
+        let before = self.data.scenarios.len();
+        self.data.scenarios.retain(|s| s.id != id);
+        if self.data.scenarios.len() == before {
+            return Ok(false);
+        }
+        self.save()?;
+        Ok(true)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn inputs(growth_years: u32, fade_years: u32) -> DcfInputs {
+        DcfInputs {
+            base_cash_flow: 100.0,
+            growth: 20.0,
+            growth_years,
+            fade_years,
+            terminal_growth: 5.0,
+            discount_rate: 10.0,
+            net_debt: 100.0,
+            shares: 10.0,
+        }
+    }
+
+    #[test]
+    fn dcf_without_stages_is_the_gordon_value_less_debt() {
+        let result = dcf(&inputs(0, 0)).unwrap();
+        assert!((result.enterprise_value - 2100.0).abs() < 1e-9);
+        assert!((result.per_share - 200.0).abs() < 1e-9);
+        assert_eq!(result.terminal_share, 100.0);
+    }
+
+    #[test]
+    fn growth_fades_to_the_terminal_rate() {
+        let result = dcf(&inputs(2, 2)).unwrap();
+        let growth: Vec<f64> = result.projections.iter().map(|p| p.growth).collect();
+        assert_eq!(growth, [20.0, 20.0, 15.0, 10.0]);
+        assert!((result.projections[1].cash_flow - 144.0).abs() < 1e-9);
+    }
+
+    #[test]
+    fn reverse_dcf_recovers_the_growth_behind_a_price() {
+        let mut base = inputs(5, 5);
+        base.growth = 12.0;
+        let price = dcf(&base).unwrap().per_share;
+        let implied = reverse_dcf(&base, price).unwrap();
+        assert!((implied.implied_growth - 12.0).abs() < 1e-6);
+
+        assert!(reverse_dcf(&base, price * 1000.0).is_err());
+        assert!(reverse_dcf(&inputs(0, 0), price).is_err());
+    }
+
+    #[test]
+    fn dividend_discount_and_graham_number() {
+        let gordon = DdmInputs {
+            dividend: 10.0,
+            growth: 8.0,
+            growth_years: 0,
+            terminal_growth: 5.0,
+            required_return: 10.0,
+        };
+        assert!((ddm(&gordon).unwrap().per_share - 210.0).abs() < 1e-9);
+        let two_stage = DdmInputs {
+            growth_years: 1,
+            ..gordon.clone()
+        };
+        let expected = 10.8 / 1.1 + 10.8 * 1.05 / 0.05 / 1.1;
+        assert!((ddm(&two_stage).unwrap().per_share - expected).abs() < 1e-9);
+
+        assert_eq!(graham_number(4.0, 10.0), Some(30.0));
+        assert_eq!(graham_number(-4.0, 10.0), None);
+    }
+
+    #[test]
+    fn invalid_assumptions_are_refused_and_left_out_of_grids() {
+        let mut below = inputs(5, 5);
+        below.discount_rate = 5.0;
+        assert!(dcf(&below).is_err());
+
+        let mut near = inputs(5, 5);
+        near.discount_rate = 6.0;
+        let grids = dcf_sensitivity(&near);
+        // Discount rate 4% against terminal growth 4% to 6%
+        assert!(grids[0].values[0].iter().all(Option::is_none));
+        assert!(grids[0].values[4].iter().all(Option::is_some));
+    }
+}
@@ -1 +1,224 @@
- 
+use axum::extract::{Path, Query, State};
+use axum::routing::get;
+use axum::{Json, Router};
+use serde::{Deserialize, Serialize};
+
+use crate::models::corporate_action::Adjustment;
+use crate::models::market::{Bar, Timeframe};
+use crate::routes::calendar::parse_date;
+use crate::routes::market::load_series;
+use crate::state::AppState;
+use crate::utils::api::{ApiError, ApiResult};
+use crate::utils::format::rupees;
+use crate::utils::indicators;
+
+/// Bars fed into the analysis; enough for a 200 period average.
+const LOOKBACK: usize = 250;
+
+pub fn router() -> Router<AppState> {
+    Router::new().route("/:symbol", get(analyze))
+}
+
+#[derive(Serialize)]
+pub struct AnalysisRow {
+    pub name: String,
+    pub value: String,
+    pub description: Option<String>,
+}
+
+impl AnalysisRow {
+    fn new(name: &str, value: String, description: &str) -> Self {
+        Self {
+            name: name.to_string(),
+            value,
+            description: Some(description.to_string()),
+        }
+    }
+}
+
+#[derive(Serialize)]
+pub struct Quote {
+    pub price: f64,
+    pub change: f64,
+    pub change_percent: f64,
+    pub volume: u64,
+}
+
+#[derive(Serialize)]
+pub struct AnalysisResult {
+    pub symbol: String,
+    pub timeframe: Timeframe,
+    pub quote: Quote,
+    pub statistical: Vec<AnalysisRow>,
+    pub visual: Vec<AnalysisRow>,
+    pub indicator: Vec<AnalysisRow>,
+    pub suggestions: Vec<String>,
+}
+
+#[derive(Deserialize)]
+struct AnalyzeQuery {
+    #[serde(default)]
+    timeframe: Timeframe,
+    to: Option<String>,
+}
+
+async fn analyze(
+    State(state): State<AppState>,
+    Path(symbol): Path<String>,
+    Query(query): Query<AnalyzeQuery>,
+) -> ApiResult<AnalysisResult> {
+    let to = query.to.as_deref().map(parse_date).transpose()?;
+    let series = load_series(
+        &state,
+        &symbol,
+        query.timeframe,
+        None,
+        to,
+        Some(LOOKBACK),
+        Adjustment::Capital,
+    );
+    if series.bars.len() < 2 {
+        return Err(ApiError::NotFound(format!(
+            "not enough history for '{}' on this timeframe",
+            symbol
+        )));
+    }
+
+    Ok(Json(build(symbol, query.timeframe, &series.bars)))
+}
+
+fn build(symbol: String, timeframe: Timeframe, bars: &[Bar]) -> AnalysisResult {
+    let closes: Vec<f64> = bars.iter().map(|b| b.close).collect();
+    let last = &bars[bars.len() - 1];
+    let prev = &bars[bars.len() - 2];
+    let price = last.close;
+
+    // Levels and statistics over the last 20 bars
+    let recent = &bars[bars.len().saturating_sub(20)..];
+    let recent_closes = &closes[closes.len().saturating_sub(20)..];
+    let support = recent.iter().map(|b| b.low).fold(f64::INFINITY, f64::min);
+    let resistance = recent
+        .iter()
+        .map(|b| b.high)
+        .fold(f64::NEG_INFINITY, f64::max);
+
+    let sma20 = indicators::sma(&closes, 20);
+    let sma50 = indicators::sma(&closes, 50);
+    let sma200 = indicators::sma(&closes, 200);
+    let rsi = indicators::rsi(&closes, 14);
+    let macd = indicators::macd(&closes);
+
+    let trend = match (sma20, sma50) {
+        (Some(fast), Some(slow)) if price > fast && fast > slow => "Uptrend",
+        (Some(fast), Some(slow)) if price < fast && fast < slow => "Downtrend",
+        (Some(_), Some(_)) => "Sideways",
+        _ => "Insufficient data",
+    };
+
+    let statistical = vec![
+        AnalysisRow::new(
+            "Mean",
+            indicators::mean(recent_closes).map_or("-".to_string(), rupees),
+            "Average close over the last 20 bars",
+        ),
+        AnalysisRow::new(
+            "Standard Deviation",
+            indicators::std_dev(recent_closes).map_or("-".to_string(), rupees),
+            "Volatility measure",
+        ),
+        AnalysisRow::new(
+            "Range",
+            rupees(resistance - support),
+            "Difference between highest and lowest price",
+        ),
+    ];
+
+    let visual = vec![
+        AnalysisRow::new("Trend", trend.to_string(), "Current price direction"),
+        AnalysisRow::new("Support", rupees(support), "Lowest low of the last 20 bars"),
+        AnalysisRow::new(
+            "Resistance",
+            rupees(resistance),
+            "Highest high of the last 20 bars",
+        ),
+    ];
+
+    let position = |average: Option<f64>| match average {
+        Some(avg) if avg < price => "Below current price",
+        Some(_) => "Above current price",
+        None => "Not enough history",
+    };
+    let indicator = vec![
+        AnalysisRow::new(
+            "RSI (14)",
+            rsi.map_or("-".to_string(), |v| format!("{:.2}", v)),
+            match rsi {
+                Some(v) if v >= 70.0 => "Overbought",
+                Some(v) if v <= 30.0 => "Oversold",
+                Some(_) => "Neutral",
+                None => "Not enough history",
+            },
+        ),
+        AnalysisRow::new(
+            "MACD / Signal",
+            macd.as_ref().map_or("-".to_string(), |m| {
+                format!("{:.2} / {:.2}", m.macd, m.signal)
+            }),
+            match &macd {
+                Some(m) if m.histogram > 0.0 => "Bullish",
+                Some(_) => "Bearish",
+                None => "Not enough history",
+            },
+        ),
+        AnalysisRow::new(
+            "Moving Average (50)",
+            sma50.map_or("-".to_string(), rupees),
+            position(sma50),
+        ),
+        AnalysisRow::new(
+            "Moving Average (200)",
+            sma200.map_or("-".to_string(), rupees),
+            position(sma200),
+        ),
+    ];
+
+    let mut suggestions = Vec::new();
+    match trend {
+        "Uptrend" => suggestions.push(format!(
+            "Consider buying near support at {} with a stop below it",
+            rupees(support)
+        )),
+        "Downtrend" => suggestions.push(format!(
+            "Avoid fresh longs until price reclaims {}",
+            rupees(sma20.unwrap_or(resistance))
+        )),
+        _ => suggestions.push(format!(
+            "Range-bound between {} and {}",
+            rupees(support),
+            rupees(resistance)
+        )),
+    }
+    suggestions.push(format!("Sell target at {}", rupees(resistance)));
+    if let Some(v) = rsi {
+        if v >= 70.0 {
+            suggestions.push("RSI is overbought, consider booking partial profits".to_string());
+        } else if v <= 30.0 {
+            suggestions.push("RSI is oversold, watch for a reversal".to_string());
+        }
+    }
+
+    AnalysisResult {
+        symbol,
+        timeframe,
+        quote: Quote {
+            price,
+            change: price - prev.close,
+            change_percent: (price - prev.close) / prev.close * 100.0,
+            volume: last.volume,
+        },
+        statistical,
+        visual,
+        indicator,
+        suggestions,
+    }
+}
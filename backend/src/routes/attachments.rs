@@ -0,0 +1,352 @@
+use axum::body::Bytes;
+use axum::extract::{DefaultBodyLimit, Path, Query, State};
+use axum::http::header;
+use axum::response::{IntoResponse, Response};
+use axum::routing::{get, post};
+use axum::{Json, Router};
+use chrono::Duration;
+use serde::Deserialize;
+
+use crate::models::attachment::{
+    self, Attachment, AttachmentKind, MAX_FILE_BYTES, MAX_PER_TRADE, MAX_THUMBNAIL_BYTES,
+};
+use crate::models::calendar::now_ist;
+use crate::models::journal::Trade;
+use crate::models::market::Timeframe;
+use crate::state::AppState;
+use crate::utils::api::{ApiError, ApiResult};
+use crate::utils::chart::trade_chart_svg;
+
+pub fn router() -> Router<AppState> {
+    Router::new()
+        .route(
+            "/",
+            get(list_attachments)
+                .post(upload)
+                // Leave headroom over the file limit so oversized uploads
+                // get our own error rather than a bare 413
+                .layer(DefaultBodyLimit::max(MAX_FILE_BYTES + 1024 * 1024)),
+        )
+        .route("/chart", post(capture_chart))
+        .route(
+            "/:id",
+            get(download)
+                .put(update_attachment)
+                .delete(delete_attachment),
+        )
+        .route(
+            "/:id/thumbnail",
+            get(thumbnail)
+                .put(upload_thumbnail)
+                .layer(DefaultBodyLimit::max(MAX_THUMBNAIL_BYTES + 64 * 1024)),
+        )
+}
+
+const CHART_SIZE: (f64, f64) = (960.0, 480.0);
+const CHART_THUMBNAIL_SIZE: (f64, f64) = (320.0, 160.0);
+
+fn not_found(id: &str) -> ApiError {
+    ApiError::NotFound(format!("no attachment '{}'", id))
+}
+
+fn journal_trade(state: &AppState, id: &str) -> Result<Trade, ApiError> {
+    state
+        .journal
+        .read()
+        .unwrap()
+        .get(id)
+        .cloned()
+        .ok_or_else(|| ApiError::NotFound(format!("no journal trade '{}'", id)))
+}
+
+/// Store a new attachment, checking the per-trade limit under the same
+/// lock so concurrent uploads can't both take the last place.
+fn store(
+    state: &AppState,
+    new: Attachment,
+    bytes: &[u8],
+    thumbnail: Option<&[u8]>,
+) -> ApiResult<Attachment> {
+    let mut attachments = state.attachments.write().unwrap();
+    if attachments.for_trade(&new.trade_id).len() >= MAX_PER_TRADE {
+        return Err(ApiError::BadRequest(format!(
+            "a trade can have at most {} attachments",
+            MAX_PER_TRADE
+        )));
+    }
+    attachments
+        .add(new, bytes, thumbnail)
+        .map(Json)
+        .map_err(ApiError::Internal)
+}
+
+#[derive(Deserialize)]
+struct ListQuery {
+    trade: String,
+}
+
+async fn list_attachments(
+    State(state): State<AppState>,
+    Query(query): Query<ListQuery>,
+) -> ApiResult<Vec<Attachment>> {
+    Ok(Json(
+        state.attachments.read().unwrap().for_trade(&query.trade),
+    ))
+}
+
+#[derive(Deserialize)]
+struct UploadQuery {
+    trade: String,
+    name: String,
+    #[serde(default)]
+    caption: String,
+}
+
+/// Attach a file to a trade. The body is the raw file; its type is taken
+/// from the content, not the request headers.
+async fn upload(
+    State(state): State<AppState>,
+    Query(query): Query<UploadQuery>,
+    body: Bytes,
+) -> ApiResult<Attachment> {
+    journal_trade(&state, &query.trade)?;
+    if body.is_empty() {
+        return Err(ApiError::BadRequest("file is empty".to_string()));
+    }
+    if body.len() > MAX_FILE_BYTES {
+        return Err(ApiError::BadRequest(format!(
+            "file is {:.1} MB, the limit is {} MB",
+            body.len() as f64 / 1_048_576.0,
+            MAX_FILE_BYTES / 1_048_576
+        )));
+    }
+    let name = attachment::clean_name(&query.name);
+    let (content_type, kind) = attachment::sniff(&body, &name).map_err(ApiError::BadRequest)?;
+
+    let new = Attachment {
+        id: String::new(),
+        trade_id: query.trade,
+        name,
+        content_type: content_type.to_string(),
+        size: body.len(),
+        kind,
+        caption: query.caption.trim().to_string(),
+        created_at: now_ist(),
+        has_thumbnail: false,
+    };
+    store(&state, new, &body, None)
+}
+
+#[derive(Deserialize)]
+struct ChartRequest {
+    trade_id: String,
+    /// Defaults to 5 minute bars for trades opened and closed on the same
+    /// day, daily bars otherwise.
+    timeframe: Option<Timeframe>,
+    #[serde(default)]
+    caption: String,
+}
+
+/// Render the price chart around a trade with its fills marked and attach
+/// it as an SVG, together with a small thumbnail.
+async fn capture_chart(
+    State(state): State<AppState>,
+    Json(request): Json<ChartRequest>,
+) -> ApiResult<Attachment> {
+    let trade = journal_trade(&state, &request.trade_id)?;
+    let summary = trade.summarize(None);
+    let (Some(entry), exit) = (summary.entry_time, summary.exit_time) else {
+        return Err(ApiError::BadRequest("trade has no fills".to_string()));
+    };
+
+    let last = exit.unwrap_or_else(now_ist).date();
+    let timeframe = request.timeframe.unwrap_or(if entry.date() == last {
+        Timeframe::M5
+    } else {
+        Timeframe::D1
+    });
+    let daily = timeframe.minutes().is_none();
+    // Some context before the entry and after the exit
+    let (from, to) = if daily {
+        (entry.date() - Duration::days(90), last + Duration::days(10))
+    } else {
+        (entry.date(), last)
+    };
+
+    let bars = {
+        let market = state.market.read().unwrap();
+        let calendar = state.calendar.read().unwrap();
+        market.bars(&trade.symbol, timeframe, Some(from), Some(to), &calendar)
+    };
+    if bars.is_empty() {
+        return Err(ApiError::BadRequest(format!(
+            "no price history for {} between {} and {}",
+            trade.symbol, from, to
+        )));
+    }
+
+    let label = serde_json::to_value(timeframe)
+        .ok()
+        .and_then(|v| v.as_str().map(str::to_string))
+        .unwrap_or_default();
+    let title = format!("{} · {}", trade.symbol, label);
+    let executions = trade.sorted_executions();
+    let chart = trade_chart_svg(
+        &title,
+        &bars,
+        &executions,
+        daily,
+        CHART_SIZE.0,
+        CHART_SIZE.1,
+    );
+    let preview = trade_chart_svg(
+        &title,
+        &bars,
+        &executions,
+        daily,
+        CHART_THUMBNAIL_SIZE.0,
+        CHART_THUMBNAIL_SIZE.1,
+    );
+
+    let new = Attachment {
+        id: String::new(),
+        trade_id: trade.id.clone(),
+        name: format!("{}-{}-{}.svg", trade.symbol, label, entry.format("%Y%m%d")),
+        content_type: "image/svg+xml".to_string(),
+        size: chart.len(),
+        kind: AttachmentKind::Chart,
+        caption: request.caption.trim().to_string(),
+        created_at: now_ist(),
+        has_thumbnail: true,
+    };
+    store(&state, new, chart.as_bytes(), Some(preview.as_bytes()))
+}
+
+/// Images and charts are shown inline, other files are downloaded.
+fn file_response(attachment: &Attachment, content_type: &str, bytes: Vec<u8>) -> Response {
+    let disposition = match attachment.kind {
+        AttachmentKind::Image | AttachmentKind::Chart => "inline",
+        AttachmentKind::File => "attachment",
+    };
+    (
+        [
+            (header::CONTENT_TYPE, content_type.to_string()),
+            (
+                header::CONTENT_DISPOSITION,
+                format!("{}; filename=\"{}\"", disposition, attachment.name),
+            ),
+            (header::X_CONTENT_TYPE_OPTIONS, "nosniff".to_string()),
+            (header::CACHE_CONTROL, "private, max-age=86400".to_string()),
+        ],
+        bytes,
+    )
+        .into_response()
+}
+
+async fn download(
+    State(state): State<AppState>,
+    Path(id): Path<String>,
+) -> Result<Response, ApiError> {
+    let store = state.attachments.read().unwrap();
+    let attachment = store.get(&id).ok_or_else(|| not_found(&id))?;
+    let bytes = store.read(&id).map_err(ApiError::Internal)?;
+    Ok(file_response(attachment, &attachment.content_type, bytes))
+}
+
+async fn thumbnail(
+    State(state): State<AppState>,
+    Path(id): Path<String>,
+) -> Result<Response, ApiError> {
+    let store = state.attachments.read().unwrap();
+    let attachment = store.get(&id).ok_or_else(|| not_found(&id))?;
+    if !attachment.has_thumbnail {
+        return Err(ApiError::NotFound(format!(
+            "attachment '{}' has no thumbnail",
+            id
+        )));
+    }
+    let bytes = store.read_thumbnail(&id).map_err(ApiError::Internal)?;
+    let content_type = match attachment.kind {
+        AttachmentKind::Chart => "image/svg+xml",
+        _ => attachment::sniff(&bytes, "")
+            .map(|(content_type, _)| content_type)
+            .unwrap_or("application/octet-stream"),
+    };
+    Ok(file_response(attachment, content_type, bytes))
+}
+
+/// Store a downscaled preview of an uploaded image. Browsers make these
+/// while uploading since they already have the decoded picture.
+async fn upload_thumbnail(
+    State(state): State<AppState>,
+    Path(id): Path<String>,
+    body: Bytes,
+) -> ApiResult<Attachment> {
+    if body.len() > MAX_THUMBNAIL_BYTES {
+        return Err(ApiError::BadRequest(format!(
+            "thumbnail is {} KB, the limit is {} KB",
+            body.len() / 1024,
+            MAX_THUMBNAIL_BYTES / 1024
+        )));
+    }
+    match attachment::sniff(&body, "") {
+        Ok((_, AttachmentKind::Image)) => {}
+        _ => {
+            return Err(ApiError::BadRequest(
+                "thumbnail must be a PNG, JPEG, GIF or WebP image".to_string(),
+            ))
+        }
+    }
+
+    let mut store = state.attachments.write().unwrap();
+    match store.get(&id).map(|a| a.kind) {
+        None => return Err(not_found(&id)),
+        Some(AttachmentKind::Image) => {}
+        Some(_) => {
+            return Err(ApiError::BadRequest(
+                "only image attachments take a thumbnail".to_string(),
+            ))
+        }
+    }
+    store
+        .set_thumbnail(&id, &body)
+        .map_err(ApiError::Internal)?
+        .map(Json)
+        .ok_or_else(|| not_found(&id))
+}
+
+#[derive(Deserialize)]
+struct AttachmentUpdate {
+    caption: String,
+}
+
+async fn update_attachment(
+    State(state): State<AppState>,
+    Path(id): Path<String>,
+    Json(update): Json<AttachmentUpdate>,
+) -> ApiResult<Attachment> {
+    state
+        .attachments
+        .write()
+        .unwrap()
+        .set_caption(&id, update.caption.trim().to_string())
+        .map_err(ApiError::Internal)?
+        .map(Json)
+        .ok_or_else(|| not_found(&id))
+}
+
+async fn delete_attachment(
+    State(state): State<AppState>,
+    Path(id): Path<String>,
+) -> ApiResult<String> {
+    let removed = state
+        .attachments
+        .write()
+        .unwrap()
+        .remove(&id)
+        .map_err(ApiError::Internal)?;
+    if !removed {
+        return Err(not_found(&id));
+    }
+    Ok(Json(id))
+}
@@ -0,0 +1,83 @@
+use axum::extract::{Path, Query, State};
+use axum::routing::get;
+use axum::{Json, Router};
+use chrono::{Datelike, NaiveDate};
+use serde::{Deserialize, Serialize};
+
+use crate::models::calendar::{now_ist, Holiday, MarketStatus, TradingDay};
+use crate::state::AppState;
+use crate::utils::api::{ApiError, ApiResult};
+
+pub fn router() -> Router<AppState> {
+    Router::new()
+        .route("/status", get(status))
+        .route("/holidays", get(holidays))
+        .route("/day/:date", get(day))
+        .route("/previous-trading-day", get(previous_trading_day))
+}
+
+pub fn parse_date(value: &str) -> Result<NaiveDate, ApiError> {
+    NaiveDate::parse_from_str(value, "%Y-%m-%d")
+        .map_err(|_| ApiError::BadRequest(format!("invalid date '{}', expected YYYY-MM-DD", value)))
+}
+
+async fn status(State(state): State<AppState>) -> ApiResult<MarketStatus> {
+    let calendar = state.calendar.read().unwrap();
+    Ok(Json(calendar.status_at(now_ist())))
+}
+
+#[derive(Deserialize)]
+struct HolidaysQuery {
+    year: Option<i32>,
+}
+
+async fn holidays(
+    State(state): State<AppState>,
+    Query(query): Query<HolidaysQuery>,
+) -> ApiResult<Vec<Holiday>> {
+    let year = query.year.unwrap_or_else(|| now_ist().year());
+    if !(1900..=2100).contains(&year) {
+        return Err(ApiError::BadRequest(
+            "year must be between 1900 and 2100".to_string(),
+        ));
+    }
+    let calendar = state.calendar.read().unwrap();
+    calendar.holidays_in_year(year).map(Json).ok_or_else(|| {
+        let years: Vec<String> = calendar.known_years().iter().map(i32::to_string).collect();
+        ApiError::NotFound(format!(
+            "unknown year {}: the holiday list covers {}",
+            year,
+            years.join(", ")
+        ))
+    })
+}
+
+async fn day(State(state): State<AppState>, Path(date): Path<String>) -> ApiResult<TradingDay> {
+    let date = parse_date(&date)?;
+    let calendar = state.calendar.read().unwrap();
+    Ok(Json(calendar.trading_day(date)))
+}
+
+#[derive(Deserialize)]
+struct PreviousQuery {
+    date: Option<String>,
+}
+
+#[derive(Serialize)]
+struct PreviousResponse {
+    date: NaiveDate,
+}
+
+async fn previous_trading_day(
+    State(state): State<AppState>,
+    Query(query): Query<PreviousQuery>,
+) -> ApiResult<PreviousResponse> {
+    let date = match query.date {
+        Some(raw) => parse_date(&raw)?,
+        None => now_ist().date(),
+    };
+    let calendar = state.calendar.read().unwrap();
+    Ok(Json(PreviousResponse {
+        date: calendar.previous_trading_day(date),
+    }))
+}
@@ -0,0 +1,104 @@
+use axum::extract::State;
+use axum::routing::{get, post};
+use axum::{Json, Router};
+use serde::{Deserialize, Serialize};
+
+use crate::models::charges::{ChargeBreakdown, ChargeSegment, Order, RateTable};
+use crate::models::instrument::{ExchangeSegment, InstrumentKind};
+use crate::models::journal::{Trade, TradeStatus};
+use crate::state::AppState;
+use crate::utils::api::{ApiError, ApiResult};
+
+pub fn router() -> Router<AppState> {
+    Router::new()
+        .route("/rates", get(rate_table))
+        .route("/estimate", post(estimate))
+        .route("/trade", post(trade))
+}
+
+#[derive(Serialize)]
+pub struct ChargeEstimate {
+    pub segment: ChargeSegment,
+    /// One entry per order, in the order they were given.
+    pub orders: Vec<ChargeBreakdown>,
+    pub total: ChargeBreakdown,
+}
+
+/// Charges for every fill of a journal trade. The segment comes from the
+/// instrument master; symbols it doesn't know are charged as NSE equity.
+pub fn trade_charges(state: &AppState, trade: &Trade) -> ChargeEstimate {
+    let (kind, exchange) = state
+        .instruments
+        .read()
+        .unwrap()
+        .get(&trade.symbol)
+        .map_or((InstrumentKind::Equity, ExchangeSegment::NseEq), |i| {
+            (i.kind, i.segment)
+        });
+    let same_day = trade
+        .executions
+        .windows(2)
+        .all(|w| w[0].time.date() == w[1].time.date());
+    let intraday = same_day && trade.summarize(None).status == TradeStatus::Closed;
+    let segment = ChargeSegment::for_instrument(kind, exchange, intraday);
+
+    let orders: Vec<Order> = trade
+        .executions
+        .iter()
+        .map(|e| Order {
+            side: e.side,
+            quantity: e.quantity,
+            price: e.price,
+        })
+        .collect();
+    let (orders, total) = state.charges.read().unwrap().orders(segment, &orders);
+
+    ChargeEstimate {
+        segment,
+        orders,
+        total,
+    }
+}
+
+async fn rate_table(State(state): State<AppState>) -> ApiResult<RateTable> {
+    Ok(Json(state.charges.read().unwrap().clone()))
+}
+
+#[derive(Deserialize)]
+struct EstimateRequest {
+    segment: ChargeSegment,
+    orders: Vec<Order>,
+}
+
+async fn estimate(
+    State(state): State<AppState>,
+    Json(body): Json<EstimateRequest>,
+) -> ApiResult<ChargeEstimate> {
+    if body
+        .orders
+        .iter()
+        .any(|o| o.quantity == 0 || o.price <= 0.0)
+    {
+        return Err(ApiError::BadRequest(
+            "orders need a positive quantity and price".to_string(),
+        ));
+    }
+    let (orders, total) = state
+        .charges
+        .read()
+        .unwrap()
+        .orders(body.segment, &body.orders);
+
+    Ok(Json(ChargeEstimate {
+        segment: body.segment,
+        orders,
+        total,
+    }))
+}
+
+async fn trade(
+    State(state): State<AppState>,
+    Json(trade): Json<Trade>,
+) -> ApiResult<ChargeEstimate> {
+    Ok(Json(trade_charges(&state, &trade)))
+}
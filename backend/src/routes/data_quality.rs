@@ -0,0 +1,179 @@
+use axum::extract::{Path, Query, State};
+use axum::routing::{get, post};
+use axum::{Json, Router};
+use chrono::NaiveDateTime;
+use serde::{Deserialize, Serialize};
+
+use crate::models::calendar::now_ist;
+use crate::models::data_quality::{self, Issue, IssueKind, IssueStatus, ScanSummary};
+use crate::models::market::{self, Bar, MarketStore};
+use crate::state::AppState;
+use crate::utils::api::{ApiError, ApiResult};
+
+pub fn router() -> Router<AppState> {
+    Router::new()
+        .route("/issues", get(list_issues))
+        .route("/scan", get(last_scan).post(scan))
+        .route("/issues/:id/reimport", post(reimport))
+        .route("/issues/:id/correct", post(correct))
+        .route("/issues/:id/ignore", post(ignore))
+}
+
+/// Run the scanner over the whole market store and record the results.
+/// Called by the scheduled job in `main` and the "Scan now" endpoint.
+pub fn run_scan(state: &AppState) -> Result<ScanSummary, String> {
+    let now = now_ist();
+    let (found, symbols) = {
+        let market = state.market.read().unwrap();
+        let calendar = state.calendar.read().unwrap();
+        let actions = state.corporate_actions.read().unwrap();
+        let found = data_quality::scan(&market, &calendar, &actions, now.date());
+        (found, market.daily_symbols().len())
+    };
+
+    state
+        .data_quality
+        .write()
+        .unwrap()
+        .record_scan(found, symbols, now)
+}
+
+#[derive(Deserialize)]
+struct IssuesQuery {
+    status: Option<IssueStatus>,
+    kind: Option<IssueKind>,
+    symbol: Option<String>,
+}
+
+async fn list_issues(
+    State(state): State<AppState>,
+    Query(query): Query<IssuesQuery>,
+) -> ApiResult<Vec<Issue>> {
+    let store = state.data_quality.read().unwrap();
+    let issues = store
+        .issues(query.status)
+        .into_iter()
+        .filter(|issue| query.kind.is_none_or(|k| issue.kind == k))
+        .filter(|issue| query.symbol.as_ref().is_none_or(|s| &issue.symbol == s))
+        .collect();
+
+    Ok(Json(issues))
+}
+
+async fn last_scan(State(state): State<AppState>) -> ApiResult<Option<ScanSummary>> {
+    Ok(Json(
+        state.data_quality.read().unwrap().last_scan().cloned(),
+    ))
+}
+
+async fn scan(State(state): State<AppState>) -> ApiResult<ScanSummary> {
+    run_scan(&state).map(Json).map_err(ApiError::Internal)
+}
+
+fn issue(state: &AppState, id: u64) -> Result<Issue, ApiError> {
+    state
+        .data_quality
+        .read()
+        .unwrap()
+        .get(id)
+        .cloned()
+        .ok_or_else(|| ApiError::NotFound(format!("no data quality issue {}", id)))
+}
+
+#[derive(Serialize)]
+struct FixResponse {
+    issue: Option<Issue>,
+    scan: ScanSummary,
+}
+
+/// Reload the issue's symbol from the files under `data/ohlcv` and rescan.
+async fn reimport(State(state): State<AppState>, Path(id): Path<u64>) -> ApiResult<FixResponse> {
+    let issue = issue(&state, id)?;
+    // Parse the files before taking the lock; only the swap holds it.
+    let fresh = MarketStore::load_dir(&state.data_dir.join("ohlcv")).map_err(ApiError::Internal)?;
+    let reloaded = state
+        .market
+        .write()
+        .unwrap()
+        .replace_symbol(fresh, &issue.symbol);
+    if reloaded == 0 {
+        return Err(ApiError::BadRequest(format!(
+            "no file under data/ohlcv has bars for {}; correct the bar instead",
+            issue.symbol
+        )));
+    }
+
+    let scan = run_scan(&state).map_err(ApiError::Internal)?;
+    let issue = state.data_quality.read().unwrap().get(id).cloned();
+    Ok(Json(FixResponse { issue, scan }))
+}
+
+#[derive(Deserialize)]
+struct Correction {
+    open: f64,
+    high: f64,
+    low: f64,
+    close: f64,
+    volume: u64,
+}
+
+/// Overwrite the bar on the issue's date with manually entered values.
+async fn correct(
+    State(state): State<AppState>,
+    Path(id): Path<u64>,
+    Json(body): Json<Correction>,
+) -> ApiResult<FixResponse> {
+    let issue = issue(&state, id)?;
+    if issue.kind == IssueKind::StaleSymbol {
+        return Err(ApiError::BadRequest(
+            "stale symbols need a re-import, not a single bar correction".to_string(),
+        ));
+    }
+    let prices = [body.open, body.high, body.low, body.close];
+    if prices.iter().any(|p| !p.is_finite() || *p <= 0.0) {
+        return Err(ApiError::BadRequest(
+            "open, high, low and close must all be positive".to_string(),
+        ));
+    }
+    if body.low > body.open.min(body.close) || body.high < body.open.max(body.close) {
+        return Err(ApiError::BadRequest(
+            "high and low must bracket the open and close".to_string(),
+        ));
+    }
+
+    let bar = Bar {
+        time: NaiveDateTime::from(issue.date),
+        open: body.open,
+        high: body.high,
+        low: body.low,
+        close: body.close,
+        volume: body.volume,
+    };
+    {
+        // Written to the symbol's file under data/ohlcv as well, so the
+        // correction outlives a restart and a later re-import.
+        let mut market = state.market.write().unwrap();
+        market::append_bars(
+            &state.data_dir.join("ohlcv"),
+            &issue.symbol,
+            std::slice::from_ref(&bar),
+        )
+        .map_err(ApiError::Internal)?;
+        market.upsert_daily(&issue.symbol, bar);
+    }
+
+    let scan = run_scan(&state).map_err(ApiError::Internal)?;
+    let issue = state.data_quality.read().unwrap().get(id).cloned();
+    Ok(Json(FixResponse { issue, scan }))
+}
+
+async fn ignore(State(state): State<AppState>, Path(id): Path<u64>) -> ApiResult<Issue> {
+    state
+        .data_quality
+        .write()
+        .unwrap()
+        .set_status(id, IssueStatus::Ignored)
+        .map_err(ApiError::Internal)?
+        .map(Json)
+        .ok_or_else(|| ApiError::NotFound(format!("no data quality issue {}", id)))
+}
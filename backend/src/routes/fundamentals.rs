@@ -0,0 +1,266 @@
+use axum::extract::{Path, Query, State};
+use axum::routing::{get, post};
+use axum::{Json, Router};
+use chrono::NaiveDate;
+use serde::{Deserialize, Serialize};
+
+use crate::models::corporate_action::Adjustment;
+use crate::models::fundamentals::{self, CompanyFundamentals, ImportKind, ImportSummary, Ratios};
+use crate::models::market::{Bar, Timeframe};
+use crate::models::scoring::{self, FactorWeights, StockInputs, StockScore};
+use crate::routes::market::load_series;
+use crate::state::AppState;
+use crate::utils::api::{ApiError, ApiResult};
+
+pub fn router() -> Router<AppState> {
+    Router::new()
+        .route("/", get(list_companies))
+        .route("/import", post(import))
+        .route("/scores", get(list_scores))
+        .route(
+            "/score-weights",
+            get(get_score_weights).put(set_score_weights),
+        )
+        .route(
+            "/:symbol",
+            get(get_company).put(put_company).delete(delete_company),
+        )
+        .route("/:symbol/profile", get(profile))
+        .route("/:symbol/score", get(stock_score))
+}
+
+fn not_found(symbol: &str) -> ApiError {
+    ApiError::NotFound(format!("no fundamentals for '{}'", symbol))
+}
+
+#[derive(Serialize)]
+struct CompanySummary {
+    symbol: String,
+    name: String,
+    sector: String,
+    industry: String,
+    quarters: usize,
+    years: usize,
+    latest_period: Option<NaiveDate>,
+}
+
+async fn list_companies(State(state): State<AppState>) -> ApiResult<Vec<CompanySummary>> {
+    let store = state.fundamentals.read().unwrap();
+    let list = store
+        .companies()
+        .map(|c| CompanySummary {
+            symbol: c.symbol.clone(),
+            name: c.name.clone(),
+            sector: c.sector.clone(),
+            industry: c.industry.clone(),
+            quarters: c.quarterly.len(),
+            years: c.annual.len(),
+            latest_period: c
+                .quarterly
+                .last()
+                .into_iter()
+                .chain(c.annual.last())
+                .map(|p| p.period_end)
+                .max(),
+        })
+        .collect();
+    Ok(Json(list))
+}
+
+#[derive(Deserialize)]
+struct ImportQuery {
+    kind: ImportKind,
+}
+
+/// Merge a CSV of company profiles, quarterly or annual statements, or
+/// shareholding into the store.
+async fn import(
+    State(state): State<AppState>,
+    Query(query): Query<ImportQuery>,
+    body: String,
+) -> ApiResult<ImportSummary> {
+    state
+        .fundamentals
+        .write()
+        .unwrap()
+        .import_csv(&body, query.kind)
+        .map(Json)
+        .map_err(ApiError::BadRequest)
+}
+
+async fn get_company(
+    State(state): State<AppState>,
+    Path(symbol): Path<String>,
+) -> ApiResult<CompanyFundamentals> {
+    state
+        .fundamentals
+        .read()
+        .unwrap()
+        .get(&symbol)
+        .cloned()
+        .map(Json)
+        .ok_or_else(|| not_found(&symbol))
+}
+
+/// Replace a company's fundamentals wholesale, e.g. from a JSON file.
+async fn put_company(
+    State(state): State<AppState>,
+    Path(symbol): Path<String>,
+    Json(mut company): Json<CompanyFundamentals>,
+) -> ApiResult<CompanyFundamentals> {
+    company.symbol = symbol;
+    state
+        .fundamentals
+        .write()
+        .unwrap()
+        .put(company)
+        .map(Json)
+        .map_err(ApiError::Internal)
+}
+
+async fn delete_company(
+    State(state): State<AppState>,
+    Path(symbol): Path<String>,
+) -> ApiResult<String> {
+    let removed = state
+        .fundamentals
+        .write()
+        .unwrap()
+        .remove(&symbol)
+        .map_err(ApiError::Internal)?;
+    if !removed {
+        return Err(not_found(&symbol));
+    }
+    Ok(Json(symbol))
+}
+
+#[derive(Serialize)]
+pub struct CompanyProfile {
+    #[serde(flatten)]
+    pub company: CompanyFundamentals,
+    /// Last daily close the ratios are priced at.
+    pub price: Option<f64>,
+    pub price_date: Option<NaiveDate>,
+    pub ratios: Ratios,
+}
+
+fn last_close(state: &AppState, symbol: &str) -> Option<Bar> {
+    state.market.read().unwrap().last_daily_bar(symbol)
+}
+
+/// A company's statements with its valuation and return ratios.
+pub fn company_profile(state: &AppState, symbol: &str) -> Option<CompanyProfile> {
+    let company = state.fundamentals.read().unwrap().get(symbol).cloned()?;
+    let last = last_close(state, &company.symbol);
+    let price = last.as_ref().map(|bar| bar.close);
+    Some(CompanyProfile {
+        ratios: fundamentals::ratios(&company, price),
+        company,
+        price,
+        price_date: last.map(|bar| bar.date()),
+    })
+}
+
+async fn profile(
+    State(state): State<AppState>,
+    Path(symbol): Path<String>,
+) -> ApiResult<CompanyProfile> {
+    company_profile(&state, &symbol)
+        .map(Json)
+        .ok_or_else(|| not_found(&symbol))
+}
+
+/// Daily bars the technical factors look at: a year plus a margin for the
+/// 12 month return.
+const SCORE_LOOKBACK: usize = 260;
+
+/// Score inputs for every company with fundamentals. Valuation uses the
+/// unadjusted last close, as EPS and book value are per current share;
+/// returns and averages use split-adjusted history.
+fn score_universe(state: &AppState) -> Vec<StockInputs> {
+    let companies: Vec<CompanyFundamentals> = state
+        .fundamentals
+        .read()
+        .unwrap()
+        .companies()
+        .cloned()
+        .collect();
+    companies
+        .into_iter()
+        .map(|company| {
+            let price = last_close(state, &company.symbol).map(|bar| bar.close);
+            let series = load_series(
+                state,
+                &company.symbol,
+                Timeframe::D1,
+                None,
+                None,
+                Some(SCORE_LOOKBACK),
+                Adjustment::Capital,
+            );
+            let closes: Vec<f64> = series.bars.iter().map(|b| b.close).collect();
+            StockInputs {
+                ratios: fundamentals::ratios(&company, price),
+                technicals: scoring::technicals(&closes),
+                price,
+                symbol: company.symbol,
+                name: company.name,
+                sector: company.sector,
+            }
+        })
+        .collect()
+}
+
+pub fn scores(state: &AppState) -> Vec<StockScore> {
+    let weights = state.fundamentals.read().unwrap().score_weights().clone();
+    scoring::score(&score_universe(state), &weights)
+}
+
+#[derive(Deserialize)]
+struct ScoresQuery {
+    sector: Option<String>,
+}
+
+/// Every scored company, best composite first.
+async fn list_scores(
+    State(state): State<AppState>,
+    Query(query): Query<ScoresQuery>,
+) -> ApiResult<Vec<StockScore>> {
+    let mut list = scores(&state);
+    if let Some(sector) = query.sector.filter(|s| !s.is_empty()) {
+        list.retain(|s| s.sector.eq_ignore_ascii_case(&sector));
+    }
+    Ok(Json(list))
+}
+
+async fn stock_score(
+    State(state): State<AppState>,
+    Path(symbol): Path<String>,
+) -> ApiResult<StockScore> {
+    let symbol = fundamentals::normalize(&symbol);
+    scores(&state)
+        .into_iter()
+        .find(|s| s.symbol == symbol)
+        .map(Json)
+        .ok_or_else(|| not_found(&symbol))
+}
+
+async fn get_score_weights(State(state): State<AppState>) -> ApiResult<FactorWeights> {
+    Ok(Json(
+        state.fundamentals.read().unwrap().score_weights().clone(),
+    ))
+}
+
+async fn set_score_weights(
+    State(state): State<AppState>,
+    Json(weights): Json<FactorWeights>,
+) -> ApiResult<FactorWeights> {
+    weights.validate().map_err(ApiError::BadRequest)?;
+    state
+        .fundamentals
+        .write()
+        .unwrap()
+        .set_score_weights(weights)
+        .map(Json)
+        .map_err(ApiError::Internal)
+}
@@ -0,0 +1,78 @@
+use axum::extract::{Path, Query, State};
+use axum::routing::{get, post};
+use axum::{Json, Router};
+use serde::{Deserialize, Serialize};
+
+use crate::models::instrument::{
+    ExchangeSegment, Instrument, InstrumentKind, InstrumentRegistry, SearchHit,
+};
+use crate::state::AppState;
+use crate::utils::api::{ApiError, ApiResult};
+
+pub fn router() -> Router<AppState> {
+    Router::new()
+        .route("/search", get(search))
+        .route("/reload", post(reload))
+        .route("/:symbol", get(get_instrument))
+}
+
+#[derive(Deserialize)]
+struct SearchQuery {
+    #[serde(default)]
+    q: String,
+    kind: Option<InstrumentKind>,
+    segment: Option<String>,
+    limit: Option<usize>,
+}
+
+fn parse_segment(raw: &str) -> Result<ExchangeSegment, ApiError> {
+    ExchangeSegment::parse(raw)
+        .ok_or_else(|| ApiError::BadRequest(format!("unknown segment '{}'", raw)))
+}
+
+async fn search(
+    State(state): State<AppState>,
+    Query(query): Query<SearchQuery>,
+) -> ApiResult<Vec<SearchHit>> {
+    let segment = query.segment.as_deref().map(parse_segment).transpose()?;
+    let limit = query.limit.unwrap_or(20).min(100);
+    let registry = state.instruments.read().unwrap();
+
+    Ok(Json(registry.search(&query.q, query.kind, segment, limit)))
+}
+
+#[derive(Deserialize)]
+struct InstrumentQuery {
+    /// Segment to look in; NSE first, then the others, when left out.
+    segment: Option<String>,
+}
+
+async fn get_instrument(
+    State(state): State<AppState>,
+    Path(symbol): Path<String>,
+    Query(query): Query<InstrumentQuery>,
+) -> ApiResult<Instrument> {
+    let segment = query.segment.as_deref().map(parse_segment).transpose()?;
+    let registry = state.instruments.read().unwrap();
+    match segment {
+        Some(segment) => registry.get_in(segment, &symbol),
+        None => registry.get(&symbol),
+    }
+    .cloned()
+    .map(Json)
+    .ok_or_else(|| ApiError::NotFound(format!("unknown symbol '{}'", symbol)))
+}
+
+#[derive(Serialize)]
+struct ReloadResponse {
+    instruments: usize,
+}
+
+async fn reload(State(state): State<AppState>) -> ApiResult<ReloadResponse> {
+    let registry = InstrumentRegistry::load_dump(&state.data_dir.join("instruments.csv"))
+        .map_err(ApiError::BadRequest)?;
+    let count = registry.len();
+    *state.instruments.write().unwrap() = registry;
+
+    Ok(Json(ReloadResponse { instruments: count }))
+}
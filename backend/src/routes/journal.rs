@@ -1 +1,542 @@
- 
+use axum::extract::{DefaultBodyLimit, Path, Query, State};
+use axum::http::header;
+use axum::response::{IntoResponse, Response};
+use axum::routing::{get, post, put};
+use axum::{Json, Router};
+use chrono::Datelike;
+use serde::{Deserialize, Serialize};
+
+use crate::models::analytics::{self, JournalAnalytics, PnlCalendar};
+use crate::models::backup::{self, BackupFormat, RestorePlan, MAX_BACKUP_BYTES};
+use crate::models::calendar::now_ist;
+use crate::models::charges::Order;
+use crate::models::journal::{Execution, JournalStore, Tag, Trade, TradeSummary};
+use crate::models::market::Timeframe;
+use crate::models::rules::Violation;
+use crate::models::tradebook::{self, ImportPlan};
+use crate::routes::calendar::parse_date;
+use crate::routes::charges::trade_charges;
+use crate::routes::rules;
+use crate::state::AppState;
+use crate::utils::api::{ApiError, ApiResult};
+
+pub fn router() -> Router<AppState> {
+    Router::new()
+        .route("/trades", get(list_trades).post(create_trade))
+        .route(
+            "/trades/:id",
+            get(get_trade).put(update_trade).delete(delete_trade),
+        )
+        .route("/trades/:id/executions", post(add_execution))
+        .route("/preview", post(preview))
+        .route("/analytics", get(analytics))
+        .route("/calendar", get(pnl_calendar))
+        .route("/tags", get(list_tags).post(create_tag))
+        .route("/tags/:name", put(update_tag).delete(delete_tag))
+        .route("/import/preview", post(import_preview))
+        .route("/import", post(import))
+        .route("/export", get(export))
+        .route(
+            "/restore/preview",
+            post(restore_preview).layer(DefaultBodyLimit::max(MAX_BACKUP_BYTES)),
+        )
+        .route(
+            "/restore",
+            post(restore).layer(DefaultBodyLimit::max(MAX_BACKUP_BYTES)),
+        )
+}
+
+#[derive(Serialize)]
+pub struct TradeView {
+    #[serde(flatten)]
+    pub trade: Trade,
+    pub summary: TradeSummary,
+    /// Trading rules the trade broke. Only filled in for trade listings.
+    pub violations: Vec<Violation>,
+}
+
+/// Last daily close, used to mark open positions.
+fn last_close(state: &AppState, symbol: &str) -> Option<f64> {
+    let market = state.market.read().unwrap();
+    let calendar = state.calendar.read().unwrap();
+    market
+        .bars(symbol, Timeframe::D1, None, None, &calendar)
+        .last()
+        .map(|bar| bar.close)
+}
+
+pub fn view(state: &AppState, trade: Trade) -> TradeView {
+    let summary = trade.summarize(last_close(state, &trade.symbol));
+    TradeView {
+        trade,
+        summary,
+        violations: Vec::new(),
+    }
+}
+
+/// Attach each trade's rule violations.
+fn flag_violations(state: &AppState, views: &mut [TradeView]) {
+    let violations = rules::violations(state);
+    for view in views {
+        view.violations = violations
+            .iter()
+            .filter(|v| v.trade_id == view.trade.id)
+            .cloned()
+            .collect();
+    }
+}
+
+fn not_found(id: &str) -> ApiError {
+    ApiError::NotFound(format!("no journal trade '{}'", id))
+}
+
+#[derive(Deserialize)]
+struct TradesQuery {
+    symbol: Option<String>,
+    tag: Option<String>,
+    /// Trades with a fill on this day.
+    date: Option<String>,
+    from: Option<String>,
+    to: Option<String>,
+}
+
+async fn list_trades(
+    State(state): State<AppState>,
+    Query(query): Query<TradesQuery>,
+) -> ApiResult<Vec<TradeView>> {
+    let from = query.from.as_deref().map(parse_date).transpose()?;
+    let to = query.to.as_deref().map(parse_date).transpose()?;
+    let date = query.date.as_deref().map(parse_date).transpose()?;
+    let trades: Vec<Trade> = state.journal.read().unwrap().trades().to_vec();
+
+    let mut views: Vec<TradeView> = trades
+        .into_iter()
+        .filter(|t| query.symbol.as_ref().is_none_or(|s| &t.symbol == s))
+        .filter(|t| query.tag.as_ref().is_none_or(|tag| t.tags.contains(tag)))
+        .filter(|t| date.is_none_or(|d| t.executions.iter().any(|e| e.time.date() == d)))
+        .map(|t| view(&state, t))
+        .filter(|v| {
+            let date = v.summary.entry_time.map(|t| t.date());
+            from.is_none_or(|f| date.is_some_and(|d| d >= f))
+                && to.is_none_or(|t| date.is_some_and(|d| d <= t))
+        })
+        .collect();
+    views.sort_by_key(|v| std::cmp::Reverse(v.summary.entry_time));
+    flag_violations(&state, &mut views);
+
+    Ok(Json(views))
+}
+
+async fn get_trade(State(state): State<AppState>, Path(id): Path<String>) -> ApiResult<TradeView> {
+    let trade = state
+        .journal
+        .read()
+        .unwrap()
+        .get(&id)
+        .cloned()
+        .ok_or_else(|| not_found(&id))?;
+    let mut view = view(&state, trade);
+    flag_violations(&state, std::slice::from_mut(&mut view));
+    Ok(Json(view))
+}
+
+async fn create_trade(
+    State(state): State<AppState>,
+    Json(trade): Json<Trade>,
+) -> ApiResult<TradeView> {
+    trade.validate().map_err(ApiError::BadRequest)?;
+    let trade = state
+        .journal
+        .write()
+        .unwrap()
+        .insert(trade)
+        .map_err(ApiError::Internal)?;
+    Ok(Json(view(&state, trade)))
+}
+
+async fn update_trade(
+    State(state): State<AppState>,
+    Path(id): Path<String>,
+    Json(trade): Json<Trade>,
+) -> ApiResult<TradeView> {
+    trade.validate().map_err(ApiError::BadRequest)?;
+    let trade = state
+        .journal
+        .write()
+        .unwrap()
+        .update(&id, trade)
+        .map_err(ApiError::Internal)?
+        .ok_or_else(|| not_found(&id))?;
+    Ok(Json(view(&state, trade)))
+}
+
+/// Append a fill to an existing trade, e.g. a partial exit.
+async fn add_execution(
+    State(state): State<AppState>,
+    Path(id): Path<String>,
+    Json(execution): Json<Execution>,
+) -> ApiResult<TradeView> {
+    let mut journal = state.journal.write().unwrap();
+    let mut trade = journal.get(&id).cloned().ok_or_else(|| not_found(&id))?;
+    trade.executions.push(execution);
+    trade.validate().map_err(ApiError::BadRequest)?;
+    let trade = journal
+        .update(&id, trade)
+        .map_err(ApiError::Internal)?
+        .ok_or_else(|| not_found(&id))?;
+    drop(journal);
+    Ok(Json(view(&state, trade)))
+}
+
+async fn delete_trade(State(state): State<AppState>, Path(id): Path<String>) -> ApiResult<String> {
+    let removed = state
+        .journal
+        .write()
+        .unwrap()
+        .remove(&id)
+        .map_err(ApiError::Internal)?;
+    if !removed {
+        return Err(not_found(&id));
+    }
+    state
+        .attachments
+        .write()
+        .unwrap()
+        .remove_trade(&id)
+        .map_err(ApiError::Internal)?;
+    Ok(Json(id))
+}
+
+#[derive(Deserialize)]
+struct AnalyticsQuery {
+    symbol: Option<String>,
+    tag: Option<String>,
+    from: Option<String>,
+    to: Option<String>,
+}
+
+/// Performance statistics over closed trades, filtered by exit date.
+async fn analytics(
+    State(state): State<AppState>,
+    Query(query): Query<AnalyticsQuery>,
+) -> ApiResult<JournalAnalytics> {
+    let from = query.from.as_deref().map(parse_date).transpose()?;
+    let to = query.to.as_deref().map(parse_date).transpose()?;
+    let journal = state.journal.read().unwrap();
+
+    let trades: Vec<_> = journal
+        .trades()
+        .iter()
+        .filter(|t| query.symbol.as_ref().is_none_or(|s| &t.symbol == s))
+        .filter(|t| query.tag.as_ref().is_none_or(|tag| t.tags.contains(tag)))
+        .map(|t| (t.clone(), t.summarize(None)))
+        .filter(|(_, s)| {
+            let date = s.exit_time.map(|t| t.date());
+            from.is_none_or(|f| date.is_none_or(|d| d >= f))
+                && to.is_none_or(|t| date.is_none_or(|d| d <= t))
+        })
+        .collect();
+
+    Ok(Json(analytics::analyze(&trades, journal.tags())))
+}
+
+async fn list_tags(State(state): State<AppState>) -> ApiResult<Vec<Tag>> {
+    Ok(Json(state.journal.read().unwrap().tags().to_vec()))
+}
+
+fn check_tag(tag: &mut Tag) -> Result<(), ApiError> {
+    tag.name = tag.name.trim().to_string();
+    if tag.name.is_empty() {
+        return Err(ApiError::BadRequest("tag needs a name".to_string()));
+    }
+    Ok(())
+}
+
+async fn create_tag(State(state): State<AppState>, Json(mut tag): Json<Tag>) -> ApiResult<Tag> {
+    check_tag(&mut tag)?;
+    let mut journal = state.journal.write().unwrap();
+    if journal.tag(&tag.name).is_some() {
+        return Err(ApiError::BadRequest(format!(
+            "tag '{}' already exists",
+            tag.name
+        )));
+    }
+    journal
+        .save_tag(None, tag)
+        .map(Json)
+        .map_err(ApiError::Internal)
+}
+
+/// Change a tag's kind or description, or rename it across all trades.
+async fn update_tag(
+    State(state): State<AppState>,
+    Path(name): Path<String>,
+    Json(mut tag): Json<Tag>,
+) -> ApiResult<Tag> {
+    check_tag(&mut tag)?;
+    let mut journal = state.journal.write().unwrap();
+    if journal.tag(&name).is_none() {
+        return Err(ApiError::NotFound(format!("no tag '{}'", name)));
+    }
+    if tag.name != name && journal.tag(&tag.name).is_some() {
+        return Err(ApiError::BadRequest(format!(
+            "tag '{}' already exists",
+            tag.name
+        )));
+    }
+    journal
+        .save_tag(Some(&name), tag)
+        .map(Json)
+        .map_err(ApiError::Internal)
+}
+
+async fn delete_tag(State(state): State<AppState>, Path(name): Path<String>) -> ApiResult<String> {
+    let removed = state
+        .journal
+        .write()
+        .unwrap()
+        .remove_tag(&name)
+        .map_err(ApiError::Internal)?;
+    if !removed {
+        return Err(ApiError::NotFound(format!("no tag '{}'", name)));
+    }
+    Ok(Json(name))
+}
+
+#[derive(Deserialize)]
+struct CalendarQuery {
+    /// `YYYY-MM`, defaults to the current month.
+    month: Option<String>,
+}
+
+async fn pnl_calendar(
+    State(state): State<AppState>,
+    Query(query): Query<CalendarQuery>,
+) -> ApiResult<PnlCalendar> {
+    let month = match query.month.as_deref() {
+        Some(value) => parse_date(&format!("{}-01", value))?,
+        None => now_ist().date().with_day(1).unwrap(),
+    };
+    let journal = state.journal.read().unwrap();
+    let calendar = state.calendar.read().unwrap();
+
+    Ok(Json(analytics::pnl_calendar(
+        journal.trades(),
+        month,
+        &calendar,
+    )))
+}
+
+/// Summarize a trade without saving it, for the entry form.
+async fn preview(
+    State(state): State<AppState>,
+    Json(trade): Json<Trade>,
+) -> ApiResult<TradeSummary> {
+    Ok(Json(view(&state, trade).summary))
+}
+
+/// Parse a tradebook and plan the import. Fills from the file get their
+/// charges estimated; fills already in the journal keep their fees.
+fn plan_import(
+    state: &AppState,
+    journal: &JournalStore,
+    text: &str,
+) -> Result<(ImportPlan, Vec<String>), ApiError> {
+    let (fills, errors) = tradebook::parse(text).map_err(ApiError::BadRequest)?;
+    let mut plan = tradebook::plan(journal, fills);
+
+    let trades = plan
+        .created
+        .iter_mut()
+        .chain(plan.updated.iter_mut().map(|(_, after)| after));
+    for trade in trades {
+        let estimate = trade_charges(state, trade);
+        for (execution, charge) in trade.executions.iter_mut().zip(&estimate.orders) {
+            if execution.id.is_empty() {
+                execution.fees = charge.total;
+            }
+        }
+    }
+    // A split fill was one order: charge it whole, at the closing trade's
+    // rates, and share that between its two parts by quantity
+    for split in plan.splits.clone() {
+        let closing = plan.trade_mut(split.closing);
+        let segment = trade_charges(state, closing).segment;
+        let Some(part) = closing.executions.last().cloned() else {
+            continue;
+        };
+        let rest = plan.created[split.opening].executions[0].quantity;
+        let order = Order {
+            side: part.side,
+            quantity: part.quantity + rest,
+            price: part.price,
+        };
+        let total = state.charges.read().unwrap().order(segment, &order).total;
+        let share = total * f64::from(part.quantity) / f64::from(order.quantity);
+        if let Some(part) = plan.trade_mut(split.closing).executions.last_mut() {
+            part.fees = share;
+        }
+        plan.created[split.opening].executions[0].fees = total - share;
+    }
+
+    Ok((plan, errors))
+}
+
+#[derive(Serialize)]
+struct TradeDiff {
+    before: TradeView,
+    after: TradeView,
+}
+
+#[derive(Serialize)]
+struct ImportPreview {
+    created: Vec<TradeView>,
+    updated: Vec<TradeDiff>,
+    duplicates: usize,
+    errors: Vec<String>,
+}
+
+/// Show what importing a tradebook would change without saving anything.
+async fn import_preview(State(state): State<AppState>, body: String) -> ApiResult<ImportPreview> {
+    let (plan, errors) = plan_import(&state, &state.journal.read().unwrap(), &body)?;
+
+    Ok(Json(ImportPreview {
+        created: plan.created.into_iter().map(|t| view(&state, t)).collect(),
+        updated: plan
+            .updated
+            .into_iter()
+            .map(|(before, after)| TradeDiff {
+                before: view(&state, before),
+                after: view(&state, after),
+            })
+            .collect(),
+        duplicates: plan.duplicates,
+        errors,
+    }))
+}
+
+#[derive(Serialize)]
+struct ImportResult {
+    created: usize,
+    updated: usize,
+    duplicates: usize,
+    errors: Vec<String>,
+}
+
+/// Import a tradebook. The plan is rebuilt from the file rather than taken
+/// from the preview, and built and stored under one write lock, so fills
+/// added in between or by a concurrent import are still deduplicated.
+async fn import(State(state): State<AppState>, body: String) -> ApiResult<ImportResult> {
+    let mut journal = state.journal.write().unwrap();
+    let (plan, errors) = plan_import(&state, &journal, &body)?;
+    let result = ImportResult {
+        created: plan.created.len(),
+        updated: plan.updated.len(),
+        duplicates: plan.duplicates,
+        errors,
+    };
+
+    let updated = plan.updated.into_iter().map(|(_, after)| after).collect();
+    journal
+        .import(plan.created, updated)
+        .map_err(ApiError::Internal)?;
+
+    Ok(Json(result))
+}
+
+#[derive(Deserialize)]
+struct BackupQuery {
+    format: Option<BackupFormat>,
+}
+
+/// Download every trade and tag as JSON, CSV or an Excel XML spreadsheet.
+async fn export(
+    State(state): State<AppState>,
+    Query(query): Query<BackupQuery>,
+) -> Result<Response, ApiError> {
+    let format = query.format.unwrap_or(BackupFormat::Json);
+    let now = now_ist();
+    let backup = backup::backup(&state.journal.read().unwrap(), now);
+    let body = backup::export(&backup, format).map_err(ApiError::Internal)?;
+
+    Ok((
+        [
+            (header::CONTENT_TYPE, format.content_type().to_string()),
+            (
+                header::CONTENT_DISPOSITION,
+                format!(
+                    "attachment; filename=\"journal-{}.{}\"",
+                    now.format("%Y%m%d-%H%M"),
+                    format.extension()
+                ),
+            ),
+        ],
+        body,
+    )
+        .into_response())
+}
+
+fn plan_restore(
+    journal: &JournalStore,
+    query: &BackupQuery,
+    text: &str,
+) -> Result<RestorePlan, ApiError> {
+    let format = query
+        .format
+        .ok_or_else(|| ApiError::BadRequest("format is required".to_string()))?;
+    backup::plan(journal, text, format).map_err(ApiError::BadRequest)
+}
+
+#[derive(Serialize)]
+struct RestorePreview {
+    trades: Vec<TradeView>,
+    tags: Vec<Tag>,
+    duplicates: usize,
+    errors: Vec<String>,
+}
+
+/// Validate a backup and show what restoring it would add.
+async fn restore_preview(
+    State(state): State<AppState>,
+    Query(query): Query<BackupQuery>,
+    body: String,
+) -> ApiResult<RestorePreview> {
+    let plan = plan_restore(&state.journal.read().unwrap(), &query, &body)?;
+    Ok(Json(RestorePreview {
+        trades: plan.trades.into_iter().map(|t| view(&state, t)).collect(),
+        tags: plan.tags,
+        duplicates: plan.duplicates,
+        errors: plan.errors,
+    }))
+}
+
+#[derive(Serialize)]
+struct RestoreResult {
+    created: usize,
+    tags: usize,
+    duplicates: usize,
+    errors: Vec<String>,
+}
+
+/// Add the valid, new trades and tags from a backup. The plan is built
+/// and applied under one write lock, so a concurrent restore can't add
+/// the same trades twice, and everything is saved at once or not at all.
+async fn restore(
+    State(state): State<AppState>,
+    Query(query): Query<BackupQuery>,
+    body: String,
+) -> ApiResult<RestoreResult> {
+    let mut journal = state.journal.write().unwrap();
+    let plan = plan_restore(&journal, &query, &body)?;
+    let result = RestoreResult {
+        created: plan.trades.len(),
+        tags: plan.tags.len(),
+        duplicates: plan.duplicates,
+        errors: plan.errors,
+    };
+    journal
+        .insert_all(plan.tags, plan.trades)
+        .map_err(ApiError::Internal)?;
+
+    Ok(Json(result))
+}
@@ -0,0 +1,205 @@
+use std::collections::BTreeMap;
+
+use axum::extract::{Path, Query, State};
+use axum::routing::{get, post};
+use axum::{Json, Router};
+use chrono::{NaiveDate, NaiveDateTime};
+use serde::{Deserialize, Serialize};
+
+use crate::models::corporate_action::{
+    self, ActionKind, AdjustedSeries, Adjustment, CorporateAction,
+};
+use crate::models::market::{self, Bar, MarketStore, Tick, Timeframe};
+use crate::routes::calendar::parse_date;
+use crate::state::AppState;
+use crate::utils::api::{ApiError, ApiResult};
+
+pub fn router() -> Router<AppState> {
+    Router::new()
+        .route("/import", post(import))
+        .route("/ticks", post(ingest_ticks))
+        .route("/:symbol/bars", get(bars))
+        .route(
+            "/:symbol/corporate-actions",
+            get(list_actions).post(add_action),
+        )
+}
+
+#[derive(Deserialize)]
+struct ImportQuery {
+    symbol: Option<String>,
+    #[serde(default)]
+    timeframe: Timeframe,
+}
+
+#[derive(Serialize)]
+struct ImportResponse {
+    bars: usize,
+}
+
+async fn import(
+    State(state): State<AppState>,
+    Query(query): Query<ImportQuery>,
+    body: String,
+) -> ApiResult<ImportResponse> {
+    // Parse before taking the lock so readers aren't held up by a large file.
+    let mut parsed = MarketStore::default();
+    let bars = parsed
+        .import_csv(&body, query.symbol.as_deref(), query.timeframe)
+        .map_err(ApiError::BadRequest)?;
+    state
+        .market
+        .write()
+        .unwrap()
+        .absorb(parsed, &state.data_dir.join("ohlcv"))
+        .map_err(ApiError::Internal)?;
+
+    Ok(Json(ImportResponse { bars }))
+}
+
+#[derive(Serialize)]
+struct TicksResponse {
+    accepted: usize,
+    rejected: usize,
+}
+
+async fn ingest_ticks(
+    State(state): State<AppState>,
+    Json(ticks): Json<Vec<Tick>>,
+) -> ApiResult<TicksResponse> {
+    let calendar = state.calendar.read().unwrap();
+    let mut market = state.market.write().unwrap();
+    let mut touched: BTreeMap<&str, BTreeMap<NaiveDateTime, Bar>> = BTreeMap::new();
+    let mut accepted = 0;
+    for tick in &ticks {
+        if let Some(bar) = market.ingest_tick(tick, &calendar) {
+            touched
+                .entry(&tick.symbol)
+                .or_default()
+                .insert(bar.time, bar);
+            accepted += 1;
+        }
+    }
+    // The minute bars as they stand after the batch, appended so later
+    // rows replace the partial ones written by earlier batches.
+    let dir = state.data_dir.join("ohlcv").join("1m");
+    for (symbol, bars) in touched {
+        let bars: Vec<Bar> = bars.into_values().collect();
+        market::append_bars(&dir, symbol, &bars).map_err(ApiError::Internal)?;
+    }
+
+    Ok(Json(TicksResponse {
+        accepted,
+        rejected: ticks.len() - accepted,
+    }))
+}
+
+#[derive(Deserialize)]
+struct BarsQuery {
+    from: Option<String>,
+    to: Option<String>,
+    /// Return only the last `limit` bars of the range.
+    limit: Option<usize>,
+    #[serde(default)]
+    timeframe: Timeframe,
+    #[serde(default)]
+    adjustment: Adjustment,
+}
+
+async fn bars(
+    State(state): State<AppState>,
+    Path(symbol): Path<String>,
+    Query(query): Query<BarsQuery>,
+) -> ApiResult<AdjustedSeries> {
+    let from = query.from.as_deref().map(parse_date).transpose()?;
+    let to = query.to.as_deref().map(parse_date).transpose()?;
+    let series = load_series(
+        &state,
+        &symbol,
+        query.timeframe,
+        from,
+        to,
+        query.limit,
+        query.adjustment,
+    );
+
+    Ok(Json(series))
+}
+
+/// Resampled, optionally adjusted bars for `symbol`, shared by the bars
+/// endpoint and the analysis routes.
+pub fn load_series(
+    state: &AppState,
+    symbol: &str,
+    timeframe: Timeframe,
+    from: Option<NaiveDate>,
+    to: Option<NaiveDate>,
+    limit: Option<usize>,
+    adjustment: Adjustment,
+) -> AdjustedSeries {
+    // Adjust the full daily history so factors that need the close before
+    // an ex-date still see it when the requested range starts later.
+    // Intraday history is too large for that and is read for the range
+    // only, or without a range for the days holding the last `limit` bars:
+    // a bar spans at most its width in minutes.
+    let history = {
+        let calendar = state.calendar.read().unwrap();
+        let market = state.market.read().unwrap();
+        let history_from = match (timeframe.minutes(), from, limit) {
+            (Some(_), Some(from), _) => Some(from),
+            (Some(width), None, Some(limit)) => {
+                market.minute_start(symbol, limit.saturating_mul(width as usize))
+            }
+            _ => None,
+        };
+        market.bars(symbol, timeframe, history_from, to, &calendar)
+    };
+    let actions = state.corporate_actions.read().unwrap();
+    let mut series = corporate_action::adjust(&history, actions.for_symbol(symbol), adjustment);
+
+    series
+        .bars
+        .retain(|bar| from.is_none_or(|from| bar.date() >= from));
+    if let Some(limit) = limit {
+        let skip = series.bars.len().saturating_sub(limit);
+        series.bars.drain(..skip);
+    }
+
+    series
+}
+
+async fn list_actions(
+    State(state): State<AppState>,
+    Path(symbol): Path<String>,
+) -> ApiResult<Vec<CorporateAction>> {
+    let actions = state.corporate_actions.read().unwrap();
+    Ok(Json(actions.for_symbol(&symbol).to_vec()))
+}
+
+#[derive(Deserialize)]
+struct NewAction {
+    ex_date: String,
+    #[serde(flatten)]
+    kind: ActionKind,
+}
+
+async fn add_action(
+    State(state): State<AppState>,
+    Path(symbol): Path<String>,
+    Json(body): Json<NewAction>,
+) -> ApiResult<CorporateAction> {
+    body.kind.validate().map_err(ApiError::BadRequest)?;
+    let action = CorporateAction {
+        symbol,
+        ex_date: parse_date(&body.ex_date)?,
+        kind: body.kind,
+    };
+    state
+        .corporate_actions
+        .write()
+        .unwrap()
+        .record(action.clone())
+        .map_err(ApiError::Internal)?;
+
+    Ok(Json(action))
+}
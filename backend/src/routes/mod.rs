@@ -0,0 +1,42 @@
+use axum::Router;
+
+use crate::state::AppState;
+
+pub mod analyze;
+pub mod attachments;
+pub mod auth;
+pub mod calendar;
+pub mod charges;
+pub mod data_quality;
+pub mod fundamentals;
+pub mod instruments;
+pub mod journal;
+pub mod market;
+pub mod options;
+pub mod portfolio;
+pub mod risk_profile;
+pub mod rules;
+pub mod screener;
+pub mod tax;
+pub mod valuation;
+
+pub fn router(state: AppState) -> Router {
+    Router::new()
+        .nest("/api/analyze", analyze::router())
+        .nest("/api/attachments", attachments::router())
+        .nest("/api/calendar", calendar::router())
+        .nest("/api/charges", charges::router())
+        .nest("/api/data-quality", data_quality::router())
+        .nest("/api/fundamentals", fundamentals::router())
+        .nest("/api/instruments", instruments::router())
+        .nest("/api/journal", journal::router())
+        .nest("/api/market", market::router())
+        .nest("/api/options", options::router())
+        .nest("/api/portfolio", portfolio::router())
+        .nest("/api/risk-profile", risk_profile::router())
+        .nest("/api/rules", rules::router())
+        .nest("/api/screener", screener::router())
+        .nest("/api/tax", tax::router())
+        .nest("/api/valuation", valuation::router())
+        .with_state(state)
+}
@@ -0,0 +1,316 @@
+use std::collections::BTreeMap;
+
+use axum::extract::{Query, State};
+use axum::routing::{get, post};
+use axum::{Json, Router};
+use chrono::NaiveDate;
+use serde::{Deserialize, Serialize};
+
+use crate::models::calendar::now_ist;
+use crate::models::instrument::InstrumentKind;
+use crate::models::market::Timeframe;
+use crate::models::option_chain::{
+    self, ChainView, ContractInfo, ImportDefaults, ImportSummary, OptionType, UnderlyingSummary,
+};
+use crate::models::option_pricing::{self, Contract, Greeks, Model};
+use crate::state::AppState;
+use crate::utils::api::{ApiError, ApiResult};
+
+pub fn router() -> Router<AppState> {
+    Router::new()
+        .route("/underlyings", get(list_underlyings))
+        .route("/chain", get(get_chain))
+        .route("/import", post(import_chain))
+        .route("/price", get(get_price))
+}
+
+/// Risk-free rate, in percent, when a request doesn't give one: about
+/// the 91-day treasury bill yield.
+const RISK_FREE_RATE: f64 = 6.5;
+
+/// Trading days of ATM IV that IV rank and percentile look back over.
+const IV_LOOKBACK: usize = 252;
+
+fn check_rate(rate: Option<f64>) -> Result<f64, ApiError> {
+    let rate = rate.unwrap_or(RISK_FREE_RATE);
+    if !(-5.0..=50.0).contains(&rate) {
+        return Err(ApiError::BadRequest(
+            "rate is a yearly percentage between -5 and 50".to_string(),
+        ));
+    }
+    Ok(rate / 100.0)
+}
+
+/// Index symbols for underlyings whose trading symbol differs from the
+/// index's name in the market data.
+const INDEX_ALIASES: [(&str, &str); 1] = [("NIFTY", "Nifty 50")];
+
+/// Daily closes of the underlying up to `to`, from its history.
+fn underlying_closes(
+    state: &AppState,
+    underlying: &str,
+    to: NaiveDate,
+) -> BTreeMap<NaiveDate, f64> {
+    let symbol = INDEX_ALIASES
+        .iter()
+        .find(|(alias, _)| alias.eq_ignore_ascii_case(underlying))
+        .map_or_else(
+            || {
+                state
+                    .instruments
+                    .read()
+                    .unwrap()
+                    .get(underlying)
+                    .map_or_else(|| underlying.to_string(), |i| i.symbol.clone())
+            },
+            |(_, symbol)| symbol.to_string(),
+        );
+    let market = state.market.read().unwrap();
+    let calendar = state.calendar.read().unwrap();
+    market
+        .bars(&symbol, Timeframe::D1, None, Some(to), &calendar)
+        .into_iter()
+        .map(|bar| (bar.date(), bar.close))
+        .collect()
+}
+
+async fn list_underlyings(State(state): State<AppState>) -> ApiResult<Vec<UnderlyingSummary>> {
+    Ok(Json(state.option_chains.read().unwrap().underlyings()))
+}
+
+#[derive(Deserialize)]
+struct ChainQuery {
+    underlying: String,
+    /// Nearest expiry still open on `date` when left out.
+    expiry: Option<NaiveDate>,
+    /// Latest snapshot when left out.
+    date: Option<NaiveDate>,
+    /// Risk-free rate in percent for the Greeks.
+    rate: Option<f64>,
+}
+
+/// The chain of one expiry with its Greeks, PCR, max pain, build-up and
+/// OI levels, and where its ATM IV ranks in the underlying's history.
+async fn get_chain(
+    State(state): State<AppState>,
+    Query(query): Query<ChainQuery>,
+) -> ApiResult<ChainView> {
+    let rate = check_rate(query.rate)?;
+    let (snapshot, history) = {
+        let store = state.option_chains.read().unwrap();
+        let expiry = match query.expiry {
+            Some(expiry) => expiry,
+            None => {
+                let latest = store
+                    .history(&query.underlying)
+                    .filter(|s| query.date.is_none_or(|d| s.date <= d))
+                    .map(|s| s.date)
+                    .max()
+                    .ok_or_else(|| {
+                        ApiError::NotFound(format!("no option chain for '{}'", query.underlying))
+                    })?;
+                store
+                    .history(&query.underlying)
+                    .filter(|s| s.expiry >= latest)
+                    .map(|s| s.expiry)
+                    .min()
+                    .ok_or_else(|| {
+                        ApiError::NotFound(format!(
+                            "no open expiry for '{}' on {}",
+                            query.underlying, latest
+                        ))
+                    })?
+            }
+        };
+        let snapshot = store
+            .snapshot(&query.underlying, expiry, query.date)
+            .cloned()
+            .ok_or_else(|| {
+                ApiError::NotFound(format!("no {} chain for '{}'", expiry, query.underlying))
+            })?;
+        let history: Vec<_> = store
+            .history(&query.underlying)
+            .filter(|s| s.date <= snapshot.date)
+            .cloned()
+            .collect();
+        (snapshot, history)
+    };
+    // Snapshots without a spot are priced at the underlying's close on or
+    // before their day, loaded once for the whole history
+    let closes = if history.iter().any(|s| s.spot.is_none()) || snapshot.spot.is_none() {
+        underlying_closes(&state, &snapshot.underlying, snapshot.date)
+    } else {
+        BTreeMap::new()
+    };
+    let spot_on = |s: &option_chain::ChainSnapshot| {
+        s.spot
+            .or_else(|| closes.range(..=s.date).next_back().map(|(_, close)| *close))
+    };
+    let mut iv_history = option_chain::iv_history(history.iter(), &spot_on, rate);
+    iv_history.drain(..iv_history.len().saturating_sub(IV_LOOKBACK + 1));
+    let spot = spot_on(&snapshot);
+    Ok(Json(option_chain::analyze(
+        &snapshot, spot, rate, iv_history,
+    )))
+}
+
+#[derive(Deserialize)]
+struct ImportQuery {
+    underlying: Option<String>,
+    expiry: Option<NaiveDate>,
+    /// Day the chain was taken; today when left out.
+    date: Option<NaiveDate>,
+    spot: Option<f64>,
+}
+
+/// Import a chain CSV, either the exchange's option chain download or one
+/// contract per row.
+async fn import_chain(
+    State(state): State<AppState>,
+    Query(query): Query<ImportQuery>,
+    body: String,
+) -> ApiResult<ImportSummary> {
+    let defaults = ImportDefaults {
+        underlying: query
+            .underlying
+            .map(|u| u.trim().to_uppercase())
+            .filter(|u| !u.is_empty()),
+        expiry: query.expiry,
+        date: query.date.unwrap_or_else(|| now_ist().date()),
+        spot: query.spot.filter(|s| *s > 0.0),
+    };
+    let (snapshots, mut summary) = {
+        let instruments = state.instruments.read().unwrap();
+        let contract = |symbol: &str| {
+            let instrument = instruments.get(symbol)?;
+            let option_type = match instrument.kind {
+                InstrumentKind::CallOption => OptionType::Call,
+                InstrumentKind::PutOption => OptionType::Put,
+                _ => return None,
+            };
+            Some(ContractInfo {
+                underlying: instrument.underlying.clone()?,
+                expiry: instrument.expiry?,
+                strike: instrument.strike?,
+                option_type,
+            })
+        };
+        option_chain::parse_csv(&body, &defaults, &contract).map_err(ApiError::BadRequest)?
+    };
+    if snapshots.is_empty() {
+        return Err(ApiError::BadRequest(match summary.errors.first() {
+            Some(error) => format!("no option quotes imported; {}", error),
+            None => "no option quotes in the file".to_string(),
+        }));
+    }
+    state
+        .option_chains
+        .write()
+        .unwrap()
+        .import(snapshots, &mut summary)
+        .map_err(ApiError::Internal)?;
+    Ok(Json(summary))
+}
+
+#[derive(Deserialize)]
+struct PriceQuery {
+    option_type: String,
+    #[serde(default)]
+    model: Model,
+    /// Spot, or the futures price for Black-76.
+    underlying_price: f64,
+    strike: f64,
+    /// Expires at the 15:30 close of this day...
+    expiry: Option<NaiveDate>,
+    /// ...or after this many calendar days.
+    days: Option<f64>,
+    /// Risk-free rate in percent.
+    rate: Option<f64>,
+    /// Dividend yield in percent, for Black-Scholes.
+    dividend_yield: Option<f64>,
+    /// Volatility in percent to price at...
+    volatility: Option<f64>,
+    /// ...or the premium to solve the implied volatility from.
+    premium: Option<f64>,
+}
+
+#[derive(Serialize)]
+struct PriceResponse {
+    model: Model,
+    years: f64,
+    /// In percent.
+    volatility: f64,
+    /// Whether the volatility was solved from the premium.
+    implied: bool,
+    greeks: Greeks,
+}
+
+/// Price and Greeks of one contract at a volatility, or its implied
+/// volatility and Greeks at a premium.
+async fn get_price(Query(query): Query<PriceQuery>) -> ApiResult<PriceResponse> {
+    let option_type = OptionType::parse(&query.option_type)
+        .ok_or_else(|| ApiError::BadRequest("option_type is CE or PE".to_string()))?;
+    let positive = |value: f64| value.is_finite() && value > 0.0;
+    if !positive(query.underlying_price) || !positive(query.strike) {
+        return Err(ApiError::BadRequest(
+            "underlying_price and strike must be positive".to_string(),
+        ));
+    }
+    let dividend_yield = query.dividend_yield.unwrap_or(0.0);
+    if !(0.0..=50.0).contains(&dividend_yield) {
+        return Err(ApiError::BadRequest(
+            "dividend_yield is a yearly percentage between 0 and 50".to_string(),
+        ));
+    }
+    let years = match (query.days, query.expiry) {
+        (Some(days), _) if days.is_finite() && days >= 0.0 => days / 365.0,
+        (Some(_), _) => {
+            return Err(ApiError::BadRequest(
+                "days must be a number of days, not negative".to_string(),
+            ))
+        }
+        (None, Some(expiry)) => {
+            let close = expiry.and_hms_opt(15, 30, 0).unwrap_or_default();
+            ((close - now_ist()).num_seconds().max(0) as f64) / (365.0 * 86_400.0)
+        }
+        (None, None) => {
+            return Err(ApiError::BadRequest(
+                "give the expiry or days to expiry".to_string(),
+            ))
+        }
+    };
+    let contract = Contract {
+        option_type,
+        model: query.model,
+        underlying: query.underlying_price,
+        strike: query.strike,
+        years,
+        rate: check_rate(query.rate)?,
+        dividend_yield: dividend_yield / 100.0,
+    };
+    let (volatility, implied) = match (query.premium, query.volatility) {
+        (Some(premium), _) if positive(premium) => {
+            let volatility = option_pricing::implied_volatility(&contract, premium)
+                .ok_or_else(|| {
+                    ApiError::BadRequest(format!(
+                        "no volatility gives a premium of {:.2}; it's outside what the option can be worth",
+                        premium
+                    ))
+                })?;
+            (volatility, true)
+        }
+        (None, Some(volatility)) if positive(volatility) => (volatility / 100.0, false),
+        _ => {
+            return Err(ApiError::BadRequest(
+                "give a positive volatility or premium".to_string(),
+            ))
+        }
+    };
+    Ok(Json(PriceResponse {
+        model: query.model,
+        years,
+        volatility: volatility * 100.0,
+        implied,
+        greeks: option_pricing::greeks(&contract, volatility),
+    }))
+}
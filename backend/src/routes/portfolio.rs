@@ -0,0 +1,603 @@
+use std::collections::HashMap;
+
+use axum::extract::{Path, Query, State};
+use axum::http::header;
+use axum::response::{IntoResponse, Response};
+use axum::routing::{get, post, put};
+use axum::{Json, Router};
+use chrono::NaiveDate;
+use serde::Deserialize;
+
+use crate::models::calendar::now_ist;
+use crate::models::corporate_action::{Adjustment, CorporateAction};
+use crate::models::instrument::{ExchangeSegment, InstrumentKind};
+use crate::models::journal::{Direction, Side};
+use crate::models::market::Timeframe;
+use crate::models::portfolio::{
+    self, CloseSeries, ImportSummary, Listing, PortfolioReport, Quote, Transaction,
+    TransactionKind, TransactionSource, ValuePoint,
+};
+use crate::models::portfolio_risk::{self, Position, RiskReport, DEFAULT_MAX_POSITION};
+use crate::models::rebalance::{self, Candidate, Constraints, Method, RebalancePlan};
+use crate::models::risk_profile;
+use crate::routes::market::load_series;
+use crate::state::AppState;
+use crate::utils::api::{ApiError, ApiResult};
+use crate::utils::csv;
+
+pub fn router() -> Router<AppState> {
+    Router::new()
+        .route("/", get(get_report))
+        .route("/history", get(get_history))
+        .route("/rebalance", get(get_rebalance))
+        .route("/rebalance/basket.csv", get(rebalance_basket))
+        .route("/risk", get(get_risk))
+        .route(
+            "/transactions",
+            get(list_transactions).post(create_transaction),
+        )
+        .route(
+            "/transactions/:id",
+            put(update_transaction).delete(delete_transaction),
+        )
+        .route("/import", post(import_csv))
+        .route("/import/journal", post(import_journal))
+}
+
+/// Index compared against first when it has data.
+const PRIMARY_BENCHMARK: &str = "Nifty 50";
+
+fn not_found(id: &str) -> ApiError {
+    ApiError::NotFound(format!("no transaction '{}'", id))
+}
+
+fn daily_closes(state: &AppState, symbol: &str) -> CloseSeries {
+    let market = state.market.read().unwrap();
+    let calendar = state.calendar.read().unwrap();
+    market
+        .bars(symbol, Timeframe::D1, None, None, &calendar)
+        .iter()
+        .map(|bar| (bar.date(), bar.close))
+        .collect()
+}
+
+/// Indices with daily history, the primary benchmark first.
+fn benchmarks(state: &AppState) -> Vec<(String, CloseSeries)> {
+    let mut symbols: Vec<String> = {
+        let market = state.market.read().unwrap();
+        let instruments = state.instruments.read().unwrap();
+        market
+            .daily_symbols()
+            .into_iter()
+            .filter(|s| {
+                instruments
+                    .get(s)
+                    .is_some_and(|i| i.kind == InstrumentKind::Index)
+            })
+            .collect()
+    };
+    symbols.sort_by_key(|s| (s != PRIMARY_BENCHMARK, s.clone()));
+    symbols
+        .into_iter()
+        .map(|symbol| {
+            let series = daily_closes(state, &symbol);
+            (symbol, series)
+        })
+        .collect()
+}
+
+/// Splits, bonuses and dividends of every symbol traded.
+fn actions(state: &AppState, transactions: &[Transaction]) -> Vec<CorporateAction> {
+    let store = state.corporate_actions.read().unwrap();
+    let mut symbols: Vec<&str> = transactions.iter().map(|t| t.symbol.as_str()).collect();
+    symbols.sort();
+    symbols.dedup();
+    symbols
+        .into_iter()
+        .flat_map(|symbol| store.for_symbol(symbol).iter().cloned())
+        .collect()
+}
+
+/// Current holdings valued at the last close, with benchmark comparisons.
+/// Shared with the dashboard summary.
+pub fn build_report(state: &AppState) -> PortfolioReport {
+    let transactions = state.portfolio.read().unwrap().transactions().to_vec();
+    let actions = actions(state, &transactions);
+    let quote = |symbol: &str| {
+        let closes = daily_closes(state, symbol);
+        let mut recent = closes.iter().rev();
+        let (date, close) = recent.next()?;
+        Some(Quote {
+            close: *close,
+            previous: recent.next().map(|(_, close)| *close),
+            date: *date,
+        })
+    };
+    let listing = |symbol: &str| {
+        if let Some(company) = state.fundamentals.read().unwrap().get(symbol) {
+            return Listing {
+                name: company.name.clone(),
+                sector: if company.sector.is_empty() {
+                    "Unclassified".to_string()
+                } else {
+                    company.sector.clone()
+                },
+            };
+        }
+        Listing {
+            name: state
+                .instruments
+                .read()
+                .unwrap()
+                .get(symbol)
+                .map_or_else(|| symbol.to_string(), |i| i.name.clone()),
+            sector: "Unclassified".to_string(),
+        }
+    };
+    portfolio::report(
+        &transactions,
+        &actions,
+        now_ist().date(),
+        &quote,
+        &listing,
+        &benchmarks(state),
+    )
+}
+
+async fn get_report(State(state): State<AppState>) -> ApiResult<PortfolioReport> {
+    Ok(Json(build_report(&state)))
+}
+
+#[derive(Deserialize)]
+struct HistoryQuery {
+    /// Index to compare with; the primary benchmark when left out.
+    benchmark: Option<String>,
+}
+
+/// Value of the holdings on every trading day since the first purchase.
+async fn get_history(
+    State(state): State<AppState>,
+    Query(query): Query<HistoryQuery>,
+) -> ApiResult<Vec<ValuePoint>> {
+    let transactions = state.portfolio.read().unwrap().transactions().to_vec();
+    let Some(first) = transactions.iter().map(|t| t.date).min() else {
+        return Ok(Json(Vec::new()));
+    };
+    let dates = state
+        .calendar
+        .read()
+        .unwrap()
+        .trading_days_between(first, now_ist().date());
+    let actions = actions(&state, &transactions);
+    let mut closes: HashMap<String, CloseSeries> = HashMap::new();
+    for transaction in &transactions {
+        if !closes.contains_key(&transaction.symbol) {
+            let series = daily_closes(&state, &transaction.symbol);
+            closes.insert(transaction.symbol.clone(), series);
+        }
+    }
+    let benchmark = daily_closes(
+        &state,
+        query.benchmark.as_deref().unwrap_or(PRIMARY_BENCHMARK),
+    );
+    let benchmark = (!benchmark.is_empty()).then_some(&benchmark);
+    Ok(Json(portfolio::history(
+        &transactions,
+        &actions,
+        &dates,
+        &closes,
+        benchmark,
+    )))
+}
+
+/// The last `days + 1` daily closes, adjusted for splits, bonuses and
+/// dividends so their returns are total returns.
+fn adjusted_closes(state: &AppState, symbol: &str, days: usize) -> CloseSeries {
+    load_series(
+        state,
+        symbol,
+        Timeframe::D1,
+        None,
+        None,
+        Some(days + 1),
+        Adjustment::Total,
+    )
+    .bars
+    .iter()
+    .map(|bar| (bar.date(), bar.close))
+    .collect()
+}
+
+/// Default risk window, a year of trading days, and the longest allowed.
+const RISK_DAYS: usize = 252;
+const MAX_RISK_DAYS: usize = 2520;
+
+#[derive(Deserialize)]
+struct RiskQuery {
+    /// Trading days of returns to measure over.
+    days: Option<usize>,
+    benchmark: Option<String>,
+}
+
+/// Beta, VaR, volatility and correlations of the current holdings, from
+/// their total-return adjusted daily closes.
+async fn get_risk(
+    State(state): State<AppState>,
+    Query(query): Query<RiskQuery>,
+) -> ApiResult<RiskReport> {
+    let days = query.days.unwrap_or(RISK_DAYS);
+    check_days(days)?;
+    let report = build_report(&state);
+    let positions: Vec<Position> = report
+        .holdings
+        .iter()
+        .filter(|h| h.price.is_some() && h.weight > 0.0)
+        .map(|h| Position {
+            symbol: h.symbol.clone(),
+            sector: h.sector.clone(),
+            weight: h.weight,
+            closes: adjusted_closes(&state, &h.symbol, days),
+        })
+        .collect();
+
+    let benchmark_symbol = query
+        .benchmark
+        .filter(|s| !s.is_empty())
+        .unwrap_or_else(|| PRIMARY_BENCHMARK.to_string());
+    let benchmark = daily_closes(&state, &benchmark_symbol);
+    let (max_position, limit_source) = match state.risk_profile.read().unwrap().profile() {
+        Some(profile) => {
+            let assessment = risk_profile::assess(profile);
+            (
+                assessment.guidance.max_position,
+                format!("your {} profile", assessment.label),
+            )
+        }
+        None => (
+            DEFAULT_MAX_POSITION,
+            "the default (no risk profile yet)".to_string(),
+        ),
+    };
+
+    let mut risk = portfolio_risk::analyze(
+        &positions,
+        (!benchmark.is_empty()).then_some((benchmark_symbol.as_str(), &benchmark)),
+        days,
+        positions.iter().map(|p| p.weight).sum::<f64>() / 100.0 * report.summary.value,
+        max_position,
+        &limit_source,
+    );
+    if !report.summary.unpriced.is_empty() {
+        risk.notes.push(format!(
+            "{} left out for lack of prices",
+            report.summary.unpriced.join(", ")
+        ));
+    }
+    Ok(Json(risk))
+}
+
+fn check_days(days: usize) -> Result<(), ApiError> {
+    if !(portfolio_risk::ROLLING_WINDOW..=MAX_RISK_DAYS).contains(&days) {
+        return Err(ApiError::BadRequest(format!(
+            "days must be between {} and {}",
+            portfolio_risk::ROLLING_WINDOW,
+            MAX_RISK_DAYS
+        )));
+    }
+    Ok(())
+}
+
+#[derive(Deserialize)]
+struct RebalanceQuery {
+    #[serde(default)]
+    method: Method,
+    /// Largest weight of one stock, in percent.
+    max_weight: Option<f64>,
+    risk_aversion: Option<f64>,
+    /// Cash to add to the portfolio, or withdraw when negative.
+    cash: Option<f64>,
+    min_trade: Option<f64>,
+    days: Option<usize>,
+    /// Comma-separated stocks to consider besides the holdings.
+    symbols: Option<String>,
+}
+
+/// Lower risk aversion lets mean-variance chase return harder.
+const DEFAULT_RISK_AVERSION: f64 = 3.0;
+const DEFAULT_MIN_TRADE: f64 = 1000.0;
+const MAX_RISK_AVERSION: f64 = 100.0;
+
+fn build_rebalance(state: &AppState, query: &RebalanceQuery) -> Result<RebalancePlan, ApiError> {
+    let days = query.days.unwrap_or(RISK_DAYS);
+    check_days(days)?;
+    let max_weight = query.max_weight.unwrap_or(DEFAULT_MAX_POSITION);
+    if !(max_weight > 0.0 && max_weight <= 100.0) {
+        return Err(ApiError::BadRequest(
+            "max_weight must be above 0 and at most 100".to_string(),
+        ));
+    }
+    let risk_aversion = query.risk_aversion.unwrap_or(DEFAULT_RISK_AVERSION);
+    if !(risk_aversion > 0.0 && risk_aversion <= MAX_RISK_AVERSION) {
+        return Err(ApiError::BadRequest(format!(
+            "risk_aversion must be above 0 and at most {}",
+            MAX_RISK_AVERSION
+        )));
+    }
+    let cash = query.cash.unwrap_or(0.0);
+    if !cash.is_finite() {
+        return Err(ApiError::BadRequest("cash must be a number".to_string()));
+    }
+    let min_trade = query.min_trade.unwrap_or(DEFAULT_MIN_TRADE);
+    if !(min_trade.is_finite() && min_trade >= 0.0) {
+        return Err(ApiError::BadRequest(
+            "min_trade can't be negative".to_string(),
+        ));
+    }
+
+    let report = build_report(state);
+    let mut held: Vec<(String, f64)> = report
+        .holdings
+        .iter()
+        .map(|h| (h.symbol.clone(), h.quantity))
+        .collect();
+    for symbol in query.symbols.as_deref().unwrap_or("").split(',') {
+        let symbol = symbol.trim().to_uppercase();
+        if !symbol.is_empty() && !held.iter().any(|(s, _)| *s == symbol) {
+            held.push((symbol, 0.0));
+        }
+    }
+
+    let mut notes = Vec::new();
+    let mut priced = Vec::new();
+    for (symbol, quantity) in held {
+        // Trades go through at the last traded price, which is the
+        // unadjusted close
+        let closes = adjusted_closes(state, &symbol, days);
+        match daily_closes(state, &symbol).values().next_back() {
+            Some(price) if *price > 0.0 && !closes.is_empty() => {
+                priced.push((symbol, quantity, *price, closes))
+            }
+            _ => notes.push(format!("{} has no price history and is left out", symbol)),
+        }
+    }
+    let series: Vec<&CloseSeries> = priced.iter().map(|(_, _, _, closes)| closes).collect();
+    let dates = portfolio_risk::shared_dates(&series, days);
+    let mut candidates: Vec<Candidate> = Vec::new();
+    for (symbol, quantity, price, closes) in priced {
+        match portfolio_risk::returns_on(&closes, &dates) {
+            Some(returns) => candidates.push(Candidate {
+                symbol,
+                price,
+                quantity,
+                returns,
+            }),
+            None => notes.push(format!(
+                "{} has a zero or negative close in the window and is left out",
+                symbol
+            )),
+        }
+    }
+
+    let constraints = Constraints {
+        max_weight,
+        risk_aversion,
+        cash,
+        min_trade,
+    };
+    let rates = state.charges.read().unwrap();
+    let mut plan = rebalance::plan(&candidates, query.method, &constraints, &rates);
+    plan.notes.extend(notes);
+    Ok(plan)
+}
+
+/// Target weights for the holdings, plus any extra stocks, and the trades
+/// that get there.
+async fn get_rebalance(
+    State(state): State<AppState>,
+    Query(query): Query<RebalanceQuery>,
+) -> ApiResult<RebalancePlan> {
+    build_rebalance(&state, &query).map(Json)
+}
+
+/// The rebalance trades as a basket of delivery limit orders at the last
+/// close, in the column layout broker basket uploads take.
+async fn rebalance_basket(
+    State(state): State<AppState>,
+    Query(query): Query<RebalanceQuery>,
+) -> Result<Response, ApiError> {
+    let plan = build_rebalance(&state, &query)?;
+    let mut lines = vec![csv::row(&[
+        "Symbol",
+        "Exchange",
+        "Transaction type",
+        "Quantity",
+        "Price",
+        "Order type",
+        "Product",
+    ])];
+    for trade in &plan.trades {
+        let exchange = match state.instruments.read().unwrap().get(&trade.symbol) {
+            Some(i) if i.segment == ExchangeSegment::BseEq => "BSE",
+            _ => "NSE",
+        };
+        lines.push(csv::row(&[
+            trade.symbol.clone(),
+            exchange.to_string(),
+            match trade.side {
+                Side::Buy => "BUY",
+                Side::Sell => "SELL",
+            }
+            .to_string(),
+            trade.quantity.to_string(),
+            format!("{:.2}", trade.price),
+            "LIMIT".to_string(),
+            "CNC".to_string(),
+        ]));
+    }
+    Ok((
+        [
+            (header::CONTENT_TYPE, "text/csv".to_string()),
+            (
+                header::CONTENT_DISPOSITION,
+                format!(
+                    "attachment; filename=\"rebalance-{}-{}.csv\"",
+                    plan.label.to_lowercase().replace(' ', "-"),
+                    now_ist().date()
+                ),
+            ),
+        ],
+        lines.join("\n") + "\n",
+    )
+        .into_response())
+}
+
+async fn list_transactions(State(state): State<AppState>) -> ApiResult<Vec<Transaction>> {
+    Ok(Json(
+        state.portfolio.read().unwrap().transactions().to_vec(),
+    ))
+}
+
+fn check(transaction: &mut Transaction) -> Result<(), ApiError> {
+    transaction.symbol = transaction.symbol.trim().to_uppercase();
+    transaction.validate().map_err(ApiError::BadRequest)
+}
+
+async fn create_transaction(
+    State(state): State<AppState>,
+    Json(mut transaction): Json<Transaction>,
+) -> ApiResult<Transaction> {
+    check(&mut transaction)?;
+    transaction.source = TransactionSource::Manual;
+    transaction.reference = None;
+    state
+        .portfolio
+        .write()
+        .unwrap()
+        .insert(transaction)
+        .map(Json)
+        .map_err(ApiError::Internal)
+}
+
+async fn update_transaction(
+    State(state): State<AppState>,
+    Path(id): Path<String>,
+    Json(mut transaction): Json<Transaction>,
+) -> ApiResult<Transaction> {
+    check(&mut transaction)?;
+    state
+        .portfolio
+        .write()
+        .unwrap()
+        .update(&id, transaction)
+        .map_err(ApiError::Internal)?
+        .map(Json)
+        .ok_or_else(|| not_found(&id))
+}
+
+async fn delete_transaction(
+    State(state): State<AppState>,
+    Path(id): Path<String>,
+) -> ApiResult<String> {
+    let removed = state
+        .portfolio
+        .write()
+        .unwrap()
+        .remove(&id)
+        .map_err(ApiError::Internal)?;
+    if !removed {
+        return Err(not_found(&id));
+    }
+    Ok(Json(id))
+}
+
+#[derive(Deserialize)]
+struct ImportQuery {
+    /// Purchase date for rows without one, e.g. a holdings snapshot.
+    /// Defaults to today.
+    date: Option<NaiveDate>,
+}
+
+/// Import a holdings export or a list of buys and sells.
+async fn import_csv(
+    State(state): State<AppState>,
+    Query(query): Query<ImportQuery>,
+    body: String,
+) -> ApiResult<ImportSummary> {
+    let date = query.date.unwrap_or_else(|| now_ist().date());
+    let (transactions, mut summary) =
+        portfolio::parse_csv(&body, date).map_err(ApiError::BadRequest)?;
+    state
+        .portfolio
+        .write()
+        .unwrap()
+        .import(transactions, &mut summary)
+        .map_err(ApiError::Internal)?;
+    Ok(Json(summary))
+}
+
+/// Copy delivery trades from the journal: long equity positions held past
+/// the day they were opened. Intraday round trips, shorts and
+/// derivatives stay in the journal only.
+async fn import_journal(State(state): State<AppState>) -> ApiResult<ImportSummary> {
+    let trades = state.journal.read().unwrap().trades().to_vec();
+    let today = now_ist().date();
+    let mut summary = ImportSummary::default();
+    let mut transactions = Vec::new();
+    {
+        let instruments = state.instruments.read().unwrap();
+        for trade in &trades {
+            let is_equity = instruments.get(&trade.symbol).is_none_or(|i| {
+                !i.kind.is_derivative()
+                    && i.kind != InstrumentKind::Index
+                    && matches!(i.segment, ExchangeSegment::NseEq | ExchangeSegment::BseEq)
+            });
+            let executions = trade.sorted_executions();
+            let Some(first) = executions.first() else {
+                continue;
+            };
+            let trade_summary = trade.summarize(None);
+            let last_day = if trade_summary.open_quantity > 0 {
+                today
+            } else {
+                executions.last().map_or(today, |e| e.time.date())
+            };
+            let delivery = last_day > first.time.date();
+            if !is_equity || trade_summary.direction != Some(Direction::Long) || !delivery {
+                continue;
+            }
+            for (index, execution) in executions.iter().enumerate() {
+                let execution_id = if execution.id.is_empty() {
+                    index.to_string()
+                } else {
+                    execution.id.clone()
+                };
+                transactions.push(Transaction {
+                    id: String::new(),
+                    symbol: trade.symbol.to_uppercase(),
+                    kind: match execution.side {
+                        Side::Buy => TransactionKind::Buy,
+                        Side::Sell => TransactionKind::Sell,
+                    },
+                    date: execution.time.date(),
+                    quantity: execution.quantity as f64,
+                    price: execution.price,
+                    fees: execution.fees,
+                    source: TransactionSource::Journal,
+                    reference: Some(format!("{}/{}", trade.id, execution_id)),
+                });
+            }
+        }
+    }
+    if transactions.is_empty() {
+        summary
+            .notes
+            .push("No long delivery trades in the journal".to_string());
+    }
+    state
+        .portfolio
+        .write()
+        .unwrap()
+        .import(transactions, &mut summary)
+        .map_err(ApiError::Internal)?;
+    Ok(Json(summary))
+}
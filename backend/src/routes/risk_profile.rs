@@ -0,0 +1,169 @@
+use axum::extract::{Path, State};
+use axum::routing::get;
+use axum::{Json, Router};
+use chrono::NaiveDateTime;
+use serde::Serialize;
+
+use crate::models::calendar::now_ist;
+use crate::models::corporate_action::Adjustment;
+use crate::models::fundamentals;
+use crate::models::market::Timeframe;
+use crate::models::risk_profile::{
+    self, Assessment, PositionSize, Question, RiskProfile, Suitability, QUESTIONS,
+};
+use crate::models::scoring;
+use crate::routes::fundamentals::{company_profile, scores};
+use crate::routes::market::load_series;
+use crate::state::AppState;
+use crate::utils::api::{ApiError, ApiResult};
+
+pub fn router() -> Router<AppState> {
+    Router::new()
+        .route(
+            "/",
+            get(get_profile).put(put_profile).delete(delete_profile),
+        )
+        .route("/questions", get(list_questions))
+        .route("/guidance/:symbol", get(stock_guidance))
+}
+
+async fn list_questions() -> ApiResult<Vec<Question>> {
+    Ok(Json(QUESTIONS.to_vec()))
+}
+
+/// The saved answers with what they score to.
+#[derive(Serialize)]
+struct ProfileReport {
+    #[serde(flatten)]
+    profile: RiskProfile,
+    assessment: Assessment,
+}
+
+fn report(profile: RiskProfile) -> ProfileReport {
+    ProfileReport {
+        assessment: risk_profile::assess(&profile),
+        profile,
+    }
+}
+
+/// Empty until the questionnaire has been filled in.
+async fn get_profile(State(state): State<AppState>) -> ApiResult<Option<ProfileReport>> {
+    let profile = state.risk_profile.read().unwrap().profile().cloned();
+    Ok(Json(profile.map(report)))
+}
+
+async fn put_profile(
+    State(state): State<AppState>,
+    Json(mut profile): Json<RiskProfile>,
+) -> ApiResult<ProfileReport> {
+    profile.validate().map_err(ApiError::BadRequest)?;
+    profile.assessed_at = Some(now_ist());
+    state
+        .risk_profile
+        .write()
+        .unwrap()
+        .set(profile.clone())
+        .map_err(ApiError::Internal)?;
+    Ok(Json(report(profile)))
+}
+
+async fn delete_profile(State(state): State<AppState>) -> ApiResult<bool> {
+    let removed = state
+        .risk_profile
+        .write()
+        .unwrap()
+        .clear()
+        .map_err(ApiError::Internal)?;
+    if !removed {
+        return Err(ApiError::NotFound("no risk profile saved".to_string()));
+    }
+    Ok(Json(true))
+}
+
+/// Daily bars for the volatility the limits and the stop use: a year of
+/// returns.
+const VOLATILITY_LOOKBACK: usize = 253;
+
+/// Profile-specific advice on one stock for Mindsage.
+#[derive(Serialize)]
+struct StockGuidance {
+    symbol: String,
+    assessed_at: Option<NaiveDateTime>,
+    price: Option<f64>,
+    volatility: Option<f64>,
+    debt_to_equity: Option<f64>,
+    composite: Option<f64>,
+    suitability: Suitability,
+    /// Empty without a capital in the profile or a price.
+    position: Option<PositionSize>,
+    /// The profile's bucket and guidance, for the allocation next to the
+    /// stock.
+    assessment: Assessment,
+}
+
+async fn stock_guidance(
+    State(state): State<AppState>,
+    Path(symbol): Path<String>,
+) -> ApiResult<StockGuidance> {
+    let symbol = fundamentals::normalize(&symbol);
+    let profile = state
+        .risk_profile
+        .read()
+        .unwrap()
+        .profile()
+        .cloned()
+        .ok_or_else(|| {
+            ApiError::NotFound(
+                "no risk profile yet; fill in the questionnaire on the Profile page".to_string(),
+            )
+        })?;
+    let assessment = risk_profile::assess(&profile);
+
+    let series = load_series(
+        &state,
+        &symbol,
+        Timeframe::D1,
+        None,
+        None,
+        Some(VOLATILITY_LOOKBACK),
+        Adjustment::Capital,
+    );
+    let closes: Vec<f64> = series.bars.iter().map(|b| b.close).collect();
+    let company = company_profile(&state, &symbol);
+    if closes.is_empty() && company.is_none() {
+        return Err(ApiError::NotFound(format!(
+            "no prices or fundamentals for '{}'",
+            symbol
+        )));
+    }
+    // The last adjusted close is the traded price; only earlier bars move
+    let price = closes.last().copied();
+    let volatility = scoring::technicals(&closes).volatility;
+    let debt_to_equity = company.as_ref().and_then(|c| c.ratios.debt_to_equity);
+    let composite = match company {
+        Some(_) => scores(&state)
+            .into_iter()
+            .find(|s| s.symbol == symbol)
+            .and_then(|s| s.composite),
+        None => None,
+    };
+
+    Ok(Json(StockGuidance {
+        suitability: risk_profile::suitability(
+            assessment.bucket,
+            volatility,
+            debt_to_equity,
+            composite,
+        ),
+        position: profile.capital.zip(price).and_then(|(capital, price)| {
+            risk_profile::position_size(assessment.bucket, capital, price, volatility)
+        }),
+        symbol,
+        assessed_at: profile.assessed_at,
+        price,
+        volatility,
+        debt_to_equity,
+        composite,
+        assessment,
+    }))
+}
@@ -0,0 +1,198 @@
+use axum::extract::{Path, Query, State};
+use axum::routing::{get, put};
+use axum::{Json, Router};
+use chrono::Duration;
+use serde::{Deserialize, Serialize};
+
+use crate::models::calendar::now_ist;
+use crate::models::rules::{self, ChecklistItem, DailyReview, Rule, Violation};
+use crate::routes::calendar::parse_date;
+use crate::state::AppState;
+use crate::utils::api::{ApiError, ApiResult};
+
+pub fn router() -> Router<AppState> {
+    Router::new()
+        .route("/", get(list_rules).post(create_rule))
+        .route("/:id", put(update_rule).delete(delete_rule))
+        .route("/checklist", get(get_checklist).put(set_checklist))
+        .route("/violations", get(list_violations))
+        .route("/reviews", get(list_reviews))
+        .route("/reviews/:date", get(get_review).put(save_review))
+}
+
+fn not_found(id: &str) -> ApiError {
+    ApiError::NotFound(format!("no rule '{}'", id))
+}
+
+/// Every rule violation in the journal, for flagging trades.
+pub fn violations(state: &AppState) -> Vec<Violation> {
+    let rules = state.rules.read().unwrap().rules().to_vec();
+    if rules.iter().all(|r| !r.enabled) {
+        return Vec::new();
+    }
+    let journal = state.journal.read().unwrap();
+    let calendar = state.calendar.read().unwrap();
+    let market_open = |day| calendar.normal_session(day).map(|s| s.start);
+    rules::check(&rules, journal.trades(), &market_open)
+}
+
+async fn list_rules(State(state): State<AppState>) -> ApiResult<Vec<Rule>> {
+    Ok(Json(state.rules.read().unwrap().rules().to_vec()))
+}
+
+async fn create_rule(State(state): State<AppState>, Json(rule): Json<Rule>) -> ApiResult<Rule> {
+    rule.validate().map_err(ApiError::BadRequest)?;
+    state
+        .rules
+        .write()
+        .unwrap()
+        .insert(rule)
+        .map(Json)
+        .map_err(ApiError::Internal)
+}
+
+async fn update_rule(
+    State(state): State<AppState>,
+    Path(id): Path<String>,
+    Json(rule): Json<Rule>,
+) -> ApiResult<Rule> {
+    rule.validate().map_err(ApiError::BadRequest)?;
+    state
+        .rules
+        .write()
+        .unwrap()
+        .update(&id, rule)
+        .map_err(ApiError::Internal)?
+        .map(Json)
+        .ok_or_else(|| not_found(&id))
+}
+
+async fn delete_rule(State(state): State<AppState>, Path(id): Path<String>) -> ApiResult<String> {
+    let removed = state
+        .rules
+        .write()
+        .unwrap()
+        .remove(&id)
+        .map_err(ApiError::Internal)?;
+    if !removed {
+        return Err(not_found(&id));
+    }
+    Ok(Json(id))
+}
+
+async fn get_checklist(State(state): State<AppState>) -> ApiResult<Vec<ChecklistItem>> {
+    Ok(Json(state.rules.read().unwrap().checklist().to_vec()))
+}
+
+async fn set_checklist(
+    State(state): State<AppState>,
+    Json(items): Json<Vec<ChecklistItem>>,
+) -> ApiResult<Vec<ChecklistItem>> {
+    state
+        .rules
+        .write()
+        .unwrap()
+        .set_checklist(items)
+        .map(Json)
+        .map_err(ApiError::Internal)
+}
+
+#[derive(Deserialize)]
+struct RangeQuery {
+    from: Option<String>,
+    to: Option<String>,
+}
+
+async fn list_violations(
+    State(state): State<AppState>,
+    Query(query): Query<RangeQuery>,
+) -> ApiResult<Vec<Violation>> {
+    let from = query.from.as_deref().map(parse_date).transpose()?;
+    let to = query.to.as_deref().map(parse_date).transpose()?;
+    let list = violations(&state)
+        .into_iter()
+        .filter(|v| from.is_none_or(|f| v.date >= f) && to.is_none_or(|t| v.date <= t))
+        .collect();
+    Ok(Json(list))
+}
+
+/// Reviews in a date range, the last 30 days by default.
+async fn list_reviews(
+    State(state): State<AppState>,
+    Query(query): Query<RangeQuery>,
+) -> ApiResult<Vec<DailyReview>> {
+    let today = now_ist().date();
+    let from = match query.from.as_deref() {
+        Some(value) => parse_date(value)?,
+        None => today - Duration::days(30),
+    };
+    let to = match query.to.as_deref() {
+        Some(value) => parse_date(value)?,
+        None => today,
+    };
+    if from > to {
+        return Err(ApiError::BadRequest(format!(
+            "from ({}) is after to ({})",
+            from, to
+        )));
+    }
+    Ok(Json(state.rules.read().unwrap().reviews(from, to)))
+}
+
+/// A day's checklist answers alongside the trades and rule breaks of that
+/// day.
+#[derive(Serialize)]
+struct DayReview {
+    review: DailyReview,
+    checklist: Vec<ChecklistItem>,
+    trades: usize,
+    violations: Vec<Violation>,
+}
+
+fn day_review(state: &AppState, review: DailyReview) -> DayReview {
+    let trades = state
+        .journal
+        .read()
+        .unwrap()
+        .trades()
+        .iter()
+        .filter(|t| t.executions.iter().any(|e| e.time.date() == review.date))
+        .count();
+    let violations = violations(state)
+        .into_iter()
+        .filter(|v| v.date == review.date)
+        .collect();
+    DayReview {
+        checklist: state.rules.read().unwrap().checklist().to_vec(),
+        review,
+        trades,
+        violations,
+    }
+}
+
+async fn get_review(
+    State(state): State<AppState>,
+    Path(date): Path<String>,
+) -> ApiResult<DayReview> {
+    let date = parse_date(&date)?;
+    let review = state.rules.read().unwrap().review(date);
+    Ok(Json(day_review(&state, review)))
+}
+
+async fn save_review(
+    State(state): State<AppState>,
+    Path(date): Path<String>,
+    Json(mut review): Json<DailyReview>,
+) -> ApiResult<DayReview> {
+    review.date = parse_date(&date)?;
+    {
+        let mut store = state.rules.write().unwrap();
+        let known: Vec<String> = store.checklist().iter().map(|i| i.id.clone()).collect();
+        review.checked.retain(|id| known.contains(id));
+        review.checked.dedup();
+        store
+            .save_review(review.clone())
+            .map_err(ApiError::Internal)?;
+    }
+    Ok(Json(day_review(&state, review)))
+}
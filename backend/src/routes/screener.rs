@@ -0,0 +1,290 @@
+use std::collections::{BTreeSet, HashMap};
+
+use axum::extract::{Path, State};
+use axum::routing::{get, post};
+use axum::{Json, Router};
+use chrono::{NaiveDateTime, NaiveTime};
+use serde::{Deserialize, Serialize};
+
+use crate::models::calendar::now_ist;
+use crate::models::corporate_action::Adjustment;
+use crate::models::fundamentals;
+use crate::models::market::Timeframe;
+use crate::models::screener::{
+    self, Filter, RetryBackoff, Screen, ScreenRun, StockData, FIELDS, FUNCTIONS,
+};
+use crate::routes::fundamentals::scores;
+use crate::routes::market::load_series;
+use crate::state::AppState;
+use crate::utils::api::{ApiError, ApiResult};
+
+pub fn router() -> Router<AppState> {
+    Router::new()
+        .route("/", get(list_screens).post(create_screen))
+        .route("/fields", get(list_fields))
+        .route("/run", post(run_expression))
+        .route(
+            "/:id",
+            get(get_screen).put(update_screen).delete(delete_screen),
+        )
+        .route("/:id/run", post(run_screen))
+}
+
+fn not_found(id: &str) -> ApiError {
+    ApiError::NotFound(format!("no screen '{}'", id))
+}
+
+/// Everything a screen can look at, for every symbol with daily history
+/// or fundamentals. Only as many bars as the filter needs are loaded, and
+/// scores are only worked out when the filter uses them.
+fn universe(state: &AppState, filter: &Filter) -> Vec<StockData> {
+    let companies: HashMap<String, fundamentals::CompanyFundamentals> = state
+        .fundamentals
+        .read()
+        .unwrap()
+        .companies()
+        .map(|c| (c.symbol.clone(), c.clone()))
+        .collect();
+    let symbols: BTreeSet<String> = state
+        .market
+        .read()
+        .unwrap()
+        .daily_symbols()
+        .into_iter()
+        .chain(companies.keys().cloned())
+        .collect();
+    let composite: HashMap<String, f64> = if filter.columns.iter().any(|c| c == "score") {
+        scores(state)
+            .into_iter()
+            .filter_map(|s| Some((s.symbol, s.composite?)))
+            .collect()
+    } else {
+        HashMap::new()
+    };
+    let lookback = filter.lookback().max(2);
+
+    symbols
+        .into_iter()
+        .map(|symbol| {
+            let bars = load_series(
+                state,
+                &symbol,
+                Timeframe::D1,
+                None,
+                None,
+                Some(lookback),
+                Adjustment::Capital,
+            )
+            .bars;
+            let company = companies.get(&symbol);
+            // Ratios are priced at the unadjusted close, like the profile
+            let price = state
+                .market
+                .read()
+                .unwrap()
+                .daily_bars(&symbol)
+                .last()
+                .map(|b| b.close);
+            let name = company.map(|c| c.name.clone()).filter(|n| !n.is_empty());
+            let name = name.or_else(|| {
+                state
+                    .instruments
+                    .read()
+                    .unwrap()
+                    .get(&symbol)
+                    .map(|i| i.name.clone())
+            });
+            StockData {
+                name: name.unwrap_or_else(|| symbol.clone()),
+                sector: company.map(|c| c.sector.clone()).unwrap_or_default(),
+                industry: company.map(|c| c.industry.clone()).unwrap_or_default(),
+                ratios: company.map(|c| fundamentals::ratios(c, price)),
+                score: composite.get(&symbol).copied(),
+                bars,
+                symbol,
+            }
+        })
+        .collect()
+}
+
+fn execute(state: &AppState, expression: &str) -> Result<ScreenRun, ApiError> {
+    let filter = Filter::parse(expression).map_err(ApiError::BadRequest)?;
+    let stocks = universe(state, &filter);
+    Ok(screener::run(&filter, expression, &stocks, now_ist()))
+}
+
+#[derive(Serialize)]
+struct FieldHelp {
+    name: String,
+    description: &'static str,
+}
+
+#[derive(Serialize)]
+struct Fields {
+    fields: Vec<FieldHelp>,
+    functions: Vec<FieldHelp>,
+}
+
+/// Names an expression may use, for the query builder.
+async fn list_fields() -> ApiResult<Fields> {
+    Ok(Json(Fields {
+        fields: FIELDS
+            .iter()
+            .map(|f| FieldHelp {
+                name: f.name.to_string(),
+                description: f.description,
+            })
+            .collect(),
+        functions: FUNCTIONS
+            .iter()
+            .map(|f| FieldHelp {
+                name: format!("{}(n)", f.name),
+                description: f.description,
+            })
+            .collect(),
+    }))
+}
+
+#[derive(Deserialize)]
+struct RunRequest {
+    expression: String,
+}
+
+/// Run an expression without saving it.
+async fn run_expression(
+    State(state): State<AppState>,
+    Json(request): Json<RunRequest>,
+) -> ApiResult<ScreenRun> {
+    execute(&state, request.expression.trim()).map(Json)
+}
+
+async fn list_screens(State(state): State<AppState>) -> ApiResult<Vec<Screen>> {
+    Ok(Json(state.screener.read().unwrap().screens().to_vec()))
+}
+
+fn check(screen: &mut Screen) -> Result<(), ApiError> {
+    screen.name = screen.name.trim().to_string();
+    screen.expression = screen.expression.trim().to_string();
+    if screen.name.is_empty() {
+        return Err(ApiError::BadRequest("screen needs a name".to_string()));
+    }
+    Filter::parse(&screen.expression).map_err(ApiError::BadRequest)?;
+    Ok(())
+}
+
+async fn create_screen(
+    State(state): State<AppState>,
+    Json(mut screen): Json<Screen>,
+) -> ApiResult<Screen> {
+    check(&mut screen)?;
+    state
+        .screener
+        .write()
+        .unwrap()
+        .insert(screen)
+        .map(Json)
+        .map_err(ApiError::Internal)
+}
+
+async fn get_screen(State(state): State<AppState>, Path(id): Path<String>) -> ApiResult<Screen> {
+    state
+        .screener
+        .read()
+        .unwrap()
+        .get(&id)
+        .cloned()
+        .map(Json)
+        .ok_or_else(|| not_found(&id))
+}
+
+async fn update_screen(
+    State(state): State<AppState>,
+    Path(id): Path<String>,
+    Json(mut screen): Json<Screen>,
+) -> ApiResult<Screen> {
+    check(&mut screen)?;
+    state
+        .screener
+        .write()
+        .unwrap()
+        .update(&id, screen)
+        .map_err(ApiError::Internal)?
+        .map(Json)
+        .ok_or_else(|| not_found(&id))
+}
+
+async fn delete_screen(State(state): State<AppState>, Path(id): Path<String>) -> ApiResult<String> {
+    let removed = state
+        .screener
+        .write()
+        .unwrap()
+        .remove(&id)
+        .map_err(ApiError::Internal)?;
+    if !removed {
+        return Err(not_found(&id));
+    }
+    Ok(Json(id))
+}
+
+fn run_saved(state: &AppState, id: &str) -> Result<ScreenRun, ApiError> {
+    let expression = state
+        .screener
+        .read()
+        .unwrap()
+        .get(id)
+        .map(|s| s.expression.clone())
+        .ok_or_else(|| not_found(id))?;
+    let run = execute(state, &expression)?;
+    state
+        .screener
+        .write()
+        .unwrap()
+        .record_run(id, run)
+        .map_err(ApiError::Internal)?
+        .ok_or_else(|| not_found(id))
+}
+
+async fn run_screen(State(state): State<AppState>, Path(id): Path<String>) -> ApiResult<ScreenRun> {
+    run_saved(&state, &id).map(Json)
+}
+
+/// Run the daily screens that haven't run since `run_at` on today's
+/// session, if today is a trading day and that time has passed. Screens
+/// that failed wait out their `backoff` first. Returns how many ran.
+pub fn run_due(
+    state: &AppState,
+    now: NaiveDateTime,
+    run_at: NaiveTime,
+    backoff: &mut RetryBackoff,
+) -> usize {
+    let today = now.date();
+    if now.time() < run_at || !state.calendar.read().unwrap().is_trading_day(today) {
+        return 0;
+    }
+    let due_since = today.and_time(run_at);
+    let due: Vec<String> = state
+        .screener
+        .read()
+        .unwrap()
+        .screens()
+        .iter()
+        .filter(|s| s.daily)
+        .filter(|s| s.last_run.as_ref().is_none_or(|r| r.ran_at < due_since))
+        .filter(|s| backoff.ready(&s.id, now))
+        .map(|s| s.id.clone())
+        .collect();
+    due.iter()
+        .filter(|id| match run_saved(state, id) {
+            Ok(run) => {
+                log::info!("Screen {} matched {} stocks", id, run.rows.len());
+                backoff.succeeded(id);
+                true
+            }
+            Err(e) => {
+                let retry_at = backoff.failed(id, now);
+                log::warn!("Screen {} failed: {:?}; retrying at {}", id, e, retry_at);
+                false
+            }
+        })
+        .count()
+}
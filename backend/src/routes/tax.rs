@@ -0,0 +1,305 @@
+use axum::extract::{Query, State};
+use axum::http::header;
+use axum::response::{IntoResponse, Response};
+use axum::routing::get;
+use axum::{Json, Router};
+use chrono::NaiveDate;
+use serde::Deserialize;
+
+use crate::models::calendar::now_ist;
+use crate::models::instrument::{ExchangeSegment, InstrumentKind};
+use crate::models::market::Timeframe;
+use crate::models::tax::{self, SymbolInfo, TaxReport};
+use crate::state::AppState;
+use crate::utils::api::{ApiError, ApiResult};
+use crate::utils::csv;
+use crate::utils::format::rupees;
+use crate::utils::pdf::TextPdf;
+
+pub fn router() -> Router<AppState> {
+    Router::new()
+        .route("/report", get(report))
+        .route("/report.csv", get(report_csv))
+        .route("/report.pdf", get(report_pdf))
+}
+
+#[derive(Deserialize)]
+struct ReportQuery {
+    /// Start year of the financial year, e.g. 2024 for FY 2024-25.
+    /// Defaults to the current one.
+    fy: Option<i32>,
+}
+
+fn build_report(state: &AppState, query: &ReportQuery) -> Result<TaxReport, ApiError> {
+    let fy = query
+        .fy
+        .unwrap_or_else(|| tax::financial_year(now_ist().date()));
+    if !(2000..=2100).contains(&fy) {
+        return Err(ApiError::BadRequest(format!(
+            "invalid financial year {}",
+            fy
+        )));
+    }
+
+    let trades = state.journal.read().unwrap().trades().to_vec();
+    let instruments = state.instruments.read().unwrap();
+    let market = state.market.read().unwrap();
+    let calendar = state.calendar.read().unwrap();
+
+    let close_on = |symbol: &str, day: NaiveDate| {
+        market
+            .bars(symbol, Timeframe::D1, Some(day), Some(day), &calendar)
+            .first()
+            .map(|bar| bar.close)
+    };
+    let lookup = |symbol: &str| {
+        instruments.get(symbol).map(|i| {
+            let option = matches!(
+                i.kind,
+                InstrumentKind::CallOption | InstrumentKind::PutOption
+            );
+            let future_expiry = (i.kind == InstrumentKind::Future)
+                .then_some(i.expiry)
+                .flatten();
+            // Futures settle at the underlying's close on expiry; the
+            // contract's own last close stands in when that is missing
+            let settlement_price = future_expiry.and_then(|expiry| {
+                i.underlying
+                    .as_deref()
+                    .and_then(|underlying| close_on(underlying, expiry))
+                    .or_else(|| close_on(symbol, expiry))
+            });
+            SymbolInfo {
+                derivative: i.kind.is_derivative()
+                    || matches!(
+                        i.segment,
+                        ExchangeSegment::NseFo
+                            | ExchangeSegment::BseFo
+                            | ExchangeSegment::NseCd
+                            | ExchangeSegment::McxFo
+                    ),
+                option_expiry: option.then_some(i.expiry).flatten(),
+                future_expiry,
+                settlement_price,
+            }
+        })
+    };
+    // Fair market value for grandfathering is the day's highest price
+    let day = NaiveDate::from_ymd_opt(2018, 1, 31);
+    let fmv_2018 = |symbol: &str| {
+        market
+            .bars(symbol, Timeframe::D1, day, day, &calendar)
+            .first()
+            .map(|bar| bar.high)
+    };
+
+    Ok(tax::report(
+        &trades,
+        fy,
+        now_ist().date(),
+        &lookup,
+        &fmv_2018,
+    ))
+}
+
+async fn report(
+    State(state): State<AppState>,
+    Query(query): Query<ReportQuery>,
+) -> ApiResult<TaxReport> {
+    build_report(&state, &query).map(Json)
+}
+
+fn download(content_type: &str, file_name: String, body: Vec<u8>) -> Response {
+    (
+        [
+            (header::CONTENT_TYPE, content_type.to_string()),
+            (
+                header::CONTENT_DISPOSITION,
+                format!("attachment; filename=\"{}\"", file_name),
+            ),
+        ],
+        body,
+    )
+        .into_response()
+}
+
+fn file_stem(report: &TaxReport) -> String {
+    format!("tax-report-{}", report.financial_year.replace(' ', "-"))
+}
+
+/// Summary by head followed by every lot, in one sheet.
+async fn report_csv(
+    State(state): State<AppState>,
+    Query(query): Query<ReportQuery>,
+) -> Result<Response, ApiError> {
+    let report = build_report(&state, &query)?;
+    let mut lines = vec![csv::row(&[
+        "Head",
+        "Lots",
+        "Turnover",
+        "Sale value",
+        "Cost",
+        "Expenses",
+        "Profit",
+        "Loss",
+        "Net",
+        "Estimated tax",
+    ])];
+    for head in &report.heads {
+        lines.push(csv::row(&[
+            head.label.to_string(),
+            head.lots.to_string(),
+            format!("{:.2}", head.turnover),
+            format!("{:.2}", head.sell_value),
+            format!("{:.2}", head.cost),
+            format!("{:.2}", head.expenses),
+            format!("{:.2}", head.profit),
+            format!("{:.2}", head.loss),
+            format!("{:.2}", head.net),
+            head.estimated_tax
+                .map_or(String::new(), |t| format!("{:.2}", t)),
+        ]));
+    }
+
+    lines.push(String::new());
+    lines.push(csv::row(&[
+        "Head",
+        "Symbol",
+        "Trade",
+        "Direction",
+        "Quantity",
+        "Buy date",
+        "Sell date",
+        "Closed",
+        "Holding days",
+        "Buy value",
+        "Sell value",
+        "Cost",
+        "Expenses",
+        "Gain",
+        "Grandfathered",
+        "Expired",
+    ]));
+    for lot in &report.lots {
+        lines.push(csv::row(&[
+            lot.head.label().to_string(),
+            lot.symbol.clone(),
+            lot.trade_id.clone(),
+            format!("{:?}", lot.direction),
+            lot.quantity.to_string(),
+            lot.buy_date.to_string(),
+            lot.sell_date.to_string(),
+            lot.closed_date.to_string(),
+            lot.holding_days.to_string(),
+            format!("{:.2}", lot.buy_value),
+            format!("{:.2}", lot.sell_value),
+            format!("{:.2}", lot.cost),
+            format!("{:.2}", lot.expenses),
+            format!("{:.2}", lot.gain),
+            if lot.grandfathered { "yes" } else { "" }.to_string(),
+            if lot.expired { "yes" } else { "" }.to_string(),
+        ]));
+    }
+
+    lines.push(String::new());
+    for note in &report.notes {
+        lines.push(csv::row(&[note]));
+    }
+
+    Ok(download(
+        "text/csv",
+        format!("{}.csv", file_stem(&report)),
+        (lines.join("\n") + "\n").into_bytes(),
+    ))
+}
+
+/// Amounts for the PDF, without the rupee sign the PDF font lacks.
+fn amount(value: f64) -> String {
+    rupees(value).replace('₹', "")
+}
+
+async fn report_pdf(
+    State(state): State<AppState>,
+    Query(query): Query<ReportQuery>,
+) -> Result<Response, ApiError> {
+    let report = build_report(&state, &query)?;
+    let mut pdf = TextPdf::new(&format!(
+        "Tax P&L report {} ({} to {}), amounts in Rs",
+        report.financial_year, report.from, report.to
+    ));
+
+    pdf.line(format!(
+        "{:<32} {:>5} {:>16} {:>16} {:>14} {:>16} {:>16}",
+        "Head", "Lots", "Turnover", "Expenses", "Net", "LTCG exemption", "Est. tax"
+    ));
+    for head in &report.heads {
+        let exemption = if head.head == tax::TaxHead::LongTermCapitalGain {
+            amount(report.ltcg_exemption)
+        } else {
+            "-".to_string()
+        };
+        pdf.line(format!(
+            "{:<32} {:>5} {:>16} {:>16} {:>14} {:>16} {:>16}",
+            head.label,
+            head.lots,
+            amount(head.turnover),
+            amount(head.expenses),
+            amount(head.net),
+            exemption,
+            head.estimated_tax.map_or("slab rate".to_string(), amount)
+        ));
+    }
+
+    pdf.blank();
+    pdf.line(format!(
+        "{:<5} {:<16} {:>6} {:<10} {:<10} {:>5} {:>15} {:>15} {:>15} {:>11} {:>14}",
+        "Head",
+        "Symbol",
+        "Qty",
+        "Bought",
+        "Sold",
+        "Days",
+        "Buy value",
+        "Sell value",
+        "Cost",
+        "Expenses",
+        "Gain"
+    ));
+    for lot in &report.lots {
+        let head = match lot.head {
+            tax::TaxHead::Speculative => "SPEC",
+            tax::TaxHead::NonSpeculative => "F&O",
+            tax::TaxHead::ShortTermCapitalGain => "STCG",
+            tax::TaxHead::LongTermCapitalGain => "LTCG",
+        };
+        pdf.line(format!(
+            "{:<5} {:<16} {:>6} {:<10} {:<10} {:>5} {:>15} {:>15} {:>15} {:>11} {:>14}{}",
+            head,
+            lot.symbol.chars().take(16).collect::<String>(),
+            lot.quantity,
+            lot.buy_date,
+            lot.sell_date,
+            lot.holding_days,
+            amount(lot.buy_value),
+            amount(lot.sell_value),
+            amount(lot.cost),
+            amount(lot.expenses),
+            amount(lot.gain),
+            if lot.grandfathered { " *" } else { "" }
+        ));
+    }
+    if report.lots.iter().any(|l| l.grandfathered) {
+        pdf.line("* cost raised to the 31 Jan 2018 fair market value");
+    }
+
+    pdf.blank();
+    for note in &report.notes {
+        pdf.line(format!("Note: {}", note));
+    }
+
+    Ok(download(
+        "application/pdf",
+        format!("{}.pdf", file_stem(&report)),
+        pdf.render(),
+    ))
+}
@@ -0,0 +1,170 @@
+use axum::extract::{Path, State};
+use axum::routing::{delete, get};
+use axum::{Json, Router};
+use serde::Serialize;
+
+use crate::models::calendar::now_ist;
+use crate::models::valuation::{
+    self, Assumptions, DcfResult, DdmResult, ReverseDcf, Scenario, Sensitivity,
+};
+use crate::routes::fundamentals::{company_profile, CompanyProfile};
+use crate::state::AppState;
+use crate::utils::api::{ApiError, ApiResult};
+
+pub fn router() -> Router<AppState> {
+    Router::new()
+        .route("/:symbol", get(get_inputs).post(calculate))
+        .route(
+            "/:symbol/scenarios",
+            get(list_scenarios).post(save_scenario),
+        )
+        .route("/scenarios/:id", delete(delete_scenario))
+}
+
+fn profile(state: &AppState, symbol: &str) -> Result<CompanyProfile, ApiError> {
+    company_profile(state, symbol)
+        .ok_or_else(|| ApiError::NotFound(format!("no fundamentals for '{}'", symbol)))
+}
+
+/// What the models start from: the market price, per-share figures and
+/// assumptions pre-filled from the statements.
+#[derive(Serialize)]
+struct ValuationInputs {
+    symbol: String,
+    price: Option<f64>,
+    eps: Option<f64>,
+    book_value_per_share: Option<f64>,
+    defaults: Assumptions,
+}
+
+async fn get_inputs(
+    State(state): State<AppState>,
+    Path(symbol): Path<String>,
+) -> ApiResult<ValuationInputs> {
+    let profile = profile(&state, &symbol)?;
+    Ok(Json(ValuationInputs {
+        defaults: valuation::default_assumptions(&profile.company, &profile.ratios),
+        symbol: profile.company.symbol,
+        price: profile.price,
+        eps: profile.ratios.ttm_eps,
+        book_value_per_share: profile.ratios.book_value_per_share,
+    }))
+}
+
+/// Every model's value for one set of assumptions. Models that can't run,
+/// e.g. dividend discount for a stock that pays none, are left out with
+/// the reason in `notes`.
+#[derive(Serialize)]
+struct ValuationReport {
+    price: Option<f64>,
+    dcf: Option<DcfResult>,
+    reverse_dcf: Option<ReverseDcf>,
+    graham_number: Option<f64>,
+    ddm: Option<DdmResult>,
+    sensitivity: Vec<Sensitivity>,
+    notes: Vec<String>,
+}
+
+async fn calculate(
+    State(state): State<AppState>,
+    Path(symbol): Path<String>,
+    Json(assumptions): Json<Assumptions>,
+) -> ApiResult<ValuationReport> {
+    let profile = profile(&state, &symbol)?;
+    let mut notes = Vec::new();
+    let mut sensitivity = Vec::new();
+
+    let dcf = match valuation::dcf(&assumptions.dcf) {
+        Ok(result) => {
+            sensitivity.extend(valuation::dcf_sensitivity(&assumptions.dcf));
+            Some(result)
+        }
+        Err(e) => {
+            notes.push(format!("DCF: {}", e));
+            None
+        }
+    };
+    let reverse_dcf = match (dcf.as_ref(), profile.price) {
+        (Some(_), Some(price)) => valuation::reverse_dcf(&assumptions.dcf, price)
+            .map_err(|e| notes.push(format!("Reverse DCF: {}", e)))
+            .ok(),
+        (Some(_), None) => {
+            notes.push("Reverse DCF: no price history for this symbol".to_string());
+            None
+        }
+        (None, _) => None,
+    };
+    let graham_number = match (profile.ratios.ttm_eps, profile.ratios.book_value_per_share) {
+        (Some(eps), Some(book)) => valuation::graham_number(eps, book).or_else(|| {
+            notes.push("Graham number: needs positive earnings and book value".to_string());
+            None
+        }),
+        _ => {
+            notes.push("Graham number: needs EPS and book value per share".to_string());
+            None
+        }
+    };
+    let ddm = match valuation::ddm(&assumptions.ddm) {
+        Ok(result) => {
+            sensitivity.push(valuation::ddm_sensitivity(&assumptions.ddm));
+            Some(result)
+        }
+        Err(e) => {
+            notes.push(format!("Dividend discount: {}", e));
+            None
+        }
+    };
+
+    Ok(Json(ValuationReport {
+        price: profile.price,
+        dcf,
+        reverse_dcf,
+        graham_number,
+        ddm,
+        sensitivity,
+        notes,
+    }))
+}
+
+async fn list_scenarios(
+    State(state): State<AppState>,
+    Path(symbol): Path<String>,
+) -> ApiResult<Vec<Scenario>> {
+    Ok(Json(state.valuations.read().unwrap().scenarios(&symbol)))
+}
+
+async fn save_scenario(
+    State(state): State<AppState>,
+    Path(symbol): Path<String>,
+    Json(mut scenario): Json<Scenario>,
+) -> ApiResult<Scenario> {
+    scenario.name = scenario.name.trim().to_string();
+    if scenario.name.is_empty() {
+        return Err(ApiError::BadRequest("scenario needs a name".to_string()));
+    }
+    scenario.symbol = symbol;
+    scenario.saved_at = Some(now_ist());
+    state
+        .valuations
+        .write()
+        .unwrap()
+        .save_scenario(scenario)
+        .map(Json)
+        .map_err(ApiError::Internal)
+}
+
+async fn delete_scenario(
+    State(state): State<AppState>,
+    Path(id): Path<String>,
+) -> ApiResult<String> {
+    let removed = state
+        .valuations
+        .write()
+        .unwrap()
+        .remove(&id)
+        .map_err(ApiError::Internal)?;
+    if !removed {
+        return Err(ApiError::NotFound(format!("no scenario '{}'", id)));
+    }
+    Ok(Json(id))
+}
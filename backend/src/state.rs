@@ -0,0 +1,122 @@
+use std::path::PathBuf;
+use std::sync::{Arc, RwLock};
+
+use crate::models::attachment::AttachmentStore;
+use crate::models::calendar::TradingCalendar;
+use crate::models::charges::RateTable;
+use crate::models::corporate_action::CorporateActionStore;
+use crate::models::data_quality::DataQualityStore;
+use crate::models::fundamentals::FundamentalsStore;
+use crate::models::instrument::InstrumentRegistry;
+use crate::models::journal::JournalStore;
+use crate::models::market::MarketStore;
+use crate::models::option_chain::OptionChainStore;
+use crate::models::portfolio::PortfolioStore;
+use crate::models::risk_profile::RiskProfileStore;
+use crate::models::rules::RulesStore;
+use crate::models::screener::ScreenerStore;
+use crate::models::valuation::ValuationStore;
+
+/// Shared handles to the in-memory stores, cloned into every handler.
+#[derive(Clone)]
+pub struct AppState {
+    pub attachments: Arc<RwLock<AttachmentStore>>,
+    pub calendar: Arc<RwLock<TradingCalendar>>,
+    pub charges: Arc<RwLock<RateTable>>,
+    pub corporate_actions: Arc<RwLock<CorporateActionStore>>,
+    pub data_quality: Arc<RwLock<DataQualityStore>>,
+    pub fundamentals: Arc<RwLock<FundamentalsStore>>,
+    pub instruments: Arc<RwLock<InstrumentRegistry>>,
+    pub journal: Arc<RwLock<JournalStore>>,
+    pub market: Arc<RwLock<MarketStore>>,
+    pub option_chains: Arc<RwLock<OptionChainStore>>,
+    pub portfolio: Arc<RwLock<PortfolioStore>>,
+    pub risk_profile: Arc<RwLock<RiskProfileStore>>,
+    pub rules: Arc<RwLock<RulesStore>>,
+    pub screener: Arc<RwLock<ScreenerStore>>,
+    pub valuations: Arc<RwLock<ValuationStore>>,
+    pub data_dir: PathBuf,
+}
+
+impl AppState {
+    pub fn load(data_dir: PathBuf) -> Self {
+        let instruments = match InstrumentRegistry::load_dump(&data_dir.join("instruments.csv")) {
+            Ok(registry) => registry,
+            Err(e) => {
+                log::warn!("Instrument dump not loaded ({}), using defaults", e);
+                InstrumentRegistry::with_defaults()
+            }
+        };
+
+        let calendar = match TradingCalendar::load(&data_dir.join("holidays.csv")) {
+            Ok(calendar) => calendar,
+            Err(e) => {
+                log::warn!("Holiday file not loaded ({}), using built-in calendar", e);
+                TradingCalendar::with_defaults()
+            }
+        };
+
+        let charges = match RateTable::load(&data_dir.join("charges.json")) {
+            Ok(table) => table,
+            Err(e) => {
+                log::warn!("Charge rates not loaded ({}), using default schedule", e);
+                RateTable::with_defaults()
+            }
+        };
+
+        let market = match MarketStore::load_dir(&data_dir.join("ohlcv")) {
+            Ok(market) => market,
+            Err(e) => {
+                log::warn!("OHLCV history not loaded ({}), starting empty", e);
+                MarketStore::default()
+            }
+        };
+
+        let mut corporate_actions =
+            match CorporateActionStore::load(&data_dir.join("corporate_actions.csv")) {
+                Ok(actions) => actions,
+                Err(e) => {
+                    log::warn!("Corporate actions not loaded ({}), starting empty", e);
+                    CorporateActionStore::default()
+                }
+            };
+        corporate_actions.load_added(&data_dir.join("corporate_actions.json"));
+
+        Self {
+            attachments: Arc::new(RwLock::new(AttachmentStore::load(
+                &data_dir.join("attachments"),
+            ))),
+            calendar: Arc::new(RwLock::new(calendar)),
+            charges: Arc::new(RwLock::new(charges)),
+            corporate_actions: Arc::new(RwLock::new(corporate_actions)),
+            data_quality: Arc::new(RwLock::new(DataQualityStore::load(
+                &data_dir.join("data_quality.json"),
+            ))),
+            fundamentals: Arc::new(RwLock::new(FundamentalsStore::load(
+                &data_dir.join("fundamentals.json"),
+            ))),
+            instruments: Arc::new(RwLock::new(instruments)),
+            journal: Arc::new(RwLock::new(JournalStore::load(
+                &data_dir.join("journal.json"),
+            ))),
+            market: Arc::new(RwLock::new(market)),
+            option_chains: Arc::new(RwLock::new(OptionChainStore::load(
+                &data_dir.join("option_chains.json"),
+            ))),
+            portfolio: Arc::new(RwLock::new(PortfolioStore::load(
+                &data_dir.join("portfolio.json"),
+            ))),
+            risk_profile: Arc::new(RwLock::new(RiskProfileStore::load(
+                &data_dir.join("risk_profile.json"),
+            ))),
+            rules: Arc::new(RwLock::new(RulesStore::load(&data_dir.join("rules.json")))),
+            screener: Arc::new(RwLock::new(ScreenerStore::load(
+                &data_dir.join("screens.json"),
+            ))),
+            valuations: Arc::new(RwLock::new(ValuationStore::load(
+                &data_dir.join("valuations.json"),
+            ))),
+            data_dir,
+        }
+    }
+}
@@ -0,0 +1,148 @@
+use std::fmt::Write;
+
+use crate::models::journal::{Execution, Side};
+use crate::models::market::Bar;
+
+const UP: &str = "#16a34a";
+const DOWN: &str = "#dc2626";
+const BUY: &str = "#2563eb";
+const SELL: &str = "#ea580c";
+
+fn escape(text: &str) -> String {
+    text.replace('&', "&amp;")
+        .replace('<', "&lt;")
+        .replace('>', "&gt;")
+}
+
+/// Candlestick chart of `bars` as a standalone SVG with a marker at every
+/// fill: an upward arrow at the price for buys, downward for sells. Charts
+/// narrower than 480px are drawn bare, without title or axis labels, for
+/// use as thumbnails.
+pub fn trade_chart_svg(
+    title: &str,
+    bars: &[Bar],
+    executions: &[&Execution],
+    daily: bool,
+    width: f64,
+    height: f64,
+) -> String {
+    let labels = width >= 480.0;
+    let (top, bottom, left, right) = if labels {
+        (28.0, 22.0, 8.0, 64.0)
+    } else {
+        (4.0, 4.0, 4.0, 4.0)
+    };
+    let plot_width = width - left - right;
+    let plot_height = height - top - bottom;
+
+    let prices = bars
+        .iter()
+        .flat_map(|b| [b.low, b.high])
+        .chain(executions.iter().map(|e| e.price));
+    let (low, high) = prices.fold((f64::MAX, f64::MIN), |(lo, hi), p| (lo.min(p), hi.max(p)));
+    let pad = ((high - low) * 0.05).max(high.abs() * 0.001).max(0.01);
+    let (low, high) = (low - pad, high + pad);
+    let y = |price: f64| top + (high - price) / (high - low) * plot_height;
+
+    let slot = plot_width / bars.len().max(1) as f64;
+    let x = |index: usize| left + slot * (index as f64 + 0.5);
+
+    let mut svg = String::new();
+    let _ = write!(
+        svg,
+        r#"<svg xmlns="http://www.w3.org/2000/svg" width="{w}" height="{h}" viewBox="0 0 {w} {h}" font-family="sans-serif">"#,
+        w = width,
+        h = height
+    );
+    let _ = write!(
+        svg,
+        r##"<rect width="{}" height="{}" fill="#ffffff"/>"##,
+        width, height
+    );
+
+    if labels {
+        for step in 0..=4 {
+            let price = low + (high - low) * step as f64 / 4.0;
+            let _ = write!(
+                svg,
+                r##"<line x1="{x1}" x2="{x2}" y1="{y:.1}" y2="{y:.1}" stroke="#e5e7eb"/><text x="{tx}" y="{ty:.1}" font-size="10" fill="#6b7280">{price:.2}</text>"##,
+                x1 = left,
+                x2 = left + plot_width,
+                y = y(price),
+                tx = left + plot_width + 6.0,
+                ty = y(price) + 3.0,
+                price = price
+            );
+        }
+        let _ = write!(
+            svg,
+            r##"<text x="{}" y="18" font-size="13" font-weight="bold" fill="#111827">{}</text>"##,
+            left,
+            escape(title)
+        );
+        let format = if daily { "%d %b %Y" } else { "%d %b %H:%M" };
+        if let (Some(first), Some(last)) = (bars.first(), bars.last()) {
+            let _ = write!(
+                svg,
+                r##"<text x="{}" y="{}" font-size="10" fill="#6b7280">{}</text><text x="{}" y="{}" font-size="10" fill="#6b7280" text-anchor="end">{}</text>"##,
+                left,
+                height - 6.0,
+                first.time.format(format),
+                left + plot_width,
+                height - 6.0,
+                last.time.format(format)
+            );
+        }
+    }
+
+    let body = (slot * 0.7).max(1.0);
+    for (index, bar) in bars.iter().enumerate() {
+        let color = if bar.close >= bar.open { UP } else { DOWN };
+        let (open, close) = (y(bar.open), y(bar.close));
+        let _ = write!(
+            svg,
+            r#"<line x1="{cx:.1}" x2="{cx:.1}" y1="{hi:.1}" y2="{lo:.1}" stroke="{c}"/><rect x="{bx:.1}" y="{by:.1}" width="{bw:.1}" height="{bh:.1}" fill="{c}"/>"#,
+            cx = x(index),
+            hi = y(bar.high),
+            lo = y(bar.low),
+            c = color,
+            bx = x(index) - body / 2.0,
+            by = open.min(close),
+            bw = body,
+            bh = (open - close).abs().max(1.0)
+        );
+    }
+
+    // Each fill sits on the last bar that started at or before it
+    let size = (height / 60.0).clamp(3.0, 7.0);
+    for execution in executions {
+        let index = bars
+            .iter()
+            .rposition(|bar| {
+                if daily {
+                    bar.time.date() <= execution.time.date()
+                } else {
+                    bar.time <= execution.time
+                }
+            })
+            .unwrap_or(0);
+        let (cx, cy) = (x(index), y(execution.price));
+        let (color, tip) = match execution.side {
+            Side::Buy => (BUY, size * 1.8),
+            Side::Sell => (SELL, -size * 1.8),
+        };
+        let _ = write!(
+            svg,
+            r##"<path d="M{cx:.1},{cy:.1} L{l:.1},{by:.1} L{r:.1},{by:.1} Z" fill="{c}" stroke="#ffffff" stroke-width="0.5"/>"##,
+            cx = cx,
+            cy = cy,
+            l = cx - size,
+            r = cx + size,
+            by = cy + tip,
+            c = color
+        );
+    }
+
+    svg.push_str("</svg>");
+    svg
+}
@@ -0,0 +1,122 @@
+/// Minimal CSV reader for the dump and export files we ingest.
+///
+/// Handles quoted fields, escaped quotes (`""`) and CRLF line endings.
+/// Blank lines are skipped.
+pub fn parse(text: &str) -> Vec<Vec<String>> {
+    let mut rows = Vec::new();
+    let mut row = Vec::new();
+    let mut field = String::new();
+    let mut in_quotes = false;
+    let mut chars = text.chars().peekable();
+
+    while let Some(c) = chars.next() {
+        match c {
+            '"' if in_quotes => {
+                if chars.peek() == Some(&'"') {
+                    field.push('"');
+                    chars.next();
+                } else {
+                    in_quotes = false;
+                }
+            }
+            '"' if field.is_empty() => in_quotes = true,
+            ',' if !in_quotes => row.push(std::mem::take(&mut field)),
+            '\r' if !in_quotes => {}
+            '\n' if !in_quotes => {
+                row.push(std::mem::take(&mut field));
+                if row.iter().any(|f| !f.is_empty()) {
+                    rows.push(std::mem::take(&mut row));
+                } else {
+                    row.clear();
+                }
+            }
+            _ => field.push(c),
+        }
+    }
+
+    if !field.is_empty() || !row.is_empty() {
+        row.push(field);
+        rows.push(row);
+    }
+
+    rows
+}
+
+/// Join fields into one CSV line, quoting any that need it.
+pub fn row<S: AsRef<str>>(fields: &[S]) -> String {
+    fields
+        .iter()
+        .map(|f| {
+            let f = f.as_ref();
+            if f.contains([',', '"', '\n', '\r']) {
+                format!("\"{}\"", f.replace('"', "\"\""))
+            } else {
+                f.to_string()
+            }
+        })
+        .collect::<Vec<_>>()
+        .join(",")
+}
+
+/// Header-aware view over parsed CSV rows. Column lookups are
+/// case-insensitive and ignore surrounding whitespace.
+pub struct Table {
+    pub headers: Vec<String>,
+    pub rows: Vec<Vec<String>>,
+}
+
+impl Table {
+    pub fn parse(text: &str) -> Self {
+        let mut rows = parse(text);
+        let headers = if rows.is_empty() {
+            Vec::new()
+        } else {
+            rows.remove(0)
+                .into_iter()
+                .map(|h| h.trim().to_lowercase())
+                .collect()
+        };
+
+        Self { headers, rows }
+    }
+
+    pub fn column(&self, names: &[&str]) -> Option<usize> {
+        names
+            .iter()
+            .find_map(|name| self.headers.iter().position(|h| h == name))
+    }
+
+    pub fn get<'a>(&self, row: &'a [String], column: Option<usize>) -> Option<&'a str> {
+        column
+            .and_then(|idx| row.get(idx))
+            .map(|value| value.trim())
+            .filter(|value| !value.is_empty())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parses_quotes_and_line_endings() {
+        let rows = parse("a,\"b, c\",\"say \"\"hi\"\"\"\r\n\r\n1,2,3");
+        assert_eq!(rows, [vec!["a", "b, c", "say \"hi\""], vec!["1", "2", "3"]]);
+    }
+
+    #[test]
+    fn row_quotes_fields_that_need_it() {
+        let line = row(&["plain", "a,b", "say \"hi\""]);
+        assert_eq!(line, "plain,\"a,b\",\"say \"\"hi\"\"\"");
+        assert_eq!(parse(&line)[0], ["plain", "a,b", "say \"hi\""]);
+    }
+
+    #[test]
+    fn table_looks_up_columns_case_insensitively() {
+        let table = Table::parse(" Symbol ,Close\nINFY,  \n");
+        let close = table.column(&["price", "close"]);
+        assert_eq!(table.column(&["symbol"]), Some(0));
+        assert_eq!(table.get(&table.rows[0], Some(0)), Some("INFY"));
+        assert_eq!(table.get(&table.rows[0], close), None);
+    }
+}
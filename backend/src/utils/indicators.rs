@@ -0,0 +1,100 @@
+//! Indicator math over close series, oldest value first.
+
+pub fn mean(values: &[f64]) -> Option<f64> {
+    (!values.is_empty()).then(|| values.iter().sum::<f64>() / values.len() as f64)
+}
+
+/// Sample standard deviation.
+pub fn std_dev(values: &[f64]) -> Option<f64> {
+    if values.len() < 2 {
+        return None;
+    }
+    let avg = mean(values)?;
+    let variance =
+        values.iter().map(|v| (v - avg).powi(2)).sum::<f64>() / (values.len() - 1) as f64;
+    Some(variance.sqrt())
+}
+
+/// Sample covariance of two equally long series.
+pub fn covariance(a: &[f64], b: &[f64]) -> Option<f64> {
+    if a.len() != b.len() || a.len() < 2 {
+        return None;
+    }
+    let (mean_a, mean_b) = (mean(a)?, mean(b)?);
+    let sum: f64 = a
+        .iter()
+        .zip(b)
+        .map(|(x, y)| (x - mean_a) * (y - mean_b))
+        .sum();
+    Some(sum / (a.len() - 1) as f64)
+}
+
+/// Simple moving average of the last `period` values.
+pub fn sma(values: &[f64], period: usize) -> Option<f64> {
+    if period == 0 || values.len() < period {
+        return None;
+    }
+    mean(&values[values.len() - period..])
+}
+
+/// Exponential moving average series, seeded with the SMA of the first
+/// `period` values. Output index `i` lines up with input `period - 1 + i`.
+pub fn ema_series(values: &[f64], period: usize) -> Vec<f64> {
+    if period == 0 || values.len() < period {
+        return Vec::new();
+    }
+    let alpha = 2.0 / (period as f64 + 1.0);
+    let mut current = values[..period].iter().sum::<f64>() / period as f64;
+    let mut out = vec![current];
+    for value in &values[period..] {
+        current = alpha * value + (1.0 - alpha) * current;
+        out.push(current);
+    }
+    out
+}
+
+/// Wilder's RSI of the latest value.
+pub fn rsi(values: &[f64], period: usize) -> Option<f64> {
+    if period == 0 || values.len() <= period {
+        return None;
+    }
+    let changes: Vec<f64> = values.windows(2).map(|w| w[1] - w[0]).collect();
+    let mut gain = changes[..period].iter().filter(|c| **c > 0.0).sum::<f64>() / period as f64;
+    let mut loss = -changes[..period].iter().filter(|c| **c < 0.0).sum::<f64>() / period as f64;
+    for change in &changes[period..] {
+        gain = (gain * (period - 1) as f64 + change.max(0.0)) / period as f64;
+        loss = (loss * (period - 1) as f64 + (-change).max(0.0)) / period as f64;
+    }
+    if loss == 0.0 {
+        return Some(100.0);
+    }
+    Some(100.0 - 100.0 / (1.0 + gain / loss))
+}
+
+pub struct Macd {
+    pub macd: f64,
+    pub signal: f64,
+    pub histogram: f64,
+}
+
+/// MACD(12, 26, 9) of the latest value.
+pub fn macd(values: &[f64]) -> Option<Macd> {
+    let fast = ema_series(values, 12);
+    let slow = ema_series(values, 26);
+    if slow.is_empty() {
+        return None;
+    }
+    // Align the fast EMA with the slow one, which starts 14 values later
+    let line: Vec<f64> = slow
+        .iter()
+        .zip(&fast[fast.len() - slow.len()..])
+        .map(|(slow, fast)| fast - slow)
+        .collect();
+    let signal = *ema_series(&line, 9).last()?;
+    let macd = *line.last()?;
+    Some(Macd {
+        macd,
+        signal,
+        histogram: macd - signal,
+    })
+}
@@ -0,0 +1,8 @@
+pub mod api;
+pub mod chart;
+pub mod csv;
+pub mod format;
+pub mod indicators;
+pub mod pdf;
+pub mod persist;
+pub mod spreadsheet;
@@ -0,0 +1,140 @@
+/// Minimal text-only PDF writer for reports: monospaced lines on A4
+/// landscape pages, paginated automatically. Only Latin-1 text renders;
+/// other characters are replaced with `?`.
+pub struct TextPdf {
+    title: String,
+    lines: Vec<String>,
+}
+
+const PAGE_WIDTH: f64 = 842.0;
+const PAGE_HEIGHT: f64 = 595.0;
+const MARGIN: f64 = 36.0;
+const FONT_SIZE: f64 = 8.0;
+const LEADING: f64 = 10.0;
+/// Characters of Courier at `FONT_SIZE` that fit between the margins.
+const LINE_WIDTH: usize = 160;
+
+fn escape(text: &str) -> String {
+    text.chars()
+        .map(|c| match c {
+            '(' | ')' | '\\' => format!("\\{}", c),
+            '₹' => "Rs ".to_string(),
+            c if (c as u32) < 256 && !c.is_control() => c.to_string(),
+            _ => "?".to_string(),
+        })
+        .collect()
+}
+
+impl TextPdf {
+    pub fn new(title: &str) -> Self {
+        Self {
+            title: title.to_string(),
+            lines: Vec::new(),
+        }
+    }
+
+    /// Add a line, wrapping at word boundaries if it is too wide.
+    pub fn line(&mut self, text: impl Into<String>) {
+        let text = text.into();
+        if text.chars().count() <= LINE_WIDTH {
+            self.lines.push(text);
+            return;
+        }
+        let mut current = String::new();
+        for word in text.split(' ') {
+            if !current.is_empty() && current.chars().count() + word.chars().count() >= LINE_WIDTH {
+                self.lines.push(std::mem::take(&mut current));
+            }
+            if !current.is_empty() {
+                current.push(' ');
+            }
+            current.push_str(word);
+        }
+        self.lines.push(current);
+    }
+
+    pub fn blank(&mut self) {
+        self.lines.push(String::new());
+    }
+
+    pub fn render(&self) -> Vec<u8> {
+        let per_page = ((PAGE_HEIGHT - 2.0 * MARGIN) / LEADING) as usize - 2;
+        let pages: Vec<&[String]> = if self.lines.is_empty() {
+            vec![&[]]
+        } else {
+            self.lines.chunks(per_page).collect()
+        };
+
+        // Objects: 1 catalog, 2 page tree, 3 font, then a page and its
+        // content stream for every page
+        let mut objects: Vec<String> = vec![
+            "<< /Type /Catalog /Pages 2 0 R >>".to_string(),
+            String::new(),
+            "<< /Type /Font /Subtype /Type1 /BaseFont /Courier /Encoding /WinAnsiEncoding >>"
+                .to_string(),
+        ];
+        let mut kids = Vec::new();
+        for (index, lines) in pages.iter().enumerate() {
+            let page_id = objects.len() + 1;
+            let content_id = page_id + 1;
+            kids.push(format!("{} 0 R", page_id));
+
+            let mut stream = format!(
+                "BT /F1 {} Tf {} TL {} {} Td ",
+                FONT_SIZE,
+                LEADING,
+                MARGIN,
+                PAGE_HEIGHT - MARGIN
+            );
+            stream.push_str(&format!(
+                "({}    page {} of {}) Tj T* T* ",
+                escape(&self.title),
+                index + 1,
+                pages.len()
+            ));
+            for line in lines.iter() {
+                stream.push_str(&format!("({}) Tj T* ", escape(line)));
+            }
+            stream.push_str("ET");
+
+            objects.push(format!(
+                "<< /Type /Page /Parent 2 0 R /MediaBox [0 0 {} {}] /Resources << /Font << /F1 3 0 R >> >> /Contents {} 0 R >>",
+                PAGE_WIDTH, PAGE_HEIGHT, content_id
+            ));
+            objects.push(format!(
+                "<< /Length {} >>\nstream\n{}\nendstream",
+                stream.chars().count(),
+                stream
+            ));
+        }
+        objects[1] = format!(
+            "<< /Type /Pages /Kids [{}] /Count {} >>",
+            kids.join(" "),
+            kids.len()
+        );
+
+        // Latin-1 text is written byte for byte, as WinAnsi expects
+        let mut out: Vec<u8> = b"%PDF-1.4\n".to_vec();
+        let mut offsets = Vec::new();
+        for (index, object) in objects.iter().enumerate() {
+            offsets.push(out.len());
+            out.extend(format!("{} 0 obj\n", index + 1).bytes());
+            out.extend(object.chars().map(|c| c as u32 as u8));
+            out.extend(b"\nendobj\n");
+        }
+        let xref = out.len();
+        out.extend(format!("xref\n0 {}\n0000000000 65535 f \n", objects.len() + 1).bytes());
+        for offset in offsets {
+            out.extend(format!("{:010} 00000 n \n", offset).bytes());
+        }
+        out.extend(
+            format!(
+                "trailer\n<< /Size {} /Root 1 0 R >>\nstartxref\n{}\n%%EOF\n",
+                objects.len() + 1,
+                xref
+            )
+            .bytes(),
+        );
+        out
+    }
+}
@@ -0,0 +1,153 @@
+use std::io::Write;
+use std::path::{Path, PathBuf};
+
+use chrono::Utc;
+use serde::de::DeserializeOwned;
+use serde::Serialize;
+
+/// Read a store's JSON file, or start from the default when there is none
+/// yet. A file that doesn't parse is renamed to `<name>.corrupt-<time>`
+/// first, so the store's next save can't overwrite data that may still be
+/// recoverable by hand. If it can't be moved aside, loading refuses to go
+/// on rather than risk that.
+pub fn load_json<T: DeserializeOwned + Default>(path: &Path) -> T {
+    let text = match std::fs::read_to_string(path) {
+        Ok(text) => text,
+        Err(e) if e.kind() == std::io::ErrorKind::NotFound => return T::default(),
+        Err(e) => panic!("failed to read {}: {}", path.display(), e),
+    };
+    match serde_json::from_str(&text) {
+        Ok(data) => data,
+        Err(e) => {
+            let aside = aside_path(path);
+            if let Err(rename) = std::fs::rename(path, &aside) {
+                panic!(
+                    "failed to parse {} ({}) and to move it aside ({}); fix or remove it",
+                    path.display(),
+                    e,
+                    rename
+                );
+            }
+            log::error!(
+                "Failed to parse {}: {}; moved it to {} and started empty",
+                path.display(),
+                e,
+                aside.display()
+            );
+            T::default()
+        }
+    }
+}
+
+fn aside_path(path: &Path) -> PathBuf {
+    let name = path
+        .file_name()
+        .map_or_else(String::new, |n| n.to_string_lossy().into_owned());
+    path.with_file_name(format!(
+        "{}.corrupt-{}",
+        name,
+        Utc::now().format("%Y%m%d%H%M%S")
+    ))
+}
+
+/// Write `value` as pretty JSON with [`write_atomic`].
+pub fn save_json<T: Serialize>(path: &Path, value: &T) -> Result<(), String> {
+    let text = serde_json::to_string_pretty(value).map_err(|e| e.to_string())?;
+    write_atomic(path, text.as_bytes())
+}
+
+/// Write `value` as compact JSON with [`write_atomic`], for stores large
+/// enough that pretty printing would noticeably grow the file and the
+/// time each save takes.
+pub fn save_json_compact<T: Serialize>(path: &Path, value: &T) -> Result<(), String> {
+    let text = serde_json::to_string(value).map_err(|e| e.to_string())?;
+    write_atomic(path, text.as_bytes())
+}
+
+/// Write to a temporary file beside `path` and rename it into place, so
+/// a crash or a full disk mid-write leaves the previous file whole.
+pub fn write_atomic(path: &Path, bytes: &[u8]) -> Result<(), String> {
+    if let Some(dir) = path.parent() {
+        std::fs::create_dir_all(dir).map_err(|e| e.to_string())?;
+    }
+    let name = path
+        .file_name()
+        .ok_or_else(|| format!("{} is not a file path", path.display()))?;
+    let temp = path.with_file_name(format!("{}.tmp", name.to_string_lossy()));
+    let write = || -> std::io::Result<()> {
+        let mut file = std::fs::File::create(&temp)?;
+        file.write_all(bytes)?;
+        file.sync_all()?;
+        std::fs::rename(&temp, path)
+    };
+    write().map_err(|e| {
+        let _ = std::fs::remove_file(&temp);
+        format!("failed to write {}: {}", path.display(), e)
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[derive(Default, Serialize, serde::Deserialize)]
+    struct File {
+        values: Vec<u32>,
+    }
+
+    fn temp_dir(name: &str) -> PathBuf {
+        let dir = std::env::temp_dir().join(format!("slynqix-{}-{}", name, std::process::id()));
+        let _ = std::fs::remove_dir_all(&dir);
+        std::fs::create_dir_all(&dir).unwrap();
+        dir
+    }
+
+    #[test]
+    fn saves_and_loads() {
+        let dir = temp_dir("persist-round-trip");
+        let path = dir.join("nested").join("store.json");
+        save_json(&path, &File { values: vec![1, 2] }).unwrap();
+
+        assert_eq!(load_json::<File>(&path).values, [1, 2]);
+        assert!(!dir.join("nested").join("store.json.tmp").exists());
+        std::fs::remove_dir_all(&dir).unwrap();
+    }
+
+    #[test]
+    fn compact_saves_load_the_same() {
+        let dir = temp_dir("persist-compact");
+        let path = dir.join("store.json");
+        save_json_compact(&path, &File { values: vec![1, 2] }).unwrap();
+
+        assert_eq!(
+            std::fs::read_to_string(&path).unwrap(),
+            "{\"values\":[1,2]}"
+        );
+        assert_eq!(load_json::<File>(&path).values, [1, 2]);
+        std::fs::remove_dir_all(&dir).unwrap();
+    }
+
+    #[test]
+    fn missing_file_loads_the_default() {
+        let dir = temp_dir("persist-missing");
+        assert!(load_json::<File>(&dir.join("store.json")).values.is_empty());
+        std::fs::remove_dir_all(&dir).unwrap();
+    }
+
+    #[test]
+    fn moves_a_corrupt_file_aside() {
+        let dir = temp_dir("persist-corrupt");
+        let path = dir.join("store.json");
+        std::fs::write(&path, "{\"values\": [1,").unwrap();
+
+        assert!(load_json::<File>(&path).values.is_empty());
+        assert!(!path.exists());
+        let aside: Vec<_> = std::fs::read_dir(&dir).unwrap().flatten().collect();
+        assert_eq!(aside.len(), 1);
+        assert_eq!(
+            std::fs::read_to_string(aside[0].path()).unwrap(),
+            "{\"values\": [1,"
+        );
+        std::fs::remove_dir_all(&dir).unwrap();
+    }
+}
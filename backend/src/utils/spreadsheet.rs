@@ -0,0 +1,294 @@
+/// Reader and writer for Excel 2003 XML spreadsheets (SpreadsheetML), the
+/// open plain-XML workbook format Excel and LibreOffice both open and save.
+/// Every cell is written as a string; reading keeps cell text as is.
+pub struct Sheet {
+    pub name: String,
+    pub rows: Vec<Vec<String>>,
+}
+
+/// Columns and rows of an Excel 2003 worksheet; an `ss:Index` past them
+/// is corrupt.
+const MAX_COLUMNS: usize = 256;
+const MAX_ROWS: usize = 65_536;
+/// Empty cells and rows that `ss:Index` may skip over in one workbook.
+const MAX_SKIPPED: usize = 1_000_000;
+
+fn escape(text: &str) -> String {
+    let mut out = String::with_capacity(text.len());
+    for c in text.chars() {
+        match c {
+            '&' => out.push_str("&amp;"),
+            '<' => out.push_str("&lt;"),
+            '>' => out.push_str("&gt;"),
+            '"' => out.push_str("&quot;"),
+            '\n' => out.push_str("&#10;"),
+            '\r' => {}
+            c if c.is_control() && c != '\t' => {}
+            c => out.push(c),
+        }
+    }
+    out
+}
+
+fn unescape(text: &str) -> String {
+    let mut out = String::with_capacity(text.len());
+    let mut rest = text;
+    while let Some(start) = rest.find('&') {
+        out.push_str(&rest[..start]);
+        rest = &rest[start..];
+        let Some(end) = rest.find(';') else {
+            break;
+        };
+        let entity = &rest[1..end];
+        let decoded = match entity {
+            "amp" => Some('&'),
+            "lt" => Some('<'),
+            "gt" => Some('>'),
+            "quot" => Some('"'),
+            "apos" => Some('\''),
+            _ => entity
+                .strip_prefix("#x")
+                .and_then(|hex| u32::from_str_radix(hex, 16).ok())
+                .or_else(|| entity.strip_prefix('#').and_then(|d| d.parse().ok()))
+                .and_then(char::from_u32),
+        };
+        match decoded {
+            Some(c) => {
+                out.push(c);
+                rest = &rest[end + 1..];
+            }
+            None => {
+                out.push('&');
+                rest = &rest[1..];
+            }
+        }
+    }
+    out.push_str(rest);
+    out
+}
+
+pub fn write(sheets: &[Sheet]) -> String {
+    let mut out = String::from(
+        "<?xml version=\"1.0\" encoding=\"UTF-8\"?>\n\
+         <?mso-application progid=\"Excel.Sheet\"?>\n\
+         <Workbook xmlns=\"urn:schemas-microsoft-com:office:spreadsheet\" \
+         xmlns:ss=\"urn:schemas-microsoft-com:office:spreadsheet\">\n",
+    );
+    for sheet in sheets {
+        out.push_str(&format!(
+            " <Worksheet ss:Name=\"{}\">\n  <Table>\n",
+            escape(&sheet.name)
+        ));
+        for row in &sheet.rows {
+            out.push_str("   <Row>");
+            for cell in row {
+                out.push_str(&format!(
+                    "<Cell><Data ss:Type=\"String\">{}</Data></Cell>",
+                    escape(cell)
+                ));
+            }
+            out.push_str("</Row>\n");
+        }
+        out.push_str("  </Table>\n </Worksheet>\n");
+    }
+    out.push_str("</Workbook>\n");
+    out
+}
+
+/// Tag name without any namespace prefix, and its attributes.
+fn split_tag(tag: &str) -> (&str, &str) {
+    let tag = tag.trim_end_matches('/').trim();
+    let (name, attributes) = tag.split_once(char::is_whitespace).unwrap_or((tag, ""));
+    let name = name.rsplit(':').next().unwrap_or(name);
+    (name, attributes)
+}
+
+/// Value of an attribute such as `ss:Name`, matched on its local name.
+fn attribute(attributes: &str, local: &str) -> Option<String> {
+    let mut rest = attributes;
+    while let Some(eq) = rest.find('=') {
+        let key = rest[..eq].trim();
+        let key = key.rsplit(':').next().unwrap_or(key);
+        let value = rest[eq + 1..].trim_start();
+        let quote = value.chars().next()?;
+        let value = value.strip_prefix(quote)?;
+        let close = value.find(quote)?;
+        if key == local {
+            return Some(unescape(&value[..close]));
+        }
+        rest = &value[close + 1..];
+    }
+    None
+}
+
+/// Pad `items` with blanks up to the element's `ss:Index`, if it has one.
+fn skip_to<T: Default>(
+    items: &mut Vec<T>,
+    attributes: &str,
+    limit: usize,
+    skipped: &mut usize,
+) -> Result<(), String> {
+    let Some(index) = attribute(attributes, "Index").and_then(|i| i.parse::<usize>().ok()) else {
+        return Ok(());
+    };
+    if index > limit {
+        return Err(format!("ss:Index {} is past the limit of {}", index, limit));
+    }
+    let missing = index.saturating_sub(items.len() + 1);
+    *skipped += missing;
+    if *skipped > MAX_SKIPPED {
+        return Err("spreadsheet skips too many empty cells".to_string());
+    }
+    items.resize_with(items.len() + missing, T::default);
+    Ok(())
+}
+
+/// Read every worksheet. Cells skipped with `ss:Index`, as Excel does for
+/// empty cells, come back as empty strings; formatting is ignored.
+pub fn read(text: &str) -> Result<Vec<Sheet>, String> {
+    if !text.contains("urn:schemas-microsoft-com:office:spreadsheet") {
+        return Err("not an Excel 2003 XML spreadsheet".to_string());
+    }
+
+    let mut sheets: Vec<Sheet> = Vec::new();
+    let mut row: Option<Vec<String>> = None;
+    let mut cell: Option<String> = None;
+    let mut in_data = false;
+    let mut skipped = 0;
+    let mut rest = text;
+
+    while let Some(open) = rest.find('<') {
+        if in_data {
+            if let Some(cell) = cell.as_mut() {
+                cell.push_str(&unescape(&rest[..open]));
+            }
+        }
+        rest = &rest[open..];
+        if let Some(comment) = rest.strip_prefix("<!--") {
+            let end = comment.find("-->").ok_or("unterminated comment")?;
+            rest = &comment[end + 3..];
+            continue;
+        }
+        let close = rest.find('>').ok_or("unterminated tag")?;
+        let tag = &rest[1..close];
+        rest = &rest[close + 1..];
+        if tag.starts_with('?') || tag.starts_with('!') {
+            continue;
+        }
+
+        let closing = tag.starts_with('/');
+        let empty = tag.ends_with('/');
+        let (name, attributes) = split_tag(tag.trim_start_matches('/'));
+        match (name, closing) {
+            ("Worksheet", false) => sheets.push(Sheet {
+                name: attribute(attributes, "Name").unwrap_or_default(),
+                rows: Vec::new(),
+            }),
+            ("Row", false) => {
+                // Rows skipped with an index come back empty
+                if let Some(sheet) = sheets.last_mut() {
+                    skip_to(&mut sheet.rows, attributes, MAX_ROWS, &mut skipped)?;
+                }
+                row = Some(Vec::new());
+                if empty {
+                    if let (Some(sheet), Some(row)) = (sheets.last_mut(), row.take()) {
+                        sheet.rows.push(row);
+                    }
+                }
+            }
+            ("Row", true) => {
+                if let (Some(sheet), Some(row)) = (sheets.last_mut(), row.take()) {
+                    sheet.rows.push(row);
+                }
+            }
+            ("Cell", false) => {
+                if let Some(row) = row.as_mut() {
+                    skip_to(row, attributes, MAX_COLUMNS, &mut skipped)?;
+                }
+                if empty {
+                    if let Some(row) = row.as_mut() {
+                        row.push(String::new());
+                    }
+                } else {
+                    cell = Some(String::new());
+                }
+            }
+            ("Cell", true) => {
+                if let (Some(row), Some(cell)) = (row.as_mut(), cell.take()) {
+                    row.push(cell);
+                }
+            }
+            ("Data", false) if !empty => in_data = true,
+            ("Data", true) => in_data = false,
+            _ => {}
+        }
+    }
+
+    if sheets.is_empty() {
+        return Err("spreadsheet has no worksheets".to_string());
+    }
+    Ok(sheets)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn workbook(body: &str) -> String {
+        format!(
+            "<Workbook xmlns=\"urn:schemas-microsoft-com:office:spreadsheet\" \
+             xmlns:ss=\"urn:schemas-microsoft-com:office:spreadsheet\">{}</Workbook>",
+            body
+        )
+    }
+
+    #[test]
+    fn round_trips_written_sheets() {
+        let sheets = [Sheet {
+            name: "Trades & fills".to_string(),
+            rows: vec![
+                vec!["symbol".to_string(), "note".to_string()],
+                vec!["M&M".to_string(), "<gap>\n\"up\"".to_string()],
+            ],
+        }];
+        let read = read(&write(&sheets)).unwrap();
+        assert_eq!(read.len(), 1);
+        assert_eq!(read[0].name, sheets[0].name);
+        assert_eq!(read[0].rows, sheets[0].rows);
+    }
+
+    #[test]
+    fn skipped_cells_and_rows_come_back_empty() {
+        let text = workbook(
+            "<Worksheet ss:Name=\"S\"><Table>\
+             <Row><Cell><Data>a</Data></Cell><Cell ss:Index=\"3\"><Data>c</Data></Cell></Row>\
+             <Row ss:Index=\"3\"><Cell><Data>d</Data></Cell></Row>\
+             </Table></Worksheet>",
+        );
+        let sheets = read(&text).unwrap();
+        assert_eq!(sheets[0].rows, vec![vec!["a", "", "c"], vec![], vec!["d"]]);
+    }
+
+    #[test]
+    fn attribute_values_starting_with_multibyte_characters() {
+        assert_eq!(
+            attribute("ss:Name=\u{20b9}x\u{20b9}", "Name"),
+            Some("x".to_string())
+        );
+        assert_eq!(
+            attribute("ss:Name=\"\u{20b9} P&amp;L\"", "Name"),
+            Some("\u{20b9} P&L".to_string())
+        );
+        assert_eq!(attribute("ss:Name=", "Name"), None);
+    }
+
+    #[test]
+    fn rejects_indexes_past_the_sheet() {
+        let text = workbook("<Worksheet><Table><Row ss:Index=\"4000000000\"/></Table></Worksheet>");
+        assert!(read(&text).is_err());
+        let text = workbook(
+            "<Worksheet><Table><Row><Cell ss:Index=\"100000\"/></Row></Table></Worksheet>",
+        );
+        assert!(read(&text).is_err());
+    }
+}
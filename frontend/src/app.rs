@@ -9,11 +9,15 @@ use crate::pages::console::Console;
 use crate::pages::aftermarket_analyzer::AftermarketAnalyzer;
 use crate::pages::history::History;
 use crate::pages::mindsage::Mindsage;
+use crate::pages::screener::Screener;
+use crate::pages::options::Options;
 use crate::pages::algo_trading::AlgoTrading;
 use crate::pages::global_sentiment::GlobalSentiment;
 use crate::pages::model_trainer::ModelTrainer;
 use crate::pages::journal::Journal;
+use crate::pages::portfolio::Portfolio;
 use crate::pages::profile::Profile;
+use crate::pages::admin_dashboard::AdminDashboard;
 use crate::utils::theme::ThemeProvider;
 
 #[component]
@@ -38,11 +42,15 @@ pub fn App() -> impl IntoView {
                                 <Route path="/aftermarket-analyzer" view=|| view! { <AftermarketAnalyzer /> } />
                                 <Route path="/history" view=|| view! { <History /> } />
                                 <Route path="/mindsage" view=|| view! { <Mindsage /> } />
+                                <Route path="/screener" view=|| view! { <Screener /> } />
+                                <Route path="/options" view=|| view! { <Options /> } />
                                 <Route path="/algo-trading" view=|| view! { <AlgoTrading /> } />
                                 <Route path="/global-sentiment" view=|| view! { <GlobalSentiment /> } />
                                 <Route path="/model-trainer" view=|| view! { <ModelTrainer /> } />
                                 <Route path="/journal" view=|| view! { <Journal /> } />
+                                <Route path="/portfolio" view=|| view! { <Portfolio /> } />
                                 <Route path="/profile" view=|| view! { <Profile /> } />
+                                <Route path="/admin" view=|| view! { <AdminDashboard /> } />
                                 <Route path="/*" view=|| view! { <div>"Not Found"</div> } />
                             </Routes>
                         </main>
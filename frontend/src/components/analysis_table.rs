@@ -1,6 +1,7 @@
 use leptos::*;
+use serde::{Deserialize, Serialize};
 
-#[derive(Clone)]
+#[derive(Clone, Debug, Serialize, Deserialize)]
 pub struct AnalysisRow {
     pub name: String,
     pub value: String,
@@ -0,0 +1,173 @@
+use leptos::*;
+use serde::{Deserialize, Serialize};
+
+use crate::components::journal_form::*;
+use crate::components::trading_rules::*;
+use crate::utils::api::*;
+
+#[derive(Clone, Debug, Default, PartialEq, Serialize, Deserialize)]
+struct Review {
+    date: String,
+    #[serde(default)]
+    checked: Vec<String>,
+    #[serde(default)]
+    pre_market_notes: String,
+    #[serde(default)]
+    post_market_notes: String,
+}
+
+#[derive(Clone, Debug, Serialize, Deserialize)]
+struct DayReview {
+    review: Review,
+    checklist: Vec<ChecklistItem>,
+    trades: usize,
+    violations: Vec<Violation>,
+}
+
+/// Pre- and post-market checklist and notes for one day, shown with the
+/// rules broken that day.
+#[component]
+pub fn DailyReview(
+    #[prop(into)] date: Signal<String>,
+    #[prop(optional, into)] reload: Option<Signal<usize>>,
+) -> impl IntoView {
+    let (day, set_day) = create_signal(None::<DayReview>);
+    let (review, set_review) = create_signal(Review::default());
+    let (message, set_message) = create_signal(None::<String>);
+    let (error, set_error) = create_signal(None::<String>);
+
+    create_effect(move |_| {
+        let date = date.get();
+        if let Some(reload) = reload {
+            reload.track();
+        }
+        set_message.set(None);
+        spawn_local(async move {
+            match get_json::<DayReview>(&format!("/rules/reviews/{}", date)).await {
+                Ok(result) => {
+                    set_review.set(result.review.clone());
+                    set_day.set(Some(result));
+                }
+                Err(e) => set_error.set(Some(e)),
+            }
+        });
+    });
+
+    let save = move |_| {
+        set_error.set(None);
+        let current = review.get();
+        spawn_local(async move {
+            let path = format!("/rules/reviews/{}", current.date);
+            match put_json::<_, DayReview>(&path, &current).await {
+                Ok(result) => {
+                    set_review.set(result.review.clone());
+                    set_day.set(Some(result));
+                    set_message.set(Some("Review saved".to_string()));
+                }
+                Err(e) => set_error.set(Some(e)),
+            }
+        });
+    };
+
+    let toggle = move |id: String| {
+        set_review.update(|r| match r.checked.iter().position(|c| *c == id) {
+            Some(index) => {
+                r.checked.remove(index);
+            }
+            None => r.checked.push(id),
+        });
+    };
+
+    view! {
+        <div class="bg-card text-card-foreground rounded-lg shadow-sm p-6 mt-6">
+            <h3 class="text-lg font-medium mb-4">{move || format!("Daily Review · {}", date.get())}</h3>
+
+            {move || error.get().map(|text| view! {
+                <p class="text-sm text-red-500 mb-3">{text}</p>
+            })}
+
+            {move || day.get().map(|day| {
+                let trades = day.trades;
+                view! {
+                    <div>
+                        <p class="text-sm text-muted-foreground mb-4">
+                            {format!(
+                                "{} trades this day, {} rule breaks",
+                                trades,
+                                day.violations.len()
+                            )}
+                        </p>
+                        {(!day.violations.is_empty()).then(|| view! {
+                            <ul class="text-sm text-red-500 space-y-1 mb-4 list-disc pl-5">
+                                {day.violations.iter().map(|v| view! {
+                                    <li>{format!("{}: {}", v.rule, v.message)}</li>
+                                }).collect::<Vec<_>>()}
+                            </ul>
+                        })}
+                        <div class="grid grid-cols-1 md:grid-cols-2 gap-6">
+                            {CHECKLIST_PHASES.into_iter().map(|(phase, label)| {
+                                let items: Vec<ChecklistItem> = day
+                                    .checklist
+                                    .iter()
+                                    .filter(|item| item.phase == phase)
+                                    .cloned()
+                                    .collect();
+                                let pre = phase == "pre_market";
+                                view! {
+                                    <div>
+                                        <h4 class="font-medium mb-2">{label}</h4>
+                                        <div class="space-y-1 mb-3">
+                                            {items.into_iter().map(|item| {
+                                                let id = item.id.clone();
+                                                let checked = item.id.clone();
+                                                view! {
+                                                    <label class="flex items-center gap-2 text-sm">
+                                                        <input
+                                                            type="checkbox"
+                                                            prop:checked=move || review.get().checked.contains(&checked)
+                                                            on:change=move |_| toggle(id.clone())
+                                                        />
+                                                        {item.text}
+                                                    </label>
+                                                }
+                                            }).collect::<Vec<_>>()}
+                                        </div>
+                                        <textarea
+                                            class="w-full px-3 py-2 border border-input rounded-md text-sm"
+                                            rows="3"
+                                            placeholder=if pre { "Plan for the day" } else { "What went well, what to fix" }
+                                            prop:value=move || {
+                                                let r = review.get();
+                                                if pre { r.pre_market_notes } else { r.post_market_notes }
+                                            }
+                                            on:input=move |ev| {
+                                                let text = event_target_value(&ev);
+                                                set_review.update(|r| if pre {
+                                                    r.pre_market_notes = text;
+                                                } else {
+                                                    r.post_market_notes = text;
+                                                });
+                                            }
+                                        ></textarea>
+                                    </div>
+                                }
+                            }).collect::<Vec<_>>()}
+                        </div>
+                    </div>
+                }
+            })}
+
+            <div class="flex justify-end items-center gap-3 mt-4">
+                {move || message.get().map(|text| view! {
+                    <span class="text-sm text-green-500">{text}</span>
+                })}
+                <button
+                    class="px-4 py-2 bg-primary text-primary-foreground rounded-md"
+                    on:click=save
+                >
+                    Save Review
+                </button>
+            </div>
+        </div>
+    }
+}
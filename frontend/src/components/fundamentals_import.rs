@@ -0,0 +1,115 @@
+use leptos::*;
+use serde::{Deserialize, Serialize};
+use wasm_bindgen::JsCast;
+use wasm_bindgen_futures::JsFuture;
+use web_sys::HtmlInputElement;
+
+use crate::utils::api::*;
+
+#[derive(Clone, Debug, Serialize, Deserialize)]
+struct ImportSummary {
+    symbols: usize,
+    rows: usize,
+    ignored_columns: Vec<String>,
+    errors: Vec<String>,
+}
+
+const IMPORT_KINDS: [(&str, &str, &str); 4] = [
+    ("profile", "Company profiles", "symbol, name, sector, industry, shares_outstanding, face_value"),
+    ("quarterly", "Quarterly results", "symbol, period_end, then statement columns"),
+    ("annual", "Annual results", "symbol, period_end, then statement columns"),
+    ("shareholding", "Shareholding pattern", "symbol, period_end, promoters, fii, dii, government, public, pledged"),
+];
+
+/// Upload CSV files of company profiles, results or shareholding. Rows are
+/// merged into what is already stored, period by period.
+#[component]
+pub fn FundamentalsImport(#[prop(into)] on_imported: Callback<()>) -> impl IntoView {
+    let file_input = create_node_ref::<html::Input>();
+    let (kind, set_kind) = create_signal("annual".to_string());
+    let (summary, set_summary) = create_signal(None::<ImportSummary>);
+    let (error, set_error) = create_signal(None::<String>);
+
+    let upload = move |_| {
+        set_error.set(None);
+        set_summary.set(None);
+        let Some(input) = file_input.get() else {
+            return;
+        };
+        let input: HtmlInputElement = input.unchecked_into();
+        let Some(file) = input.files().and_then(|files| files.get(0)) else {
+            set_error.set(Some("Choose a CSV file first".to_string()));
+            return;
+        };
+        let path = format!("/fundamentals/import?kind={}", kind.get());
+        spawn_local(async move {
+            let text = match JsFuture::from(file.text()).await {
+                Ok(text) => text.as_string().unwrap_or_default(),
+                Err(_) => {
+                    set_error.set(Some("Could not read the file".to_string()));
+                    return;
+                }
+            };
+            match post_text::<ImportSummary>(&path, &text).await {
+                Ok(result) => {
+                    set_summary.set(Some(result));
+                    on_imported.call(());
+                }
+                Err(e) => set_error.set(Some(e)),
+            }
+        });
+    };
+
+    view! {
+        <div class="bg-card text-card-foreground rounded-lg shadow-sm p-6 mt-6">
+            <h3 class="text-lg font-medium mb-4">Import Fundamentals</h3>
+            <div class="flex flex-col md:flex-row gap-2 items-end">
+                <div>
+                    <label class="block text-sm font-medium mb-1">File contains</label>
+                    <select
+                        class="px-3 py-2 border border-input rounded-md"
+                        on:change=move |ev| set_kind.set(event_target_value(&ev))
+                    >
+                        {IMPORT_KINDS.into_iter().map(|(value, label, _)| view! {
+                            <option value=value selected=move || kind.get() == value>{label}</option>
+                        }).collect::<Vec<_>>()}
+                    </select>
+                </div>
+                <input type="file" accept=".csv" class="text-sm" node_ref=file_input />
+                <button
+                    class="px-4 py-2 bg-primary text-primary-foreground rounded-md"
+                    on:click=upload
+                >
+                    Import
+                </button>
+            </div>
+            <p class="text-xs text-muted-foreground mt-2">
+                {move || IMPORT_KINDS
+                    .iter()
+                    .find(|(value, _, _)| *value == kind.get())
+                    .map(|(_, _, columns)| format!("Columns: {}. Amounts in ₹ crore.", columns))}
+            </p>
+
+            {move || error.get().map(|text| view! {
+                <p class="text-sm text-red-500 mt-3">{text}</p>
+            })}
+            {move || summary.get().map(|s| view! {
+                <div class="text-sm mt-3 space-y-1">
+                    <p class="text-green-500">
+                        {format!("Imported {} rows for {} symbols", s.rows, s.symbols)}
+                    </p>
+                    {(!s.ignored_columns.is_empty()).then(|| view! {
+                        <p class="text-muted-foreground">
+                            {format!("Ignored columns: {}", s.ignored_columns.join(", "))}
+                        </p>
+                    })}
+                    {(!s.errors.is_empty()).then(|| view! {
+                        <ul class="text-red-500 list-disc pl-5">
+                            {s.errors.iter().map(|e| view! { <li>{e.clone()}</li> }).collect::<Vec<_>>()}
+                        </ul>
+                    })}
+                </div>
+            })}
+        </div>
+    }
+}
@@ -1,6 +1,8 @@
 use leptos::*;
 use leptos_router::*;
 
+use crate::components::market_status::MarketStatusBadge;
+
 #[component]
 pub fn Header(
     #[prop(into)] toggle_sidebar: Callback<MouseEvent>,
@@ -33,6 +35,8 @@ pub fn Header(
             </div>
             
             <div class="flex items-center space-x-4">
+                <MarketStatusBadge />
+                
                 <button class="p-2 rounded-full hover:bg-secondary">
                     // Notification Bell Icon
                     <div class="w-5 h-5">
@@ -0,0 +1,260 @@
+use leptos::*;
+use serde::{Deserialize, Serialize};
+
+use crate::components::stat_card::*;
+use crate::utils::api::*;
+use crate::utils::format::rupees;
+
+#[derive(Clone, Debug, Default, Serialize, Deserialize)]
+struct PerformanceStats {
+    trades: usize,
+    wins: usize,
+    losses: usize,
+    win_rate: f64,
+    net_pnl: f64,
+    gross_profit: f64,
+    gross_loss: f64,
+    avg_win: f64,
+    avg_loss: f64,
+    expectancy: f64,
+    profit_factor: Option<f64>,
+    max_drawdown: f64,
+    longest_win_streak: usize,
+    longest_loss_streak: usize,
+    current_streak: i64,
+}
+
+#[derive(Clone, Debug, Serialize, Deserialize)]
+struct EquityPoint {
+    time: String,
+    equity: f64,
+    drawdown: f64,
+}
+
+#[derive(Clone, Debug, Serialize, Deserialize)]
+struct Breakdown {
+    key: String,
+    trades: usize,
+    win_rate: f64,
+    net_pnl: f64,
+    avg_pnl: f64,
+    contribution: Option<f64>,
+}
+
+#[derive(Clone, Debug, Serialize, Deserialize)]
+struct Analytics {
+    overall: PerformanceStats,
+    equity_curve: Vec<EquityPoint>,
+    by_symbol: Vec<Breakdown>,
+    by_weekday: Vec<Breakdown>,
+    by_hour: Vec<Breakdown>,
+    by_holding_time: Vec<Breakdown>,
+    by_setup: Vec<Breakdown>,
+    by_mistake: Vec<Breakdown>,
+    by_emotion: Vec<Breakdown>,
+    by_direction: Vec<Breakdown>,
+    open_trades: usize,
+}
+
+const GROUPINGS: [(&str, &str); 8] = [
+    ("symbol", "Symbol"),
+    ("weekday", "Weekday"),
+    ("hour", "Entry Hour"),
+    ("holding", "Holding Time"),
+    ("setup", "Setup"),
+    ("mistake", "Mistake"),
+    ("emotion", "Emotion"),
+    ("direction", "Long vs Short"),
+];
+
+const CHART_WIDTH: f64 = 800.0;
+const CHART_HEIGHT: f64 = 200.0;
+
+/// SVG polyline points for the equity curve, scaled to the chart box with
+/// zero included so the baseline is always visible.
+fn curve_points(points: &[EquityPoint]) -> (String, f64) {
+    let max = points.iter().map(|p| p.equity).fold(0.0, f64::max);
+    let min = points.iter().map(|p| p.equity).fold(0.0, f64::min);
+    let span = (max - min).max(1.0);
+    let step = CHART_WIDTH / points.len().max(1) as f64;
+    let y = |value: f64| CHART_HEIGHT - (value - min) / span * CHART_HEIGHT;
+
+    let mut line = vec![format!("0,{:.1}", y(0.0))];
+    line.extend(
+        points
+            .iter()
+            .enumerate()
+            .map(|(i, p)| format!("{:.1},{:.1}", (i + 1) as f64 * step, y(p.equity))),
+    );
+    (line.join(" "), y(0.0))
+}
+
+#[component]
+pub fn JournalAnalytics() -> impl IntoView {
+    let (analytics, set_analytics) = create_signal(None::<Analytics>);
+    let (grouping, set_grouping) = create_signal("symbol".to_string());
+    let (error, set_error) = create_signal(None::<String>);
+
+    spawn_local(async move {
+        match get_json::<Analytics>("/journal/analytics").await {
+            Ok(result) => set_analytics.set(Some(result)),
+            Err(e) => set_error.set(Some(e)),
+        }
+    });
+
+    view! {
+        <div>
+            {move || error.get().map(|message| view! {
+                <p class="text-sm text-red-500 mb-4">{message}</p>
+            })}
+
+            {move || analytics.get().map(|data| {
+                let stats = data.overall.clone();
+                let (points, baseline) = curve_points(&data.equity_curve);
+                let rows = match grouping.get().as_str() {
+                    "weekday" => data.by_weekday.clone(),
+                    "hour" => data.by_hour.clone(),
+                    "holding" => data.by_holding_time.clone(),
+                    "setup" => data.by_setup.clone(),
+                    "mistake" => data.by_mistake.clone(),
+                    "emotion" => data.by_emotion.clone(),
+                    "direction" => data.by_direction.clone(),
+                    _ => data.by_symbol.clone(),
+                };
+                view! {
+                    <div class="grid grid-cols-1 md:grid-cols-4 gap-4 mb-6">
+                        <StatCard stat=StatData {
+                            title: "Net PnL".to_string(),
+                            value: rupees(stats.net_pnl),
+                            description: Some(format!("{} closed, {} open", stats.trades, data.open_trades)),
+                        } />
+                        <StatCard stat=StatData {
+                            title: "Win Rate".to_string(),
+                            value: format!("{:.1}%", stats.win_rate),
+                            description: Some(format!("{} wins / {} losses", stats.wins, stats.losses)),
+                        } />
+                        <StatCard stat=StatData {
+                            title: "Expectancy".to_string(),
+                            value: rupees(stats.expectancy),
+                            description: Some(format!(
+                                "Avg win {} / avg loss {}",
+                                rupees(stats.avg_win),
+                                rupees(stats.avg_loss)
+                            )),
+                        } />
+                        <StatCard stat=StatData {
+                            title: "Profit Factor".to_string(),
+                            value: stats.profit_factor.map_or("-".to_string(), |v| format!("{:.2}", v)),
+                            description: Some(format!(
+                                "{} won / {} lost",
+                                rupees(stats.gross_profit),
+                                rupees(stats.gross_loss)
+                            )),
+                        } />
+                        <StatCard stat=StatData {
+                            title: "Max Drawdown".to_string(),
+                            value: rupees(stats.max_drawdown),
+                            description: Some("Peak to trough of realized PnL".to_string()),
+                        } />
+                        <StatCard stat=StatData {
+                            title: "Longest Win Streak".to_string(),
+                            value: stats.longest_win_streak.to_string(),
+                            description: None,
+                        } />
+                        <StatCard stat=StatData {
+                            title: "Longest Loss Streak".to_string(),
+                            value: stats.longest_loss_streak.to_string(),
+                            description: None,
+                        } />
+                        <StatCard stat=StatData {
+                            title: "Current Streak".to_string(),
+                            value: match stats.current_streak {
+                                0 => "-".to_string(),
+                                n if n > 0 => format!("{} wins", n),
+                                n => format!("{} losses", -n),
+                            },
+                            description: None,
+                        } />
+                    </div>
+
+                    <div class="bg-card text-card-foreground rounded-lg shadow-sm p-6 mb-6">
+                        <h3 class="text-lg font-medium mb-4">Equity Curve</h3>
+                        {if data.equity_curve.is_empty() {
+                            view! { <p class="text-sm text-muted-foreground">No closed trades yet</p> }.into_view()
+                        } else {
+                            view! {
+                                <svg
+                                    class="w-full h-48"
+                                    viewBox=format!("0 0 {} {}", CHART_WIDTH, CHART_HEIGHT)
+                                    preserveAspectRatio="none"
+                                >
+                                    <line
+                                        x1="0"
+                                        x2=CHART_WIDTH.to_string()
+                                        y1=baseline.to_string()
+                                        y2=baseline.to_string()
+                                        stroke="currentColor"
+                                        stroke-opacity="0.2"
+                                    />
+                                    <polyline
+                                        points=points
+                                        fill="none"
+                                        stroke="currentColor"
+                                        stroke-width="2"
+                                        class=if stats.net_pnl >= 0.0 { "text-green-500" } else { "text-red-500" }
+                                    />
+                                </svg>
+                            }.into_view()
+                        }}
+                    </div>
+
+                    <div class="bg-card text-card-foreground rounded-lg shadow-sm overflow-hidden">
+                        <div class="p-4 border-b border-border flex justify-between items-center">
+                            <h3 class="text-lg font-medium">Breakdown</h3>
+                            <select
+                                class="px-3 py-2 border border-input rounded-md"
+                                on:change=move |ev| set_grouping.set(event_target_value(&ev))
+                            >
+                                {GROUPINGS.into_iter().map(|(value, label)| view! {
+                                    <option value=value selected=move || grouping.get() == value>{label}</option>
+                                }).collect::<Vec<_>>()}
+                            </select>
+                        </div>
+                        <div class="overflow-x-auto">
+                            <table class="w-full">
+                                <thead>
+                                    <tr class="border-b border-border">
+                                        <th class="text-left p-3 text-muted-foreground font-medium">Group</th>
+                                        <th class="text-left p-3 text-muted-foreground font-medium">Trades</th>
+                                        <th class="text-left p-3 text-muted-foreground font-medium">Win Rate</th>
+                                        <th class="text-left p-3 text-muted-foreground font-medium">Net PnL</th>
+                                        <th class="text-left p-3 text-muted-foreground font-medium">Avg PnL</th>
+                                        <th class="text-left p-3 text-muted-foreground font-medium">Share of PnL</th>
+                                    </tr>
+                                </thead>
+                                <tbody>
+                                    {rows.into_iter().map(|row| view! {
+                                        <tr class="border-b border-border">
+                                            <td class="p-3">{row.key}</td>
+                                            <td class="p-3">{row.trades.to_string()}</td>
+                                            <td class="p-3">{format!("{:.1}%", row.win_rate)}</td>
+                                            <td class="p-3">
+                                                <span class={if row.net_pnl >= 0.0 { "text-green-500" } else { "text-red-500" }}>
+                                                    {rupees(row.net_pnl)}
+                                                </span>
+                                            </td>
+                                            <td class="p-3">{rupees(row.avg_pnl)}</td>
+                                            <td class="p-3">
+                                                {row.contribution.map_or("-".to_string(), |v| format!("{:+.1}%", v))}
+                                            </td>
+                                        </tr>
+                                    }).collect::<Vec<_>>()}
+                                </tbody>
+                            </table>
+                        </div>
+                    </div>
+                }
+            })}
+        </div>
+    }
+}
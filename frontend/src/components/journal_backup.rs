@@ -0,0 +1,222 @@
+use leptos::*;
+use serde::{Deserialize, Serialize};
+use wasm_bindgen::JsCast;
+use wasm_bindgen_futures::JsFuture;
+use web_sys::HtmlInputElement;
+
+use crate::components::journal_form::*;
+use crate::components::journal_tags::*;
+use crate::utils::api::*;
+
+#[derive(Clone, Debug, Serialize, Deserialize)]
+struct RestorePreview {
+    trades: Vec<JournalEntry>,
+    tags: Vec<Tag>,
+    duplicates: usize,
+    errors: Vec<String>,
+}
+
+#[derive(Clone, Debug, Serialize, Deserialize)]
+struct RestoreResult {
+    created: usize,
+    tags: usize,
+    duplicates: usize,
+}
+
+const FORMATS: [(&str, &str); 3] = [("json", "JSON"), ("csv", "CSV"), ("xml", "Spreadsheet")];
+
+/// Backup format from the file extension.
+fn format_of(name: &str) -> Option<&'static str> {
+    let extension = name.rsplit('.').next()?.to_lowercase();
+    match extension.as_str() {
+        "json" => Some("json"),
+        "csv" => Some("csv"),
+        "xml" | "xls" => Some("xml"),
+        _ => None,
+    }
+}
+
+async fn read_backup(input: &HtmlInputElement) -> Result<(String, &'static str), String> {
+    let file = input
+        .files()
+        .and_then(|files| files.get(0))
+        .ok_or("Choose a backup file first")?;
+    let format = format_of(&file.name()).ok_or("Backups are .json, .csv or .xml files")?;
+    let text = JsFuture::from(file.text())
+        .await
+        .map_err(|_| "Could not read the file".to_string())?;
+    let text = text.as_string().ok_or("Could not read the file".to_string())?;
+    Ok((text, format))
+}
+
+/// Download the whole journal as JSON, CSV or a spreadsheet, and restore
+/// from any of them after reviewing what would be added.
+#[component]
+pub fn JournalBackup(#[prop(into)] on_restored: Callback<()>) -> impl IntoView {
+    let file_input = create_node_ref::<html::Input>();
+    let contents = store_value((String::new(), ""));
+    let (preview, set_preview) = create_signal(None::<RestorePreview>);
+    let (message, set_message) = create_signal(None::<String>);
+    let (error, set_error) = create_signal(None::<String>);
+
+    let load_preview = move |_| {
+        set_error.set(None);
+        set_message.set(None);
+        let Some(input) = file_input.get() else {
+            return;
+        };
+        let input: HtmlInputElement = input.unchecked_into();
+        spawn_local(async move {
+            let (text, format) = match read_backup(&input).await {
+                Ok(file) => file,
+                Err(e) => {
+                    set_error.set(Some(e));
+                    return;
+                }
+            };
+            let path = format!("/journal/restore/preview?format={}", format);
+            match post_text::<RestorePreview>(&path, &text).await {
+                Ok(result) => {
+                    contents.set_value((text, format));
+                    set_preview.set(Some(result));
+                }
+                Err(e) => set_error.set(Some(e)),
+            }
+        });
+    };
+
+    let commit = move |_| {
+        let (text, format) = contents.get_value();
+        spawn_local(async move {
+            let path = format!("/journal/restore?format={}", format);
+            match post_text::<RestoreResult>(&path, &text).await {
+                Ok(result) => {
+                    set_preview.set(None);
+                    set_message.set(Some(format!(
+                        "Restored {} trades and {} tags, skipped {} already in the journal",
+                        result.created, result.tags, result.duplicates
+                    )));
+                    on_restored.call(());
+                }
+                Err(e) => set_error.set(Some(e)),
+            }
+        });
+    };
+
+    view! {
+        <div class="bg-card text-card-foreground rounded-lg shadow-sm p-6 mt-6">
+            <h3 class="text-lg font-medium mb-4">Backup and Restore</h3>
+            <div class="flex flex-col md:flex-row md:justify-between gap-4 md:items-end">
+                <div>
+                    <p class="text-sm font-medium mb-2">Export all trades with executions, tags and notes</p>
+                    <div class="space-x-2">
+                        {FORMATS.iter().map(|(format, label)| view! {
+                            <a
+                                class="px-4 py-2 bg-secondary text-secondary-foreground rounded-md inline-block"
+                                href=format!("{}/journal/export?format={}", API_BASE, format)
+                            >
+                                {*label}
+                            </a>
+                        }).collect::<Vec<_>>()}
+                    </div>
+                </div>
+                <div class="flex gap-4 items-end">
+                    <div>
+                        <label class="block text-sm font-medium mb-1">Restore from a backup</label>
+                        <input type="file" accept=".json,.csv,.xml,.xls" class="text-sm" node_ref=file_input />
+                    </div>
+                    <button
+                        class="px-4 py-2 bg-secondary text-secondary-foreground rounded-md"
+                        on:click=load_preview
+                    >
+                        Preview
+                    </button>
+                </div>
+            </div>
+
+            {move || message.get().map(|text| view! {
+                <p class="text-sm text-green-500 mt-3">{text}</p>
+            })}
+            {move || error.get().map(|text| view! {
+                <p class="text-sm text-red-500 mt-3">{text}</p>
+            })}
+
+            {move || preview.get().map(|plan| {
+                let nothing_to_do = plan.trades.is_empty() && plan.tags.is_empty();
+                view! {
+                    <div class="mt-4">
+                        <p class="text-sm text-muted-foreground mb-2">
+                            {format!(
+                                "{} trades and {} tags to add, {} trades already in the journal",
+                                plan.trades.len(),
+                                plan.tags.len(),
+                                plan.duplicates
+                            )}
+                        </p>
+                        {plan.errors.into_iter().map(|line| view! {
+                            <p class="text-xs text-red-500">{line}</p>
+                        }).collect::<Vec<_>>()}
+                        {(!plan.tags.is_empty()).then(|| view! {
+                            <p class="text-sm mb-2">
+                                {format!(
+                                    "New tags: {}",
+                                    plan.tags.iter().map(|t| format!("{} ({})", t.name, t.kind)).collect::<Vec<_>>().join(", ")
+                                )}
+                            </p>
+                        })}
+                        <div class="overflow-x-auto max-h-96">
+                            <table class="w-full">
+                                <thead>
+                                    <tr class="border-b border-border">
+                                        <th class="text-left p-3 text-muted-foreground font-medium">Symbol</th>
+                                        <th class="text-left p-3 text-muted-foreground font-medium">Entry</th>
+                                        <th class="text-left p-3 text-muted-foreground font-medium">Fills</th>
+                                        <th class="text-left p-3 text-muted-foreground font-medium">Tags</th>
+                                        <th class="text-left p-3 text-muted-foreground font-medium">Realized PnL</th>
+                                    </tr>
+                                </thead>
+                                <tbody>
+                                    {plan.trades.into_iter().map(|entry| {
+                                        let summary = entry.summary.clone().unwrap_or_default();
+                                        let entry_time = summary
+                                            .entry_time
+                                            .map(|t| t.replace('T', " "))
+                                            .unwrap_or_default();
+                                        view! {
+                                            <tr class="border-b border-border">
+                                                <td class="p-3">{entry.symbol}</td>
+                                                <td class="p-3 whitespace-nowrap">{entry_time}</td>
+                                                <td class="p-3">{entry.executions.len().to_string()}</td>
+                                                <td class="p-3">{entry.tags.join(", ")}</td>
+                                                <td class="p-3">
+                                                    <span class={if summary.realized_pnl >= 0.0 { "text-green-500" } else { "text-red-500" }}>
+                                                        {format!("₹{:.2}", summary.realized_pnl)}
+                                                    </span>
+                                                </td>
+                                            </tr>
+                                        }
+                                    }).collect::<Vec<_>>()}
+                                </tbody>
+                            </table>
+                        </div>
+                        <div class="mt-4 flex justify-end space-x-2">
+                            <button
+                                class="px-4 py-2 bg-primary text-primary-foreground rounded-md"
+                                disabled=nothing_to_do
+                                on:click=commit
+                            >
+                                Restore
+                            </button>
+                            <button
+                                class="px-4 py-2 bg-secondary text-secondary-foreground rounded-md"
+                                on:click=move |_| set_preview.set(None)
+                            >
+                                Cancel
+                            </button>
+                        </div>
+                    </div>
+                }
+            })}
+        </div>
+    }
+}
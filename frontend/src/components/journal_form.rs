@@ -1,183 +1,487 @@
 use leptos::*;
 use serde::{Deserialize, Serialize};
-use wasm_bindgen::JsCast;
-use web_sys::HtmlInputElement;
 
-#[derive(Clone, Debug, Serialize, Deserialize)]
-pub struct JournalEntry {
-    pub id: Option<String>,
+use crate::components::journal_tags::*;
+use crate::components::symbol_search::*;
+use crate::utils::api::*;
+
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct Execution {
+    #[serde(default)]
+    pub id: String,
+    pub side: String,
+    pub quantity: u32,
+    pub price: f64,
+    /// `YYYY-MM-DDTHH:MM:SS` in IST.
+    pub time: String,
+    #[serde(default)]
+    pub fees: f64,
+    #[serde(default)]
+    pub trade_id: Option<String>,
+}
+
+impl Execution {
+    fn new(side: &str, date: &str) -> Self {
+        Self {
+            id: String::new(),
+            side: side.to_string(),
+            quantity: 0,
+            price: 0.0,
+            time: format!("{}T09:15:00", date),
+            fees: 0.0,
+            trade_id: None,
+        }
+    }
+}
+
+#[derive(Clone, Debug, Default, PartialEq, Serialize, Deserialize)]
+pub struct TradeSummary {
+    pub direction: Option<String>,
+    pub status: String,
+    pub entry_quantity: u32,
+    pub exit_quantity: u32,
+    pub open_quantity: u32,
+    pub avg_entry_price: f64,
+    pub avg_exit_price: f64,
+    pub open_avg_price: f64,
+    pub gross_pnl: f64,
+    pub fees: f64,
+    pub realized_pnl: f64,
+    pub unrealized_pnl: Option<f64>,
+    pub entry_time: Option<String>,
+    pub exit_time: Option<String>,
+}
+
+#[derive(Clone, Debug, Default, PartialEq, Serialize, Deserialize)]
+pub struct ChargeBreakdown {
+    pub turnover: f64,
+    pub brokerage: f64,
+    pub stt: f64,
+    pub exchange: f64,
+    pub sebi: f64,
+    pub stamp_duty: f64,
+    pub gst: f64,
+    pub total: f64,
+}
+
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct ChargeEstimate {
+    pub segment: String,
+    pub orders: Vec<ChargeBreakdown>,
+    pub total: ChargeBreakdown,
+}
+
+/// A trading rule a journal trade broke, flagged by the server.
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct Violation {
+    pub rule_id: String,
+    pub rule: String,
+    pub trade_id: String,
     pub date: String,
+    pub message: String,
+}
+
+/// A journal trade: every fill from the first entry until the position is
+/// flat. The summary is computed by the server.
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct JournalEntry {
+    #[serde(default)]
+    pub id: String,
     pub symbol: String,
-    pub quantity: i32,
-    pub buy_price: f64,
-    pub sell_price: f64,
-    pub action: String,
-    pub fees: f64,
-    pub pnl: f64,
+    pub executions: Vec<Execution>,
+    #[serde(default)]
     pub notes: String,
+    #[serde(default)]
+    pub tags: Vec<String>,
+    #[serde(default, skip_serializing)]
+    pub summary: Option<TradeSummary>,
+    #[serde(default, skip_serializing)]
+    pub violations: Vec<Violation>,
+}
+
+impl JournalEntry {
+    fn blank(date: &str) -> Self {
+        Self {
+            id: String::new(),
+            symbol: String::new(),
+            executions: vec![Execution::new("Buy", date)],
+            notes: String::new(),
+            tags: Vec::new(),
+            summary: None,
+            violations: Vec::new(),
+        }
+    }
+}
+
+pub fn pnl_class(value: f64) -> &'static str {
+    if value >= 0.0 {
+        "text-green-500"
+    } else {
+        "text-red-500"
+    }
+}
+
+/// `datetime-local` inputs drop the seconds, the server expects them.
+fn with_seconds(value: String) -> String {
+    if value.len() == 16 {
+        value + ":00"
+    } else {
+        value
+    }
 }
 
 #[component]
 pub fn JournalForm(
     #[prop(into)] on_save: Callback<JournalEntry>,
-    #[prop(default)] selected_date: String,
+    /// Day new fills default to, as `YYYY-MM-DD`.
+    #[prop(optional, into)]
+    selected_date: MaybeSignal<String>,
+    /// Trade to load into the form for editing.
+    #[prop(optional, into)]
+    edit: Option<Signal<Option<JournalEntry>>>,
 ) -> impl IntoView {
-    let (entry, set_entry) = create_signal(JournalEntry {
-        id: None,
-        date: selected_date,
-        symbol: String::new(),
-        quantity: 0,
-        buy_price: 0.0,
-        sell_price: 0.0,
-        action: "Buy".to_string(),
-        fees: 0.0,
-        pnl: 0.0,
-        notes: String::new(),
+    let date = store_value(selected_date.get_untracked());
+    let (entry, set_entry) = create_signal(JournalEntry::blank(&date.get_value()));
+    let (summary, set_summary) = create_signal(None::<TradeSummary>);
+    let (charges, set_charges) = create_signal(None::<ChargeEstimate>);
+    // On for new trades; an edited trade keeps the fees it was saved with
+    // unless it is switched on
+    let (auto_fees, set_auto_fees) = create_signal(true);
+
+    // Picking another day moves the fills of an unsaved trade along with it
+    create_effect(move |previous: Option<()>| {
+        let day = selected_date.get();
+        date.set_value(day.clone());
+        if previous.is_none() {
+            return;
+        }
+        set_entry.update(|entry| {
+            if !entry.id.is_empty() {
+                return;
+            }
+            for execution in &mut entry.executions {
+                let clock = execution.time.split_once('T').map_or("09:15:00", |(_, clock)| clock);
+                execution.time = format!("{}T{}", day, clock);
+            }
+        });
     });
-    
-    // Update PnL when relevant fields change
-    create_effect(move |_| {
+
+    if let Some(edit) = edit {
+        create_effect(move |_| {
+            if let Some(trade) = edit.get() {
+                set_auto_fees.set(trade.id.is_empty());
+                set_entry.set(trade);
+            }
+        });
+    }
+
+    // Ask the server to match the fills once they stop changing. With auto
+    // fees on, charges are filled in first and the preview runs once the
+    // fees have settled.
+    // Notes and tags don't change the numbers, so only fills trigger it
+    let fills = create_memo(move |_| {
         let current = entry.get();
-        let pnl = (current.sell_price - current.buy_price) * current.quantity as f64 - current.fees;
-        set_entry.update(|e| e.pnl = pnl);
+        (current.symbol, current.executions)
     });
-    
-    let handle_input = move |field: &'static str, ev: web_sys::Event| {
-        let target = ev.target().unwrap().unchecked_into::<HtmlInputElement>();
-        let value = target.value();
-        
+    let pending = store_value(None::<TimeoutHandle>);
+    // Fills whose fees were just filled in from an estimate, which needn't
+    // be estimated again
+    let estimated = store_value(None::<Vec<Execution>>);
+    create_effect(move |_| {
+        let (symbol, executions) = fills.get();
+        let current = JournalEntry {
+            symbol,
+            executions,
+            ..JournalEntry::blank("")
+        };
+        let auto = auto_fees.get();
+        let complete = !current.symbol.is_empty()
+            && current
+                .executions
+                .iter()
+                .all(|e| e.quantity > 0 && e.price > 0.0);
+        if let Some(handle) = pending.get_value() {
+            handle.clear();
+        }
+        if !complete {
+            pending.set_value(None);
+            set_summary.set(None);
+            set_charges.set(None);
+            return;
+        }
+        let settled = estimated.get_value().as_ref() == Some(&current.executions);
+        let request = move || {
+            spawn_local(async move {
+                if auto && !settled {
+                    match post_json::<_, ChargeEstimate>("/charges/trade", &current).await {
+                        Ok(estimate) => {
+                            let changed = current
+                                .executions
+                                .iter()
+                                .zip(&estimate.orders)
+                                .any(|(e, c)| e.fees != c.total);
+                            if changed {
+                                set_entry.update(|entry| {
+                                    for (execution, charge) in
+                                        entry.executions.iter_mut().zip(&estimate.orders)
+                                    {
+                                        execution.fees = charge.total;
+                                    }
+                                    estimated.set_value(Some(entry.executions.clone()));
+                                });
+                            }
+                            set_charges.set(Some(estimate));
+                            if changed {
+                                return;
+                            }
+                        }
+                        Err(e) => log::warn!("Charge estimate failed: {}", e),
+                    }
+                } else if !auto {
+                    set_charges.set(None);
+                }
+                match post_json::<_, TradeSummary>("/journal/preview", &current).await {
+                    Ok(result) => set_summary.set(Some(result)),
+                    Err(e) => log::warn!("Trade preview failed: {}", e),
+                }
+            })
+        };
+        let handle = set_timeout_with_handle(request, std::time::Duration::from_millis(400));
+        pending.set_value(handle.ok());
+    });
+
+    let handle_execution = move |index: usize, field: &'static str, ev: web_sys::Event| {
+        let value = event_target_value(&ev);
         set_entry.update(|entry| {
+            let Some(execution) = entry.executions.get_mut(index) else {
+                return;
+            };
             match field {
-                "symbol" => entry.symbol = value,
-                "quantity" => entry.quantity = value.parse().unwrap_or(0),
-                "buy_price" => entry.buy_price = value.parse().unwrap_or(0.0),
-                "sell_price" => entry.sell_price = value.parse().unwrap_or(0.0),
-                "action" => entry.action = value,
-                "fees" => entry.fees = value.parse().unwrap_or(0.0),
-                "notes" => entry.notes = value,
+                "side" => execution.side = value,
+                "quantity" => execution.quantity = value.parse().unwrap_or(0),
+                "price" => execution.price = value.parse().unwrap_or(0.0),
+                "time" => execution.time = with_seconds(value),
+                "fees" => execution.fees = value.parse().unwrap_or(0.0),
                 _ => {}
             }
         });
     };
-    
-    let save = move |_| {
-        on_save.call(entry.get());
-        
-        // Reset form
-        set_entry.update(|e| {
-            e.symbol = String::new();
-            e.quantity = 0;
-            e.buy_price = 0.0;
-            e.sell_price = 0.0;
-            e.action = "Buy".to_string();
-            e.fees = 0.0;
-            e.pnl = 0.0;
-            e.notes = String::new();
+
+    let add_execution = move |_| {
+        set_entry.update(|entry| {
+            // A new fill defaults to closing the position
+            let side = match entry.executions.first().map(|e| e.side.as_str()) {
+                Some("Buy") => "Sell",
+                _ => "Buy",
+            };
+            let mut execution = Execution::new(side, &date.get_value());
+            if let Some(last) = entry.executions.last() {
+                execution.time = last.time.clone();
+            }
+            entry.executions.push(execution);
         });
     };
-    
-    let cancel = move |_| {
-        // Reset form
-        set_entry.update(|e| {
-            e.symbol = String::new();
-            e.quantity = 0;
-            e.buy_price = 0.0;
-            e.sell_price = 0.0;
-            e.action = "Buy".to_string();
-            e.fees = 0.0;
-            e.pnl = 0.0;
-            e.notes = String::new();
+
+    let remove_execution = move |index: usize| {
+        set_entry.update(|entry| {
+            if entry.executions.len() > 1 {
+                entry.executions.remove(index);
+            }
         });
     };
-    
+
+    let reset = move || {
+        set_auto_fees.set(true);
+        set_entry.set(JournalEntry::blank(&date.get_value()));
+    };
+
+    let save = move |_| {
+        on_save.call(entry.get());
+        reset();
+    };
+
+    let cancel = move |_| reset();
+
     view! {
         <div class="space-y-6">
             <div class="bg-card text-card-foreground rounded-lg shadow-sm p-6">
-                <h3 class="text-lg font-medium mb-4">Trade Details</h3>
-                <div class="grid grid-cols-1 md:grid-cols-2 lg:grid-cols-3 gap-4">
-                    <div>
-                        <label class="block text-sm font-medium mb-1">Symbol</label>
-                        <input 
-                            type="text" 
-                            class="w-full px-3 py-2 border border-input rounded-md" 
-                            value={move || entry.get().symbol}
-                            on:input=move |ev| handle_input("symbol", ev)
-                        />
-                    </div>
-                    <div>
-                        <label class="block text-sm font-medium mb-1">Quantity</label>
-                        <input 
-                            type="number" 
-                            class="w-full px-3 py-2 border border-input rounded-md" 
-                            value={move || entry.get().quantity.to_string()}
-                            on:input=move |ev| handle_input("quantity", ev)
-                        />
-                    </div>
-                    <div>
-                        <label class="block text-sm font-medium mb-1">Buy Price</label><input 
-                            type="number" 
-                            step="0.01"
-                            class="w-full px-3 py-2 border border-input rounded-md" 
-                            value={move || entry.get().buy_price.to_string()}
-                            on:input=move |ev| handle_input("buy_price", ev)
-                        />
-                    </div>
-                    <div>
-                        <label class="block text-sm font-medium mb-1">Sell Price</label>
-                        <input 
-                            type="number" 
-                            step="0.01"
-                            class="w-full px-3 py-2 border border-input rounded-md" 
-                            value={move || entry.get().sell_price.to_string()}
-                            on:input=move |ev| handle_input("sell_price", ev)
-                        />
-                    </div>
-                    <div>
-                        <label class="block text-sm font-medium mb-1">Action</label>
-                        <select 
-                            class="w-full px-3 py-2 border border-input rounded-md" 
-                            on:change=move |ev| handle_input("action", ev)
-                        >
-                            <option selected={move || entry.get().action == "Buy"}>Buy</option>
-                            <option selected={move || entry.get().action == "Sell"}>Sell</option>
-                        </select>
-                    </div>
-                    <div>
-                        <label class="block text-sm font-medium mb-1">Fees</label>
-                        <input 
-                            type="number" 
-                            step="0.01"
-                            class="w-full px-3 py-2 border border-input rounded-md" 
-                            value={move || entry.get().fees.to_string()}
-                            on:input=move |ev| handle_input("fees", ev)
-                        />
-                    </div>
-                    <div>
-                        <label class="block text-sm font-medium mb-1">PnL</label>
-                        <input 
-                            type="number" 
-                            class="w-full px-3 py-2 border border-input rounded-md bg-muted" 
-                            value={move || format!("{:.2}", entry.get().pnl)}
-                            readonly=true
+                <h3 class="text-lg font-medium mb-4">
+                    {move || if entry.get().id.is_empty() { "Trade Details" } else { "Edit Trade" }}
+                </h3>
+                <div class="w-full md:w-64">
+                    <SymbolSearch
+                        value=Signal::derive(move || entry.get().symbol)
+                        on_select=move |hit: InstrumentHit| set_entry.update(|e| e.symbol = hit.symbol)
+                        label="Symbol"
+                    />
+                </div>
+
+                <div class="overflow-x-auto mt-4">
+                    <table class="w-full">
+                        <thead>
+                            <tr class="border-b border-border">
+                                <th class="text-left p-2 text-muted-foreground font-medium">Side</th>
+                                <th class="text-left p-2 text-muted-foreground font-medium">Quantity</th>
+                                <th class="text-left p-2 text-muted-foreground font-medium">Price</th>
+                                <th class="text-left p-2 text-muted-foreground font-medium">Time</th>
+                                <th class="text-left p-2 text-muted-foreground font-medium">Fees</th>
+                                <th class="p-2"></th>
+                            </tr>
+                        </thead>
+                        <tbody>
+                            {move || entry.get().executions.into_iter().enumerate().map(|(index, execution)| {
+                                let is_buy = execution.side == "Buy";
+                                view! {
+                                    <tr class="border-b border-border">
+                                        <td class="p-2">
+                                            <select
+                                                class="px-2 py-1 border border-input rounded-md"
+                                                on:change=move |ev| handle_execution(index, "side", ev)
+                                            >
+                                                <option value="Buy" selected=is_buy>Buy</option>
+                                                <option value="Sell" selected=!is_buy>Sell</option>
+                                            </select>
+                                        </td>
+                                        <td class="p-2">
+                                            <input
+                                                type="number"
+                                                class="w-24 px-2 py-1 border border-input rounded-md"
+                                                value=execution.quantity.to_string()
+                                                on:change=move |ev| handle_execution(index, "quantity", ev)
+                                            />
+                                        </td>
+                                        <td class="p-2">
+                                            <input
+                                                type="number"
+                                                step="0.05"
+                                                class="w-28 px-2 py-1 border border-input rounded-md"
+                                                value=execution.price.to_string()
+                                                on:change=move |ev| handle_execution(index, "price", ev)
+                                            />
+                                        </td>
+                                        <td class="p-2">
+                                            <input
+                                                type="datetime-local"
+                                                class="px-2 py-1 border border-input rounded-md"
+                                                value=execution.time.chars().take(16).collect::<String>()
+                                                on:change=move |ev| handle_execution(index, "time", ev)
+                                            />
+                                        </td>
+                                        <td class="p-2">
+                                            <input
+                                                type="number"
+                                                step="0.01"
+                                                class="w-24 px-2 py-1 border border-input rounded-md"
+                                                readonly=move || auto_fees.get()
+                                                value=format!("{:.2}", execution.fees)
+                                                on:change=move |ev| handle_execution(index, "fees", ev)
+                                            />
+                                        </td>
+                                        <td class="p-2">
+                                            <button
+                                                class="px-2 py-1 text-muted-foreground text-sm"
+                                                on:click=move |_| remove_execution(index)
+                                            >
+                                                Remove
+                                            </button>
+                                        </td>
+                                    </tr>
+                                }
+                            }).collect::<Vec<_>>()}
+                        </tbody>
+                    </table>
+                </div>
+                <div class="mt-2 flex items-center justify-between">
+                    <button
+                        class="px-3 py-1 bg-secondary text-secondary-foreground rounded-md text-sm"
+                        on:click=add_execution
+                    >
+                        Add Execution
+                    </button>
+                    <label class="flex items-center gap-2 text-sm">
+                        <input
+                            type="checkbox"
+                            prop:checked=move || auto_fees.get()
+                            on:change=move |ev| set_auto_fees.set(event_target_checked(&ev))
                         />
+                        "Calculate brokerage and statutory charges"
+                    </label>
+                </div>
+
+                {move || charges.get().map(|c| view! {
+                    <p class="mt-2 text-xs text-muted-foreground">
+                        {format!(
+                            "{}: brokerage ₹{:.2}, STT/CTT ₹{:.2}, exchange ₹{:.2}, SEBI ₹{:.2}, stamp duty ₹{:.2}, GST ₹{:.2}, total ₹{:.2}",
+                            c.segment.replace('_', " "),
+                            c.total.brokerage,
+                            c.total.stt,
+                            c.total.exchange,
+                            c.total.sebi,
+                            c.total.stamp_duty,
+                            c.total.gst,
+                            c.total.total,
+                        )}
+                    </p>
+                })}
+
+                {move || summary.get().map(|s| view! {
+                    <div class="grid grid-cols-2 md:grid-cols-5 gap-4 mt-4 p-4 bg-muted rounded-md text-sm">
+                        <div>
+                            <p class="text-muted-foreground">Direction</p>
+                            <p class="font-medium">{s.direction.unwrap_or_default()}</p>
+                        </div>
+                        <div>
+                            <p class="text-muted-foreground">Avg Entry / Exit</p>
+                            <p class="font-medium">
+                                {format!("₹{:.2} / ₹{:.2}", s.avg_entry_price, s.avg_exit_price)}
+                            </p>
+                        </div>
+                        <div>
+                            <p class="text-muted-foreground">Open Quantity</p>
+                            <p class="font-medium">{s.open_quantity.to_string()}</p>
+                        </div>
+                        <div>
+                            <p class="text-muted-foreground">Realized PnL</p>
+                            <p class=format!("font-medium {}", pnl_class(s.realized_pnl))>
+                                {format!("₹{:.2}", s.realized_pnl)}
+                            </p>
+                        </div>
+                        <div>
+                            <p class="text-muted-foreground">Unrealized PnL</p>
+                            <p class="font-medium">
+                                {s.unrealized_pnl.map_or("-".to_string(), |v| format!("₹{:.2}", v))}
+                            </p>
+                        </div>
                     </div>
+                })}
+
+                <div class="mt-4">
+                    <label class="block text-sm font-medium mb-1">Tags</label>
+                    <TagPicker
+                        selected=Signal::derive(move || entry.get().tags)
+                        on_change=move |tags: Vec<String>| set_entry.update(|e| e.tags = tags)
+                    />
                 </div>
                 <div class="mt-4">
                     <label class="block text-sm font-medium mb-1">Notes</label>
-                    <textarea 
-                        class="w-full px-3 py-2 border border-input rounded-md" 
+                    <textarea
+                        class="w-full px-3 py-2 border border-input rounded-md"
                         rows="3"
-                        on:input=move |ev| handle_input("notes", ev)
-                    >{move || entry.get().notes}</textarea>
+                        prop:value=move || entry.get().notes
+                        on:input=move |ev| {
+                            let value = event_target_value(&ev);
+                            set_entry.update(|e| e.notes = value);
+                        }
+                    ></textarea>
                 </div>
                 <div class="mt-4 flex justify-end space-x-2">
-                    <button 
+                    <button
                         class="px-4 py-2 bg-primary text-primary-foreground rounded-md"
                         on:click=save
                     >
                         Save
                     </button>
-                    <button 
+                    <button
                         class="px-4 py-2 bg-secondary text-secondary-foreground rounded-md"
                         on:click=cancel
                     >
@@ -190,7 +494,12 @@ pub fn JournalForm(
 }
 
 #[component]
-pub fn JournalTable(entries: Vec<JournalEntry>) -> impl IntoView {
+pub fn JournalTable(
+    entries: Vec<JournalEntry>,
+    #[prop(optional)] on_view: Option<Callback<JournalEntry>>,
+    #[prop(optional)] on_edit: Option<Callback<JournalEntry>>,
+    #[prop(optional)] on_delete: Option<Callback<String>>,
+) -> impl IntoView {
     view! {
         <div class="bg-card text-card-foreground rounded-lg shadow-sm overflow-hidden mt-6">
             <div class="p-4 border-b border-border">
@@ -200,32 +509,100 @@ pub fn JournalTable(entries: Vec<JournalEntry>) -> impl IntoView {
                 <table class="w-full">
                     <thead>
                         <tr class="border-b border-border">
+                            <th class="text-left p-3 text-muted-foreground font-medium">Date</th>
                             <th class="text-left p-3 text-muted-foreground font-medium">Symbol</th>
+                            <th class="text-left p-3 text-muted-foreground font-medium">Direction</th>
                             <th class="text-left p-3 text-muted-foreground font-medium">Quantity</th>
-                            <th class="text-left p-3 text-muted-foreground font-medium">Buy Price</th>
-                            <th class="text-left p-3 text-muted-foreground font-medium">Sell Price</th>
-                            <th class="text-left p-3 text-muted-foreground font-medium">Action</th>
+                            <th class="text-left p-3 text-muted-foreground font-medium">Avg Entry</th>
+                            <th class="text-left p-3 text-muted-foreground font-medium">Avg Exit</th>
                             <th class="text-left p-3 text-muted-foreground font-medium">Fees</th>
-                            <th class="text-left p-3 text-muted-foreground font-medium">PnL</th>
+                            <th class="text-left p-3 text-muted-foreground font-medium">Realized PnL</th>
+                            <th class="text-left p-3 text-muted-foreground font-medium">Unrealized PnL</th>
                             <th class="text-left p-3 text-muted-foreground font-medium">Notes</th>
+                            <th class="p-3"></th>
                         </tr>
                     </thead>
                     <tbody>
-                        {entries.into_iter().map(|entry| view! {
-                            <tr class="border-b border-border">
-                                <td class="p-3">{entry.symbol}</td>
-                                <td class="p-3">{entry.quantity.to_string()}</td>
-                                <td class="p-3">{"₹".to_string() + &entry.buy_price.to_string()}</td>
-                                <td class="p-3">{"₹".to_string() + &entry.sell_price.to_string()}</td>
-                                <td class="p-3">{entry.action}</td>
-                                <td class="p-3">{"₹".to_string() + &entry.fees.to_string()}</td>
-                                <td class="p-3">
-                                    <span class={if entry.pnl >= 0.0 { "text-green-500" } else { "text-red-500" }}>
-                                        {format!("₹{:.2}", entry.pnl)}
-                                    </span>
-                                </td>
-                                <td class="p-3">{entry.notes}</td>
-                            </tr>
+                        {entries.into_iter().map(|entry| {
+                            let summary = entry.summary.clone().unwrap_or_default();
+                            let quantity = if summary.open_quantity > 0 {
+                                format!("{} ({} open)", summary.entry_quantity, summary.open_quantity)
+                            } else {
+                                summary.entry_quantity.to_string()
+                            };
+                            let id = entry.id.clone();
+                            let edited = entry.clone();
+                            let viewed = entry.clone();
+                            view! {
+                                <tr class="border-b border-border">
+                                    <td class="p-3 whitespace-nowrap">
+                                        {summary.entry_time.clone().unwrap_or_default().chars().take(10).collect::<String>()}
+                                    </td>
+                                    <td class="p-3">{entry.symbol}</td>
+                                    <td class="p-3">{summary.direction.clone().unwrap_or_default()}</td>
+                                    <td class="p-3">{quantity}</td>
+                                    <td class="p-3">{format!("₹{:.2}", summary.avg_entry_price)}</td>
+                                    <td class="p-3">
+                                        {if summary.exit_quantity > 0 { format!("₹{:.2}", summary.avg_exit_price) } else { "-".to_string() }}
+                                    </td>
+                                    <td class="p-3">{format!("₹{:.2}", summary.fees)}</td>
+                                    <td class="p-3">
+                                        <span class=pnl_class(summary.realized_pnl)>
+                                            {format!("₹{:.2}", summary.realized_pnl)}
+                                        </span>
+                                    </td>
+                                    <td class="p-3">
+                                        {match summary.unrealized_pnl {
+                                            Some(v) if summary.open_quantity > 0 => view! {
+                                                <span class=pnl_class(v)>{format!("₹{:.2}", v)}</span>
+                                            }.into_view(),
+                                            _ => "-".into_view(),
+                                        }}
+                                    </td>
+                                    <td class="p-3">
+                                        {entry.notes}
+                                        <div class="flex flex-wrap gap-1 mt-1">
+                                            {entry.tags.into_iter().map(|tag| view! {
+                                                <span class="px-2 py-0.5 bg-muted rounded-md text-xs">{tag}</span>
+                                            }).collect::<Vec<_>>()}
+                                            {entry.violations.into_iter().map(|violation| view! {
+                                                <span
+                                                    class="px-2 py-0.5 bg-red-500/10 text-red-500 rounded-md text-xs"
+                                                    title=violation.message
+                                                >
+                                                    {format!("Broke: {}", violation.rule)}
+                                                </span>
+                                            }).collect::<Vec<_>>()}
+                                        </div>
+                                    </td>
+                                    <td class="p-3 space-x-2 whitespace-nowrap">
+                                        {on_view.map(|on_view| view! {
+                                            <button
+                                                class="px-2 py-1 bg-secondary text-secondary-foreground rounded-md text-sm"
+                                                on:click=move |_| on_view.call(viewed.clone())
+                                            >
+                                                View
+                                            </button>
+                                        })}
+                                        {on_edit.map(|on_edit| view! {
+                                            <button
+                                                class="px-2 py-1 bg-secondary text-secondary-foreground rounded-md text-sm"
+                                                on:click=move |_| on_edit.call(edited.clone())
+                                            >
+                                                Edit
+                                            </button>
+                                        })}
+                                        {on_delete.map(|on_delete| view! {
+                                            <button
+                                                class="px-2 py-1 text-muted-foreground text-sm"
+                                                on:click=move |_| on_delete.call(id.clone())
+                                            >
+                                                Delete
+                                            </button>
+                                        })}
+                                    </td>
+                                </tr>
+                            }
                         }).collect::<Vec<_>>()}
                     </tbody>
                 </table>
@@ -0,0 +1,243 @@
+use leptos::*;
+use serde::{Deserialize, Serialize};
+
+use crate::utils::api::*;
+
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct Tag {
+    pub name: String,
+    pub kind: String,
+    #[serde(default)]
+    pub description: String,
+}
+
+pub const TAG_KINDS: [(&str, &str); 3] = [
+    ("setup", "Setups"),
+    ("mistake", "Mistakes"),
+    ("emotion", "Emotions"),
+];
+
+fn chip_class(kind: &str, active: bool) -> &'static str {
+    match (kind, active) {
+        (_, false) => "px-2 py-1 rounded-md text-xs border border-input text-muted-foreground",
+        ("mistake", true) => "px-2 py-1 rounded-md text-xs border border-red-500 bg-red-500 text-white",
+        ("emotion", true) => "px-2 py-1 rounded-md text-xs border border-yellow-500 bg-yellow-500 text-white",
+        _ => "px-2 py-1 rounded-md text-xs border border-primary bg-primary text-primary-foreground",
+    }
+}
+
+/// Load the tag list once and share it with the caller.
+pub fn load_tags(set_tags: WriteSignal<Vec<Tag>>) {
+    spawn_local(async move {
+        match get_json::<Vec<Tag>>("/journal/tags").await {
+            Ok(list) => set_tags.set(list),
+            Err(e) => log::warn!("Loading journal tags failed: {}", e),
+        }
+    });
+}
+
+/// Toggle chips for every defined tag, grouped by kind.
+#[component]
+pub fn TagPicker(
+    #[prop(into)] selected: Signal<Vec<String>>,
+    #[prop(into)] on_change: Callback<Vec<String>>,
+) -> impl IntoView {
+    let (tags, set_tags) = create_signal(Vec::<Tag>::new());
+    load_tags(set_tags);
+
+    let toggle = move |name: String| {
+        let mut current = selected.get_untracked();
+        match current.iter().position(|t| *t == name) {
+            Some(index) => {
+                current.remove(index);
+            }
+            None => current.push(name),
+        }
+        on_change.call(current);
+    };
+
+    view! {
+        <div class="space-y-2">
+            {TAG_KINDS.into_iter().map(|(kind, label)| view! {
+                <div class="flex flex-wrap items-center gap-2">
+                    <span class="text-xs font-medium text-muted-foreground w-20">{label}</span>
+                    {move || tags
+                        .get()
+                        .into_iter()
+                        .filter(|tag| tag.kind == kind)
+                        .map(|tag| {
+                            let name = tag.name.clone();
+                            let check = tag.name.clone();
+                            view! {
+                                <button
+                                    type="button"
+                                    title=tag.description
+                                    class=move || chip_class(kind, selected.get().contains(&check))
+                                    on:click=move |_| toggle(name.clone())
+                                >
+                                    {tag.name}
+                                </button>
+                            }
+                        })
+                        .collect::<Vec<_>>()}
+                </div>
+            }).collect::<Vec<_>>()}
+        </div>
+    }
+}
+
+/// Add, re-classify, rename and delete journal tags.
+#[component]
+pub fn TagManager(#[prop(into)] on_change: Callback<()>) -> impl IntoView {
+    let (tags, set_tags) = create_signal(Vec::<Tag>::new());
+    let (new_name, set_new_name) = create_signal(String::new());
+    let (new_kind, set_new_kind) = create_signal("setup".to_string());
+    let (error, set_error) = create_signal(None::<String>);
+    load_tags(set_tags);
+
+    let refresh = move || {
+        load_tags(set_tags);
+        on_change.call(());
+    };
+
+    let add = move |_| {
+        set_error.set(None);
+        let tag = Tag {
+            name: new_name.get(),
+            kind: new_kind.get(),
+            description: String::new(),
+        };
+        spawn_local(async move {
+            match post_json::<_, Tag>("/journal/tags", &tag).await {
+                Ok(_) => {
+                    set_new_name.set(String::new());
+                    refresh();
+                }
+                Err(e) => set_error.set(Some(e)),
+            }
+        });
+    };
+
+    let update = move |original: String, tag: Tag| {
+        set_error.set(None);
+        spawn_local(async move {
+            let path = format!("/journal/tags/{}", encode(&original));
+            match put_json::<_, Tag>(&path, &tag).await {
+                Ok(_) => refresh(),
+                Err(e) => set_error.set(Some(e)),
+            }
+        });
+    };
+
+    let remove = move |name: String| {
+        spawn_local(async move {
+            match delete_json::<String>(&format!("/journal/tags/{}", encode(&name))).await {
+                Ok(_) => refresh(),
+                Err(e) => set_error.set(Some(e)),
+            }
+        });
+    };
+
+    view! {
+        <div class="bg-card text-card-foreground rounded-lg shadow-sm p-6 mt-6">
+            <h3 class="text-lg font-medium mb-4">Manage Tags</h3>
+            <div class="flex flex-col md:flex-row gap-2 items-end mb-4">
+                <div>
+                    <label class="block text-sm font-medium mb-1">Name</label>
+                    <input
+                        type="text"
+                        class="px-3 py-2 border border-input rounded-md"
+                        prop:value=move || new_name.get()
+                        on:input=move |ev| set_new_name.set(event_target_value(&ev))
+                    />
+                </div>
+                <div>
+                    <label class="block text-sm font-medium mb-1">Kind</label>
+                    <select
+                        class="px-3 py-2 border border-input rounded-md"
+                        on:change=move |ev| set_new_kind.set(event_target_value(&ev))
+                    >
+                        {TAG_KINDS.into_iter().map(|(kind, label)| view! {
+                            <option value=kind selected=move || new_kind.get() == kind>{label}</option>
+                        }).collect::<Vec<_>>()}
+                    </select>
+                </div>
+                <button
+                    class="px-4 py-2 bg-primary text-primary-foreground rounded-md"
+                    on:click=add
+                >
+                    Add Tag
+                </button>
+            </div>
+
+            {move || error.get().map(|message| view! {
+                <p class="text-sm text-red-500 mb-3">{message}</p>
+            })}
+
+            <table class="w-full">
+                <thead>
+                    <tr class="border-b border-border">
+                        <th class="text-left p-3 text-muted-foreground font-medium">Name</th>
+                        <th class="text-left p-3 text-muted-foreground font-medium">Kind</th>
+                        <th class="text-left p-3 text-muted-foreground font-medium">Description</th>
+                        <th class="p-3"></th>
+                    </tr>
+                </thead>
+                <tbody>
+                    {move || tags.get().into_iter().map(|tag| {
+                        let original = store_value(tag.name.clone());
+                        let current = store_value(tag.clone());
+                        let save = move || update(original.get_value(), current.get_value());
+                        view! {
+                            <tr class="border-b border-border">
+                                <td class="p-3">
+                                    <input
+                                        type="text"
+                                        class="px-2 py-1 border border-input rounded-md"
+                                        value=tag.name.clone()
+                                        on:change=move |ev| {
+                                            current.update_value(|t| t.name = event_target_value(&ev));
+                                            save();
+                                        }
+                                    />
+                                </td>
+                                <td class="p-3">
+                                    <select
+                                        class="px-2 py-1 border border-input rounded-md"
+                                        on:change=move |ev| {
+                                            current.update_value(|t| t.kind = event_target_value(&ev));
+                                            save();
+                                        }
+                                    >
+                                        {TAG_KINDS.into_iter().map(|(kind, label)| view! {
+                                            <option value=kind selected=tag.kind == kind>{label}</option>
+                                        }).collect::<Vec<_>>()}
+                                    </select>
+                                </td>
+                                <td class="p-3">
+                                    <input
+                                        type="text"
+                                        class="w-full px-2 py-1 border border-input rounded-md"
+                                        value=tag.description.clone()
+                                        on:change=move |ev| {
+                                            current.update_value(|t| t.description = event_target_value(&ev));
+                                            save();
+                                        }
+                                    />
+                                </td>
+                                <td class="p-3">
+                                    <button
+                                        class="px-2 py-1 text-muted-foreground text-sm"
+                                        on:click=move |_| remove(original.get_value())
+                                    >
+                                        Delete
+                                    </button>
+                                </td>
+                            </tr>
+                        }
+                    }).collect::<Vec<_>>()}
+                </tbody>
+            </table>
+        </div>
+    }
+}
@@ -0,0 +1,59 @@
+use std::time::Duration;
+
+use leptos::*;
+use serde::{Deserialize, Serialize};
+
+use crate::utils::api::*;
+
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct MarketStatus {
+    pub now: String,
+    pub phase: String,
+    pub label: String,
+    pub is_trading_day: bool,
+    pub next_change: Option<String>,
+    pub note: Option<String>,
+}
+
+#[component]
+pub fn MarketStatusBadge() -> impl IntoView {
+    let (status, set_status) = create_signal(None::<MarketStatus>);
+
+    let refresh = move || {
+        spawn_local(async move {
+            match get_json::<MarketStatus>("/calendar/status").await {
+                Ok(current) => set_status.set(Some(current)),
+                Err(e) => log::warn!("Failed to load market status: {}", e),
+            }
+        });
+    };
+
+    refresh();
+    if let Ok(handle) = set_interval_with_handle(refresh, Duration::from_secs(60)) {
+        on_cleanup(move || handle.clear());
+    }
+
+    view! {
+        {move || status.get().map(|current| {
+            let dot = match current.phase.as_str() {
+                "normal" => "bg-green-500",
+                "pre_open" | "closing" => "bg-yellow-500",
+                _ => "bg-red-500",
+            };
+            let title = current
+                .next_change
+                .map(|next| format!("Next change at {} IST", next.replace('T', " ")))
+                .into_iter()
+                .chain(current.note)
+                .collect::<Vec<_>>()
+                .join("\n");
+
+            view! {
+                <div class="flex items-center px-3 py-1 rounded-full bg-secondary text-sm" title=title>
+                    <span class=format!("w-2 h-2 rounded-full mr-2 {}", dot)></span>
+                    <span>{current.label}</span>
+                </div>
+            }
+        })}
+    }
+}
@@ -0,0 +1,31 @@
+pub mod analysis_table;
+pub mod coming_soon;
+pub mod daily_review;
+pub mod fundamentals_import;
+pub mod header;
+pub mod journal_analytics;
+pub mod journal_backup;
+pub mod journal_form;
+pub mod journal_tags;
+pub mod market_card;
+pub mod market_status;
+pub mod option_calculator;
+pub mod picture_in_picture;
+pub mod pnl_calendar;
+pub mod portfolio_risk;
+pub mod portfolio_summary;
+pub mod rebalance_planner;
+pub mod risk_guidance;
+pub mod screen_results;
+pub mod sidebar;
+pub mod stat_card;
+pub mod stock_score;
+pub mod symbol_search;
+pub mod tax_report;
+pub mod theme_toggle;
+pub mod timeframe_select;
+pub mod toast;
+pub mod trade_detail;
+pub mod tradebook_import;
+pub mod trading_rules;
+pub mod valuation_models;
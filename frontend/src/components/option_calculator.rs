@@ -0,0 +1,206 @@
+use leptos::*;
+use serde::{Deserialize, Serialize};
+
+use crate::utils::api::*;
+
+#[derive(Clone, Debug, Serialize, Deserialize)]
+struct Greeks {
+    price: f64,
+    delta: f64,
+    gamma: f64,
+    theta: f64,
+    vega: f64,
+    rho: f64,
+}
+
+#[derive(Clone, Debug, Serialize, Deserialize)]
+struct PriceResult {
+    model: String,
+    years: f64,
+    volatility: f64,
+    implied: bool,
+    greeks: Greeks,
+}
+
+/// Price and Greeks of one contract at a volatility, or its implied
+/// volatility from a premium.
+#[component]
+pub fn OptionCalculator() -> impl IntoView {
+    let (option_type, set_option_type) = create_signal("CE".to_string());
+    let (model, set_model) = create_signal("black_scholes".to_string());
+    let (underlying_price, set_underlying_price) = create_signal(String::new());
+    let (strike, set_strike) = create_signal(String::new());
+    let (expiry, set_expiry) = create_signal(String::new());
+    let (rate, set_rate) = create_signal("6.5".to_string());
+    let (dividend_yield, set_dividend_yield) = create_signal(String::new());
+    // Volatility to price at, or premium to solve from
+    let (solve_iv, set_solve_iv) = create_signal(false);
+    let (input, set_input) = create_signal(String::new());
+    let (result, set_result) = create_signal(None::<PriceResult>);
+    let (error, set_error) = create_signal(None::<String>);
+
+    let calculate = move |_| {
+        set_error.set(None);
+        if expiry.get().is_empty() {
+            set_error.set(Some("Choose the expiry".to_string()));
+            return;
+        }
+        let mut path = format!(
+            "/options/price?option_type={}&model={}&underlying_price={}&strike={}&expiry={}&rate={}",
+            option_type.get(),
+            model.get(),
+            encode(&underlying_price.get().trim().replace(',', "")),
+            encode(&strike.get().trim().replace(',', "")),
+            expiry.get(),
+            encode(rate.get().trim())
+        );
+        if model.get() == "black_scholes" && !dividend_yield.get().trim().is_empty() {
+            path.push_str(&format!(
+                "&dividend_yield={}",
+                encode(dividend_yield.get().trim())
+            ));
+        }
+        let field = if solve_iv.get() {
+            "premium"
+        } else {
+            "volatility"
+        };
+        path.push_str(&format!("&{}={}", field, encode(input.get().trim())));
+        spawn_local(async move {
+            match get_json::<PriceResult>(&path).await {
+                Ok(data) => set_result.set(Some(data)),
+                Err(e) => {
+                    set_result.set(None);
+                    set_error.set(Some(e));
+                }
+            }
+        });
+    };
+
+    let field = |label: &'static str,
+                 placeholder: &'static str,
+                 value: ReadSignal<String>,
+                 set: WriteSignal<String>| {
+        view! {
+            <div>
+                <label class="block text-sm font-medium mb-1">{label}</label>
+                <input
+                    type="text"
+                    class="w-28 px-3 py-2 bg-background border border-border rounded-md"
+                    placeholder=placeholder
+                    prop:value=value
+                    on:input=move |ev| set.set(event_target_value(&ev))
+                />
+            </div>
+        }
+    };
+
+    view! {
+        <div class="bg-card text-card-foreground rounded-lg shadow-sm p-6 mt-6">
+            <h3 class="text-lg font-medium">Option Calculator</h3>
+            <p class="text-sm text-muted-foreground mb-4">
+                "Black-Scholes on the spot or Black-76 on the futures price, expiring at the 15:30 close."
+            </p>
+
+            <div class="flex flex-wrap gap-2 items-end">
+                <div>
+                    <label class="block text-sm font-medium mb-1">Type</label>
+                    <select
+                        class="px-3 py-2 border border-input rounded-md"
+                        on:change=move |ev| set_option_type.set(event_target_value(&ev))
+                    >
+                        <option value="CE" selected=move || option_type.get() == "CE">Call</option>
+                        <option value="PE" selected=move || option_type.get() == "PE">Put</option>
+                    </select>
+                </div>
+                <div>
+                    <label class="block text-sm font-medium mb-1">Model</label>
+                    <select
+                        class="px-3 py-2 border border-input rounded-md"
+                        on:change=move |ev| set_model.set(event_target_value(&ev))
+                    >
+                        <option value="black_scholes" selected=move || model.get() == "black_scholes">Black-Scholes</option>
+                        <option value="black76" selected=move || model.get() == "black76">Black-76</option>
+                    </select>
+                </div>
+                {move || field(
+                    if model.get() == "black76" { "Futures price" } else { "Spot" },
+                    "48000",
+                    underlying_price,
+                    set_underlying_price,
+                )}
+                {field("Strike", "48000", strike, set_strike)}
+                <div>
+                    <label class="block text-sm font-medium mb-1">Expiry</label>
+                    <input
+                        type="date"
+                        class="px-3 py-2 bg-background border border-border rounded-md"
+                        prop:value=expiry
+                        on:input=move |ev| set_expiry.set(event_target_value(&ev))
+                    />
+                </div>
+                {field("Rate (%)", "6.5", rate, set_rate)}
+                {move || (model.get() == "black_scholes").then(|| {
+                    field("Dividend yield (%)", "0", dividend_yield, set_dividend_yield)
+                })}
+                <div>
+                    <label class="block text-sm font-medium mb-1">Solve from</label>
+                    <select
+                        class="px-3 py-2 border border-input rounded-md"
+                        on:change=move |ev| set_solve_iv.set(event_target_value(&ev) == "premium")
+                    >
+                        <option value="volatility" selected=move || !solve_iv.get()>Volatility (%)</option>
+                        <option value="premium" selected=move || solve_iv.get()>Premium</option>
+                    </select>
+                </div>
+                <div>
+                    <label class="block text-sm font-medium mb-1">
+                        {move || if solve_iv.get() { "Premium" } else { "Volatility (%)" }}
+                    </label>
+                    <input
+                        type="text"
+                        class="w-28 px-3 py-2 bg-background border border-border rounded-md"
+                        placeholder=move || if solve_iv.get() { "250" } else { "14" }
+                        prop:value=input
+                        on:input=move |ev| set_input.set(event_target_value(&ev))
+                    />
+                </div>
+                <button class="px-4 py-2 bg-primary text-primary-foreground rounded-md" on:click=calculate>
+                    Calculate
+                </button>
+            </div>
+
+            {move || error.get().map(|text| view! {
+                <p class="text-sm text-red-500 mt-4">{text}</p>
+            })}
+
+            {move || result.get().map(|r| {
+                let g = r.greeks.clone();
+                let cells = [
+                    ("Price", format!("{:.2}", g.price)),
+                    (if r.implied { "Implied vol" } else { "Volatility" }, format!("{:.2}%", r.volatility)),
+                    ("Delta", format!("{:.4}", g.delta)),
+                    ("Gamma", format!("{:.6}", g.gamma)),
+                    ("Theta / day", format!("{:.2}", g.theta)),
+                    ("Vega / 1%", format!("{:.2}", g.vega)),
+                    ("Rho / 1%", format!("{:.2}", g.rho)),
+                ];
+                view! {
+                    <div class="mt-6">
+                        <div class="grid grid-cols-2 md:grid-cols-4 lg:grid-cols-7 gap-4 text-sm">
+                            {cells.into_iter().map(|(label, value)| view! {
+                                <div>
+                                    <p class="text-muted-foreground">{label}</p>
+                                    <p class="font-medium">{value}</p>
+                                </div>
+                            }).collect::<Vec<_>>()}
+                        </div>
+                        <p class="text-xs text-muted-foreground mt-2">
+                            {format!("{:.1} days to expiry", r.years * 365.0)}
+                        </p>
+                    </div>
+                }
+            })}
+        </div>
+    }
+}
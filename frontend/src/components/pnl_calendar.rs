@@ -0,0 +1,186 @@
+use chrono::{Datelike, Duration, Months, NaiveDate};
+use leptos::*;
+use serde::{Deserialize, Serialize};
+
+use crate::utils::api::*;
+
+#[derive(Clone, Debug, Serialize, Deserialize)]
+struct PnlDay {
+    date: NaiveDate,
+    net_pnl: f64,
+    trades: usize,
+    is_trading_day: bool,
+    holiday: Option<String>,
+}
+
+#[derive(Clone, Debug, Serialize, Deserialize)]
+struct PnlWeek {
+    start: NaiveDate,
+    net_pnl: f64,
+    trades: usize,
+}
+
+#[derive(Clone, Debug, Serialize, Deserialize)]
+struct PnlCalendarData {
+    month: NaiveDate,
+    days: Vec<PnlDay>,
+    weeks: Vec<PnlWeek>,
+    net_pnl: f64,
+    trades: usize,
+    winning_days: usize,
+    losing_days: usize,
+}
+
+const WEEKDAYS: [&str; 7] = ["Mon", "Tue", "Wed", "Thu", "Fri", "Sat", "Sun"];
+
+fn pnl_text_class(value: f64) -> &'static str {
+    if value > 0.0 {
+        "text-green-500"
+    } else if value < 0.0 {
+        "text-red-500"
+    } else {
+        "text-muted-foreground"
+    }
+}
+
+/// Cell background: green or red, stronger the larger the day is relative
+/// to the month's biggest day.
+fn day_class(day: &PnlDay, largest: f64, selected: bool) -> String {
+    let shade = if day.trades == 0 || day.net_pnl == 0.0 {
+        if day.is_trading_day { "" } else { "bg-muted" }
+    } else {
+        let strong = day.net_pnl.abs() >= largest * 0.5;
+        match (day.net_pnl > 0.0, strong) {
+            (true, true) => "bg-green-500/40",
+            (true, false) => "bg-green-500/15",
+            (false, true) => "bg-red-500/40",
+            (false, false) => "bg-red-500/15",
+        }
+    };
+    let ring = if selected { "ring-2 ring-primary" } else { "" };
+    format!("p-2 h-20 rounded-md border border-border text-left align-top cursor-pointer {} {}", shade, ring)
+}
+
+/// Month view of realized PnL. Clicking a day reports it as `YYYY-MM-DD`.
+#[component]
+pub fn PnlCalendar(
+    #[prop(into)] selected: Signal<String>,
+    #[prop(into)] on_select: Callback<String>,
+    /// Bumped by the caller when journal trades change.
+    #[prop(optional, into)]
+    reload: Option<Signal<usize>>,
+) -> impl IntoView {
+    let today = chrono::Local::now().date_naive();
+    let (month, set_month) = create_signal(today.with_day(1).unwrap());
+    let (data, set_data) = create_signal(None::<PnlCalendarData>);
+    let (error, set_error) = create_signal(None::<String>);
+
+    create_effect(move |_| {
+        if let Some(reload) = reload {
+            reload.track();
+        }
+        let path = format!("/journal/calendar?month={}", month.get().format("%Y-%m"));
+        spawn_local(async move {
+            match get_json::<PnlCalendarData>(&path).await {
+                Ok(result) => {
+                    set_error.set(None);
+                    set_data.set(Some(result));
+                }
+                Err(e) => set_error.set(Some(e)),
+            }
+        });
+    });
+
+    let previous = move |_| set_month.update(|m| *m = *m - Months::new(1));
+    let next = move |_| set_month.update(|m| *m = *m + Months::new(1));
+
+    view! {
+        <div class="bg-card text-card-foreground rounded-lg shadow-sm p-6 mt-6">
+            <div class="flex justify-between items-center mb-4">
+                <button class="px-3 py-1 bg-secondary text-secondary-foreground rounded-md" on:click=previous>
+                    "‹"
+                </button>
+                <div class="text-center">
+                    <h3 class="text-lg font-medium">{move || month.get().format("%B %Y").to_string()}</h3>
+                    {move || data.get().map(|d| view! {
+                        <p class="text-sm">
+                            <span class=pnl_text_class(d.net_pnl)>{format!("₹{:.2}", d.net_pnl)}</span>
+                            <span class="text-muted-foreground">
+                                {format!(
+                                    " · {} trades · {} green / {} red days",
+                                    d.trades, d.winning_days, d.losing_days
+                                )}
+                            </span>
+                        </p>
+                    })}
+                </div>
+                <button class="px-3 py-1 bg-secondary text-secondary-foreground rounded-md" on:click=next>
+                    "›"
+                </button>
+            </div>
+
+            {move || error.get().map(|message| view! {
+                <p class="text-sm text-red-500 mb-3">{message}</p>
+            })}
+
+            <table class="w-full table-fixed border-separate border-spacing-1">
+                <thead>
+                    <tr>
+                        {WEEKDAYS.into_iter().map(|day| view! {
+                            <th class="text-left p-1 text-muted-foreground font-medium text-xs">{day}</th>
+                        }).collect::<Vec<_>>()}
+                        <th class="text-left p-1 text-muted-foreground font-medium text-xs">Week</th>
+                    </tr>
+                </thead>
+                <tbody>
+                    {move || data.get().map(|d| {
+                        let largest = d.days.iter().map(|day| day.net_pnl.abs()).fold(0.0, f64::max);
+                        d.weeks.into_iter().map(|week| {
+                            let cells = (0..7).map(|offset| {
+                                let date = week.start + Duration::days(offset);
+                                match d.days.iter().find(|day| day.date == date).cloned() {
+                                    Some(day) => {
+                                        let key = date.format("%Y-%m-%d").to_string();
+                                        let is_selected = key.clone();
+                                        let shaded = day.clone();
+                                        view! {
+                                            <td
+                                                class=move || day_class(&shaded, largest, selected.get() == is_selected)
+                                                title=day.holiday.clone().unwrap_or_default()
+                                                on:click=move |_| on_select.call(key.clone())
+                                            >
+                                                <div class="text-xs text-muted-foreground">{date.day().to_string()}</div>
+                                                {(day.trades > 0).then(|| view! {
+                                                    <div class=format!("text-sm font-medium {}", pnl_text_class(day.net_pnl))>
+                                                        {format!("₹{:.0}", day.net_pnl)}
+                                                    </div>
+                                                    <div class="text-xs text-muted-foreground">
+                                                        {format!("{} trades", day.trades)}
+                                                    </div>
+                                                })}
+                                            </td>
+                                        }.into_view()
+                                    }
+                                    None => view! { <td></td> }.into_view(),
+                                }
+                            }).collect::<Vec<_>>();
+                            view! {
+                                <tr>
+                                    {cells}
+                                    <td class="p-2 align-top">
+                                        <div class=format!("text-sm font-medium {}", pnl_text_class(week.net_pnl))>
+                                            {format!("₹{:.0}", week.net_pnl)}
+                                        </div>
+                                        <div class="text-xs text-muted-foreground">
+                                            {format!("{} trades", week.trades)}
+                                        </div>
+                                    </td>
+                                </tr>
+                            }
+                        }).collect::<Vec<_>>()
+                    })}
+                </tbody>
+            </table>
+        </div>
+    }
+}
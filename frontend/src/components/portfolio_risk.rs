@@ -0,0 +1,357 @@
+use leptos::*;
+use serde::{Deserialize, Serialize};
+
+use crate::components::stat_card::*;
+use crate::utils::api::*;
+
+#[derive(Clone, Debug, Serialize, Deserialize)]
+struct ValueAtRisk {
+    confidence: f64,
+    historical: f64,
+    parametric: f64,
+    expected_shortfall: f64,
+    historical_amount: f64,
+    parametric_amount: f64,
+    expected_shortfall_amount: f64,
+}
+
+#[derive(Clone, Debug, Serialize, Deserialize)]
+struct VolatilityPoint {
+    date: String,
+    portfolio: f64,
+    benchmark: Option<f64>,
+}
+
+#[derive(Clone, Debug, Serialize, Deserialize)]
+struct HoldingRisk {
+    symbol: String,
+    weight: f64,
+    volatility: Option<f64>,
+    beta: Option<f64>,
+    risk_contribution: Option<f64>,
+}
+
+#[derive(Clone, Debug, Default, Serialize, Deserialize)]
+struct CorrelationMatrix {
+    symbols: Vec<String>,
+    values: Vec<Vec<f64>>,
+}
+
+#[derive(Clone, Debug, Serialize, Deserialize)]
+struct RiskReport {
+    from: Option<String>,
+    to: Option<String>,
+    observations: usize,
+    benchmark: Option<String>,
+    volatility: Option<f64>,
+    beta: Option<f64>,
+    benchmark_correlation: Option<f64>,
+    value_at_risk: Vec<ValueAtRisk>,
+    rolling_volatility: Vec<VolatilityPoint>,
+    holdings: Vec<HoldingRisk>,
+    correlation: CorrelationMatrix,
+    effective_holdings: Option<f64>,
+    warnings: Vec<String>,
+    notes: Vec<String>,
+}
+
+const WINDOWS: [(usize, &str); 4] = [
+    (63, "3 months"),
+    (126, "6 months"),
+    (252, "1 year"),
+    (504, "2 years"),
+];
+
+const CHART_WIDTH: f64 = 800.0;
+const CHART_HEIGHT: f64 = 160.0;
+
+fn optional(value: Option<f64>, decimals: usize, suffix: &str) -> String {
+    value.map_or("-".to_string(), |v| format!("{:.*}{}", decimals, v, suffix))
+}
+
+/// Red for moving together, blue for moving apart.
+fn heat(value: f64) -> String {
+    let alpha = value.abs().min(1.0) * 0.8;
+    if value >= 0.0 {
+        format!("background-color: rgba(239, 68, 68, {:.2})", alpha)
+    } else {
+        format!("background-color: rgba(59, 130, 246, {:.2})", alpha)
+    }
+}
+
+fn polyline(values: &[f64], max: f64) -> String {
+    let step = CHART_WIDTH / (values.len().max(2) - 1) as f64;
+    values
+        .iter()
+        .enumerate()
+        .map(|(i, v)| {
+            format!(
+                "{:.1},{:.1}",
+                i as f64 * step,
+                CHART_HEIGHT - v / max * CHART_HEIGHT
+            )
+        })
+        .collect::<Vec<_>>()
+        .join(" ")
+}
+
+/// Beta, VaR, rolling volatility and correlations of the current holdings,
+/// for the portfolio page.
+#[component]
+pub fn PortfolioRisk(
+    /// Index to measure beta against.
+    #[prop(into)]
+    benchmark: Signal<String>,
+    /// Bumped when the holdings change.
+    #[prop(into)]
+    revision: Signal<usize>,
+) -> impl IntoView {
+    let (days, set_days) = create_signal(252usize);
+    let (report, set_report) = create_signal(None::<RiskReport>);
+    let (error, set_error) = create_signal(None::<String>);
+
+    create_effect(move |_| {
+        revision.track();
+        let path = format!(
+            "/portfolio/risk?days={}&benchmark={}",
+            days.get(),
+            encode(&benchmark.get())
+        );
+        spawn_local(async move {
+            match get_json::<RiskReport>(&path).await {
+                Ok(data) => {
+                    set_error.set(None);
+                    set_report.set(Some(data));
+                }
+                Err(e) => set_error.set(Some(e)),
+            }
+        });
+    });
+
+    view! {
+        <div class="bg-card text-card-foreground rounded-lg shadow-sm p-6 mt-6">
+            <div class="flex justify-between items-center mb-4">
+                <div>
+                    <h3 class="text-lg font-medium">Risk</h3>
+                    <p class="text-xs text-muted-foreground">
+                        {move || report.get().and_then(|r| r.from.zip(r.to).map(|(from, to)| format!(
+                            "Today's holdings at today's weights, {} daily returns from {} to {}",
+                            r.observations, from, to
+                        )))}
+                    </p>
+                </div>
+                <select
+                    class="px-3 py-2 border border-input rounded-md text-sm"
+                    on:change=move |ev| {
+                        if let Ok(value) = event_target_value(&ev).parse() {
+                            set_days.set(value);
+                        }
+                    }
+                >
+                    {WINDOWS.into_iter().map(|(value, label)| view! {
+                        <option value=value.to_string() selected=move || days.get() == value>{label}</option>
+                    }).collect::<Vec<_>>()}
+                </select>
+            </div>
+
+            {move || error.get().map(|text| view! {
+                <p class="text-sm text-red-500 mb-4">{text}</p>
+            })}
+
+            {move || report.get().map(|r| {
+                let var95 = r.value_at_risk.iter().find(|v| v.confidence == 95.0).cloned();
+                let benchmark_label = r.benchmark.clone().unwrap_or_else(|| "the index".to_string());
+                view! {
+                    <div>
+                        <div class="grid grid-cols-2 md:grid-cols-4 gap-4">
+                            <StatCard stat=StatData {
+                                title: "Volatility".to_string(),
+                                value: optional(r.volatility, 1, "%"),
+                                description: Some("Annualized".to_string()),
+                            } />
+                            <StatCard stat=StatData {
+                                title: "Beta".to_string(),
+                                value: optional(r.beta, 2, ""),
+                                description: Some(format!(
+                                    "vs {}, correlation {}",
+                                    benchmark_label,
+                                    optional(r.benchmark_correlation, 2, "")
+                                )),
+                            } />
+                            <StatCard stat=StatData {
+                                title: "1-Day VaR (95%)".to_string(),
+                                value: var95.as_ref().map_or("-".to_string(), |v| format!("₹{:.0}", v.historical_amount)),
+                                description: var95.as_ref().map(|v| format!("{:.2}% historical", v.historical)),
+                            } />
+                            <StatCard stat=StatData {
+                                title: "Expected Shortfall (95%)".to_string(),
+                                value: var95.as_ref().map_or("-".to_string(), |v| format!("₹{:.0}", v.expected_shortfall_amount)),
+                                description: Some("Average loss on the worst 5% of days".to_string()),
+                            } />
+                        </div>
+
+                        {(!r.warnings.is_empty()).then(|| view! {
+                            <div class="mt-4 p-4 rounded-md border border-yellow-500">
+                                <h4 class="font-medium mb-2">Concentration</h4>
+                                <ul class="list-disc list-inside text-sm space-y-1">
+                                    {r.warnings.iter().map(|w| view! { <li>{w.clone()}</li> }).collect::<Vec<_>>()}
+                                </ul>
+                            </div>
+                        })}
+                        {(!r.notes.is_empty()).then(|| view! {
+                            <ul class="list-disc list-inside text-sm text-muted-foreground mt-4">
+                                {r.notes.iter().map(|n| view! { <li>{n.clone()}</li> }).collect::<Vec<_>>()}
+                            </ul>
+                        })}
+
+                        <VarTable rows=r.value_at_risk.clone() />
+                        <RollingVolatility points=r.rolling_volatility.clone() benchmark=benchmark_label />
+                        <HoldingRiskTable holdings=r.holdings.clone() effective=r.effective_holdings />
+                        <CorrelationHeatmap matrix=r.correlation.clone() />
+                    </div>
+                }
+            })}
+        </div>
+    }
+}
+
+#[component]
+fn VarTable(rows: Vec<ValueAtRisk>) -> impl IntoView {
+    view! {
+        <div class="overflow-x-auto mt-6">
+            <h4 class="font-medium mb-2">One-Day Loss Estimates</h4>
+            <table class="w-full text-sm">
+                <thead>
+                    <tr class="border-b border-border">
+                        <th class="text-left p-3 text-muted-foreground font-medium">Confidence</th>
+                        <th class="text-left p-3 text-muted-foreground font-medium">Historical VaR</th>
+                        <th class="text-left p-3 text-muted-foreground font-medium">Parametric VaR</th>
+                        <th class="text-left p-3 text-muted-foreground font-medium">Expected Shortfall</th>
+                    </tr>
+                </thead>
+                <tbody>
+                    {rows.into_iter().map(|v| view! {
+                        <tr class="border-b border-border">
+                            <td class="p-3">{format!("{:.0}%", v.confidence)}</td>
+                            <td class="p-3">{format!("₹{:.0} ({:.2}%)", v.historical_amount, v.historical)}</td>
+                            <td class="p-3">{format!("₹{:.0} ({:.2}%)", v.parametric_amount, v.parametric)}</td>
+                            <td class="p-3">{format!("₹{:.0} ({:.2}%)", v.expected_shortfall_amount, v.expected_shortfall)}</td>
+                        </tr>
+                    }).collect::<Vec<_>>()}
+                </tbody>
+            </table>
+            <p class="text-xs text-muted-foreground mt-2">
+                "Historical uses the worst days actually seen; parametric assumes normally distributed returns, which understates fat tails."
+            </p>
+        </div>
+    }
+}
+
+#[component]
+fn RollingVolatility(points: Vec<VolatilityPoint>, benchmark: String) -> impl IntoView {
+    if points.is_empty() {
+        return view! { <div></div> }.into_view();
+    }
+    let portfolio: Vec<f64> = points.iter().map(|p| p.portfolio).collect();
+    let index: Vec<f64> = points.iter().filter_map(|p| p.benchmark).collect();
+    let max = portfolio
+        .iter()
+        .chain(&index)
+        .fold(1.0_f64, |m, v| m.max(*v))
+        * 1.1;
+    let index_line = (index.len() == points.len()).then(|| polyline(&index, max));
+    view! {
+        <div class="mt-6">
+            <h4 class="font-medium mb-2">Rolling 20-Day Volatility</h4>
+            <svg
+                class="w-full h-40"
+                viewBox=format!("0 0 {} {}", CHART_WIDTH, CHART_HEIGHT)
+                preserveAspectRatio="none"
+            >
+                {index_line.map(|line| view! {
+                    <polyline points=line fill="none" stroke="currentColor" stroke-width="2" class="text-yellow-500" />
+                })}
+                <polyline points=polyline(&portfolio, max) fill="none" stroke="currentColor" stroke-width="2" class="text-primary" />
+            </svg>
+            <div class="flex gap-4 text-xs text-muted-foreground mt-2">
+                <span class="text-primary">Portfolio</span>
+                <span class="text-yellow-500">{benchmark}</span>
+                <span class="ml-auto">
+                    {format!("{} to {}, peak {:.0}%", points[0].date, points[points.len() - 1].date, max / 1.1)}
+                </span>
+            </div>
+        </div>
+    }
+    .into_view()
+}
+
+#[component]
+fn HoldingRiskTable(holdings: Vec<HoldingRisk>, effective: Option<f64>) -> impl IntoView {
+    view! {
+        <div class="overflow-x-auto mt-6">
+            <h4 class="font-medium mb-2">By Holding</h4>
+            <table class="w-full text-sm">
+                <thead>
+                    <tr class="border-b border-border">
+                        <th class="text-left p-3 text-muted-foreground font-medium">Symbol</th>
+                        <th class="text-left p-3 text-muted-foreground font-medium">Weight</th>
+                        <th class="text-left p-3 text-muted-foreground font-medium">Volatility</th>
+                        <th class="text-left p-3 text-muted-foreground font-medium">Beta</th>
+                        <th class="text-left p-3 text-muted-foreground font-medium">Share of Risk</th>
+                    </tr>
+                </thead>
+                <tbody>
+                    {holdings.into_iter().map(|h| view! {
+                        <tr class="border-b border-border">
+                            <td class="p-3 font-medium">{h.symbol}</td>
+                            <td class="p-3">{format!("{:.1}%", h.weight)}</td>
+                            <td class="p-3">{optional(h.volatility, 1, "%")}</td>
+                            <td class="p-3">{optional(h.beta, 2, "")}</td>
+                            <td class="p-3">{optional(h.risk_contribution, 1, "%")}</td>
+                        </tr>
+                    }).collect::<Vec<_>>()}
+                </tbody>
+            </table>
+            <p class="text-xs text-muted-foreground mt-2">
+                {effective.map(|e| format!(
+                    "As concentrated as {:.1} equally weighted stocks",
+                    e
+                ))}
+            </p>
+        </div>
+    }
+}
+
+#[component]
+fn CorrelationHeatmap(matrix: CorrelationMatrix) -> impl IntoView {
+    if matrix.symbols.len() < 2 {
+        return view! { <div></div> }.into_view();
+    }
+    let symbols = matrix.symbols.clone();
+    view! {
+        <div class="overflow-x-auto mt-6">
+            <h4 class="font-medium mb-2">Correlation of Daily Returns</h4>
+            <table class="text-xs">
+                <thead>
+                    <tr>
+                        <th class="p-2"></th>
+                        {symbols.iter().map(|s| view! {
+                            <th class="p-2 text-muted-foreground font-medium">{s.clone()}</th>
+                        }).collect::<Vec<_>>()}
+                    </tr>
+                </thead>
+                <tbody>
+                    {matrix.values.into_iter().zip(matrix.symbols).map(|(row, symbol)| view! {
+                        <tr>
+                            <th class="p-2 text-left text-muted-foreground font-medium">{symbol}</th>
+                            {row.into_iter().map(|value| view! {
+                                <td class="p-2 text-center w-14" style=heat(value)>{format!("{:.2}", value)}</td>
+                            }).collect::<Vec<_>>()}
+                        </tr>
+                    }).collect::<Vec<_>>()}
+                </tbody>
+            </table>
+        </div>
+    }
+    .into_view()
+}
@@ -0,0 +1,112 @@
+use leptos::*;
+use leptos_router::*;
+use serde::{Deserialize, Serialize};
+
+use crate::utils::api::*;
+
+#[derive(Clone, Debug, Serialize, Deserialize)]
+struct SummaryData {
+    holdings: usize,
+    invested: f64,
+    value: f64,
+    day_change: f64,
+    day_change_pct: Option<f64>,
+    total_gain: f64,
+    xirr: Option<f64>,
+}
+
+#[derive(Clone, Debug, Serialize, Deserialize)]
+struct BenchmarkData {
+    symbol: String,
+    xirr: Option<f64>,
+}
+
+#[derive(Clone, Debug, Serialize, Deserialize)]
+struct ReportData {
+    summary: SummaryData,
+    benchmarks: Vec<BenchmarkData>,
+}
+
+fn signed_class(value: f64) -> &'static str {
+    if value >= 0.0 {
+        "text-green-500"
+    } else {
+        "text-red-500"
+    }
+}
+
+/// Portfolio value, today's move and return against the first benchmark,
+/// for the dashboard.
+#[component]
+pub fn PortfolioSummary() -> impl IntoView {
+    let (report, set_report) = create_signal(None::<ReportData>);
+
+    spawn_local(async move {
+        if let Ok(data) = get_json::<ReportData>("/portfolio").await {
+            set_report.set(Some(data));
+        }
+    });
+
+    view! {
+        <div class="bg-card text-card-foreground rounded-lg shadow-sm p-6 mb-8">
+            <div class="flex justify-between items-center mb-4">
+                <h2 class="text-xl font-bold">Portfolio</h2>
+                <A href="/portfolio" class="text-primary font-medium">"Details →"</A>
+            </div>
+            {move || match report.get() {
+                None => view! {
+                    <p class="text-muted-foreground">Loading...</p>
+                }.into_view(),
+                Some(r) if r.summary.holdings == 0 => view! {
+                    <p class="text-muted-foreground">
+                        No holdings yet. Import them from the journal or a holdings file on the Portfolio page.
+                    </p>
+                }.into_view(),
+                Some(r) => {
+                    let s = r.summary;
+                    let benchmark = r.benchmarks.first().cloned();
+                    view! {
+                        <div class="grid grid-cols-2 md:grid-cols-4 gap-4">
+                            <div>
+                                <p class="text-sm text-muted-foreground">Value</p>
+                                <p class="text-2xl font-bold">{format!("₹{:.0}", s.value)}</p>
+                                <p class="text-xs text-muted-foreground">
+                                    {format!("{} holdings, ₹{:.0} invested", s.holdings, s.invested)}
+                                </p>
+                            </div>
+                            <div>
+                                <p class="text-sm text-muted-foreground">Today</p>
+                                <p class=format!("text-2xl font-bold {}", signed_class(s.day_change))>
+                                    {format!("{:+.0}", s.day_change)}
+                                </p>
+                                <p class="text-xs text-muted-foreground">
+                                    {s.day_change_pct.map(|p| format!("{:+.2}%", p))}
+                                </p>
+                            </div>
+                            <div>
+                                <p class="text-sm text-muted-foreground">Total Gain</p>
+                                <p class=format!("text-2xl font-bold {}", signed_class(s.total_gain))>
+                                    {format!("{:+.0}", s.total_gain)}
+                                </p>
+                                <p class="text-xs text-muted-foreground">Including realized and dividends</p>
+                            </div>
+                            <div>
+                                <p class="text-sm text-muted-foreground">XIRR</p>
+                                <p class="text-2xl font-bold">
+                                    {s.xirr.map_or("-".to_string(), |x| format!("{:.1}%", x))}
+                                </p>
+                                <p class="text-xs text-muted-foreground">
+                                    {benchmark.map(|b| format!(
+                                        "{} {}",
+                                        b.symbol,
+                                        b.xirr.map_or("-".to_string(), |x| format!("{:.1}%", x))
+                                    ))}
+                                </p>
+                            </div>
+                        </div>
+                    }.into_view()
+                }
+            }}
+        </div>
+    }
+}
@@ -0,0 +1,312 @@
+use leptos::*;
+use serde::{Deserialize, Serialize};
+
+use crate::utils::api::*;
+
+#[derive(Clone, Debug, Serialize, Deserialize)]
+struct TargetWeight {
+    symbol: String,
+    price: f64,
+    current_weight: f64,
+    target_weight: f64,
+    expected_return: Option<f64>,
+    volatility: Option<f64>,
+    risk_contribution: Option<f64>,
+}
+
+#[derive(Clone, Debug, Serialize, Deserialize)]
+struct RebalanceTrade {
+    symbol: String,
+    side: String,
+    quantity: u32,
+    price: f64,
+    value: f64,
+    charges: f64,
+}
+
+#[derive(Clone, Debug, Default, Serialize, Deserialize)]
+struct PortfolioStats {
+    expected_return: Option<f64>,
+    volatility: Option<f64>,
+}
+
+#[derive(Clone, Debug, Serialize, Deserialize)]
+struct RebalancePlan {
+    label: String,
+    max_weight: f64,
+    observations: usize,
+    value: f64,
+    cash: f64,
+    targets: Vec<TargetWeight>,
+    current: PortfolioStats,
+    target: PortfolioStats,
+    trades: Vec<RebalanceTrade>,
+    buy_value: f64,
+    sell_value: f64,
+    charges: f64,
+    cash_left: f64,
+    notes: Vec<String>,
+}
+
+const METHODS: [(&str, &str); 4] = [
+    ("equal_weight", "Equal weight"),
+    ("minimum_variance", "Minimum variance"),
+    ("risk_parity", "Risk parity"),
+    ("mean_variance", "Mean-variance"),
+];
+
+fn percent(value: Option<f64>) -> String {
+    value.map_or("-".to_string(), |v| format!("{:.1}%", v))
+}
+
+/// Target weights for the portfolio by one of the optimisers, and the
+/// trades and charges to get there.
+#[component]
+pub fn RebalancePlanner() -> impl IntoView {
+    let (method, set_method) = create_signal("risk_parity".to_string());
+    let (max_weight, set_max_weight) = create_signal("10".to_string());
+    let (risk_aversion, set_risk_aversion) = create_signal("3".to_string());
+    let (cash, set_cash) = create_signal(String::new());
+    let (min_trade, set_min_trade) = create_signal("1000".to_string());
+    let (symbols, set_symbols) = create_signal(String::new());
+    let (plan, set_plan) = create_signal(None::<RebalancePlan>);
+    // Settings the shown plan was made with, for its basket download
+    let (planned, set_planned) = create_signal(String::new());
+    let (error, set_error) = create_signal(None::<String>);
+    let (loading, set_loading) = create_signal(false);
+
+    let query = move || {
+        let mut query = format!(
+            "method={}&max_weight={}&min_trade={}",
+            method.get(),
+            encode(max_weight.get().trim()),
+            encode(min_trade.get().trim())
+        );
+        if method.get() == "mean_variance" {
+            query.push_str(&format!(
+                "&risk_aversion={}",
+                encode(risk_aversion.get().trim())
+            ));
+        }
+        if !cash.get().trim().is_empty() {
+            query.push_str(&format!(
+                "&cash={}",
+                encode(&cash.get().trim().replace(',', ""))
+            ));
+        }
+        if !symbols.get().trim().is_empty() {
+            query.push_str(&format!("&symbols={}", encode(symbols.get().trim())));
+        }
+        query
+    };
+
+    let run = move |_| {
+        let settings = query();
+        let path = format!("/portfolio/rebalance?{}", settings);
+        set_planned.set(settings);
+        set_error.set(None);
+        set_loading.set(true);
+        spawn_local(async move {
+            match get_json::<RebalancePlan>(&path).await {
+                Ok(data) => set_plan.set(Some(data)),
+                Err(e) => {
+                    set_plan.set(None);
+                    set_error.set(Some(e));
+                }
+            }
+            set_loading.set(false);
+        });
+    };
+
+    let input = |label: &'static str,
+                 placeholder: &'static str,
+                 value: ReadSignal<String>,
+                 set: WriteSignal<String>| {
+        view! {
+            <div>
+                <label class="block text-sm font-medium mb-1">{label}</label>
+                <input
+                    type="text"
+                    class="w-28 px-3 py-2 bg-background border border-border rounded-md"
+                    placeholder=placeholder
+                    prop:value=value
+                    on:input=move |ev| set.set(event_target_value(&ev))
+                />
+            </div>
+        }
+    };
+
+    view! {
+        <div class="bg-card text-card-foreground rounded-lg shadow-sm p-6 mt-6">
+            <h3 class="text-lg font-medium">Rebalance Portfolio</h3>
+            <p class="text-sm text-muted-foreground mb-4">
+                "Target weights for your holdings and any stocks you add, from a year of daily returns, with the trades and charges to get there."
+            </p>
+
+            <div class="flex flex-wrap gap-2 items-end">
+                <div>
+                    <label class="block text-sm font-medium mb-1">Method</label>
+                    <select
+                        class="px-3 py-2 border border-input rounded-md"
+                        on:change=move |ev| set_method.set(event_target_value(&ev))
+                    >
+                        {METHODS.into_iter().map(|(value, label)| view! {
+                            <option value=value selected=move || method.get() == value>{label}</option>
+                        }).collect::<Vec<_>>()}
+                    </select>
+                </div>
+                {input("Max per stock (%)", "10", max_weight, set_max_weight)}
+                {move || (method.get() == "mean_variance").then(|| {
+                    input("Risk aversion", "3", risk_aversion, set_risk_aversion)
+                })}
+                {input("Add cash (₹)", "0", cash, set_cash)}
+                {input("Min trade (₹)", "1000", min_trade, set_min_trade)}
+                <div>
+                    <label class="block text-sm font-medium mb-1">Also consider</label>
+                    <input
+                        type="text"
+                        class="w-56 px-3 py-2 bg-background border border-border rounded-md"
+                        placeholder="TCS, INFY"
+                        prop:value=symbols
+                        on:input=move |ev| set_symbols.set(event_target_value(&ev))
+                    />
+                </div>
+                <button
+                    class="px-4 py-2 bg-primary text-primary-foreground rounded-md"
+                    disabled=move || loading.get()
+                    on:click=run
+                >
+                    {move || if loading.get() { "Planning..." } else { "Plan" }}
+                </button>
+            </div>
+
+            {move || error.get().map(|text| view! {
+                <p class="text-sm text-red-500 mt-4">{text}</p>
+            })}
+
+            {move || plan.get().map(|p| view! {
+                <div class="mt-6">
+                    <div class="grid grid-cols-2 md:grid-cols-4 gap-4 text-sm">
+                        <div>
+                            <p class="text-muted-foreground">Expected return</p>
+                            <p class="font-medium">
+                                {format!("{} → {}", percent(p.current.expected_return), percent(p.target.expected_return))}
+                            </p>
+                        </div>
+                        <div>
+                            <p class="text-muted-foreground">Volatility</p>
+                            <p class="font-medium">
+                                {format!("{} → {}", percent(p.current.volatility), percent(p.target.volatility))}
+                            </p>
+                        </div>
+                        <div>
+                            <p class="text-muted-foreground">Trades</p>
+                            <p class="font-medium">
+                                {format!("Buy ₹{:.0}, sell ₹{:.0}", p.buy_value, p.sell_value)}
+                            </p>
+                        </div>
+                        <div>
+                            <p class="text-muted-foreground">Charges</p>
+                            <p class="font-medium">{format!("₹{:.2}", p.charges)}</p>
+                            <p class=if p.cash_left < 0.0 { "text-xs text-red-500" } else { "text-xs text-muted-foreground" }>
+                                {format!("Cash left ₹{:.0}", p.cash_left)}
+                            </p>
+                        </div>
+                    </div>
+                    <p class="text-xs text-muted-foreground mt-2">
+                        {format!(
+                            "{} over {} daily returns, at most {:.1}% per stock; holdings worth ₹{:.0}{}",
+                            p.label,
+                            p.observations,
+                            p.max_weight,
+                            p.value,
+                            if p.cash != 0.0 { format!(" plus ₹{:.0} cash", p.cash) } else { String::new() }
+                        )}
+                    </p>
+
+                    <div class="overflow-x-auto mt-4">
+                        <table class="w-full text-sm">
+                            <thead>
+                                <tr class="border-b border-border">
+                                    {["Symbol", "Price", "Current", "Target", "", "Return", "Volatility", "Share of Risk"]
+                                        .into_iter()
+                                        .map(|h| view! { <th class="text-left p-3 text-muted-foreground font-medium">{h}</th> })
+                                        .collect::<Vec<_>>()}
+                                </tr>
+                            </thead>
+                            <tbody>
+                                {p.targets.iter().map(|t| view! {
+                                    <tr class="border-b border-border">
+                                        <td class="p-3 font-medium">{t.symbol.clone()}</td>
+                                        <td class="p-3">{format!("{:.2}", t.price)}</td>
+                                        <td class="p-3">{format!("{:.1}%", t.current_weight)}</td>
+                                        <td class="p-3 font-medium">{format!("{:.1}%", t.target_weight)}</td>
+                                        <td class="p-3 w-40">
+                                            <div class="h-2 bg-secondary rounded-md overflow-hidden">
+                                                <div class="h-2 bg-primary" style=format!("width: {:.1}%", t.target_weight)></div>
+                                            </div>
+                                        </td>
+                                        <td class="p-3">{percent(t.expected_return)}</td>
+                                        <td class="p-3">{percent(t.volatility)}</td>
+                                        <td class="p-3">{percent(t.risk_contribution)}</td>
+                                    </tr>
+                                }).collect::<Vec<_>>()}
+                            </tbody>
+                        </table>
+                    </div>
+
+                    <div class="flex justify-between items-center mt-6 mb-2">
+                        <h4 class="font-medium">Trades</h4>
+                        {(!p.trades.is_empty()).then(|| view! {
+                            <a
+                                class="px-4 py-2 bg-secondary text-secondary-foreground rounded-md text-sm"
+                                href=move || format!("{}/portfolio/rebalance/basket.csv?{}", API_BASE, planned.get())
+                            >
+                                Download Basket
+                            </a>
+                        })}
+                    </div>
+                    {if p.trades.is_empty() {
+                        view! {
+                            <p class="text-sm text-muted-foreground">The holdings are already at their targets</p>
+                        }.into_view()
+                    } else {
+                        view! {
+                            <table class="w-full text-sm">
+                                <thead>
+                                    <tr class="border-b border-border">
+                                        {["Side", "Symbol", "Qty", "Price", "Value", "Charges"]
+                                            .into_iter()
+                                            .map(|h| view! { <th class="text-left p-3 text-muted-foreground font-medium">{h}</th> })
+                                            .collect::<Vec<_>>()}
+                                    </tr>
+                                </thead>
+                                <tbody>
+                                    {p.trades.iter().map(|t| {
+                                        let buy = t.side == "Buy";
+                                        view! {
+                                            <tr class="border-b border-border">
+                                                <td class=if buy { "p-3 text-green-500" } else { "p-3 text-red-500" }>{t.side.clone()}</td>
+                                                <td class="p-3 font-medium">{t.symbol.clone()}</td>
+                                                <td class="p-3">{t.quantity}</td>
+                                                <td class="p-3">{format!("{:.2}", t.price)}</td>
+                                                <td class="p-3">{format!("₹{:.0}", t.value)}</td>
+                                                <td class="p-3">{format!("₹{:.2}", t.charges)}</td>
+                                            </tr>
+                                        }
+                                    }).collect::<Vec<_>>()}
+                                </tbody>
+                            </table>
+                        }.into_view()
+                    }}
+
+                    {(!p.notes.is_empty()).then(|| view! {
+                        <ul class="list-disc list-inside text-sm text-muted-foreground mt-4">
+                            {p.notes.iter().map(|n| view! { <li>{n.clone()}</li> }).collect::<Vec<_>>()}
+                        </ul>
+                    })}
+                </div>
+            })}
+        </div>
+    }
+}
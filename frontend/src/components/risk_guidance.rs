@@ -0,0 +1,228 @@
+use leptos::*;
+use leptos_router::*;
+use serde::{Deserialize, Serialize};
+
+use crate::utils::api::*;
+
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct Allocation {
+    pub asset_class: String,
+    pub percent: f64,
+}
+
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct Guidance {
+    pub summary: String,
+    pub allocation: Vec<Allocation>,
+    pub max_position: f64,
+    pub risk_per_trade: f64,
+    pub max_volatility: Option<f64>,
+    pub max_debt_to_equity: Option<f64>,
+    pub min_score: Option<f64>,
+}
+
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct Assessment {
+    pub capacity: f64,
+    pub tolerance: f64,
+    pub bucket: String,
+    pub label: String,
+    pub guidance: Guidance,
+    pub notes: Vec<String>,
+}
+
+#[derive(Clone, Debug, Serialize, Deserialize)]
+struct Suitability {
+    fit: String,
+    reasons: Vec<String>,
+}
+
+#[derive(Clone, Debug, Serialize, Deserialize)]
+struct PositionSize {
+    capital: f64,
+    price: f64,
+    stop: Option<f64>,
+    shares: u64,
+    value: f64,
+    percent_of_capital: f64,
+    risk_amount: Option<f64>,
+    note: String,
+}
+
+#[derive(Clone, Debug, Serialize, Deserialize)]
+struct GuidanceData {
+    symbol: String,
+    price: Option<f64>,
+    volatility: Option<f64>,
+    debt_to_equity: Option<f64>,
+    composite: Option<f64>,
+    suitability: Suitability,
+    position: Option<PositionSize>,
+    assessment: Assessment,
+}
+
+const ASSET_COLORS: [&str; 4] = ["bg-primary", "bg-blue-400", "bg-yellow-500", "bg-gray-400"];
+
+/// Suggested asset allocation as a stacked bar with a legend.
+#[component]
+pub fn AllocationBar(allocation: Vec<Allocation>) -> impl IntoView {
+    let slices = allocation
+        .iter()
+        .zip(ASSET_COLORS.iter().cycle())
+        .map(|(a, color)| (a.clone(), *color))
+        .collect::<Vec<_>>();
+    let legend = slices.clone();
+    view! {
+        <div>
+            <div class="flex h-4 rounded-md overflow-hidden">
+                {slices.into_iter().map(|(a, color)| view! {
+                    <div
+                        class=color
+                        style=format!("width: {}%", a.percent)
+                        title=format!("{} {:.0}%", a.asset_class, a.percent)
+                    ></div>
+                }).collect::<Vec<_>>()}
+            </div>
+            <div class="flex flex-wrap gap-4 mt-2 text-sm">
+                {legend.into_iter().map(|(a, color)| view! {
+                    <span class="flex items-center gap-1">
+                        <span class=format!("inline-block w-3 h-3 rounded-sm {}", color)></span>
+                        {format!("{} {:.0}%", a.asset_class, a.percent)}
+                    </span>
+                }).collect::<Vec<_>>()}
+            </div>
+        </div>
+    }
+}
+
+/// The limits a bucket puts on single stocks.
+#[component]
+pub fn GuidanceLimits(guidance: Guidance) -> impl IntoView {
+    let limit = |value: Option<f64>, format: fn(f64) -> String| {
+        value.map_or("No limit".to_string(), format)
+    };
+    let rows = vec![
+        ("Largest single stock", format!("{:.0}% of capital", guidance.max_position)),
+        ("Risk per trade", format!("{}% of capital at the stop", guidance.risk_per_trade)),
+        ("Volatility", limit(guidance.max_volatility, |v| format!("Up to {:.0}% a year", v))),
+        ("Debt / equity", limit(guidance.max_debt_to_equity, |v| format!("Up to {:.1}", v))),
+        ("Composite score", limit(guidance.min_score, |v| format!("At least {:.0}", v))),
+    ];
+    view! {
+        <div class="grid grid-cols-1 md:grid-cols-2 gap-x-8 gap-y-2 text-sm">
+            {rows.into_iter().map(|(label, value)| view! {
+                <div class="flex justify-between border-b border-border py-1">
+                    <span class="text-muted-foreground">{label}</span>
+                    <span>{value}</span>
+                </div>
+            }).collect::<Vec<_>>()}
+        </div>
+    }
+}
+
+/// Mindsage's advice on one stock for the saved risk profile: whether it
+/// suits the profile, how much of it to buy and the overall allocation.
+#[component]
+pub fn RiskGuidance(#[prop(into)] symbol: Signal<String>) -> impl IntoView {
+    let (data, set_data) = create_signal(None::<GuidanceData>);
+    let (error, set_error) = create_signal(None::<String>);
+
+    create_effect(move |_| {
+        let symbol = symbol.get();
+        if symbol.is_empty() {
+            return;
+        }
+        spawn_local(async move {
+            match get_json::<GuidanceData>(&format!("/risk-profile/guidance/{}", encode(&symbol))).await {
+                Ok(guidance) => {
+                    set_error.set(None);
+                    set_data.set(Some(guidance));
+                }
+                Err(e) => {
+                    set_data.set(None);
+                    set_error.set(Some(e));
+                }
+            }
+        });
+    });
+
+    view! {
+        <div class="bg-card text-card-foreground rounded-lg shadow-sm p-6 mt-6">
+            <div class="flex justify-between items-center mb-4">
+                <h3 class="text-lg font-medium">For Your Risk Profile</h3>
+                <A href="/profile" class="text-sm text-primary">Edit profile</A>
+            </div>
+
+            {move || error.get().map(|text| view! {
+                <p class="text-sm text-muted-foreground">{text}</p>
+            })}
+
+            {move || data.get().map(|d| {
+                let (badge, verdict) = match d.suitability.fit.as_str() {
+                    "suitable" => ("px-2 py-1 rounded-md text-sm bg-green-500 text-white", "Suits"),
+                    "caution" => ("px-2 py-1 rounded-md text-sm bg-yellow-500 text-white", "Caution for"),
+                    _ => ("px-2 py-1 rounded-md text-sm bg-red-500 text-white", "Does not suit"),
+                };
+                let facts = format!(
+                    "Volatility {} · Debt/equity {} · Score {}",
+                    d.volatility.map_or("-".to_string(), |v| format!("{:.1}%", v)),
+                    d.debt_to_equity.map_or("-".to_string(), |v| format!("{:.2}", v)),
+                    d.composite.map_or("-".to_string(), |v| format!("{:.0}", v)),
+                );
+                view! {
+                    <div>
+                        <div class="flex items-center gap-3">
+                            <span class=badge>
+                                {format!("{} your {} profile", verdict, d.assessment.label)}
+                            </span>
+                            <span class="text-xs text-muted-foreground">{facts}</span>
+                        </div>
+                        <ul class="list-disc list-inside text-sm mt-2">
+                            {d.suitability.reasons.iter().map(|r| view! { <li>{r.clone()}</li> }).collect::<Vec<_>>()}
+                        </ul>
+
+                        <h4 class="font-medium mt-6 mb-2">Position Size</h4>
+                        {match d.position.clone() {
+                            Some(p) => view! {
+                                <div class="text-sm">
+                                    <p class="text-xl font-bold">
+                                        {format!("{} shares · ₹{:.0}", p.shares, p.value)}
+                                    </p>
+                                    <p class="text-muted-foreground">
+                                        {format!(
+                                            "{:.1}% of ₹{:.0} capital at ₹{:.2}",
+                                            p.percent_of_capital, p.capital, p.price
+                                        )}
+                                    </p>
+                                    {p.stop.map(|stop| view! {
+                                        <p class="text-muted-foreground">
+                                            {format!(
+                                                "Stop at ₹{:.2}, two daily standard deviations below, risks ₹{:.0}",
+                                                stop,
+                                                p.risk_amount.unwrap_or(0.0)
+                                            )}
+                                        </p>
+                                    })}
+                                    <p class="text-muted-foreground">{p.note}</p>
+                                </div>
+                            }.into_view(),
+                            None => view! {
+                                <p class="text-sm text-muted-foreground">
+                                    {format!(
+                                        "Up to {:.0}% of capital, risking {}% at the stop. Add your capital to the profile for a share count.",
+                                        d.assessment.guidance.max_position,
+                                        d.assessment.guidance.risk_per_trade
+                                    )}
+                                </p>
+                            }.into_view(),
+                        }}
+
+                        <h4 class="font-medium mt-6 mb-2">Suggested Allocation</h4>
+                        <AllocationBar allocation=d.assessment.guidance.allocation.clone() />
+                        <p class="text-sm text-muted-foreground mt-2">{d.assessment.guidance.summary.clone()}</p>
+                    </div>
+                }
+            })}
+        </div>
+    }
+}
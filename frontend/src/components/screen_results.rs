@@ -0,0 +1,180 @@
+use leptos::*;
+use serde::{Deserialize, Serialize};
+
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct ScreenRow {
+    pub symbol: String,
+    pub name: String,
+    pub sector: String,
+    pub close: Option<f64>,
+    pub change: Option<f64>,
+    pub values: Vec<Option<f64>>,
+}
+
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct ScreenRun {
+    pub expression: String,
+    pub ran_at: String,
+    pub as_of: Option<String>,
+    pub scanned: usize,
+    pub columns: Vec<String>,
+    pub rows: Vec<ScreenRow>,
+    #[serde(default)]
+    pub added: Vec<String>,
+    #[serde(default)]
+    pub removed: Vec<String>,
+}
+
+/// Column a result table is sorted by: symbol, close, change or one of
+/// the expression's values.
+#[derive(Clone, Copy, Debug, PartialEq)]
+enum SortKey {
+    Symbol,
+    Close,
+    Change,
+    Value(usize),
+}
+
+fn number(value: Option<f64>) -> String {
+    value.map_or("-".to_string(), |v| {
+        if v.abs() >= 100_000.0 {
+            format!("{:.0}", v)
+        } else {
+            format!("{:.2}", v)
+        }
+    })
+}
+
+/// Matches of a screen run in the analysis table style, sortable by any
+/// column. Symbols new since the previous run are marked.
+#[component]
+pub fn ScreenResults(run: ScreenRun) -> impl IntoView {
+    let (sort, set_sort) = create_signal((SortKey::Symbol, true));
+    let columns = run.columns.clone();
+    let added = run.added.clone();
+    let rows = store_value(run.rows.clone());
+
+    let sorted = move || {
+        let (key, ascending) = sort.get();
+        let mut list = rows.get_value();
+        let value = |row: &ScreenRow| match key {
+            SortKey::Close => row.close,
+            SortKey::Change => row.change,
+            SortKey::Value(index) => row.values.get(index).copied().flatten(),
+            SortKey::Symbol => None,
+        };
+        list.sort_by(|a, b| {
+            let order = match key {
+                SortKey::Symbol => a.symbol.cmp(&b.symbol),
+                // Rows without a value go last either way
+                _ => match (value(a), value(b)) {
+                    (Some(x), Some(y)) => x.total_cmp(&y),
+                    (Some(_), None) => return std::cmp::Ordering::Less,
+                    (None, Some(_)) => return std::cmp::Ordering::Greater,
+                    (None, None) => std::cmp::Ordering::Equal,
+                },
+            };
+            if ascending {
+                order
+            } else {
+                order.reverse()
+            }
+        });
+        list
+    };
+
+    let header = move |label: String, key: SortKey| {
+        view! {
+            <th
+                class="text-left p-3 text-muted-foreground font-medium cursor-pointer select-none whitespace-nowrap"
+                on:click=move |_| set_sort.update(|(current, ascending)| {
+                    if *current == key {
+                        *ascending = !*ascending;
+                    } else {
+                        *current = key;
+                        // Numbers read best largest first
+                        *ascending = key == SortKey::Symbol;
+                    }
+                })
+            >
+                {move || {
+                    let (current, ascending) = sort.get();
+                    let arrow = match (current == key, ascending) {
+                        (false, _) => "",
+                        (true, true) => " ▲",
+                        (true, false) => " ▼",
+                    };
+                    format!("{}{}", label, arrow)
+                }}
+            </th>
+        }
+    };
+
+    view! {
+        <div class="bg-card text-card-foreground rounded-lg shadow-sm overflow-hidden mt-6">
+            <div class="p-4 border-b border-border">
+                <h3 class="text-lg font-medium">
+                    {format!("{} matches out of {} stocks", run.rows.len(), run.scanned)}
+                </h3>
+                <p class="text-xs text-muted-foreground mt-1">
+                    {format!(
+                        "{} · data as of {} · run {}",
+                        run.expression,
+                        run.as_of.clone().unwrap_or_else(|| "-".to_string()),
+                        run.ran_at.chars().take(16).collect::<String>().replace('T', " ")
+                    )}
+                </p>
+                {(!run.removed.is_empty()).then(|| view! {
+                    <p class="text-xs text-muted-foreground mt-1">
+                        {format!("No longer matching: {}", run.removed.join(", "))}
+                    </p>
+                })}
+            </div>
+            <div class="p-0 overflow-x-auto">
+                <table class="w-full">
+                    <thead>
+                        <tr class="border-b border-border">
+                            {header("Symbol".to_string(), SortKey::Symbol)}
+                            <th class="text-left p-3 text-muted-foreground font-medium">Sector</th>
+                            {header("Close".to_string(), SortKey::Close)}
+                            {header("Change %".to_string(), SortKey::Change)}
+                            {columns
+                                .iter()
+                                .enumerate()
+                                .map(|(index, column)| header(column.clone(), SortKey::Value(index)))
+                                .collect::<Vec<_>>()}
+                        </tr>
+                    </thead>
+                    <tbody>
+                        {move || sorted().into_iter().map(|row| {
+                            let is_new = added.contains(&row.symbol);
+                            let change_class = match row.change {
+                                Some(c) if c > 0.0 => "p-3 text-green-500",
+                                Some(c) if c < 0.0 => "p-3 text-red-500",
+                                _ => "p-3",
+                            };
+                            view! {
+                                <tr class="border-b border-border">
+                                    <td class="p-3 font-medium" title=row.name.clone()>
+                                        {row.symbol.clone()}
+                                        {is_new.then(|| view! {
+                                            <span class="ml-2 text-xs px-1 rounded bg-primary text-primary-foreground">New</span>
+                                        })}
+                                    </td>
+                                    <td class="p-3 text-muted-foreground">{row.sector.clone()}</td>
+                                    <td class="p-3">{number(row.close)}</td>
+                                    <td class=change_class>
+                                        {row.change.map_or("-".to_string(), |c| format!("{:+.2}", c))}
+                                    </td>
+                                    {row.values.iter().map(|v| view! {
+                                        <td class="p-3">{number(*v)}</td>
+                                    }).collect::<Vec<_>>()}
+                                </tr>
+                            }
+                        }).collect::<Vec<_>>()}
+                    </tbody>
+                </table>
+            </div>
+        </div>
+    }
+}
@@ -47,6 +47,16 @@ pub fn Sidebar(
                         label="Mindsage" 
                         is_active=is_active("/mindsage")
                     />
+                    <SidebarLink 
+                        path="/screener" 
+                        label="Screener" 
+                        is_active=is_active("/screener")
+                    />
+                    <SidebarLink 
+                        path="/options" 
+                        label="Options" 
+                        is_active=is_active("/options")
+                    />
                     <SidebarLink 
                         path="/algo-trading" 
                         label="Algo Trading" 
@@ -67,11 +77,21 @@ pub fn Sidebar(
                         label="Journal" 
                         is_active=is_active("/journal")
                     />
+                    <SidebarLink 
+                        path="/portfolio" 
+                        label="Portfolio" 
+                        is_active=is_active("/portfolio")
+                    />
                     <SidebarLink 
                         path="/profile" 
                         label="Profile" 
                         is_active=is_active("/profile")
                     />
+                    <SidebarLink 
+                        path="/admin" 
+                        label="Admin" 
+                        is_active=is_active("/admin")
+                    />
                 </nav>
             </div>
         </aside>
@@ -0,0 +1,385 @@
+use leptos::*;
+use serde::{Deserialize, Serialize};
+
+use crate::utils::api::*;
+
+#[derive(Clone, Debug, Serialize, Deserialize)]
+struct MetricScore {
+    label: String,
+    value: Option<f64>,
+    unit: String,
+    percentile: Option<f64>,
+}
+
+#[derive(Clone, Debug, Serialize, Deserialize)]
+struct FactorScore {
+    factor: String,
+    label: String,
+    weight: f64,
+    effective_weight: f64,
+    score: Option<f64>,
+    contribution: f64,
+    sector_percentile: Option<f64>,
+    metrics: Vec<MetricScore>,
+}
+
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct ScoreData {
+    pub symbol: String,
+    pub name: String,
+    pub sector: String,
+    pub peer_group: String,
+    pub sector_size: usize,
+    pub composite: Option<f64>,
+    pub coverage: f64,
+    pub sector_percentile: Option<f64>,
+    factors: Vec<FactorScore>,
+    pub why: Vec<String>,
+}
+
+#[derive(Clone, Debug, Default, PartialEq, Serialize, Deserialize)]
+struct FactorWeights {
+    valuation: f64,
+    quality: f64,
+    growth: f64,
+    momentum: f64,
+    trend: f64,
+    volatility: f64,
+}
+
+impl FactorWeights {
+    fn get(&self, factor: &str) -> f64 {
+        match factor {
+            "valuation" => self.valuation,
+            "quality" => self.quality,
+            "growth" => self.growth,
+            "momentum" => self.momentum,
+            "trend" => self.trend,
+            _ => self.volatility,
+        }
+    }
+
+    fn set(&mut self, factor: &str, value: f64) {
+        match factor {
+            "valuation" => self.valuation = value,
+            "quality" => self.quality = value,
+            "growth" => self.growth = value,
+            "momentum" => self.momentum = value,
+            "trend" => self.trend = value,
+            _ => self.volatility = value,
+        }
+    }
+}
+
+const FACTORS: [(&str, &str, &str); 6] = [
+    ("valuation", "Valuation", "Earnings, book and dividend yield"),
+    ("quality", "Quality", "ROE, ROCE, leverage and margins"),
+    ("growth", "Growth", "3 year revenue and profit CAGR"),
+    ("momentum", "Momentum", "3 and 12 month returns"),
+    ("trend", "Trend", "Price against 50 and 200 day averages"),
+    ("volatility", "Volatility", "Annualized volatility, lower is better"),
+];
+
+fn score_class(score: f64) -> &'static str {
+    if score >= 65.0 {
+        "text-green-500"
+    } else if score <= 35.0 {
+        "text-red-500"
+    } else {
+        ""
+    }
+}
+
+fn percent(value: Option<f64>) -> String {
+    value.map_or("-".to_string(), |v| format!("{:.0}", v))
+}
+
+/// Composite score of one stock with what each factor contributed, how it
+/// ranks in its sector and the reasons behind it.
+#[component]
+pub fn StockScore(
+    #[prop(into)] symbol: Signal<String>,
+    #[prop(optional, into)] reload: Option<Signal<usize>>,
+) -> impl IntoView {
+    let (score, set_score) = create_signal(None::<ScoreData>);
+    let (expanded, set_expanded) = create_signal(None::<String>);
+
+    create_effect(move |_| {
+        let symbol = symbol.get();
+        if let Some(reload) = reload {
+            reload.track();
+        }
+        if symbol.is_empty() {
+            return;
+        }
+        spawn_local(async move {
+            let path = format!("/fundamentals/{}/score", encode(&symbol));
+            set_score.set(get_json::<ScoreData>(&path).await.ok());
+        });
+    });
+
+    view! {
+        {move || score.get().map(|s| {
+            let peers = if s.peer_group == "sector" {
+                format!("Ranked against {} {} companies", s.sector_size, s.sector)
+            } else {
+                "Ranked against all scored companies".to_string()
+            };
+            view! {
+                <div class="bg-card text-card-foreground rounded-lg shadow-sm p-6 mt-6">
+                    <div class="flex flex-col md:flex-row md:justify-between gap-2 mb-4">
+                        <div>
+                            <h3 class="text-lg font-medium">Mindsage Score</h3>
+                            <p class="text-xs text-muted-foreground">{peers}</p>
+                        </div>
+                        <div class="md:text-right">
+                            <p class=format!(
+                                "text-3xl font-bold {}",
+                                s.composite.map_or("", score_class)
+                            )>
+                                {s.composite.map_or("-".to_string(), |v| format!("{:.0}/100", v))}
+                            </p>
+                            <p class="text-xs text-muted-foreground">
+                                {format!(
+                                    "Sector percentile {} · {:.0}% of weight has data",
+                                    percent(s.sector_percentile),
+                                    s.coverage
+                                )}
+                            </p>
+                        </div>
+                    </div>
+
+                    <table class="w-full text-sm">
+                        <thead>
+                            <tr class="border-b border-border">
+                                <th class="text-left p-2 text-muted-foreground font-medium">Factor</th>
+                                <th class="text-right p-2 text-muted-foreground font-medium">Score</th>
+                                <th class="text-right p-2 text-muted-foreground font-medium">Weight</th>
+                                <th class="text-left p-2 text-muted-foreground font-medium w-1/3">Contribution</th>
+                                <th class="text-right p-2 text-muted-foreground font-medium">Sector Pctl</th>
+                            </tr>
+                        </thead>
+                        <tbody>
+                            {s.factors.into_iter().map(|f| {
+                                let key = f.factor.clone();
+                                let open = f.factor.clone();
+                                let metrics = f.metrics.clone();
+                                view! {
+                                    <tr
+                                        class="border-b border-border cursor-pointer hover:bg-secondary"
+                                        on:click=move |_| set_expanded.update(|e| {
+                                            *e = if e.as_deref() == Some(key.as_str()) { None } else { Some(key.clone()) };
+                                        })
+                                    >
+                                        <td class="p-2">{f.label.clone()}</td>
+                                        <td class=format!("p-2 text-right {}", f.score.map_or("", score_class))>
+                                            {percent(f.score)}
+                                        </td>
+                                        <td class="p-2 text-right">
+                                            {if f.score.is_some() {
+                                                format!("{:.0}%", f.effective_weight)
+                                            } else {
+                                                format!("{:.0} (no data)", f.weight)
+                                            }}
+                                        </td>
+                                        <td class="p-2">
+                                            <div class="flex items-center gap-2">
+                                                <div class="flex-1 h-2 bg-secondary rounded">
+                                                    <div
+                                                        class="h-2 bg-primary rounded"
+                                                        style=format!("width: {:.1}%", f.contribution.clamp(0.0, 100.0))
+                                                    ></div>
+                                                </div>
+                                                <span class="w-12 text-right">{format!("{:.1}", f.contribution)}</span>
+                                            </div>
+                                        </td>
+                                        <td class="p-2 text-right">{percent(f.sector_percentile)}</td>
+                                    </tr>
+                                    {move || (expanded.get().as_deref() == Some(open.as_str())).then(|| view! {
+                                        <tr class="border-b border-border">
+                                            <td colspan="5" class="p-2 pl-6">
+                                                <table class="w-full text-xs">
+                                                    {metrics.iter().map(|m| view! {
+                                                        <tr>
+                                                            <td class="py-1 text-muted-foreground">{m.label.clone()}</td>
+                                                            <td class="py-1 text-right">
+                                                                {m.value.map_or("-".to_string(), |v| format!("{:.2}{}", v, m.unit))}
+                                                            </td>
+                                                            <td class="py-1 text-right">
+                                                                {m.percentile.map_or("no data".to_string(), |p| format!("{:.0} pctl", p))}
+                                                            </td>
+                                                        </tr>
+                                                    }).collect::<Vec<_>>()}
+                                                </table>
+                                            </td>
+                                        </tr>
+                                    })}
+                                }
+                            }).collect::<Vec<_>>()}
+                        </tbody>
+                    </table>
+
+                    <h4 class="font-medium mt-4 mb-2">Why</h4>
+                    <ul class="text-sm space-y-1 list-disc pl-5">
+                        {s.why.into_iter().map(|line| view! { <li>{line}</li> }).collect::<Vec<_>>()}
+                    </ul>
+                </div>
+            }
+        })}
+    }
+}
+
+/// Edit how much each factor counts towards the composite score.
+#[component]
+pub fn ScoreWeights(#[prop(into)] on_change: Callback<()>) -> impl IntoView {
+    let (weights, set_weights) = create_signal(FactorWeights::default());
+    let (message, set_message) = create_signal(None::<String>);
+    let (error, set_error) = create_signal(None::<String>);
+
+    spawn_local(async move {
+        match get_json::<FactorWeights>("/fundamentals/score-weights").await {
+            Ok(saved) => set_weights.set(saved),
+            Err(e) => set_error.set(Some(e)),
+        }
+    });
+
+    let save = move |_| {
+        set_error.set(None);
+        set_message.set(None);
+        let current = weights.get();
+        spawn_local(async move {
+            match put_json::<_, FactorWeights>("/fundamentals/score-weights", &current).await {
+                Ok(saved) => {
+                    set_weights.set(saved);
+                    set_message.set(Some("Weights saved".to_string()));
+                    on_change.call(());
+                }
+                Err(e) => set_error.set(Some(e)),
+            }
+        });
+    };
+
+    view! {
+        <div class="bg-card text-card-foreground rounded-lg shadow-sm p-6 mt-6">
+            <h3 class="text-lg font-medium mb-4">Score Weights</h3>
+            <div class="grid grid-cols-1 md:grid-cols-3 gap-4">
+                {FACTORS.into_iter().map(|(factor, label, description)| view! {
+                    <div>
+                        <label class="block text-sm font-medium mb-1">{label}</label>
+                        <input
+                            type="number"
+                            min="0"
+                            step="5"
+                            class="w-full px-3 py-2 border border-input rounded-md"
+                            prop:value=move || weights.get().get(factor).to_string()
+                            on:input=move |ev| {
+                                let value = event_target_value(&ev).parse::<f64>().unwrap_or(0.0);
+                                set_weights.update(|w| w.set(factor, value));
+                            }
+                        />
+                        <p class="text-xs text-muted-foreground mt-1">{description}</p>
+                    </div>
+                }).collect::<Vec<_>>()}
+            </div>
+            {move || error.get().map(|text| view! {
+                <p class="text-sm text-red-500 mt-3">{text}</p>
+            })}
+            <div class="flex justify-end items-center gap-3 mt-4">
+                {move || message.get().map(|text| view! {
+                    <span class="text-sm text-green-500">{text}</span>
+                })}
+                <button
+                    class="px-4 py-2 bg-primary text-primary-foreground rounded-md"
+                    on:click=save
+                >
+                    Save Weights
+                </button>
+            </div>
+        </div>
+    }
+}
+
+/// Every scored company, best first, optionally within one sector.
+#[component]
+pub fn ScoreRanking(
+    #[prop(into)] on_select: Callback<String>,
+    #[prop(optional, into)] reload: Option<Signal<usize>>,
+) -> impl IntoView {
+    let (scores, set_scores) = create_signal(Vec::<ScoreData>::new());
+    let (sector, set_sector) = create_signal(String::new());
+
+    create_effect(move |_| {
+        if let Some(reload) = reload {
+            reload.track();
+        }
+        spawn_local(async move {
+            if let Ok(list) = get_json::<Vec<ScoreData>>("/fundamentals/scores").await {
+                set_scores.set(list);
+            }
+        });
+    });
+
+    let sectors = move || {
+        let mut list: Vec<String> = scores
+            .get()
+            .into_iter()
+            .map(|s| s.sector)
+            .filter(|s| !s.is_empty())
+            .collect();
+        list.sort();
+        list.dedup();
+        list
+    };
+
+    view! {
+        <div class="bg-card text-card-foreground rounded-lg shadow-sm p-6 mt-6">
+            <div class="flex justify-between items-center mb-4">
+                <h3 class="text-lg font-medium">Ranking</h3>
+                <select
+                    class="px-3 py-2 border border-input rounded-md text-sm"
+                    on:change=move |ev| set_sector.set(event_target_value(&ev))
+                >
+                    <option value="">All sectors</option>
+                    {move || sectors().into_iter().map(|s| view! {
+                        <option value=s.clone()>{s.clone()}</option>
+                    }).collect::<Vec<_>>()}
+                </select>
+            </div>
+            <table class="w-full text-sm">
+                <thead>
+                    <tr class="border-b border-border">
+                        <th class="text-left p-2 text-muted-foreground font-medium">Symbol</th>
+                        <th class="text-left p-2 text-muted-foreground font-medium">Sector</th>
+                        <th class="text-right p-2 text-muted-foreground font-medium">Score</th>
+                        <th class="text-right p-2 text-muted-foreground font-medium">Sector Pctl</th>
+                        <th class="text-right p-2 text-muted-foreground font-medium">Coverage</th>
+                    </tr>
+                </thead>
+                <tbody>
+                    {move || scores
+                        .get()
+                        .into_iter()
+                        .filter(|s| sector.with(|sector| sector.is_empty() || s.sector == *sector))
+                        .map(|s| {
+                            let symbol = s.symbol.clone();
+                            view! {
+                                <tr
+                                    class="border-b border-border cursor-pointer hover:bg-secondary"
+                                    on:click=move |_| on_select.call(symbol.clone())
+                                >
+                                    <td class="p-2" title=s.name.clone()>{s.symbol.clone()}</td>
+                                    <td class="p-2">{s.sector.clone()}</td>
+                                    <td class=format!("p-2 text-right {}", s.composite.map_or("", score_class))>
+                                        {percent(s.composite)}
+                                    </td>
+                                    <td class="p-2 text-right">{percent(s.sector_percentile)}</td>
+                                    <td class="p-2 text-right">{format!("{:.0}%", s.coverage)}</td>
+                                </tr>
+                            }
+                        })
+                        .collect::<Vec<_>>()}
+                </tbody>
+            </table>
+        </div>
+    }
+}
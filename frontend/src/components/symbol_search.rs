@@ -0,0 +1,103 @@
+use leptos::*;
+use serde::{Deserialize, Serialize};
+
+use crate::utils::api::*;
+
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct InstrumentHit {
+    pub symbol: String,
+    pub name: String,
+    pub kind: String,
+    pub segment: String,
+    pub expiry: Option<String>,
+    pub strike: Option<f64>,
+    pub lot_size: u32,
+}
+
+/// Typeahead over the instrument master. `on_select` gets the whole hit,
+/// so callers can tell the same symbol on NSE and BSE apart by segment.
+#[component]
+pub fn SymbolSearch(
+    #[prop(into)] value: Signal<String>,
+    #[prop(into)] on_select: Callback<InstrumentHit>,
+    #[prop(default = "Select Symbol")] label: &'static str,
+) -> impl IntoView {
+    let (query, set_query) = create_signal(value.get_untracked());
+    let (results, set_results) = create_signal(Vec::<InstrumentHit>::new());
+    let (is_open, set_open) = create_signal(false);
+    // Only the latest request may update the list, so slow responses for
+    // earlier keystrokes don't overwrite newer results
+    let latest_request = store_value(0u32);
+
+    create_effect(move |_| set_query.set(value.get()));
+
+    let run_search = move |text: String| {
+        latest_request.update_value(|id| *id += 1);
+        let request = latest_request.get_value();
+
+        spawn_local(async move {
+            let path = format!("/instruments/search?q={}&limit=10", encode(&text));
+            match get_json::<Vec<InstrumentHit>>(&path).await {
+                Ok(hits) if latest_request.get_value() == request => set_results.set(hits),
+                Ok(_) => {}
+                Err(e) => log::warn!("Symbol search failed: {}", e),
+            }
+        });
+    };
+
+    let handle_input = move |ev: web_sys::Event| {
+        let text = event_target_value(&ev);
+        set_query.set(text.clone());
+        set_open.set(true);
+        run_search(text);
+    };
+
+    let handle_focus = move |_| {
+        set_open.set(true);
+        run_search(query.get_untracked());
+    };
+
+    let select = move |hit: InstrumentHit| {
+        set_query.set(hit.symbol.clone());
+        set_open.set(false);
+        on_select.call(hit);
+    };
+
+    view! {
+        <div class="relative">
+            <label class="block text-sm font-medium mb-1">{label}</label>
+            <input
+                type="text"
+                class="w-full px-3 py-2 border border-input rounded-md"
+                placeholder="Search symbol..."
+                prop:value=query
+                on:input=handle_input
+                on:focus=handle_focus
+                on:blur=move |_| set_open.set(false)
+            />
+            {move || (is_open.get() && !results.get().is_empty()).then(|| view! {
+                <ul class="absolute z-20 w-full mt-1 bg-card text-card-foreground border border-border rounded-md shadow-sm max-h-64 overflow-y-auto">
+                    {results.get().into_iter().map(|hit| {
+                        let selected = hit.clone();
+                        let detail = match (&hit.expiry, hit.strike) {
+                            (Some(expiry), Some(strike)) => format!("{} · {} · {:.2}", hit.segment, expiry, strike),
+                            (Some(expiry), None) => format!("{} · {}", hit.segment, expiry),
+                            _ => format!("{} · {}", hit.segment, hit.name),
+                        };
+
+                        view! {
+                            // mousedown fires before the input's blur closes the list
+                            <li
+                                class="px-3 py-2 cursor-pointer hover:bg-secondary"
+                                on:mousedown=move |_| select(selected.clone())
+                            >
+                                <div class="font-medium">{hit.symbol}</div>
+                                <div class="text-xs text-muted-foreground">{detail}</div>
+                            </li>
+                        }
+                    }).collect::<Vec<_>>()}
+                </ul>
+            })}
+        </div>
+    }
+}
@@ -0,0 +1,246 @@
+use chrono::Datelike;
+use leptos::*;
+use serde::{Deserialize, Serialize};
+
+use crate::components::stat_card::*;
+use crate::utils::api::*;
+use crate::utils::format::rupees;
+
+#[derive(Clone, Debug, Serialize, Deserialize)]
+struct HeadSummary {
+    head: String,
+    label: String,
+    lots: usize,
+    turnover: f64,
+    sell_value: f64,
+    cost: f64,
+    expenses: f64,
+    profit: f64,
+    loss: f64,
+    net: f64,
+    estimated_tax: Option<f64>,
+}
+
+#[derive(Clone, Debug, Serialize, Deserialize)]
+struct TaxLot {
+    trade_id: String,
+    symbol: String,
+    head: String,
+    direction: String,
+    quantity: u32,
+    buy_date: String,
+    sell_date: String,
+    holding_days: i64,
+    buy_value: f64,
+    sell_value: f64,
+    cost: f64,
+    expenses: f64,
+    gain: f64,
+    grandfathered: bool,
+}
+
+#[derive(Clone, Debug, Serialize, Deserialize)]
+struct TaxReportData {
+    financial_year: String,
+    from: String,
+    to: String,
+    heads: Vec<HeadSummary>,
+    lots: Vec<TaxLot>,
+    ltcg_exemption: f64,
+    notes: Vec<String>,
+}
+
+fn head_short(head: &str) -> &'static str {
+    match head {
+        "speculative" => "Intraday",
+        "non_speculative" => "F&O",
+        "short_term_capital_gain" => "STCG",
+        "long_term_capital_gain" => "LTCG",
+        _ => "-",
+    }
+}
+
+/// Start year of the current financial year (April to March).
+fn current_financial_year() -> i32 {
+    let today = chrono::Local::now().date_naive();
+    if today.month() >= 4 {
+        today.year()
+    } else {
+        today.year() - 1
+    }
+}
+
+/// Journal trades classified into Indian tax heads for one financial year,
+/// with CSV and PDF downloads.
+#[component]
+pub fn TaxReport() -> impl IntoView {
+    let current = current_financial_year();
+    let (year, set_year) = create_signal(current);
+    let (report, set_report) = create_signal(None::<TaxReportData>);
+    let (error, set_error) = create_signal(None::<String>);
+
+    create_effect(move |_| {
+        let path = format!("/tax/report?fy={}", year.get());
+        spawn_local(async move {
+            match get_json::<TaxReportData>(&path).await {
+                Ok(result) => {
+                    set_error.set(None);
+                    set_report.set(Some(result));
+                }
+                Err(e) => set_error.set(Some(e)),
+            }
+        });
+    });
+
+    view! {
+        <div>
+            <div class="flex justify-between items-center mb-6">
+                <select
+                    class="px-3 py-2 border border-input rounded-md"
+                    on:change=move |ev| {
+                        if let Ok(value) = event_target_value(&ev).parse() {
+                            set_year.set(value);
+                        }
+                    }
+                >
+                    {(0..6).map(|back| {
+                        let start = current - back;
+                        view! {
+                            <option value=start.to_string() selected=move || year.get() == start>
+                                {format!("FY {}-{:02}", start, (start + 1) % 100)}
+                            </option>
+                        }
+                    }).collect::<Vec<_>>()}
+                </select>
+                <div class="space-x-2">
+                    <a
+                        class="px-4 py-2 bg-secondary text-secondary-foreground rounded-md"
+                        href=move || format!("{}/tax/report.csv?fy={}", API_BASE, year.get())
+                    >
+                        Download CSV
+                    </a>
+                    <a
+                        class="px-4 py-2 bg-secondary text-secondary-foreground rounded-md"
+                        href=move || format!("{}/tax/report.pdf?fy={}", API_BASE, year.get())
+                    >
+                        Download PDF
+                    </a>
+                </div>
+            </div>
+
+            {move || error.get().map(|message| view! {
+                <p class="text-sm text-red-500 mb-4">{message}</p>
+            })}
+
+            {move || report.get().map(|report| view! {
+                <div class="grid grid-cols-1 md:grid-cols-4 gap-4 mb-6">
+                    {report.heads.iter().map(|head| view! {
+                        <StatCard stat=StatData {
+                            title: head.label.clone(),
+                            value: rupees(head.net),
+                            description: Some(match head.estimated_tax {
+                                Some(tax) => format!("{} lots · est. tax {}", head.lots, rupees(tax)),
+                                None => format!("{} lots · turnover {}", head.lots, rupees(head.turnover)),
+                            }),
+                        } />
+                    }).collect::<Vec<_>>()}
+                </div>
+
+                <div class="bg-card text-card-foreground rounded-lg shadow-sm overflow-hidden mb-6">
+                    <div class="p-4 border-b border-border">
+                        <h3 class="text-lg font-medium">
+                            {format!("{} ({} to {})", report.financial_year, report.from, report.to)}
+                        </h3>
+                    </div>
+                    <div class="overflow-x-auto">
+                        <table class="w-full">
+                            <thead>
+                                <tr class="border-b border-border">
+                                    <th class="text-left p-3 text-muted-foreground font-medium">Head</th>
+                                    <th class="text-left p-3 text-muted-foreground font-medium">Turnover</th>
+                                    <th class="text-left p-3 text-muted-foreground font-medium">Sale Value</th>
+                                    <th class="text-left p-3 text-muted-foreground font-medium">Cost</th>
+                                    <th class="text-left p-3 text-muted-foreground font-medium">Expenses</th>
+                                    <th class="text-left p-3 text-muted-foreground font-medium">Profit</th>
+                                    <th class="text-left p-3 text-muted-foreground font-medium">Loss</th>
+                                    <th class="text-left p-3 text-muted-foreground font-medium">Net</th>
+                                    <th class="text-left p-3 text-muted-foreground font-medium">Est. Tax</th>
+                                </tr>
+                            </thead>
+                            <tbody>
+                                {report.heads.iter().map(|head| view! {
+                                    <tr class="border-b border-border">
+                                        <td class="p-3">{head.label.clone()}</td>
+                                        <td class="p-3">{rupees(head.turnover)}</td>
+                                        <td class="p-3">{rupees(head.sell_value)}</td>
+                                        <td class="p-3">{rupees(head.cost)}</td>
+                                        <td class="p-3">{rupees(head.expenses)}</td>
+                                        <td class="p-3 text-green-500">{rupees(head.profit)}</td>
+                                        <td class="p-3 text-red-500">{rupees(head.loss)}</td>
+                                        <td class="p-3">{rupees(head.net)}</td>
+                                        <td class="p-3">
+                                            {head.estimated_tax.map_or("Slab rate".to_string(), rupees)}
+                                        </td>
+                                    </tr>
+                                }).collect::<Vec<_>>()}
+                            </tbody>
+                        </table>
+                    </div>
+                    <p class="p-4 text-xs text-muted-foreground">
+                        {format!("LTCG exemption applied: {}", rupees(report.ltcg_exemption))}
+                    </p>
+                </div>
+
+                <div class="bg-card text-card-foreground rounded-lg shadow-sm overflow-hidden mb-6">
+                    <div class="p-4 border-b border-border">
+                        <h3 class="text-lg font-medium">Lots</h3>
+                    </div>
+                    <div class="overflow-x-auto">
+                        <table class="w-full text-sm">
+                            <thead>
+                                <tr class="border-b border-border">
+                                    <th class="text-left p-3 text-muted-foreground font-medium">Head</th>
+                                    <th class="text-left p-3 text-muted-foreground font-medium">Symbol</th>
+                                    <th class="text-left p-3 text-muted-foreground font-medium">Quantity</th>
+                                    <th class="text-left p-3 text-muted-foreground font-medium">Bought</th>
+                                    <th class="text-left p-3 text-muted-foreground font-medium">Sold</th>
+                                    <th class="text-left p-3 text-muted-foreground font-medium">Days</th>
+                                    <th class="text-left p-3 text-muted-foreground font-medium">Cost</th>
+                                    <th class="text-left p-3 text-muted-foreground font-medium">Sale Value</th>
+                                    <th class="text-left p-3 text-muted-foreground font-medium">Expenses</th>
+                                    <th class="text-left p-3 text-muted-foreground font-medium">Gain</th>
+                                </tr>
+                            </thead>
+                            <tbody>
+                                {report.lots.iter().map(|lot| view! {
+                                    <tr class="border-b border-border">
+                                        <td class="p-3">{head_short(&lot.head)}</td>
+                                        <td class="p-3">{lot.symbol.clone()}</td>
+                                        <td class="p-3">{lot.quantity.to_string()}</td>
+                                        <td class="p-3 whitespace-nowrap">{lot.buy_date.clone()}</td>
+                                        <td class="p-3 whitespace-nowrap">{lot.sell_date.clone()}</td>
+                                        <td class="p-3">{lot.holding_days.to_string()}</td>
+                                        <td class="p-3" title=if lot.grandfathered { "Grandfathered at the 31 Jan 2018 price" } else { "" }>
+                                            {format!("{}{}", rupees(lot.cost), if lot.grandfathered { " *" } else { "" })}
+                                        </td>
+                                        <td class="p-3">{rupees(lot.sell_value)}</td>
+                                        <td class="p-3">{rupees(lot.expenses)}</td>
+                                        <td class="p-3">
+                                            <span class={if lot.gain >= 0.0 { "text-green-500" } else { "text-red-500" }}>
+                                                {rupees(lot.gain)}
+                                            </span>
+                                        </td>
+                                    </tr>
+                                }).collect::<Vec<_>>()}
+                            </tbody>
+                        </table>
+                    </div>
+                </div>
+
+                <ul class="text-xs text-muted-foreground space-y-1 list-disc pl-5">
+                    {report.notes.iter().map(|note| view! { <li>{note.clone()}</li> }).collect::<Vec<_>>()}
+                </ul>
+            })}
+        </div>
+    }
+}
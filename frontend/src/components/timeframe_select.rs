@@ -0,0 +1,30 @@
+use leptos::*;
+
+pub const TIMEFRAMES: [&str; 8] = ["1m", "3m", "5m", "15m", "1h", "1D", "1W", "1M"];
+
+#[component]
+pub fn TimeframeSelect(
+    #[prop(into)] value: Signal<String>,
+    #[prop(into)] on_change: Callback<String>,
+) -> impl IntoView {
+    view! {
+        <div>
+            <label class="block text-sm font-medium mb-1">Timeframe</label>
+            <select 
+                class="w-full px-3 py-2 border border-input rounded-md" 
+                on:change=move |ev| on_change.call(event_target_value(&ev))
+            >
+                {TIMEFRAMES.into_iter().map(|timeframe| {
+                    view! {
+                        <option 
+                            value={timeframe} 
+                            selected={move || value.get() == timeframe}
+                        >
+                            {timeframe}
+                        </option>
+                    }
+                }).collect::<Vec<_>>()}
+            </select>
+        </div>
+    }
+}
@@ -0,0 +1,444 @@
+use leptos::*;
+use serde::{Deserialize, Serialize};
+use wasm_bindgen::JsCast;
+use wasm_bindgen_futures::JsFuture;
+use web_sys::{Blob, CanvasRenderingContext2d, HtmlCanvasElement, HtmlImageElement, HtmlInputElement, Url};
+
+use crate::components::journal_form::*;
+use crate::utils::api::*;
+
+/// Must match the backend limits.
+const MAX_UPLOAD_BYTES: f64 = 10.0 * 1024.0 * 1024.0;
+const THUMBNAIL_WIDTH: f64 = 320.0;
+
+const CHART_TIMEFRAMES: [(&str, &str); 5] = [
+    ("", "Auto"),
+    ("5m", "5 min"),
+    ("15m", "15 min"),
+    ("1h", "1 hour"),
+    ("1D", "Daily"),
+];
+
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+struct Attachment {
+    id: String,
+    trade_id: String,
+    name: String,
+    content_type: String,
+    size: usize,
+    kind: String,
+    #[serde(default)]
+    caption: String,
+    created_at: String,
+    #[serde(default)]
+    has_thumbnail: bool,
+}
+
+impl Attachment {
+    fn url(&self) -> String {
+        format!("{}/attachments/{}", API_BASE, self.id)
+    }
+
+    fn preview_url(&self) -> String {
+        if self.has_thumbnail {
+            format!("{}/attachments/{}/thumbnail", API_BASE, self.id)
+        } else {
+            self.url()
+        }
+    }
+
+    fn is_visual(&self) -> bool {
+        self.kind == "image" || self.kind == "chart"
+    }
+}
+
+#[derive(Serialize)]
+struct ChartRequest {
+    trade_id: String,
+    timeframe: Option<String>,
+    caption: String,
+}
+
+#[derive(Serialize)]
+struct CaptionUpdate {
+    caption: String,
+}
+
+fn file_size(bytes: usize) -> String {
+    if bytes >= 1024 * 1024 {
+        format!("{:.1} MB", bytes as f64 / 1_048_576.0)
+    } else {
+        format!("{:.0} KB", (bytes as f64 / 1024.0).max(1.0))
+    }
+}
+
+/// Downscale an image in the browser to a JPEG thumbnail. The browser has
+/// to decode the picture anyway, so this keeps image codecs out of the
+/// backend.
+async fn make_thumbnail(image: &Blob) -> Result<Blob, String> {
+    let fail = |_| "Could not create a thumbnail".to_string();
+    let url = Url::create_object_url_with_blob(image).map_err(fail)?;
+    let element = HtmlImageElement::new().map_err(fail)?;
+    element.set_src(&url);
+    let decoded = JsFuture::from(element.decode()).await;
+    let _ = Url::revoke_object_url(&url);
+    decoded.map_err(fail)?;
+
+    let (width, height) = (element.natural_width() as f64, element.natural_height() as f64);
+    if width == 0.0 || height == 0.0 {
+        return Err("Could not create a thumbnail".to_string());
+    }
+    let scale = (THUMBNAIL_WIDTH / width).min(1.0);
+    let canvas: HtmlCanvasElement = document()
+        .create_element("canvas")
+        .map_err(fail)?
+        .unchecked_into();
+    canvas.set_width((width * scale).round() as u32);
+    canvas.set_height((height * scale).round() as u32);
+    let context: CanvasRenderingContext2d = canvas
+        .get_context("2d")
+        .map_err(fail)?
+        .ok_or("Could not create a thumbnail")?
+        .unchecked_into();
+    context
+        .draw_image_with_html_image_element_and_dw_and_dh(
+            &element,
+            0.0,
+            0.0,
+            width * scale,
+            height * scale,
+        )
+        .map_err(fail)?;
+
+    let promise = js_sys::Promise::new(&mut |resolve, _| {
+        let _ = canvas.to_blob_with_type_and_encoder_options(
+            &resolve,
+            "image/jpeg",
+            &wasm_bindgen::JsValue::from_f64(0.8),
+        );
+    });
+    let blob = JsFuture::from(promise).await.map_err(fail)?;
+    blob.dyn_into::<Blob>().map_err(fail)
+}
+
+/// Screenshots, rendered charts and files attached to a trade.
+#[component]
+pub fn TradeAttachments(#[prop(into)] trade_id: String) -> impl IntoView {
+    let trade_id = store_value(trade_id);
+    let file_input = create_node_ref::<html::Input>();
+    let (attachments, set_attachments) = create_signal(Vec::<Attachment>::new());
+    let (caption, set_caption) = create_signal(String::new());
+    let (timeframe, set_timeframe) = create_signal(String::new());
+    let (expanded, set_expanded) = create_signal(None::<String>);
+    let (busy, set_busy) = create_signal(false);
+    let (error, set_error) = create_signal(None::<String>);
+
+    let load = move || {
+        let path = format!("/attachments?trade={}", encode(&trade_id.get_value()));
+        spawn_local(async move {
+            match get_json::<Vec<Attachment>>(&path).await {
+                Ok(list) => set_attachments.set(list),
+                Err(e) => set_error.set(Some(e)),
+            }
+        });
+    };
+    load();
+
+    let upload = move |_| {
+        set_error.set(None);
+        let Some(input) = file_input.get() else {
+            return;
+        };
+        let input: HtmlInputElement = input.unchecked_into();
+        let Some(file) = input.files().and_then(|files| files.get(0)) else {
+            set_error.set(Some("Choose a file first".to_string()));
+            return;
+        };
+        if file.size() > MAX_UPLOAD_BYTES {
+            set_error.set(Some(format!(
+                "{} is {}, the limit is 10 MB",
+                file.name(),
+                file_size(file.size() as usize)
+            )));
+            return;
+        }
+        let path = format!(
+            "/attachments?trade={}&name={}&caption={}",
+            encode(&trade_id.get_value()),
+            encode(&file.name()),
+            encode(&caption.get_untracked())
+        );
+        set_busy.set(true);
+        spawn_local(async move {
+            match post_blob::<Attachment>(&path, &file).await {
+                Ok(attachment) => {
+                    // A missing thumbnail only means the full image is shown
+                    // scaled down, so failures here are not fatal
+                    if attachment.kind == "image" {
+                        match make_thumbnail(&file).await {
+                            Ok(thumbnail) => {
+                                let path = format!("/attachments/{}/thumbnail", attachment.id);
+                                if let Err(e) = put_blob::<Attachment>(&path, &thumbnail).await {
+                                    log::warn!("Thumbnail upload failed: {}", e);
+                                }
+                            }
+                            Err(e) => log::warn!("{}", e),
+                        }
+                    }
+                    input.set_value("");
+                    set_caption.set(String::new());
+                    load();
+                }
+                Err(e) => set_error.set(Some(e)),
+            }
+            set_busy.set(false);
+        });
+    };
+
+    let capture = move |_| {
+        set_error.set(None);
+        let request = ChartRequest {
+            trade_id: trade_id.get_value(),
+            timeframe: Some(timeframe.get_untracked()).filter(|t| !t.is_empty()),
+            caption: caption.get_untracked(),
+        };
+        set_busy.set(true);
+        spawn_local(async move {
+            match post_json::<_, Attachment>("/attachments/chart", &request).await {
+                Ok(_) => {
+                    set_caption.set(String::new());
+                    load();
+                }
+                Err(e) => set_error.set(Some(e)),
+            }
+            set_busy.set(false);
+        });
+    };
+
+    let rename = move |id: String, caption: String| {
+        spawn_local(async move {
+            let path = format!("/attachments/{}", id);
+            match put_json::<_, Attachment>(&path, &CaptionUpdate { caption }).await {
+                Ok(_) => load(),
+                Err(e) => set_error.set(Some(e)),
+            }
+        });
+    };
+
+    let remove = move |id: String| {
+        spawn_local(async move {
+            match delete_json::<String>(&format!("/attachments/{}", id)).await {
+                Ok(_) => {
+                    set_expanded.update(|open| {
+                        if open.as_deref() == Some(id.as_str()) {
+                            *open = None;
+                        }
+                    });
+                    load();
+                }
+                Err(e) => set_error.set(Some(e)),
+            }
+        });
+    };
+
+    view! {
+        <div>
+            <h4 class="font-medium mb-3">Attachments</h4>
+            <div class="flex flex-col md:flex-row gap-2 items-end mb-4">
+                <div>
+                    <label class="block text-sm font-medium mb-1">Screenshot or file</label>
+                    <input
+                        type="file"
+                        accept="image/png,image/jpeg,image/gif,image/webp,application/pdf,.csv,.txt,.md"
+                        class="text-sm"
+                        node_ref=file_input
+                    />
+                </div>
+                <div class="flex-1">
+                    <label class="block text-sm font-medium mb-1">Caption</label>
+                    <input
+                        type="text"
+                        class="w-full px-3 py-2 border border-input rounded-md"
+                        placeholder="e.g. Entry on 5m breakout"
+                        prop:value=move || caption.get()
+                        on:input=move |ev| set_caption.set(event_target_value(&ev))
+                    />
+                </div>
+                <button
+                    class="px-4 py-2 bg-primary text-primary-foreground rounded-md"
+                    disabled=move || busy.get()
+                    on:click=upload
+                >
+                    Upload
+                </button>
+                <select
+                    class="px-3 py-2 border border-input rounded-md"
+                    on:change=move |ev| set_timeframe.set(event_target_value(&ev))
+                >
+                    {CHART_TIMEFRAMES.into_iter().map(|(value, label)| view! {
+                        <option value=value selected=move || timeframe.get() == value>{label}</option>
+                    }).collect::<Vec<_>>()}
+                </select>
+                <button
+                    class="px-4 py-2 bg-secondary text-secondary-foreground rounded-md"
+                    disabled=move || busy.get()
+                    on:click=capture
+                >
+                    Capture Chart
+                </button>
+            </div>
+            <p class="text-xs text-muted-foreground mb-3">
+                "Images, PDF, CSV or text up to 10 MB, 20 per trade. Captured charts mark every fill."
+            </p>
+
+            {move || error.get().map(|message| view! {
+                <p class="text-sm text-red-500 mb-3">{message}</p>
+            })}
+
+            {move || expanded.get().and_then(|id| {
+                attachments.get().into_iter().find(|a| a.id == id)
+            }).map(|attachment| view! {
+                <div class="mb-4 border border-border rounded-md p-2">
+                    <img src=attachment.url() alt=attachment.name.clone() class="w-full rounded-md" />
+                    <div class="flex justify-between items-center mt-2 text-sm">
+                        <span>{if attachment.caption.is_empty() { attachment.name.clone() } else { attachment.caption.clone() }}</span>
+                        <button class="px-2 py-1 text-muted-foreground" on:click=move |_| set_expanded.set(None)>
+                            Close
+                        </button>
+                    </div>
+                </div>
+            })}
+
+            <div class="grid grid-cols-2 md:grid-cols-4 gap-4">
+                {move || attachments.get().into_iter().map(|attachment| {
+                    let id = attachment.id.clone();
+                    let open = attachment.id.clone();
+                    let remove_id = attachment.id.clone();
+                    view! {
+                        <div class="border border-border rounded-md p-2 text-sm">
+                            {if attachment.is_visual() {
+                                view! {
+                                    <img
+                                        src=attachment.preview_url()
+                                        alt=attachment.name.clone()
+                                        class="w-full h-32 object-cover rounded-md cursor-pointer"
+                                        on:click=move |_| set_expanded.set(Some(open.clone()))
+                                    />
+                                }.into_view()
+                            } else {
+                                view! {
+                                    <a
+                                        href=attachment.url()
+                                        class="flex items-center justify-center h-32 bg-muted rounded-md text-muted-foreground"
+                                    >
+                                        {attachment.name.rsplit('.').next().unwrap_or("file").to_uppercase()}
+                                    </a>
+                                }.into_view()
+                            }}
+                            <input
+                                type="text"
+                                class="w-full mt-2 px-2 py-1 border border-input rounded-md"
+                                placeholder="Caption"
+                                value=attachment.caption.clone()
+                                on:change=move |ev| rename(id.clone(), event_target_value(&ev))
+                            />
+                            <div class="flex justify-between items-center mt-1 text-xs text-muted-foreground">
+                                <span class="truncate" title=attachment.name.clone()>
+                                    {format!("{} · {}", attachment.name, file_size(attachment.size))}
+                                </span>
+                                <button class="px-1" on:click=move |_| remove(remove_id.clone())>
+                                    Delete
+                                </button>
+                            </div>
+                        </div>
+                    }
+                }).collect::<Vec<_>>()}
+            </div>
+        </div>
+    }
+}
+
+/// Full view of one journal trade: every fill, notes, tags and
+/// attachments.
+#[component]
+pub fn TradeDetail(entry: JournalEntry, #[prop(into)] on_close: Callback<()>) -> impl IntoView {
+    let summary = entry.summary.clone().unwrap_or_default();
+    let mut executions = entry.executions.clone();
+    executions.sort_by(|a, b| a.time.cmp(&b.time));
+
+    view! {
+        <div class="bg-card text-card-foreground rounded-lg shadow-sm p-6 mt-6">
+            <div class="flex justify-between items-center mb-4">
+                <h3 class="text-lg font-medium">
+                    {format!(
+                        "{} · {} · {}",
+                        entry.symbol,
+                        summary.direction.clone().unwrap_or_default(),
+                        if summary.open_quantity > 0 { "Open" } else { "Closed" }
+                    )}
+                </h3>
+                <button
+                    class="px-3 py-1 bg-secondary text-secondary-foreground rounded-md"
+                    on:click=move |_| on_close.call(())
+                >
+                    Close
+                </button>
+            </div>
+
+            <div class="grid grid-cols-2 md:grid-cols-4 gap-4 mb-4 text-sm">
+                <div>
+                    <div class="text-muted-foreground">Avg Entry</div>
+                    <div>{format!("₹{:.2}", summary.avg_entry_price)}</div>
+                </div>
+                <div>
+                    <div class="text-muted-foreground">Avg Exit</div>
+                    <div>
+                        {if summary.exit_quantity > 0 { format!("₹{:.2}", summary.avg_exit_price) } else { "-".to_string() }}
+                    </div>
+                </div>
+                <div>
+                    <div class="text-muted-foreground">Fees</div>
+                    <div>{format!("₹{:.2}", summary.fees)}</div>
+                </div>
+                <div>
+                    <div class="text-muted-foreground">Realized PnL</div>
+                    <div class=pnl_class(summary.realized_pnl)>{format!("₹{:.2}", summary.realized_pnl)}</div>
+                </div>
+            </div>
+
+            <table class="w-full mb-4 text-sm">
+                <thead>
+                    <tr class="border-b border-border">
+                        <th class="text-left p-2 text-muted-foreground font-medium">Time</th>
+                        <th class="text-left p-2 text-muted-foreground font-medium">Side</th>
+                        <th class="text-left p-2 text-muted-foreground font-medium">Quantity</th>
+                        <th class="text-left p-2 text-muted-foreground font-medium">Price</th>
+                        <th class="text-left p-2 text-muted-foreground font-medium">Fees</th>
+                    </tr>
+                </thead>
+                <tbody>
+                    {executions.into_iter().map(|execution| view! {
+                        <tr class="border-b border-border">
+                            <td class="p-2">{execution.time.replace('T', " ")}</td>
+                            <td class="p-2">{execution.side}</td>
+                            <td class="p-2">{execution.quantity.to_string()}</td>
+                            <td class="p-2">{format!("₹{:.2}", execution.price)}</td>
+                            <td class="p-2">{format!("₹{:.2}", execution.fees)}</td>
+                        </tr>
+                    }).collect::<Vec<_>>()}
+                </tbody>
+            </table>
+
+            {(!entry.notes.is_empty()).then(|| view! {
+                <p class="text-sm mb-3 whitespace-pre-wrap">{entry.notes.clone()}</p>
+            })}
+            <div class="flex flex-wrap gap-1 mb-4">
+                {entry.tags.iter().map(|tag| view! {
+                    <span class="px-2 py-0.5 bg-muted rounded-md text-xs">{tag.clone()}</span>
+                }).collect::<Vec<_>>()}
+            </div>
+
+            <TradeAttachments trade_id=entry.id.clone() />
+        </div>
+    }
+}
@@ -0,0 +1,214 @@
+use leptos::*;
+use serde::{Deserialize, Serialize};
+use wasm_bindgen::JsCast;
+use wasm_bindgen_futures::JsFuture;
+use web_sys::HtmlInputElement;
+
+use crate::components::journal_form::*;
+use crate::utils::api::*;
+
+#[derive(Clone, Debug, Serialize, Deserialize)]
+struct TradeDiff {
+    before: JournalEntry,
+    after: JournalEntry,
+}
+
+#[derive(Clone, Debug, Serialize, Deserialize)]
+struct ImportPreview {
+    created: Vec<JournalEntry>,
+    updated: Vec<TradeDiff>,
+    duplicates: usize,
+    errors: Vec<String>,
+}
+
+#[derive(Clone, Debug, Serialize, Deserialize)]
+struct ImportResult {
+    created: usize,
+    updated: usize,
+    duplicates: usize,
+}
+
+async fn read_file(input: &HtmlInputElement) -> Result<String, String> {
+    let file = input
+        .files()
+        .and_then(|files| files.get(0))
+        .ok_or("Choose a tradebook CSV first")?;
+    let text = JsFuture::from(file.text())
+        .await
+        .map_err(|_| "Could not read the file".to_string())?;
+    text.as_string().ok_or("Could not read the file".to_string())
+}
+
+fn summary_cells(entry: &JournalEntry) -> (String, String, String, f64) {
+    let summary = entry.summary.clone().unwrap_or_default();
+    (
+        summary.direction.unwrap_or_default(),
+        entry.executions.len().to_string(),
+        if summary.open_quantity > 0 {
+            format!("{} open", summary.open_quantity)
+        } else {
+            "Closed".to_string()
+        },
+        summary.realized_pnl,
+    )
+}
+
+/// Upload a broker tradebook, review the trades it would create or extend,
+/// then commit them to the journal.
+#[component]
+pub fn TradebookImport(#[prop(into)] on_imported: Callback<()>) -> impl IntoView {
+    let file_input = create_node_ref::<html::Input>();
+    let contents = store_value(String::new());
+    let (preview, set_preview) = create_signal(None::<ImportPreview>);
+    let (message, set_message) = create_signal(None::<String>);
+    let (error, set_error) = create_signal(None::<String>);
+
+    let load_preview = move |_| {
+        set_error.set(None);
+        set_message.set(None);
+        let Some(input) = file_input.get() else {
+            return;
+        };
+        let input: HtmlInputElement = input.unchecked_into();
+        spawn_local(async move {
+            let text = match read_file(&input).await {
+                Ok(text) => text,
+                Err(e) => {
+                    set_error.set(Some(e));
+                    return;
+                }
+            };
+            match post_text::<ImportPreview>("/journal/import/preview", &text).await {
+                Ok(result) => {
+                    contents.set_value(text);
+                    set_preview.set(Some(result));
+                }
+                Err(e) => set_error.set(Some(e)),
+            }
+        });
+    };
+
+    let commit = move |_| {
+        let text = contents.get_value();
+        spawn_local(async move {
+            match post_text::<ImportResult>("/journal/import", &text).await {
+                Ok(result) => {
+                    set_preview.set(None);
+                    set_message.set(Some(format!(
+                        "Imported {} new trades, extended {}, skipped {} duplicate fills",
+                        result.created, result.updated, result.duplicates
+                    )));
+                    on_imported.call(());
+                }
+                Err(e) => set_error.set(Some(e)),
+            }
+        });
+    };
+
+    view! {
+        <div class="bg-card text-card-foreground rounded-lg shadow-sm p-6 mt-6">
+            <h3 class="text-lg font-medium mb-4">Import Tradebook</h3>
+            <div class="flex flex-col md:flex-row gap-4 items-end">
+                <div>
+                    <label class="block text-sm font-medium mb-1">Tradebook or contract note (CSV)</label>
+                    <input type="file" accept=".csv,text/csv" class="text-sm" node_ref=file_input />
+                </div>
+                <button
+                    class="px-4 py-2 bg-secondary text-secondary-foreground rounded-md"
+                    on:click=load_preview
+                >
+                    Preview
+                </button>
+            </div>
+
+            {move || message.get().map(|text| view! {
+                <p class="text-sm text-green-500 mt-3">{text}</p>
+            })}
+            {move || error.get().map(|text| view! {
+                <p class="text-sm text-red-500 mt-3">{text}</p>
+            })}
+
+            {move || preview.get().map(|plan| {
+                let nothing_to_do = plan.created.is_empty() && plan.updated.is_empty();
+                let rows = plan
+                    .created
+                    .iter()
+                    .map(|entry| ("New", None, entry.clone()))
+                    .chain(plan.updated.iter().map(|diff| ("Update", Some(diff.before.clone()), diff.after.clone())))
+                    .collect::<Vec<_>>();
+                view! {
+                    <div class="mt-4">
+                        <p class="text-sm text-muted-foreground mb-2">
+                            {format!(
+                                "{} new trades, {} open trades extended, {} duplicate fills skipped",
+                                plan.created.len(),
+                                plan.updated.len(),
+                                plan.duplicates
+                            )}
+                        </p>
+                        {plan.errors.into_iter().map(|line| view! {
+                            <p class="text-xs text-red-500">{line}</p>
+                        }).collect::<Vec<_>>()}
+                        <div class="overflow-x-auto">
+                            <table class="w-full">
+                                <thead>
+                                    <tr class="border-b border-border">
+                                        <th class="text-left p-3 text-muted-foreground font-medium">Change</th>
+                                        <th class="text-left p-3 text-muted-foreground font-medium">Symbol</th>
+                                        <th class="text-left p-3 text-muted-foreground font-medium">Direction</th>
+                                        <th class="text-left p-3 text-muted-foreground font-medium">Fills</th>
+                                        <th class="text-left p-3 text-muted-foreground font-medium">Status</th>
+                                        <th class="text-left p-3 text-muted-foreground font-medium">Realized PnL</th>
+                                    </tr>
+                                </thead>
+                                <tbody>
+                                    {rows.into_iter().map(|(change, before, after)| {
+                                        let (direction, fills, status, pnl) = summary_cells(&after);
+                                        // Show the before values next to the new ones
+                                        let (fills, status, pnl_text) = match before.as_ref().map(summary_cells) {
+                                            Some((_, old_fills, old_status, old_pnl)) => (
+                                                format!("{} → {}", old_fills, fills),
+                                                format!("{} → {}", old_status, status),
+                                                format!("₹{:.2} → ₹{:.2}", old_pnl, pnl),
+                                            ),
+                                            None => (fills, status, format!("₹{:.2}", pnl)),
+                                        };
+                                        view! {
+                                            <tr class="border-b border-border">
+                                                <td class="p-3">{change}</td>
+                                                <td class="p-3">{after.symbol}</td>
+                                                <td class="p-3">{direction}</td>
+                                                <td class="p-3">{fills}</td>
+                                                <td class="p-3">{status}</td>
+                                                <td class="p-3">
+                                                    <span class={if pnl >= 0.0 { "text-green-500" } else { "text-red-500" }}>
+                                                        {pnl_text}
+                                                    </span>
+                                                </td>
+                                            </tr>
+                                        }
+                                    }).collect::<Vec<_>>()}
+                                </tbody>
+                            </table>
+                        </div>
+                        <div class="mt-4 flex justify-end space-x-2">
+                            <button
+                                class="px-4 py-2 bg-primary text-primary-foreground rounded-md"
+                                disabled=nothing_to_do
+                                on:click=commit
+                            >
+                                Import
+                            </button>
+                            <button
+                                class="px-4 py-2 bg-secondary text-secondary-foreground rounded-md"
+                                on:click=move |_| set_preview.set(None)
+                            >
+                                Cancel
+                            </button>
+                        </div>
+                    </div>
+                }
+            })}
+        </div>
+    }
+}
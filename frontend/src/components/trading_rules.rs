@@ -0,0 +1,312 @@
+use leptos::*;
+use serde::{Deserialize, Serialize};
+
+use crate::utils::api::*;
+
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct Rule {
+    #[serde(default)]
+    pub id: String,
+    pub kind: String,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub limit: Option<u32>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub minutes: Option<u32>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub amount: Option<f64>,
+    pub enabled: bool,
+}
+
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct ChecklistItem {
+    #[serde(default)]
+    pub id: String,
+    pub phase: String,
+    pub text: String,
+}
+
+pub const RULE_KINDS: [(&str, &str); 3] = [
+    ("max_trades_per_day", "Max trades per day"),
+    ("no_trading_after_open", "No trading after the open (minutes)"),
+    ("max_loss_per_day", "Max loss per day (₹)"),
+];
+
+pub const CHECKLIST_PHASES: [(&str, &str); 2] = [
+    ("pre_market", "Pre-market"),
+    ("post_market", "Post-market"),
+];
+
+impl Rule {
+    fn new(kind: &str, value: f64) -> Self {
+        Self {
+            id: String::new(),
+            kind: kind.to_string(),
+            limit: (kind == "max_trades_per_day").then_some(value as u32),
+            minutes: (kind == "no_trading_after_open").then_some(value as u32),
+            amount: (kind == "max_loss_per_day").then_some(value),
+            enabled: true,
+        }
+    }
+
+    fn describe(&self) -> String {
+        match self.kind.as_str() {
+            "max_trades_per_day" => {
+                let limit = self.limit.unwrap_or_default();
+                format!("At most {} trade{} a day", limit, if limit == 1 { "" } else { "s" })
+            }
+            "no_trading_after_open" => {
+                format!("No trading in the first {} minutes", self.minutes.unwrap_or_default())
+            }
+            "max_loss_per_day" => {
+                format!("Stop after a ₹{:.2} loss in a day", self.amount.unwrap_or_default())
+            }
+            other => other.to_string(),
+        }
+    }
+}
+
+/// Define personal trading rules and the daily pre- and post-market
+/// checklist.
+#[component]
+pub fn TradingRules(#[prop(into)] on_change: Callback<()>) -> impl IntoView {
+    let (rules, set_rules) = create_signal(Vec::<Rule>::new());
+    let (checklist, set_checklist) = create_signal(Vec::<ChecklistItem>::new());
+    let (new_kind, set_new_kind) = create_signal("max_trades_per_day".to_string());
+    let (new_value, set_new_value) = create_signal(String::new());
+    let (message, set_message) = create_signal(None::<String>);
+    let (error, set_error) = create_signal(None::<String>);
+
+    let load = move || {
+        spawn_local(async move {
+            match get_json::<Vec<Rule>>("/rules").await {
+                Ok(list) => set_rules.set(list),
+                Err(e) => set_error.set(Some(e)),
+            }
+            match get_json::<Vec<ChecklistItem>>("/rules/checklist").await {
+                Ok(list) => set_checklist.set(list),
+                Err(e) => set_error.set(Some(e)),
+            }
+        });
+    };
+    load();
+
+    let refresh = move || {
+        load();
+        on_change.call(());
+    };
+
+    let add = move |_| {
+        set_error.set(None);
+        let Ok(value) = new_value.get().trim().parse::<f64>() else {
+            set_error.set(Some("Enter a number for the rule".to_string()));
+            return;
+        };
+        let rule = Rule::new(&new_kind.get(), value);
+        spawn_local(async move {
+            match post_json::<_, Rule>("/rules", &rule).await {
+                Ok(_) => {
+                    set_new_value.set(String::new());
+                    refresh();
+                }
+                Err(e) => set_error.set(Some(e)),
+            }
+        });
+    };
+
+    let toggle = move |mut rule: Rule| {
+        rule.enabled = !rule.enabled;
+        spawn_local(async move {
+            match put_json::<_, Rule>(&format!("/rules/{}", encode(&rule.id)), &rule).await {
+                Ok(_) => refresh(),
+                Err(e) => set_error.set(Some(e)),
+            }
+        });
+    };
+
+    let remove = move |id: String| {
+        spawn_local(async move {
+            match delete_json::<String>(&format!("/rules/{}", encode(&id))).await {
+                Ok(_) => refresh(),
+                Err(e) => set_error.set(Some(e)),
+            }
+        });
+    };
+
+    let save_checklist = move |_| {
+        set_error.set(None);
+        set_message.set(None);
+        let items = checklist.get();
+        spawn_local(async move {
+            match put_json::<_, Vec<ChecklistItem>>("/rules/checklist", &items).await {
+                Ok(saved) => {
+                    set_checklist.set(saved);
+                    set_message.set(Some("Checklist saved".to_string()));
+                    on_change.call(());
+                }
+                Err(e) => set_error.set(Some(e)),
+            }
+        });
+    };
+
+    view! {
+        <div>
+            {move || error.get().map(|text| view! {
+                <p class="text-sm text-red-500 mb-3">{text}</p>
+            })}
+
+            <div class="bg-card text-card-foreground rounded-lg shadow-sm p-6">
+                <h3 class="text-lg font-medium mb-4">Trading Rules</h3>
+                <div class="flex flex-col md:flex-row gap-2 items-end mb-4">
+                    <div>
+                        <label class="block text-sm font-medium mb-1">Rule</label>
+                        <select
+                            class="px-3 py-2 border border-input rounded-md"
+                            on:change=move |ev| set_new_kind.set(event_target_value(&ev))
+                        >
+                            {RULE_KINDS.into_iter().map(|(kind, label)| view! {
+                                <option value=kind selected=move || new_kind.get() == kind>{label}</option>
+                            }).collect::<Vec<_>>()}
+                        </select>
+                    </div>
+                    <div>
+                        <label class="block text-sm font-medium mb-1">Value</label>
+                        <input
+                            type="number"
+                            min="1"
+                            class="px-3 py-2 border border-input rounded-md w-32"
+                            prop:value=move || new_value.get()
+                            on:input=move |ev| set_new_value.set(event_target_value(&ev))
+                        />
+                    </div>
+                    <button
+                        class="px-4 py-2 bg-primary text-primary-foreground rounded-md"
+                        on:click=add
+                    >
+                        Add Rule
+                    </button>
+                </div>
+
+                <table class="w-full">
+                    <thead>
+                        <tr class="border-b border-border">
+                            <th class="text-left p-3 text-muted-foreground font-medium">Rule</th>
+                            <th class="text-left p-3 text-muted-foreground font-medium">Enabled</th>
+                            <th class="p-3"></th>
+                        </tr>
+                    </thead>
+                    <tbody>
+                        {move || rules.get().into_iter().map(|rule| {
+                            let id = rule.id.clone();
+                            let enabled = rule.enabled;
+                            let toggled = rule.clone();
+                            view! {
+                                <tr class="border-b border-border">
+                                    <td class="p-3">{rule.describe()}</td>
+                                    <td class="p-3">
+                                        <input
+                                            type="checkbox"
+                                            checked=enabled
+                                            on:change=move |_| toggle(toggled.clone())
+                                        />
+                                    </td>
+                                    <td class="p-3 text-right">
+                                        <button
+                                            class="px-2 py-1 text-muted-foreground text-sm"
+                                            on:click=move |_| remove(id.clone())
+                                        >
+                                            Delete
+                                        </button>
+                                    </td>
+                                </tr>
+                            }
+                        }).collect::<Vec<_>>()}
+                    </tbody>
+                </table>
+                {move || rules.get().is_empty().then(|| view! {
+                    <p class="text-sm text-muted-foreground mt-3">
+                        No rules yet. Trades are flagged once you add one.
+                    </p>
+                })}
+            </div>
+
+            <div class="bg-card text-card-foreground rounded-lg shadow-sm p-6 mt-6">
+                <h3 class="text-lg font-medium mb-4">Daily Checklist</h3>
+                <div class="grid grid-cols-1 md:grid-cols-2 gap-6">
+                    {CHECKLIST_PHASES.into_iter().map(|(phase, label)| {
+                        let (draft, set_draft) = create_signal(String::new());
+                        view! {
+                            <div>
+                                <h4 class="font-medium mb-2">{label}</h4>
+                                <div class="space-y-2">
+                                    {move || checklist
+                                        .get()
+                                        .into_iter()
+                                        .enumerate()
+                                        .filter(|(_, item)| item.phase == phase)
+                                        .map(|(index, item)| view! {
+                                            <div class="flex gap-2">
+                                                <input
+                                                    type="text"
+                                                    class="flex-1 px-2 py-1 border border-input rounded-md"
+                                                    value=item.text
+                                                    on:change=move |ev| set_checklist.update(|items| {
+                                                        items[index].text = event_target_value(&ev);
+                                                    })
+                                                />
+                                                <button
+                                                    class="px-2 py-1 text-muted-foreground text-sm"
+                                                    on:click=move |_| set_checklist.update(|items| {
+                                                        items.remove(index);
+                                                    })
+                                                >
+                                                    Remove
+                                                </button>
+                                            </div>
+                                        })
+                                        .collect::<Vec<_>>()}
+                                    <div class="flex gap-2">
+                                        <input
+                                            type="text"
+                                            class="flex-1 px-2 py-1 border border-input rounded-md"
+                                            placeholder="New item"
+                                            prop:value=move || draft.get()
+                                            on:input=move |ev| set_draft.set(event_target_value(&ev))
+                                        />
+                                        <button
+                                            class="px-2 py-1 bg-secondary text-secondary-foreground rounded-md text-sm"
+                                            on:click=move |_| {
+                                                let text = draft.get().trim().to_string();
+                                                if text.is_empty() {
+                                                    return;
+                                                }
+                                                set_checklist.update(|items| items.push(ChecklistItem {
+                                                    id: String::new(),
+                                                    phase: phase.to_string(),
+                                                    text,
+                                                }));
+                                                set_draft.set(String::new());
+                                            }
+                                        >
+                                            Add
+                                        </button>
+                                    </div>
+                                </div>
+                            </div>
+                        }
+                    }).collect::<Vec<_>>()}
+                </div>
+                <div class="flex justify-end items-center gap-3 mt-4">
+                    {move || message.get().map(|text| view! {
+                        <span class="text-sm text-green-500">{text}</span>
+                    })}
+                    <button
+                        class="px-4 py-2 bg-primary text-primary-foreground rounded-md"
+                        on:click=save_checklist
+                    >
+                        Save Checklist
+                    </button>
+                </div>
+            </div>
+        </div>
+    }
+}
@@ -0,0 +1,530 @@
+use leptos::*;
+use serde::{Deserialize, Serialize};
+
+use crate::utils::api::*;
+use crate::utils::format::rupees;
+
+#[derive(Clone, Debug, Default, PartialEq, Serialize, Deserialize)]
+struct DcfInputs {
+    base_cash_flow: f64,
+    growth: f64,
+    growth_years: u32,
+    fade_years: u32,
+    terminal_growth: f64,
+    discount_rate: f64,
+    net_debt: f64,
+    shares: f64,
+}
+
+#[derive(Clone, Debug, Default, PartialEq, Serialize, Deserialize)]
+struct DdmInputs {
+    dividend: f64,
+    growth: f64,
+    growth_years: u32,
+    terminal_growth: f64,
+    required_return: f64,
+}
+
+#[derive(Clone, Debug, Default, PartialEq, Serialize, Deserialize)]
+struct Assumptions {
+    dcf: DcfInputs,
+    ddm: DdmInputs,
+}
+
+#[derive(Clone, Debug, Serialize, Deserialize)]
+struct ValuationInputs {
+    price: Option<f64>,
+    eps: Option<f64>,
+    book_value_per_share: Option<f64>,
+    defaults: Assumptions,
+}
+
+#[derive(Clone, Debug, Serialize, Deserialize)]
+struct ProjectedYear {
+    year: u32,
+    growth: f64,
+    cash_flow: f64,
+    present_value: f64,
+}
+
+#[derive(Clone, Debug, Serialize, Deserialize)]
+struct DcfResult {
+    projections: Vec<ProjectedYear>,
+    terminal_present_value: f64,
+    enterprise_value: f64,
+    equity_value: f64,
+    per_share: f64,
+    terminal_share: f64,
+}
+
+#[derive(Clone, Debug, Serialize, Deserialize)]
+struct ReverseDcf {
+    implied_growth: f64,
+}
+
+#[derive(Clone, Debug, Serialize, Deserialize)]
+struct DdmResult {
+    per_share: f64,
+}
+
+#[derive(Clone, Debug, Serialize, Deserialize)]
+struct Sensitivity {
+    title: String,
+    row_label: String,
+    column_label: String,
+    rows: Vec<f64>,
+    columns: Vec<f64>,
+    values: Vec<Vec<Option<f64>>>,
+}
+
+#[derive(Clone, Debug, Serialize, Deserialize)]
+struct ValuationReport {
+    price: Option<f64>,
+    dcf: Option<DcfResult>,
+    reverse_dcf: Option<ReverseDcf>,
+    graham_number: Option<f64>,
+    ddm: Option<DdmResult>,
+    sensitivity: Vec<Sensitivity>,
+    notes: Vec<String>,
+}
+
+#[derive(Clone, Debug, Serialize, Deserialize)]
+struct Scenario {
+    #[serde(default)]
+    id: String,
+    name: String,
+    assumptions: Assumptions,
+    #[serde(default)]
+    saved_at: Option<String>,
+}
+
+/// Value against the market price, e.g. "+12.5% vs price".
+fn upside(value: f64, price: Option<f64>) -> Option<String> {
+    let price = price.filter(|p| *p > 0.0)?;
+    Some(format!("{:+.1}% vs price", (value / price - 1.0) * 100.0))
+}
+
+/// A labelled number input bound to one assumption.
+#[component]
+fn AssumptionInput(
+    label: &'static str,
+    #[prop(into)] value: Signal<f64>,
+    #[prop(into)] on_change: Callback<f64>,
+    #[prop(default = "any")] step: &'static str,
+) -> impl IntoView {
+    view! {
+        <div>
+            <label class="block text-xs font-medium text-muted-foreground mb-1">{label}</label>
+            <input
+                type="number"
+                step=step
+                class="w-full px-2 py-1 border border-input rounded-md"
+                prop:value=move || value.get().to_string()
+                on:change=move |ev| {
+                    if let Ok(parsed) = event_target_value(&ev).parse::<f64>() {
+                        on_change.call(parsed);
+                    }
+                }
+            />
+        </div>
+    }
+}
+
+fn result_card(title: &str, value: Option<f64>, price: Option<f64>, detail: String) -> impl IntoView {
+    view! {
+        <div class="bg-card text-card-foreground rounded-lg p-4 shadow-sm border border-border">
+            <h4 class="text-sm font-medium text-muted-foreground">{title.to_string()}</h4>
+            <p class="text-2xl font-bold mt-1">{value.map_or("-".to_string(), rupees)}</p>
+            <p class="text-xs text-muted-foreground mt-1">
+                {value.and_then(|v| upside(v, price)).unwrap_or(detail)}
+            </p>
+        </div>
+    }
+}
+
+/// DCF, reverse DCF, Graham number and dividend discount valuations of
+/// one stock, pre-filled from its statements, with sensitivity tables
+/// and named scenarios.
+#[component]
+pub fn ValuationModels(#[prop(into)] symbol: Signal<String>) -> impl IntoView {
+    let (inputs, set_inputs) = create_signal(None::<ValuationInputs>);
+    let (assumptions, set_assumptions) = create_signal(Assumptions::default());
+    let (report, set_report) = create_signal(None::<ValuationReport>);
+    let (scenarios, set_scenarios) = create_signal(Vec::<Scenario>::new());
+    let (scenario_name, set_scenario_name) = create_signal(String::new());
+    let (error, set_error) = create_signal(None::<String>);
+
+    let calculate = move || {
+        let symbol = symbol.get_untracked();
+        let current = assumptions.get_untracked();
+        spawn_local(async move {
+            let path = format!("/valuation/{}", encode(&symbol));
+            match post_json::<_, ValuationReport>(&path, &current).await {
+                Ok(result) => set_report.set(Some(result)),
+                Err(e) => set_error.set(Some(e)),
+            }
+        });
+    };
+
+    let load_scenarios = move || {
+        let symbol = symbol.get_untracked();
+        spawn_local(async move {
+            let path = format!("/valuation/{}/scenarios", encode(&symbol));
+            if let Ok(list) = get_json::<Vec<Scenario>>(&path).await {
+                set_scenarios.set(list);
+            }
+        });
+    };
+
+    create_effect(move |_| {
+        let symbol = symbol.get();
+        set_error.set(None);
+        set_report.set(None);
+        if symbol.is_empty() {
+            return;
+        }
+        spawn_local(async move {
+            match get_json::<ValuationInputs>(&format!("/valuation/{}", encode(&symbol))).await {
+                Ok(loaded) => {
+                    set_assumptions.set(loaded.defaults.clone());
+                    set_inputs.set(Some(loaded));
+                    calculate();
+                    load_scenarios();
+                }
+                Err(e) => {
+                    set_inputs.set(None);
+                    set_error.set(Some(e));
+                }
+            }
+        });
+    });
+
+    // Recalculate whenever an assumption changes
+    let update = move |change: Box<dyn Fn(&mut Assumptions)>| {
+        set_assumptions.update(|a| change(a));
+        set_error.set(None);
+        calculate();
+    };
+
+    let save_scenario = move |_| {
+        let name = scenario_name.get().trim().to_string();
+        if name.is_empty() {
+            set_error.set(Some("Name the scenario first".to_string()));
+            return;
+        }
+        let scenario = Scenario {
+            id: String::new(),
+            name,
+            assumptions: assumptions.get(),
+            saved_at: None,
+        };
+        let symbol = symbol.get();
+        spawn_local(async move {
+            let path = format!("/valuation/{}/scenarios", encode(&symbol));
+            match post_json::<_, Scenario>(&path, &scenario).await {
+                Ok(_) => {
+                    set_scenario_name.set(String::new());
+                    load_scenarios();
+                }
+                Err(e) => set_error.set(Some(e)),
+            }
+        });
+    };
+
+    let delete_scenario = move |id: String| {
+        spawn_local(async move {
+            match delete_json::<String>(&format!("/valuation/scenarios/{}", encode(&id))).await {
+                Ok(_) => load_scenarios(),
+                Err(e) => set_error.set(Some(e)),
+            }
+        });
+    };
+
+    let dcf = move |get: fn(&DcfInputs) -> f64| Signal::derive(move || get(&assumptions.get().dcf));
+    let ddm = move |get: fn(&DdmInputs) -> f64| Signal::derive(move || get(&assumptions.get().ddm));
+
+    view! {
+        <div class="bg-card text-card-foreground rounded-lg shadow-sm p-6 mt-6">
+            <div class="flex justify-between items-center mb-4">
+                <h3 class="text-lg font-medium">Intrinsic Value</h3>
+                <button
+                    class="px-3 py-1 bg-secondary text-secondary-foreground rounded-md text-sm"
+                    on:click=move |_| {
+                        if let Some(loaded) = inputs.get() {
+                            set_assumptions.set(loaded.defaults);
+                            calculate();
+                        }
+                    }
+                >
+                    Reset to Defaults
+                </button>
+            </div>
+
+            {move || error.get().map(|text| view! {
+                <p class="text-sm text-red-500 mb-3">{text}</p>
+            })}
+
+            <div class="grid grid-cols-1 md:grid-cols-2 gap-6">
+                <div>
+                    <h4 class="font-medium mb-2">Discounted Cash Flow (₹ Cr)</h4>
+                    <div class="grid grid-cols-2 md:grid-cols-4 gap-2">
+                        <AssumptionInput
+                            label="Base free cash flow"
+                            value=dcf(|d| d.base_cash_flow)
+                            on_change=move |v| update(Box::new(move |a| a.dcf.base_cash_flow = v))
+                        />
+                        <AssumptionInput
+                            label="Growth %"
+                            step="0.5"
+                            value=dcf(|d| d.growth)
+                            on_change=move |v| update(Box::new(move |a| a.dcf.growth = v))
+                        />
+                        <AssumptionInput
+                            label="Growth years"
+                            step="1"
+                            value=dcf(|d| d.growth_years as f64)
+                            on_change=move |v: f64| update(Box::new(move |a| a.dcf.growth_years = v.max(0.0) as u32))
+                        />
+                        <AssumptionInput
+                            label="Fade years"
+                            step="1"
+                            value=dcf(|d| d.fade_years as f64)
+                            on_change=move |v: f64| update(Box::new(move |a| a.dcf.fade_years = v.max(0.0) as u32))
+                        />
+                        <AssumptionInput
+                            label="Terminal growth %"
+                            step="0.5"
+                            value=dcf(|d| d.terminal_growth)
+                            on_change=move |v| update(Box::new(move |a| a.dcf.terminal_growth = v))
+                        />
+                        <AssumptionInput
+                            label="Discount rate %"
+                            step="0.5"
+                            value=dcf(|d| d.discount_rate)
+                            on_change=move |v| update(Box::new(move |a| a.dcf.discount_rate = v))
+                        />
+                        <AssumptionInput
+                            label="Net debt"
+                            value=dcf(|d| d.net_debt)
+                            on_change=move |v| update(Box::new(move |a| a.dcf.net_debt = v))
+                        />
+                        <AssumptionInput
+                            label="Shares (Cr)"
+                            value=dcf(|d| d.shares)
+                            on_change=move |v| update(Box::new(move |a| a.dcf.shares = v))
+                        />
+                    </div>
+                </div>
+                <div>
+                    <h4 class="font-medium mb-2">Dividend Discount (₹ per share)</h4>
+                    <div class="grid grid-cols-2 md:grid-cols-3 gap-2">
+                        <AssumptionInput
+                            label="Dividend"
+                            value=ddm(|d| d.dividend)
+                            on_change=move |v| update(Box::new(move |a| a.ddm.dividend = v))
+                        />
+                        <AssumptionInput
+                            label="Growth %"
+                            step="0.5"
+                            value=ddm(|d| d.growth)
+                            on_change=move |v| update(Box::new(move |a| a.ddm.growth = v))
+                        />
+                        <AssumptionInput
+                            label="Growth years"
+                            step="1"
+                            value=ddm(|d| d.growth_years as f64)
+                            on_change=move |v: f64| update(Box::new(move |a| a.ddm.growth_years = v.max(0.0) as u32))
+                        />
+                        <AssumptionInput
+                            label="Terminal growth %"
+                            step="0.5"
+                            value=ddm(|d| d.terminal_growth)
+                            on_change=move |v| update(Box::new(move |a| a.ddm.terminal_growth = v))
+                        />
+                        <AssumptionInput
+                            label="Required return %"
+                            step="0.5"
+                            value=ddm(|d| d.required_return)
+                            on_change=move |v| update(Box::new(move |a| a.ddm.required_return = v))
+                        />
+                    </div>
+                </div>
+            </div>
+
+            {move || report.get().map(|r| {
+                let price = r.price;
+                let book = inputs.get().and_then(|i| i.eps.zip(i.book_value_per_share));
+                view! {
+                    <div>
+                        <div class="grid grid-cols-2 md:grid-cols-4 gap-4 mt-6">
+                            {result_card(
+                                "DCF Value",
+                                r.dcf.as_ref().map(|d| d.per_share),
+                                price,
+                                "Not enough inputs".to_string(),
+                            )}
+                            <div class="bg-card text-card-foreground rounded-lg p-4 shadow-sm border border-border">
+                                <h4 class="text-sm font-medium text-muted-foreground">Reverse DCF</h4>
+                                <p class="text-2xl font-bold mt-1">
+                                    {r.reverse_dcf.as_ref().map_or("-".to_string(), |d| format!("{:.1}%", d.implied_growth))}
+                                </p>
+                                <p class="text-xs text-muted-foreground mt-1">
+                                    {price.map_or("No price".to_string(), |p| format!("Growth priced in at {}", rupees(p)))}
+                                </p>
+                            </div>
+                            {result_card(
+                                "Graham Number",
+                                r.graham_number,
+                                price,
+                                book.map_or("Needs EPS and book value".to_string(), |(eps, bvps)| {
+                                    format!("EPS {:.2}, book {:.2}", eps, bvps)
+                                }),
+                            )}
+                            {result_card(
+                                "Dividend Discount",
+                                r.ddm.as_ref().map(|d| d.per_share),
+                                price,
+                                "Not enough inputs".to_string(),
+                            )}
+                        </div>
+
+                        {(!r.notes.is_empty()).then(|| view! {
+                            <ul class="text-xs text-muted-foreground list-disc pl-5 mt-3">
+                                {r.notes.iter().map(|n| view! { <li>{n.clone()}</li> }).collect::<Vec<_>>()}
+                            </ul>
+                        })}
+
+                        {r.dcf.map(|d| view! {
+                            <div class="overflow-x-auto mt-6">
+                                <h4 class="font-medium mb-2">
+                                    {format!(
+                                        "Projection · EV ₹{:.0} Cr, equity ₹{:.0} Cr, {:.0}% from terminal value",
+                                        d.enterprise_value, d.equity_value, d.terminal_share
+                                    )}
+                                </h4>
+                                <table class="w-full text-sm">
+                                    <thead>
+                                        <tr class="border-b border-border">
+                                            <th class="text-left p-2 text-muted-foreground font-medium">Year</th>
+                                            <th class="text-right p-2 text-muted-foreground font-medium">Growth</th>
+                                            <th class="text-right p-2 text-muted-foreground font-medium">Cash Flow</th>
+                                            <th class="text-right p-2 text-muted-foreground font-medium">Present Value</th>
+                                        </tr>
+                                    </thead>
+                                    <tbody>
+                                        {d.projections.into_iter().map(|p| view! {
+                                            <tr class="border-b border-border">
+                                                <td class="p-2">{p.year}</td>
+                                                <td class="p-2 text-right">{format!("{:.1}%", p.growth)}</td>
+                                                <td class="p-2 text-right">{format!("{:.0}", p.cash_flow)}</td>
+                                                <td class="p-2 text-right">{format!("{:.0}", p.present_value)}</td>
+                                            </tr>
+                                        }).collect::<Vec<_>>()}
+                                        <tr>
+                                            <td class="p-2" colspan="3">Terminal value</td>
+                                            <td class="p-2 text-right">{format!("{:.0}", d.terminal_present_value)}</td>
+                                        </tr>
+                                    </tbody>
+                                </table>
+                            </div>
+                        })}
+
+                        <div class="grid grid-cols-1 lg:grid-cols-3 gap-4 mt-6">
+                            {r.sensitivity.into_iter().map(|s| view! {
+                                <div class="overflow-x-auto">
+                                    <h4 class="text-sm font-medium mb-2">{s.title.clone()}</h4>
+                                    <table class="w-full text-xs">
+                                        <thead>
+                                            <tr class="border-b border-border">
+                                                <th class="p-1 text-left text-muted-foreground font-medium">
+                                                    {format!("{} ↓ / {} →", s.row_label, s.column_label)}
+                                                </th>
+                                                {s.columns.iter().map(|c| view! {
+                                                    <th class="p-1 text-right text-muted-foreground font-medium">{format!("{:.1}%", c)}</th>
+                                                }).collect::<Vec<_>>()}
+                                            </tr>
+                                        </thead>
+                                        <tbody>
+                                            {s.rows.iter().zip(s.values.iter()).enumerate().map(|(i, (row, values))| view! {
+                                                <tr class="border-b border-border">
+                                                    <td class="p-1 text-muted-foreground">{format!("{:.1}%", row)}</td>
+                                                    {values.iter().enumerate().map(|(j, value)| {
+                                                        // The middle cell is the current assumption
+                                                        let class = if i == 2 && j == 2 {
+                                                            "p-1 text-right font-bold"
+                                                        } else {
+                                                            match (value, price) {
+                                                                (Some(v), Some(p)) if *v >= p => "p-1 text-right text-green-500",
+                                                                (Some(_), Some(_)) => "p-1 text-right text-red-500",
+                                                                _ => "p-1 text-right",
+                                                            }
+                                                        };
+                                                        view! {
+                                                            <td class=class>{value.map_or("-".to_string(), |v| format!("{:.0}", v))}</td>
+                                                        }
+                                                    }).collect::<Vec<_>>()}
+                                                </tr>
+                                            }).collect::<Vec<_>>()}
+                                        </tbody>
+                                    </table>
+                                </div>
+                            }).collect::<Vec<_>>()}
+                        </div>
+                    </div>
+                }
+            })}
+
+            <div class="mt-6">
+                <h4 class="font-medium mb-2">Scenarios</h4>
+                <div class="flex gap-2 mb-3">
+                    <input
+                        type="text"
+                        class="flex-1 px-3 py-2 border border-input rounded-md"
+                        placeholder="Scenario name, e.g. Bear case"
+                        prop:value=move || scenario_name.get()
+                        on:input=move |ev| set_scenario_name.set(event_target_value(&ev))
+                    />
+                    <button
+                        class="px-4 py-2 bg-primary text-primary-foreground rounded-md"
+                        on:click=save_scenario
+                    >
+                        Save Scenario
+                    </button>
+                </div>
+                <div class="space-y-1">
+                    {move || scenarios.get().into_iter().map(|s| {
+                        let id = s.id.clone();
+                        let saved = s.assumptions.clone();
+                        view! {
+                            <div class="flex justify-between items-center text-sm border-b border-border py-1">
+                                <span>
+                                    {s.name.clone()}
+                                    <span class="text-xs text-muted-foreground ml-2">
+                                        {s.saved_at.clone().unwrap_or_default().replace('T', " ")}
+                                    </span>
+                                </span>
+                                <span class="space-x-2">
+                                    <button
+                                        class="px-2 py-1 bg-secondary text-secondary-foreground rounded-md"
+                                        on:click=move |_| {
+                                            set_assumptions.set(saved.clone());
+                                            calculate();
+                                        }
+                                    >
+                                        Load
+                                    </button>
+                                    <button
+                                        class="px-2 py-1 text-muted-foreground"
+                                        on:click=move |_| delete_scenario(id.clone())
+                                    >
+                                        Delete
+                                    </button>
+                                </span>
+                            </div>
+                        }
+                    }).collect::<Vec<_>>()}
+                </div>
+            </div>
+        </div>
+    }
+}
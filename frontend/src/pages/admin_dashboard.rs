@@ -0,0 +1,296 @@
+use leptos::*;
+use serde::{Deserialize, Serialize};
+
+use crate::components::stat_card::*;
+use crate::utils::api::*;
+
+#[derive(Clone, Debug, Serialize, Deserialize)]
+struct Issue {
+    id: u64,
+    symbol: String,
+    date: String,
+    kind: String,
+    detail: String,
+    status: String,
+}
+
+#[derive(Clone, Debug, Serialize, Deserialize)]
+struct ScanSummary {
+    ran_at: String,
+    symbols: usize,
+    open_issues: usize,
+    new_issues: usize,
+}
+
+#[derive(Clone, Debug, Deserialize)]
+struct FixResponse {
+    scan: ScanSummary,
+}
+
+#[derive(Clone, Debug, Default, Serialize)]
+struct Correction {
+    open: Option<f64>,
+    high: Option<f64>,
+    low: Option<f64>,
+    close: Option<f64>,
+    volume: u64,
+}
+
+impl Correction {
+    /// Whether every price has been entered and is above zero.
+    fn is_complete(&self) -> bool {
+        [self.open, self.high, self.low, self.close]
+            .iter()
+            .all(|p| p.is_some_and(|p| p > 0.0))
+    }
+}
+
+fn issue_label(kind: &str) -> &'static str {
+    match kind {
+        "missing_bar" => "Missing bar",
+        "non_positive_price" => "Zero/negative price",
+        "ohlc_inconsistent" => "OHLC inconsistent",
+        "outlier_spike" => "Outlier spike",
+        "stale_symbol" => "Stale symbol",
+        _ => "Other",
+    }
+}
+
+#[component]
+pub fn AdminDashboard() -> impl IntoView {
+    let (issues, set_issues) = create_signal(Vec::<Issue>::new());
+    let (summary, set_summary) = create_signal(None::<ScanSummary>);
+    let (status_filter, set_status_filter) = create_signal("open".to_string());
+    let (editing, set_editing) = create_signal(None::<u64>);
+    let (correction, set_correction) = create_signal(Correction::default());
+    let (is_loading, set_loading) = create_signal(false);
+    let (error, set_error) = create_signal(None::<String>);
+
+    let load_issues = move || {
+        let status = status_filter.get_untracked();
+        let path = if status == "all" {
+            "/data-quality/issues".to_string()
+        } else {
+            format!("/data-quality/issues?status={}", status)
+        };
+        spawn_local(async move {
+            match get_json::<Vec<Issue>>(&path).await {
+                Ok(list) => set_issues.set(list),
+                Err(e) => set_error.set(Some(e)),
+            }
+        });
+    };
+
+    spawn_local(async move {
+        if let Ok(Some(last)) = get_json::<Option<ScanSummary>>("/data-quality/scan").await {
+            set_summary.set(Some(last));
+        }
+    });
+    load_issues();
+
+    let scan_now = move |_| {
+        set_loading.set(true);
+        set_error.set(None);
+        spawn_local(async move {
+            match post_json::<_, ScanSummary>("/data-quality/scan", &()).await {
+                Ok(result) => {
+                    set_summary.set(Some(result));
+                    load_issues();
+                }
+                Err(e) => set_error.set(Some(e)),
+            }
+            set_loading.set(false);
+        });
+    };
+
+    let run_fix = move |path: String, body: Option<Correction>| {
+        set_error.set(None);
+        spawn_local(async move {
+            let result = match body {
+                Some(correction) => post_json::<_, FixResponse>(&path, &correction).await,
+                None => post_json::<_, FixResponse>(&path, &()).await,
+            };
+            match result {
+                Ok(fix) => {
+                    set_summary.set(Some(fix.scan));
+                    set_editing.set(None);
+                    load_issues();
+                }
+                Err(e) => set_error.set(Some(e)),
+            }
+        });
+    };
+
+    let ignore = move |id: u64| {
+        spawn_local(async move {
+            match post_json::<_, Issue>(&format!("/data-quality/issues/{}/ignore", id), &()).await {
+                Ok(_) => load_issues(),
+                Err(e) => set_error.set(Some(e)),
+            }
+        });
+    };
+
+    let handle_correction = move |field: &'static str, ev: web_sys::Event| {
+        let value = event_target_value(&ev);
+        set_correction.update(|c| match field {
+            "open" => c.open = value.parse().ok(),
+            "high" => c.high = value.parse().ok(),
+            "low" => c.low = value.parse().ok(),
+            "close" => c.close = value.parse().ok(),
+            "volume" => c.volume = value.parse().unwrap_or(0),
+            _ => {}
+        });
+    };
+
+    view! {
+        <div>
+            <div class="flex justify-between items-center mb-6">
+                <h1 class="text-2xl font-bold">Admin Dashboard</h1>
+                <button
+                    class="px-4 py-2 bg-primary text-primary-foreground rounded-md"
+                    on:click=scan_now
+                    disabled=is_loading
+                >
+                    {move || if is_loading() { "Scanning..." } else { "Scan Now" }}
+                </button>
+            </div>
+
+            {move || summary.get().map(|last| view! {
+                <div class="grid grid-cols-1 md:grid-cols-3 gap-4 mb-6">
+                    <StatCard stat=StatData {
+                        title: "Symbols Scanned".to_string(),
+                        value: last.symbols.to_string(),
+                        description: Some(format!("Last run {}", last.ran_at.replace('T', " "))),
+                    } />
+                    <StatCard stat=StatData {
+                        title: "Open Issues".to_string(),
+                        value: last.open_issues.to_string(),
+                        description: None,
+                    } />
+                    <StatCard stat=StatData {
+                        title: "New Issues".to_string(),
+                        value: last.new_issues.to_string(),
+                        description: Some("Found by the last scan".to_string()),
+                    } />
+                </div>
+            })}
+
+            {move || error.get().map(|message| view! {
+                <p class="text-sm text-red-500 mb-4">{message}</p>
+            })}
+
+            <div class="bg-card text-card-foreground rounded-lg shadow-sm overflow-hidden">
+                <div class="p-4 border-b border-border flex justify-between items-center">
+                    <h3 class="text-lg font-medium">Data Quality Issues</h3>
+                    <select
+                        class="px-3 py-2 border border-input rounded-md"
+                        on:change=move |ev| {
+                            set_status_filter.set(event_target_value(&ev));
+                            load_issues();
+                        }
+                    >
+                        <option value="open">Open</option>
+                        <option value="resolved">Resolved</option>
+                        <option value="ignored">Ignored</option>
+                        <option value="all">All</option>
+                    </select>
+                </div>
+                <div class="overflow-x-auto">
+                    <table class="w-full">
+                        <thead>
+                            <tr class="border-b border-border">
+                                <th class="text-left p-3 text-muted-foreground font-medium">Symbol</th>
+                                <th class="text-left p-3 text-muted-foreground font-medium">Date</th>
+                                <th class="text-left p-3 text-muted-foreground font-medium">Issue</th>
+                                <th class="text-left p-3 text-muted-foreground font-medium">Detail</th>
+                                <th class="text-left p-3 text-muted-foreground font-medium">Status</th>
+                                <th class="text-left p-3 text-muted-foreground font-medium">Actions</th>
+                            </tr>
+                        </thead>
+                        <tbody>
+                            {move || issues.get().into_iter().map(|issue| {
+                                let id = issue.id;
+                                let can_correct = issue.kind != "stale_symbol";
+                                view! {
+                                    <tr class="border-b border-border">
+                                        <td class="p-3">{issue.symbol}</td>
+                                        <td class="p-3">{issue.date}</td>
+                                        <td class="p-3">{issue_label(&issue.kind)}</td>
+                                        <td class="p-3 text-muted-foreground">{issue.detail}</td>
+                                        <td class="p-3">{issue.status}</td>
+                                        <td class="p-3 space-x-2 whitespace-nowrap">
+                                            <button
+                                                class="px-2 py-1 bg-secondary text-secondary-foreground rounded-md text-sm"
+                                                on:click=move |_| run_fix(format!("/data-quality/issues/{}/reimport", id), None)
+                                            >
+                                                Re-import
+                                            </button>
+                                            {can_correct.then(|| view! {
+                                                <button
+                                                    class="px-2 py-1 bg-secondary text-secondary-foreground rounded-md text-sm"
+                                                    on:click=move |_| {
+                                                        set_correction.set(Correction::default());
+                                                        set_editing.set(Some(id));
+                                                    }
+                                                >
+                                                    Correct
+                                                </button>
+                                            })}
+                                            <button
+                                                class="px-2 py-1 text-muted-foreground text-sm"
+                                                on:click=move |_| ignore(id)
+                                            >
+                                                Ignore
+                                            </button>
+                                        </td>
+                                    </tr>
+                                    {move || (editing.get() == Some(id)).then(|| view! {
+                                        <tr class="border-b border-border bg-muted">
+                                            <td class="p-3" colspan="6">
+                                                <div class="flex flex-wrap gap-2 items-end">
+                                                    {["open", "high", "low", "close", "volume"].into_iter().map(|field| view! {
+                                                        <div>
+                                                            <label class="block text-xs font-medium mb-1">{field.to_uppercase()}</label>
+                                                            <input
+                                                                type="number"
+                                                                step="0.01"
+                                                                class="w-28 px-2 py-1 border border-input rounded-md"
+                                                                on:input=move |ev| handle_correction(field, ev)
+                                                            />
+                                                        </div>
+                                                    }).collect::<Vec<_>>()}
+                                                    <button
+                                                        class="px-3 py-1 bg-primary text-primary-foreground rounded-md"
+                                                        on:click=move |_| {
+                                                            let correction = correction.get();
+                                                            if !correction.is_complete() {
+                                                                set_error.set(Some("Enter an open, high, low and close above zero".to_string()));
+                                                                return;
+                                                            }
+                                                            run_fix(
+                                                                format!("/data-quality/issues/{}/correct", id),
+                                                                Some(correction),
+                                                            )
+                                                        }
+                                                    >
+                                                        Save Bar
+                                                    </button>
+                                                    <button
+                                                        class="px-3 py-1 bg-secondary text-secondary-foreground rounded-md"
+                                                        on:click=move |_| set_editing.set(None)
+                                                    >
+                                                        Cancel
+                                                    </button>
+                                                </div>
+                                            </td>
+                                        </tr>
+                                    })}
+                                }
+                            }).collect::<Vec<_>>()}
+                        </tbody>
+                    </table>
+                </div>
+            </div>
+        </div>
+    }
+}
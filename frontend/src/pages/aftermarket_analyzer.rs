@@ -2,10 +2,13 @@ use leptos::*;
 use leptos_router::*;
 use serde::{Deserialize, Serialize};
 
+use crate::components::symbol_search::*;
+use crate::components::timeframe_select::*;
+use crate::utils::api::*;
+
 #[derive(Clone, Debug, Serialize, Deserialize)]
 struct OHLCVData {
-    symbol: String,
-    date: String,
+    time: String,
     open: f64,
     high: f64,
     low: f64,
@@ -13,87 +16,114 @@ struct OHLCVData {
     volume: i64,
 }
 
+#[derive(Clone, Debug, Deserialize)]
+struct AdjustmentFactor {
+    ex_date: String,
+    action: String,
+    price_factor: f64,
+    cumulative: f64,
+}
+
+#[derive(Clone, Debug, Deserialize)]
+struct AdjustedSeries {
+    bars: Vec<OHLCVData>,
+    factors: Vec<AdjustmentFactor>,
+}
+
+#[derive(Clone, Debug, Deserialize)]
+struct TradingDay {
+    is_trading_day: bool,
+    holiday: Option<String>,
+    nearest_trading_day: String,
+    note: Option<String>,
+}
+
+#[derive(Clone, Debug, Deserialize)]
+struct PreviousTradingDay {
+    date: String,
+}
+
 #[component]
 pub fn AftermarketAnalyzer() -> impl IntoView {
     let (selected_symbol, set_selected_symbol) = create_signal("Nifty 50".to_string());
     let (selected_date, set_selected_date) = create_signal("2025-03-20".to_string());
     let (ohlcv_data, set_ohlcv_data) = create_signal(Vec::<OHLCVData>::new());
+    let (factors, set_factors) = create_signal(Vec::<AdjustmentFactor>::new());
+    let (is_adjusted, set_adjusted) = create_signal(true);
+    let (timeframe, set_timeframe) = create_signal("1D".to_string());
+    let (error, set_error) = create_signal(None::<String>);
     let (is_loading, set_loading) = create_signal(false);
+    let (date_note, set_date_note) = create_signal(None::<String>);
+    // Only the check for the latest date picked may move the date, so a
+    // slow response for an earlier pick can't override a later one
+    let latest_check = store_value(0u32);
     
-    // Mock symbols
-    let symbols = vec![
-        "Nifty 50", "BankNifty", "FinNifty", "Sensex", "Midcap"
-    ];
+    // Default to the last completed session rather than a fixed date
+    spawn_local(async move {
+        if let Ok(previous) = get_json::<PreviousTradingDay>("/calendar/previous-trading-day").await {
+            // Unless a date was picked while this was loading
+            if latest_check.get_value() == 0 {
+                set_selected_date.set(previous.date);
+            }
+        }
+    });
     
-    let handle_symbol_change = move |ev: web_sys::Event| {
-        let input = event_target_value(&ev);
-        set_selected_symbol.set(input);
+    let handle_symbol_change = move |hit: InstrumentHit| {
+        set_selected_symbol.set(hit.symbol);
     };
     
     let handle_date_change = move |ev: web_sys::Event| {
         let input = event_target_value(&ev);
-        set_selected_date.set(input);
+        set_selected_date.set(input.clone());
+        set_date_note.set(None);
+        latest_check.update_value(|id| *id += 1);
+        let check = latest_check.get_value();
+        
+        // Weekends and exchange holidays have no data, snap to the trading day before
+        spawn_local(async move {
+            let result = get_json::<TradingDay>(&format!("/calendar/day/{}", input)).await;
+            if latest_check.get_value() != check {
+                return;
+            }
+            match result {
+                Ok(day) if !day.is_trading_day => {
+                    let reason = day.holiday.unwrap_or_else(|| "weekend".to_string());
+                    set_date_note.set(Some(format!(
+                        "{} is not a trading day ({}), showing {} instead",
+                        input, reason, day.nearest_trading_day
+                    )));
+                    set_selected_date.set(day.nearest_trading_day);
+                }
+                Ok(day) => set_date_note.set(day.note),
+                Err(e) => log::warn!("Failed to check trading day: {}", e),
+            }
+        });
     };
     
     let fetch_data = move |_| {
         set_loading.set(true);
+        set_error.set(None);
         
-        // Mock API call
-        set_timeout(
-            move || {
-                let mock_data = vec![
-                    OHLCVData {
-                        symbol: selected_symbol.get(),
-                        date: "2025-03-20".to_string(),
-                        open: 24510.75,
-                        high: 24650.30,
-                        low: 24435.20,
-                        close: 24587.25,
-                        volume: 128500000,
-                    },
-                    OHLCVData {
-                        symbol: selected_symbol.get(),
-                        date: "2025-03-19".to_string(),
-                        open: 24380.50,
-                        high: 24530.80,
-                        low: 24350.10,
-                        close: 24463.80,
-                        volume: 115300000,
-                    },
-                    OHLCVData {
-                        symbol: selected_symbol.get(),
-                        date: "2025-03-18".to_string(),
-                        open: 24275.30,
-                        high: 24410.60,
-                        low: 24210.90,
-                        close: 24385.40,
-                        volume: 98700000,
-                    },
-                    OHLCVData {
-                        symbol: selected_symbol.get(),
-                        date: "2025-03-17".to_string(),
-                        open: 24150.20,
-                        high: 24320.40,
-                        low: 24080.75,
-                        close: 24275.30,
-                        volume: 106400000,
-                    },
-                    OHLCVData {
-                        symbol: selected_symbol.get(),
-                        date: "2025-03-14".to_string(),
-                        open: 24080.15,
-                        high: 24220.35,
-                        low: 23980.50,
-                        close: 24150.20,
-                        volume: 92800000,
-                    },
-                ];
-                
-                set_ohlcv_data.set(mock_data);
-                set_loading.set(false);
-            },
-            1000,
+        let adjustment = if is_adjusted.get() { "capital" } else { "raw" };
+        let path = format!(
+            "/market/{}/bars?to={}&limit=5&timeframe={}&adjustment={}",
+            encode(&selected_symbol.get()),
+            selected_date.get(),
+            timeframe.get(),
+            adjustment
         );
+        
+        spawn_local(async move {
+            match get_json::<AdjustedSeries>(&path).await {
+                Ok(series) => {
+                    // Latest session first
+                    set_ohlcv_data.set(series.bars.into_iter().rev().collect());
+                    set_factors.set(series.factors);
+                }
+                Err(e) => set_error.set(Some(e)),
+            }
+            set_loading.set(false);
+        });
     };
     
     view! {
@@ -105,32 +135,34 @@ pub fn AftermarketAnalyzer() -> impl IntoView {
             <div class="bg-card text-card-foreground rounded-lg p-6 shadow-sm mb-6">
                 <div class="flex flex-col md:flex-row gap-4 items-end">
                     <div class="w-full md:w-64">
-                        <label class="block text-sm font-medium mb-1">Select Symbol</label>
-                        <select 
-                            class="w-full px-3 py-2 border border-input rounded-md" 
-                            on:change=handle_symbol_change
-                        >
-                            {symbols.into_iter().map(|symbol| {
-                                view! {
-                                    <option 
-                                        value={symbol} 
-                                        selected={move || selected_symbol.get() == symbol}
-                                    >
-                                        {symbol}
-                                    </option>
-                                }
-                            }).collect::<Vec<_>>()}
-                        </select>
+                        <SymbolSearch
+                            value=selected_symbol
+                            on_select=handle_symbol_change
+                        />
                     </div>
                     <div class="w-full md:w-64">
                         <label class="block text-sm font-medium mb-1">Select Date</label>
                         <input 
                             type="date" 
                             class="w-full px-3 py-2 border border-input rounded-md" 
-                            value=selected_date
+                            prop:value=selected_date
                             on:change=handle_date_change
                         />
                     </div>
+                    <div class="w-full md:w-32">
+                        <TimeframeSelect
+                            value=timeframe
+                            on_change=move |value: String| set_timeframe.set(value)
+                        />
+                    </div>
+                    <label class="flex items-center gap-2 py-2">
+                        <input
+                            type="checkbox"
+                            prop:checked=is_adjusted
+                            on:change=move |ev| set_adjusted.set(event_target_checked(&ev))
+                        />
+                        <span class="text-sm">Adjust for corporate actions</span>
+                    </label>
                     <button 
                         class="px-4 py-2 bg-primary text-primary-foreground rounded-md"
                         on:click=fetch_data
@@ -139,8 +171,44 @@ pub fn AftermarketAnalyzer() -> impl IntoView {
                         {move || if is_loading() { "Fetching..." } else { "Fetch Data" }}
                     </button>
                 </div>
+                {move || date_note.get().map(|note| view! {
+                    <p class="text-sm text-muted-foreground mt-3">{note}</p>
+                })}
+                {move || error.get().map(|message| view! {
+                    <p class="text-sm text-red-500 mt-3">{message}</p>
+                })}
             </div>
             
+            {move || (!factors.get().is_empty()).then(|| view! {
+                <div class="bg-card text-card-foreground rounded-lg shadow-sm overflow-hidden mb-6">
+                    <div class="p-4 border-b border-border">
+                        <h3 class="text-lg font-medium">Adjustment Factors</h3>
+                    </div>
+                    <div class="overflow-x-auto">
+                        <table class="w-full">
+                            <thead>
+                                <tr class="border-b border-border">
+                                    <th class="text-left p-3 text-muted-foreground font-medium">Ex-Date</th>
+                                    <th class="text-left p-3 text-muted-foreground font-medium">Action</th>
+                                    <th class="text-left p-3 text-muted-foreground font-medium">Factor</th>
+                                    <th class="text-left p-3 text-muted-foreground font-medium">Cumulative</th>
+                                </tr>
+                            </thead>
+                            <tbody>
+                                {factors.get().into_iter().map(|factor| view! {
+                                    <tr class="border-b border-border">
+                                        <td class="p-3">{factor.ex_date}</td>
+                                        <td class="p-3">{factor.action}</td>
+                                        <td class="p-3">{format!("{:.4}", factor.price_factor)}</td>
+                                        <td class="p-3">{format!("{:.4}", factor.cumulative)}</td>
+                                    </tr>
+                                }).collect::<Vec<_>>()}
+                            </tbody>
+                        </table>
+                    </div>
+                </div>
+            })}
+            
             {move || if !ohlcv_data.get().is_empty() {
                 view! {
                     <div>
@@ -152,7 +220,7 @@ pub fn AftermarketAnalyzer() -> impl IntoView {
                                 <table class="w-full">
                                     <thead>
                                         <tr class="border-b border-border">
-                                            <th class="text-left p-3 text-muted-foreground font-medium">Date</th>
+                                            <th class="text-left p-3 text-muted-foreground font-medium">Time</th>
                                             <th class="text-left p-3 text-muted-foreground font-medium">Open</th>
                                             <th class="text-left p-3 text-muted-foreground font-medium">High</th>
                                             <th class="text-left p-3 text-muted-foreground font-medium">Low</th>
@@ -163,7 +231,7 @@ pub fn AftermarketAnalyzer() -> impl IntoView {
                                     <tbody>
                                         {ohlcv_data.get().into_iter().map(|data| view! {
                                             <tr class="border-b border-border">
-                                                <td class="p-3">{data.date}</td>
+                                                <td class="p-3">{data.time.trim_end_matches("T00:00:00").replace('T', " ")}</td>
                                                 <td class="p-3">{format!("₹{:.2}", data.open)}</td>
                                                 <td class="p-3">{format!("₹{:.2}", data.high)}</td>
                                                 <td class="p-3">{format!("₹{:.2}", data.low)}</td>
@@ -4,10 +4,21 @@ use serde::{Deserialize, Serialize};
 
 use crate::components::analysis_table::*;
 use crate::components::stat_card::*;
+use crate::components::symbol_search::*;
+use crate::components::timeframe_select::*;
 use crate::utils::api::*;
 
+#[derive(Clone, Debug, Serialize, Deserialize)]
+struct Quote {
+    price: f64,
+    change: f64,
+    change_percent: f64,
+    volume: u64,
+}
+
 #[derive(Clone, Debug, Serialize, Deserialize)]
 struct AnalysisResult {
+    quote: Quote,
     statistical: Vec<AnalysisRow>,
     visual: Vec<AnalysisRow>,
     indicator: Vec<AnalysisRow>,
@@ -17,6 +28,8 @@ struct AnalysisResult {
 #[component]
 pub fn Console() -> impl IntoView {
     let (selected_symbol, set_selected_symbol) = create_signal("Nifty 50".to_string());
+    let (timeframe, set_timeframe) = create_signal("1D".to_string());
+    let (error, set_error) = create_signal(None::<String>);
     let (analysis_result, set_analysis_result) = create_signal(None::<AnalysisResult>);
     let (is_loading, set_loading) = create_signal(false);
     let (current_time, set_current_time) = create_signal(String::new());
@@ -35,96 +48,34 @@ pub fn Console() -> impl IntoView {
         });
     });
     
-    // Mock symbols
-    let symbols = vec![
-        "Nifty 50", "BankNifty", "FinNifty", "Sensex", "Midcap"
-    ];
+    let handle_symbol_change = move |hit: InstrumentHit| {
+        set_selected_symbol.set(hit.symbol);
+    };
     
-    let handle_symbol_change = move |ev: web_sys::Event| {
-        let input = event_target_value(&ev);
-        set_selected_symbol.set(input);
+    let handle_timeframe_change = move |value: String| {
+        set_timeframe.set(value);
     };
     
     let analyze = move |_| {
         set_loading.set(true);
+        set_error.set(None);
         
-        // Mock API call
-        set_timeout(
-            move || {
-                let mock_result = AnalysisResult {
-                    statistical: vec![
-                        AnalysisRow {
-                            name: "Mean".to_string(),
-                            value: "₹24,587.25".to_string(),
-                            description: Some("Average price over the period".to_string()),
-                        },
-                        AnalysisRow {
-                            name: "Standard Deviation".to_string(),
-                            value: "₹235.42".to_string(),
-                            description: Some("Volatility measure".to_string()),
-                        },
-                        AnalysisRow {
-                            name: "Range".to_string(),
-                            value: "₹980.75".to_string(),
-                            description: Some("Difference between highest and lowest price".to_string()),
-                        },
-                    ],
-                    visual: vec![
-                        AnalysisRow {
-                            name: "Trend".to_string(),
-                            value: "Uptrend".to_string(),
-                            description: Some("Current price direction".to_string()),
-                        },
-                        AnalysisRow {
-                            name: "Pattern".to_string(),
-                            value: "Double Bottom".to_string(),
-                            description: Some("Recent price pattern".to_string()),
-                        },
-                        AnalysisRow {
-                            name: "Support".to_string(),
-                            value: "₹24,320.00".to_string(),
-                            description: Some("Price level with buying interest".to_string()),
-                        },
-                        AnalysisRow {
-                            name: "Resistance".to_string(),
-                            value: "₹24,750.00".to_string(),
-                            description: Some("Price level with selling interest".to_string()),
-                        },
-                    ],
-                    indicator: vec![
-                        AnalysisRow {
-                            name: "RSI (14)".to_string(),
-                            value: "58.32".to_string(),
-                            description: Some("Neutral".to_string()),
-                        },
-                        AnalysisRow {
-                            name: "MACD".to_string(),
-                            value: "35.76".to_string(),
-                            description: Some("Bullish".to_string()),
-                        },
-                        AnalysisRow {
-                            name: "Moving Average (50)".to_string(),
-                            value: "₹24,320.75".to_string(),
-                            description: Some("Below current price".to_string()),
-                        },
-                        AnalysisRow {
-                            name: "Moving Average (200)".to_string(),
-                            value: "₹23,875.40".to_string(),
-                            description: Some("Below current price".to_string()),
-                        },
-                    ],
-                    suggestions: vec![
-                        "Consider buying at ₹24,400 with stop loss at ₹24,320".to_string(),
-                        "Sell target at ₹24,750".to_string(),
-                        "Hold positions if already long".to_string(),
-                    ],
-                };
-                
-                set_analysis_result.set(Some(mock_result));
-                set_loading.set(false);
-            },
-            1000,
+        let path = format!(
+            "/analyze/{}?timeframe={}",
+            encode(&selected_symbol.get()),
+            timeframe.get()
         );
+        
+        spawn_local(async move {
+            match get_json::<AnalysisResult>(&path).await {
+                Ok(result) => set_analysis_result.set(Some(result)),
+                Err(e) => {
+                    set_analysis_result.set(None);
+                    set_error.set(Some(e));
+                }
+            }
+            set_loading.set(false);
+        });
     };
     
     view! {
@@ -137,22 +88,16 @@ pub fn Console() -> impl IntoView {
             <div class="bg-card text-card-foreground rounded-lg p-6 shadow-sm mb-6">
                 <div class="flex flex-col md:flex-row gap-4 items-end">
                     <div class="w-full md:w-64">
-                        <label class="block text-sm font-medium mb-1">Select Symbol</label>
-                        <select 
-                            class="w-full px-3 py-2 border border-input rounded-md" 
-                            on:change=handle_symbol_change
-                        >
-                            {symbols.into_iter().map(|symbol| {
-                                view! {
-                                    <option 
-                                        value={symbol} 
-                                        selected={move || selected_symbol.get() == symbol}
-                                    >
-                                        {symbol}
-                                    </option>
-                                }
-                            }).collect::<Vec<_>>()}
-                        </select>
+                        <SymbolSearch
+                            value=selected_symbol
+                            on_select=handle_symbol_change
+                        />
+                    </div>
+                    <div class="w-full md:w-32">
+                        <TimeframeSelect
+                            value=timeframe
+                            on_change=handle_timeframe_change
+                        />
                     </div>
                     <button 
                         class="px-4 py-2 bg-primary text-primary-foreground rounded-md"
@@ -162,6 +107,9 @@ pub fn Console() -> impl IntoView {
                         {move || if is_loading() { "Analyzing..." } else { "Analyze" }}
                     </button>
                 </div>
+                {move || error.get().map(|message| view! {
+                    <p class="text-sm text-red-500 mt-3">{message}</p>
+                })}
             </div>
             
             {move || if let Some(result) = analysis_result.get() {
@@ -170,18 +118,24 @@ pub fn Console() -> impl IntoView {
                         <div class="grid grid-cols-1 md:grid-cols-3 gap-4">
                             <StatCard stat=StatData {
                                 title: "Current Price".to_string(),
-                                value: "₹24,587.25".to_string(),
-                                description: Some("As of now".to_string()),
+                                value: format!("₹{:.2}", result.quote.price),
+                                description: Some("Last close".to_string()),
                             } />
                             <StatCard stat=StatData {
-                                title: "Day Change".to_string(),
-                                value: "+₹123.45 (+0.51%)".to_string(),
-                                description: Some("Today's movement".to_string()),
+                                title: "Change".to_string(),
+                                value: format!(
+                                    "{}₹{:.2} ({}{:.2}%)",
+                                    if result.quote.change >= 0.0 { "+" } else { "-" },
+                                    result.quote.change.abs(),
+                                    if result.quote.change >= 0.0 { "+" } else { "" },
+                                    result.quote.change_percent
+                                ),
+                                description: Some("Since the previous bar".to_string()),
                             } />
                             <StatCard stat=StatData {
                                 title: "Volume".to_string(),
-                                value: "128.5M".to_string(),
-                                description: Some("Today's trading volume".to_string()),
+                                value: result.quote.volume.to_string(),
+                                description: Some("Latest bar volume".to_string()),
                             } />
                         </div>
                         
@@ -234,14 +188,14 @@ pub fn Console() -> impl IntoView {
     }
 }
 
+// Market hours are IST, so show IST regardless of the browser's timezone
 fn get_current_time() -> String {
     let date = js_sys::Date::new_0();
-    let hours = date.get_hours();
-    let minutes = date.get_minutes();
-    let seconds = date.get_seconds();
+    let minutes = (date.get_utc_hours() * 60 + date.get_utc_minutes() + 330) % (24 * 60);
+    let seconds = date.get_utc_seconds();
     
     format!(
-        "{:02}:{:02}:{:02}",
-        hours, minutes, seconds
+        "{:02}:{:02}:{:02} IST",
+        minutes / 60, minutes % 60, seconds
     )
 }
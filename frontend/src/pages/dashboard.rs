@@ -2,6 +2,7 @@ use leptos::*;
 use leptos_router::*;
 
 use crate::components::market_card::*;
+use crate::components::portfolio_summary::*;
 use crate::utils::api::*;
 
 #[component]
@@ -96,6 +97,8 @@ pub fn Dashboard() -> impl IntoView {
                                 <MarketCard market=data />
                             }).collect::<Vec<_>>()}
                         </div>
+
+                        <PortfolioSummary />
                         
                         <h2 class="text-xl font-bold mb-4">Quick Access</h2>
                         <div class="grid grid-cols-1 md:grid-cols-3 gap-6">
@@ -109,6 +112,11 @@ pub fn Dashboard() -> impl IntoView {
                                 description="Track your trades and analyze performance"
                                 path="/journal"
                             />
+                            <QuickAccessCard 
+                                title="Portfolio"
+                                description="Track holdings, returns and how they compare with the index"
+                                path="/portfolio"
+                            />
                             <QuickAccessCard 
                                 title="Aftermarket Analyzer"
                                 description="Analyze market data after market hours"
@@ -1 +1,278 @@
- 
+use leptos::*;
+
+use crate::components::daily_review::*;
+use crate::components::journal_analytics::*;
+use crate::components::journal_backup::*;
+use crate::components::journal_form::*;
+use crate::components::journal_tags::*;
+use crate::components::pnl_calendar::*;
+use crate::components::stat_card::*;
+use crate::components::tax_report::*;
+use crate::components::trade_detail::*;
+use crate::components::tradebook_import::*;
+use crate::components::trading_rules::*;
+use crate::utils::api::*;
+
+#[component]
+pub fn Journal() -> impl IntoView {
+    let (trades, set_trades) = create_signal(Vec::<JournalEntry>::new());
+    let (editing, set_editing) = create_signal(None::<JournalEntry>);
+    let (viewing, set_viewing) = create_signal(None::<JournalEntry>);
+    let (error, set_error) = create_signal(None::<String>);
+    let (tab, set_tab) = create_signal("trades");
+    let (tags, set_tags) = create_signal(Vec::<Tag>::new());
+    let (tag_filter, set_tag_filter) = create_signal(String::new());
+    let (show_tags, set_show_tags) = create_signal(false);
+    let (selected_day, set_selected_day) = create_signal(None::<String>);
+    let (revision, set_revision) = create_signal(0usize);
+    let today = chrono::Local::now().date_naive().format("%Y-%m-%d").to_string();
+    let form_date = Signal::derive(move || selected_day.get().unwrap_or_else(|| today.clone()));
+    load_tags(set_tags);
+
+    let load_trades = move || {
+        let mut query = Vec::new();
+        let tag = tag_filter.get_untracked();
+        if !tag.is_empty() {
+            query.push(format!("tag={}", encode(&tag)));
+        }
+        if let Some(day) = selected_day.get_untracked() {
+            query.push(format!("date={}", day));
+        }
+        let path = if query.is_empty() {
+            "/journal/trades".to_string()
+        } else {
+            format!("/journal/trades?{}", query.join("&"))
+        };
+        spawn_local(async move {
+            match get_json::<Vec<JournalEntry>>(&path).await {
+                Ok(list) => set_trades.set(list),
+                Err(e) => set_error.set(Some(e)),
+            }
+        });
+    };
+    load_trades();
+
+    // Saving, deleting or importing changes the calendar too
+    let reload_trades = move || {
+        load_trades();
+        set_revision.update(|n| *n += 1);
+    };
+
+    let save = move |entry: JournalEntry| {
+        set_error.set(None);
+        set_editing.set(None);
+        spawn_local(async move {
+            let result = if entry.id.is_empty() {
+                post_json::<_, JournalEntry>("/journal/trades", &entry).await
+            } else {
+                put_json::<_, JournalEntry>(&format!("/journal/trades/{}", encode(&entry.id)), &entry)
+                    .await
+            };
+            match result {
+                Ok(_) => reload_trades(),
+                Err(e) => set_error.set(Some(e)),
+            }
+        });
+    };
+
+    let delete = move |id: String| {
+        set_viewing.update(|open| {
+            if open.as_ref().is_some_and(|entry| entry.id == id) {
+                *open = None;
+            }
+        });
+        spawn_local(async move {
+            match delete_json::<String>(&format!("/journal/trades/{}", encode(&id))).await {
+                Ok(_) => reload_trades(),
+                Err(e) => set_error.set(Some(e)),
+            }
+        });
+    };
+
+    let totals = move || {
+        let list = trades.get();
+        let realized: f64 = list
+            .iter()
+            .filter_map(|t| t.summary.as_ref())
+            .map(|s| s.realized_pnl)
+            .sum();
+        let unrealized: f64 = list
+            .iter()
+            .filter_map(|t| t.summary.as_ref())
+            .filter_map(|s| s.unrealized_pnl)
+            .sum();
+        let open = list
+            .iter()
+            .filter_map(|t| t.summary.as_ref())
+            .filter(|s| s.open_quantity > 0)
+            .count();
+        (realized, unrealized, open, list.len())
+    };
+
+    view! {
+        <div>
+            <div class="flex justify-between items-center mb-6">
+                <h1 class="text-2xl font-bold">Trading Journal</h1>
+                <div class="space-x-2">
+                    <button
+                        class=move || if tab.get() == "trades" {
+                            "px-4 py-2 bg-primary text-primary-foreground rounded-md"
+                        } else {
+                            "px-4 py-2 bg-secondary text-secondary-foreground rounded-md"
+                        }
+                        on:click=move |_| set_tab.set("trades")
+                    >
+                        Trades
+                    </button>
+                    <button
+                        class=move || if tab.get() == "analytics" {
+                            "px-4 py-2 bg-primary text-primary-foreground rounded-md"
+                        } else {
+                            "px-4 py-2 bg-secondary text-secondary-foreground rounded-md"
+                        }
+                        on:click=move |_| set_tab.set("analytics")
+                    >
+                        Analytics
+                    </button>
+                    <button
+                        class=move || if tab.get() == "tax" {
+                            "px-4 py-2 bg-primary text-primary-foreground rounded-md"
+                        } else {
+                            "px-4 py-2 bg-secondary text-secondary-foreground rounded-md"
+                        }
+                        on:click=move |_| set_tab.set("tax")
+                    >
+                        Tax Report
+                    </button>
+                    <button
+                        class=move || if tab.get() == "rules" {
+                            "px-4 py-2 bg-primary text-primary-foreground rounded-md"
+                        } else {
+                            "px-4 py-2 bg-secondary text-secondary-foreground rounded-md"
+                        }
+                        on:click=move |_| set_tab.set("rules")
+                    >
+                        Rules
+                    </button>
+                </div>
+            </div>
+
+            {move || if tab.get() == "analytics" {
+                view! { <JournalAnalytics /> }.into_view()
+            } else if tab.get() == "tax" {
+                view! { <TaxReport /> }.into_view()
+            } else if tab.get() == "rules" {
+                // Rule changes re-flag the trades
+                view! { <TradingRules on_change=move |_| reload_trades() /> }.into_view()
+            } else {
+                view! {
+                    {move || {
+                        let (realized, unrealized, open, count) = totals();
+                        view! {
+                            <div class="grid grid-cols-1 md:grid-cols-3 gap-4 mb-6">
+                                <StatCard stat=StatData {
+                                    title: "Realized PnL".to_string(),
+                                    value: format!("₹{:.2}", realized),
+                                    description: Some(format!("{} trades, net of fees", count)),
+                                } />
+                                <StatCard stat=StatData {
+                                    title: "Unrealized PnL".to_string(),
+                                    value: format!("₹{:.2}", unrealized),
+                                    description: Some("Open lots marked at the last close".to_string()),
+                                } />
+                                <StatCard stat=StatData {
+                                    title: "Open Trades".to_string(),
+                                    value: open.to_string(),
+                                    description: None,
+                                } />
+                            </div>
+                        }
+                    }}
+
+                    <JournalForm on_save=save edit=editing selected_date=form_date />
+                    <TradebookImport on_imported=move |_| reload_trades() />
+                    <JournalBackup on_restored=move |_| {
+                        load_tags(set_tags);
+                        reload_trades();
+                    } />
+
+                    {move || error.get().map(|message| view! {
+                        <p class="text-sm text-red-500 mt-3">{message}</p>
+                    })}
+
+                    <PnlCalendar
+                        reload=revision
+                        selected=Signal::derive(move || selected_day.get().unwrap_or_default())
+                        on_select=move |day: String| {
+                            set_selected_day.set(Some(day));
+                            load_trades();
+                        }
+                    />
+
+                    {move || selected_day.get().map(|day| view! {
+                        <div class="flex items-center gap-2 mt-6 text-sm">
+                            <span class="text-muted-foreground">{format!("Showing trades on {}", day)}</span>
+                            <button
+                                class="px-2 py-1 text-primary"
+                                on:click=move |_| {
+                                    set_selected_day.set(None);
+                                    load_trades();
+                                }
+                            >
+                                Clear
+                            </button>
+                        </div>
+                    })}
+
+                    <DailyReview date=form_date reload=revision />
+
+                    <div class="flex justify-between items-center mt-6">
+                        <select
+                            class="px-3 py-2 border border-input rounded-md"
+                            on:change=move |ev| {
+                                set_tag_filter.set(event_target_value(&ev));
+                                load_trades();
+                            }
+                        >
+                            <option value="">All tags</option>
+                            {move || tags.get().into_iter().map(|tag| {
+                                let name = tag.name.clone();
+                                view! {
+                                    <option value=tag.name.clone() selected=move || tag_filter.get() == name>
+                                        {tag.name}
+                                    </option>
+                                }
+                            }).collect::<Vec<_>>()}
+                        </select>
+                        <button
+                            class="px-4 py-2 bg-secondary text-secondary-foreground rounded-md"
+                            on:click=move |_| set_show_tags.update(|open| *open = !*open)
+                        >
+                            Manage Tags
+                        </button>
+                    </div>
+
+                    {move || show_tags.get().then(|| view! {
+                        <TagManager on_change=move |_| {
+                            load_tags(set_tags);
+                            load_trades();
+                        } />
+                    })}
+
+                    {move || viewing.get().map(|entry| view! {
+                        <TradeDetail entry=entry on_close=move |_| set_viewing.set(None) />
+                    })}
+
+                    {move || view! {
+                        <JournalTable
+                            entries=trades.get()
+                            on_view=Callback::new(move |entry| set_viewing.set(Some(entry)))
+                            on_edit=Callback::new(move |entry| set_editing.set(Some(entry)))
+                            on_delete=Callback::new(delete)
+                        />
+                    }}
+                }.into_view()
+            }}
+        </div>
+    }
+}
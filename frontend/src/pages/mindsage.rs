@@ -1 +1,349 @@
- 
+use std::collections::HashMap;
+
+use leptos::*;
+use serde::{Deserialize, Serialize};
+
+use crate::components::fundamentals_import::*;
+use crate::components::rebalance_planner::*;
+use crate::components::risk_guidance::*;
+use crate::components::stat_card::*;
+use crate::components::stock_score::*;
+use crate::components::symbol_search::*;
+use crate::components::valuation_models::*;
+use crate::utils::api::*;
+
+#[derive(Clone, Debug, Serialize, Deserialize)]
+struct CompanySummary {
+    symbol: String,
+    name: String,
+    sector: String,
+    quarters: usize,
+    years: usize,
+}
+
+#[derive(Clone, Debug, Serialize, Deserialize)]
+struct Period {
+    period_end: String,
+    #[serde(flatten)]
+    values: HashMap<String, Option<f64>>,
+}
+
+#[derive(Clone, Debug, Default, Serialize, Deserialize)]
+struct Ratios {
+    market_cap: Option<f64>,
+    ttm_revenue: Option<f64>,
+    ttm_net_profit: Option<f64>,
+    ttm_eps: Option<f64>,
+    pe: Option<f64>,
+    book_value_per_share: Option<f64>,
+    pb: Option<f64>,
+    roe: Option<f64>,
+    roce: Option<f64>,
+    debt_to_equity: Option<f64>,
+    operating_margin: Option<f64>,
+    net_margin: Option<f64>,
+    dividend_yield: Option<f64>,
+    revenue_cagr_3y: Option<f64>,
+    revenue_cagr_5y: Option<f64>,
+    profit_cagr_3y: Option<f64>,
+    profit_cagr_5y: Option<f64>,
+}
+
+#[derive(Clone, Debug, Serialize, Deserialize)]
+struct ProfileData {
+    symbol: String,
+    name: String,
+    sector: String,
+    industry: String,
+    shares_outstanding: Option<f64>,
+    quarterly: Vec<Period>,
+    annual: Vec<Period>,
+    shareholding: Vec<Period>,
+    price: Option<f64>,
+    price_date: Option<String>,
+    ratios: Ratios,
+}
+
+const PROFIT_AND_LOSS: [(&str, &str); 11] = [
+    ("revenue", "Revenue"),
+    ("operating_expenses", "Expenses"),
+    ("operating_profit", "Operating Profit"),
+    ("other_income", "Other Income"),
+    ("depreciation", "Depreciation"),
+    ("interest", "Interest"),
+    ("profit_before_tax", "Profit before Tax"),
+    ("tax", "Tax"),
+    ("net_profit", "Net Profit"),
+    ("eps", "EPS (₹)"),
+    ("dividend_per_share", "Dividend per Share (₹)"),
+];
+
+const BALANCE_SHEET: [(&str, &str); 8] = [
+    ("share_capital", "Share Capital"),
+    ("reserves", "Reserves"),
+    ("borrowings", "Borrowings"),
+    ("other_liabilities", "Other Liabilities"),
+    ("total_assets", "Total Assets"),
+    ("fixed_assets", "Fixed Assets"),
+    ("investments", "Investments"),
+    ("cash", "Cash"),
+];
+
+const CASH_FLOW: [(&str, &str); 4] = [
+    ("operating_cash_flow", "Operating Activities"),
+    ("investing_cash_flow", "Investing Activities"),
+    ("financing_cash_flow", "Financing Activities"),
+    ("capex", "Capex"),
+];
+
+const SHAREHOLDING: [(&str, &str); 6] = [
+    ("promoters", "Promoters"),
+    ("fii", "FIIs"),
+    ("dii", "DIIs"),
+    ("government", "Government"),
+    ("public", "Public"),
+    ("pledged", "Pledged (of promoter)"),
+];
+
+fn number(value: Option<f64>, suffix: &str) -> String {
+    value.map_or("-".to_string(), |v| format!("{:.2}{}", v, suffix))
+}
+
+fn crore(value: Option<f64>) -> String {
+    value.map_or("-".to_string(), |v| format!("₹{:.0} Cr", v))
+}
+
+/// Periods as columns, oldest first, with one row per line item. Rows the
+/// source never filled are left out.
+#[component]
+fn StatementTable(
+    title: &'static str,
+    periods: Vec<Period>,
+    rows: &'static [(&'static str, &'static str)],
+) -> impl IntoView {
+    let rows: Vec<_> = rows
+        .iter()
+        .filter(|(key, _)| periods.iter().any(|p| p.values.get(*key).copied().flatten().is_some()))
+        .collect();
+    if periods.is_empty() || rows.is_empty() {
+        return view! {
+            <div class="bg-card text-card-foreground rounded-lg shadow-sm p-6 mt-6">
+                <h3 class="text-lg font-medium mb-2">{title}</h3>
+                <p class="text-sm text-muted-foreground">No data imported</p>
+            </div>
+        }
+        .into_view();
+    }
+    let headers: Vec<String> = periods.iter().map(|p| p.period_end.clone()).collect();
+
+    view! {
+        <div class="bg-card text-card-foreground rounded-lg shadow-sm p-6 mt-6">
+            <h3 class="text-lg font-medium mb-4">{title}</h3>
+            <div class="overflow-x-auto">
+                <table class="w-full text-sm">
+                    <thead>
+                        <tr class="border-b border-border">
+                            <th class="text-left p-2 text-muted-foreground font-medium"></th>
+                            {headers.into_iter().map(|h| view! {
+                                <th class="text-right p-2 text-muted-foreground font-medium whitespace-nowrap">{h}</th>
+                            }).collect::<Vec<_>>()}
+                        </tr>
+                    </thead>
+                    <tbody>
+                        {rows.into_iter().map(|(key, label)| view! {
+                            <tr class="border-b border-border">
+                                <td class="p-2 whitespace-nowrap">{*label}</td>
+                                {periods.iter().map(|p| view! {
+                                    <td class="p-2 text-right">
+                                        {number(p.values.get(*key).copied().flatten(), "")}
+                                    </td>
+                                }).collect::<Vec<_>>()}
+                            </tr>
+                        }).collect::<Vec<_>>()}
+                    </tbody>
+                </table>
+            </div>
+        </div>
+    }
+    .into_view()
+}
+
+#[component]
+pub fn Mindsage() -> impl IntoView {
+    let (symbol, set_symbol) = create_signal(String::new());
+    let (companies, set_companies) = create_signal(Vec::<CompanySummary>::new());
+    let (profile, set_profile) = create_signal(None::<ProfileData>);
+    let (error, set_error) = create_signal(None::<String>);
+    let (revision, set_revision) = create_signal(0usize);
+
+    create_effect(move |_| {
+        revision.track();
+        spawn_local(async move {
+            match get_json::<Vec<CompanySummary>>("/fundamentals").await {
+                Ok(list) => {
+                    if symbol.get_untracked().is_empty() {
+                        if let Some(first) = list.first() {
+                            set_symbol.set(first.symbol.clone());
+                        }
+                    }
+                    set_companies.set(list);
+                }
+                Err(e) => set_error.set(Some(e)),
+            }
+        });
+    });
+
+    create_effect(move |_| {
+        let symbol = symbol.get();
+        revision.track();
+        if symbol.is_empty() {
+            return;
+        }
+        set_error.set(None);
+        spawn_local(async move {
+            match get_json::<ProfileData>(&format!("/fundamentals/{}/profile", encode(&symbol))).await {
+                Ok(data) => set_profile.set(Some(data)),
+                Err(e) => {
+                    set_profile.set(None);
+                    set_error.set(Some(e));
+                }
+            }
+        });
+    });
+
+    view! {
+        <div>
+            <div class="flex justify-between items-center mb-6">
+                <h1 class="text-2xl font-bold">Mindsage</h1>
+            </div>
+
+            <div class="bg-card text-card-foreground rounded-lg shadow-sm p-6">
+                <div class="flex flex-col md:flex-row gap-4 md:items-end">
+                    <SymbolSearch
+                        value=symbol
+                        on_select=move |hit: InstrumentHit| set_symbol.set(hit.symbol)
+                        label="Company"
+                    />
+                    <div class="flex flex-wrap gap-2">
+                        {move || companies.get().into_iter().map(|c| {
+                            let selected = c.symbol.clone();
+                            let active = c.symbol.clone();
+                            view! {
+                                <button
+                                    class=move || if symbol.get() == active {
+                                        "px-3 py-1 bg-primary text-primary-foreground rounded-md text-sm"
+                                    } else {
+                                        "px-3 py-1 bg-secondary text-secondary-foreground rounded-md text-sm"
+                                    }
+                                    title=c.name.clone()
+                                    on:click=move |_| set_symbol.set(selected.clone())
+                                >
+                                    {c.symbol}
+                                </button>
+                            }
+                        }).collect::<Vec<_>>()}
+                    </div>
+                </div>
+            </div>
+
+            {move || error.get().map(|text| view! {
+                <p class="text-sm text-red-500 mt-4">{text}</p>
+            })}
+
+            {move || profile.get().map(|p| {
+                let r = p.ratios.clone();
+                view! {
+                    <div>
+                        <div class="bg-card text-card-foreground rounded-lg shadow-sm p-6 mt-6">
+                            <div class="flex flex-col md:flex-row md:justify-between gap-2">
+                                <div>
+                                    <h2 class="text-xl font-bold">{format!("{} ({})", p.name, p.symbol)}</h2>
+                                    <p class="text-sm text-muted-foreground">
+                                        {format!("{} · {}", p.sector, p.industry)}
+                                    </p>
+                                </div>
+                                <div class="md:text-right">
+                                    <p class="text-2xl font-bold">{p.price.map_or("No price".to_string(), |v| format!("₹{:.2}", v))}</p>
+                                    <p class="text-xs text-muted-foreground">
+                                        {p.price_date.clone().map(|d| format!("Close on {}", d))}
+                                    </p>
+                                </div>
+                            </div>
+                        </div>
+
+                        <div class="grid grid-cols-2 md:grid-cols-4 gap-4 mt-6">
+                            <StatCard stat=StatData {
+                                title: "Market Cap".to_string(),
+                                value: crore(r.market_cap),
+                                description: p.shares_outstanding.map(|s| format!("{:.2} Cr shares", s)),
+                            } />
+                            <StatCard stat=StatData {
+                                title: "P/E".to_string(),
+                                value: number(r.pe, ""),
+                                description: Some(format!("TTM EPS {}", number(r.ttm_eps, ""))),
+                            } />
+                            <StatCard stat=StatData {
+                                title: "P/B".to_string(),
+                                value: number(r.pb, ""),
+                                description: Some(format!("Book value {}", number(r.book_value_per_share, ""))),
+                            } />
+                            <StatCard stat=StatData {
+                                title: "Dividend Yield".to_string(),
+                                value: number(r.dividend_yield, "%"),
+                                description: None,
+                            } />
+                            <StatCard stat=StatData {
+                                title: "ROE".to_string(),
+                                value: number(r.roe, "%"),
+                                description: Some(format!("ROCE {}", number(r.roce, "%"))),
+                            } />
+                            <StatCard stat=StatData {
+                                title: "Debt / Equity".to_string(),
+                                value: number(r.debt_to_equity, ""),
+                                description: None,
+                            } />
+                            <StatCard stat=StatData {
+                                title: "Margins".to_string(),
+                                value: number(r.operating_margin, "%"),
+                                description: Some(format!("Operating; net {}", number(r.net_margin, "%"))),
+                            } />
+                            <StatCard stat=StatData {
+                                title: "TTM".to_string(),
+                                value: crore(r.ttm_revenue),
+                                description: Some(format!("Net profit {}", crore(r.ttm_net_profit))),
+                            } />
+                            <StatCard stat=StatData {
+                                title: "Revenue CAGR".to_string(),
+                                value: number(r.revenue_cagr_3y, "%"),
+                                description: Some(format!("3 years; 5 years {}", number(r.revenue_cagr_5y, "%"))),
+                            } />
+                            <StatCard stat=StatData {
+                                title: "Profit CAGR".to_string(),
+                                value: number(r.profit_cagr_3y, "%"),
+                                description: Some(format!("3 years; 5 years {}", number(r.profit_cagr_5y, "%"))),
+                            } />
+                        </div>
+
+                        <StockScore symbol=symbol reload=revision />
+                        <RiskGuidance symbol=symbol />
+                        <ValuationModels symbol=symbol />
+
+                        <StatementTable title="Quarterly Results (₹ Cr)" periods=p.quarterly.clone() rows=&PROFIT_AND_LOSS />
+                        <StatementTable title="Profit & Loss (₹ Cr)" periods=p.annual.clone() rows=&PROFIT_AND_LOSS />
+                        <StatementTable title="Balance Sheet (₹ Cr)" periods=p.annual.clone() rows=&BALANCE_SHEET />
+                        <StatementTable title="Cash Flow (₹ Cr)" periods=p.annual.clone() rows=&CASH_FLOW />
+                        <StatementTable title="Shareholding Pattern (%)" periods=p.shareholding.clone() rows=&SHAREHOLDING />
+                    </div>
+                }
+            })}
+
+            <ScoreRanking
+                on_select=move |s: String| set_symbol.set(s)
+                reload=revision
+            />
+            <RebalancePlanner />
+            <ScoreWeights on_change=move |_| set_revision.update(|r| *r += 1) />
+            <FundamentalsImport on_imported=move |_| set_revision.update(|r| *r += 1) />
+        </div>
+    }
+}
@@ -0,0 +1,14 @@
+pub mod admin_dashboard;
+pub mod aftermarket_analyzer;
+pub mod algo_trading;
+pub mod console;
+pub mod dashboard;
+pub mod global_sentiment;
+pub mod history;
+pub mod journal;
+pub mod mindsage;
+pub mod model_trainer;
+pub mod options;
+pub mod portfolio;
+pub mod profile;
+pub mod screener;
@@ -0,0 +1,709 @@
+use leptos::*;
+use serde::{Deserialize, Serialize};
+use wasm_bindgen::JsCast;
+use wasm_bindgen_futures::JsFuture;
+use web_sys::HtmlInputElement;
+
+use crate::components::option_calculator::*;
+use crate::components::stat_card::*;
+use crate::utils::api::*;
+
+#[derive(Clone, Debug, Serialize, Deserialize)]
+struct UnderlyingSummary {
+    underlying: String,
+    expiries: Vec<String>,
+    latest: String,
+}
+
+#[derive(Clone, Debug, Serialize, Deserialize)]
+struct OptionQuote {
+    ltp: Option<f64>,
+    price_change: Option<f64>,
+    oi: f64,
+    oi_change: Option<f64>,
+    volume: f64,
+    iv: Option<f64>,
+}
+
+#[derive(Clone, Debug, Serialize, Deserialize)]
+struct Greeks {
+    price: f64,
+    delta: f64,
+    gamma: f64,
+    theta: f64,
+    vega: f64,
+    rho: f64,
+}
+
+#[derive(Clone, Debug, Serialize, Deserialize)]
+struct ChainRow {
+    strike: f64,
+    call: Option<OptionQuote>,
+    put: Option<OptionQuote>,
+    call_build_up: Option<String>,
+    put_build_up: Option<String>,
+    call_greeks: Option<Greeks>,
+    put_greeks: Option<Greeks>,
+}
+
+#[derive(Clone, Debug, Serialize, Deserialize)]
+struct BuildUpTotal {
+    option_type: String,
+    build_up: String,
+    label: String,
+    strikes: usize,
+    oi_change: f64,
+}
+
+#[derive(Clone, Debug, Serialize, Deserialize)]
+struct OiLevel {
+    strike: f64,
+    oi: f64,
+    oi_change: Option<f64>,
+}
+
+#[derive(Clone, Debug, Serialize, Deserialize)]
+struct PainPoint {
+    strike: f64,
+    payout: f64,
+}
+
+#[derive(Clone, Debug, Serialize, Deserialize)]
+struct ChainAnalytics {
+    spot: Option<f64>,
+    atm_strike: Option<f64>,
+    call_oi: f64,
+    put_oi: f64,
+    call_oi_change: f64,
+    put_oi_change: f64,
+    call_volume: f64,
+    put_volume: f64,
+    pcr: Option<f64>,
+    pcr_volume: Option<f64>,
+    pcr_change: Option<f64>,
+    max_pain: Option<f64>,
+    pain: Vec<PainPoint>,
+    supports: Vec<OiLevel>,
+    resistances: Vec<OiLevel>,
+    build_up: Vec<BuildUpTotal>,
+    rate: f64,
+    days_to_expiry: i64,
+    atm_iv: Option<f64>,
+    iv_rank: Option<f64>,
+    iv_percentile: Option<f64>,
+    iv_days: usize,
+    notes: Vec<String>,
+}
+
+#[derive(Clone, Debug, Serialize, Deserialize)]
+struct ChainView {
+    underlying: String,
+    expiry: String,
+    date: String,
+    rows: Vec<ChainRow>,
+    analytics: ChainAnalytics,
+}
+
+#[derive(Clone, Debug, Default, Serialize, Deserialize)]
+struct ImportSummary {
+    snapshots: usize,
+    strikes: usize,
+    errors: Vec<String>,
+    notes: Vec<String>,
+}
+
+const PAIN_WIDTH: f64 = 600.0;
+const PAIN_HEIGHT: f64 = 120.0;
+
+fn number(value: Option<f64>, decimals: usize) -> String {
+    value.map_or("-".to_string(), |v| format!("{:.*}", decimals, v))
+}
+
+/// Contracts in lakhs once they run that large, as the exchange shows OI.
+fn contracts(value: f64) -> String {
+    if value.abs() >= 100_000.0 {
+        format!("{:.2}L", value / 100_000.0)
+    } else {
+        format!("{:.0}", value)
+    }
+}
+
+fn signed_contracts(value: Option<f64>) -> String {
+    value.map_or("-".to_string(), |v| {
+        let sign = if v > 0.0 { "+" } else { "" };
+        format!("{}{}", sign, contracts(v))
+    })
+}
+
+fn change_class(value: Option<f64>) -> &'static str {
+    match value {
+        Some(v) if v > 0.0 => "p-2 text-right text-green-500",
+        Some(v) if v < 0.0 => "p-2 text-right text-red-500",
+        _ => "p-2 text-right",
+    }
+}
+
+fn build_up_badge(build_up: Option<String>) -> impl IntoView {
+    let (label, class) = match build_up.as_deref() {
+        Some("long_build_up") => (
+            "Long",
+            "text-xs px-2 py-0.5 rounded-md bg-green-500/10 text-green-500",
+        ),
+        Some("short_build_up") => (
+            "Short",
+            "text-xs px-2 py-0.5 rounded-md bg-red-500/10 text-red-500",
+        ),
+        Some("short_covering") => (
+            "Covering",
+            "text-xs px-2 py-0.5 rounded-md bg-blue-500/10 text-blue-500",
+        ),
+        Some("long_unwinding") => (
+            "Unwinding",
+            "text-xs px-2 py-0.5 rounded-md bg-yellow-500/10 text-yellow-500",
+        ),
+        _ => ("", ""),
+    };
+    view! { <span class=class>{label}</span> }
+}
+
+fn levels(levels: &[OiLevel]) -> String {
+    if levels.is_empty() {
+        return "-".to_string();
+    }
+    levels
+        .iter()
+        .map(|l| format!("{:.0}", l.strike))
+        .collect::<Vec<_>>()
+        .join(", ")
+}
+
+#[component]
+pub fn Options() -> impl IntoView {
+    let (underlyings, set_underlyings) = create_signal(Vec::<UnderlyingSummary>::new());
+    let (underlying, set_underlying) = create_signal(String::new());
+    let (expiry, set_expiry) = create_signal(String::new());
+    let (chain, set_chain) = create_signal(None::<ChainView>);
+    let (error, set_error) = create_signal(None::<String>);
+    let (revision, set_revision) = create_signal(0usize);
+    let (show_greeks, set_show_greeks) = create_signal(false);
+
+    create_effect(move |_| {
+        revision.track();
+        spawn_local(async move {
+            match get_json::<Vec<UnderlyingSummary>>("/options/underlyings").await {
+                Ok(list) => {
+                    if underlying.get_untracked().is_empty() {
+                        if let Some(first) = list.first() {
+                            set_underlying.set(first.underlying.clone());
+                        }
+                    }
+                    set_underlyings.set(list);
+                }
+                Err(e) => set_error.set(Some(e)),
+            }
+        });
+    });
+
+    create_effect(move |_| {
+        revision.track();
+        let name = underlying.get();
+        let expiry = expiry.get();
+        if name.is_empty() {
+            return;
+        }
+        spawn_local(async move {
+            let mut path = format!("/options/chain?underlying={}", encode(&name));
+            if !expiry.is_empty() {
+                path.push_str(&format!("&expiry={}", expiry));
+            }
+            match get_json::<ChainView>(&path).await {
+                Ok(data) => {
+                    set_error.set(None);
+                    set_chain.set(Some(data));
+                }
+                Err(e) => {
+                    set_chain.set(None);
+                    set_error.set(Some(e));
+                }
+            }
+        });
+    });
+
+    let expiries = move || {
+        underlyings
+            .get()
+            .into_iter()
+            .find(|u| u.underlying == underlying.get())
+            .map(|u| u.expiries)
+            .unwrap_or_default()
+    };
+
+    view! {
+        <div>
+            <div class="flex justify-between items-center mb-6">
+                <h1 class="text-2xl font-bold">Options</h1>
+                <div class="flex gap-2">
+                    <select
+                        class="px-3 py-2 border border-input rounded-md"
+                        on:change=move |ev| {
+                            set_expiry.set(String::new());
+                            set_underlying.set(event_target_value(&ev));
+                        }
+                    >
+                        {move || underlyings.get().into_iter().map(|u| {
+                            let name = u.underlying.clone();
+                            view! {
+                                <option value=u.underlying.clone() selected=move || underlying.get() == name>
+                                    {u.underlying}
+                                </option>
+                            }
+                        }).collect::<Vec<_>>()}
+                    </select>
+                    <select
+                        class="px-3 py-2 border border-input rounded-md"
+                        on:change=move |ev| set_expiry.set(event_target_value(&ev))
+                    >
+                        <option value="" selected=move || expiry.get().is_empty()>Nearest expiry</option>
+                        {move || expiries().into_iter().map(|e| {
+                            let value = e.clone();
+                            view! {
+                                <option value=e.clone() selected=move || expiry.get() == value>{e}</option>
+                            }
+                        }).collect::<Vec<_>>()}
+                    </select>
+                </div>
+            </div>
+
+            {move || error.get().map(|text| view! {
+                <p class="text-sm text-red-500 mb-4">{text}</p>
+            })}
+
+            {move || (underlyings.get().is_empty() && error.get().is_none()).then(|| view! {
+                <p class="text-sm text-muted-foreground mb-4">
+                    "No option chains yet. Import the exchange's option chain download or a contract-per-row CSV below."
+                </p>
+            })}
+
+            {move || chain.get().map(|c| {
+                let a = c.analytics.clone();
+                view! {
+                    <div>
+                        <div class="grid grid-cols-2 md:grid-cols-3 lg:grid-cols-6 gap-4">
+                            <StatCard stat=StatData {
+                                title: "Spot".to_string(),
+                                value: number(a.spot, 2),
+                                description: Some(format!("ATM {}", number(a.atm_strike, 0))),
+                            } />
+                            <StatCard stat=StatData {
+                                title: "PCR (OI)".to_string(),
+                                value: number(a.pcr, 2),
+                                description: Some(format!(
+                                    "Volume {}, change {}",
+                                    number(a.pcr_volume, 2),
+                                    number(a.pcr_change, 2)
+                                )),
+                            } />
+                            <StatCard stat=StatData {
+                                title: "ATM IV".to_string(),
+                                value: a.atm_iv.map_or("-".to_string(), |iv| format!("{:.1}%", iv)),
+                                description: Some(match (a.iv_rank, a.iv_percentile) {
+                                    (Some(rank), Some(percentile)) => format!(
+                                        "IV rank {:.0}, percentile {:.0} over {} days",
+                                        rank, percentile, a.iv_days
+                                    ),
+                                    _ => format!("{} days of IV history", a.iv_days),
+                                }),
+                            } />
+                            <StatCard stat=StatData {
+                                title: "Max Pain".to_string(),
+                                value: number(a.max_pain, 0),
+                                description: Some(format!("Expiry {}", c.expiry)),
+                            } />
+                            <StatCard stat=StatData {
+                                title: "Support".to_string(),
+                                value: a.supports.first().map_or("-".to_string(), |l| format!("{:.0}", l.strike)),
+                                description: Some(format!("Put OI at {}", levels(&a.supports))),
+                            } />
+                            <StatCard stat=StatData {
+                                title: "Resistance".to_string(),
+                                value: a.resistances.first().map_or("-".to_string(), |l| format!("{:.0}", l.strike)),
+                                description: Some(format!("Call OI at {}", levels(&a.resistances))),
+                            } />
+                        </div>
+                        <p class="text-xs text-muted-foreground mt-2">
+                            {format!(
+                                "{} chain as of {}: call OI {} ({}), put OI {} ({})",
+                                c.underlying,
+                                c.date,
+                                contracts(a.call_oi),
+                                signed_contracts(Some(a.call_oi_change)),
+                                contracts(a.put_oi),
+                                signed_contracts(Some(a.put_oi_change))
+                            )}
+                        </p>
+
+                        {(!a.notes.is_empty()).then(|| view! {
+                            <ul class="list-disc list-inside text-sm text-muted-foreground mt-4">
+                                {a.notes.iter().map(|n| view! { <li>{n.clone()}</li> }).collect::<Vec<_>>()}
+                            </ul>
+                        })}
+
+                        <div class="grid grid-cols-1 lg:grid-cols-2 gap-6">
+                            <BuildUpSummary totals=a.build_up.clone() />
+                            <PainChart pain=a.pain.clone() max_pain=a.max_pain />
+                        </div>
+                        <div class="flex justify-end items-center gap-2 mt-6 text-sm">
+                            <span class="text-muted-foreground">
+                                {format!("{} days to expiry, Greeks at {:.2}% risk-free", a.days_to_expiry, a.rate)}
+                            </span>
+                            <button
+                                class="px-3 py-1 bg-secondary text-secondary-foreground rounded-md"
+                                on:click=move |_| set_show_greeks.update(|on| *on = !*on)
+                            >
+                                {if show_greeks.get() { "Hide Greeks" } else { "Show Greeks" }}
+                            </button>
+                        </div>
+                        <ChainTable rows=c.rows.clone() spot=a.spot atm=a.atm_strike greeks=show_greeks.get() />
+                    </div>
+                }
+            })}
+
+            <OptionCalculator />
+            <ChainImport on_imported=move |_| set_revision.update(|r| *r += 1) />
+        </div>
+    }
+}
+
+/// Strikes of each side grouped by how price and OI moved together.
+#[component]
+fn BuildUpSummary(totals: Vec<BuildUpTotal>) -> impl IntoView {
+    view! {
+        <div class="bg-card text-card-foreground rounded-lg shadow-sm overflow-hidden mt-6">
+            <div class="p-4 border-b border-border">
+                <h3 class="text-lg font-medium">OI Build-up</h3>
+                <p class="text-xs text-muted-foreground mt-1">
+                    "Price and OI up is long build-up, price down and OI up short build-up, price up and OI down short covering, both down long unwinding"
+                </p>
+            </div>
+            <table class="w-full text-sm">
+                <thead>
+                    <tr class="border-b border-border">
+                        {["Side", "Activity", "Strikes", "OI Change"]
+                            .into_iter()
+                            .map(|h| view! { <th class="text-left p-3 text-muted-foreground font-medium">{h}</th> })
+                            .collect::<Vec<_>>()}
+                    </tr>
+                </thead>
+                <tbody>
+                    {if totals.is_empty() {
+                        view! {
+                            <tr>
+                                <td class="p-3 text-muted-foreground" colspan="4">"No price and OI changes to classify"</td>
+                            </tr>
+                        }.into_view()
+                    } else {
+                        totals.into_iter().map(|t| view! {
+                            <tr class="border-b border-border">
+                                <td class="p-3">{if t.option_type == "call" { "Calls" } else { "Puts" }}</td>
+                                <td class="p-3">{build_up_badge(Some(t.build_up.clone()))}" "{t.label}</td>
+                                <td class="p-3">{t.strikes}</td>
+                                <td class="p-3">{signed_contracts(Some(t.oi_change))}</td>
+                            </tr>
+                        }).collect::<Vec<_>>().into_view()
+                    }}
+                </tbody>
+            </table>
+        </div>
+    }
+}
+
+/// What option buyers would collect at each expiry price; the lowest bar
+/// is max pain.
+#[component]
+fn PainChart(pain: Vec<PainPoint>, max_pain: Option<f64>) -> impl IntoView {
+    let highest = pain.iter().map(|p| p.payout).fold(0.0, f64::max);
+    let width = PAIN_WIDTH / pain.len().max(1) as f64;
+    view! {
+        <div class="bg-card text-card-foreground rounded-lg shadow-sm p-6 mt-6">
+            <h3 class="text-lg font-medium">Max Pain</h3>
+            <p class="text-xs text-muted-foreground mb-4">
+                "Total payout to option buyers if the underlying expires at each strike"
+            </p>
+            {if highest <= 0.0 {
+                view! { <p class="text-sm text-muted-foreground">No open interest</p> }.into_view()
+            } else {
+                view! {
+                    <svg
+                        class="w-full h-32"
+                        viewBox=format!("0 0 {} {}", PAIN_WIDTH, PAIN_HEIGHT)
+                        preserveAspectRatio="none"
+                    >
+                        {pain.iter().enumerate().map(|(i, p)| {
+                            let height = (p.payout / highest * PAIN_HEIGHT).max(1.0);
+                            let class = if Some(p.strike) == max_pain { "text-primary" } else { "text-muted-foreground" };
+                            view! {
+                                <rect
+                                    x=format!("{:.2}", (i as f64 + 0.1) * width)
+                                    y=format!("{:.2}", PAIN_HEIGHT - height)
+                                    width=format!("{:.2}", width * 0.8)
+                                    height=format!("{:.2}", height)
+                                    fill="currentColor"
+                                    class=class
+                                >
+                                    <title>{format!("{:.0}: {}", p.strike, contracts(p.payout))}</title>
+                                </rect>
+                            }
+                        }).collect::<Vec<_>>()}
+                    </svg>
+                    <div class="flex justify-between text-xs text-muted-foreground mt-2">
+                        <span>{pain.first().map(|p| format!("{:.0}", p.strike))}</span>
+                        <span class="text-primary">{format!("Max pain {}", number(max_pain, 0))}</span>
+                        <span>{pain.last().map(|p| format!("{:.0}", p.strike))}</span>
+                    </div>
+                }.into_view()
+            }}
+        </div>
+    }
+}
+
+/// Calls and puts either side of the strike, in-the-money sides shaded
+/// and the at-the-money strike marked. With `greeks` on, volume and price
+/// change make way for delta, gamma, theta and vega.
+#[component]
+fn ChainTable(
+    rows: Vec<ChainRow>,
+    spot: Option<f64>,
+    atm: Option<f64>,
+    greeks: bool,
+) -> impl IntoView {
+    let headers: Vec<&str> = if greeks {
+        vec![
+            "Build-up", "OI", "Chg OI", "IV", "Delta", "Gamma", "Theta", "Vega", "LTP",
+        ]
+    } else {
+        vec!["Build-up", "OI", "Chg OI", "Volume", "IV", "LTP", "Chg"]
+    };
+    let columns = headers.len();
+    let side = move |quote: Option<OptionQuote>,
+                     build_up: Option<String>,
+                     sensitivities: Option<Greeks>,
+                     itm: bool,
+                     calls: bool| {
+        let shade = if itm { "bg-secondary" } else { "" };
+        let Some(q) = quote else {
+            return view! {
+                <td class=shade colspan=columns></td>
+            }
+            .into_view();
+        };
+        let greek = |f: fn(&Greeks) -> f64, decimals: usize| {
+            number(sensitivities.as_ref().map(f), decimals).into_view()
+        };
+        let cells = if greeks {
+            vec![
+                ("p-2", build_up_badge(build_up).into_view()),
+                ("p-2 text-right", contracts(q.oi).into_view()),
+                (
+                    change_class(q.oi_change),
+                    signed_contracts(q.oi_change).into_view(),
+                ),
+                ("p-2 text-right", number(q.iv, 1).into_view()),
+                ("p-2 text-right", greek(|g| g.delta, 2)),
+                ("p-2 text-right", greek(|g| g.gamma, 4)),
+                ("p-2 text-right", greek(|g| g.theta, 2)),
+                ("p-2 text-right", greek(|g| g.vega, 2)),
+                ("p-2 text-right font-medium", number(q.ltp, 2).into_view()),
+            ]
+        } else {
+            vec![
+                ("p-2", build_up_badge(build_up).into_view()),
+                ("p-2 text-right", contracts(q.oi).into_view()),
+                (
+                    change_class(q.oi_change),
+                    signed_contracts(q.oi_change).into_view(),
+                ),
+                ("p-2 text-right", contracts(q.volume).into_view()),
+                ("p-2 text-right", number(q.iv, 1).into_view()),
+                ("p-2 text-right font-medium", number(q.ltp, 2).into_view()),
+                (
+                    change_class(q.price_change),
+                    number(q.price_change, 2).into_view(),
+                ),
+            ]
+        };
+        let cells = if calls {
+            cells
+        } else {
+            cells.into_iter().rev().collect()
+        };
+        cells
+            .into_iter()
+            .map(|(class, cell)| view! { <td class=format!("{} {}", class, shade)>{cell}</td> })
+            .collect::<Vec<_>>()
+            .into_view()
+    };
+
+    view! {
+        <div class="bg-card text-card-foreground rounded-lg shadow-sm overflow-hidden mt-6">
+            <div class="p-4 border-b border-border">
+                <h3 class="text-lg font-medium">Option Chain</h3>
+            </div>
+            <div class="p-0 overflow-x-auto">
+                <table class="w-full text-sm">
+                    <thead>
+                        <tr class="border-b border-border">
+                            <th class="p-2 text-center text-muted-foreground font-medium" colspan=columns>Calls</th>
+                            <th class="p-2"></th>
+                            <th class="p-2 text-center text-muted-foreground font-medium" colspan=columns>Puts</th>
+                        </tr>
+                        <tr class="border-b border-border">
+                            {headers.iter().copied().map(|h| view! { <th class="text-right p-2 text-muted-foreground font-medium">{h}</th> }).collect::<Vec<_>>()}
+                            <th class="text-center p-2 text-muted-foreground font-medium">Strike</th>
+                            {headers.iter().copied().rev().map(|h| view! { <th class="text-right p-2 text-muted-foreground font-medium">{h}</th> }).collect::<Vec<_>>()}
+                        </tr>
+                    </thead>
+                    <tbody>
+                        {rows.into_iter().map(|row| {
+                            let call_itm = spot.is_some_and(|s| row.strike < s);
+                            let put_itm = spot.is_some_and(|s| row.strike > s);
+                            let row_class = if Some(row.strike) == atm {
+                                "border-b border-border border-y-2 border-y-primary"
+                            } else {
+                                "border-b border-border"
+                            };
+                            view! {
+                                <tr class=row_class>
+                                    {side(row.call, row.call_build_up, row.call_greeks, call_itm, true)}
+                                    <td class="p-2 text-center font-medium">{format!("{:.0}", row.strike)}</td>
+                                    {side(row.put, row.put_build_up, row.put_greeks, put_itm, false)}
+                                </tr>
+                            }
+                        }).collect::<Vec<_>>()}
+                    </tbody>
+                </table>
+            </div>
+        </div>
+    }
+}
+
+#[component]
+fn ChainImport(#[prop(into)] on_imported: Callback<()>) -> impl IntoView {
+    let file_input = create_node_ref::<html::Input>();
+    let (underlying, set_underlying) = create_signal(String::new());
+    let (expiry, set_expiry) = create_signal(String::new());
+    let (date, set_date) = create_signal(String::new());
+    let (spot, set_spot) = create_signal(String::new());
+    let (summary, set_summary) = create_signal(None::<ImportSummary>);
+    let (error, set_error) = create_signal(None::<String>);
+
+    let upload = move |_| {
+        set_error.set(None);
+        set_summary.set(None);
+        let Some(input) = file_input.get() else {
+            return;
+        };
+        let input: HtmlInputElement = input.unchecked_into();
+        let Some(file) = input.files().and_then(|files| files.get(0)) else {
+            set_error.set(Some("Choose a CSV file first".to_string()));
+            return;
+        };
+        let mut params = Vec::new();
+        for (key, value) in [
+            ("underlying", underlying.get_untracked()),
+            ("expiry", expiry.get_untracked()),
+            ("date", date.get_untracked()),
+            ("spot", spot.get_untracked().replace(',', "")),
+        ] {
+            if !value.trim().is_empty() {
+                params.push(format!("{}={}", key, encode(value.trim())));
+            }
+        }
+        let path = format!("/options/import?{}", params.join("&"));
+        spawn_local(async move {
+            let text = match JsFuture::from(file.text()).await {
+                Ok(text) => text.as_string().unwrap_or_default(),
+                Err(_) => {
+                    set_error.set(Some("Could not read the file".to_string()));
+                    return;
+                }
+            };
+            match post_text::<ImportSummary>(&path, &text).await {
+                Ok(result) => {
+                    set_summary.set(Some(result));
+                    on_imported.call(());
+                }
+                Err(e) => set_error.set(Some(e)),
+            }
+        });
+    };
+
+    view! {
+        <div class="bg-card text-card-foreground rounded-lg shadow-sm p-6 mt-6">
+            <h3 class="text-lg font-medium mb-4">Import Option Chain</h3>
+            <div class="flex flex-wrap gap-2 items-end">
+                <input type="file" accept=".csv" class="text-sm" node_ref=file_input />
+                <div>
+                    <label class="block text-sm font-medium mb-1">Underlying</label>
+                    <input
+                        type="text"
+                        class="w-32 px-3 py-2 bg-background border border-border rounded-md"
+                        placeholder="BANKNIFTY"
+                        prop:value=underlying
+                        on:input=move |ev| set_underlying.set(event_target_value(&ev))
+                    />
+                </div>
+                <div>
+                    <label class="block text-sm font-medium mb-1">Expiry</label>
+                    <input
+                        type="date"
+                        class="px-3 py-2 bg-background border border-border rounded-md"
+                        prop:value=expiry
+                        on:input=move |ev| set_expiry.set(event_target_value(&ev))
+                    />
+                </div>
+                <div>
+                    <label class="block text-sm font-medium mb-1">As of</label>
+                    <input
+                        type="date"
+                        class="px-3 py-2 bg-background border border-border rounded-md"
+                        prop:value=date
+                        on:input=move |ev| set_date.set(event_target_value(&ev))
+                    />
+                </div>
+                <div>
+                    <label class="block text-sm font-medium mb-1">Spot</label>
+                    <input
+                        type="text"
+                        class="w-28 px-3 py-2 bg-background border border-border rounded-md"
+                        placeholder="Optional"
+                        prop:value=spot
+                        on:input=move |ev| set_spot.set(event_target_value(&ev))
+                    />
+                </div>
+                <button class="px-4 py-2 bg-primary text-primary-foreground rounded-md" on:click=upload>
+                    Import CSV
+                </button>
+            </div>
+            <p class="text-xs text-muted-foreground mt-2">
+                "The exchange's option chain download needs the underlying and expiry. Contract-per-row files need strike and CE/PE type columns, or trading symbols from the instrument list, plus OI, LTP, volume and IV. Missing OI and price changes are worked out from the previous day's import."
+            </p>
+
+            {move || error.get().map(|text| view! {
+                <p class="text-sm text-red-500 mt-3">{text}</p>
+            })}
+            {move || summary.get().map(|s| view! {
+                <div class="text-sm mt-3 space-y-1">
+                    <p class="text-green-500">
+                        {format!("Imported {} strikes across {} chains", s.strikes, s.snapshots)}
+                    </p>
+                    {s.notes.iter().map(|n| view! { <p class="text-muted-foreground">{n.clone()}</p> }).collect::<Vec<_>>()}
+                    {(!s.errors.is_empty()).then(|| view! {
+                        <ul class="text-red-500 list-disc pl-5">
+                            {s.errors.iter().map(|e| view! { <li>{e.clone()}</li> }).collect::<Vec<_>>()}
+                        </ul>
+                    })}
+                </div>
+            })}
+        </div>
+    }
+}
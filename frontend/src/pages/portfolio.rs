@@ -0,0 +1,716 @@
+use leptos::*;
+use serde::{Deserialize, Serialize};
+use wasm_bindgen::JsCast;
+use wasm_bindgen_futures::JsFuture;
+use web_sys::HtmlInputElement;
+
+use crate::components::portfolio_risk::*;
+use crate::components::stat_card::*;
+use crate::utils::api::*;
+
+#[derive(Clone, Debug, Serialize, Deserialize)]
+struct Holding {
+    symbol: String,
+    name: String,
+    sector: String,
+    quantity: f64,
+    avg_cost: f64,
+    invested: f64,
+    price: Option<f64>,
+    price_date: Option<String>,
+    value: f64,
+    day_change: Option<f64>,
+    unrealized: Option<f64>,
+    unrealized_pct: Option<f64>,
+    realized: f64,
+    dividends: f64,
+    weight: f64,
+    xirr: Option<f64>,
+}
+
+#[derive(Clone, Debug, Serialize, Deserialize)]
+struct ClosedHolding {
+    symbol: String,
+    realized: f64,
+    dividends: f64,
+    xirr: Option<f64>,
+}
+
+#[derive(Clone, Debug, Serialize, Deserialize)]
+struct SectorWeight {
+    sector: String,
+    value: f64,
+    weight: f64,
+    holdings: usize,
+}
+
+#[derive(Clone, Debug, Serialize, Deserialize)]
+struct Summary {
+    holdings: usize,
+    invested: f64,
+    value: f64,
+    day_change: f64,
+    day_change_pct: Option<f64>,
+    unrealized: f64,
+    unrealized_pct: Option<f64>,
+    realized: f64,
+    dividends: f64,
+    total_gain: f64,
+    xirr: Option<f64>,
+}
+
+#[derive(Clone, Debug, Serialize, Deserialize)]
+struct Benchmark {
+    symbol: String,
+    value: f64,
+    gain: f64,
+    xirr: Option<f64>,
+    excess_xirr: Option<f64>,
+}
+
+#[derive(Clone, Debug, Serialize, Deserialize)]
+struct Report {
+    as_of: String,
+    summary: Summary,
+    holdings: Vec<Holding>,
+    closed: Vec<ClosedHolding>,
+    sectors: Vec<SectorWeight>,
+    benchmarks: Vec<Benchmark>,
+    notes: Vec<String>,
+}
+
+#[derive(Clone, Debug, Serialize, Deserialize)]
+struct ValuePoint {
+    date: String,
+    invested: f64,
+    value: f64,
+    benchmark: Option<f64>,
+}
+
+#[derive(Clone, Debug, Serialize, Deserialize)]
+struct Transaction {
+    #[serde(default)]
+    id: String,
+    symbol: String,
+    kind: String,
+    date: String,
+    quantity: f64,
+    price: f64,
+    fees: f64,
+    #[serde(default)]
+    source: String,
+}
+
+#[derive(Clone, Debug, Serialize, Deserialize)]
+struct ImportSummary {
+    added: usize,
+    skipped: usize,
+    errors: Vec<String>,
+    notes: Vec<String>,
+}
+
+const CHART_WIDTH: f64 = 800.0;
+const CHART_HEIGHT: f64 = 200.0;
+
+fn money(value: f64) -> String {
+    format!("₹{:.0}", value)
+}
+
+fn signed(value: Option<f64>, suffix: &str) -> String {
+    value.map_or("-".to_string(), |v| format!("{:+.2}{}", v, suffix))
+}
+
+fn signed_class(value: Option<f64>) -> &'static str {
+    match value {
+        Some(v) if v > 0.0 => "p-3 text-green-500",
+        Some(v) if v < 0.0 => "p-3 text-red-500",
+        _ => "p-3",
+    }
+}
+
+fn quantity(value: f64) -> String {
+    if value.fract().abs() < 1e-6 {
+        format!("{:.0}", value)
+    } else {
+        format!("{:.4}", value)
+    }
+}
+
+/// SVG polyline points for one series, scaled to the chart box.
+fn line_points(values: &[Option<f64>], min: f64, max: f64) -> String {
+    let span = (max - min).max(1.0);
+    let step = CHART_WIDTH / (values.len().max(2) - 1) as f64;
+    values
+        .iter()
+        .enumerate()
+        .filter_map(|(i, v)| {
+            v.map(|v| {
+                format!(
+                    "{:.1},{:.1}",
+                    i as f64 * step,
+                    CHART_HEIGHT - (v - min) / span * CHART_HEIGHT
+                )
+            })
+        })
+        .collect::<Vec<_>>()
+        .join(" ")
+}
+
+#[component]
+pub fn Portfolio() -> impl IntoView {
+    let (report, set_report) = create_signal(None::<Report>);
+    let (history, set_history) = create_signal(Vec::<ValuePoint>::new());
+    let (transactions, set_transactions) = create_signal(Vec::<Transaction>::new());
+    let (benchmark, set_benchmark) = create_signal(String::new());
+    let (revision, set_revision) = create_signal(0usize);
+    let (error, set_error) = create_signal(None::<String>);
+
+    create_effect(move |_| {
+        revision.track();
+        spawn_local(async move {
+            match get_json::<Report>("/portfolio").await {
+                Ok(data) => {
+                    if benchmark.get_untracked().is_empty() {
+                        if let Some(first) = data.benchmarks.first() {
+                            set_benchmark.set(first.symbol.clone());
+                        }
+                    }
+                    set_report.set(Some(data));
+                }
+                Err(e) => set_error.set(Some(e)),
+            }
+            match get_json::<Vec<Transaction>>("/portfolio/transactions").await {
+                Ok(list) => set_transactions.set(list),
+                Err(e) => set_error.set(Some(e)),
+            }
+        });
+    });
+
+    create_effect(move |_| {
+        revision.track();
+        let index = benchmark.get();
+        spawn_local(async move {
+            let path = if index.is_empty() {
+                "/portfolio/history".to_string()
+            } else {
+                format!("/portfolio/history?benchmark={}", encode(&index))
+            };
+            match get_json::<Vec<ValuePoint>>(&path).await {
+                Ok(points) => set_history.set(points),
+                Err(e) => set_error.set(Some(e)),
+            }
+        });
+    });
+
+    view! {
+        <div>
+            <div class="flex justify-between items-center mb-6">
+                <h1 class="text-2xl font-bold">Portfolio</h1>
+                <span class="text-sm text-muted-foreground">
+                    {move || report.get().map(|r| format!("As of {}", r.as_of))}
+                </span>
+            </div>
+
+            {move || error.get().map(|text| view! {
+                <p class="text-sm text-red-500 mb-4">{text}</p>
+            })}
+
+            {move || report.get().map(|r| {
+                let s = r.summary.clone();
+                view! {
+                    <div>
+                        <div class="grid grid-cols-2 md:grid-cols-3 lg:grid-cols-6 gap-4">
+                            <StatCard stat=StatData {
+                                title: "Current Value".to_string(),
+                                value: money(s.value),
+                                description: Some(format!("{} holdings", s.holdings)),
+                            } />
+                            <StatCard stat=StatData {
+                                title: "Invested".to_string(),
+                                value: money(s.invested),
+                                description: Some("Cost of shares held".to_string()),
+                            } />
+                            <StatCard stat=StatData {
+                                title: "Day Change".to_string(),
+                                value: format!("{:+.0}", s.day_change),
+                                description: s.day_change_pct.map(|p| format!("{:+.2}%", p)),
+                            } />
+                            <StatCard stat=StatData {
+                                title: "Unrealized".to_string(),
+                                value: format!("{:+.0}", s.unrealized),
+                                description: s.unrealized_pct.map(|p| format!("{:+.2}%", p)),
+                            } />
+                            <StatCard stat=StatData {
+                                title: "Realized".to_string(),
+                                value: format!("{:+.0}", s.realized),
+                                description: Some(format!("Dividends {}", money(s.dividends))),
+                            } />
+                            <StatCard stat=StatData {
+                                title: "XIRR".to_string(),
+                                value: s.xirr.map_or("-".to_string(), |x| format!("{:.2}%", x)),
+                                description: Some(format!("Total gain {:+.0}", s.total_gain)),
+                            } />
+                        </div>
+
+                        {(!r.notes.is_empty()).then(|| view! {
+                            <ul class="list-disc list-inside text-sm text-muted-foreground mt-4">
+                                {r.notes.iter().map(|n| view! { <li>{n.clone()}</li> }).collect::<Vec<_>>()}
+                            </ul>
+                        })}
+
+                        <BenchmarkTable benchmarks=r.benchmarks.clone() portfolio_value=s.value portfolio_gain=s.total_gain portfolio_xirr=s.xirr />
+                        <HoldingsTable holdings=r.holdings.clone() closed=r.closed.clone() />
+                        <SectorAllocation sectors=r.sectors.clone() />
+                    </div>
+                }
+            })}
+
+            <div class="bg-card text-card-foreground rounded-lg shadow-sm p-6 mt-6">
+                <div class="flex justify-between items-center mb-4">
+                    <h3 class="text-lg font-medium">Value Over Time</h3>
+                    <select
+                        class="px-3 py-2 border border-input rounded-md text-sm"
+                        on:change=move |ev| set_benchmark.set(event_target_value(&ev))
+                    >
+                        {move || report.get().map(|r| r.benchmarks.into_iter().map(|b| {
+                            let symbol = b.symbol.clone();
+                            view! {
+                                <option value=b.symbol.clone() selected=move || benchmark.get() == symbol>
+                                    {format!("vs {}", b.symbol)}
+                                </option>
+                            }
+                        }).collect::<Vec<_>>())}
+                    </select>
+                </div>
+                {move || {
+                    let points = history.get();
+                    if points.is_empty() {
+                        return view! {
+                            <p class="text-sm text-muted-foreground">No transactions yet</p>
+                        }.into_view();
+                    }
+                    let all = points
+                        .iter()
+                        .flat_map(|p| [Some(p.value), Some(p.invested), p.benchmark])
+                        .flatten();
+                    let (min, max) = all.fold((f64::MAX, f64::MIN), |(lo, hi), v| (lo.min(v), hi.max(v)));
+                    let series = |f: fn(&ValuePoint) -> Option<f64>| {
+                        line_points(&points.iter().map(f).collect::<Vec<_>>(), min, max)
+                    };
+                    view! {
+                        <svg
+                            class="w-full h-48"
+                            viewBox=format!("0 0 {} {}", CHART_WIDTH, CHART_HEIGHT)
+                            preserveAspectRatio="none"
+                        >
+                            <polyline points=series(|p| Some(p.invested)) fill="none" stroke="currentColor" stroke-opacity="0.3" stroke-width="1" />
+                            <polyline points=series(|p| p.benchmark) fill="none" stroke="currentColor" stroke-width="2" class="text-yellow-500" />
+                            <polyline points=series(|p| Some(p.value)) fill="none" stroke="currentColor" stroke-width="2" class="text-primary" />
+                        </svg>
+                        <div class="flex gap-4 text-xs text-muted-foreground mt-2">
+                            <span class="text-primary">Portfolio</span>
+                            <span class="text-yellow-500">{move || format!("Same cash flows in {}", benchmark.get())}</span>
+                            <span>Invested</span>
+                            <span class="ml-auto">
+                                {format!("{} to {}", points[0].date, points[points.len() - 1].date)}
+                            </span>
+                        </div>
+                    }.into_view()
+                }}
+            </div>
+
+            <PortfolioRisk benchmark=benchmark revision=revision />
+
+            <TransactionList transactions=transactions on_change=move |_| set_revision.update(|r| *r += 1) />
+            <PortfolioImport on_imported=move |_| set_revision.update(|r| *r += 1) />
+        </div>
+    }
+}
+
+/// The portfolio next to what its cash flows would have made in each index.
+#[component]
+fn BenchmarkTable(
+    benchmarks: Vec<Benchmark>,
+    portfolio_value: f64,
+    portfolio_gain: f64,
+    portfolio_xirr: Option<f64>,
+) -> impl IntoView {
+    view! {
+        <div class="bg-card text-card-foreground rounded-lg shadow-sm overflow-hidden mt-6">
+            <div class="p-4 border-b border-border">
+                <h3 class="text-lg font-medium">Benchmark Comparison</h3>
+                <p class="text-xs text-muted-foreground mt-1">
+                    "Every purchase invested in the index on the same day, and every sale and dividend taken out of it"
+                </p>
+            </div>
+            <div class="p-0">
+                <table class="w-full">
+                    <thead>
+                        <tr class="border-b border-border">
+                            <th class="text-left p-3 text-muted-foreground font-medium">Index</th>
+                            <th class="text-left p-3 text-muted-foreground font-medium">Value</th>
+                            <th class="text-left p-3 text-muted-foreground font-medium">Gain</th>
+                            <th class="text-left p-3 text-muted-foreground font-medium">XIRR</th>
+                            <th class="text-left p-3 text-muted-foreground font-medium">Portfolio vs Index</th>
+                        </tr>
+                    </thead>
+                    <tbody>
+                        <tr class="border-b border-border font-medium">
+                            <td class="p-3">Portfolio</td>
+                            <td class="p-3">{money(portfolio_value)}</td>
+                            <td class=signed_class(Some(portfolio_gain))>{format!("{:+.0}", portfolio_gain)}</td>
+                            <td class="p-3">{portfolio_xirr.map_or("-".to_string(), |x| format!("{:.2}%", x))}</td>
+                            <td class="p-3"></td>
+                        </tr>
+                        {if benchmarks.is_empty() {
+                            view! {
+                                <tr>
+                                    <td class="p-3 text-muted-foreground" colspan="5">"No index history loaded"</td>
+                                </tr>
+                            }.into_view()
+                        } else {
+                            benchmarks.into_iter().map(|b| view! {
+                                <tr class="border-b border-border">
+                                    <td class="p-3">{b.symbol}</td>
+                                    <td class="p-3">{money(b.value)}</td>
+                                    <td class=signed_class(Some(b.gain))>{format!("{:+.0}", b.gain)}</td>
+                                    <td class="p-3">{b.xirr.map_or("-".to_string(), |x| format!("{:.2}%", x))}</td>
+                                    <td class=signed_class(b.excess_xirr)>{signed(b.excess_xirr, " pts")}</td>
+                                </tr>
+                            }).collect::<Vec<_>>().into_view()
+                        }}
+                    </tbody>
+                </table>
+            </div>
+        </div>
+    }
+}
+
+#[component]
+fn HoldingsTable(holdings: Vec<Holding>, closed: Vec<ClosedHolding>) -> impl IntoView {
+    view! {
+        <div class="bg-card text-card-foreground rounded-lg shadow-sm overflow-hidden mt-6">
+            <div class="p-4 border-b border-border">
+                <h3 class="text-lg font-medium">Holdings</h3>
+            </div>
+            <div class="p-0 overflow-x-auto">
+                <table class="w-full">
+                    <thead>
+                        <tr class="border-b border-border">
+                            {["Symbol", "Qty", "Avg Cost", "Price", "Value", "Weight", "Day", "Unrealized", "Realized", "Dividends", "XIRR"]
+                                .into_iter()
+                                .map(|h| view! { <th class="text-left p-3 text-muted-foreground font-medium">{h}</th> })
+                                .collect::<Vec<_>>()}
+                        </tr>
+                    </thead>
+                    <tbody>
+                        {holdings.into_iter().map(|h| view! {
+                            <tr class="border-b border-border">
+                                <td class="p-3 font-medium" title=format!("{} · {}", h.name, h.sector)>{h.symbol}</td>
+                                <td class="p-3">{quantity(h.quantity)}</td>
+                                <td class="p-3">{format!("{:.2}", h.avg_cost)}</td>
+                                <td class="p-3" title=h.price_date.clone().unwrap_or_default()>
+                                    {h.price.map_or("-".to_string(), |p| format!("{:.2}", p))}
+                                </td>
+                                <td class="p-3">{money(h.value)}</td>
+                                <td class="p-3">{format!("{:.1}%", h.weight)}</td>
+                                <td class=signed_class(h.day_change)>{signed(h.day_change, "")}</td>
+                                <td class=signed_class(h.unrealized)>
+                                    {match (h.unrealized, h.unrealized_pct) {
+                                        (Some(u), Some(p)) => format!("{:+.0} ({:+.1}%)", u, p),
+                                        (Some(u), None) => format!("{:+.0}", u),
+                                        _ => "-".to_string(),
+                                    }}
+                                </td>
+                                <td class=signed_class(Some(h.realized))>{format!("{:+.0}", h.realized)}</td>
+                                <td class="p-3">{money(h.dividends)}</td>
+                                <td class="p-3">{h.xirr.map_or("-".to_string(), |x| format!("{:.1}%", x))}</td>
+                            </tr>
+                        }).collect::<Vec<_>>()}
+                    </tbody>
+                </table>
+            </div>
+            {(!closed.is_empty()).then(|| view! {
+                <div class="p-4 border-t border-border text-sm">
+                    <p class="text-muted-foreground mb-2">Sold out</p>
+                    {closed.into_iter().map(|c| view! {
+                        <p>
+                            {format!(
+                                "{}: realized {:+.0}, dividends {}, XIRR {}",
+                                c.symbol,
+                                c.realized,
+                                money(c.dividends),
+                                c.xirr.map_or("-".to_string(), |x| format!("{:.1}%", x))
+                            )}
+                        </p>
+                    }).collect::<Vec<_>>()}
+                </div>
+            })}
+        </div>
+    }
+}
+
+#[component]
+fn SectorAllocation(sectors: Vec<SectorWeight>) -> impl IntoView {
+    view! {
+        <div class="bg-card text-card-foreground rounded-lg shadow-sm p-6 mt-6">
+            <h3 class="text-lg font-medium mb-4">Sector Allocation</h3>
+            <div class="space-y-3">
+                {sectors.into_iter().map(|s| view! {
+                    <div>
+                        <div class="flex justify-between text-sm mb-1">
+                            <span>{format!("{} ({})", s.sector, s.holdings)}</span>
+                            <span>{format!("{} · {:.1}%", money(s.value), s.weight)}</span>
+                        </div>
+                        <div class="h-2 bg-secondary rounded-md overflow-hidden">
+                            <div class="h-2 bg-primary" style=format!("width: {:.1}%", s.weight)></div>
+                        </div>
+                    </div>
+                }).collect::<Vec<_>>()}
+            </div>
+        </div>
+    }
+}
+
+/// Buys and sells behind the holdings, with a form for adding one.
+#[component]
+fn TransactionList(
+    #[prop(into)] transactions: Signal<Vec<Transaction>>,
+    #[prop(into)] on_change: Callback<()>,
+) -> impl IntoView {
+    let (symbol, set_symbol) = create_signal(String::new());
+    let (kind, set_kind) = create_signal("buy".to_string());
+    let (date, set_date) = create_signal(String::new());
+    let (qty, set_qty) = create_signal(String::new());
+    let (price, set_price) = create_signal(String::new());
+    let (fees, set_fees) = create_signal(String::new());
+    let (error, set_error) = create_signal(None::<String>);
+
+    let add = move |_| {
+        let number = |text: String| text.trim().replace(',', "").parse::<f64>().ok();
+        let (Some(quantity), Some(price)) =
+            (number(qty.get_untracked()), number(price.get_untracked()))
+        else {
+            set_error.set(Some("Enter a quantity and price".to_string()));
+            return;
+        };
+        if date.get_untracked().is_empty() {
+            set_error.set(Some("Enter the trade date".to_string()));
+            return;
+        }
+        let transaction = Transaction {
+            id: String::new(),
+            symbol: symbol.get_untracked(),
+            kind: kind.get_untracked(),
+            date: date.get_untracked(),
+            quantity,
+            price,
+            fees: number(fees.get_untracked()).unwrap_or(0.0),
+            source: "manual".to_string(),
+        };
+        set_error.set(None);
+        spawn_local(async move {
+            match post_json::<_, Transaction>("/portfolio/transactions", &transaction).await {
+                Ok(_) => {
+                    set_symbol.set(String::new());
+                    set_qty.set(String::new());
+                    set_price.set(String::new());
+                    set_fees.set(String::new());
+                    on_change.call(());
+                }
+                Err(e) => set_error.set(Some(e)),
+            }
+        });
+    };
+
+    let remove = move |id: String| {
+        spawn_local(async move {
+            match delete_json::<String>(&format!("/portfolio/transactions/{}", encode(&id))).await {
+                Ok(_) => on_change.call(()),
+                Err(e) => set_error.set(Some(e)),
+            }
+        });
+    };
+
+    let input = |label: &'static str,
+                 input_type: &'static str,
+                 value: ReadSignal<String>,
+                 set: WriteSignal<String>| {
+        view! {
+            <div>
+                <label class="block text-sm font-medium mb-1">{label}</label>
+                <input
+                    type=input_type
+                    class="w-32 px-3 py-2 bg-background border border-border rounded-md"
+                    prop:value=value
+                    on:input=move |ev| set.set(event_target_value(&ev))
+                />
+            </div>
+        }
+    };
+
+    view! {
+        <div class="bg-card text-card-foreground rounded-lg shadow-sm overflow-hidden mt-6">
+            <div class="p-4 border-b border-border">
+                <h3 class="text-lg font-medium">Transactions</h3>
+                <div class="flex flex-wrap gap-2 items-end mt-4">
+                    {input("Symbol", "text", symbol, set_symbol)}
+                    <div>
+                        <label class="block text-sm font-medium mb-1">Side</label>
+                        <select
+                            class="px-3 py-2 border border-input rounded-md"
+                            on:change=move |ev| set_kind.set(event_target_value(&ev))
+                        >
+                            <option value="buy" selected=move || kind.get() == "buy">Buy</option>
+                            <option value="sell" selected=move || kind.get() == "sell">Sell</option>
+                        </select>
+                    </div>
+                    {input("Date", "date", date, set_date)}
+                    {input("Quantity", "text", qty, set_qty)}
+                    {input("Price", "text", price, set_price)}
+                    {input("Fees", "text", fees, set_fees)}
+                    <button class="px-4 py-2 bg-primary text-primary-foreground rounded-md" on:click=add>
+                        Add
+                    </button>
+                </div>
+                {move || error.get().map(|text| view! {
+                    <p class="text-sm text-red-500 mt-3">{text}</p>
+                })}
+            </div>
+            <div class="p-0 overflow-x-auto">
+                <table class="w-full">
+                    <thead>
+                        <tr class="border-b border-border">
+                            {["Date", "Symbol", "Side", "Qty", "Price", "Fees", "Source", ""]
+                                .into_iter()
+                                .map(|h| view! { <th class="text-left p-3 text-muted-foreground font-medium">{h}</th> })
+                                .collect::<Vec<_>>()}
+                        </tr>
+                    </thead>
+                    <tbody>
+                        {move || transactions.get().into_iter().rev().map(|t| {
+                            let id = t.id.clone();
+                            view! {
+                                <tr class="border-b border-border">
+                                    <td class="p-3">{t.date}</td>
+                                    <td class="p-3 font-medium">{t.symbol}</td>
+                                    <td class=if t.kind == "buy" { "p-3 text-green-500" } else { "p-3 text-red-500" }>
+                                        {if t.kind == "buy" { "Buy" } else { "Sell" }}
+                                    </td>
+                                    <td class="p-3">{quantity(t.quantity)}</td>
+                                    <td class="p-3">{format!("{:.2}", t.price)}</td>
+                                    <td class="p-3">{format!("{:.2}", t.fees)}</td>
+                                    <td class="p-3 text-muted-foreground">{t.source}</td>
+                                    <td class="p-3">
+                                        <button class="text-red-500 text-sm" on:click=move |_| remove(id.clone())>
+                                            Delete
+                                        </button>
+                                    </td>
+                                </tr>
+                            }
+                        }).collect::<Vec<_>>()}
+                    </tbody>
+                </table>
+            </div>
+        </div>
+    }
+}
+
+/// Import a broker holdings export or transaction list, or copy delivery
+/// trades from the journal.
+#[component]
+fn PortfolioImport(#[prop(into)] on_imported: Callback<()>) -> impl IntoView {
+    let file_input = create_node_ref::<html::Input>();
+    let (date, set_date) = create_signal(String::new());
+    let (summary, set_summary) = create_signal(None::<ImportSummary>);
+    let (error, set_error) = create_signal(None::<String>);
+
+    let finish = move |result: Result<ImportSummary, String>| match result {
+        Ok(result) => {
+            set_summary.set(Some(result));
+            on_imported.call(());
+        }
+        Err(e) => set_error.set(Some(e)),
+    };
+
+    let upload = move |_| {
+        set_error.set(None);
+        set_summary.set(None);
+        let Some(input) = file_input.get() else {
+            return;
+        };
+        let input: HtmlInputElement = input.unchecked_into();
+        let Some(file) = input.files().and_then(|files| files.get(0)) else {
+            set_error.set(Some("Choose a CSV file first".to_string()));
+            return;
+        };
+        let date = date.get_untracked();
+        let path = if date.is_empty() {
+            "/portfolio/import".to_string()
+        } else {
+            format!("/portfolio/import?date={}", date)
+        };
+        spawn_local(async move {
+            let text = match JsFuture::from(file.text()).await {
+                Ok(text) => text.as_string().unwrap_or_default(),
+                Err(_) => {
+                    set_error.set(Some("Could not read the file".to_string()));
+                    return;
+                }
+            };
+            finish(post_text::<ImportSummary>(&path, &text).await);
+        });
+    };
+
+    let from_journal = move |_| {
+        set_error.set(None);
+        set_summary.set(None);
+        spawn_local(async move {
+            finish(post_json::<_, ImportSummary>("/portfolio/import/journal", &()).await);
+        });
+    };
+
+    view! {
+        <div class="bg-card text-card-foreground rounded-lg shadow-sm p-6 mt-6">
+            <h3 class="text-lg font-medium mb-4">Import Holdings</h3>
+            <div class="flex flex-col md:flex-row gap-2 items-end">
+                <input type="file" accept=".csv" class="text-sm" node_ref=file_input />
+                <div>
+                    <label class="block text-sm font-medium mb-1">Bought on (rows without a date)</label>
+                    <input
+                        type="date"
+                        class="px-3 py-2 bg-background border border-border rounded-md"
+                        prop:value=date
+                        on:input=move |ev| set_date.set(event_target_value(&ev))
+                    />
+                </div>
+                <button class="px-4 py-2 bg-primary text-primary-foreground rounded-md" on:click=upload>
+                    Import CSV
+                </button>
+                <button class="px-4 py-2 bg-secondary text-secondary-foreground rounded-md" on:click=from_journal>
+                    Import from Journal
+                </button>
+            </div>
+            <p class="text-xs text-muted-foreground mt-2">
+                "Columns: symbol, quantity, price or average cost, and optionally date, side (buy/sell) and fees. The journal import copies long equity trades held overnight."
+            </p>
+
+            {move || error.get().map(|text| view! {
+                <p class="text-sm text-red-500 mt-3">{text}</p>
+            })}
+            {move || summary.get().map(|s| view! {
+                <div class="text-sm mt-3 space-y-1">
+                    <p class="text-green-500">
+                        {format!("Added {} transactions, skipped {} already imported", s.added, s.skipped)}
+                    </p>
+                    {s.notes.iter().map(|n| view! { <p class="text-muted-foreground">{n.clone()}</p> }).collect::<Vec<_>>()}
+                    {(!s.errors.is_empty()).then(|| view! {
+                        <ul class="text-red-500 list-disc pl-5">
+                            {s.errors.iter().map(|e| view! { <li>{e.clone()}</li> }).collect::<Vec<_>>()}
+                        </ul>
+                    })}
+                </div>
+            })}
+        </div>
+    }
+}
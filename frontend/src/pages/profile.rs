@@ -1 +1,197 @@
- 
+use std::collections::BTreeMap;
+
+use leptos::*;
+use serde::{Deserialize, Serialize};
+
+use crate::components::risk_guidance::*;
+use crate::utils::api::*;
+
+#[derive(Clone, Debug, Serialize, Deserialize)]
+struct Question {
+    id: String,
+    dimension: String,
+    text: String,
+    options: Vec<String>,
+}
+
+#[derive(Clone, Debug, Serialize, Deserialize)]
+struct ProfileData {
+    answers: BTreeMap<String, usize>,
+    capital: Option<f64>,
+    assessed_at: Option<String>,
+    assessment: Assessment,
+}
+
+#[derive(Serialize)]
+struct ProfileInput {
+    answers: BTreeMap<String, usize>,
+    capital: Option<f64>,
+}
+
+/// Risk profile questionnaire. There is one profile for the installation,
+/// which Mindsage uses for its allocation, sizing and suitability advice.
+#[component]
+pub fn Profile() -> impl IntoView {
+    let (questions, set_questions) = create_signal(Vec::<Question>::new());
+    let (answers, set_answers) = create_signal(BTreeMap::<String, usize>::new());
+    let (capital, set_capital) = create_signal(String::new());
+    let (profile, set_profile) = create_signal(None::<ProfileData>);
+    let (error, set_error) = create_signal(None::<String>);
+    let (saving, set_saving) = create_signal(false);
+
+    spawn_local(async move {
+        match get_json::<Vec<Question>>("/risk-profile/questions").await {
+            Ok(list) => set_questions.set(list),
+            Err(e) => set_error.set(Some(e)),
+        }
+        match get_json::<Option<ProfileData>>("/risk-profile").await {
+            Ok(Some(data)) => {
+                set_answers.set(data.answers.clone());
+                set_capital.set(data.capital.map(|c| format!("{:.0}", c)).unwrap_or_default());
+                set_profile.set(Some(data));
+            }
+            Ok(None) => {}
+            Err(e) => set_error.set(Some(e)),
+        }
+    });
+
+    let save = move |_| {
+        let capital = capital.get_untracked();
+        let capital = if capital.trim().is_empty() {
+            None
+        } else {
+            match capital.trim().replace(',', "").parse::<f64>() {
+                Ok(value) => Some(value),
+                Err(_) => {
+                    set_error.set(Some("Capital must be a number".to_string()));
+                    return;
+                }
+            }
+        };
+        let input = ProfileInput {
+            answers: answers.get_untracked(),
+            capital,
+        };
+        set_error.set(None);
+        set_saving.set(true);
+        spawn_local(async move {
+            match put_json::<_, ProfileData>("/risk-profile", &input).await {
+                Ok(data) => set_profile.set(Some(data)),
+                Err(e) => set_error.set(Some(e)),
+            }
+            set_saving.set(false);
+        });
+    };
+
+    let answered = move || answers.get().len();
+
+    view! {
+        <div>
+            <div class="flex justify-between items-center mb-6">
+                <h1 class="text-2xl font-bold">Profile</h1>
+            </div>
+
+            {move || profile.get().map(|p| {
+                let a = p.assessment.clone();
+                view! {
+                    <div class="bg-card text-card-foreground rounded-lg shadow-sm p-6 mb-6">
+                        <div class="flex flex-col md:flex-row md:justify-between gap-2">
+                            <div>
+                                <p class="text-sm text-muted-foreground">Risk profile</p>
+                                <h2 class="text-2xl font-bold">{a.label.clone()}</h2>
+                                <p class="text-sm text-muted-foreground">{a.guidance.summary.clone()}</p>
+                            </div>
+                            <div class="md:text-right text-sm">
+                                <p>{format!("Capacity {:.0} / 100", a.capacity)}</p>
+                                <p>{format!("Tolerance {:.0} / 100", a.tolerance)}</p>
+                                <p class="text-xs text-muted-foreground">
+                                    {p.assessed_at.clone().map(|d| format!(
+                                        "Assessed {}",
+                                        d.chars().take(16).collect::<String>().replace('T', " ")
+                                    ))}
+                                </p>
+                            </div>
+                        </div>
+
+                        {(!a.notes.is_empty()).then(|| view! {
+                            <ul class="list-disc list-inside text-sm mt-4">
+                                {a.notes.iter().map(|n| view! { <li>{n.clone()}</li> }).collect::<Vec<_>>()}
+                            </ul>
+                        })}
+
+                        <h4 class="font-medium mt-6 mb-2">Suggested Allocation</h4>
+                        <AllocationBar allocation=a.guidance.allocation.clone() />
+
+                        <h4 class="font-medium mt-6 mb-2">Limits for Single Stocks</h4>
+                        <GuidanceLimits guidance=a.guidance.clone() />
+                    </div>
+                }
+            })}
+
+            <div class="bg-card text-card-foreground rounded-lg shadow-sm p-6">
+                <h3 class="text-lg font-medium">Risk Questionnaire</h3>
+                <p class="text-sm text-muted-foreground mb-4">
+                    "The first questions measure how much risk your situation can carry, the rest how much you are comfortable with. The profile follows the lower of the two."
+                </p>
+
+                <div class="space-y-6">
+                    {move || questions.get().into_iter().enumerate().map(|(index, q)| {
+                        let id = q.id.clone();
+                        view! {
+                            <div>
+                                <p class="font-medium mb-2">{format!("{}. {}", index + 1, q.text)}</p>
+                                <div class="flex flex-wrap gap-2">
+                                    {q.options.iter().enumerate().map(|(option, label)| {
+                                        let chosen = id.clone();
+                                        let picked = id.clone();
+                                        view! {
+                                            <button
+                                                class=move || if answers.get().get(&chosen) == Some(&option) {
+                                                    "px-3 py-1 bg-primary text-primary-foreground rounded-md text-sm"
+                                                } else {
+                                                    "px-3 py-1 bg-secondary text-secondary-foreground rounded-md text-sm"
+                                                }
+                                                on:click=move |_| set_answers.update(|a| {
+                                                    a.insert(picked.clone(), option);
+                                                })
+                                            >
+                                                {label.clone()}
+                                            </button>
+                                        }
+                                    }).collect::<Vec<_>>()}
+                                </div>
+                            </div>
+                        }
+                    }).collect::<Vec<_>>()}
+                </div>
+
+                <div class="flex flex-col md:flex-row gap-4 md:items-end mt-6">
+                    <div>
+                        <label class="block text-sm font-medium mb-1">"Investable capital (₹, optional)"</label>
+                        <input
+                            type="text"
+                            class="px-3 py-2 bg-background border border-border rounded-md"
+                            placeholder="1000000"
+                            prop:value=capital
+                            on:input=move |ev| set_capital.set(event_target_value(&ev))
+                        />
+                    </div>
+                    <button
+                        class="px-4 py-2 bg-primary text-primary-foreground rounded-md"
+                        disabled=move || saving.get() || answered() < questions.get().len()
+                        on:click=save
+                    >
+                        {move || if saving.get() { "Saving..." } else { "Save Profile" }}
+                    </button>
+                    <span class="text-sm text-muted-foreground pb-2">
+                        {move || format!("{} of {} answered", answered(), questions.get().len())}
+                    </span>
+                </div>
+
+                {move || error.get().map(|text| view! {
+                    <p class="text-sm text-red-500 mt-4">{text}</p>
+                })}
+            </div>
+        </div>
+    }
+}
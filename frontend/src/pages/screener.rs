@@ -0,0 +1,339 @@
+use leptos::*;
+use serde::{Deserialize, Serialize};
+
+use crate::components::screen_results::*;
+use crate::utils::api::*;
+
+#[derive(Clone, Debug, Serialize, Deserialize)]
+struct FieldHelp {
+    name: String,
+    description: String,
+}
+
+#[derive(Clone, Debug, Default, Serialize, Deserialize)]
+struct FieldList {
+    fields: Vec<FieldHelp>,
+    functions: Vec<FieldHelp>,
+}
+
+#[derive(Clone, Debug, Serialize, Deserialize)]
+struct SavedScreen {
+    #[serde(default)]
+    id: String,
+    name: String,
+    expression: String,
+    daily: bool,
+    #[serde(default, skip_serializing)]
+    last_run: Option<ScreenRun>,
+}
+
+#[derive(Serialize)]
+struct RunRequest {
+    expression: String,
+}
+
+const OPERATORS: [&str; 9] = [">", "<", ">=", "<=", "=", "!=", "and", "or", "not"];
+
+/// Append a term to an expression, separated by a space.
+fn append(expression: &str, term: &str) -> String {
+    let trimmed = expression.trim_end();
+    if trimmed.is_empty() {
+        term.to_string()
+    } else {
+        format!("{} {}", trimmed, term)
+    }
+}
+
+#[component]
+pub fn Screener() -> impl IntoView {
+    let (expression, set_expression) = create_signal(String::new());
+    let (fields, set_fields) = create_signal(FieldList::default());
+    let (screens, set_screens) = create_signal(Vec::<SavedScreen>::new());
+    let (selected, set_selected) = create_signal(None::<String>);
+    let (name, set_name) = create_signal(String::new());
+    let (daily, set_daily) = create_signal(false);
+    let (result, set_result) = create_signal(None::<ScreenRun>);
+    let (running, set_running) = create_signal(false);
+    let (error, set_error) = create_signal(None::<String>);
+    let (revision, set_revision) = create_signal(0usize);
+
+    spawn_local(async move {
+        match get_json::<FieldList>("/screener/fields").await {
+            Ok(list) => set_fields.set(list),
+            Err(e) => set_error.set(Some(e)),
+        }
+    });
+
+    create_effect(move |_| {
+        revision.track();
+        spawn_local(async move {
+            match get_json::<Vec<SavedScreen>>("/screener").await {
+                Ok(list) => set_screens.set(list),
+                Err(e) => set_error.set(Some(e)),
+            }
+        });
+    });
+
+    let run = move |_| {
+        let expression = expression.get_untracked();
+        if expression.trim().is_empty() {
+            set_error.set(Some("Enter a filter expression".to_string()));
+            return;
+        }
+        set_error.set(None);
+        set_running.set(true);
+        spawn_local(async move {
+            match post_json::<_, ScreenRun>("/screener/run", &RunRequest { expression }).await {
+                Ok(run) => set_result.set(Some(run)),
+                Err(e) => set_error.set(Some(e)),
+            }
+            set_running.set(false);
+        });
+    };
+
+    let run_saved = move |id: String| {
+        set_error.set(None);
+        set_running.set(true);
+        spawn_local(async move {
+            match post_json::<_, ScreenRun>(&format!("/screener/{}/run", encode(&id)), &()).await {
+                Ok(run) => {
+                    set_expression.set(run.expression.clone());
+                    set_result.set(Some(run));
+                    set_revision.update(|r| *r += 1);
+                }
+                Err(e) => set_error.set(Some(e)),
+            }
+            set_running.set(false);
+        });
+    };
+
+    let load = move |screen: SavedScreen| {
+        set_selected.set(Some(screen.id.clone()));
+        set_name.set(screen.name.clone());
+        set_daily.set(screen.daily);
+        set_expression.set(screen.expression.clone());
+        set_result.set(screen.last_run.clone());
+    };
+
+    let clear = move |_| {
+        set_selected.set(None);
+        set_name.set(String::new());
+        set_daily.set(false);
+        set_expression.set(String::new());
+        set_result.set(None);
+        set_error.set(None);
+    };
+
+    // Updates the loaded screen, or saves a new one
+    let save = move |_| {
+        let screen = SavedScreen {
+            id: String::new(),
+            name: name.get_untracked(),
+            expression: expression.get_untracked(),
+            daily: daily.get_untracked(),
+            last_run: None,
+        };
+        let id = selected.get_untracked();
+        set_error.set(None);
+        spawn_local(async move {
+            let saved = match id {
+                Some(id) => put_json::<_, SavedScreen>(&format!("/screener/{}", encode(&id)), &screen).await,
+                None => post_json::<_, SavedScreen>("/screener", &screen).await,
+            };
+            match saved {
+                Ok(screen) => {
+                    set_selected.set(Some(screen.id));
+                    set_revision.update(|r| *r += 1);
+                }
+                Err(e) => set_error.set(Some(e)),
+            }
+        });
+    };
+
+    let delete = move |id: String| {
+        spawn_local(async move {
+            match delete_json::<String>(&format!("/screener/{}", encode(&id))).await {
+                Ok(_) => {
+                    if selected.get_untracked().as_deref() == Some(id.as_str()) {
+                        set_selected.set(None);
+                    }
+                    set_revision.update(|r| *r += 1);
+                }
+                Err(e) => set_error.set(Some(e)),
+            }
+        });
+    };
+
+    let chip = move |term: String, title: String| {
+        let inserted = term.replace("(n)", "(20)");
+        view! {
+            <button
+                class="px-2 py-1 bg-secondary text-secondary-foreground rounded-md text-xs font-mono"
+                title=title
+                on:click=move |_| set_expression.update(|e| *e = append(e, &inserted))
+            >
+                {term}
+            </button>
+        }
+    };
+
+    view! {
+        <div>
+            <div class="flex justify-between items-center mb-6">
+                <h1 class="text-2xl font-bold">Screener</h1>
+            </div>
+
+            <div class="bg-card text-card-foreground rounded-lg shadow-sm p-6">
+                <label class="block text-sm font-medium mb-1">Filter</label>
+                <textarea
+                    class="w-full px-3 py-2 bg-background border border-border rounded-md font-mono text-sm"
+                    rows="3"
+                    placeholder="pe < 20 and roe > 15 and close > sma(200)"
+                    prop:value=expression
+                    on:input=move |ev| set_expression.set(event_target_value(&ev))
+                ></textarea>
+
+                <div class="mt-4">
+                    <p class="text-xs text-muted-foreground mb-2">Fields</p>
+                    <div class="flex flex-wrap gap-2">
+                        {move || fields.get().fields.into_iter()
+                            .map(|f| chip(f.name, f.description))
+                            .collect::<Vec<_>>()}
+                    </div>
+                </div>
+                <div class="mt-3">
+                    <p class="text-xs text-muted-foreground mb-2">Indicators (n bars back from the latest close)</p>
+                    <div class="flex flex-wrap gap-2">
+                        {move || fields.get().functions.into_iter()
+                            .map(|f| chip(f.name, f.description))
+                            .collect::<Vec<_>>()}
+                    </div>
+                </div>
+                <div class="mt-3">
+                    <p class="text-xs text-muted-foreground mb-2">Operators</p>
+                    <div class="flex flex-wrap gap-2">
+                        {OPERATORS.iter()
+                            .map(|op| chip(op.to_string(), String::new()))
+                            .collect::<Vec<_>>()}
+                    </div>
+                </div>
+
+                <div class="flex flex-col md:flex-row gap-4 md:items-end mt-6">
+                    <button
+                        class="px-4 py-2 bg-primary text-primary-foreground rounded-md"
+                        disabled=move || running.get()
+                        on:click=run
+                    >
+                        {move || if running.get() { "Running..." } else { "Run" }}
+                    </button>
+                    <div>
+                        <label class="block text-sm font-medium mb-1">Screen name</label>
+                        <input
+                            type="text"
+                            class="px-3 py-2 bg-background border border-border rounded-md"
+                            prop:value=name
+                            on:input=move |ev| set_name.set(event_target_value(&ev))
+                        />
+                    </div>
+                    <label class="flex items-center gap-2 text-sm pb-2">
+                        <input
+                            type="checkbox"
+                            prop:checked=daily
+                            on:change=move |ev| set_daily.set(event_target_checked(&ev))
+                        />
+                        "Run daily after the close"
+                    </label>
+                    <button class="px-4 py-2 bg-secondary text-secondary-foreground rounded-md" on:click=save>
+                        {move || if selected.get().is_some() { "Update Screen" } else { "Save Screen" }}
+                    </button>
+                    <button class="px-4 py-2 bg-secondary text-secondary-foreground rounded-md" on:click=clear>
+                        "New"
+                    </button>
+                </div>
+
+                {move || error.get().map(|text| view! {
+                    <p class="text-sm text-red-500 mt-4">{text}</p>
+                })}
+            </div>
+
+            {move || result.get().map(|run| view! { <ScreenResults run=run /> })}
+
+            <div class="bg-card text-card-foreground rounded-lg shadow-sm overflow-hidden mt-6">
+                <div class="p-4 border-b border-border">
+                    <h3 class="text-lg font-medium">Saved Screens</h3>
+                </div>
+                <div class="p-0">
+                    <table class="w-full">
+                        <thead>
+                            <tr class="border-b border-border">
+                                <th class="text-left p-3 text-muted-foreground font-medium">Name</th>
+                                <th class="text-left p-3 text-muted-foreground font-medium">Filter</th>
+                                <th class="text-left p-3 text-muted-foreground font-medium">Daily</th>
+                                <th class="text-left p-3 text-muted-foreground font-medium">Last Run</th>
+                                <th class="text-left p-3 text-muted-foreground font-medium"></th>
+                            </tr>
+                        </thead>
+                        <tbody>
+                            {move || {
+                                let list = screens.get();
+                                if list.is_empty() {
+                                    return view! {
+                                        <tr>
+                                            <td class="p-3 text-muted-foreground" colspan="5">"No saved screens yet"</td>
+                                        </tr>
+                                    }.into_view();
+                                }
+                                list.into_iter().map(move |screen| {
+                                    let loaded = screen.clone();
+                                    let run_id = screen.id.clone();
+                                    let delete_id = screen.id.clone();
+                                    let last_run = screen.last_run.as_ref().map_or("Never".to_string(), |run| {
+                                        let mut text = format!(
+                                            "{} · {} matches",
+                                            run.ran_at.chars().take(16).collect::<String>().replace('T', " "),
+                                            run.rows.len()
+                                        );
+                                        if !run.added.is_empty() || !run.removed.is_empty() {
+                                            text.push_str(&format!(" (+{} / -{})", run.added.len(), run.removed.len()));
+                                        }
+                                        text
+                                    });
+                                    view! {
+                                        <tr class="border-b border-border">
+                                            <td class="p-3 font-medium">{screen.name.clone()}</td>
+                                            <td class="p-3 font-mono text-xs">{screen.expression.clone()}</td>
+                                            <td class="p-3">{if screen.daily { "Yes" } else { "No" }}</td>
+                                            <td class="p-3 text-sm text-muted-foreground">{last_run}</td>
+                                            <td class="p-3">
+                                                <div class="flex gap-2">
+                                                    <button
+                                                        class="px-3 py-1 bg-secondary text-secondary-foreground rounded-md text-sm"
+                                                        on:click=move |_| load(loaded.clone())
+                                                    >
+                                                        "Open"
+                                                    </button>
+                                                    <button
+                                                        class="px-3 py-1 bg-primary text-primary-foreground rounded-md text-sm"
+                                                        on:click=move |_| run_saved(run_id.clone())
+                                                    >
+                                                        "Run"
+                                                    </button>
+                                                    <button
+                                                        class="px-3 py-1 text-red-500 text-sm"
+                                                        on:click=move |_| delete(delete_id.clone())
+                                                    >
+                                                        "Delete"
+                                                    </button>
+                                                </div>
+                                            </td>
+                                        </tr>
+                                    }
+                                }).collect_view()
+                            }}
+                        </tbody>
+                    </table>
+                </div>
+            </div>
+        </div>
+    }
+}
@@ -1 +1,96 @@
- 
+use gloo_net::http::Request;
+use serde::de::DeserializeOwned;
+use serde::Serialize;
+
+pub const API_BASE: &str = "/api";
+
+#[derive(serde::Deserialize)]
+struct ErrorBody {
+    error: String,
+}
+
+pub fn encode(value: &str) -> String {
+    js_sys::encode_uri_component(value).into()
+}
+
+async fn read_response<T: DeserializeOwned>(
+    response: gloo_net::http::Response,
+) -> Result<T, String> {
+    if response.ok() {
+        response.json::<T>().await.map_err(|e| e.to_string())
+    } else {
+        let status = response.status();
+        match response.json::<ErrorBody>().await {
+            Ok(body) => Err(body.error),
+            Err(_) => Err(format!("Request failed with status {}", status)),
+        }
+    }
+}
+
+pub async fn get_json<T: DeserializeOwned>(path: &str) -> Result<T, String> {
+    let response = Request::get(&format!("{}{}", API_BASE, path))
+        .send()
+        .await
+        .map_err(|e| e.to_string())?;
+    read_response(response).await
+}
+
+pub async fn post_json<B: Serialize, T: DeserializeOwned>(path: &str, body: &B) -> Result<T, String> {
+    let response = Request::post(&format!("{}{}", API_BASE, path))
+        .json(body)
+        .map_err(|e| e.to_string())?
+        .send()
+        .await
+        .map_err(|e| e.to_string())?;
+    read_response(response).await
+}
+
+/// Post a raw text body, e.g. a CSV file, and read a JSON response.
+pub async fn post_text<T: DeserializeOwned>(path: &str, body: &str) -> Result<T, String> {
+    let response = Request::post(&format!("{}{}", API_BASE, path))
+        .header("Content-Type", "text/plain")
+        .body(body)
+        .send()
+        .await
+        .map_err(|e| e.to_string())?;
+    read_response(response).await
+}
+
+/// Post a file or other blob as the raw body, e.g. an attachment upload.
+pub async fn post_blob<T: DeserializeOwned>(path: &str, blob: &web_sys::Blob) -> Result<T, String> {
+    let response = Request::post(&format!("{}{}", API_BASE, path))
+        .header("Content-Type", &blob.type_())
+        .body(blob.clone())
+        .send()
+        .await
+        .map_err(|e| e.to_string())?;
+    read_response(response).await
+}
+
+pub async fn put_blob<T: DeserializeOwned>(path: &str, blob: &web_sys::Blob) -> Result<T, String> {
+    let response = Request::put(&format!("{}{}", API_BASE, path))
+        .header("Content-Type", &blob.type_())
+        .body(blob.clone())
+        .send()
+        .await
+        .map_err(|e| e.to_string())?;
+    read_response(response).await
+}
+
+pub async fn put_json<B: Serialize, T: DeserializeOwned>(path: &str, body: &B) -> Result<T, String> {
+    let response = Request::put(&format!("{}{}", API_BASE, path))
+        .json(body)
+        .map_err(|e| e.to_string())?
+        .send()
+        .await
+        .map_err(|e| e.to_string())?;
+    read_response(response).await
+}
+
+pub async fn delete_json<T: DeserializeOwned>(path: &str) -> Result<T, String> {
+    let response = Request::delete(&format!("{}{}", API_BASE, path))
+        .send()
+        .await
+        .map_err(|e| e.to_string())?;
+    read_response(response).await
+}
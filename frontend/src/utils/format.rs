@@ -0,0 +1,21 @@
+/// Format as rupees with Indian digit grouping, e.g. ₹1,23,456.70.
+pub fn rupees(value: f64) -> String {
+    let formatted = format!("{:.2}", value.abs());
+    let (whole, fraction) = formatted.split_once('.').unwrap_or((&formatted, "00"));
+    let mut grouped = String::new();
+    let digits: Vec<char> = whole.chars().collect();
+    let head = digits.len().saturating_sub(3);
+    for (idx, c) in digits[..head].iter().enumerate() {
+        if idx > 0 && (head - idx).is_multiple_of(2) {
+            grouped.push(',');
+        }
+        grouped.push(*c);
+    }
+    if head > 0 {
+        grouped.push(',');
+    }
+    grouped.extend(&digits[head..]);
+
+    let sign = if value < 0.0 { "-" } else { "" };
+    format!("{}₹{}.{}", sign, grouped, fraction)
+}